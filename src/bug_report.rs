@@ -0,0 +1,160 @@
+//! Bug report generator for rustm itself.
+//!
+//! Builds a GitHub issue body with the rustm version, OS, terminal details and
+//! the end of `rustm.log`. Paths are redacted before anything leaves the
+//! machine: the projects directory becomes `<projects>`, the home directory
+//! `~` and the user name `<user>`.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::logging;
+
+/// Number of `rustm.log` lines included in the report.
+const LOG_LINES: usize = 100;
+/// Longest body passed in the new-issue URL; longer bodies are left to the clipboard.
+const URL_BODY_LIMIT: usize = 6000;
+/// Shortest user name that is redacted (shorter ones would mangle unrelated words).
+const MIN_REDACTED_USER_LEN: usize = 3;
+
+/// Title prefilled in the new issue.
+pub const ISSUE_TITLE: &str = "Bug: ";
+
+/// Build the (redacted) issue body. `terminal_size` is `(columns, rows)`.
+pub fn issue_body(config: &Config, terminal_size: (usize, usize)) -> String {
+    let mut body = String::new();
+    writeln!(
+        body,
+        "## Description\n\n<!-- What happened? What did you expect? -->\n"
+    )
+    .unwrap();
+    writeln!(body, "## Steps to reproduce\n\n1. \n").unwrap();
+    writeln!(body, "## Environment\n").unwrap();
+    writeln!(body, "- rustm: {}", env!("CARGO_PKG_VERSION")).unwrap();
+    writeln!(
+        body,
+        "- OS: {} ({})",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+    .unwrap();
+    for var in ["TERM", "COLORTERM", "TERM_PROGRAM"] {
+        if let Ok(value) = std::env::var(var) {
+            writeln!(body, "- {var}: {value}").unwrap();
+        }
+    }
+    writeln!(
+        body,
+        "- Terminal size: {}x{}",
+        terminal_size.0, terminal_size.1
+    )
+    .unwrap();
+
+    let log = logging::tail(LOG_LINES);
+    writeln!(
+        body,
+        "\n## Log (last {} lines of rustm.log)\n\n```\n{}\n```",
+        log.len(),
+        log.join("\n")
+    )
+    .unwrap();
+
+    redact(&body, &redactions(config))
+}
+
+/// URL of a new issue on the rustm repository, prefilled with `body` when it is short enough.
+///
+/// `None` when the package has no `repository` set.
+pub fn new_issue_url(body: &str) -> Option<String> {
+    let repository = env!("CARGO_PKG_REPOSITORY").trim_end_matches('/');
+    if repository.is_empty() {
+        return None;
+    }
+    let mut url = format!(
+        "{repository}/issues/new?title={}",
+        percent_encode(ISSUE_TITLE)
+    );
+    let encoded = percent_encode(body);
+    if encoded.len() <= URL_BODY_LIMIT {
+        write!(url, "&body={encoded}").unwrap();
+    }
+    Some(url)
+}
+
+/// Write `body` to `bug-report.md` next to `config.yaml`, for when there is no clipboard.
+pub fn save(body: &str) -> std::io::Result<PathBuf> {
+    let path = Config::file_path().with_file_name("bug-report.md");
+    fs::write(&path, body)?;
+    Ok(path)
+}
+
+/// `(text, replacement)` pairs, longest text first so nested paths are replaced whole.
+fn redactions(config: &Config) -> Vec<(String, &'static str)> {
+    let mut pairs = vec![(config.projects_directory().to_string(), "<projects>")];
+    if let Some(home) = dirs::home_dir() {
+        pairs.push((home.display().to_string(), "~"));
+    }
+    if let Ok(user) = std::env::var("USER").or_else(|_| std::env::var("USERNAME")) {
+        pairs.push((user, "<user>"));
+    }
+    pairs.retain(|(text, _)| text.len() >= MIN_REDACTED_USER_LEN);
+    pairs.sort_by_key(|(text, _)| std::cmp::Reverse(text.len()));
+    pairs
+}
+
+fn redact(text: &str, redactions: &[(String, &str)]) -> String {
+    redactions
+        .iter()
+        .fold(text.to_string(), |acc, (from, to)| acc.replace(from, to))
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters.
+fn percent_encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            out.push(char::from(byte));
+        } else {
+            write!(out, "%{byte:02X}").unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_longest_paths_first() {
+        let redactions = vec![
+            ("/home/alice/code".to_string(), "<projects>"),
+            ("/home/alice".to_string(), "~"),
+            ("alice".to_string(), "<user>"),
+        ];
+        let text = "scan /home/alice/code/demo, config /home/alice/.config, by alice";
+        assert_eq!(
+            redact(text, &redactions),
+            "scan <projects>/demo, config ~/.config, by <user>"
+        );
+    }
+
+    #[test]
+    fn percent_encodes_reserved_characters() {
+        assert_eq!(percent_encode("a b/é-~"), "a%20b%2F%C3%A9-~");
+    }
+
+    #[test]
+    fn new_issue_url_carries_the_body() {
+        let url = new_issue_url("## Description\n\nit broke").unwrap();
+        assert_eq!(
+            url,
+            "https://github.com/sub07/rustm/issues/new?title=Bug%3A%20\
+             &body=%23%23%20Description%0A%0Ait%20broke"
+        );
+        let long = "x".repeat(URL_BODY_LIMIT + 1);
+        assert!(!new_issue_url(&long).unwrap().contains("&body="));
+    }
+}
@@ -0,0 +1,205 @@
+//! Cargo command execution.
+//!
+//! Small wrapper around `crate::process` for running `cargo <args>` (or a
+//! cargo-compatible tool such as `cross`) inside a project directory and mapping
+//! failures to a single error type the UI can show. Records logged while a
+//! command runs carry `project=<directory name>`.
+
+use std::fmt;
+use std::path::Path;
+
+use log::{error, info};
+
+use crate::logging;
+use crate::process::{self, CommandLine, ProcessOutput};
+
+/// Errors produced when running a cargo command.
+#[derive(Debug)]
+pub enum CargoError {
+    NotFound(String),
+    Failed { status: i32, stderr: String },
+    Io(std::io::Error),
+}
+
+impl fmt::Display for CargoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(program) => write!(f, "Unable to locate `{program}` in PATH"),
+            Self::Failed { status, stderr } => {
+                write!(f, "command exited with code {status}: {stderr}")
+            }
+            Self::Io(e) => write!(f, "I/O error running cargo: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CargoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Run `cargo <args>` in `project_path` and return its stdout on success.
+pub fn run(project_path: &Path, args: &[&str]) -> Result<String, CargoError> {
+    run_tool("cargo", project_path, args, &[])
+}
+
+/// Run `program <args>` in `project_path` with extra environment variables.
+pub fn run_tool(
+    program: &str,
+    project_path: &Path,
+    args: &[&str],
+    envs: &[(&str, &str)],
+) -> Result<String, CargoError> {
+    let output = execute(program, project_path, args, envs)?;
+    if !output.success() {
+        let status = output.code.unwrap_or(-1);
+        error!("{program} {} failed with code {status}", args.join(" "));
+        return Err(CargoError::Failed {
+            status,
+            stderr: output.stderr.trim().to_string(),
+        });
+    }
+
+    Ok(output.stdout)
+}
+
+/// Run `cargo <args>` in `project_path` and return its output whatever its exit status.
+///
+/// For commands that report on stdout even when they fail (`--message-format=json`).
+pub fn output(project_path: &Path, args: &[&str]) -> Result<ProcessOutput, CargoError> {
+    execute("cargo", project_path, args, &[])
+}
+
+fn execute(
+    program: &str,
+    project_path: &Path,
+    args: &[&str],
+    envs: &[(&str, &str)],
+) -> Result<ProcessOutput, CargoError> {
+    let cmd = CommandLine::new(program, project_path)
+        .args(args.iter().copied())
+        .envs(envs);
+
+    let _project = logging::scope("project", project_name(project_path));
+    info!("Executing in {}: {cmd}", project_path.display());
+
+    process::with(|runner| runner.output(&cmd)).map_err(|e| spawn_error(program, e))
+}
+
+/// Run `program <args>` in `project_path`, passing each stdout/stderr line to `log` as it arrives.
+///
+/// Returns whether the command succeeded; the output itself only goes to `log`.
+pub fn run_tool_streaming(
+    program: &str,
+    project_path: &Path,
+    args: &[&str],
+    log: &dyn Fn(String),
+) -> Result<bool, CargoError> {
+    let cmd = CommandLine::new(program, project_path).args(args.iter().copied());
+
+    let _project = logging::scope("project", project_name(project_path));
+    info!("Executing in {}: {cmd}", project_path.display());
+
+    let code =
+        process::with(|runner| runner.stream(&cmd, log)).map_err(|e| spawn_error(program, e))?;
+    if code != Some(0) {
+        error!(
+            "{program} {} failed with code {}",
+            args.join(" "),
+            code.unwrap_or(-1)
+        );
+    }
+    Ok(code == Some(0))
+}
+
+/// `program` could not be started.
+fn spawn_error(program: &str, e: std::io::Error) -> CargoError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        CargoError::NotFound(program.to_string())
+    } else {
+        CargoError::Io(e)
+    }
+}
+
+/// Name of the project directory, for the log context.
+fn project_name(project_path: &Path) -> String {
+    project_path.file_name().map_or_else(
+        || project_path.display().to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    )
+}
+
+/// `true` when an executable named `program` can be found in `PATH`.
+pub fn tool_available(program: &str) -> bool {
+    let Some(paths) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&paths)
+        .any(|dir| dir.join(program).is_file() || dir.join(format!("{program}.exe")).is_file())
+}
+
+/// Host target triple as reported by `rustc -vV`.
+pub fn host_triple() -> Result<String, CargoError> {
+    let out = run_tool("rustc", Path::new("."), &["-vV"], &[])?;
+    out.lines()
+        .find_map(|l| l.strip_prefix("host: "))
+        .map(|h| h.trim().to_string())
+        .ok_or_else(|| CargoError::Failed {
+            status: 0,
+            stderr: "`rustc -vV` did not report a host triple".into(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::FakeRunner;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn commands_go_through_the_runner() {
+        let fake = Rc::new(FakeRunner::default());
+        let _runner = process::replace(fake.clone());
+        fake.succeed("rustc 1.90.0\nhost: x86_64-unknown-linux-gnu\nrelease: 1.90.0\n");
+        fake.fail(101, "error: could not compile `demo`\n");
+        fake.not_found();
+        fake.succeed("Compiling demo\nFinished\n");
+
+        assert_eq!(host_triple().unwrap(), "x86_64-unknown-linux-gnu");
+        let dir = Path::new("/projects/demo");
+        assert!(matches!(
+            run_tool("cargo", dir, &["build"], &[("RUSTFLAGS", "-D warnings")]),
+            Err(CargoError::Failed { status: 101, stderr }) if stderr == "error: could not compile `demo`"
+        ));
+        assert!(matches!(
+            run_tool("cross", dir, &["build"], &[]),
+            Err(CargoError::NotFound(program)) if program == "cross"
+        ));
+        let lines = RefCell::new(Vec::new());
+        let log = |line: String| lines.borrow_mut().push(line);
+        assert!(run_tool_streaming("cargo", dir, &["test", "--", "--nocapture"], &log).unwrap());
+        assert_eq!(*lines.borrow(), ["Compiling demo", "Finished"]);
+
+        let calls = fake.calls();
+        let lines: Vec<String> = calls.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "rustc -vV",
+                "cargo build",
+                "cross build",
+                "cargo test -- --nocapture"
+            ]
+        );
+        assert_eq!(calls[1].dir, dir);
+        assert_eq!(
+            calls[1].envs,
+            [("RUSTFLAGS".to_string(), "-D warnings".to_string())]
+        );
+    }
+}
@@ -0,0 +1,695 @@
+//! Command-line subcommands.
+//!
+//! `rustm` without arguments starts the TUI. A subcommand runs without any
+//! TUI and exits:
+//! - `rustm list`: print the projects of the projects directory.
+//! - `rustm new <NAME> [--lib] [--edition E] [--vcs git|hg|none] [--commit]
+//!   [--license mit|apache|dual|none] [--description TEXT] [--readme] [--tooling]
+//!   [--remote github|gitlab [--public]] [--open]`:
+//!   create a project.
+//! - `rustm open <NAME|PATH>`: open a project in the editor.
+//! - `rustm config get [KEY]` / `rustm config set <KEY> <VALUE>`: read or
+//!   change a setting, `KEY` being dotted (`scan.max_depth`).
+//! - `rustm report [PATH] [--json]`: print the environment report of the
+//!   project at `PATH` (current directory by default).
+//! - `rustm batch <OPERATION> [--filter EXPR] [--tag TAG] [--dry-run]`: clean,
+//!   fetch, pull or archive every project matching the filter (see
+//!   `project::filter` and `project::batch`), printing one line per project.
+//!   Exits with 3 when the operation failed for some of them.
+//! - `rustm daemon [--interval SECS] [--metrics [ADDR]]`: rescan the projects
+//!   directory periodically, which feeds the event stream, optionally serving
+//!   Prometheus metrics (see `crate::metrics`); runs until killed.
+//!
+//! `rustm --events jsonl` prints the event stream (see `crate::events`) as it
+//! grows, until interrupted.
+//!
+//! `--format json` (any subcommand) prints machine-readable output instead:
+//! the project list, the setting, the report, or for the other subcommands an
+//! outcome object (`{"ok": true, "path": ...}` / `{"ok": false, "error": ...}`)
+//! on stdout, errors included. `batch` prints `{"ok": ..., "projects": [...]}`,
+//! one outcome per project.
+//!
+//! Arguments are parsed with `clap`. Everything but `report` needs a
+//! configuration: run `rustm` once to go through the initial setup.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use log::warn;
+use serde::Serialize;
+
+use crate::config::{Config, LoadStatus, Overrides};
+use crate::editor;
+use crate::events;
+use crate::forge::Provider;
+use crate::metadata;
+use crate::metrics;
+use crate::project::archive;
+use crate::project::batch::Operation;
+use crate::project::create::{
+    self, CreateAndOpenError, CreateProjectParams, ProjectEdition, ProjectType, Vcs,
+};
+use crate::project::env_report;
+use crate::project::filter::Filter;
+use crate::project::license::License;
+use crate::project::list::{self, ProjectInfo};
+
+#[derive(Parser)]
+#[command(name = "rustm", version, about = "A TUI for managing Rust projects")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Projects directory for this run, instead of the configured one [env: RUSTM_PROJECTS_DIR]
+    #[arg(long, global = true, value_name = "DIR")]
+    projects_dir: Option<String>,
+    /// Editor command for this run, instead of the configured one [env: RUSTM_EDITOR]
+    #[arg(long, global = true, value_name = "CMD")]
+    editor: Option<String>,
+    /// Print rustm's events as they happen instead of starting the TUI
+    #[arg(long, value_name = "FORMAT", value_parser = ["jsonl"])]
+    events: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Operation of `rustm batch`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum BatchOperation {
+    /// Delete the build artifacts
+    Clean,
+    /// Fetch the upstream's remote
+    Fetch,
+    /// Fast-forward to the upstream
+    Pull,
+    /// Move to the archive directory
+    Archive,
+    /// Compress into the archive directory
+    ArchiveTarGz,
+}
+
+impl BatchOperation {
+    /// Changes the projects (every operation but fetching): without a filter,
+    /// it takes `--all`.
+    const fn changes_projects(self) -> bool {
+        !matches!(self, Self::Fetch)
+    }
+
+    const fn operation(self) -> Operation {
+        match self {
+            Self::Clean => Operation::Clean,
+            Self::Fetch => Operation::Fetch,
+            Self::Pull => Operation::Pull,
+            Self::Archive => Operation::Archive(archive::Format::Directory),
+            Self::ArchiveTarGz => Operation::Archive(archive::Format::TarGz),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the projects of the projects directory
+    List,
+    /// Create a project in the projects directory
+    New(NewArgs),
+    /// Open a project in the editor
+    Open {
+        /// Project name, or path to a project directory
+        project: String,
+    },
+    /// Read or change the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Print the environment report of a project
+    Report {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Same as `--format json`
+        #[arg(long)]
+        json: bool,
+    },
+    /// Apply an operation to the projects matching a filter
+    Batch {
+        operation: BatchOperation,
+        /// Conditions the projects must meet, e.g. `target_size>1GB age>90d`
+        #[arg(long, default_value = "")]
+        filter: String,
+        /// Only the projects with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Apply to every project when neither --filter nor --tag is given
+        #[arg(long, conflicts_with_all = ["filter", "tag"])]
+        all: bool,
+        /// List the matching projects without applying the operation
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Rescan the projects periodically, optionally serving metrics
+    Daemon {
+        /// Seconds between scans of the projects directory
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+        /// Serve Prometheus metrics on ADDR (127.0.0.1:9898 when given alone)
+        #[arg(long, value_name = "ADDR", num_args = 0..=1, default_missing_value = METRICS_ADDR)]
+        metrics: Option<SocketAddr>,
+    },
+}
+
+/// Address of the metrics endpoint for `--metrics` without a value.
+const METRICS_ADDR: &str = "127.0.0.1:9898";
+
+/// Pause between two checks of the event stream by the daemon.
+const DAEMON_TICK: Duration = Duration::from_secs(1);
+
+#[derive(Args)]
+struct NewArgs {
+    name: String,
+    /// Create a library instead of a binary
+    #[arg(long)]
+    lib: bool,
+    /// Rust edition (default_edition of the configuration, else 2024)
+    #[arg(long, value_parser = ["2015", "2018", "2021", "2024"])]
+    edition: Option<String>,
+    /// Version control of the project
+    #[arg(long, default_value = "git", value_parser = ["git", "hg", "none"])]
+    vcs: String,
+    /// Commit the new project's files
+    #[arg(long)]
+    commit: bool,
+    /// Write license files (dual: MIT OR Apache-2.0), default_license of the configuration if omitted
+    #[arg(long, value_parser = ["mit", "apache", "dual", "none"])]
+    license: Option<String>,
+    /// package.description, also used by --readme
+    #[arg(long, default_value = "")]
+    description: String,
+    /// Write a README.md with the name, description and license
+    #[arg(long)]
+    readme: bool,
+    /// Copy .gitignore, rustfmt.toml and clippy.toml from <config dir>/templates
+    #[arg(long)]
+    tooling: bool,
+    /// Create a repository on this forge, add it as origin and push the initial commit
+    #[arg(long, value_parser = ["github", "gitlab"])]
+    remote: Option<String>,
+    /// Make the remote repository public (private by default)
+    #[arg(long, requires = "remote")]
+    public: bool,
+    /// Open the project in the editor once created
+    #[arg(long)]
+    open: bool,
+}
+
+impl NewArgs {
+    /// Parameters of the project to create, the defaults of `config` for the
+    /// options not given.
+    fn params(self, config: &Config) -> CreateProjectParams {
+        let mut params = CreateProjectParams::from_config(self.name, config);
+        if self.lib {
+            params.project_type = ProjectType::Library;
+        }
+        if let Some(edition) = self.edition.as_deref().and_then(ProjectEdition::parse) {
+            params.edition = edition;
+        }
+        params.vcs = Vcs::parse(&self.vcs).unwrap_or_default();
+        params.initial_commit = self.commit;
+        if let Some(license) = self.license {
+            params.license = License::parse(&license);
+        }
+        params.description = self.description;
+        params.readme = self.readme;
+        params.tooling = self.tooling;
+        params.remote = self.remote.as_deref().and_then(Provider::parse);
+        params.public_remote = self.public;
+        params
+    }
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print a setting (the whole configuration without KEY)
+    Get { key: Option<String> },
+    /// Change a setting; VALUE is parsed as YAML
+    Set { key: String, value: String },
+}
+
+/// Result of a subcommand printing no data, for `--format json`.
+#[derive(Serialize)]
+struct Outcome {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Outcome of `rustm batch` for one project, for `--format json`.
+#[derive(Serialize)]
+struct BatchOutcome {
+    name: String,
+    path: PathBuf,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Output of `rustm batch` for `--format json`: `ok` when no project failed.
+#[derive(Serialize)]
+struct BatchReport {
+    ok: bool,
+    projects: Vec<BatchOutcome>,
+}
+
+/// Run the subcommand in `args` (program name excluded).
+///
+/// Returns `None` when there is no subcommand (start the TUI), otherwise the exit code.
+/// `--projects-dir` / `--editor` are recorded as configuration overrides either way.
+pub fn run(args: &[String]) -> Option<i32> {
+    if args.is_empty() {
+        return None;
+    }
+    let cli = match Cli::try_parse_from(
+        std::iter::once("rustm").chain(args.iter().map(String::as_str)),
+    ) {
+        Ok(cli) => cli,
+        Err(e) => {
+            // Also covers `--help` and `--version`, printed to stdout with a zero exit code.
+            let _ = e.print();
+            return Some(e.exit_code());
+        }
+    };
+    Overrides::set_cli(Overrides {
+        projects_directory: cli.projects_dir,
+        editor_cmd: cli.editor,
+    });
+    let format = cli.format;
+    if cli.events.is_some() {
+        if cli.command.is_some() {
+            eprintln!("error: --events cannot be combined with a subcommand");
+            return Some(2);
+        }
+        return Some(follow_events());
+    }
+    Some(match cli.command? {
+        Command::List => with_config(format, |config| list(config, format)),
+        Command::New(args) => with_config(format, |config| {
+            let open = args.open;
+            new(config, format, args.params(config), open)
+        }),
+        Command::Open { project } => with_config(format, |config| open(config, format, &project)),
+        Command::Config { action } => with_config(format, |config| match action {
+            ConfigAction::Get { key } => {
+                config_get(config, format, key.as_deref().unwrap_or_default())
+            }
+            ConfigAction::Set { key, value } => config_set(config, format, &key, &value),
+        }),
+        Command::Report { path, json } => report(&path, if json { Format::Json } else { format }),
+        Command::Batch {
+            operation,
+            filter,
+            tag,
+            all,
+            dry_run,
+        } => {
+            if operation.changes_projects()
+                && !dry_run
+                && !all
+                && filter.trim().is_empty()
+                && tag.is_none()
+            {
+                Cli::command()
+                    .error(
+                        ErrorKind::MissingRequiredArgument,
+                        "this would apply to every project: pass --filter, --tag or --all",
+                    )
+                    .exit();
+            }
+            with_config(format, |config| {
+                let filter = match Filter::parse(&filter) {
+                    Ok(filter) => filter,
+                    Err(e) => return fail(format, None, &format!("Invalid filter: {e}")),
+                };
+                let filter = match tag {
+                    Some(tag) => filter.with_tag(&tag),
+                    None => filter,
+                };
+                let operation = (!dry_run).then(|| operation.operation());
+                batch(config, format, &filter, operation)
+            })
+        }
+        Command::Daemon { interval, metrics } => with_config(format, |config| {
+            daemon(config, format, Duration::from_secs(interval), metrics)
+        }),
+    })
+}
+
+/// Print the event stream until stdout is closed (exit code 0) or the events cannot be read (1).
+fn follow_events() -> i32 {
+    match events::follow(&mut std::io::stdout().lock()) {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => 0,
+        Err(e) => {
+            eprintln!("Cannot follow {}: {e}", events::file_path().display());
+            1
+        }
+        Ok(()) => 0,
+    }
+}
+
+/// Scan the projects every `interval` and count the builds of the event
+/// stream, serving the metrics on `metrics_addr` when set. Only returns when
+/// it cannot start (exit code 1).
+fn daemon(
+    config: &Config,
+    format: Format,
+    interval: Duration,
+    metrics_addr: Option<SocketAddr>,
+) -> i32 {
+    let shared = metrics::Shared::default();
+    if let Some(addr) = metrics_addr {
+        match metrics::serve(addr, Arc::clone(&shared)) {
+            Ok(bound) => eprintln!("Serving metrics on http://{bound}/metrics"),
+            Err(e) => {
+                return fail(
+                    format,
+                    None,
+                    &format!("Cannot serve metrics on {addr}: {e}"),
+                );
+            }
+        }
+    }
+    let mut follower = match events::Follower::new() {
+        Ok(follower) => follower,
+        Err(e) => {
+            let path = events::file_path();
+            return fail(
+                format,
+                None,
+                &format!("Cannot follow {}: {e}", path.display()),
+            );
+        }
+    };
+    let mut next_scan = Instant::now();
+    loop {
+        if Instant::now() >= next_scan {
+            let started = Instant::now();
+            let scanned = list::list_projects(config);
+            let mut metrics = shared.lock().unwrap_or_else(PoisonError::into_inner);
+            match scanned {
+                Ok(projects) => metrics.record_scan(&projects, started.elapsed()),
+                Err(e) => {
+                    warn!("Daemon scan failed: {e}");
+                    metrics.record_scan_error();
+                }
+            }
+            next_scan = started + interval;
+        }
+        match follower.read_lines() {
+            Ok(lines) => {
+                let mut metrics = shared.lock().unwrap_or_else(PoisonError::into_inner);
+                for line in lines {
+                    metrics.record_event(&line);
+                }
+            }
+            Err(e) => warn!("Cannot read {}: {e}", events::file_path().display()),
+        }
+        thread::sleep(DAEMON_TICK);
+    }
+}
+
+/// Run `f` with the configuration, or report why there is none (exit code 1).
+fn with_config(format: Format, f: impl FnOnce(&Config) -> i32) -> i32 {
+    match Config::load() {
+        Ok(LoadStatus::Ready(config)) => f(&config),
+        Ok(LoadStatus::NeedsInitialSetup(_)) => fail(
+            format,
+            None,
+            "rustm is not configured yet: run `rustm` once to set it up",
+        ),
+        Err(e) => fail(format, None, &e.to_string()),
+    }
+}
+
+/// Report success, printing `path` when there is one; exit code 0.
+fn succeed(format: Format, path: Option<&Path>) -> i32 {
+    match format {
+        Format::Text => {
+            if let Some(path) = path {
+                println!("{}", path.display());
+            }
+        }
+        Format::Json => print_json(&Outcome {
+            ok: true,
+            path: path.map(Path::to_path_buf),
+            error: None,
+        }),
+    }
+    0
+}
+
+/// Report `error` (to stderr, or as a JSON outcome on stdout); exit code 1.
+fn fail(format: Format, path: Option<&Path>, error: &str) -> i32 {
+    match format {
+        Format::Text => {
+            if let Some(path) = path {
+                println!("{}", path.display());
+            }
+            eprintln!("{error}");
+        }
+        Format::Json => print_json(&Outcome {
+            ok: false,
+            path: path.map(Path::to_path_buf),
+            error: Some(error.to_string()),
+        }),
+    }
+    1
+}
+
+fn print_json(value: &impl Serialize) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{json}"),
+        Err(e) => eprintln!("Cannot serialize the output: {e}"),
+    }
+}
+
+fn list(config: &Config, format: Format) -> i32 {
+    let mut projects = match list::list_projects(config) {
+        Ok(projects) => projects,
+        Err(e) => return fail(format, None, &format!("Cannot list the projects: {e}")),
+    };
+    list::sort_projects(&mut projects, metadata::snapshot().sort_mode());
+    match format {
+        Format::Text => {
+            let width = projects.iter().map(|p| p.name.len()).max().unwrap_or(0);
+            for p in &projects {
+                println!("{}", list_line(p, width));
+            }
+        }
+        Format::Json => print_json(&projects),
+    }
+    0
+}
+
+/// Apply `operation` to the projects matching `filter`, or only list them
+/// without one; exit code 3 when it failed for some.
+fn batch(config: &Config, format: Format, filter: &Filter, operation: Option<Operation>) -> i32 {
+    let mut projects = match list::list_projects(config) {
+        Ok(projects) => projects,
+        Err(e) => return fail(format, None, &format!("Cannot list the projects: {e}")),
+    };
+    list::sort_projects(&mut projects, metadata::snapshot().sort_mode());
+    let root = Path::new(config.projects_directory());
+    let outcomes: Vec<BatchOutcome> = filter
+        .select(projects)
+        .into_iter()
+        .map(|project| {
+            let result = match operation {
+                Some(operation) => operation.apply(root, &project, &|_| {}),
+                None => Ok("matches".to_string()),
+            };
+            if format == Format::Text {
+                match &result {
+                    Ok(note) => println!("✓ {}: {note}", project.name),
+                    Err(e) => println!("✗ {}: {e}", project.name),
+                }
+            }
+            BatchOutcome {
+                name: project.name,
+                path: project.path,
+                ok: result.is_ok(),
+                note: result.as_ref().ok().cloned(),
+                error: result.err(),
+            }
+        })
+        .collect();
+    let failed = outcomes.iter().filter(|o| !o.ok).count();
+    match format {
+        Format::Text => eprintln!("{} succeeded, {failed} failed.", outcomes.len() - failed),
+        Format::Json => print_json(&BatchReport {
+            ok: failed == 0,
+            projects: outcomes,
+        }),
+    }
+    if failed == 0 { 0 } else { 3 }
+}
+
+/// `name  *  version  branch  path`, `*` marking uncommitted changes (`-` otherwise).
+fn list_line(p: &ProjectInfo, name_width: usize) -> String {
+    format!(
+        "{:<name_width$}  {}  {:<8}  {:<18}  {}",
+        p.name,
+        if p.has_uncommitted_changes { "*" } else { "-" },
+        p.version.as_deref().unwrap_or("-"),
+        p.branch.as_deref().unwrap_or("-"),
+        p.path.display()
+    )
+}
+
+fn new(config: &Config, format: Format, params: CreateProjectParams, open: bool) -> i32 {
+    match create::create_and_optionally_open(config, params, open) {
+        Ok(result) => {
+            if open {
+                metadata::record_opened(&result.project_path);
+            }
+            for failure in &result.setup_failures {
+                eprintln!("Not set up: {failure}");
+            }
+            if let Some(e) = &result.commit_error {
+                eprintln!("Initial commit not made: {e}");
+            }
+            if let Some(url) = &result.remote_url {
+                eprintln!("Remote repository: {url}");
+            }
+            if let Some(e) = &result.remote_error {
+                eprintln!("Remote repository not set up: {e}");
+            }
+            succeed(format, Some(&result.project_path))
+        }
+        Err(e) => {
+            // The project exists even when the editor could not be started.
+            let created = match &e {
+                CreateAndOpenError::OpenAfterCreate { result, .. } => Some(&result.project_path),
+                CreateAndOpenError::CreateFailed(_) => None,
+            };
+            fail(format, created.map(PathBuf::as_path), &e.to_string())
+        }
+    }
+}
+
+fn open(config: &Config, format: Format, project: &str) -> i32 {
+    let path = match find_project(config, project) {
+        Ok(path) => path,
+        Err(message) => return fail(format, None, &message),
+    };
+    match editor::launch(config, &path)
+        .and_then(|foreground| foreground.into_iter().try_for_each(editor::run_foreground))
+    {
+        Ok(()) => {
+            metadata::record_opened(&path);
+            match format {
+                Format::Text => 0,
+                Format::Json => succeed(format, Some(&path)),
+            }
+        }
+        Err(e) => fail(
+            format,
+            None,
+            &format!("Cannot open {}: {e}", path.display()),
+        ),
+    }
+}
+
+/// Directory of the project named `project`, or `project` itself when it is a project directory.
+fn find_project(config: &Config, project: &str) -> Result<PathBuf, String> {
+    let as_path = Path::new(project);
+    if as_path.join("Cargo.toml").is_file() {
+        return Ok(as_path.to_path_buf());
+    }
+    let projects = list::list_projects(config).map_err(|e| e.to_string())?;
+    let found: Vec<&ProjectInfo> = projects.iter().filter(|p| p.name == project).collect();
+    match found.as_slice() {
+        [] => Err(format!("No project named '{project}'")),
+        [p] => Ok(p.path.clone()),
+        several => Err(format!(
+            "Several projects are named '{project}', give a path instead:\n{}",
+            several
+                .iter()
+                .map(|p| format!("  {}", p.path.display()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )),
+    }
+}
+
+fn config_get(config: &Config, format: Format, key: &str) -> i32 {
+    let Some(value) = config.get(key) else {
+        return fail(format, None, &format!("Unknown setting '{key}'"));
+    };
+    match format {
+        Format::Text => match serde_norway::to_string(&value) {
+            Ok(yaml) => println!("{}", yaml.trim_end()),
+            Err(e) => return fail(format, None, &e.to_string()),
+        },
+        Format::Json => print_json(&value),
+    }
+    0
+}
+
+fn config_set(config: &Config, format: Format, key: &str, value: &str) -> i32 {
+    match config.set(key, value) {
+        Ok(_) => match format {
+            Format::Text => 0,
+            Format::Json => succeed(format, None),
+        },
+        Err(e) => fail(format, None, &format!("Cannot set '{key}': {e}")),
+    }
+}
+
+fn report(path: &Path, format: Format) -> i32 {
+    if !path.join("Cargo.toml").is_file() {
+        return fail(
+            format,
+            None,
+            &format!("{} is not a Rust project (no Cargo.toml)", path.display()),
+        );
+    }
+
+    let report = env_report::collect(path);
+    match format {
+        Format::Text => print!("{}", report.to_text()),
+        Format::Json => println!("{}", report.to_json()),
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments_are_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn batch_all_excludes_filters() {
+        let parse = |args: &[&str]| Cli::try_parse_from([&["rustm", "batch"], args].concat());
+        assert!(parse(&["clean", "--all"]).is_ok());
+        assert!(parse(&["clean", "--all", "--tag", "work"]).is_err());
+        assert!(BatchOperation::Archive.changes_projects());
+        assert!(!BatchOperation::Fetch.changes_projects());
+    }
+}
@@ -8,6 +8,12 @@
 //! - Provide cheap cloning via Arc
 //! - Provide validation for `projects_directory` (exists, is a directory, readable, writable)
 //! - Disallow blank / empty strings
+//! - Optional settings (e.g. `editor_profiles`) default when absent so older files stay valid
+//! - `projects_directory` and `editor_cmd` can be overridden for one run (`Overrides`):
+//!   `--projects-dir` / `--editor`, else `RUSTM_PROJECTS_DIR` / `RUSTM_EDITOR`. `load()`
+//!   applies them over the file; they are never written back to it
+//! - A project can adjust a few settings in its own `.rustm.yaml` (`ProjectSettings`);
+//!   `Config::for_project` merges them into an `EffectiveConfig`
 //!
 //! UI / TUI integration policy (kept decoupled here):
 //! - The TUI layer should call `load()`:
@@ -21,24 +27,925 @@
 //! YAML backend: `serde_norway` (spec requirement; API-compatible with `serde_yaml`).
 
 use std::{
+    collections::BTreeMap,
     fs,
     io::{self, Write},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, OnceLock},
 };
 
+use log::{LevelFilter, info, warn};
 use serde::{Deserialize, Serialize};
 
+use crate::metadata;
+use crate::project::create::{ProjectEdition, ProjectType};
+use crate::project::kind::ProjectKind;
+use crate::project::license::License;
+use crate::project::manifest::LINT_LEVELS;
+use crate::theme;
+
 /// Public handle to configuration (cheap clone).
 #[derive(Clone)]
 pub struct Config {
     inner: Arc<ConfigInner>,
+    overrides: Arc<Overrides>,
+}
+
+/// Environment variable overriding `projects_directory`.
+const PROJECTS_DIR_VAR: &str = "RUSTM_PROJECTS_DIR";
+
+/// Environment variable overriding `editor_cmd`.
+const EDITOR_VAR: &str = "RUSTM_EDITOR";
+
+/// Target of the no_std probe when `no_std_target` is unset: a bare-metal target without `std`.
+const DEFAULT_NO_STD_TARGET: &str = "thumbv7m-none-eabi";
+
+/// Overrides given on the command line, set once at startup.
+static CLI_OVERRIDES: OnceLock<Overrides> = OnceLock::new();
+
+/// Values taking precedence over `config.yaml` for this run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Overrides {
+    pub projects_directory: Option<String>,
+    pub editor_cmd: Option<String>,
+}
+
+impl Overrides {
+    /// Record the `--projects-dir` / `--editor` values; they win over the environment.
+    pub fn set_cli(cli: Self) {
+        let _ = CLI_OVERRIDES.set(cli);
+    }
+
+    /// Command line values, else environment variables (blank values ignored).
+    fn current() -> Self {
+        let cli = CLI_OVERRIDES.get().cloned().unwrap_or_default();
+        let env = |name| std::env::var(name).ok();
+        Self {
+            projects_directory: non_blank(cli.projects_directory.or_else(|| env(PROJECTS_DIR_VAR))),
+            editor_cmd: non_blank(cli.editor_cmd.or_else(|| env(EDITOR_VAR))),
+        }
+    }
+}
+
+fn non_blank(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct ConfigInner {
     projects_directory: String,
     editor_cmd: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    editor_profiles: Vec<EditorProfile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    editors: Vec<NamedEditor>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    editor_modes: BTreeMap<String, EditorMode>,
+    #[serde(default)]
+    workspace_prep: WorkspacePrep,
+    #[serde(default)]
+    scan: ScanSettings,
+    #[serde(default)]
+    release: ReleaseSettings,
+    #[serde(default)]
+    commit: CommitSettings,
+    #[serde(default)]
+    scaffold: ScaffoldSettings,
+    #[serde(default)]
+    feature_matrix: FeatureMatrixSettings,
+    #[serde(default)]
+    sandbox: SandboxSettings,
+    #[serde(default)]
+    forge: ForgeSettings,
+    #[serde(default)]
+    quarantine_new_projects: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    remote_runners: Vec<RemoteRunner>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    github_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gitlab_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    terminal_cmd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    theme: Option<String>,
+    #[serde(default)]
+    timestamps: Timestamps,
+    #[serde(default)]
+    age_colors: AgeColors,
+    #[serde(default)]
+    rendering_mode: RenderingMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    no_std_target: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    template_index: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author_email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default_license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default_edition: Option<u16>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    presets: Vec<Preset>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    lint_policy: BTreeMap<String, BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    log_levels: BTreeMap<String, String>,
+}
+
+impl ConfigInner {
+    /// Required settings, the others at their defaults.
+    fn new(projects_directory: String, editor_cmd: String) -> Self {
+        Self {
+            projects_directory,
+            editor_cmd,
+            editor_profiles: Vec::new(),
+            editors: Vec::new(),
+            editor_modes: BTreeMap::new(),
+            workspace_prep: WorkspacePrep::default(),
+            scan: ScanSettings::default(),
+            release: ReleaseSettings::default(),
+            commit: CommitSettings::default(),
+            scaffold: ScaffoldSettings::default(),
+            feature_matrix: FeatureMatrixSettings::default(),
+            sandbox: SandboxSettings::default(),
+            forge: ForgeSettings::default(),
+            quarantine_new_projects: false,
+            remote_runners: Vec::new(),
+            github_token: None,
+            gitlab_token: None,
+            terminal_cmd: None,
+            theme: None,
+            timestamps: Timestamps::default(),
+            age_colors: AgeColors::default(),
+            rendering_mode: RenderingMode::default(),
+            no_std_target: None,
+            template_index: None,
+            author_name: None,
+            author_email: None,
+            default_license: None,
+            default_edition: None,
+            presets: Vec::new(),
+            lint_policy: BTreeMap::new(),
+            log_levels: BTreeMap::new(),
+        }
+    }
+}
+
+/// A setting of `config.yaml`, by dotted key, as listed by the settings screen.
+pub struct Setting {
+    pub key: &'static str,
+    pub description: &'static str,
+    /// Can be left unset (empty value).
+    pub optional: bool,
+}
+
+/// Every setting, in display order.
+pub const SETTINGS: [Setting; 49] = [
+    Setting {
+        key: "projects_directory",
+        description: "Directory where projects are created and listed from",
+        optional: false,
+    },
+    Setting {
+        key: "editor_cmd",
+        description: "Command opening a project (the path is appended)",
+        optional: false,
+    },
+    Setting {
+        key: "editor_profiles",
+        description: "Editor commands for matching projects (YAML list of name / when / commands)",
+        optional: true,
+    },
+    Setting {
+        key: "editors",
+        description: "Editors offered when opening a project (YAML list of name / command)",
+        optional: true,
+    },
+    Setting {
+        key: "editor_modes",
+        description: "How each editor runs: detach (GUI) or terminal (suspends the TUI), e.g. {vim: terminal}",
+        optional: true,
+    },
+    Setting {
+        key: "terminal_cmd",
+        description: "Command opening a terminal in a project (auto-detected when unset)",
+        optional: true,
+    },
+    Setting {
+        key: "theme",
+        description: "TUI theme: auto, dark, light, solarized or a file of the themes directory (auto when unset)",
+        optional: true,
+    },
+    Setting {
+        key: "timestamps",
+        description: "Times shown as relative (3 days ago) or absolute (2025-06-01 14:03 UTC)",
+        optional: false,
+    },
+    Setting {
+        key: "age_colors.fresh_days",
+        description: "Project list times younger than this many days are green",
+        optional: false,
+    },
+    Setting {
+        key: "age_colors.stale_days",
+        description: "Project list times younger than this many days are yellow, older ones dimmed",
+        optional: false,
+    },
+    Setting {
+        key: "rendering_mode",
+        description: "Glyphs of the project kinds in the list: ascii, or nerd_font for terminals with a Nerd Font",
+        optional: false,
+    },
+    Setting {
+        key: "workspace_prep.generate_cmd",
+        description: "Code generation command run by \"Open full workspace\"",
+        optional: true,
+    },
+    Setting {
+        key: "workspace_prep.cargo_check",
+        description: "Run cargo check before opening the full workspace",
+        optional: false,
+    },
+    Setting {
+        key: "workspace_prep.vscode_settings",
+        description: "Create .vscode/settings.json when missing",
+        optional: false,
+    },
+    Setting {
+        key: "workspace_prep.vscode_settings_template",
+        description: "Template for .vscode/settings.json (built-in when unset)",
+        optional: true,
+    },
+    Setting {
+        key: "scan.max_depth",
+        description: "Directory depth searched for projects",
+        optional: false,
+    },
+    Setting {
+        key: "scan.ignore",
+        description: "Directory names never searched (YAML list)",
+        optional: false,
+    },
+    Setting {
+        key: "scan.skip_hidden",
+        description: "Skip directories starting with a dot",
+        optional: false,
+    },
+    Setting {
+        key: "scan.follow_symlinks",
+        description: "Descend into symbolic links to directories",
+        optional: false,
+    },
+    Setting {
+        key: "scan.git_status",
+        description: "Compute the git working tree status and upstream divergence (slow on network drives)",
+        optional: false,
+    },
+    Setting {
+        key: "scan.roots",
+        description: "Directories of the projects directory scanned differently, e.g. {mirror: {max_depth: 1, git_status: false}}",
+        optional: true,
+    },
+    Setting {
+        key: "scan.status_refresh_secs",
+        description: "Seconds between git status rescans of the open project list (0: off)",
+        optional: false,
+    },
+    Setting {
+        key: "release.targets",
+        description: "Release target triples, host only when empty (YAML list)",
+        optional: false,
+    },
+    Setting {
+        key: "release.builder",
+        description: "Release build tool: auto, cargo, cross or zigbuild",
+        optional: false,
+    },
+    Setting {
+        key: "commit.large_file_kib",
+        description: "Staged files above this size are reported before committing (0: off)",
+        optional: false,
+    },
+    Setting {
+        key: "scaffold.module_style",
+        description: "New modules: file (src/<name>.rs) or mod_rs (src/<name>/mod.rs)",
+        optional: false,
+    },
+    Setting {
+        key: "feature_matrix.commands",
+        description: "Cargo commands run for every feature combination (YAML list)",
+        optional: false,
+    },
+    Setting {
+        key: "feature_matrix.no_default_features",
+        description: "Feature matrix: build without the default features",
+        optional: false,
+    },
+    Setting {
+        key: "feature_matrix.each_feature",
+        description: "Feature matrix: build each feature alone",
+        optional: false,
+    },
+    Setting {
+        key: "feature_matrix.all_features",
+        description: "Feature matrix: build with all features",
+        optional: false,
+    },
+    Setting {
+        key: "feature_matrix.skip",
+        description: "Features never built alone by the feature matrix (YAML list)",
+        optional: false,
+    },
+    Setting {
+        key: "sandbox.enabled",
+        description: "Run builds and tests of the project menu in a container",
+        optional: false,
+    },
+    Setting {
+        key: "sandbox.runtime",
+        description: "Container runtime of the sandbox: auto, docker or podman",
+        optional: false,
+    },
+    Setting {
+        key: "sandbox.image",
+        description: "Rust image of the sandbox (rust:latest by default)",
+        optional: false,
+    },
+    Setting {
+        key: "quarantine_new_projects",
+        description: "Mark projects appearing in the projects directory as untrusted",
+        optional: false,
+    },
+    Setting {
+        key: "remote_runners",
+        description: "SSH hosts for \"Check on other platforms\" (YAML list of name / host / path)",
+        optional: true,
+    },
+    Setting {
+        key: "no_std_target",
+        description: "Target of the no_std probe, without std (thumbv7m-none-eabi when unset)",
+        optional: true,
+    },
+    Setting {
+        key: "author_name",
+        description: "Author of new projects: package.authors and license copyright holder (git user.name when unset)",
+        optional: true,
+    },
+    Setting {
+        key: "author_email",
+        description: "Email of the author, added to package.authors of new projects",
+        optional: true,
+    },
+    Setting {
+        key: "default_license",
+        description: "License preselected for new projects: mit, apache or dual (none when unset)",
+        optional: true,
+    },
+    Setting {
+        key: "default_edition",
+        description: "Rust edition preselected for new projects (2024 when unset)",
+        optional: true,
+    },
+    Setting {
+        key: "template_index",
+        description: "URL (or file) of a JSON / YAML index of project templates offered on creation",
+        optional: true,
+    },
+    Setting {
+        key: "presets",
+        description: "Creation presets (YAML list of name / project_type / edition / template / ci / license / registry / lints)",
+        optional: true,
+    },
+    Setting {
+        key: "lint_policy",
+        description: "Canonical lint levels per tool, applied by the \"Apply lint policy\" batch action, e.g. {rust: {unsafe_code: forbid}, clippy: {pedantic: warn}}",
+        optional: true,
+    },
+    Setting {
+        key: "log_levels",
+        description: "Log level per module of rustm.log, e.g. {git2: warn, rustm::project: trace}",
+        optional: true,
+    },
+    Setting {
+        key: "github_token",
+        description: "GitHub token for publishing releases and creating repositories (GITHUB_TOKEN when unset)",
+        optional: true,
+    },
+    Setting {
+        key: "gitlab_token",
+        description: "GitLab token for creating repositories (GITLAB_TOKEN when unset)",
+        optional: true,
+    },
+    Setting {
+        key: "forge.gitlab_url",
+        description: "GitLab instance of new remote repositories (https://gitlab.com by default)",
+        optional: false,
+    },
+    Setting {
+        key: "forge.protocol",
+        description: "URL of the origin of new remote repositories: ssh or https",
+        optional: false,
+    },
+];
+
+/// Editor launch profile: command(s) used instead of `editor_cmd` for matching projects.
+///
+/// Profiles are evaluated in declaration order when opening a project; the first
+/// match wins. Each command gets the project path appended as last argument.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditorProfile {
+    pub name: String,
+    #[serde(default)]
+    pub when: ProfileMatch,
+    pub commands: Vec<String>,
+}
+
+/// Editor offered when opening a project, besides the default one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedEditor {
+    pub name: String,
+    pub command: String,
+}
+
+/// Named set of choices for new projects, picked at once in the create dialog.
+///
+/// Unset fields keep the dialog's choice (type, edition) or add nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    /// `bin` or `lib`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edition: Option<String>,
+    /// Name of a template of `template_index`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Add the GitHub Actions workflow of the hygiene checklist.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ci: bool,
+    /// SPDX expression set as `package.license`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Only registry the package may be published to (`package.publish`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    /// Lint levels by tool, then lint: `{clippy: {pedantic: warn}}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lints: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// How an editor runs from the TUI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EditorMode {
+    /// Started in the background (GUI editors).
+    Detach,
+    /// Run in the terminal, the TUI suspended until it exits.
+    Terminal,
+}
+
+/// Conditions a project must satisfy for a profile to apply (all set fields must match).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileMatch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ProjectKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependency: Option<String>,
+}
+
+/// Steps performed by "Open full workspace" before launching the editor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspacePrep {
+    /// Code generation command run in the project directory (skipped when unset).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generate_cmd: Option<String>,
+    /// Run `cargo check --all-targets` to warm build scripts and proc macros.
+    pub cargo_check: bool,
+    /// Create `.vscode/settings.json` when missing.
+    pub vscode_settings: bool,
+    /// Template file for `.vscode/settings.json` (built-in template when unset).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vscode_settings_template: Option<String>,
+}
+
+impl Default for WorkspacePrep {
+    fn default() -> Self {
+        Self {
+            generate_cmd: None,
+            cargo_check: true,
+            vscode_settings: true,
+            vscode_settings_template: None,
+        }
+    }
+}
+
+/// How the projects directory is scanned for Rust projects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanSettings {
+    /// Maximum directory depth below the projects directory (1 = immediate children only).
+    pub max_depth: usize,
+    /// Directory names never descended into.
+    pub ignore: Vec<String>,
+    /// Skip directories whose name starts with a dot.
+    pub skip_hidden: bool,
+    /// Seconds between automatic git status rescans of the open project list (0: off).
+    pub status_refresh_secs: u64,
+    /// Descend into symbolic links to directories.
+    pub follow_symlinks: bool,
+    /// Compute the working tree status and upstream divergence of git projects.
+    pub git_status: bool,
+    /// Directories of the projects directory (relative paths) scanned with other settings.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub roots: BTreeMap<String, RootScan>,
+}
+
+impl Default for ScanSettings {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            ignore: vec!["target".into(), "node_modules".into()],
+            skip_hidden: true,
+            status_refresh_secs: 0,
+            follow_symlinks: false,
+            git_status: true,
+            roots: BTreeMap::new(),
+        }
+    }
+}
+
+/// Scan settings of one directory of `scan.roots`; unset fields keep the `scan` ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RootScan {
+    /// Depth below this directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub follow_symlinks: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_status: Option<bool>,
+}
+
+impl ScanSettings {
+    /// The directories to scan below `root`, each with its settings: `root`
+    /// first, then the existing directories of `roots`.
+    ///
+    /// Each directory is left out of the scans of the others (see `excluded`).
+    pub fn scan_roots(&self, root: &Path) -> Vec<(PathBuf, ScanSettings)> {
+        let base = Self {
+            roots: BTreeMap::new(),
+            ..self.clone()
+        };
+        let mut scans = vec![(root.to_path_buf(), base.clone())];
+        for (dir, overrides) in &self.roots {
+            let path = root.join(dir);
+            if !path.is_dir() {
+                warn!("scan.roots: {} is not a directory", path.display());
+                continue;
+            }
+            scans.push((
+                path,
+                Self {
+                    max_depth: overrides.max_depth.unwrap_or(base.max_depth),
+                    follow_symlinks: overrides.follow_symlinks.unwrap_or(base.follow_symlinks),
+                    ignore: overrides
+                        .ignore
+                        .clone()
+                        .unwrap_or_else(|| base.ignore.clone()),
+                    git_status: overrides.git_status.unwrap_or(base.git_status),
+                    ..base.clone()
+                },
+            ));
+        }
+        scans
+    }
+
+    /// Whether `dir` below `root` is one of `roots`, scanned on its own.
+    pub fn excluded(&self, root: &Path, dir: &Path) -> bool {
+        dir.strip_prefix(root)
+            .is_ok_and(|relative| self.roots.keys().any(|r| Path::new(r) == relative))
+    }
+
+    /// `git_status` for the project at `path` below `root`: the one of the deepest of `roots` containing it.
+    pub fn git_status_at(&self, root: &Path, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(root) else {
+            return self.git_status;
+        };
+        self.roots
+            .iter()
+            .filter(|(dir, _)| relative.starts_with(dir))
+            .max_by_key(|(dir, _)| Path::new(dir).components().count())
+            .and_then(|(_, overrides)| overrides.git_status)
+            .unwrap_or(self.git_status)
+    }
+}
+
+/// Settings for the "Build release artifacts" workflow.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReleaseSettings {
+    /// Target triples to build (host triple only when empty).
+    pub targets: Vec<String>,
+    /// Build tool selection.
+    pub builder: ReleaseBuilder,
+}
+
+/// Tool used to build release artifacts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseBuilder {
+    /// `cargo` for the host, otherwise `cross`, then `cargo-zigbuild`, then `cargo`, whichever is installed.
+    #[default]
+    Auto,
+    Cargo,
+    Cross,
+    Zigbuild,
+}
+
+/// Checks of the commit screen.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommitSettings {
+    /// Staged files larger than this many KiB are reported before committing (0 disables the check).
+    pub large_file_kib: u64,
+}
+
+impl Default for CommitSettings {
+    fn default() -> Self {
+        Self {
+            large_file_kib: 1024,
+        }
+    }
+}
+
+/// Per-project settings file, at the project root.
+pub const PROJECT_FILE: &str = ".rustm.yaml";
+
+/// Settings of a project's `.rustm.yaml`; every field is optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectSettings {
+    /// Editor command for this project, instead of `editor_profiles` and `editor_cmd`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor_cmd: Option<String>,
+    /// Cargo profile used by "Build" (`cargo build --profile <name>`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_profile: Option<String>,
+    /// Commands offered in the project menu.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<CustomAction>,
+}
+
+/// Named command run in the project directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomAction {
+    pub name: String,
+    pub command: String,
+}
+
+impl ProjectSettings {
+    /// Settings of the project at `project_path` (`None` without `.rustm.yaml`).
+    ///
+    /// Blank values are dropped, as are actions without a name or a command.
+    pub fn load(project_path: &Path) -> Result<Option<Self>, String> {
+        let path = project_path.join(PROJECT_FILE);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Cannot read {}: {e}", path.display())),
+        };
+        if text.trim().is_empty() {
+            return Ok(Some(Self::default()));
+        }
+        let settings: Self = serde_norway::from_str(&text)
+            .map_err(|e| format!("Invalid {}: {e}", path.display()))?;
+        Ok(Some(Self {
+            editor_cmd: non_blank(settings.editor_cmd),
+            build_profile: non_blank(settings.build_profile),
+            actions: settings
+                .actions
+                .into_iter()
+                .filter(|a| !a.name.trim().is_empty() && !a.command.trim().is_empty())
+                .collect(),
+        }))
+    }
+}
+
+/// Configuration for one project: `config.yaml` with the project's `.rustm.yaml` applied.
+#[derive(Clone)]
+pub struct EffectiveConfig {
+    config: Config,
+    project: Arc<ProjectSettings>,
+}
+
+impl EffectiveConfig {
+    /// Editor command of `.rustm.yaml`, unless `editor_cmd` is overridden for this run.
+    pub fn project_editor_cmd(&self) -> Option<&str> {
+        if self.config.is_overridden("editor_cmd") {
+            return None;
+        }
+        self.project.editor_cmd.as_deref()
+    }
+
+    /// Editor command: run override, else `.rustm.yaml`, else `config.yaml`.
+    pub fn editor_cmd(&self) -> &str {
+        self.project_editor_cmd()
+            .unwrap_or_else(|| self.config.editor_cmd())
+    }
+
+    /// Cargo profile for builds (`None`: cargo's default).
+    pub fn build_profile(&self) -> Option<&str> {
+        self.project.build_profile.as_deref()
+    }
+
+    /// Custom actions of the project, in file order.
+    pub fn actions(&self) -> &[CustomAction] {
+        &self.project.actions
+    }
+}
+
+/// Settings of the code generators.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScaffoldSettings {
+    /// File layout of new modules.
+    pub module_style: ModuleStyle,
+}
+
+/// Where the code of a new module goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModuleStyle {
+    /// `src/<name>.rs`.
+    #[default]
+    File,
+    /// `src/<name>/mod.rs`.
+    ModRs,
+}
+
+/// Feature combinations and commands of the feature matrix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeatureMatrixSettings {
+    /// Cargo commands (`check`, `test --no-run`, ...) run for every combination.
+    pub commands: Vec<String>,
+    /// Add a `--no-default-features` combination.
+    pub no_default_features: bool,
+    /// Add a combination per feature, with only that feature enabled.
+    pub each_feature: bool,
+    /// Add an `--all-features` combination.
+    pub all_features: bool,
+    /// Features left out of the "each feature" combinations.
+    pub skip: Vec<String>,
+}
+
+impl Default for FeatureMatrixSettings {
+    fn default() -> Self {
+        Self {
+            commands: vec!["check".into(), "test".into()],
+            no_default_features: true,
+            each_feature: true,
+            all_features: true,
+            skip: Vec::new(),
+        }
+    }
+}
+
+/// Container sandbox of builds and tests (see `project::sandbox`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SandboxSettings {
+    /// Run the project menu's builds and tests in a container.
+    pub enabled: bool,
+    pub runtime: ContainerRuntime,
+    /// Image with a Rust toolchain, `cargo` on its `PATH`.
+    pub image: String,
+}
+
+impl Default for SandboxSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            runtime: ContainerRuntime::default(),
+            image: "rust:latest".into(),
+        }
+    }
+}
+
+/// Program running the sandbox containers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRuntime {
+    /// `podman` when installed, else `docker`.
+    #[default]
+    Auto,
+    Docker,
+    Podman,
+}
+
+/// Remote repositories created with new projects (see `crate::forge`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForgeSettings {
+    /// Root URL of the GitLab instance.
+    pub gitlab_url: String,
+    pub protocol: RemoteProtocol,
+}
+
+impl Default for ForgeSettings {
+    fn default() -> Self {
+        Self {
+            gitlab_url: "https://gitlab.com".into(),
+            protocol: RemoteProtocol::default(),
+        }
+    }
+}
+
+/// URL scheme of the `origin` remote of a created repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteProtocol {
+    /// Pushed with the SSH agent's keys.
+    #[default]
+    Ssh,
+    /// Pushed with the git credential helpers.
+    Https,
+}
+
+/// How times are shown (see `crate::format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Timestamps {
+    /// `3 days ago`.
+    #[default]
+    Relative,
+    /// `2025-06-01 14:03 UTC`.
+    Absolute,
+}
+
+/// Glyph set of the project list (see `ui::project_list`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderingMode {
+    /// Plain characters, readable with any font.
+    #[default]
+    Ascii,
+    /// Icons of the Nerd Fonts patched fonts.
+    NerdFont,
+}
+
+/// Age buckets coloring the last commit and last opened columns of the project list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgeColors {
+    /// Times younger than this many days are fresh.
+    pub fresh_days: u32,
+    /// Times younger than this many days (and not fresh) are weeks old; older
+    /// ones are months old.
+    pub stale_days: u32,
+}
+
+impl Default for AgeColors {
+    fn default() -> Self {
+        Self {
+            fresh_days: 7,
+            stale_days: 60,
+        }
+    }
+}
+
+impl AgeColors {
+    /// Bucket of something `seconds` old.
+    pub fn age(&self, seconds: i64) -> theme::Age {
+        let days = seconds / 86_400;
+        if days < i64::from(self.fresh_days) {
+            theme::Age::Fresh
+        } else if days < i64::from(self.stale_days) {
+            theme::Age::Weeks
+        } else {
+            theme::Age::Months
+        }
+    }
+}
+
+/// Build host of "Check on other platforms", reached with `ssh` and `rsync`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteRunner {
+    pub name: String,
+    /// `ssh` destination (`user@host` or a `~/.ssh/config` alias).
+    pub host: String,
+    /// Directory on the host receiving the project copies (one subdirectory per project).
+    pub path: String,
 }
 
 /// Status returned when attempting to load config from disk.
@@ -60,6 +967,9 @@ pub enum LoadError {
     /// YAML exists but is syntactically invalid or semantically unacceptable.
     Corrupt(String),
 
+    /// A command line / environment override is unusable (e.g. missing projects directory).
+    InvalidOverride(String),
+
     Io(io::Error),
 }
 
@@ -68,6 +978,10 @@ pub enum SaveError {
     Io(io::Error),
     Serialize(String),
     Validation(ValidationError),
+    /// `Config::set` on a key the configuration does not have.
+    UnknownKey(String),
+    /// `Config::set` with a value of the wrong type.
+    InvalidValue(String),
 }
 
 /// Validation errors for user-provided values.
@@ -81,67 +995,83 @@ pub enum ValidationError {
 }
 
 impl Config {
-    /// Attempt to load configuration from disk.
+    /// Attempt to load configuration from disk, with the command line and environment overrides applied.
     ///
     /// Returns:
     /// - `Ok(LoadStatus::Ready)` if file exists, parses, and validates
     /// - `Ok(LoadStatus::NeedsInitialSetup)` if file missing OR some field blank
     /// - `Err(LoadError::Corrupt)` if YAML malformed
+    /// - `Err(LoadError::InvalidOverride)` if an overridden projects directory is invalid
     /// - `Err(LoadError::Io)` for unexpected I/O problems
     pub fn load() -> Result<LoadStatus, LoadError> {
-        let path = config_file_path();
-
-        if !path.exists() {
-            return Ok(LoadStatus::NeedsInitialSetup(SetupReason::MissingFile));
-        }
-
-        let raw = fs::read_to_string(&path).map_err(LoadError::Io)?;
+        Self::load_from(&config_file_path(), Overrides::current())
+    }
 
-        match serde_norway::from_str::<ConfigInner>(&raw) {
-            Ok(inner) => {
-                // Semantic validation (no blank fields, valid directory)
-                if inner.projects_directory.trim().is_empty() {
-                    return Ok(LoadStatus::NeedsInitialSetup(SetupReason::IncompleteData));
-                }
-                if inner.editor_cmd.trim().is_empty() {
-                    return Ok(LoadStatus::NeedsInitialSetup(SetupReason::IncompleteData));
-                }
-                // Validate projects directory (if invalid => request setup again; user can correct)
-                let pd = PathBuf::from(&inner.projects_directory);
-                if let Err(e) = validate_projects_directory(&pd) {
-                    let msg = match e {
-                        ValidationError::ProjectsDirDoesNotExist(_) => {
-                            "projects_directory does not exist"
-                        }
-                        ValidationError::ProjectsDirNotDirectory(_) => {
-                            "projects_directory is not a directory"
-                        }
-                        ValidationError::ProjectsDirNotWritable(_) => {
-                            "projects_directory not writable"
-                        }
-                        ValidationError::ProjectsDirNotReadable(_) => {
-                            "projects_directory not readable"
-                        }
-                        ValidationError::EmptyField(_) => "projects_directory blank",
+    /// `load` for the file at `path`, `overrides` taking precedence over it.
+    ///
+    /// Without a file, overriding both required settings is enough to run (other settings
+    /// take their defaults); nothing is written.
+    fn load_from(path: &Path, overrides: Overrides) -> Result<LoadStatus, LoadError> {
+        let inner = if path.exists() {
+            let raw = fs::read_to_string(path).map_err(LoadError::Io)?;
+            match serde_norway::from_str::<ConfigInner>(&raw) {
+                Ok(inner) => inner,
+                Err(err) => {
+                    // Distinguish between YAML syntax errors (fatal) and missing fields.
+                    let msg = err.to_string();
+                    return if looks_like_missing_field(&msg) {
+                        Ok(LoadStatus::NeedsInitialSetup(SetupReason::IncompleteData))
+                    } else {
+                        Err(LoadError::Corrupt(msg))
                     };
-                    log::warn!("Config validation failed: {msg}");
-                    return Ok(LoadStatus::NeedsInitialSetup(SetupReason::IncompleteData));
                 }
-
-                Ok(LoadStatus::Ready(Self {
-                    inner: Arc::new(inner),
-                }))
             }
-            Err(err) => {
-                // Distinguish between YAML syntax errors (fatal) and missing fields.
-                let msg = err.to_string();
-                if looks_like_missing_field(&msg) {
-                    Ok(LoadStatus::NeedsInitialSetup(SetupReason::IncompleteData))
-                } else {
-                    Err(LoadError::Corrupt(msg))
+        } else {
+            match (&overrides.projects_directory, &overrides.editor_cmd) {
+                (Some(projects_directory), Some(editor_cmd)) => {
+                    ConfigInner::new(projects_directory.clone(), editor_cmd.clone())
                 }
+                _ => return Ok(LoadStatus::NeedsInitialSetup(SetupReason::MissingFile)),
             }
+        };
+        let config = Self {
+            inner: Arc::new(inner),
+            overrides: Arc::new(overrides),
+        };
+
+        // Semantic validation (no blank fields, valid directory)
+        if config.projects_directory().trim().is_empty() || config.editor_cmd().trim().is_empty() {
+            return Ok(LoadStatus::NeedsInitialSetup(SetupReason::IncompleteData));
         }
+        // Validate projects directory (if invalid => request setup again; user can correct)
+        let pd = PathBuf::from(config.projects_directory());
+        if let Err(e) = validate_projects_directory(&pd) {
+            if config.overrides.projects_directory.is_some() {
+                return Err(LoadError::InvalidOverride(e.to_string()));
+            }
+            let msg = match e {
+                ValidationError::ProjectsDirDoesNotExist(_) => "projects_directory does not exist",
+                ValidationError::ProjectsDirNotDirectory(_) => {
+                    "projects_directory is not a directory"
+                }
+                ValidationError::ProjectsDirNotWritable(_) => "projects_directory not writable",
+                ValidationError::ProjectsDirNotReadable(_) => "projects_directory not readable",
+                ValidationError::EmptyField(_) => "projects_directory blank",
+            };
+            log::warn!("Config validation failed: {msg}");
+            return Ok(LoadStatus::NeedsInitialSetup(SetupReason::IncompleteData));
+        }
+        validate_editor_profiles(&config.inner.editor_profiles).map_err(LoadError::Corrupt)?;
+        validate_editors(&config.inner.editors).map_err(LoadError::Corrupt)?;
+        validate_presets(&config.inner).map_err(LoadError::Corrupt)?;
+        validate_lints("lint_policy", &config.inner.lint_policy).map_err(LoadError::Corrupt)?;
+        validate_remote_runners(&config.inner.remote_runners).map_err(LoadError::Corrupt)?;
+        validate_log_levels(&config.inner.log_levels).map_err(LoadError::Corrupt)?;
+        validate_sandbox(&config.inner.sandbox).map_err(LoadError::Corrupt)?;
+        validate_age_colors(&config.inner.age_colors).map_err(LoadError::Corrupt)?;
+        validate_scan_roots(&config.inner.scan).map_err(LoadError::Corrupt)?;
+        validate_project_defaults(&config.inner).map_err(LoadError::Corrupt)?;
+        Ok(LoadStatus::Ready(config))
     }
 
     /// Create, validate, persist, and return a new Config.
@@ -159,10 +1089,10 @@ impl Config {
         }
         validate_projects_directory(projects_directory).map_err(SaveError::Validation)?;
 
-        let inner = ConfigInner {
-            projects_directory: projects_directory.to_string_lossy().into_owned(),
-            editor_cmd: editor_cmd.trim().to_string(),
-        };
+        let inner = ConfigInner::new(
+            projects_directory.to_string_lossy().into_owned(),
+            editor_cmd.trim().to_string(),
+        );
 
         let yaml =
             serde_norway::to_string(&inner).map_err(|e| SaveError::Serialize(e.to_string()))?;
@@ -182,11 +1112,11 @@ impl Config {
 
         Ok(Self {
             inner: Arc::new(inner),
+            overrides: Arc::new(Overrides::current()),
         })
     }
 
     /// Persist current state (validation already assumed correct).
-    #[allow(dead_code)]
     pub fn save(&self) -> Result<(), SaveError> {
         validate_projects_directory(Path::new(&self.inner.projects_directory))
             .map_err(SaveError::Validation)?;
@@ -213,19 +1143,348 @@ impl Config {
         Ok(())
     }
 
-    /// Accessor: projects directory (guaranteed non-empty).
+    /// Accessor: projects directory (guaranteed non-empty), overrides included.
     pub fn projects_directory(&self) -> &str {
-        &self.inner.projects_directory
+        self.overrides
+            .projects_directory
+            .as_deref()
+            .unwrap_or(&self.inner.projects_directory)
     }
 
+    /// Editor command, overrides included.
     pub fn editor_cmd(&self) -> &str {
-        &self.inner.editor_cmd
+        self.overrides
+            .editor_cmd
+            .as_deref()
+            .unwrap_or(&self.inner.editor_cmd)
+    }
+
+    /// `true` when the setting at `key` is overridden for this run (its file value is ignored).
+    pub fn is_overridden(&self, key: &str) -> bool {
+        match key {
+            "projects_directory" => self.overrides.projects_directory.is_some(),
+            "editor_cmd" => self.overrides.editor_cmd.is_some(),
+            _ => false,
+        }
+    }
+
+    /// Editor launch profiles, in evaluation order (may be empty).
+    pub fn editor_profiles(&self) -> &[EditorProfile] {
+        &self.inner.editor_profiles
+    }
+
+    /// Editors to choose from when opening a project, in file order (may be empty).
+    pub fn editors(&self) -> &[NamedEditor] {
+        &self.inner.editors
+    }
+
+    /// Mode set in `editor_modes` for the editor named `name` (program file stem).
+    pub fn editor_mode(&self, name: &str) -> Option<EditorMode> {
+        self.inner.editor_modes.get(name).copied()
+    }
+
+    /// Steps for "Open full workspace".
+    pub fn workspace_prep(&self) -> &WorkspacePrep {
+        &self.inner.workspace_prep
+    }
+
+    /// Project discovery settings.
+    pub fn scan(&self) -> &ScanSettings {
+        &self.inner.scan
+    }
+
+    /// Release artifact builder settings.
+    pub fn release(&self) -> &ReleaseSettings {
+        &self.inner.release
+    }
+
+    /// Commit screen settings.
+    pub fn commit(&self) -> &CommitSettings {
+        &self.inner.commit
+    }
+
+    /// Code generator settings.
+    pub fn scaffold(&self) -> &ScaffoldSettings {
+        &self.inner.scaffold
+    }
+
+    /// Feature matrix settings.
+    pub fn feature_matrix(&self) -> &FeatureMatrixSettings {
+        &self.inner.feature_matrix
+    }
+
+    /// Container sandbox settings.
+    pub fn sandbox(&self) -> &SandboxSettings {
+        &self.inner.sandbox
+    }
+
+    /// Mark projects found by a scan for the first time as untrusted (see `ui::trust`).
+    pub fn quarantine_new_projects(&self) -> bool {
+        self.inner.quarantine_new_projects
+    }
+
+    /// Hosts of "Check on other platforms", in file order (may be empty).
+    pub fn remote_runners(&self) -> &[RemoteRunner] {
+        &self.inner.remote_runners
+    }
+
+    /// Log level overrides of `log_levels`: module path prefix and level.
+    pub fn log_levels(&self) -> Vec<(String, LevelFilter)> {
+        self.inner
+            .log_levels
+            .iter()
+            .filter_map(|(target, level)| Some((target.clone(), level.parse().ok()?)))
+            .collect()
+    }
+
+    /// GitHub API token: `github_token` from the file, else the `GITHUB_TOKEN` environment variable.
+    pub fn github_token(&self) -> Option<String> {
+        self.inner
+            .github_token
+            .clone()
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+            .filter(|t| !t.trim().is_empty())
+    }
+
+    /// GitLab API token: `gitlab_token` from the file, else the `GITLAB_TOKEN` environment variable.
+    pub fn gitlab_token(&self) -> Option<String> {
+        self.inner
+            .gitlab_token
+            .clone()
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| std::env::var("GITLAB_TOKEN").ok())
+            .filter(|t| !t.trim().is_empty())
+    }
+
+    /// Remote repositories of new projects.
+    pub fn forge(&self) -> &ForgeSettings {
+        &self.inner.forge
+    }
+
+    /// Command opening a terminal in a project directory (`None`: auto-detect).
+    pub fn terminal_cmd(&self) -> Option<&str> {
+        self.inner
+            .terminal_cmd
+            .as_deref()
+            .filter(|c| !c.trim().is_empty())
+    }
+
+    /// This configuration merged with the `.rustm.yaml` of `project_path`.
+    ///
+    /// A missing file changes nothing; an invalid one is logged and ignored, as
+    /// is the file of an untrusted project (its commands would run unasked).
+    pub fn for_project(&self, project_path: &Path) -> EffectiveConfig {
+        let project = if metadata::is_untrusted(project_path) {
+            info!(
+                "{PROJECT_FILE} of untrusted {} ignored",
+                project_path.display()
+            );
+            None
+        } else {
+            ProjectSettings::load(project_path).unwrap_or_else(|e| {
+                warn!("{e}");
+                None
+            })
+        };
+        EffectiveConfig {
+            config: self.clone(),
+            project: Arc::new(project.unwrap_or_default()),
+        }
+    }
+
+    /// Target triple of the no_std probe (see `project::no_std`).
+    pub fn no_std_target(&self) -> &str {
+        self.inner
+            .no_std_target
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .unwrap_or(DEFAULT_NO_STD_TARGET)
+    }
+
+    /// Location of the project template index (see `project::templates`), when set.
+    pub fn template_index(&self) -> Option<&str> {
+        self.inner
+            .template_index
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+    }
+
+    /// Author of new projects (`author_name`), when set.
+    pub fn author_name(&self) -> Option<&str> {
+        self.inner
+            .author_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+    }
+
+    /// Email of the author (`author_email`), when set.
+    pub fn author_email(&self) -> Option<&str> {
+        self.inner
+            .author_email
+            .as_deref()
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+    }
+
+    /// License preselected for new projects, when set.
+    pub fn default_license(&self) -> Option<License> {
+        self.inner
+            .default_license
+            .as_deref()
+            .and_then(License::parse)
+    }
+
+    /// Edition preselected for new projects; the latest when unset.
+    pub fn default_edition(&self) -> ProjectEdition {
+        self.inner
+            .default_edition
+            .and_then(|e| ProjectEdition::parse(&e.to_string()))
+            .unwrap_or_default()
+    }
+
+    /// Canonical lint levels: tool (`rust`, `clippy`, ...), lint and level.
+    pub fn lint_policy(&self) -> &BTreeMap<String, BTreeMap<String, String>> {
+        &self.inner.lint_policy
+    }
+
+    /// Creation presets, in declaration order.
+    pub fn presets(&self) -> &[Preset] {
+        &self.inner.presets
+    }
+
+    /// Relative or absolute times (see `crate::format`).
+    pub fn timestamps(&self) -> Timestamps {
+        self.inner.timestamps
+    }
+
+    /// Age thresholds of the project list colors.
+    pub fn age_colors(&self) -> AgeColors {
+        self.inner.age_colors
+    }
+
+    /// Glyph set of the project list.
+    pub fn rendering_mode(&self) -> RenderingMode {
+        self.inner.rendering_mode
+    }
+
+    /// Name of the TUI theme (see `crate::theme`); `auto` when unset.
+    pub fn theme(&self) -> &str {
+        self.inner
+            .theme
+            .as_deref()
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or(theme::AUTO_THEME)
+    }
+
+    /// Configuration parsed from `yaml`, without overrides nor validation (for tests).
+    #[cfg(test)]
+    pub fn from_yaml(yaml: &str) -> Self {
+        Self {
+            inner: Arc::new(serde_norway::from_str(yaml).expect("valid configuration")),
+            overrides: Arc::new(Overrides::default()),
+        }
     }
 
     /// Path to the on-disk configuration file.
     pub fn file_path() -> PathBuf {
         config_file_path()
     }
+
+    /// Setting at dotted `key` (e.g. `scan.max_depth`) as in the file, overrides excluded; the
+    /// whole configuration when `key` is empty.
+    pub fn get(&self, key: &str) -> Option<serde_norway::Value> {
+        let root = serde_norway::to_value(&*self.inner).ok()?;
+        key.split('.')
+            .filter(|k| !k.is_empty())
+            .try_fold(&root, |v, k| v.get(k))
+            .cloned()
+    }
+
+    /// Copy with the setting at dotted `key` set to `value` (parsed as YAML), validated and saved.
+    pub fn set(&self, key: &str, value: &str) -> Result<Self, SaveError> {
+        let inner = set_value(&self.inner, key, value)?;
+        if inner.editor_cmd.trim().is_empty() {
+            return Err(SaveError::Validation(ValidationError::EmptyField(
+                "editor_cmd",
+            )));
+        }
+        validate_editor_profiles(&inner.editor_profiles).map_err(SaveError::InvalidValue)?;
+        validate_editors(&inner.editors).map_err(SaveError::InvalidValue)?;
+        validate_presets(&inner).map_err(SaveError::InvalidValue)?;
+        validate_lints("lint_policy", &inner.lint_policy).map_err(SaveError::InvalidValue)?;
+        validate_remote_runners(&inner.remote_runners).map_err(SaveError::InvalidValue)?;
+        validate_log_levels(&inner.log_levels).map_err(SaveError::InvalidValue)?;
+        validate_sandbox(&inner.sandbox).map_err(SaveError::InvalidValue)?;
+        validate_age_colors(&inner.age_colors).map_err(SaveError::InvalidValue)?;
+        if !inner.forge.gitlab_url.starts_with("https://")
+            && !inner.forge.gitlab_url.starts_with("http://")
+        {
+            return Err(SaveError::InvalidValue(
+                "forge.gitlab_url must be an http(s) URL".into(),
+            ));
+        }
+        validate_scan_roots(&inner.scan).map_err(SaveError::InvalidValue)?;
+        validate_project_defaults(&inner).map_err(SaveError::InvalidValue)?;
+        if inner
+            .feature_matrix
+            .commands
+            .iter()
+            .any(|c| c.trim().is_empty())
+        {
+            return Err(SaveError::InvalidValue(
+                "feature_matrix.commands cannot contain blank commands".into(),
+            ));
+        }
+        if let Some(name) = &inner.theme {
+            theme::load(name).map_err(|e| SaveError::InvalidValue(e.to_string()))?;
+        }
+        let config = Self {
+            inner: Arc::new(inner),
+            overrides: Arc::clone(&self.overrides),
+        };
+        config.save()?;
+        Ok(config)
+    }
+}
+
+/// `inner` with the setting at dotted `key` replaced by the YAML `value`.
+///
+/// Unknown keys are refused: they would be dropped when deserializing. An
+/// empty `value` unsets optional settings.
+fn set_value(inner: &ConfigInner, key: &str, value: &str) -> Result<ConfigInner, SaveError> {
+    let unknown = || SaveError::UnknownKey(key.to_string());
+    let mut root =
+        serde_norway::to_value(inner).map_err(|e| SaveError::Serialize(e.to_string()))?;
+    let value = serde_norway::from_str::<serde_norway::Value>(value)
+        .unwrap_or_else(|_| serde_norway::Value::String(value.to_string()));
+    let (parents, last) = match key.rsplit_once('.') {
+        Some((parents, last)) => (parents.split('.').collect::<Vec<_>>(), last),
+        None => (Vec::new(), key),
+    };
+    let mut node = &mut root;
+    for parent in parents {
+        node = node.get_mut(parent).ok_or_else(unknown)?;
+    }
+    let serde_norway::Value::Mapping(map) = node else {
+        return Err(unknown());
+    };
+    // Optional settings are omitted from the YAML when unset, so a missing key is not an error yet.
+    map.insert(serde_norway::Value::String(last.to_string()), value);
+    let updated: ConfigInner =
+        serde_norway::from_value(root).map_err(|e| SaveError::InvalidValue(e.to_string()))?;
+    let updated_root =
+        serde_norway::to_value(&updated).map_err(|e| SaveError::Serialize(e.to_string()))?;
+    // Unset optional settings are omitted from the YAML, hence the `SETTINGS` lookup.
+    let known = SETTINGS.iter().any(|s| s.key == key)
+        || key
+            .split('.')
+            .try_fold(&updated_root, |v, k| v.get(k))
+            .is_some();
+    if known { Ok(updated) } else { Err(unknown()) }
 }
 
 /// Build canonical path to config.yaml
@@ -275,6 +1534,152 @@ pub fn validate_projects_directory(path: &Path) -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Reject profiles that could never launch anything (no command or blank command).
+fn validate_editor_profiles(profiles: &[EditorProfile]) -> Result<(), String> {
+    for profile in profiles {
+        if profile.commands.is_empty() || profile.commands.iter().any(|c| c.trim().is_empty()) {
+            return Err(format!(
+                "editor profile '{}' must list at least one non-blank command",
+                profile.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_editors(editors: &[NamedEditor]) -> Result<(), String> {
+    for (i, editor) in editors.iter().enumerate() {
+        if editor.name.trim().is_empty() || editor.command.trim().is_empty() {
+            return Err("every editor of `editors` needs a name and a command".into());
+        }
+        if editors[..i].iter().any(|e| e.name == editor.name) {
+            return Err(format!("editor '{}' is listed twice", editor.name));
+        }
+    }
+    Ok(())
+}
+
+fn validate_presets(inner: &ConfigInner) -> Result<(), String> {
+    for (i, preset) in inner.presets.iter().enumerate() {
+        let name = &preset.name;
+        if name.trim().is_empty() {
+            return Err("every preset of `presets` needs a name".into());
+        }
+        if inner.presets[..i].iter().any(|p| &p.name == name) {
+            return Err(format!("preset '{name}' is listed twice"));
+        }
+        if let Some(t) = &preset.project_type
+            && ProjectType::parse(t).is_none()
+        {
+            return Err(format!("preset '{name}': project_type must be bin or lib"));
+        }
+        if let Some(e) = &preset.edition
+            && ProjectEdition::parse(e).is_none()
+        {
+            return Err(format!("preset '{name}': unknown edition '{e}'"));
+        }
+        if preset.template.is_some() && inner.template_index.is_none() {
+            return Err(format!(
+                "preset '{name}' uses a template but `template_index` is not set"
+            ));
+        }
+        validate_lints(&format!("preset '{name}'"), &preset.lints)?;
+    }
+    Ok(())
+}
+
+/// Lint levels of `lints` (tool, lint, level) must be cargo's; `what` names them in the error.
+fn validate_lints(
+    what: &str,
+    lints: &BTreeMap<String, BTreeMap<String, String>>,
+) -> Result<(), String> {
+    for (tool, lints) in lints {
+        for (lint, level) in lints {
+            if !LINT_LEVELS.contains(&level.as_str()) {
+                return Err(format!(
+                    "{what}: level of {tool}::{lint} must be one of {}",
+                    LINT_LEVELS.join(", ")
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_remote_runners(runners: &[RemoteRunner]) -> Result<(), String> {
+    for (i, runner) in runners.iter().enumerate() {
+        if runner.host.trim().is_empty() || runner.path.trim().is_empty() {
+            return Err(format!(
+                "remote runner '{}' needs a host and a path",
+                runner.name
+            ));
+        }
+        if runners[..i].iter().any(|r| r.name == runner.name) {
+            return Err(format!("remote runner '{}' is listed twice", runner.name));
+        }
+    }
+    Ok(())
+}
+
+fn validate_sandbox(sandbox: &SandboxSettings) -> Result<(), String> {
+    if sandbox.image.trim().is_empty() {
+        return Err("sandbox.image cannot be blank".into());
+    }
+    Ok(())
+}
+
+fn validate_age_colors(ages: &AgeColors) -> Result<(), String> {
+    if ages.fresh_days > ages.stale_days {
+        return Err("age_colors.fresh_days cannot be above age_colors.stale_days".into());
+    }
+    Ok(())
+}
+
+fn validate_project_defaults(inner: &ConfigInner) -> Result<(), String> {
+    if let Some(license) = &inner.default_license
+        && License::parse(license).is_none()
+    {
+        return Err(format!(
+            "default_license '{license}' must be mit, apache or dual"
+        ));
+    }
+    if let Some(edition) = inner.default_edition
+        && ProjectEdition::parse(&edition.to_string()).is_none()
+    {
+        return Err(format!("default_edition: unknown edition '{edition}'"));
+    }
+    Ok(())
+}
+
+/// Directories of `scan.roots` must be relative paths inside the projects directory.
+fn validate_scan_roots(scan: &ScanSettings) -> Result<(), String> {
+    for dir in scan.roots.keys() {
+        let path = Path::new(dir);
+        if dir.trim().is_empty()
+            || path.is_absolute()
+            || !path
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_)))
+        {
+            return Err(format!(
+                "scan.roots: '{dir}' must be a directory relative to the projects directory"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_log_levels(levels: &BTreeMap<String, String>) -> Result<(), String> {
+    for (target, level) in levels {
+        if level.parse::<LevelFilter>().is_err() {
+            return Err(format!(
+                "log level '{level}' of {target} must be off, error, warn, info, debug or trace"
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// Heuristic to detect missing-field style serde messages.
 fn looks_like_missing_field(msg: &str) -> bool {
     msg.contains("missing field")
@@ -290,6 +1695,10 @@ impl std::fmt::Display for LoadError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Corrupt(s) => write!(f, "Corrupt config YAML: {s}"),
+            Self::InvalidOverride(s) => write!(
+                f,
+                "Invalid override (--projects-dir / {PROJECTS_DIR_VAR}): {s}"
+            ),
             Self::Io(e) => write!(f, "I/O error loading config: {e}"),
         }
     }
@@ -323,6 +1732,8 @@ impl std::fmt::Display for SaveError {
             Self::Io(e) => write!(f, "I/O error saving config: {e}"),
             Self::Serialize(e) => write!(f, "Serialization error: {e}"),
             Self::Validation(e) => write!(f, "Validation error: {e}"),
+            Self::UnknownKey(key) => write!(f, "Unknown setting '{key}'"),
+            Self::InvalidValue(e) => write!(f, "Invalid value: {e}"),
         }
     }
 }
@@ -331,37 +1742,344 @@ impl std::error::Error for SaveError {}
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    fn temp_dir() -> PathBuf {
-        let mut d = std::env::temp_dir();
-        let nonce = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        d.push(format!("rustm_test_{nonce}"));
-        fs::create_dir_all(&d).unwrap();
-        d
-    }
+    use crate::temp_dir::TempDir;
 
     #[test]
     fn validate_projects_directory_ok() {
-        let d = temp_dir();
+        let d = TempDir::new("config");
         assert!(validate_projects_directory(&d).is_ok());
     }
 
     #[test]
     fn validate_projects_directory_missing() {
-        let d = temp_dir().join("nope");
-        let e = validate_projects_directory(&d).unwrap_err();
+        let d = TempDir::new("config");
+        let e = validate_projects_directory(&d.join("nope")).unwrap_err();
         matches!(e, ValidationError::ProjectsDirDoesNotExist(_));
     }
 
     #[test]
     fn create_and_persist_roundtrip() {
-        let d = temp_dir();
+        let d = TempDir::new("config");
         let cfg = Config::create_and_persist(&d, "code").unwrap();
         assert_eq!(cfg.projects_directory(), d.to_string_lossy());
         assert_eq!(cfg.editor_cmd(), "code");
     }
+
+    #[test]
+    fn editor_profiles_parse_and_validate() {
+        let inner: ConfigInner = serde_norway::from_str(
+            "projects_directory: /tmp\neditor_cmd: vim\neditor_profiles:\n  - name: embedded\n    when:\n      kind: embedded\n    commands: [code --profile Embedded]\n",
+        )
+        .unwrap();
+        assert_eq!(inner.editor_profiles.len(), 1);
+        assert_eq!(
+            inner.editor_profiles[0].when.kind,
+            Some(ProjectKind::Embedded)
+        );
+        assert!(validate_editor_profiles(&inner.editor_profiles).is_ok());
+
+        let empty = EditorProfile {
+            name: "broken".into(),
+            when: ProfileMatch::default(),
+            commands: vec![" ".into()],
+        };
+        assert!(validate_editor_profiles(&[empty]).is_err());
+    }
+
+    #[test]
+    fn remote_runners_need_a_host_and_a_path() {
+        let inner: ConfigInner = serde_norway::from_str(
+            "projects_directory: /tmp\neditor_cmd: vim\nremote_runners:\n  - name: mac\n    host: me@mac-mini\n    path: builds\n",
+        )
+        .unwrap();
+        assert!(validate_remote_runners(&inner.remote_runners).is_ok());
+
+        let mut runners = inner.remote_runners.clone();
+        runners.push(runners[0].clone());
+        assert!(validate_remote_runners(&runners).is_err());
+        runners[1].name = "windows".into();
+        runners[1].path = " ".into();
+        assert!(validate_remote_runners(&runners).is_err());
+    }
+
+    #[test]
+    fn editors_need_a_unique_name_and_a_command() {
+        let inner: ConfigInner = serde_norway::from_str(
+            "projects_directory: /tmp\neditor_cmd: vim\neditors:\n  - name: VS Code\n    command: code\n  - name: RustRover\n    command: rustrover\n",
+        )
+        .unwrap();
+        assert!(validate_editors(&inner.editors).is_ok());
+
+        let mut editors = inner.editors.clone();
+        editors[1].name = "VS Code".into();
+        assert!(validate_editors(&editors).is_err());
+        editors[1].name = "RustRover".into();
+        editors[1].command = " ".into();
+        assert!(validate_editors(&editors).is_err());
+    }
+
+    #[test]
+    fn presets_are_validated() {
+        let mut inner: ConfigInner = serde_norway::from_str(
+            "projects_directory: /tmp\neditor_cmd: vim\npresets:\n  - name: company-service\n    project_type: bin\n    edition: '2021'\n    ci: true\n    lints:\n      clippy: {pedantic: warn}\n",
+        )
+        .unwrap();
+        assert!(validate_presets(&inner).is_ok());
+
+        inner.presets[0]
+            .lints
+            .get_mut("clippy")
+            .unwrap()
+            .insert("all".into(), "loud".into());
+        assert!(validate_presets(&inner).is_err());
+        inner.presets[0].lints.clear();
+        inner.presets[0].edition = Some("2020".into());
+        assert!(validate_presets(&inner).is_err());
+        inner.presets[0].edition = None;
+        inner.presets[0].template = Some("axum-service".into());
+        assert!(validate_presets(&inner).is_err());
+        inner.template_index = Some("https://example.com/templates.yaml".into());
+        assert!(validate_presets(&inner).is_ok());
+
+        let policy = set_value(&inner, "lint_policy", "{rust: {unsafe_code: forbid}}").unwrap();
+        assert!(validate_lints("lint_policy", &policy.lint_policy).is_ok());
+        let policy = set_value(&inner, "lint_policy", "{clippy: {all: loud}}").unwrap();
+        assert!(validate_lints("lint_policy", &policy.lint_policy).is_err());
+    }
+
+    #[test]
+    fn log_levels_must_be_known() {
+        let inner: ConfigInner = serde_norway::from_str(
+            "projects_directory: /tmp\neditor_cmd: vim\nlog_levels:\n  git2: warn\n  rustm::project: TRACE\n",
+        )
+        .unwrap();
+        assert!(validate_log_levels(&inner.log_levels).is_ok());
+        assert!(matches!(
+            set_value(&inner, "log_levels", "{git2: loud}")
+                .map(|i| validate_log_levels(&i.log_levels)),
+            Ok(Err(_))
+        ));
+    }
+
+    #[test]
+    fn scan_roots_override_the_scan_settings() {
+        let inner: ConfigInner = serde_norway::from_str(
+            "projects_directory: /tmp\neditor_cmd: vim\nscan:\n  roots:\n    mirror: {max_depth: 1, git_status: false}\n    mirror/hot: {git_status: true}\n",
+        )
+        .unwrap();
+        assert!(validate_scan_roots(&inner.scan).is_ok());
+        let scan = &inner.scan;
+        let root = TempDir::new("scan_roots");
+        fs::create_dir_all(root.join("mirror")).unwrap();
+
+        let scans = scan.scan_roots(&root);
+        assert_eq!(scans.len(), 2); // mirror/hot does not exist
+        assert_eq!((scans[0].1.max_depth, scans[0].1.git_status), (3, true));
+        assert_eq!(scans[1].0, root.join("mirror"));
+        assert_eq!((scans[1].1.max_depth, scans[1].1.git_status), (1, false));
+        assert!(scans[1].1.roots.is_empty());
+        assert!(scan.excluded(&root, &root.join("mirror")));
+        assert!(!scan.excluded(&root, &root.join("mirror").join("a")));
+
+        assert!(scan.git_status_at(&root, &root.join("local")));
+        assert!(!scan.git_status_at(&root, &root.join("mirror").join("a")));
+        assert!(scan.git_status_at(&root, &root.join("mirror").join("hot").join("b")));
+
+        for bad in ["{/abs: {}}", "{../up: {}}", "{mirror: {depth: 1}}"] {
+            let rejected = set_value(&inner, "scan.roots", bad)
+                .map(|i| validate_scan_roots(&i.scan))
+                .map_or(true, |r| r.is_err());
+            assert!(rejected, "{bad}");
+        }
+    }
+
+    #[test]
+    fn set_value_by_dotted_key() {
+        let inner: ConfigInner =
+            serde_norway::from_str("projects_directory: /tmp\neditor_cmd: vim\n").unwrap();
+        let updated = set_value(&inner, "scan.max_depth", "5").unwrap();
+        assert_eq!(updated.scan.max_depth, 5);
+        let updated = set_value(&updated, "terminal_cmd", "alacritty").unwrap();
+        assert_eq!(updated.terminal_cmd.as_deref(), Some("alacritty"));
+        assert_eq!(updated.scan.max_depth, 5);
+
+        assert!(matches!(
+            set_value(&inner, "scan.depth", "5"),
+            Err(SaveError::UnknownKey(_))
+        ));
+        assert!(matches!(
+            set_value(&inner, "editor_cmd.name", "x"),
+            Err(SaveError::UnknownKey(_))
+        ));
+        assert!(matches!(
+            set_value(&inner, "scan.max_depth", "deep"),
+            Err(SaveError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn age_colors_bucket_times() {
+        let ages = AgeColors::default();
+        let day = 86_400;
+        assert_eq!(ages.age(-5), theme::Age::Fresh);
+        assert_eq!(ages.age(6 * day), theme::Age::Fresh);
+        assert_eq!(ages.age(7 * day), theme::Age::Weeks);
+        assert_eq!(ages.age(59 * day), theme::Age::Weeks);
+        assert_eq!(ages.age(400 * day), theme::Age::Months);
+
+        let inner: ConfigInner =
+            serde_norway::from_str("projects_directory: /tmp\neditor_cmd: vim\n").unwrap();
+        let updated = set_value(&inner, "age_colors.stale_days", "3").unwrap();
+        assert!(validate_age_colors(&updated.age_colors).is_err());
+        let updated = set_value(&updated, "age_colors.fresh_days", "0").unwrap();
+        assert!(validate_age_colors(&updated.age_colors).is_ok());
+        assert_eq!(updated.age_colors.age(0), theme::Age::Weeks);
+    }
+
+    #[test]
+    fn project_defaults_are_validated() {
+        let inner: ConfigInner = serde_norway::from_str(
+            "projects_directory: /tmp\neditor_cmd: vim\nauthor_name: ' Jo Doe '\ndefault_license: dual\ndefault_edition: 2021\n",
+        )
+        .unwrap();
+        assert!(validate_project_defaults(&inner).is_ok());
+        let config = Config {
+            inner: Arc::new(inner),
+            overrides: Arc::new(Overrides::default()),
+        };
+        assert_eq!(config.author_name(), Some("Jo Doe"));
+        assert_eq!(config.author_email(), None);
+        assert_eq!(config.default_license(), Some(License::Dual));
+        assert_eq!(config.default_edition(), ProjectEdition::E2021);
+
+        for (key, bad) in [("default_license", "gpl"), ("default_edition", "2020")] {
+            let updated = set_value(&config.inner, key, bad).unwrap();
+            assert!(validate_project_defaults(&updated).is_err(), "{key}: {bad}");
+        }
+    }
+
+    #[test]
+    fn settings_list_every_key() {
+        let mut inner: ConfigInner =
+            serde_norway::from_str("projects_directory: /tmp\neditor_cmd: vim\n").unwrap();
+        for setting in SETTINGS.iter().filter(|s| s.optional) {
+            inner = set_value(&inner, setting.key, "[]")
+                .or_else(|_| set_value(&inner, setting.key, "{}"))
+                .or_else(|_| set_value(&inner, setting.key, "x"))
+                .or_else(|_| set_value(&inner, setting.key, "1"))
+                .unwrap();
+        }
+        let root = serde_norway::to_value(&inner).unwrap();
+        let serde_norway::Value::Mapping(top) = root else {
+            panic!("not a mapping");
+        };
+        for (key, value) in top {
+            let key = key.as_str().unwrap().to_string();
+            let keys: Vec<String> = match value {
+                serde_norway::Value::Mapping(nested)
+                    if key != "editor_profiles" && key != "log_levels" =>
+                {
+                    nested
+                        .keys()
+                        .map(|k| format!("{key}.{}", k.as_str().unwrap()))
+                        .collect()
+                }
+                _ => vec![key],
+            };
+            for key in keys {
+                assert!(SETTINGS.iter().any(|s| s.key == key), "{key} not listed");
+            }
+        }
+
+        let unset = set_value(&inner, "terminal_cmd", "").unwrap();
+        assert!(unset.terminal_cmd.is_none());
+    }
+
+    #[test]
+    fn overrides_take_precedence_over_the_file() {
+        let dir = TempDir::new("config");
+        let other = TempDir::new("config");
+        let file = dir.join("config.yaml");
+        let overrides = |projects: Option<&Path>, editor: Option<&str>| Overrides {
+            projects_directory: projects.map(|p| p.to_string_lossy().into_owned()),
+            editor_cmd: editor.map(ToString::to_string),
+        };
+
+        // No file: both required settings must be overridden.
+        assert!(matches!(
+            Config::load_from(&file, overrides(Some(&other), None)),
+            Ok(LoadStatus::NeedsInitialSetup(SetupReason::MissingFile))
+        ));
+        let Ok(LoadStatus::Ready(config)) =
+            Config::load_from(&file, overrides(Some(&other), Some("hx")))
+        else {
+            panic!("overrides alone should be enough");
+        };
+        assert_eq!(config.editor_cmd(), "hx");
+        assert_eq!(config.scan().max_depth, 3);
+
+        fs::write(
+            &file,
+            format!("projects_directory: {}\neditor_cmd: vim\n", dir.display()),
+        )
+        .unwrap();
+        let Ok(LoadStatus::Ready(config)) = Config::load_from(&file, overrides(None, Some("code")))
+        else {
+            panic!("valid configuration refused");
+        };
+        assert_eq!(config.projects_directory(), dir.to_string_lossy());
+        assert_eq!(config.editor_cmd(), "code");
+        assert!(config.is_overridden("editor_cmd"));
+        // The file value is what gets saved.
+        assert_eq!(
+            config.get("editor_cmd"),
+            Some(serde_norway::Value::String("vim".into()))
+        );
+
+        assert!(matches!(
+            Config::load_from(&file, overrides(Some(&dir.join("missing")), None)),
+            Err(LoadError::InvalidOverride(_))
+        ));
+    }
+
+    #[test]
+    fn project_settings_merge_over_the_config() {
+        let project = TempDir::new("config");
+        let config = |editor: Option<&str>| Config {
+            inner: Arc::new(ConfigInner::new("/tmp".into(), "vim".into())),
+            overrides: Arc::new(Overrides {
+                projects_directory: None,
+                editor_cmd: editor.map(ToString::to_string),
+            }),
+        };
+
+        let effective = config(None).for_project(&project);
+        assert_eq!(effective.editor_cmd(), "vim");
+        assert!(effective.build_profile().is_none());
+
+        fs::write(
+            project.join(PROJECT_FILE),
+            "editor_cmd: hx\nbuild_profile: release-lto\nactions:\n  - name: Serve\n    command: trunk serve\n  - name: ' '\n    command: ls\n",
+        )
+        .unwrap();
+        let effective = config(None).for_project(&project);
+        assert_eq!(effective.editor_cmd(), "hx");
+        assert_eq!(effective.build_profile(), Some("release-lto"));
+        assert_eq!(
+            effective.actions(),
+            [CustomAction {
+                name: "Serve".into(),
+                command: "trunk serve".into()
+            }]
+        );
+        // A run override still wins.
+        assert_eq!(
+            config(Some("code")).for_project(&project).editor_cmd(),
+            "code"
+        );
+
+        fs::write(project.join(PROJECT_FILE), "editor: hx\n").unwrap();
+        assert!(ProjectSettings::load(&project).is_err());
+        assert_eq!(config(None).for_project(&project).editor_cmd(), "vim");
+    }
 }
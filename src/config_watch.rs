@@ -0,0 +1,154 @@
+//! Hot reload of `config.yaml`.
+//!
+//! A `notify` watcher on the configuration directory picks up external edits
+//! of `config.yaml` (editors, `rustm config set`, another rustm instance).
+//! Events come in bursts (atomic saves are a write then a rename), so the file
+//! is loaded once they settle. A valid configuration different from the one
+//! the TUI runs with is handed to the UI thread through the cursive callback
+//! sink; an invalid one is reported and the current configuration kept.
+
+use std::ffi::OsStr;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError, mpsc};
+use std::thread;
+use std::time::Duration;
+
+use cursive::views::Dialog;
+use cursive::{CbSink, Cursive};
+use log::{info, warn};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::config::{Config, LoadStatus};
+
+/// Quiet period after the last event before loading the file.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Configuration the TUI currently runs with, to skip reloads changing nothing (e.g. our own saves).
+static APPLIED: Mutex<Option<serde_norway::Value>> = Mutex::new(None);
+
+/// Called on the UI thread with the reloaded configuration.
+pub type OnReload = fn(&mut Cursive, Config);
+
+/// Record that the TUI now runs with `config`.
+pub fn mark_applied(config: &Config) {
+    *APPLIED.lock().unwrap_or_else(PoisonError::into_inner) = config.get("");
+}
+
+/// Watch `config.yaml` for the rest of the process, starting from `config`.
+pub fn spawn(cb_sink: CbSink, config: &Config, on_reload: OnReload) {
+    mark_applied(config);
+    let file = Config::file_path();
+    let Some(dir) = file.parent().map(Path::to_path_buf) else {
+        return;
+    };
+    thread::spawn(move || {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(tx) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                warn!("Configuration hot reload disabled: {e}");
+                return;
+            }
+        };
+        if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            warn!("Cannot watch {}: {e}", dir.display());
+            return;
+        }
+        info!("Watching {} for changes", file.display());
+        while let Ok(event) = rx.recv() {
+            if !event.is_ok_and(|e| touches(&e, file.file_name())) {
+                continue;
+            }
+            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+            let Some(callback) = reload() else {
+                continue;
+            };
+            let sent = cb_sink.send(Box::new(move |s: &mut Cursive| match callback {
+                Ok(config) => on_reload(s, config),
+                Err(reason) => s.add_layer(Dialog::info(format!(
+                    "config.yaml changed but cannot be used:\n{reason}\n\nThe current configuration is kept."
+                ))),
+            }));
+            if sent.is_err() {
+                // The TUI is gone.
+                break;
+            }
+        }
+    });
+}
+
+/// The new configuration, an explanation when it is invalid, or `None` when nothing changed.
+fn reload() -> Option<Result<Config, String>> {
+    match Config::load() {
+        Ok(LoadStatus::Ready(config)) => {
+            let value = config.get("");
+            let mut applied = APPLIED.lock().unwrap_or_else(PoisonError::into_inner);
+            if *applied == value {
+                return None;
+            }
+            *applied = value;
+            info!("Configuration changed on disk, reloading");
+            Some(Ok(config))
+        }
+        Ok(LoadStatus::NeedsInitialSetup(_)) => {
+            warn!("Ignoring incomplete configuration change");
+            Some(Err(
+                "a required setting is missing or blank, or the projects directory is invalid"
+                    .into(),
+            ))
+        }
+        Err(e) => {
+            warn!("Ignoring invalid configuration change: {e}");
+            Some(Err(e.to_string()))
+        }
+    }
+}
+
+/// `true` when `event` may have changed the file named `name` (temporary files excluded).
+fn touches(event: &Event, name: Option<&OsStr>) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event.paths.iter().any(|p| p.file_name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind, RenameMode};
+    use std::path::PathBuf;
+
+    #[test]
+    fn only_changes_of_the_file_count() {
+        let name = Some(OsStr::new("config.yaml"));
+        let event = |kind, path: &str| Event::new(kind).add_path(PathBuf::from(path));
+        assert!(touches(
+            &event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::To)),
+                "/cfg/rustm/config.yaml"
+            ),
+            name
+        ));
+        assert!(touches(
+            &event(
+                EventKind::Create(CreateKind::File),
+                "/cfg/rustm/config.yaml"
+            ),
+            name
+        ));
+        assert!(!touches(
+            &event(
+                EventKind::Create(CreateKind::File),
+                "/cfg/rustm/config.yaml.tmp"
+            ),
+            name
+        ));
+        assert!(!touches(
+            &event(
+                EventKind::Access(AccessKind::Read),
+                "/cfg/rustm/config.yaml"
+            ),
+            name
+        ));
+    }
+}
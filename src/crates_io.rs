@@ -0,0 +1,64 @@
+//! crates.io registry API client.
+//!
+//! Read-only lookups (search, latest version) used by dependency related features. Requests are
+//! anonymous; the crates.io crawler policy only requires an identifying user
+//! agent, which `http::agent` sends.
+
+use semver::Version;
+use serde::Deserialize;
+
+use crate::http::{self, HttpError};
+
+const API_ROOT: &str = "https://crates.io/api/v1";
+
+#[derive(Deserialize)]
+struct CrateResponse {
+    #[serde(rename = "crate")]
+    krate: CrateInfo,
+}
+
+#[derive(Deserialize)]
+struct CrateInfo {
+    max_stable_version: Option<String>,
+    max_version: String,
+}
+
+/// One crates.io search result.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchHit {
+    pub name: String,
+    pub max_version: String,
+    pub max_stable_version: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    crates: Vec<SearchHit>,
+}
+
+/// Search crates.io for `query` (best matches first, at most `limit` results).
+pub fn search(query: &str, limit: usize) -> Result<Vec<SearchHit>, HttpError> {
+    let url = format!("{API_ROOT}/crates");
+    let response = http::agent()
+        .get(&url)
+        .query("q", query)
+        .query("per_page", limit.to_string())
+        .call()?
+        .body_mut()
+        .read_json::<SearchResponse>()?;
+    Ok(response.crates)
+}
+
+/// Newest stable version of `name` (newest pre-release when only those exist).
+pub fn latest_version(name: &str) -> Result<Version, HttpError> {
+    let url = format!("{API_ROOT}/crates/{name}");
+    let info = http::agent()
+        .get(&url)
+        .call()?
+        .body_mut()
+        .read_json::<CrateResponse>()?
+        .krate;
+    let raw = info.max_stable_version.unwrap_or(info.max_version);
+    Version::parse(&raw).map_err(|e| HttpError::Decode(e.to_string()))
+}
@@ -0,0 +1,155 @@
+//! Desktop integration: clipboard, default browser and terminal emulator.
+//!
+//! Both go through the usual platform tools rather than a library, trying
+//! them in order until one can be spawned.
+
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use log::{info, warn};
+
+/// Clipboard writers, in order of preference: `(program, args)`.
+const CLIPBOARD_TOOLS: [(&str, &[&str]); 5] = [
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("pbcopy", &[]),
+    ("clip.exe", &[]),
+];
+
+/// URL openers, in order of preference; the URL is appended.
+const OPENERS: [(&str, &[&str]); 3] = [
+    ("xdg-open", &[]),
+    ("open", &[]),
+    ("cmd.exe", &["/C", "start", ""]),
+];
+
+/// Terminal emulators tried when none is configured (after `$TERMINAL`), started in the directory.
+const TERMINALS: [&str; 8] = [
+    "x-terminal-emulator",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "alacritty",
+    "kitty",
+    "wezterm",
+    "xterm",
+];
+
+#[derive(Debug)]
+pub enum DesktopError {
+    /// None of the tools could be run.
+    NoTool(Vec<&'static str>),
+    Failed(&'static str),
+}
+
+impl fmt::Display for DesktopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoTool(tools) => write!(f, "None of {} is available", tools.join(", ")),
+            Self::Failed(tool) => write!(f, "{tool} failed"),
+        }
+    }
+}
+
+impl std::error::Error for DesktopError {}
+
+/// Copy `text` to the system clipboard; returns the tool used.
+///
+/// A tool that starts but fails (`wl-copy` outside Wayland) does not stop the
+/// others from being tried.
+pub fn copy_to_clipboard(text: &str) -> Result<&'static str, DesktopError> {
+    let mut failed = None;
+    for (program, args) in CLIPBOARD_TOOLS {
+        let Ok(mut child) = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        let ok = child.wait().is_ok_and(|status| status.success());
+        if written && ok {
+            info!(
+                "Copied {} bytes to the clipboard with {program}",
+                text.len()
+            );
+            return Ok(program);
+        }
+        warn!("{program} failed to copy to the clipboard");
+        failed.get_or_insert(program);
+    }
+    Err(match failed {
+        Some(program) => DesktopError::Failed(program),
+        None => DesktopError::NoTool(CLIPBOARD_TOOLS.iter().map(|(p, _)| *p).collect()),
+    })
+}
+
+/// Open a terminal in `dir` with `configured` (whitespace-split), else the first available one.
+pub fn open_terminal(dir: &Path, configured: Option<&str>) -> Result<&'static str, DesktopError> {
+    if let Some(cmd) = configured {
+        let mut parts = cmd.split_whitespace();
+        let program = parts.next().unwrap_or_default();
+        return Command::new(program)
+            .args(parts)
+            .current_dir(dir)
+            .spawn()
+            .map(|_| "terminal_cmd")
+            .map_err(|_| DesktopError::Failed("terminal_cmd"));
+    }
+    if cfg!(target_os = "macos") {
+        return Command::new("open")
+            .args(["-a", "Terminal"])
+            .arg(dir)
+            .spawn()
+            .map(|_| "Terminal.app")
+            .map_err(|_| DesktopError::Failed("open -a Terminal"));
+    }
+    if let Ok(terminal) = std::env::var("TERMINAL")
+        && Command::new(&terminal).current_dir(dir).spawn().is_ok()
+    {
+        info!("Opened {terminal} in {}", dir.display());
+        return Ok("$TERMINAL");
+    }
+    for program in TERMINALS {
+        if Command::new(program)
+            .current_dir(dir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .is_ok()
+        {
+            info!("Opened {program} in {}", dir.display());
+            return Ok(program);
+        }
+    }
+    Err(DesktopError::NoTool(TERMINALS.to_vec()))
+}
+
+/// Open `url` in the default browser (does not wait for the browser).
+pub fn open_url(url: &str) -> Result<(), DesktopError> {
+    for (program, args) in OPENERS {
+        if Command::new(program)
+            .args(args)
+            .arg(url)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .is_ok()
+        {
+            info!("Opened {url} with {program}");
+            return Ok(());
+        }
+    }
+    Err(DesktopError::NoTool(
+        OPENERS.iter().map(|(p, _)| *p).collect(),
+    ))
+}
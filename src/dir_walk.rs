@@ -0,0 +1,140 @@
+//! Parallel directory walk.
+//!
+//! Computes the size of directory trees with `available_parallelism` scoped
+//! threads sharing one queue of directories, so a single huge tree (a
+//! `target/` directory) is spread over every worker instead of holding one up.
+//! Sizes are apparent sizes (the file lengths); symbolic links are not
+//! followed and count for nothing.
+
+use std::collections::HashMap;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+use log::debug;
+
+/// Upper bound on the walker threads: the disk, not the CPU, is the limit.
+const MAX_WORKERS: usize = 8;
+
+/// Directories waiting to be read, by root, and the number being read.
+struct Queue {
+    pending: Vec<(usize, PathBuf)>,
+    active: usize,
+}
+
+/// Total size in bytes of every tree of `roots`, in order.
+///
+/// A root inside another one is walked once: its size is added to the
+/// enclosing root. Unreadable entries are skipped.
+pub fn sizes(roots: &[PathBuf]) -> Vec<u64> {
+    let index: HashMap<&Path, usize> = roots
+        .iter()
+        .enumerate()
+        .map(|(i, root)| (root.as_path(), i))
+        .collect();
+    let own: Vec<AtomicU64> = roots.iter().map(|_| AtomicU64::new(0)).collect();
+    let parents: Mutex<Vec<Option<usize>>> = Mutex::new(vec![None; roots.len()]);
+    let queue = Mutex::new(Queue {
+        pending: roots
+            .iter()
+            .enumerate()
+            .filter(|(i, root)| index.get(root.as_path()) == Some(i))
+            .map(|(i, root)| (i, root.clone()))
+            .collect(),
+        active: 0,
+    });
+    let ready = Condvar::new();
+
+    let workers = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(MAX_WORKERS);
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while let Some((root, dir)) = next(&queue, &ready) {
+                    let mut found = Vec::new();
+                    let mut bytes = 0;
+                    for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+                        let Ok(file_type) = entry.file_type() else {
+                            continue;
+                        };
+                        let path = entry.path();
+                        if file_type.is_dir() {
+                            match index.get(path.as_path()) {
+                                Some(&nested) => lock(&parents)[nested] = Some(root),
+                                None => found.push((root, path)),
+                            }
+                        } else if file_type.is_file() {
+                            bytes += entry.metadata().map_or(0, |m| m.len());
+                        }
+                    }
+                    own[root].fetch_add(bytes, Ordering::Relaxed);
+                    let mut queue = lock(&queue);
+                    queue.pending.extend(found);
+                    queue.active -= 1;
+                    ready.notify_all();
+                }
+            });
+        }
+    });
+
+    let mut totals: Vec<u64> = own.into_iter().map(AtomicU64::into_inner).collect();
+    let parents = parents.into_inner().unwrap_or_else(|e| e.into_inner());
+    // Deepest roots first, so that nested roots add up along the chain.
+    let mut order: Vec<usize> = (0..roots.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(roots[i].components().count()));
+    for i in order {
+        if let Some(parent) = parents[i] {
+            totals[parent] += totals[i];
+        }
+    }
+    debug!("Walked {} roots with {workers} workers", roots.len());
+    totals
+}
+
+/// The next directory to read, waiting while other workers may still queue some.
+fn next(queue: &Mutex<Queue>, ready: &Condvar) -> Option<(usize, PathBuf)> {
+    let mut queue = lock(queue);
+    loop {
+        if let Some(item) = queue.pending.pop() {
+            queue.active += 1;
+            return Some(item);
+        }
+        if queue.active == 0 {
+            return None;
+        }
+        queue = ready.wait(queue).unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn sums_trees_and_nested_roots() {
+        let root = TempDir::new("dir_walk");
+        let target = root.join("target");
+        fs::create_dir_all(target.join("debug/deps")).unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("Cargo.toml"), [0u8; 10]).unwrap();
+        fs::write(root.join("src/main.rs"), [0u8; 20]).unwrap();
+        fs::write(target.join("debug/deps/demo"), [0u8; 300]).unwrap();
+        fs::write(target.join("CACHEDIR.TAG"), [0u8; 5]).unwrap();
+
+        let missing = root.join("missing");
+        assert_eq!(
+            sizes(&[root.to_path_buf(), target.clone(), missing]),
+            [335, 305, 0]
+        );
+        assert_eq!(sizes(&[target]), [305]);
+    }
+}
@@ -0,0 +1,490 @@
+//! Editor launching.
+//!
+//! Decides which command(s) open a given project and runs them:
+//! - `resolve_commands` uses the editor of `editors` last chosen for the project
+//!   (remembered in the metadata store), else the `editor_cmd` of the project's
+//!   `.rustm.yaml` when set, else evaluates the configured `editor_profiles`
+//!   (first match wins) against the project's kind and dependencies, falling
+//!   back to `editor_cmd`.
+//! - `launch` spawns the resolved command(s) without waiting (safe from the TUI).
+//!   Terminal editors (`mode`: `editor_modes`, else a list of known ones) are
+//!   not spawned but handed back, for the TUI to suspend itself while they run
+//!   (see `ui::suspend`); GUI editors are detached from the terminal.
+//! - `open_in_editor` runs a single command outside of the TUI, waiting for
+//!   terminal editors to exit.
+//! - `launch_at` opens a single file at a line, using the syntax of the
+//!   editor (`-g file:line`, `+line file`, `file:line`); unknown editors only
+//!   get the file.
+//!
+//! Commands are split into words like a shell does (quotes and backslash
+//! escapes, no expansions). `{path}` anywhere in a command is replaced by the
+//! project path (`code --folder-uri {path}`); without it, the path is appended
+//! as the last argument.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use log::{info, warn};
+
+use crate::config::{Config, EditorMode, EditorProfile, NamedEditor, PROJECT_FILE, ProfileMatch};
+use crate::metadata;
+use crate::project::kind::{self, ProjectKind};
+use crate::project::manifest::Manifest;
+use crate::wsl::{self, WslPath};
+
+/// Editors running in the terminal unless `editor_modes` says otherwise.
+const TERMINAL_EDITORS: [&str; 11] = [
+    "vi", "vim", "nvim", "nano", "micro", "hx", "helix", "kak", "joe", "ne", "mg",
+];
+
+/// Placeholder of editor commands replaced by the project path.
+const PATH_PLACEHOLDER: &str = "{path}";
+
+/// Error category for editor opening failures.
+#[derive(Debug)]
+pub enum OpenEditorError {
+    EditorCommandEmpty,
+    /// A quote of the editor command is never closed.
+    UnterminatedQuote,
+    Spawn(std::io::Error),
+    Failed(i32),
+}
+
+impl fmt::Display for OpenEditorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EditorCommandEmpty => write!(f, "Editor command is empty"),
+            Self::UnterminatedQuote => write!(f, "Editor command has an unterminated quote"),
+            Self::Spawn(e) => write!(f, "Failed to spawn editor command: {e}"),
+            Self::Failed(code) => write!(f, "Editor command exited with status {code}"),
+        }
+    }
+}
+
+impl std::error::Error for OpenEditorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Spawn(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Determine the command(s) used to open `project_path`.
+///
+/// Returns the command of the editor of `editors` chosen for the project (see
+/// `chosen_editor`), else the default commands (`default_commands`).
+pub fn resolve_commands(config: &Config, project_path: &Path) -> Vec<String> {
+    match chosen_editor(config, project_path) {
+        Some(editor) => {
+            info!(
+                "Editor '{}' chosen for {}",
+                editor.name,
+                project_path.display()
+            );
+            vec![editor.command.clone()]
+        }
+        None => default_commands(config, project_path),
+    }
+}
+
+/// Editor of `editors` last chosen for `project_path`, unless `editor_cmd` is overridden for this run.
+pub fn chosen_editor<'a>(config: &'a Config, project_path: &Path) -> Option<&'a NamedEditor> {
+    if config.is_overridden("editor_cmd") {
+        return None;
+    }
+    let name = metadata::snapshot().get(project_path)?.editor.clone()?;
+    config.editors().iter().find(|e| e.name == name)
+}
+
+/// Default command(s) opening `project_path`, when no editor of `editors` was chosen.
+///
+/// Returns the editor command of the project's `.rustm.yaml`, else the
+/// commands of the first matching editor profile, or `editor_cmd` when no
+/// profile matches (or the manifest cannot be read).
+pub fn default_commands(config: &Config, project_path: &Path) -> Vec<String> {
+    let effective = config.for_project(project_path);
+    if let Some(editor_cmd) = effective.project_editor_cmd() {
+        info!(
+            "Editor command of {} used for {}",
+            PROJECT_FILE,
+            project_path.display()
+        );
+        return vec![editor_cmd.to_string()];
+    }
+    let profiles = config.editor_profiles();
+    if !profiles.is_empty() {
+        match Manifest::read(project_path) {
+            Ok(manifest) => {
+                let kind = kind::detect(project_path, &manifest);
+                if let Some(profile) = first_matching_profile(profiles, kind, &manifest) {
+                    info!(
+                        "Editor profile '{}' selected for {}",
+                        profile.name,
+                        project_path.display()
+                    );
+                    return profile.commands.clone();
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Cannot evaluate editor profiles for {}: {e}",
+                    project_path.display()
+                );
+            }
+        }
+    }
+    vec![effective.editor_cmd().to_string()]
+}
+
+/// Open `project_path` with the resolved editor command(s), without waiting for them.
+///
+/// Detached editors are started; the commands of terminal editors are returned,
+/// for the caller to run in the foreground (see `run_foreground`).
+pub fn launch(config: &Config, project_path: &Path) -> Result<Vec<Command>, OpenEditorError> {
+    let mut foreground = Vec::new();
+    for editor_cmd in resolve_commands(config, project_path) {
+        let cmd = build_command(&editor_cmd, project_path)?;
+        info!(
+            "Launching editor for '{}': {editor_cmd}",
+            project_path.display()
+        );
+        foreground.extend(start(config, cmd)?);
+    }
+    Ok(foreground)
+}
+
+/// Open `file` at `line` with the first resolved command of `project_path`, without waiting.
+///
+/// Returns the command of a terminal editor instead of starting it, as `launch`.
+pub fn launch_at(
+    config: &Config,
+    project_path: &Path,
+    file: &Path,
+    line: u64,
+) -> Result<Option<Command>, OpenEditorError> {
+    let editor_cmd = resolve_commands(config, project_path)
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let (mut cmd, wsl) = editor_command(&editor_cmd, project_path, false)?;
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    let target = match wsl {
+        Some(wsl) => {
+            PathBuf::from(wsl::locate(&project_path.join(file)).map_or(wsl.path, |f| f.path))
+        }
+        None => file.to_path_buf(),
+    };
+    cmd.current_dir(project_path)
+        .args(location_args(&program, &target, line));
+    info!(
+        "Launching editor at {}:{line}: {editor_cmd}",
+        file.display()
+    );
+    start(config, cmd)
+}
+
+/// How the editor `program` runs: `editor_modes`, else terminal for the known terminal editors.
+pub fn mode(config: &Config, program: &str) -> EditorMode {
+    let name = program_name(program);
+    config
+        .editor_mode(name)
+        .unwrap_or(if TERMINAL_EDITORS.contains(&name) {
+            EditorMode::Terminal
+        } else {
+            EditorMode::Detach
+        })
+}
+
+/// Spawn `cmd` when its editor is detached; hand it back when it needs the terminal.
+fn start(config: &Config, mut cmd: Command) -> Result<Option<Command>, OpenEditorError> {
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    match mode(config, &program) {
+        EditorMode::Terminal => Ok(Some(cmd)),
+        EditorMode::Detach => {
+            // The editor's output would be drawn over the TUI.
+            cmd.stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(OpenEditorError::Spawn)?;
+            Ok(None)
+        }
+    }
+}
+
+/// Run `cmd` attached to the terminal and wait for it.
+pub fn run_foreground(mut cmd: Command) -> Result<(), OpenEditorError> {
+    let status = cmd.status().map_err(OpenEditorError::Spawn)?;
+    if !status.success() {
+        return Err(OpenEditorError::Failed(status.code().unwrap_or(-1)));
+    }
+    Ok(())
+}
+
+/// File stem of `program` (`/usr/bin/nvim` is `nvim`).
+fn program_name(program: &str) -> &str {
+    Path::new(program)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(program)
+}
+
+/// Arguments opening `file` at `line` for the editor `program`.
+fn location_args(program: &str, file: &Path, line: u64) -> Vec<String> {
+    let file = file.display().to_string();
+    match program_name(program) {
+        "code" | "code-insiders" | "codium" | "cursor" => {
+            vec!["-g".into(), format!("{file}:{line}")]
+        }
+        "vi" | "vim" | "nvim" | "gvim" | "emacs" | "emacsclient" | "nano" | "micro" | "kak" => {
+            vec![format!("+{line}"), file]
+        }
+        "hx" | "helix" | "zed" | "subl" => vec![format!("{file}:{line}")],
+        _ => vec![file],
+    }
+}
+
+/// Open the project in the provided editor command (string), outside of the TUI.
+///
+/// Strategy:
+/// - Split editor command like a shell (quotes and escapes, see `split_command`).
+/// - First word is program, remainder are args.
+/// - Replace `{path}` with the project directory path, or append it.
+/// - Detached editors are spawned; terminal editors run in the foreground
+///   and are waited for (blocking).
+pub fn open_in_editor(
+    config: &Config,
+    editor_cmd: &str,
+    project_path: &Path,
+) -> Result<(), OpenEditorError> {
+    let cmd = build_command(editor_cmd, project_path)?;
+
+    info!(
+        "Opening project '{}' with editor command: {}",
+        project_path.display(),
+        editor_cmd
+    );
+
+    match start(config, cmd)? {
+        Some(cmd) => run_foreground(cmd),
+        None => Ok(()),
+    }
+}
+
+fn build_command(editor_cmd: &str, project_path: &Path) -> Result<Command, OpenEditorError> {
+    editor_command(editor_cmd, project_path, true).map(|(cmd, _)| cmd)
+}
+
+/// `editor_cmd` for `project_path`, and where the project is in WSL for WSL remotes.
+///
+/// `{path}` is replaced by the project path (the Linux one for WSL remotes);
+/// without it, the path is appended when `append_path`.
+fn editor_command(
+    editor_cmd: &str,
+    project_path: &Path,
+    append_path: bool,
+) -> Result<(Command, Option<WslPath>), OpenEditorError> {
+    let mut words = split_command(editor_cmd)?.into_iter();
+    let program = words.next().ok_or(OpenEditorError::EditorCommandEmpty)?;
+    let words: Vec<String> = words.collect();
+    let remote = remote(&program, project_path);
+    let path = remote.as_ref().map_or_else(
+        || project_path.as_os_str().to_owned(),
+        |(_, wsl)| wsl.path.clone().into(),
+    );
+    let mut cmd = Command::new(&program);
+    cmd.args(
+        words
+            .iter()
+            .map(|w| w.replace(PATH_PLACEHOLDER, &path.to_string_lossy())),
+    );
+    if let Some((args, _)) = &remote {
+        cmd.args(args);
+    }
+    if append_path && !words.iter().any(|w| w.contains(PATH_PLACEHOLDER)) {
+        cmd.arg(path);
+    }
+    Ok((cmd, remote.map(|(_, wsl)| wsl)))
+}
+
+/// Words of `command`, split at whitespace like a shell does.
+///
+/// Single quotes keep everything literally; double quotes keep everything but
+/// `\"` and `\\`. Outside quotes, a backslash only escapes whitespace, a quote
+/// or a backslash, so that Windows paths (`C:\Tools\edit.exe`) need no escaping.
+fn split_command(command: &str) -> Result<Vec<String>, OpenEditorError> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(OpenEditorError::UnterminatedQuote),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next_if(|c| matches!(c, '"' | '\\')) {
+                            Some(escaped) => word.push(escaped),
+                            None => word.push('\\'),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(OpenEditorError::UnterminatedQuote),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                match chars.next_if(|c| c.is_whitespace() || matches!(c, '\'' | '"' | '\\')) {
+                    Some(escaped) => word.push(escaped),
+                    None => word.push('\\'),
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// WSL remote arguments of `program` for `project_path`, and where the project is in WSL.
+fn remote(program: &str, project_path: &Path) -> Option<(Vec<String>, WslPath)> {
+    let wsl = wsl::locate(project_path)?;
+    wsl::remote_args(program, &wsl).map(|args| (args, wsl))
+}
+
+fn first_matching_profile<'a>(
+    profiles: &'a [EditorProfile],
+    kind: ProjectKind,
+    manifest: &Manifest,
+) -> Option<&'a EditorProfile> {
+    profiles
+        .iter()
+        .find(|p| profile_matches(&p.when, kind, manifest))
+}
+
+fn profile_matches(when: &ProfileMatch, kind: ProjectKind, manifest: &Manifest) -> bool {
+    when.kind.is_none_or(|k| k == kind)
+        && when
+            .dependency
+            .as_deref()
+            .is_none_or(|d| manifest.has_dependency(d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, when: ProfileMatch) -> EditorProfile {
+        EditorProfile {
+            name: name.into(),
+            when,
+            commands: vec![format!("{name}-editor")],
+        }
+    }
+
+    #[test]
+    fn first_matching_profile_wins() {
+        let manifest =
+            Manifest::parse("[package]\nname = 'g'\n[dependencies]\nbevy = '0.14'\n").unwrap();
+        let profiles = vec![
+            profile(
+                "embedded",
+                ProfileMatch {
+                    kind: Some(ProjectKind::Embedded),
+                    dependency: None,
+                },
+            ),
+            profile(
+                "bevy",
+                ProfileMatch {
+                    kind: None,
+                    dependency: Some("bevy".into()),
+                },
+            ),
+            profile("catch-all", ProfileMatch::default()),
+        ];
+
+        let chosen = first_matching_profile(&profiles, ProjectKind::Binary, &manifest).unwrap();
+        assert_eq!(chosen.name, "bevy");
+
+        let chosen = first_matching_profile(&profiles, ProjectKind::Embedded, &manifest).unwrap();
+        assert_eq!(chosen.name, "embedded");
+    }
+
+    #[test]
+    fn location_args_follow_the_editor_syntax() {
+        let file = Path::new("src/main.rs");
+        assert_eq!(
+            location_args("/usr/bin/code", file, 7),
+            vec!["-g", "src/main.rs:7"]
+        );
+        assert_eq!(location_args("nvim", file, 7), vec!["+7", "src/main.rs"]);
+        assert_eq!(location_args("unknown-ide", file, 7), vec!["src/main.rs"]);
+    }
+
+    #[test]
+    fn commands_split_like_a_shell() {
+        assert_eq!(
+            split_command(r#"code  --user-data-dir '/tmp/my dir' "a \"b\"" C:\Tools\x my\ file"#)
+                .unwrap(),
+            [
+                "code",
+                "--user-data-dir",
+                "/tmp/my dir",
+                r#"a "b""#,
+                r"C:\Tools\x",
+                "my file"
+            ]
+        );
+        assert!(matches!(
+            split_command("vim 'open"),
+            Err(OpenEditorError::UnterminatedQuote)
+        ));
+    }
+
+    #[test]
+    fn path_placeholder_replaces_the_appended_path() {
+        let args = |cmd: &Command| -> Vec<String> {
+            cmd.get_args()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect()
+        };
+        let path = Path::new("/p/demo");
+        let cmd = build_command("code --folder-uri file://{path}", path).unwrap();
+        assert_eq!(args(&cmd), ["--folder-uri", "file:///p/demo"]);
+        let cmd = build_command("code -n", path).unwrap();
+        assert_eq!(args(&cmd), ["-n", "/p/demo"]);
+    }
+
+    #[test]
+    fn blank_command_is_rejected() {
+        assert!(matches!(
+            build_command("   ", Path::new("/tmp")),
+            Err(OpenEditorError::EditorCommandEmpty)
+        ));
+    }
+}
@@ -0,0 +1,207 @@
+//! Machine-readable event stream.
+//!
+//! rustm (the TUI and the subcommands alike) appends what happens to
+//! `events.jsonl` in the configuration directory, one JSON object per line:
+//! `{"time": <seconds since epoch>, "event": "project_created", ...}`.
+//! `rustm --events jsonl` follows that file and prints the new events on
+//! stdout as they come, for status bars and notification daemons.
+//!
+//! The file is emptied once it exceeds `MAX_SIZE`; followers notice and read it
+//! from the start again. Writing an event never fails the operation it reports.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use log::warn;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::metadata;
+
+const FILE_NAME: &str = "events.jsonl";
+
+/// Size past which the file is emptied before appending.
+const MAX_SIZE: u64 = 1024 * 1024;
+
+/// How often a follower checks for new events.
+const POLL: Duration = Duration::from_millis(250);
+
+/// Something rustm did, or noticed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    ProjectCreated {
+        name: String,
+        path: PathBuf,
+    },
+    /// A cargo command run from the project menu ended (`success`: exit status 0).
+    BuildFinished {
+        name: String,
+        path: PathBuf,
+        command: String,
+        success: bool,
+        duration_ms: u64,
+    },
+    /// A scan found a new git status, branch or upstream tracking.
+    StatusChanged {
+        name: String,
+        path: PathBuf,
+        dirty: bool,
+        branch: Option<String>,
+        ahead_behind: Option<(usize, usize)>,
+    },
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    time: u64,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+/// `<config dir>/events.jsonl`.
+pub fn file_path() -> PathBuf {
+    let cfg_file = Config::file_path();
+    cfg_file
+        .parent()
+        .map_or_else(Config::file_path, Path::to_path_buf)
+        .join(FILE_NAME)
+}
+
+/// Append `events` to the event stream.
+pub fn emit(events: impl IntoIterator<Item = Event>) {
+    let path = file_path();
+    for event in events {
+        if let Err(e) = append(&path, &event) {
+            warn!("Cannot write to {}: {e}", path.display());
+            return;
+        }
+    }
+}
+
+fn append(path: &Path, event: &Event) -> io::Result<()> {
+    let line = serde_json::to_string(&Record {
+        time: metadata::now(),
+        event,
+    })?;
+    if fs::metadata(path).is_ok_and(|m| m.len() > MAX_SIZE) {
+        File::create(path)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    // One write per line, so that concurrent rustm processes do not interleave.
+    file.write_all(format!("{line}\n").as_bytes())
+}
+
+/// Print the events appended from now on to `out`, until writing fails (the
+/// reader went away).
+pub fn follow(out: &mut impl Write) -> io::Result<()> {
+    let mut follower = Follower::new()?;
+    loop {
+        for line in follower.read_lines()? {
+            writeln!(out, "{line}")?;
+        }
+        out.flush()?;
+        thread::sleep(POLL);
+    }
+}
+
+/// Reader of the events appended to the stream after it was created.
+pub struct Follower {
+    file: File,
+    position: u64,
+    pending: String,
+}
+
+impl Follower {
+    /// Start at the current end of the stream, creating the file if needed.
+    pub fn new() -> io::Result<Self> {
+        let path = file_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .read(true)
+            .open(&path)?;
+        let position = file.seek(SeekFrom::End(0))?;
+        Ok(Self {
+            file,
+            position,
+            pending: String::new(),
+        })
+    }
+
+    /// The complete lines appended since the previous call.
+    pub fn read_lines(&mut self) -> io::Result<Vec<String>> {
+        read_new_lines(&mut self.file, &mut self.position, &mut self.pending)
+    }
+}
+
+/// Complete lines appended to `file` since `position`, which is moved past
+/// them; an incomplete last line is kept in `pending`.
+fn read_new_lines(
+    file: &mut File,
+    position: &mut u64,
+    pending: &mut String,
+) -> io::Result<Vec<String>> {
+    if file.metadata()?.len() < *position {
+        // Emptied by `append`: start over.
+        *position = 0;
+        pending.clear();
+    }
+    file.seek(SeekFrom::Start(*position))?;
+    let mut chunk = String::new();
+    *position += file.read_to_string(&mut chunk)? as u64;
+    pending.push_str(&chunk);
+    let Some(end) = pending.rfind('\n') else {
+        return Ok(Vec::new());
+    };
+    let lines = pending[..end].lines().map(ToString::to_string).collect();
+    pending.drain(..=end);
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn appended_events_are_read_back_as_lines() {
+        let dir = TempDir::new("events");
+        let path = dir.join("events.jsonl");
+        File::create(&path).unwrap();
+        let mut reader = File::open(&path).unwrap();
+        let (mut position, mut pending) = (0, String::new());
+
+        let created = Event::ProjectCreated {
+            name: "demo".into(),
+            path: PathBuf::from("/projects/demo"),
+        };
+        append(&path, &created).unwrap();
+        let lines = read_new_lines(&mut reader, &mut position, &mut pending).unwrap();
+        assert_eq!(lines.len(), 1);
+        let value: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(value["event"], "project_created");
+        assert_eq!(value["name"], "demo");
+        assert!(value["time"].as_u64().unwrap() > 0);
+
+        // A partial line waits for its end; an emptied file is read from the start.
+        fs::write(&path, "{\"event\":").unwrap();
+        assert!(
+            read_new_lines(&mut reader, &mut position, &mut pending)
+                .unwrap()
+                .is_empty()
+        );
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"\"x\"}\n").unwrap();
+        assert_eq!(
+            read_new_lines(&mut reader, &mut position, &mut pending).unwrap(),
+            ["{\"event\":\"x\"}"]
+        );
+    }
+}
@@ -0,0 +1,293 @@
+//! Human-readable times and sizes.
+//!
+//! Every screen formats ages, timestamps and sizes through this module so they
+//! read the same everywhere and follow the same preferences:
+//! - `timestamps` of the configuration: relative times (`3 days ago`, `3d` in
+//!   columns) or absolute ones (`2025-06-01 14:03 UTC`, the date in columns);
+//! - the locale of the environment, POSIX style: `LC_ALL`, then `LC_TIME` for
+//!   the order of dates and `LC_NUMERIC` for the decimal separator, then `LANG`.
+//!
+//! Absolute times are in UTC: rustm does not read the time zone database.
+//! Wording stays English whatever the locale.
+
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::Timestamps;
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+const MONTH: i64 = 30 * DAY;
+const YEAR: i64 = 365 * DAY;
+
+/// Units of relative times, largest first: length, compact and long names.
+const TIME_UNITS: [(i64, &str, &str); 6] = [
+    (YEAR, "y", "year"),
+    (MONTH, "mo", "month"),
+    (WEEK, "w", "week"),
+    (DAY, "d", "day"),
+    (HOUR, "h", "hour"),
+    (MINUTE, "m", "minute"),
+];
+
+/// Absolute timestamps, as set by `set_timestamps`.
+static ABSOLUTE: AtomicBool = AtomicBool::new(false);
+
+static LOCALE: LazyLock<Locale> = LazyLock::new(Locale::from_env);
+
+/// Apply the `timestamps` setting of the configuration.
+pub fn set_timestamps(timestamps: Timestamps) {
+    ABSOLUTE.store(timestamps == Timestamps::Absolute, Ordering::Relaxed);
+}
+
+fn timestamps() -> Timestamps {
+    if ABSOLUTE.load(Ordering::Relaxed) {
+        Timestamps::Absolute
+    } else {
+        Timestamps::Relative
+    }
+}
+
+/// Locale of the environment; tests always get `Locale::POSIX`.
+fn locale() -> Locale {
+    if cfg!(test) { Locale::POSIX } else { *LOCALE }
+}
+
+/// Order of the fields of a date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateOrder {
+    /// `2025-06-01`.
+    Ymd,
+    /// `01.06.2025`.
+    Dmy,
+    /// `06/01/2025`.
+    Mdy,
+}
+
+/// How dates and decimal numbers are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    dates: DateOrder,
+    date_separator: char,
+    decimal: char,
+}
+
+impl Locale {
+    /// ISO dates and decimal point, for `C`, `POSIX` and unknown locales.
+    pub const POSIX: Self = Self {
+        dates: DateOrder::Ymd,
+        date_separator: '-',
+        decimal: '.',
+    };
+
+    fn from_env() -> Self {
+        let var = |name| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let category = |name| {
+            var("LC_ALL")
+                .or_else(|| var(name))
+                .or_else(|| var("LANG"))
+                .unwrap_or_default()
+        };
+        Self::parse(&category("LC_TIME"), &category("LC_NUMERIC"))
+    }
+
+    /// Locale of the `time` and `numeric` locale names (`de_DE.UTF-8`, `en_US`, `C`).
+    fn parse(time: &str, numeric: &str) -> Self {
+        let (dates, date_separator) = match language(time) {
+            ("en", "US") => (DateOrder::Mdy, '/'),
+            ("en", "CA") => (DateOrder::Ymd, '-'),
+            ("en", territory) if !territory.is_empty() => (DateOrder::Dmy, '/'),
+            (
+                "de" | "ru" | "pl" | "cs" | "sk" | "fi" | "nb" | "nn" | "no" | "da" | "tr" | "uk"
+                | "ro" | "hr" | "sl",
+                _,
+            ) => (DateOrder::Dmy, '.'),
+            ("fr" | "es" | "it" | "pt" | "el" | "ca", _) => (DateOrder::Dmy, '/'),
+            ("nl", _) => (DateOrder::Dmy, '-'),
+            _ => (Self::POSIX.dates, Self::POSIX.date_separator),
+        };
+        let decimal = match language(numeric) {
+            ("de", "CH") => '.',
+            (
+                "de" | "fr" | "es" | "it" | "pt" | "nl" | "ru" | "pl" | "cs" | "sk" | "fi" | "sv"
+                | "nb" | "nn" | "no" | "da" | "tr" | "uk" | "ro" | "hr" | "sl" | "el" | "ca" | "lt"
+                | "hu" | "id",
+                _,
+            ) => ',',
+            _ => Self::POSIX.decimal,
+        };
+        Self {
+            dates,
+            date_separator,
+            decimal,
+        }
+    }
+}
+
+/// Language and territory of a locale name: `de_DE.UTF-8@euro` is `("de", "DE")`.
+fn language(name: &str) -> (&str, &str) {
+    let name = name.split(['.', '@']).next().unwrap_or_default();
+    name.split_once('_').unwrap_or((name, ""))
+}
+
+/// Largest unit fitting in `seconds`, with the count; `None` under a minute.
+fn largest_unit(seconds: i64) -> Option<(i64, &'static str, &'static str)> {
+    TIME_UNITS
+        .iter()
+        .find(|(length, _, _)| seconds >= *length)
+        .map(|&(length, short, long)| (seconds / length, short, long))
+}
+
+/// Compact age of something `seconds` old: `now`, `5m`, `3h`, `2d`, `4w`, `7mo`, `2y`.
+pub fn age(seconds: i64) -> String {
+    match largest_unit(seconds) {
+        Some((count, short, _)) => format!("{count}{short}"),
+        None => "now".to_string(),
+    }
+}
+
+/// Age of something `seconds` old in words: `just now`, `1 hour ago`, `3 days ago`.
+pub fn ago(seconds: i64) -> String {
+    match largest_unit(seconds) {
+        Some((1, _, long)) => format!("1 {long} ago"),
+        Some((count, _, long)) => format!("{count} {long}s ago"),
+        None => "just now".to_string(),
+    }
+}
+
+/// `time` (seconds since epoch) in full, as set by `timestamps`: `3 days ago`
+/// or `2025-06-01 14:03 UTC`.
+pub fn timestamp(time: i64, now: i64) -> String {
+    timestamp_with(timestamps(), locale(), time, now)
+}
+
+/// `time` in a list column: `3d` or `2025-06-01`.
+pub fn timestamp_column(time: i64, now: i64) -> String {
+    match timestamps() {
+        Timestamps::Relative => age(now - time),
+        Timestamps::Absolute => date(locale(), time),
+    }
+}
+
+/// Width of `timestamp_column` values, to align columns.
+pub fn column_width() -> usize {
+    match timestamps() {
+        Timestamps::Relative => 4,
+        Timestamps::Absolute => 10,
+    }
+}
+
+fn timestamp_with(timestamps: Timestamps, locale: Locale, time: i64, now: i64) -> String {
+    match timestamps {
+        Timestamps::Relative => ago(now - time),
+        Timestamps::Absolute => {
+            let minutes = time.rem_euclid(DAY) / MINUTE;
+            format!(
+                "{} {:02}:{:02} UTC",
+                date(locale, time),
+                minutes / 60,
+                minutes % 60
+            )
+        }
+    }
+}
+
+/// Date of `time` (UTC) in the order of `locale`.
+fn date(locale: Locale, time: i64) -> String {
+    let (year, month, day) = civil_date(time.div_euclid(DAY));
+    let sep = locale.date_separator;
+    match locale.dates {
+        DateOrder::Ymd => format!("{year}{sep}{month:02}{sep}{day:02}"),
+        DateOrder::Dmy => format!("{day:02}{sep}{month:02}{sep}{year}"),
+        DateOrder::Mdy => format!("{month:02}{sep}{day:02}{sep}{year}"),
+    }
+}
+
+/// Year, month and day of `days` since 1970-01-01 (Howard Hinnant's algorithm).
+pub fn civil_date(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// `512 B`, `12.0 KiB`, `1.5 MiB`, `2.1 GiB`, with the decimal separator of the locale.
+pub fn size(bytes: u64) -> String {
+    size_with(locale(), bytes)
+}
+
+fn size_with(locale: Locale, bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit]).replacen('.', &locale.decimal.to_string(), 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_times_use_the_largest_unit() {
+        assert_eq!(age(-5), "now");
+        assert_eq!(age(59), "now");
+        assert_eq!(age(60 * 5), "5m");
+        assert_eq!(age(3600 * 3 + 59), "3h");
+        assert_eq!(age(86_400 * 2), "2d");
+        assert_eq!(age(86_400 * 15), "2w");
+        assert_eq!(age(86_400 * 200), "6mo");
+        assert_eq!(age(86_400 * 800), "2y");
+
+        assert_eq!(ago(30), "just now");
+        assert_eq!(ago(3600), "1 hour ago");
+        assert_eq!(ago(86_400 * 3 + 5), "3 days ago");
+    }
+
+    #[test]
+    fn absolute_times_follow_the_locale() {
+        // 2025-06-01 14:03:27 UTC.
+        let time = 1_748_786_607;
+        let at = |locale| timestamp_with(Timestamps::Absolute, locale, time, time + 60);
+        assert_eq!(at(Locale::POSIX), "2025-06-01 14:03 UTC");
+        assert_eq!(at(Locale::parse("en_US.UTF-8", "")), "06/01/2025 14:03 UTC");
+        assert_eq!(
+            at(Locale::parse("de_DE.UTF-8@euro", "")),
+            "01.06.2025 14:03 UTC"
+        );
+        assert_eq!(at(Locale::parse("fr_FR", "")), "01/06/2025 14:03 UTC");
+        assert_eq!(at(Locale::parse("C", "")), "2025-06-01 14:03 UTC");
+        assert_eq!(
+            timestamp_with(Timestamps::Relative, Locale::POSIX, time, time + 60),
+            "1 minute ago"
+        );
+
+        assert_eq!(civil_date(0), (1970, 1, 1));
+        assert_eq!(civil_date(-1), (1969, 12, 31));
+        assert_eq!(civil_date(19_782), (2024, 2, 29));
+    }
+
+    #[test]
+    fn sizes_use_binary_units_and_the_decimal_separator() {
+        assert_eq!(size(512), "512 B");
+        assert_eq!(size(3 * 1024 * 1024 / 2), "1.5 MiB");
+        assert_eq!(size(3 << 40), "3.0 TiB");
+        let german = Locale::parse("", "de_DE.UTF-8");
+        assert_eq!(size_with(german, 1_503_238_554), "1,4 GiB");
+        assert_eq!(size_with(Locale::parse("", "de_CH"), 2048), "2.0 KiB");
+    }
+}
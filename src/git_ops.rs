@@ -0,0 +1,1199 @@
+//! Git repository operations.
+//!
+//! Wraps `git2::Repository` calls used by features (tags, remotes, branches,
+//! pull/push, ...) and
+//! turns low-level `git2` failures into `GitError` variants with messages that
+//! make sense to the user.
+
+use std::cell::Cell;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use git2::{Cred, CredentialType, FetchOptions, PushOptions, RemoteCallbacks, Repository};
+use log::info;
+
+/// Credential callback invocations before giving up (libgit2 retries forever otherwise).
+const MAX_CREDENTIAL_ATTEMPTS: usize = 4;
+
+/// Friendly git error.
+#[derive(Debug)]
+pub enum GitError {
+    NotARepository(PathBuf),
+    NoSuchRemote(String),
+    NoSuchBranch(String),
+    BranchExists(String),
+    InvalidBranchName(String),
+    /// The branch cannot be created because `HEAD` has no commit yet.
+    UnbornHead,
+    /// Checking out would overwrite local changes.
+    CheckoutConflict(String),
+    /// `HEAD` is not on a branch.
+    DetachedHead,
+    /// The remote rejected the credentials (or none could be found).
+    AuthFailed(String),
+    /// Pull: local and remote histories diverged.
+    NotFastForward,
+    /// Push: the remote refused the update (usually because it has new commits).
+    PushRejected(String),
+    NoSuchSnapshot(String),
+    Git(git2::Error),
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotARepository(p) => write!(f, "Not a git repository: {}", p.display()),
+            Self::NoSuchRemote(name) => write!(f, "No remote named '{name}' is configured"),
+            Self::NoSuchBranch(name) => write!(f, "No local branch named '{name}'"),
+            Self::BranchExists(name) => write!(f, "A branch named '{name}' already exists"),
+            Self::InvalidBranchName(name) => write!(f, "'{name}' is not a valid branch name"),
+            Self::UnbornHead => write!(f, "The repository has no commit yet"),
+            Self::CheckoutConflict(name) => write!(
+                f,
+                "Cannot switch to '{name}': local changes would be overwritten (commit or stash them first)"
+            ),
+            Self::DetachedHead => write!(f, "HEAD is detached; check out a branch first"),
+            Self::AuthFailed(url) => write!(f, "Authentication failed for {url}"),
+            Self::NotFastForward => write!(
+                f,
+                "Local and remote branches have diverged; merge or rebase manually"
+            ),
+            Self::PushRejected(msg) => write!(f, "Push rejected by the remote: {msg}"),
+            Self::NoSuchSnapshot(name) => write!(f, "No snapshot named '{name}'"),
+            Self::Git(e) => write!(f, "Git error: {}", e.message()),
+        }
+    }
+}
+
+impl std::error::Error for GitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Git(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<git2::Error> for GitError {
+    fn from(e: git2::Error) -> Self {
+        Self::Git(e)
+    }
+}
+
+/// Open the repository rooted at `dir`.
+pub fn open(dir: &Path) -> Result<Repository, GitError> {
+    Repository::open(dir).map_err(|e| {
+        if e.code() == git2::ErrorCode::NotFound {
+            GitError::NotARepository(dir.to_path_buf())
+        } else {
+            GitError::Git(e)
+        }
+    })
+}
+
+/// Name of a tag pointing at `HEAD`, if any (highest name first when several do).
+pub fn current_tag(dir: &Path) -> Result<Option<String>, GitError> {
+    let repo = open(dir)?;
+    let head = repo.head()?.peel_to_commit()?.id();
+
+    let mut matching = Vec::new();
+    for name in repo.tag_names(None)?.iter().flatten() {
+        let target = repo
+            .revparse_single(&format!("refs/tags/{name}"))
+            .and_then(|obj| obj.peel_to_commit());
+        if target.is_ok_and(|c| c.id() == head) {
+            matching.push(name.to_string());
+        }
+    }
+    matching.sort();
+    Ok(matching.pop())
+}
+
+/// Most recent tag reachable from `HEAD` (like `git describe --tags --abbrev=0`).
+pub fn latest_tag(dir: &Path) -> Result<Option<String>, GitError> {
+    let repo = open(dir)?;
+    let mut opts = git2::DescribeOptions::new();
+    opts.describe_tags();
+    let describe = match repo.describe(&opts) {
+        Ok(d) => d,
+        Err(e)
+            if matches!(
+                e.code(),
+                git2::ErrorCode::NotFound | git2::ErrorCode::UnbornBranch
+            ) =>
+        {
+            return Ok(None);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let mut fmt = git2::DescribeFormatOptions::new();
+    fmt.abbreviated_size(0);
+    Ok(Some(describe.format(Some(&fmt))?))
+}
+
+/// URL of the remote called `name`.
+pub fn remote_url(dir: &Path, name: &str) -> Result<String, GitError> {
+    let repo = open(dir)?;
+    let remote = repo
+        .find_remote(name)
+        .map_err(|_| GitError::NoSuchRemote(name.to_string()))?;
+    remote
+        .url()
+        .map(ToString::to_string)
+        .ok_or_else(|| GitError::NoSuchRemote(name.to_string()))
+}
+
+/// Name of the branch `HEAD` points to; `None` when `HEAD` is detached.
+///
+/// Works on freshly initialised repositories too (unborn branch).
+pub fn current_branch(dir: &Path) -> Result<Option<String>, GitError> {
+    let repo = open(dir)?;
+    match repo.head() {
+        Ok(head) if head.is_branch() => Ok(head.shorthand().map(ToString::to_string)),
+        Ok(_) => Ok(None),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
+            let head = repo.find_reference("HEAD")?;
+            Ok(head
+                .symbolic_target()
+                .and_then(|t| t.strip_prefix("refs/heads/"))
+                .map(ToString::to_string))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Commits `(ahead, behind)` the current branch is compared to its upstream.
+///
+/// `None` when `HEAD` is detached or unborn, or the branch has no upstream.
+pub fn ahead_behind(dir: &Path) -> Result<Option<(usize, usize)>, GitError> {
+    let repo = open(dir)?;
+    let head = match repo.head() {
+        Ok(head) if head.is_branch() => head,
+        Ok(_) => return Ok(None),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let Some(local) = head.target() else {
+        return Ok(None);
+    };
+    let upstream = match git2::Branch::wrap(head).upstream() {
+        Ok(upstream) => upstream,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let Some(remote) = upstream.get().target() else {
+        return Ok(None);
+    };
+    Ok(Some(repo.graph_ahead_behind(local, remote)?))
+}
+
+/// Working tree changes as `XY path` lines (`git status --short` style).
+///
+/// `X` is the index column, `Y` the worktree column; untracked files show as `??`.
+pub fn status_lines(dir: &Path) -> Result<Vec<String>, GitError> {
+    let repo = open(dir)?;
+    let mut opts = git2::StatusOptions::new();
+    opts.include_untracked(true).recurse_untracked_dirs(true);
+    let statuses = repo.statuses(Some(&mut opts))?;
+    let mut lines = Vec::new();
+    for entry in statuses.iter() {
+        let status = entry.status();
+        let Some(path) = entry.path() else {
+            continue;
+        };
+        let code = if status.is_wt_new() {
+            "??".to_string()
+        } else {
+            let index = if status.is_index_new() {
+                'A'
+            } else if status.is_index_modified() {
+                'M'
+            } else if status.is_index_deleted() {
+                'D'
+            } else if status.is_index_renamed() {
+                'R'
+            } else {
+                ' '
+            };
+            let worktree = if status.is_wt_modified() {
+                'M'
+            } else if status.is_wt_deleted() {
+                'D'
+            } else if status.is_wt_renamed() {
+                'R'
+            } else {
+                ' '
+            };
+            format!("{index}{worktree}")
+        };
+        if code.trim().is_empty() {
+            continue;
+        }
+        lines.push(format!("{code} {path}"));
+    }
+    Ok(lines)
+}
+
+/// A commit as shown in activity listings.
+#[derive(Debug, Clone)]
+pub struct CommitSummary {
+    /// Abbreviated id (8 hex digits).
+    pub id: String,
+    pub author: String,
+    /// Commit time, seconds since epoch.
+    pub time: i64,
+    /// First line of the message.
+    pub summary: String,
+    pub message: String,
+}
+
+/// Up to `limit` most recent commits reachable from `HEAD`, newest first.
+///
+/// Unborn branches yield an empty list.
+pub fn recent_commits(dir: &Path, limit: usize) -> Result<Vec<CommitSummary>, GitError> {
+    let repo = open(dir)?;
+    match repo.head() {
+        Ok(_) => {}
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    }
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    let mut commits = Vec::new();
+    for oid in walk.take(limit) {
+        let commit = repo.find_commit(oid?)?;
+        let id = commit.id().to_string();
+        commits.push(CommitSummary {
+            id: id[..8].to_string(),
+            author: commit.author().name().unwrap_or("?").to_string(),
+            time: commit.time().seconds(),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            message: commit.message().unwrap_or_default().trim_end().to_string(),
+        });
+    }
+    Ok(commits)
+}
+
+/// Local commits that no remote has.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnpushedWork {
+    /// At least one remote is configured.
+    pub has_remote: bool,
+    /// `(branch, commits)` for each local branch with commits missing from every
+    /// remote-tracking branch (all its commits when there is no remote), sorted.
+    pub branches: Vec<(String, usize)>,
+}
+
+impl UnpushedWork {
+    /// Total number of commits that exist only locally (shared commits counted once per branch).
+    pub fn commits(&self) -> usize {
+        self.branches.iter().map(|(_, n)| n).sum()
+    }
+}
+
+/// Local branches with commits not reachable from any remote-tracking branch.
+///
+/// Based on the remote-tracking refs, i.e. on the last fetch or push.
+pub fn unpushed_work(dir: &Path) -> Result<UnpushedWork, GitError> {
+    let repo = open(dir)?;
+    let has_remote = !repo.remotes()?.is_empty();
+    let mut branches = Vec::new();
+    for branch in repo.branches(Some(git2::BranchType::Local))? {
+        let (branch, _) = branch?;
+        let (Some(name), Some(tip)) = (branch.name()?, branch.get().target()) else {
+            continue;
+        };
+        let mut walk = repo.revwalk()?;
+        walk.push(tip)?;
+        walk.hide_glob("refs/remotes")?;
+        let count = walk.count();
+        if count > 0 {
+            branches.push((name.to_string(), count));
+        }
+    }
+    branches.sort();
+    Ok(UnpushedWork {
+        has_remote,
+        branches,
+    })
+}
+
+/// Add a remote `name` pointing at `url`.
+pub fn add_remote(dir: &Path, name: &str, url: &str) -> Result<(), GitError> {
+    open(dir)?.remote(name, url)?;
+    info!("Added remote {name} ({url}) to {}", dir.display());
+    Ok(())
+}
+
+/// A local branch that is both ahead of and behind its upstream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub branch: String,
+    /// Upstream short name, e.g. `origin/main`.
+    pub upstream: String,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+/// Name of the default branch: the target of `refs/remotes/<remote>/HEAD`
+/// (`origin` first), else a local `main` or `master`.
+pub fn default_branch(dir: &Path) -> Result<Option<String>, GitError> {
+    let repo = open(dir)?;
+    let mut remotes: Vec<String> = repo.remotes()?.iter().flatten().map(String::from).collect();
+    remotes.sort_by_key(|r| r != "origin");
+    for remote in &remotes {
+        let Ok(head) = repo.find_reference(&format!("refs/remotes/{remote}/HEAD")) else {
+            continue;
+        };
+        let prefix = format!("refs/remotes/{remote}/");
+        if let Some(branch) = head.symbolic_target().and_then(|t| t.strip_prefix(&prefix)) {
+            return Ok(Some(branch.to_string()));
+        }
+    }
+    Ok(["main", "master"]
+        .into_iter()
+        .find(|name| repo.find_branch(name, git2::BranchType::Local).is_ok())
+        .map(ToString::to_string))
+}
+
+/// The local default branch when it has diverged from its upstream.
+///
+/// `None` without a default branch, without an upstream, or when one side
+/// simply contains the other. Based on the last fetch.
+pub fn default_branch_divergence(dir: &Path) -> Result<Option<Divergence>, GitError> {
+    let Some(name) = default_branch(dir)? else {
+        return Ok(None);
+    };
+    let repo = open(dir)?;
+    let local = match repo.find_branch(&name, git2::BranchType::Local) {
+        Ok(local) => local,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let upstream = match local.upstream() {
+        Ok(upstream) => upstream,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let (Some(ours), Some(theirs)) = (local.get().target(), upstream.get().target()) else {
+        return Ok(None);
+    };
+    let (ahead, behind) = repo.graph_ahead_behind(ours, theirs)?;
+    if ahead == 0 || behind == 0 {
+        return Ok(None);
+    }
+    Ok(Some(Divergence {
+        branch: name,
+        upstream: upstream.name()?.unwrap_or_default().to_string(),
+        ahead,
+        behind,
+    }))
+}
+
+/// Names of all local branches, sorted.
+pub fn local_branches(dir: &Path) -> Result<Vec<String>, GitError> {
+    let repo = open(dir)?;
+    let mut names = Vec::new();
+    for branch in repo.branches(Some(git2::BranchType::Local))? {
+        let (branch, _) = branch?;
+        if let Some(name) = branch.name()? {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Check out the existing local branch `name`, refusing to overwrite local changes.
+pub fn checkout_branch(dir: &Path, name: &str) -> Result<(), GitError> {
+    let repo = open(dir)?;
+    let branch = repo
+        .find_branch(name, git2::BranchType::Local)
+        .map_err(|_| GitError::NoSuchBranch(name.to_string()))?;
+    let refname = branch
+        .get()
+        .name()
+        .ok_or_else(|| GitError::NoSuchBranch(name.to_string()))?
+        .to_string();
+    let target = branch.get().peel(git2::ObjectType::Commit)?;
+
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.safe();
+    repo.checkout_tree(&target, Some(&mut checkout))
+        .map_err(|e| match e.code() {
+            git2::ErrorCode::Conflict => GitError::CheckoutConflict(name.to_string()),
+            _ => GitError::Git(e),
+        })?;
+    repo.set_head(&refname)?;
+    Ok(())
+}
+
+/// Create branch `name` at `HEAD` and switch to it.
+pub fn create_branch(dir: &Path, name: &str) -> Result<(), GitError> {
+    if !git2::Branch::name_is_valid(name)? {
+        return Err(GitError::InvalidBranchName(name.to_string()));
+    }
+    let repo = open(dir)?;
+    if repo.find_branch(name, git2::BranchType::Local).is_ok() {
+        return Err(GitError::BranchExists(name.to_string()));
+    }
+    let head = match repo.head() {
+        Ok(head) => head.peel_to_commit()?,
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Err(GitError::UnbornHead),
+        Err(e) => return Err(e.into()),
+    };
+    let branch = repo.branch(name, &head, false)?;
+    // Same commit as HEAD: no working tree update needed.
+    if let Some(refname) = branch.get().name() {
+        repo.set_head(refname)?;
+    }
+    Ok(())
+}
+
+/// Delete the local branch `name` (not the current one).
+pub fn delete_branch(dir: &Path, name: &str) -> Result<(), GitError> {
+    let repo = open(dir)?;
+    let mut branch = repo
+        .find_branch(name, git2::BranchType::Local)
+        .map_err(|_| GitError::NoSuchBranch(name.to_string()))?;
+    branch.delete()?;
+    Ok(())
+}
+
+/// Discard every change to tracked files (`git reset --hard HEAD`).
+pub fn reset_hard(dir: &Path) -> Result<(), GitError> {
+    let repo = open(dir)?;
+    let head = repo.head()?.peel(git2::ObjectType::Commit)?;
+    repo.reset(&head, git2::ResetType::Hard, None)?;
+    Ok(())
+}
+
+/// Stage every change (`git add -A`) and commit it on top of `HEAD` (as the
+/// first commit on an unborn branch); returns the abbreviated id.
+pub fn commit_all(dir: &Path, message: &str) -> Result<String, GitError> {
+    let repo = open(dir)?;
+    let mut index = repo.index()?;
+    index.add_all(["*"], git2::IndexAddOption::DEFAULT, None)?;
+    index.update_all(["*"], None)?;
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit()?),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e.into()),
+    };
+    let signature = repo.signature()?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let id = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )?;
+    info!("Committed {id} in {}", dir.display());
+    Ok(id.to_string()[..8].to_string())
+}
+
+/// Namespace of the safety snapshot refs.
+const SNAPSHOT_REFS: &str = "refs/rustm/snapshots/";
+
+/// Snapshots kept per repository; older ones are deleted when a new one is taken.
+const MAX_SNAPSHOTS: usize = 20;
+
+/// Safety snapshot taken before a risky workflow (see `snapshot`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Name of the ref below `refs/rustm/snapshots/`.
+    pub name: String,
+    /// What was about to happen, e.g. "rebase".
+    pub label: String,
+    /// Branch checked out at the time (`None` with a detached `HEAD`).
+    pub branch: Option<String>,
+    /// Seconds since epoch.
+    pub time: i64,
+}
+
+/// Record `HEAD` and the uncommitted changes (untracked files included) before `label`.
+///
+/// The state is committed, without touching the index, the working tree or
+/// any branch, as a commit whose parent is `HEAD`, kept under
+/// `refs/rustm/snapshots/`; it is never pushed. `target/` and ignored files
+/// are left out. `None` outside git repositories and when `HEAD` has no commit yet.
+pub fn snapshot(dir: &Path, label: &str) -> Result<Option<Snapshot>, GitError> {
+    let repo = match open(dir) {
+        Ok(repo) => repo,
+        Err(GitError::NotARepository(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let branch = head
+        .is_branch()
+        .then(|| head.shorthand().map(str::to_string))
+        .flatten();
+    let parent = head.peel_to_commit()?;
+
+    // The repository index, changed in memory only and reloaded afterwards.
+    let mut index = repo.index()?;
+    let mut skip_target = |path: &Path, _: &[u8]| i32::from(path.starts_with("target"));
+    let tree = index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, Some(&mut skip_target))
+        .and_then(|()| index.update_all(["*"], None))
+        .and_then(|()| index.write_tree());
+    index.read(true)?;
+    let tree = repo.find_tree(tree?)?;
+
+    let signature = repo
+        .signature()
+        .or_else(|_| git2::Signature::now("rustm", "rustm@localhost"))?;
+    let mut message = format!("rustm snapshot: {label}\n");
+    if let Some(branch) = &branch {
+        message.push_str(&format!("\nbranch: {branch}\n"));
+    }
+    let id = repo.commit(None, &signature, &signature, &message, &tree, &[&parent])?;
+
+    let time = signature.when().seconds();
+    let slug: String = label
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let mut name = format!("{time}-{slug}");
+    let mut n = 1;
+    while repo
+        .find_reference(&format!("{SNAPSHOT_REFS}{name}"))
+        .is_ok()
+    {
+        n += 1;
+        name = format!("{time}-{slug}-{n}");
+    }
+    repo.reference(&format!("{SNAPSHOT_REFS}{name}"), id, false, &message)?;
+    info!("Snapshot {name} of {} taken before {label}", dir.display());
+
+    for old in snapshots(dir)?.iter().skip(MAX_SNAPSHOTS) {
+        delete_snapshot(dir, &old.name)?;
+    }
+    Ok(Some(Snapshot {
+        name,
+        label: label.to_string(),
+        branch,
+        time,
+    }))
+}
+
+/// Safety snapshots of the repository at `dir`, newest first.
+pub fn snapshots(dir: &Path) -> Result<Vec<Snapshot>, GitError> {
+    let repo = open(dir)?;
+    let mut snapshots = Vec::new();
+    for reference in repo.references_glob(&format!("{SNAPSHOT_REFS}*"))? {
+        let reference = reference?;
+        let (Some(full), Ok(commit)) = (reference.name(), reference.peel_to_commit()) else {
+            continue;
+        };
+        let message = commit.message().unwrap_or_default();
+        snapshots.push(Snapshot {
+            name: full.trim_start_matches(SNAPSHOT_REFS).to_string(),
+            label: commit
+                .summary()
+                .unwrap_or_default()
+                .trim_start_matches("rustm snapshot: ")
+                .to_string(),
+            branch: message
+                .lines()
+                .find_map(|l| l.strip_prefix("branch: "))
+                .map(str::to_string),
+            time: commit.time().seconds(),
+        });
+    }
+    snapshots.sort_by(|a, b| b.time.cmp(&a.time).then_with(|| b.name.cmp(&a.name)));
+    Ok(snapshots)
+}
+
+/// Bring the repository back to the snapshot `name`.
+///
+/// Its branch (when it still exists) is checked out and reset to the commit
+/// `HEAD` was on, and the uncommitted changes of the time come back as
+/// unstaged changes. Current uncommitted changes to tracked files are lost;
+/// files created since are kept.
+pub fn restore_snapshot(dir: &Path, name: &str) -> Result<(), GitError> {
+    let repo = open(dir)?;
+    let reference = repo
+        .find_reference(&format!("{SNAPSHOT_REFS}{name}"))
+        .map_err(|_| GitError::NoSuchSnapshot(name.to_string()))?;
+    let snapshot = reference.peel_to_commit()?;
+    let parent = snapshot.parent(0)?;
+    let branch = snapshot
+        .message()
+        .unwrap_or_default()
+        .lines()
+        .find_map(|l| l.strip_prefix("branch: "))
+        .and_then(|b| repo.find_branch(b, git2::BranchType::Local).ok())
+        .and_then(|b| b.get().name().map(str::to_string));
+    match branch {
+        Some(refname) => repo.set_head(&refname)?,
+        None => repo.set_head_detached(parent.id())?,
+    }
+    repo.reset(parent.as_object(), git2::ResetType::Hard, None)?;
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.force();
+    repo.checkout_tree(snapshot.as_object(), Some(&mut checkout))?;
+    repo.reset_default(Some(parent.as_object()), ["*"])?;
+    info!("Restored snapshot {name} in {}", dir.display());
+    Ok(())
+}
+
+/// Delete the snapshot `name`.
+pub fn delete_snapshot(dir: &Path, name: &str) -> Result<(), GitError> {
+    let repo = open(dir)?;
+    let mut reference = repo
+        .find_reference(&format!("{SNAPSHOT_REFS}{name}"))
+        .map_err(|_| GitError::NoSuchSnapshot(name.to_string()))?;
+    reference.delete()?;
+    Ok(())
+}
+
+/// Fetch the remote of the current branch's upstream (`origin` without one, or
+/// with a detached `HEAD`), updating its remote-tracking branches only.
+pub fn fetch(dir: &Path, log: &dyn Fn(String)) -> Result<(), GitError> {
+    let repo = open(dir)?;
+    let remote_name = match head_branch(&repo) {
+        Ok(branch) => upstream_of(&repo, &branch).0,
+        Err(_) => "origin".to_string(),
+    };
+    let mut remote = repo
+        .find_remote(&remote_name)
+        .map_err(|_| GitError::NoSuchRemote(remote_name.clone()))?;
+    let url = remote.url().unwrap_or_default().to_string();
+    log(format!("Fetching {remote_name} ({url})..."));
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks(repo.config().ok(), log));
+    remote
+        .fetch(&[] as &[&str], Some(&mut opts), None)
+        .map_err(|e| network_error(e, &url))?;
+    info!("Fetched {remote_name} in {}", dir.display());
+    Ok(())
+}
+
+/// Result of a successful pull.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullOutcome {
+    UpToDate,
+    FastForwarded,
+}
+
+/// Fetch the upstream of the current branch and fast-forward to it.
+///
+/// Without an upstream, `origin/<branch>` is used. Diverged histories are
+/// reported as `NotFastForward`: merging or rebasing is left to the user.
+pub fn pull(dir: &Path, log: &dyn Fn(String)) -> Result<PullOutcome, GitError> {
+    let repo = open(dir)?;
+    let branch = head_branch(&repo)?;
+    let (remote_name, merge_ref) = upstream_of(&repo, &branch);
+
+    let mut remote = repo
+        .find_remote(&remote_name)
+        .map_err(|_| GitError::NoSuchRemote(remote_name.clone()))?;
+    let url = remote.url().unwrap_or_default().to_string();
+    log(format!("Fetching {remote_name} ({url})..."));
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks(repo.config().ok(), log));
+    remote
+        .fetch(&[merge_ref.as_str()], Some(&mut opts), None)
+        .map_err(|e| network_error(e, &url))?;
+
+    let fetch_head = repo.find_reference("FETCH_HEAD")?;
+    let incoming = repo.reference_to_annotated_commit(&fetch_head)?;
+    let (analysis, _) = repo.merge_analysis(&[&incoming])?;
+    if analysis.is_up_to_date() {
+        log("Already up to date".into());
+        return Ok(PullOutcome::UpToDate);
+    }
+    if !analysis.is_fast_forward() {
+        return Err(GitError::NotFastForward);
+    }
+
+    let refname = format!("refs/heads/{branch}");
+    let mut reference = repo.find_reference(&refname)?;
+    let target = repo.find_object(incoming.id(), None)?;
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.safe();
+    repo.checkout_tree(&target, Some(&mut checkout))
+        .map_err(|e| match e.code() {
+            git2::ErrorCode::Conflict => GitError::CheckoutConflict(branch.clone()),
+            _ => GitError::Git(e),
+        })?;
+    reference.set_target(incoming.id(), "rustm: pull (fast-forward)")?;
+    info!("Fast-forwarded {} to {}", dir.display(), incoming.id());
+    log(format!("Fast-forwarded {branch} to {:.7}", incoming.id()));
+    Ok(PullOutcome::FastForwarded)
+}
+
+/// Create an empty repository in `dir` whose first branch is `branch`.
+pub fn init(dir: &Path, branch: &str) -> Result<(), GitError> {
+    let mut opts = git2::RepositoryInitOptions::new();
+    opts.initial_head(branch);
+    Repository::init_opts(dir, &opts)?;
+    Ok(())
+}
+
+/// Make the first commit of the repository at `dir` land on `branch`, whatever
+/// `init.defaultBranch` says; `false` when `HEAD` already has commits.
+///
+/// For repositories created by other tools (`cargo new`): only this
+/// repository's `HEAD` changes, never the git configuration.
+pub fn set_initial_branch(dir: &Path, branch: &str) -> Result<bool, GitError> {
+    let repo = open(dir)?;
+    match repo.head() {
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {}
+        Ok(_) => return Ok(false),
+        Err(e) => return Err(e.into()),
+    }
+    repo.set_head(&format!("refs/heads/{branch}"))?;
+    Ok(true)
+}
+
+/// Clone `url` into `dest` (which must not exist yet).
+pub fn clone(url: &str, dest: &Path, log: &dyn Fn(String)) -> Result<(), GitError> {
+    log(format!("Cloning {url}..."));
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(callbacks(git2::Config::open_default().ok(), log));
+    git2::build::RepoBuilder::new()
+        .fetch_options(opts)
+        .clone(url, dest)
+        .map_err(|e| network_error(e, url))?;
+    info!("Cloned {url} into {}", dest.display());
+    Ok(())
+}
+
+/// Push the current branch to its upstream (setting `origin/<branch>` as upstream when missing).
+pub fn push(dir: &Path, log: &dyn Fn(String)) -> Result<(), GitError> {
+    let branch = head_branch(&open(dir)?)?;
+    push_branch(dir, &branch, log)
+}
+
+/// Push the local `branch` to its upstream (setting `origin/<branch>` as upstream when missing).
+pub fn push_branch(dir: &Path, branch: &str, log: &dyn Fn(String)) -> Result<(), GitError> {
+    let repo = open(dir)?;
+    let has_upstream = repo
+        .find_branch(branch, git2::BranchType::Local)
+        .and_then(|b| b.upstream())
+        .is_ok();
+    let (remote_name, merge_ref) = upstream_of(&repo, branch);
+
+    let mut remote = repo
+        .find_remote(&remote_name)
+        .map_err(|_| GitError::NoSuchRemote(remote_name.clone()))?;
+    let url = remote.url().unwrap_or_default().to_string();
+    log(format!("Pushing {branch} to {remote_name} ({url})..."));
+
+    let rejection: Cell<Option<String>> = Cell::new(None);
+    let mut cbs = callbacks(repo.config().ok(), log);
+    cbs.push_update_reference(|_, status| {
+        if let Some(msg) = status {
+            rejection.set(Some(msg.to_string()));
+        }
+        Ok(())
+    });
+    let mut opts = PushOptions::new();
+    opts.remote_callbacks(cbs);
+    let refspec = format!("refs/heads/{branch}:{merge_ref}");
+    remote
+        .push(&[refspec.as_str()], Some(&mut opts))
+        .map_err(|e| match e.code() {
+            git2::ErrorCode::NotFastForward => GitError::PushRejected("non-fast-forward".into()),
+            _ => network_error(e, &url),
+        })?;
+    if let Some(msg) = rejection.take() {
+        return Err(GitError::PushRejected(msg));
+    }
+
+    if !has_upstream {
+        let mut local = repo.find_branch(branch, git2::BranchType::Local)?;
+        local.set_upstream(Some(&format!("{remote_name}/{branch}")))?;
+        log(format!("Upstream set to {remote_name}/{branch}"));
+    }
+    info!("Pushed {branch} of {}", dir.display());
+    Ok(())
+}
+
+fn head_branch(repo: &Repository) -> Result<String, GitError> {
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Err(GitError::UnbornHead),
+        Err(e) => return Err(e.into()),
+    };
+    if !head.is_branch() {
+        return Err(GitError::DetachedHead);
+    }
+    head.shorthand()
+        .map(ToString::to_string)
+        .ok_or(GitError::DetachedHead)
+}
+
+/// `(remote, remote ref)` configured for `branch`, defaulting to `origin` and the same name.
+fn upstream_of(repo: &Repository, branch: &str) -> (String, String) {
+    let config = repo.config().ok();
+    let get = |key: &str| config.as_ref().and_then(|c| c.get_string(key).ok());
+    (
+        get(&format!("branch.{branch}.remote")).unwrap_or_else(|| "origin".into()),
+        get(&format!("branch.{branch}.merge")).unwrap_or_else(|| format!("refs/heads/{branch}")),
+    )
+}
+
+/// Callbacks shared by fetch and push: credentials and progress lines.
+///
+/// Credentials are tried in order: SSH agent (SSH URLs), git credential
+/// helpers (HTTPS), then libgit2 defaults.
+fn callbacks(config: Option<git2::Config>, log: &dyn Fn(String)) -> RemoteCallbacks<'_> {
+    let attempts = Cell::new(0);
+    let mut cbs = RemoteCallbacks::new();
+    cbs.credentials(move |url, username, allowed| {
+        attempts.set(attempts.get() + 1);
+        if attempts.get() > MAX_CREDENTIAL_ATTEMPTS {
+            return Err(git2::Error::new(
+                git2::ErrorCode::Auth,
+                git2::ErrorClass::Net,
+                "no valid credentials",
+            ));
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            return Cred::ssh_key_from_agent(username.unwrap_or("git"));
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT)
+            && let Some(config) = &config
+            && let Ok(cred) = Cred::credential_helper(config, url, username)
+        {
+            return Ok(cred);
+        }
+        Cred::default()
+    });
+
+    let last_step = Cell::new(None);
+    cbs.transfer_progress(move |stats| {
+        let total = stats.total_objects();
+        // Report in 25% steps.
+        if let Some(step) = (stats.received_objects() * 4).checked_div(total)
+            && last_step.replace(Some(step)) != Some(step)
+        {
+            log(format!(
+                "  received {}/{} objects",
+                stats.received_objects(),
+                total
+            ));
+        }
+        true
+    });
+    cbs.push_transfer_progress(move |current, total, _bytes| {
+        if total > 0 && current == total {
+            log(format!("  sent {total} objects"));
+        }
+    });
+    cbs
+}
+
+fn network_error(e: git2::Error, url: &str) -> GitError {
+    if e.code() == git2::ErrorCode::Auth
+        || e.message().contains("authentication")
+        || e.message().contains("no valid credentials")
+    {
+        GitError::AuthFailed(url.to_string())
+    } else {
+        GitError::Git(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+    use std::fs;
+
+    fn temp_repo() -> (TempDir, Repository) {
+        let d = TempDir::new("git_ops");
+        let repo = Repository::init(&d).unwrap();
+        (d, repo)
+    }
+
+    fn commit_file(repo: &Repository, name: &str) -> git2::Oid {
+        let root = repo.workdir().unwrap().to_path_buf();
+        fs::write(root.join(name), name).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, name, &tree, &parent_refs)
+            .unwrap()
+    }
+
+    #[test]
+    fn current_tag_only_matches_head() {
+        let (dir, repo) = temp_repo();
+        let first = commit_file(&repo, "a.txt");
+        repo.tag_lightweight("v0.1.0", &repo.find_object(first, None).unwrap(), false)
+            .unwrap();
+        assert_eq!(current_tag(&dir).unwrap().as_deref(), Some("v0.1.0"));
+
+        commit_file(&repo, "b.txt");
+        assert_eq!(current_tag(&dir).unwrap(), None);
+        assert_eq!(latest_tag(&dir).unwrap().as_deref(), Some("v0.1.0"));
+    }
+
+    #[test]
+    fn status_lines_report_index_and_worktree_changes() {
+        let (dir, repo) = temp_repo();
+        commit_file(&repo, "a.txt");
+        fs::write(dir.join("a.txt"), "changed").unwrap();
+        fs::write(dir.join("new.txt"), "new").unwrap();
+        fs::write(dir.join("staged.txt"), "staged").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+
+        let mut lines = status_lines(&dir).unwrap();
+        lines.sort();
+        assert_eq!(lines, vec![" M a.txt", "?? new.txt", "A  staged.txt"]);
+    }
+
+    #[test]
+    fn recent_commits_are_newest_first_and_limited() {
+        let (dir, repo) = temp_repo();
+        assert!(recent_commits(&dir, 5).unwrap().is_empty());
+        commit_file(&repo, "a.txt");
+        commit_file(&repo, "b.txt");
+        commit_file(&repo, "c.txt");
+
+        let commits = recent_commits(&dir, 2).unwrap();
+        let summaries: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, ["c.txt", "b.txt"]);
+        assert_eq!(commits[0].author, "Test");
+        assert_eq!(commits[0].id.len(), 8);
+    }
+
+    #[test]
+    fn create_and_switch_branches() {
+        let (dir, repo) = temp_repo();
+        assert!(matches!(
+            create_branch(&dir, "feature"),
+            Err(GitError::UnbornHead)
+        ));
+        commit_file(&repo, "a.txt");
+        let initial = current_branch(&dir).unwrap().unwrap();
+
+        create_branch(&dir, "feature").unwrap();
+        assert_eq!(current_branch(&dir).unwrap().as_deref(), Some("feature"));
+        assert!(matches!(
+            create_branch(&dir, "feature"),
+            Err(GitError::BranchExists(_))
+        ));
+        assert!(matches!(
+            create_branch(&dir, "bad..name"),
+            Err(GitError::InvalidBranchName(_))
+        ));
+
+        commit_file(&repo, "b.txt");
+        checkout_branch(&dir, &initial).unwrap();
+        assert_eq!(current_branch(&dir).unwrap(), Some(initial.clone()));
+        assert!(!dir.join("b.txt").exists());
+
+        let mut expected = vec![initial, "feature".to_string()];
+        expected.sort();
+        assert_eq!(local_branches(&dir).unwrap(), expected);
+    }
+
+    #[test]
+    fn initial_branch_is_set_per_repository() {
+        let (dir, repo) = temp_repo();
+        assert!(set_initial_branch(&dir, "trunk").unwrap());
+        assert_eq!(current_branch(&dir).unwrap().as_deref(), Some("trunk"));
+        commit_file(&repo, "a.txt");
+        assert_eq!(current_branch(&dir).unwrap().as_deref(), Some("trunk"));
+        assert!(!set_initial_branch(&dir, "main").unwrap());
+        assert_eq!(current_branch(&dir).unwrap().as_deref(), Some("trunk"));
+
+        let fresh = dir.join("fresh");
+        init(&fresh, "main").unwrap();
+        assert_eq!(current_branch(&fresh).unwrap().as_deref(), Some("main"));
+        let fresh_repo = Repository::open(&fresh).unwrap();
+        let mut config = fresh_repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        fs::write(fresh.join("a.txt"), "a").unwrap();
+        commit_all(&fresh, "Initial commit").unwrap();
+        let root = fresh_repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(root.parent_count(), 0);
+        assert_eq!(current_branch(&fresh).unwrap().as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn ahead_behind_counts_against_upstream() {
+        let (dir, repo) = temp_repo();
+        let base = commit_file(&repo, "a.txt");
+        let branch = current_branch(&dir).unwrap().unwrap();
+        assert_eq!(ahead_behind(&dir).unwrap(), None);
+
+        // Fake a remote-tracking branch one commit behind the local one.
+        repo.reference("refs/remotes/origin/main", base, true, "test")
+            .unwrap();
+        repo.remote("origin", "https://example.com/repo.git")
+            .unwrap();
+        let mut cfg = repo.config().unwrap();
+        cfg.set_str(&format!("branch.{branch}.remote"), "origin")
+            .unwrap();
+        cfg.set_str(&format!("branch.{branch}.merge"), "refs/heads/main")
+            .unwrap();
+        commit_file(&repo, "b.txt");
+        commit_file(&repo, "c.txt");
+
+        assert_eq!(ahead_behind(&dir).unwrap(), Some((2, 0)));
+    }
+
+    #[test]
+    fn push_then_pull_fast_forwards_through_a_bare_remote() {
+        let (bare_dir, _) = temp_repo();
+        let bare_dir = bare_dir.join("remote.git");
+        Repository::init_bare(&bare_dir).unwrap();
+        let url = bare_dir.to_str().unwrap();
+        let log = |_: String| {};
+
+        let (a_dir, a) = temp_repo();
+        a.remote("origin", url).unwrap();
+        commit_file(&a, "a.txt");
+        push(&a_dir, &log).unwrap();
+
+        let b_dir = TempDir::new("git_ops");
+        let b = Repository::clone(url, &b_dir).unwrap();
+        assert_eq!(pull(&b_dir, &log).unwrap(), PullOutcome::UpToDate);
+
+        commit_file(&a, "b.txt");
+        push(&a_dir, &log).unwrap();
+        assert_eq!(pull(&b_dir, &log).unwrap(), PullOutcome::FastForwarded);
+        assert!(b_dir.join("b.txt").exists());
+
+        // Diverge: both sides commit, b's push must be rejected.
+        commit_file(&a, "c.txt");
+        push(&a_dir, &log).unwrap();
+        commit_file(&b, "d.txt");
+        assert!(matches!(push(&b_dir, &log), Err(GitError::PushRejected(_))));
+        assert!(matches!(pull(&b_dir, &log), Err(GitError::NotFastForward)));
+    }
+
+    #[test]
+    fn unpushed_work_tracks_commits_missing_from_remotes() {
+        let (bare_dir, _) = temp_repo();
+        let bare_dir = bare_dir.join("remote.git");
+        Repository::init_bare(&bare_dir).unwrap();
+        let log = |_: String| {};
+
+        let (dir, repo) = temp_repo();
+        commit_file(&repo, "a.txt");
+        let branch = current_branch(&dir).unwrap().unwrap();
+        let work = unpushed_work(&dir).unwrap();
+        assert!(!work.has_remote);
+        assert_eq!(work.branches, vec![(branch.clone(), 1)]);
+
+        add_remote(&dir, "origin", bare_dir.to_str().unwrap()).unwrap();
+        push_branch(&dir, &branch, &log).unwrap();
+        let work = unpushed_work(&dir).unwrap();
+        assert!(work.has_remote);
+        assert!(work.branches.is_empty());
+
+        commit_file(&repo, "b.txt");
+        commit_file(&repo, "c.txt");
+        assert_eq!(unpushed_work(&dir).unwrap().commits(), 2);
+    }
+
+    #[test]
+    fn diverged_default_branch_is_detected() {
+        let (bare_dir, _) = temp_repo();
+        let bare_dir = bare_dir.join("remote.git");
+        Repository::init_bare(&bare_dir).unwrap();
+        let url = bare_dir.to_str().unwrap();
+        let log = |_: String| {};
+
+        let (a_dir, a) = temp_repo();
+        a.remote("origin", url).unwrap();
+        commit_file(&a, "a.txt");
+        push(&a_dir, &log).unwrap();
+        let b_dir = TempDir::new("git_ops");
+        let b = Repository::clone(url, &b_dir).unwrap();
+        let branch = current_branch(&b_dir).unwrap().unwrap();
+        assert_eq!(default_branch(&b_dir).unwrap(), Some(branch.clone()));
+        assert_eq!(default_branch_divergence(&b_dir).unwrap(), None);
+
+        commit_file(&a, "b.txt");
+        push(&a_dir, &log).unwrap();
+        commit_file(&b, "c.txt");
+        fetch(&b_dir, &log).unwrap();
+        let divergence = default_branch_divergence(&b_dir).unwrap().unwrap();
+        assert_eq!(divergence.branch, branch);
+        assert_eq!(divergence.upstream, format!("origin/{branch}"));
+        assert_eq!((divergence.ahead, divergence.behind), (1, 1));
+    }
+
+    #[test]
+    fn snapshots_restore_commits_and_uncommitted_changes() {
+        let (dir, repo) = temp_repo();
+        let first = commit_file(&repo, "a.txt");
+        fs::write(dir.join("a.txt"), "edited").unwrap();
+        fs::write(dir.join("new.txt"), "new").unwrap();
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("target/big"), "artifact").unwrap();
+
+        let taken = snapshot(&dir, "bulk fix").unwrap().unwrap();
+        assert!(taken.name.ends_with("-bulk-fix"));
+        assert_eq!(status_lines(&dir).unwrap().len(), 3);
+
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        commit_all(&dir, "Risky change").unwrap();
+        assert_ne!(repo.head().unwrap().target(), Some(first));
+        let listed = snapshots(&dir).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].label, "bulk fix");
+        assert_eq!(listed[0].branch, current_branch(&dir).unwrap());
+
+        restore_snapshot(&dir, &taken.name).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(first));
+        assert_eq!(fs::read_to_string(dir.join("a.txt")).unwrap(), "edited");
+        assert!(dir.join("new.txt").is_file());
+        let index = repo.index().unwrap();
+        assert!(index.get_path(Path::new("new.txt"), 0).is_none());
+        assert!(index.get_path(Path::new("target/big"), 0).is_none());
+
+        delete_snapshot(&dir, &taken.name).unwrap();
+        assert!(snapshots(&dir).unwrap().is_empty());
+        assert!(matches!(
+            restore_snapshot(&dir, &taken.name),
+            Err(GitError::NoSuchSnapshot(_))
+        ));
+    }
+
+    #[test]
+    fn missing_remote_is_reported() {
+        let (dir, _repo) = temp_repo();
+        assert!(matches!(
+            remote_url(&dir, "origin"),
+            Err(GitError::NoSuchRemote(_))
+        ));
+    }
+}
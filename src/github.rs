@@ -0,0 +1,247 @@
+//! GitHub REST API client (releases, repositories).
+//!
+//! Only what the release workflow needs: resolve `owner/repo` from a remote URL,
+//! create (or reuse) the release for a tag, list, delete and upload its assets;
+//! and the repository creation of `crate::forge`.
+
+use std::fs;
+use std::path::Path;
+
+use log::info;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::http::{self, HttpError};
+
+const API_ROOT: &str = "https://api.github.com";
+const API_VERSION: &str = "2022-11-28";
+
+#[cfg(test)]
+thread_local! {
+    /// API root of the clients created on this thread, see `use_api_root`.
+    static TEST_API_ROOT: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Send the API calls of the clients later created on this thread to `root`
+/// (a `http::FakeServer`).
+#[cfg(test)]
+pub fn use_api_root(root: &str) {
+    TEST_API_ROOT.with(|r| *r.borrow_mut() = Some(root.to_string()));
+}
+
+fn api_root() -> String {
+    #[cfg(test)]
+    if let Some(root) = TEST_API_ROOT.with(|r| r.borrow().clone()) {
+        return root;
+    }
+    API_ROOT.to_string()
+}
+
+/// `owner/repo` pair identifying a GitHub repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoSlug {
+    pub owner: String,
+    pub repo: String,
+}
+
+impl RepoSlug {
+    /// Parse an SSH (`git@github.com:o/r.git`, `ssh://git@github.com/o/r`) or HTTPS remote URL.
+    pub fn from_remote_url(url: &str) -> Option<Self> {
+        let rest = url
+            .strip_prefix("git@github.com:")
+            .or_else(|| url.strip_prefix("ssh://git@github.com/"))
+            .or_else(|| url.strip_prefix("https://github.com/"))
+            .or_else(|| url.strip_prefix("http://github.com/"))?;
+        let rest = rest.trim_end_matches('/');
+        let rest = rest.strip_suffix(".git").unwrap_or(rest);
+        let (owner, repo) = rest.split_once('/')?;
+        if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+            return None;
+        }
+        Some(Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+}
+
+/// Subset of the GitHub release object.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub id: u64,
+    pub html_url: String,
+    pub upload_url: String,
+}
+
+/// Subset of the GitHub release asset object.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Asset {
+    pub id: u64,
+    pub name: String,
+}
+
+/// Subset of the GitHub repository object.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Repository {
+    pub html_url: String,
+    pub ssh_url: String,
+    pub clone_url: String,
+}
+
+/// Authenticated API client.
+pub struct GitHubClient {
+    token: String,
+    api_root: String,
+}
+
+impl GitHubClient {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            api_root: api_root(),
+        }
+    }
+
+    /// Create the release for `tag`, or return the existing one if it already exists.
+    pub fn create_or_get_release(
+        &self,
+        slug: &RepoSlug,
+        tag: &str,
+        notes: &str,
+    ) -> Result<Release, HttpError> {
+        let url = format!(
+            "{}/repos/{}/{}/releases",
+            self.api_root, slug.owner, slug.repo
+        );
+        let body = json!({ "tag_name": tag, "name": tag, "body": notes });
+        let created = self
+            .authorized(http::agent().post(&url))
+            .send_json(body)
+            .and_then(|mut r| r.body_mut().read_json::<Release>());
+
+        match created {
+            Ok(release) => {
+                info!("Created GitHub release {} for {tag}", release.id);
+                Ok(release)
+            }
+            // 422: a release for this tag already exists.
+            Err(ureq::Error::StatusCode(422)) => {
+                let url = format!(
+                    "{}/repos/{}/{}/releases/tags/{tag}",
+                    self.api_root, slug.owner, slug.repo
+                );
+                let release = self
+                    .authorized(http::agent().get(&url))
+                    .call()?
+                    .body_mut()
+                    .read_json::<Release>()?;
+                info!("Reusing existing GitHub release {} for {tag}", release.id);
+                Ok(release)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Create the repository `name` of the authenticated user.
+    pub fn create_repository(
+        &self,
+        name: &str,
+        description: &str,
+        private: bool,
+    ) -> Result<Repository, HttpError> {
+        let body = json!({ "name": name, "description": description, "private": private });
+        let repository = self
+            .authorized(http::agent().post(&format!("{}/user/repos", self.api_root)))
+            .send_json(body)?
+            .body_mut()
+            .read_json::<Repository>()?;
+        info!("Created GitHub repository {}", repository.html_url);
+        Ok(repository)
+    }
+
+    /// Assets already uploaded to `release` (the first 100, more than a release has).
+    pub fn list_assets(&self, slug: &RepoSlug, release: &Release) -> Result<Vec<Asset>, HttpError> {
+        let url = format!(
+            "{}/repos/{}/{}/releases/{}/assets",
+            self.api_root, slug.owner, slug.repo, release.id
+        );
+        Ok(self
+            .authorized(http::agent().get(&url))
+            .query("per_page", "100")
+            .call()?
+            .body_mut()
+            .read_json::<Vec<Asset>>()?)
+    }
+
+    /// Delete `asset` from its release.
+    pub fn delete_asset(&self, slug: &RepoSlug, asset: &Asset) -> Result<(), HttpError> {
+        let url = format!(
+            "{}/repos/{}/{}/releases/assets/{}",
+            self.api_root, slug.owner, slug.repo, asset.id
+        );
+        self.authorized(http::agent().delete(&url)).call()?;
+        info!("Deleted release asset {}", asset.name);
+        Ok(())
+    }
+
+    /// Upload `file` as a release asset named `asset_name(file)`.
+    pub fn upload_asset(&self, release: &Release, file: &Path) -> Result<(), HttpError> {
+        let name = asset_name(file);
+        let data = fs::read(file).map_err(|e| HttpError::Transport(e.to_string()))?;
+        // `upload_url` is a URI template: ".../assets{?name,label}".
+        let base = release
+            .upload_url
+            .split('{')
+            .next()
+            .unwrap_or(&release.upload_url);
+
+        self.authorized(http::agent().post(base))
+            .query("name", &name)
+            .header("Content-Type", "application/octet-stream")
+            .send(&data[..])?;
+        info!("Uploaded release asset {name}");
+        Ok(())
+    }
+
+    fn authorized<B>(&self, req: ureq::RequestBuilder<B>) -> ureq::RequestBuilder<B> {
+        req.header("Authorization", &format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", API_VERSION)
+    }
+}
+
+/// Name of the release asset of `file`: its file name.
+pub fn asset_name(file: &Path) -> String {
+    file.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ssh_and_https_remotes() {
+        let expected = Some(RepoSlug {
+            owner: "sub07".into(),
+            repo: "rustm".into(),
+        });
+        assert_eq!(
+            RepoSlug::from_remote_url("git@github.com:sub07/rustm.git"),
+            expected
+        );
+        assert_eq!(
+            RepoSlug::from_remote_url("https://github.com/sub07/rustm"),
+            expected
+        );
+        assert_eq!(
+            RepoSlug::from_remote_url("ssh://git@github.com/sub07/rustm.git"),
+            expected
+        );
+        assert_eq!(
+            RepoSlug::from_remote_url("https://gitlab.com/sub07/rustm"),
+            None
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! GitLab REST API client (repositories).
+//!
+//! Only what `crate::forge` needs: create a project of the authenticated user,
+//! on gitlab.com or a self-managed instance.
+
+use log::info;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::http::{self, HttpError};
+
+/// Subset of the GitLab project object.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Project {
+    pub web_url: String,
+    pub ssh_url_to_repo: String,
+    pub http_url_to_repo: String,
+}
+
+/// Authenticated API client of the instance at `base_url` (`https://gitlab.com`).
+pub struct GitLabClient {
+    base_url: String,
+    token: String,
+}
+
+impl GitLabClient {
+    pub fn new(base_url: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            token: token.into(),
+        }
+    }
+
+    /// Create the project `name` in the namespace of the authenticated user.
+    pub fn create_project(
+        &self,
+        name: &str,
+        description: &str,
+        private: bool,
+    ) -> Result<Project, HttpError> {
+        let url = format!("{}/api/v4/projects", self.base_url.trim_end_matches('/'));
+        let visibility = if private { "private" } else { "public" };
+        let body = json!({
+            "name": name,
+            "path": name,
+            "description": description,
+            "visibility": visibility,
+        });
+        let project = http::agent()
+            .post(&url)
+            .header("PRIVATE-TOKEN", &self.token)
+            .send_json(body)?
+            .body_mut()
+            .read_json::<Project>()?;
+        info!("Created GitLab project {}", project.web_url);
+        Ok(project)
+    }
+}
@@ -0,0 +1,198 @@
+//! Syntax highlighting for file previews.
+//!
+//! syntect parses the file into scopes; scopes are then mapped onto the
+//! colors of the current theme palette (comments dimmed, keywords in the
+//! accent color, ...) rather than onto a syntect theme, so highlighted text
+//! matches the rest of the UI. Syntaxes are syntect's bundled set plus a small
+//! TOML grammar (`syntaxes/TOML.sublime-syntax`), which syntect lacks. Files of
+//! unknown type are shown as plain text.
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use cursive::theme::{Effect, PaletteColor, Style};
+use cursive::utils::markup::StyledString;
+use log::warn;
+use syntect::parsing::{ParseState, ScopeStack, SyntaxDefinition, SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+const TOML_SYNTAX: &str = include_str!("syntaxes/TOML.sublime-syntax");
+
+static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+
+/// Highlighting category of a piece of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Plain,
+    Comment,
+    String,
+    Constant,
+    Keyword,
+    /// Type / function names, table headers, headings, keys.
+    Name,
+}
+
+impl Class {
+    /// Most specific class of a scope stack (innermost scope first).
+    fn of(stack: &ScopeStack) -> Self {
+        for scope in stack.as_slice().iter().rev() {
+            let name = scope.build_string();
+            let class = match name.split('.').next().unwrap_or_default() {
+                "comment" => Self::Comment,
+                "string" => Self::String,
+                "constant" => Self::Constant,
+                "keyword" | "storage" => Self::Keyword,
+                "entity" | "support" | "markup" if !name.starts_with("markup.raw") => Self::Name,
+                "variable" if name.starts_with("variable.other.key") => Self::Name,
+                _ => continue,
+            };
+            return class;
+        }
+        Self::Plain
+    }
+
+    fn style(self) -> Style {
+        match self {
+            Self::Plain => Style::from(PaletteColor::Primary),
+            Self::Comment => Style::from(PaletteColor::Tertiary).combine(Effect::Italic),
+            Self::String => Style::from(PaletteColor::Secondary),
+            Self::Constant => Style::from(PaletteColor::HighlightInactive),
+            Self::Keyword => Style::from(PaletteColor::Highlight).combine(Effect::Bold),
+            Self::Name => Style::from(PaletteColor::TitlePrimary).combine(Effect::Bold),
+        }
+    }
+}
+
+/// The first `max_lines` lines of `content`, highlighted for the type of `path`.
+pub fn highlight_lines(path: &Path, content: &str, max_lines: usize) -> Vec<StyledString> {
+    classify(path, content, max_lines)
+        .into_iter()
+        .map(|segments| {
+            let mut line = StyledString::new();
+            for (text, class) in segments {
+                line.append_styled(text, class.style());
+            }
+            line
+        })
+        .collect()
+}
+
+/// Split the first `max_lines` lines into classified segments (line endings dropped).
+fn classify(path: &Path, content: &str, max_lines: usize) -> Vec<Vec<(String, Class)>> {
+    let syntaxes = syntaxes();
+    let Some(syntax) = find_syntax(syntaxes, path, content) else {
+        return content
+            .lines()
+            .take(max_lines)
+            .map(|l| vec![(l.to_string(), Class::Plain)])
+            .collect();
+    };
+
+    let mut state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut out = Vec::new();
+    for line in LinesWithEndings::from(content).take(max_lines) {
+        let line_text = line.trim_end_matches(['\n', '\r']);
+        let ops = match state.parse_line(line, syntaxes) {
+            Ok(ops) => ops,
+            Err(e) => {
+                // Keep the rest readable even if the grammar chokes.
+                warn!("Highlighting {} failed: {e}", path.display());
+                out.push(vec![(line_text.to_string(), Class::Plain)]);
+                continue;
+            }
+        };
+        let mut segments = Vec::new();
+        let mut start = 0;
+        for (pos, op) in ops {
+            let end = pos.min(line_text.len());
+            if end > start {
+                segments.push((line_text[start..end].to_string(), Class::of(&stack)));
+                start = end;
+            }
+            if stack.apply(&op).is_err() {
+                break;
+            }
+        }
+        if start < line_text.len() {
+            segments.push((line_text[start..].to_string(), Class::of(&stack)));
+        }
+        out.push(segments);
+    }
+    out
+}
+
+fn find_syntax<'a>(
+    syntaxes: &'a SyntaxSet,
+    path: &Path,
+    content: &str,
+) -> Option<&'a SyntaxReference> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .and_then(|e| syntaxes.find_syntax_by_extension(e))
+        .or_else(|| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| syntaxes.find_syntax_by_extension(n))
+        })
+        .or_else(|| syntaxes.find_syntax_by_first_line(content.lines().next()?))
+}
+
+fn syntaxes() -> &'static SyntaxSet {
+    SYNTAXES.get_or_init(|| {
+        let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+        match SyntaxDefinition::load_from_str(TOML_SYNTAX, true, Some("TOML")) {
+            Ok(toml) => builder.add(toml),
+            Err(e) => warn!("Cannot load the TOML syntax: {e}"),
+        }
+        builder.build()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class_of(lines: &[Vec<(String, Class)>], line: usize, text: &str) -> Class {
+        lines[line]
+            .iter()
+            .find(|(t, _)| t.contains(text))
+            .map(|(_, c)| *c)
+            .unwrap_or_else(|| panic!("'{text}' not found in line {line}: {lines:?}"))
+    }
+
+    #[test]
+    fn classifies_rust_and_toml() {
+        let rust = classify(
+            Path::new("main.rs"),
+            "// entry\nfn main() { let s = \"hi\"; }\n",
+            10,
+        );
+        assert_eq!(class_of(&rust, 0, "entry"), Class::Comment);
+        assert_eq!(class_of(&rust, 1, "fn"), Class::Keyword);
+        assert_eq!(class_of(&rust, 1, "hi"), Class::String);
+
+        let toml = classify(
+            Path::new("Cargo.toml"),
+            "[package]\nname = \"demo\" # the name\nversion = 3\n",
+            2,
+        );
+        assert_eq!(toml.len(), 2);
+        assert_eq!(class_of(&toml, 0, "package"), Class::Name);
+        assert_eq!(class_of(&toml, 1, "name"), Class::Name);
+        assert_eq!(class_of(&toml, 1, "demo"), Class::String);
+        assert_eq!(class_of(&toml, 1, "the name"), Class::Comment);
+    }
+
+    #[test]
+    fn unknown_files_are_plain() {
+        let lines = classify(Path::new("notes.unknownext"), "a\nb\n", 10);
+        assert_eq!(
+            lines,
+            vec![
+                vec![("a".to_string(), Class::Plain)],
+                vec![("b".to_string(), Class::Plain)]
+            ]
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Minimal blocking HTTP helpers.
+//!
+//! Wraps a shared `ureq` agent configured with rustm's user agent and sane
+//! timeouts, and maps failures to `HttpError` so callers (GitHub API, ...) only
+//! deal with one error type. Calls are blocking: run them from a background
+//! thread (see `ui::progress`), never on the cursive event loop.
+
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use ureq::Agent;
+
+/// User agent sent with every request (required by the GitHub API).
+pub const USER_AGENT: &str = concat!("rustm/", env!("CARGO_PKG_VERSION"));
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Errors returned by HTTP helpers.
+#[derive(Debug)]
+pub enum HttpError {
+    /// Server answered with a non-success status.
+    Status(u16),
+    /// Connection, TLS or protocol failure.
+    Transport(String),
+    /// Response body could not be decoded.
+    Decode(String),
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status(code) => write!(f, "Server returned HTTP {code}"),
+            Self::Transport(e) => write!(f, "Network error: {e}"),
+            Self::Decode(e) => write!(f, "Unexpected response: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+impl From<ureq::Error> for HttpError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::StatusCode(code) => Self::Status(code),
+            ureq::Error::Json(e) => Self::Decode(e.to_string()),
+            other => Self::Transport(other.to_string()),
+        }
+    }
+}
+
+/// Shared agent (connection pooling across calls).
+pub fn agent() -> &'static Agent {
+    static AGENT: OnceLock<Agent> = OnceLock::new();
+    AGENT.get_or_init(|| {
+        Agent::config_builder()
+            .user_agent(USER_AGENT)
+            .timeout_global(Some(TIMEOUT))
+            .build()
+            .into()
+    })
+}
+
+/// Local HTTP server answering each request with the next queued `(status,
+/// body)` JSON reply, one request per connection. `$SERVER` in a body is
+/// replaced by the URL of the server.
+#[cfg(test)]
+pub struct FakeServer {
+    /// `http://127.0.0.1:<port>`.
+    pub url: String,
+    handle: std::thread::JoinHandle<Vec<String>>,
+}
+
+#[cfg(test)]
+impl FakeServer {
+    pub fn start(replies: Vec<(u16, String)>) -> Self {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn({
+            let url = url.clone();
+            move || {
+                let mut requests = Vec::new();
+                for (status, body) in replies {
+                    let body = body.replace("$SERVER", &url);
+                    let (stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream);
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    let mut length = 0;
+                    loop {
+                        let mut header = String::new();
+                        reader.read_line(&mut header).unwrap();
+                        let header = header.trim_end();
+                        if header.is_empty() {
+                            break;
+                        }
+                        if let Some((name, value)) = header.split_once(':')
+                            && name.eq_ignore_ascii_case("content-length")
+                        {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                    let mut content = vec![0; length];
+                    reader.read_exact(&mut content).unwrap();
+                    // "POST /path?query HTTP/1.1" without the version.
+                    let request = request_line.rsplit_once(' ').unwrap().0;
+                    requests.push(request.to_string());
+                    write!(
+                        reader.get_mut(),
+                        "HTTP/1.1 {status} Fake\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .unwrap();
+                }
+                requests
+            }
+        });
+        Self { url, handle }
+    }
+
+    /// Wait for every reply to be sent; returns the requests, `METHOD /path?query`.
+    pub fn requests(self) -> Vec<String> {
+        self.handle.join().unwrap()
+    }
+}
@@ -0,0 +1,247 @@
+//! Keyboard shortcuts.
+//!
+//! Maps global actions to keys. Defaults can be overridden by `keymap.yaml`,
+//! next to `config.yaml`, a map from action name to key:
+//!
+//! ```yaml
+//! quit: ctrl+q
+//! refresh: r
+//! ```
+//!
+//! Keys are a single character (`q`, `/`), `space`, `esc`, `f1` to `f12`, or
+//! `ctrl+<letter>` / `alt+<letter>`. Unknown actions and unparsable keys are
+//! logged and ignored, keeping the default. A missing file means defaults.
+//!
+//! The keymap is loaded once, on first access (`get`).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use cursive::event::{Event, Key};
+use log::warn;
+
+use crate::config::Config;
+
+/// File name of the keymap, inside the configuration directory.
+const KEYMAP_FILE: &str = "keymap.yaml";
+
+static KEYMAP: OnceLock<Keymap> = OnceLock::new();
+
+/// An action reachable with a shortcut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    Quit,
+    Create,
+    List,
+    Refresh,
+    RefreshStatus,
+    Search,
+    OpenEditor,
+    Mark,
+}
+
+impl Action {
+    pub const ALL: [Self; 8] = [
+        Self::Quit,
+        Self::Create,
+        Self::List,
+        Self::Refresh,
+        Self::RefreshStatus,
+        Self::Search,
+        Self::OpenEditor,
+        Self::Mark,
+    ];
+
+    /// Name used in `keymap.yaml`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::Create => "create",
+            Self::List => "list",
+            Self::Refresh => "refresh",
+            Self::RefreshStatus => "refresh_status",
+            Self::Search => "search",
+            Self::OpenEditor => "open_editor",
+            Self::Mark => "mark",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Quit => "Quit rustm (anywhere no text is being typed)",
+            Self::Create => "Create a new project (main menu)",
+            Self::List => "List projects (main menu)",
+            Self::Refresh => "Rescan the projects (project list)",
+            Self::RefreshStatus => "Rescan the git status of the listed projects (project list)",
+            Self::Search => "Find in the selected project (project list)",
+            Self::OpenEditor => "Open the selected project in the editor (project list)",
+            Self::Mark => "Mark or unmark the selected project for a batch action (project list)",
+        }
+    }
+
+    fn default_key(self) -> &'static str {
+        match self {
+            Self::Quit => "q",
+            Self::Create => "n",
+            Self::List => "l",
+            Self::Refresh => "f5",
+            Self::RefreshStatus => "g",
+            Self::Search => "/",
+            Self::OpenEditor => "e",
+            Self::Mark => "space",
+        }
+    }
+}
+
+/// Key of every action.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    keys: BTreeMap<Action, String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            keys: Action::ALL
+                .iter()
+                .map(|a| (*a, a.default_key().to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Key bound to `action`, as written in `keymap.yaml`.
+    pub fn key(&self, action: Action) -> &str {
+        self.keys
+            .get(&action)
+            .map_or_else(|| action.default_key(), String::as_str)
+    }
+
+    /// Event triggering `action`.
+    pub fn event(&self, action: Action) -> Event {
+        parse_key(self.key(action)).unwrap_or(Event::Unknown(Vec::new()))
+    }
+
+    /// Defaults overridden by the `action: key` entries of `yaml`.
+    fn from_yaml(yaml: &str) -> Self {
+        let mut keymap = Self::default();
+        let entries = match serde_norway::from_str::<Option<BTreeMap<String, String>>>(yaml) {
+            Ok(Some(entries)) => entries,
+            Ok(None) => return keymap,
+            Err(e) => {
+                warn!("Ignoring unreadable keymap: {e}");
+                return keymap;
+            }
+        };
+        for (name, key) in entries {
+            let Some(action) = Action::ALL.into_iter().find(|a| a.name() == name) else {
+                warn!("Ignoring unknown keymap action '{name}'");
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            if parse_key(&key).is_none() {
+                warn!("Ignoring unknown key '{key}' for '{name}'");
+                continue;
+            }
+            keymap.keys.insert(action, key);
+        }
+        keymap
+    }
+
+    fn load_from(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(yaml) => Self::from_yaml(&yaml),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                warn!("Cannot read keymap {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+}
+
+/// The keymap, loaded from `keymap.yaml` on first access.
+pub fn get() -> &'static Keymap {
+    KEYMAP.get_or_init(|| Keymap::load_from(&file_path()))
+}
+
+/// `<config dir>/keymap.yaml`.
+pub fn file_path() -> PathBuf {
+    let cfg_file = Config::file_path();
+    cfg_file
+        .parent()
+        .map_or_else(Config::file_path, Path::to_path_buf)
+        .join(KEYMAP_FILE)
+}
+
+/// Event of a key written like `q`, `space`, `f5`, `ctrl+n` or `alt+x`.
+fn parse_key(key: &str) -> Option<Event> {
+    let single = |s: &str| {
+        let mut chars = s.chars();
+        chars.next().filter(|_| chars.next().is_none())
+    };
+    if let Some(rest) = key.strip_prefix("ctrl+") {
+        return single(rest)
+            .filter(char::is_ascii_lowercase)
+            .map(Event::CtrlChar);
+    }
+    if let Some(rest) = key.strip_prefix("alt+") {
+        return single(rest)
+            .filter(char::is_ascii_lowercase)
+            .map(Event::AltChar);
+    }
+    let function = |n: u8| {
+        [
+            Key::F1,
+            Key::F2,
+            Key::F3,
+            Key::F4,
+            Key::F5,
+            Key::F6,
+            Key::F7,
+            Key::F8,
+            Key::F9,
+            Key::F10,
+            Key::F11,
+            Key::F12,
+        ]
+        .get(usize::from(n).checked_sub(1)?)
+        .copied()
+    };
+    match key {
+        "space" => Some(Event::Char(' ')),
+        "esc" => Some(Event::Key(Key::Esc)),
+        _ => match key.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            Some(n) => function(n).map(Event::Key),
+            None => single(key).filter(|c| !c.is_whitespace()).map(Event::Char),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keys_and_keeps_defaults_for_bad_entries() {
+        assert_eq!(parse_key("q"), Some(Event::Char('q')));
+        assert_eq!(parse_key("ctrl+n"), Some(Event::CtrlChar('n')));
+        assert_eq!(parse_key("f12"), Some(Event::Key(Key::F12)));
+        assert_eq!(parse_key("f13"), None);
+        assert_eq!(parse_key("ctrl+"), None);
+        assert_eq!(parse_key("enter"), None);
+
+        let keymap = Keymap::from_yaml("quit: Ctrl+Q\nrefresh: nope\nteleport: t\n");
+        assert_eq!(keymap.key(Action::Quit), "ctrl+q");
+        assert_eq!(keymap.event(Action::Quit), Event::CtrlChar('q'));
+        assert_eq!(keymap.key(Action::Refresh), "f5");
+        assert_eq!(keymap.key(Action::Search), "/");
+        assert_eq!(Keymap::from_yaml("").key(Action::List), "l");
+        assert_eq!(keymap.event(Action::Mark), Event::Char(' '));
+        assert_eq!(keymap.event(Action::RefreshStatus), Event::Char('g'));
+    }
+}
@@ -1,8 +1,10 @@
+use std::cell::RefCell;
 use std::fs::{self, OpenOptions};
+use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
-use std::sync::Once;
+use std::sync::{Once, PoisonError, RwLock};
 
-use log::{LevelFilter, info};
+use log::{LevelFilter, Record, info};
 use simplelog::{Config as LogConfig, ConfigBuilder, WriteLogger};
 
 use crate::config::Config; // For deriving the config directory path.
@@ -10,6 +12,14 @@ use crate::config::Config; // For deriving the config directory path.
 // One–time initialization guard.
 static INIT: Once = Once::new();
 
+/// `log_levels` overrides: target prefix (`git2`, `rustm::project`) and its level.
+static LEVELS: RwLock<Vec<(String, LevelFilter)>> = RwLock::new(Vec::new());
+
+thread_local! {
+    /// Key=value context appended to the records of this thread, outermost first.
+    static CONTEXT: RefCell<Vec<(&'static str, String)>> = const { RefCell::new(Vec::new()) };
+}
+
 /// Initialize the application logging subsystem.
 ///
 /// Spec (updated):
@@ -43,23 +53,36 @@ pub fn init_logging() -> Result<bool, InitLogError> {
     }
 }
 
-/// Filtering logger that excludes records whose target starts with `cursive_core`.
+/// Logger applying the level of each target (see `level_for`) and appending the context.
 struct FilteringLogger {
     inner: Box<dyn log::Log>,
 }
 
 impl log::Log for FilteringLogger {
     fn enabled(&self, metadata: &log::Metadata) -> bool {
-        if metadata.target().starts_with("cursive_core") {
-            return false;
-        }
-
-        self.inner.enabled(metadata)
+        let levels = LEVELS.read().unwrap_or_else(PoisonError::into_inner);
+        metadata.level() <= level_for(metadata.target(), &levels)
     }
     fn log(&self, record: &log::Record) {
-        if self.enabled(record.metadata()) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let context = CONTEXT
+            .try_with(|c| format_context(&c.borrow()))
+            .unwrap_or_default();
+        if context.is_empty() {
             self.inner.log(record);
+            return;
         }
+        self.inner.log(
+            &Record::builder()
+                .metadata(record.metadata().clone())
+                .args(format_args!("{} | {context}", record.args()))
+                .module_path(record.module_path())
+                .file(record.file())
+                .line(record.line())
+                .build(),
+        );
     }
     fn flush(&self) {
         self.inner.flush();
@@ -89,13 +112,8 @@ fn real_init() -> Result<(), InitLogError> {
     }
     let log_cfg: LogConfig = builder.build();
 
-    let level = if cfg!(debug_assertions) {
-        LevelFilter::Trace
-    } else {
-        LevelFilter::Info
-    };
-
-    let inner = WriteLogger::new(level, log_cfg, file);
+    // Levels are applied by `FilteringLogger`.
+    let inner = WriteLogger::new(LevelFilter::Trace, log_cfg, file);
 
     let inner: Box<dyn log::Log> = inner;
     let filtering = FilteringLogger { inner };
@@ -103,13 +121,137 @@ fn real_init() -> Result<(), InitLogError> {
     log::set_boxed_logger(Box::new(filtering))
         .map_err(|e| InitLogError::SetLogger(e.to_string()))?;
 
-    log::set_max_level(level);
+    log::set_max_level(default_level());
 
     info!("Logger initialized at {}", log_path.display());
 
     Ok(())
 }
 
+/// Level of targets without override: everything in debug builds, `INFO` and above in release.
+fn default_level() -> LevelFilter {
+    if cfg!(debug_assertions) {
+        LevelFilter::Trace
+    } else {
+        LevelFilter::Info
+    }
+}
+
+/// Apply the `log_levels` of the configuration, replacing the previous ones.
+pub fn set_levels(levels: Vec<(String, LevelFilter)>) {
+    let max = levels
+        .iter()
+        .map(|(_, level)| *level)
+        .fold(default_level(), Ord::max);
+    *LEVELS.write().unwrap_or_else(PoisonError::into_inner) = levels;
+    log::set_max_level(max);
+}
+
+/// Level of `target`: the override with the longest matching prefix, else the default.
+///
+/// A prefix matches whole path segments (`rustm::project` matches
+/// `rustm::project::list`, not `rustm::projects`). `cursive_core` is off
+/// unless overridden: it logs every event.
+fn level_for(target: &str, levels: &[(String, LevelFilter)]) -> LevelFilter {
+    levels
+        .iter()
+        .filter(|(prefix, _)| {
+            target
+                .strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+        })
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map_or_else(
+            || {
+                if target.starts_with("cursive_core") {
+                    LevelFilter::Off
+                } else {
+                    default_level()
+                }
+            },
+            |(_, level)| *level,
+        )
+}
+
+/// Context field added by `scope`, removed when dropped.
+#[must_use = "the field is removed when the scope is dropped"]
+pub struct Scope {
+    fields: usize,
+    // Fields are per thread: the guard must be dropped on the thread that created it.
+    _thread: PhantomData<*const ()>,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let _ = CONTEXT.try_with(|c| {
+            let mut context = c.borrow_mut();
+            let len = context.len().saturating_sub(self.fields);
+            context.truncate(len);
+        });
+    }
+}
+
+/// Append `key=value` to the records logged by this thread until the guard is dropped.
+pub fn scope(key: &'static str, value: impl Into<String>) -> Scope {
+    restore(vec![(key, value.into())])
+}
+
+/// Context of this thread, to hand to another thread with `restore`.
+pub fn context() -> Vec<(&'static str, String)> {
+    CONTEXT.try_with(|c| c.borrow().clone()).unwrap_or_default()
+}
+
+/// Add the `fields` of another thread's `context` to this thread's.
+pub fn restore(fields: Vec<(&'static str, String)>) -> Scope {
+    let count = fields.len();
+    let _ = CONTEXT.try_with(|c| c.borrow_mut().extend(fields));
+    Scope {
+        fields: count,
+        _thread: PhantomData,
+    }
+}
+
+/// `key=value key="value with spaces"`; a key set twice keeps its innermost value.
+fn format_context(fields: &[(&'static str, String)]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for (i, (key, value)) in fields.iter().enumerate() {
+        if fields[i + 1..].iter().any(|(k, _)| k == key) {
+            continue;
+        }
+        if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') {
+            parts.push(format!("{key}={value:?}"));
+        } else {
+            parts.push(format!("{key}={value}"));
+        }
+    }
+    parts.join(" ")
+}
+
+/// Last `lines` lines of `rustm.log` (fewer if the file is shorter, none if it cannot be read).
+pub fn tail(lines: usize) -> Vec<String> {
+    let content = fs::read_to_string(log_file_path()).unwrap_or_default();
+    last_lines(&content, lines, |_| true)
+}
+
+/// Like `tail`, without the `DEBUG` and `TRACE` records of debug builds.
+pub fn relevant_tail(lines: usize) -> Vec<String> {
+    let content = fs::read_to_string(log_file_path()).unwrap_or_default();
+    last_lines(&content, lines, is_relevant)
+}
+
+/// Records are `HH:MM:SS [LEVEL] message`; continuation lines are kept.
+fn is_relevant(line: &str) -> bool {
+    !matches!(line.split_whitespace().nth(1), Some("[DEBUG]" | "[TRACE]"))
+}
+
+fn last_lines(content: &str, lines: usize, keep: impl Fn(&str) -> bool) -> Vec<String> {
+    let kept: Vec<&str> = content.lines().filter(|l| keep(l)).collect();
+    kept[kept.len().saturating_sub(lines)..]
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
 /// Determine the log file path: same directory as `config.yaml`.
 fn log_file_path() -> PathBuf {
     let cfg_file = Config::file_path();
@@ -153,4 +295,59 @@ mod tests {
         trace!("trace after init");
         error!("error after init");
     }
+
+    #[test]
+    fn levels_follow_the_longest_prefix() {
+        let levels = vec![
+            ("rustm".to_string(), LevelFilter::Warn),
+            ("rustm::project".to_string(), LevelFilter::Trace),
+            ("git2".to_string(), LevelFilter::Error),
+        ];
+        assert_eq!(
+            level_for("rustm::project::list", &levels),
+            LevelFilter::Trace
+        );
+        assert_eq!(level_for("rustm::projects", &levels), LevelFilter::Warn);
+        assert_eq!(level_for("git2", &levels), LevelFilter::Error);
+        assert_eq!(level_for("ureq::tls", &levels), default_level());
+        assert_eq!(
+            level_for("cursive_core::cursive", &levels),
+            LevelFilter::Off
+        );
+    }
+
+    #[test]
+    fn formats_the_context() {
+        let fields = vec![
+            ("operation", "Feature matrix: demo".to_string()),
+            ("project", "outer".to_string()),
+            ("project", "demo".to_string()),
+        ];
+        assert_eq!(
+            format_context(&fields),
+            r#"operation="Feature matrix: demo" project=demo"#
+        );
+
+        let outer = scope("operation", "build");
+        {
+            let _inner = restore(vec![("project", "demo".into())]);
+            assert_eq!(context().len(), 2);
+        }
+        assert_eq!(context(), [("operation", "build".to_string())]);
+        drop(outer);
+        assert!(context().is_empty());
+    }
+
+    #[test]
+    fn relevant_lines_skip_debug_and_trace() {
+        let content = "10:00:01 [INFO] Scanning\n10:00:02 [DEBUG] Entry a\n10:00:03 [TRACE] Entry b\n10:00:04 [ERROR] Commit failed\n";
+        assert_eq!(
+            last_lines(content, 5, is_relevant),
+            ["10:00:01 [INFO] Scanning", "10:00:04 [ERROR] Commit failed"]
+        );
+        assert_eq!(
+            last_lines(content, 1, |_| true),
+            ["10:00:04 [ERROR] Commit failed"]
+        );
+    }
 }
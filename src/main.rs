@@ -3,35 +3,290 @@
 //! Current state:
 //! - Initializes logging ASAP.
 //! - Loads configuration (see `config` module).
+//! - Runs command-line subcommands (see `cli` module) without starting the TUI.
 //! - If initial setup is required, shows a placeholder fullscreen prompt
 //!   allowing the user to input the required fields (very minimal for now).
 //! - After configuration is available, shows a placeholder main menu in a
-//!   cursive TUI with two global actions:
+//!   cursive TUI with global actions:
 //!     * Create new project (placeholder flow)
 //!     * List projects (placeholder list dialog)
+//!     * Reopen last project (in the editor)
+//!     * Recent activity (latest commits across all projects)
+//!     * Playground (run a Rust snippet)
+//!     * Help (keybindings, report a problem with rustm itself)
+//! - Installs the keyboard shortcuts of `keymap` (quit anywhere, main menu
+//!   entries).
 //!
 //! This is intentionally skeletal; real feature wiring (nicer UI, error
 //! surfaces, navigation) can be layered atop these scaffolds.
 
+mod bug_report;
+
+mod cargo_ops;
+
+mod cli;
+
 mod config;
 
+mod config_watch;
+
+mod crates_io;
+
+mod desktop;
+
+mod dir_walk;
+
+mod editor;
+
+mod events;
+
+mod forge;
+
+mod format;
+
+mod git_ops;
+
+mod github;
+
+mod gitlab;
+
+mod highlight;
+
+mod http;
+
+mod keymap;
+
 mod logging;
 
+mod metadata;
+
+mod metrics;
+
+mod playground;
+
+mod process;
+
+mod projects_watch;
+
+#[cfg(test)]
+mod temp_dir;
+
+mod template;
+
 mod theme;
+
+mod transaction;
+
+mod wsl;
 mod project {
+    pub mod activity;
+
+    pub mod archive;
+
+    pub mod audit;
+
+    pub mod batch;
+
+    pub mod changelog;
+
+    pub mod cherry_pick;
+
+    pub mod clean;
+
+    pub mod clippy;
+
+    pub mod contributors;
+
+    pub mod convert;
 
     pub mod create;
 
+    pub mod deps;
+
+    pub mod disk_usage;
+
+    pub mod diverge;
+
+    pub mod env_report;
+
+    pub mod feature_matrix;
+
+    pub mod filter;
+
+    pub mod graph;
+
+    pub mod hoist;
+
+    pub mod hygiene;
+
+    pub mod kind;
+
+    pub mod large_files;
+
+    pub mod license;
+
+    pub mod lint_fix;
+
+    pub mod lint_policy;
+
     pub mod list;
+
+    pub mod manage;
+
+    pub mod manifest;
+
+    pub mod minimal_versions;
+
+    pub mod no_std;
+
+    pub mod notes;
+
+    pub mod package_metadata;
+
+    pub mod packaging;
+
+    pub mod patch;
+
+    pub mod prepare;
+
+    pub mod presets;
+
+    pub mod publish;
+
+    pub mod rebase;
+
+    pub mod release;
+
+    pub mod remote_check;
+
+    pub mod rollup;
+
+    pub mod sandbox;
+
+    pub mod scaffold;
+
+    pub mod search;
+
+    pub mod secrets;
+
+    pub mod staging;
+
+    pub mod tags;
+
+    pub mod templates;
+
+    pub mod toolchain;
+
+    pub mod tooling;
+
+    pub mod tree;
+
+    pub mod vanished;
+
+    pub mod workspace;
+}
+
+mod ui {
+    pub mod activity;
+
+    pub mod archive;
+
+    pub mod audit;
+
+    pub mod batch;
+
+    pub mod branch_picker;
+
+    pub mod cherry_pick;
+
+    pub mod clippy;
+
+    pub mod commit;
+
+    pub mod contributors;
+
+    pub mod dependencies;
+
+    pub mod disk_usage;
+
+    pub mod diverge;
+
+    pub mod error_view;
+
+    pub mod feature_matrix;
+
+    pub mod file_tree;
+
+    pub mod git_actions;
+
+    pub mod git_log;
+
+    pub mod help;
+
+    pub mod hoist;
+
+    pub mod hygiene;
+
+    pub mod lint_fix;
+
+    pub mod minimal_versions;
+
+    pub mod new_member;
+
+    pub mod notify;
+
+    pub mod package_metadata;
+
+    pub mod patches;
+
+    pub mod playground;
+
+    pub mod preview;
+
+    pub mod progress;
+
+    pub mod project_detail;
+
+    pub mod project_list;
+
+    pub mod project_menu;
+
+    pub mod rebase;
+
+    pub mod remote_check;
+
+    pub mod scaffold;
+
+    pub mod search;
+
+    pub mod settings;
+
+    pub mod snapshots;
+
+    pub mod status_bar;
+
+    pub mod suspend;
+
+    #[cfg(test)]
+    pub mod testing;
+
+    pub mod toolchain;
+
+    pub mod trust;
+
+    pub mod unpushed;
+
+    pub mod vanished;
 }
 
-use config::{Config, LoadError, LoadStatus, SetupReason};
+use std::sync::Arc;
+
+use config::{Config, LoadError, LoadStatus, Preset, SetupReason};
 use cursive::Cursive;
 use cursive::view::{Nameable, Resizable, Scrollable};
-use cursive::views::{Dialog, EditView, LinearLayout, SelectView, TextView};
+use cursive::views::{Checkbox, Dialog, EditView, LinearLayout, OnEventView, SelectView, TextView};
+use keymap::Action;
 use log::{error, info};
-use std::fmt::Write;
-use std::process::Command;
 fn main() {
     // 1. Initialize logging first.
     if let Err(e) = logging::init_logging() {
@@ -39,6 +294,12 @@ fn main() {
         // Continue anyway; not fatal for user experience.
     }
 
+    // Subcommands run without the TUI (and without requiring a configuration).
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     // 2. Attempt to load configuration.
     let config = match Config::load() {
         Ok(LoadStatus::Ready(cfg)) => {
@@ -58,6 +319,11 @@ fn main() {
                 );
                 std::process::exit(1);
             }
+            LoadError::InvalidOverride(msg) => {
+                error!("Invalid configuration override: {msg}");
+                eprintln!("Invalid --projects-dir / RUSTM_PROJECTS_DIR: {msg}");
+                std::process::exit(1);
+            }
             LoadError::Io(ioe) => {
                 error!("I/O error loading config: {ioe}");
                 eprintln!("I/O error loading config: {ioe}");
@@ -82,7 +348,7 @@ const fn reason_variant(r: &SetupReason) -> &'static str {
 /// Extremely bare-bones; no validation feedback loop beyond error dialog.
 fn initial_setup_flow(reason: &SetupReason) {
     let mut siv = cursive::default();
-    theme::apply_theme(&mut siv);
+    theme::apply_theme(&mut siv, theme::AUTO_THEME);
 
     let msg = match reason {
         SetupReason::MissingFile => "Welcome! Let's set up rustm.".to_string(),
@@ -132,42 +398,152 @@ fn initial_setup_flow(reason: &SetupReason) {
             .button("Quit", cursive::Cursive::quit),
     );
 
-    siv.run();
+    ui::suspend::run_loop(&mut siv);
 }
 
 /// After saving config from initial setup, proceed to main TUI without restarting.
 fn launch_post_setup(siv: &mut Cursive, config: Config) {
+    logging::set_levels(config.log_levels());
+    format::set_timestamps(config.timestamps());
+    siv.add_global_callback(keymap::get().event(Action::Quit), Cursive::quit);
+    config_watch::spawn(siv.cb_sink().clone(), &config, config_reloaded);
+    ui::status_bar::install(siv, &config);
     siv.add_layer(main_menu_view(config));
 }
 
 /// Run the main TUI with a simple global menu.
 fn run_main_tui(config: Config) {
+    logging::set_levels(config.log_levels());
+    format::set_timestamps(config.timestamps());
     let mut siv = cursive::default();
-    theme::apply_theme(&mut siv);
+    theme::apply_theme(&mut siv, config.theme());
+    siv.add_global_callback(keymap::get().event(Action::Quit), Cursive::quit);
+    config_watch::spawn(siv.cb_sink().clone(), &config, config_reloaded);
+    ui::status_bar::install(&mut siv, &config);
     siv.add_layer(main_menu_view(config));
-    siv.run();
+    ui::suspend::run_loop(&mut siv);
 }
 
-/// Build the main menu view.
-fn main_menu_view(config: Config) -> Dialog {
+/// Build the main menu view, with the shortcuts of its entries.
+fn main_menu_view(config: Config) -> OnEventView<Dialog> {
     let mut menu = SelectView::<&'static str>::new()
         .item("Create new project", "create")
         .item("List projects", "list")
+        .item("Reopen last project", "reopen")
+        .item("Recent activity", "activity")
+        .item("Disk usage", "disk_usage")
+        .item("Archived projects", "archived")
+        .item("Playground", "playground")
+        .item("Settings", "settings")
+        .item("Help", "help")
         .item("Quit", "quit");
 
+    let create_config = config.clone();
+    let list_config = config.clone();
     menu.set_on_submit(move |s, choice| match *choice {
         "create" => show_create_project_dialog(s, config.clone()),
-        "list" => show_list_projects(s, &config),
+        "list" => ui::project_list::show(s, &config),
+        "reopen" => reopen_last_project(s, &config),
+        "activity" => ui::activity::show(s, &config),
+        "disk_usage" => ui::disk_usage::show(s, &config),
+        "archived" => ui::archive::show(s, &config),
+        "playground" => ui::playground::show(s),
+        "settings" => show_settings(s, &config),
+        "help" => ui::help::show(s, &config),
         "quit" => s.quit(),
         _ => {}
     });
 
-    Dialog::around(menu.scrollable().fixed_size((40, 10))).title("rustm - Global Mode")
+    let keys = keymap::get();
+    OnEventView::new(
+        Dialog::around(menu.scrollable().fixed_size((40, 12))).title("rustm - Global Mode"),
+    )
+    .on_event(keys.event(Action::Create), move |s| {
+        show_create_project_dialog(s, create_config.clone());
+    })
+    .on_event(keys.event(Action::List), move |s| {
+        ui::project_list::show(s, &list_config);
+    })
+}
+
+/// Settings screen; saving rebuilds the main menu around the new configuration.
+fn show_settings(s: &mut Cursive, config: &Config) {
+    ui::settings::show(
+        s,
+        config,
+        Arc::new(|s, config| {
+            restart_with(s, &config);
+            show_settings(s, &config);
+        }),
+    );
+}
+
+/// `config.yaml` was edited outside rustm: carry on with its new content.
+fn config_reloaded(s: &mut Cursive, config: Config) {
+    restart_with(s, &config);
+    ui::notify::toast(s, "config.yaml changed on disk and was reloaded.");
 }
 
-/// Create project dialog: prompts for project name, project type, and Rust edition.
+/// Switch the TUI to `config`.
+///
+/// Every open view captured the previous configuration: start over from the main menu.
+fn restart_with(s: &mut Cursive, config: &Config) {
+    config_watch::mark_applied(config);
+    logging::set_levels(config.log_levels());
+    format::set_timestamps(config.timestamps());
+    theme::apply_theme(s, config.theme());
+    ui::status_bar::clear_screen(s);
+    ui::status_bar::reset(s, config);
+    s.add_layer(main_menu_view(config.clone()));
+}
+
+/// Open the most recently opened project in the editor.
+fn reopen_last_project(s: &mut Cursive, config: &Config) {
+    match metadata::last_opened_project() {
+        Some(path) => ui::project_detail::open_project(s, config, &path),
+        None => s.add_layer(Dialog::info("No project has been opened yet.")),
+    }
+}
+
+/// Create project dialog, offering the templates of `template_index` when set.
 fn show_create_project_dialog(s: &mut Cursive, config: Config) {
-    use project::create::{ProjectEdition, ProjectType};
+    let tooling_files = project::tooling::available(&project::tooling::dir());
+    let Some(location) = config.template_index().map(ToString::to_string) else {
+        create_project_form(s, config, Vec::new(), tooling_files);
+        return;
+    };
+    ui::progress::run(
+        s,
+        "Loading templates",
+        move |_| project::templates::load_index(&location),
+        move |s, index| match index {
+            Ok(index) => {
+                create_project_form(s, config, index.templates, tooling_files);
+                if index.cached {
+                    ui::notify::toast(s, "Template index unavailable; showing the cached copy.");
+                }
+            }
+            Err(e) => {
+                error!("Cannot load the template index: {e}");
+                create_project_form(s, config, Vec::new(), tooling_files);
+                ui::error_view::show(s, "Cannot load the template index", &e);
+            }
+        },
+    );
+}
+
+/// Create project form: prompts for project name, project type, Rust edition and template;
+/// offers to copy `tooling_files` (see `project::tooling`).
+fn create_project_form(
+    s: &mut Cursive,
+    config: Config,
+    templates: Vec<project::templates::Template>,
+    tooling_files: Vec<&'static str>,
+) {
+    use forge::Provider;
+    use project::create::{ProjectEdition, ProjectType, Vcs};
+    use project::license::License;
+    use project::templates::Template;
 
     // Select for project type (default Binary)
     let mut type_select = SelectView::<&'static str>::new()
@@ -176,16 +552,44 @@ fn show_create_project_dialog(s: &mut Cursive, config: Config) {
         .item("Library (--lib)", "lib");
     type_select.set_selection(0);
 
-    // Select for Rust edition (default latest: 2024)
+    // Select for Rust edition (default_edition, else latest: 2024)
     let mut edition_select = SelectView::<&'static str>::new()
         .popup()
         .item("2015", "2015")
         .item("2018", "2018")
         .item("2021", "2021")
         .item("2024 (latest)", "2024");
-    edition_select.set_selection(3);
+    let default_edition = config.default_edition();
+    edition_select.set_selection(
+        ProjectEdition::ALL
+            .iter()
+            .position(|e| *e == default_edition)
+            .unwrap_or(3),
+    );
 
-    let form = LinearLayout::vertical()
+    // Select for version control (default git)
+    let vcs_select = SelectView::<Vcs>::new()
+        .popup()
+        .item("Git", Vcs::Git)
+        .item("Mercurial", Vcs::Hg)
+        .item("None", Vcs::None);
+
+    // Select for license files (default_license, else none)
+    let mut license_select = SelectView::<Option<License>>::new()
+        .popup()
+        .item("None", None);
+    for license in License::ALL {
+        license_select.add_item(license.spdx(), Some(license));
+    }
+    let default_license = config.default_license();
+    if let Some(i) = License::ALL
+        .iter()
+        .position(|l| Some(*l) == default_license)
+    {
+        license_select.set_selection(i + 1);
+    }
+
+    let mut form = LinearLayout::vertical()
         .child(TextView::new("Project name:"))
         .child(
             EditView::new()
@@ -195,13 +599,121 @@ fn show_create_project_dialog(s: &mut Cursive, config: Config) {
         .child(TextView::new("Project type:"))
         .child(type_select.with_name("project_type").fixed_width(24))
         .child(TextView::new("Rust edition:"))
-        .child(edition_select.with_name("project_edition").fixed_width(24));
+        .child(edition_select.with_name("project_edition").fixed_width(24))
+        .child(TextView::new("Version control:"))
+        .child(vcs_select.with_name("project_vcs").fixed_width(24))
+        .child(
+            LinearLayout::horizontal()
+                .child(Checkbox::new().with_name("project_initial_commit"))
+                .child(TextView::new(" Create an initial commit")),
+        )
+        .child(TextView::new("License:"))
+        .child(license_select.with_name("project_license").fixed_width(24))
+        .child(TextView::new("Description (optional):"))
+        .child(
+            EditView::new()
+                .with_name("project_description")
+                .fixed_width(50),
+        )
+        .child(
+            LinearLayout::horizontal()
+                .child(Checkbox::new().with_name("project_readme"))
+                .child(TextView::new(" Create a README.md")),
+        );
+
+    // Select for the remote repository (default none)
+    let mut remote_select = SelectView::<Option<Provider>>::new()
+        .popup()
+        .item("None", None);
+    for provider in Provider::ALL {
+        remote_select.add_item(provider.label(), Some(provider));
+    }
+    form = form
+        .child(TextView::new("Remote repository:"))
+        .child(remote_select.with_name("project_remote").fixed_width(24))
+        .child(
+            LinearLayout::horizontal()
+                .child(Checkbox::new().with_name("project_remote_public"))
+                .child(TextView::new(" Public (private by default)")),
+        );
+
+    if !tooling_files.is_empty() {
+        form = form.child(
+            LinearLayout::horizontal()
+                .child(Checkbox::new().checked().with_name("project_tooling"))
+                .child(TextView::new(format!(
+                    " Add my {}",
+                    tooling_files.join(", ")
+                ))),
+        );
+    }
+
+    let presets = config.presets();
+    if !presets.is_empty() {
+        let mut preset_select = SelectView::<Option<Preset>>::new()
+            .popup()
+            .item("None", None);
+        for preset in presets {
+            preset_select.add_item(preset.name.clone(), Some(preset.clone()));
+        }
+        preset_select.set_on_select(|s, preset| {
+            if let Some(preset) = preset {
+                select_preset_choices(s, preset);
+            }
+            let text = preset.as_ref().map(preset_summary).unwrap_or_default();
+            s.call_on_name("project_preset_description", |v: &mut TextView| {
+                v.set_content(text);
+            });
+        });
+        form = form
+            .child(TextView::new("Preset:"))
+            .child(preset_select.with_name("project_preset").fixed_width(30))
+            .child(
+                TextView::new("")
+                    .with_name("project_preset_description")
+                    .fixed_width(50),
+            );
+    }
+
+    let has_templates = !templates.is_empty();
+    if has_templates {
+        let mut template_select = SelectView::<Option<Template>>::new()
+            .popup()
+            .item("None (cargo new)", None);
+        for template in templates {
+            template_select.add_item(template.name.clone(), Some(template));
+        }
+        template_select.set_on_select(|s, template| {
+            let text = match template {
+                Some(t) => format!(
+                    "{}\n(type and edition come from the template)",
+                    t.description
+                ),
+                None => String::new(),
+            };
+            s.call_on_name("project_template_description", |v: &mut TextView| {
+                v.set_content(text);
+            });
+        });
+        form = form
+            .child(TextView::new("Template:"))
+            .child(
+                template_select
+                    .with_name("project_template")
+                    .fixed_width(30),
+            )
+            .child(
+                TextView::new("")
+                    .with_name("project_template_description")
+                    .fixed_width(50),
+            );
+    }
 
     s.add_layer(
-        Dialog::around(form)
+        Dialog::around(form.scrollable())
             .title("Create Project")
             .button("Create", move |siv| {
-                use project::create::{CreateProjectParams, create_project};
+                use project::create::{CreateProjectError, CreateProjectParams, create_project};
 
                 let name = siv
                     .call_on_name("new_project_name", |v: &mut EditView| v.get_content())
@@ -222,12 +734,77 @@ fn show_create_project_dialog(s: &mut Cursive, config: Config) {
                     .flatten()
                     .unwrap_or("2024");
 
+                let vcs = siv
+                    .call_on_name("project_vcs", |v: &mut SelectView<Vcs>| {
+                        v.selection().map(|s| *s)
+                    })
+                    .flatten()
+                    .unwrap_or_default();
+
+                let initial_commit = siv
+                    .call_on_name("project_initial_commit", |v: &mut Checkbox| v.is_checked())
+                    .unwrap_or(false);
+
+                let license = siv
+                    .call_on_name("project_license", |v: &mut SelectView<Option<License>>| {
+                        v.selection().and_then(|l| *l)
+                    })
+                    .flatten();
+
+                let description = siv
+                    .call_on_name("project_description", |v: &mut EditView| {
+                        v.get_content().trim().to_string()
+                    })
+                    .unwrap_or_default();
+
+                let readme = siv
+                    .call_on_name("project_readme", |v: &mut Checkbox| v.is_checked())
+                    .unwrap_or(false);
+
+                let tooling = siv
+                    .call_on_name("project_tooling", |v: &mut Checkbox| v.is_checked())
+                    .unwrap_or(false);
+
+                let remote = siv
+                    .call_on_name("project_remote", |v: &mut SelectView<Option<Provider>>| {
+                        v.selection().and_then(|p| *p)
+                    })
+                    .flatten();
+
+                let public_remote = siv
+                    .call_on_name("project_remote_public", |v: &mut Checkbox| v.is_checked())
+                    .unwrap_or(false);
+
+                let template = siv
+                    .call_on_name(
+                        "project_template",
+                        |v: &mut SelectView<Option<Template>>| {
+                            v.selection().and_then(|t| (*t).clone())
+                        },
+                    )
+                    .flatten();
+
+                let preset = siv
+                    .call_on_name("project_preset", |v: &mut SelectView<Option<Preset>>| {
+                        v.selection().and_then(|p| (*p).clone())
+                    })
+                    .flatten();
+
                 if name.trim().is_empty() {
                     siv.add_layer(Dialog::info("Project name cannot be empty."));
 
                     return;
                 }
 
+                if let Some(wanted) = preset.as_ref().and_then(|p| p.template.as_ref())
+                    && !has_templates
+                {
+                    siv.add_layer(Dialog::info(format!(
+                        "The preset uses the template '{wanted}', but the template index is unavailable."
+                    )));
+                    return;
+                }
+
                 let project_type = match selected_type {
                     "lib" => ProjectType::Library,
                     _ => ProjectType::Binary,
@@ -240,61 +817,65 @@ fn show_create_project_dialog(s: &mut Cursive, config: Config) {
                     _ => ProjectEdition::E2024,
                 };
 
-                // Build params with defaults then override fields explicitly.
-                let mut params = CreateProjectParams::new(name);
+                // Build params with the configured defaults (author) then override fields explicitly.
+                let mut params = CreateProjectParams::from_config(name, &config);
 
                 params.project_type = project_type;
                 params.edition = edition;
-
-                match create_project(&config, params) {
-                    Ok(res) => {
-                        siv.pop_layer();
-                        let project_path = res.project_path.clone();
-                        let editor_cmd = config.editor_cmd().to_string();
-
-                        siv.add_layer(
-                            Dialog::around(TextView::new(format!(
-                                "Project created at:\n{}\n\nOpen in editor?",
-                                project_path.display()
-                            )))
-                            .title("Project Created")
-                            .button("Open", move |s2| {
-                                if editor_cmd.trim().is_empty() {
-                                    s2.add_layer(Dialog::info("Editor command not set."));
-                                    return;
-                                }
-                                let mut parts = editor_cmd.split_whitespace();
-                                if let Some(program) = parts.next() {
-                                    let mut cmd = Command::new(program);
-                                    for arg in parts {
-                                        cmd.arg(arg);
-                                    }
-                                    cmd.arg(&project_path);
-                                    match cmd.spawn() {
-                                        Ok(_) => {
-                                            s2.add_layer(Dialog::info("Editor launched."));
-                                        }
-                                        Err(e) => {
-                                            s2.add_layer(Dialog::info(format!(
-                                                "Failed to launch editor: {e}"
-                                            )));
-                                        }
-                                    }
-                                } else {
-                                    s2.add_layer(Dialog::info("Invalid editor command."));
-                                }
-                            })
-                            .button("Skip", |s2| {
-                                s2.pop_layer();
-                                s2.add_layer(Dialog::info("Project creation complete."));
-                            }),
-                        );
-                    }
-
-                    Err(e) => {
-                        siv.add_layer(Dialog::info(format!("Failed to create project:\n{e}")));
+                params.preset = preset;
+                params.vcs = vcs;
+                params.initial_commit = initial_commit;
+                params.license = license;
+                params.description = description;
+                params.readme = readme;
+                params.tooling = tooling;
+                params.remote = remote;
+                params.public_remote = public_remote;
+
+                if template.is_none() && remote.is_none() {
+                    match create_project(&config, params) {
+                        Ok(res) => {
+                            siv.pop_layer();
+                            project_created(siv, &config, res);
+                        }
+                        Err(e) => {
+                            ui::error_view::show(siv, "Failed to create project", &e);
+                        }
                     }
+                    return;
                 }
+
+                // Downloading the template and creating the remote may take a while: in the
+                // background.
+                let title = match &template {
+                    Some(template) => format!("Creating from {}", template.name),
+                    None => format!("Creating {}", params.name),
+                };
+                let work_config = config.clone();
+                let config = config.clone();
+                ui::progress::run(
+                    siv,
+                    &title,
+                    move |p| {
+                        if let Some(template) = template {
+                            let log = |line: String| p.log(line);
+                            let dir = project::templates::fetch(&template, &log)
+                                .map_err(CreateProjectError::Template)?;
+                            params.template = Some(dir);
+                        }
+                        create_project(&work_config, params)
+                    },
+                    move |s, result| match result {
+                        Ok(res) => {
+                            s.pop_layer();
+                            project_created(s, &config, res);
+                        }
+                        Err(e) => {
+                            error!("Project creation failed: {e}");
+                            ui::error_view::show(s, "Failed to create project", &e);
+                        }
+                    },
+                );
             })
             .button("Cancel", |siv| {
                 siv.pop_layer();
@@ -302,35 +883,131 @@ fn show_create_project_dialog(s: &mut Cursive, config: Config) {
     );
 }
 
-/// Show a simple list of projects discovered.
-fn show_list_projects(s: &mut Cursive, config: &Config) {
-    use project::list::list_projects;
-
-    match list_projects(config) {
-        Ok(projects) => {
-            if projects.is_empty() {
-                s.add_layer(Dialog::info("No Rust projects found."));
-                return;
-            }
-            let mut text = String::new();
-            for p in projects {
-                let mut line = p.name.to_string();
-                if p.has_uncommitted_changes {
-                    line.push_str(" *");
-                }
-                writeln!(line, "  {}", p.path.display()).unwrap();
-                text.push_str(&line);
-            }
-            s.add_layer(
-                Dialog::around(TextView::new(text).scrollable().fixed_size((60, 20)))
-                    .title("Projects")
-                    .button("Close", |siv| {
-                        siv.pop_layer();
-                    }),
-            );
+/// Set the type, edition and template popups of the create form to the choices of `preset`.
+fn select_preset_choices(s: &mut Cursive, preset: &Preset) {
+    use project::templates::Template;
+
+    fn select<T: Send + Sync + 'static>(s: &mut Cursive, name: &str, matches: impl Fn(&T) -> bool) {
+        let callback = s.call_on_name(name, |v: &mut SelectView<T>| {
+            let index = v.iter().position(|(_, value)| matches(value))?;
+            Some(v.set_selection(index))
+        });
+        if let Some(Some(callback)) = callback {
+            callback(s);
         }
-        Err(e) => {
-            s.add_layer(Dialog::info(format!("Failed to list projects:\n{e}")));
+    }
+
+    if let Some(project_type) = &preset.project_type {
+        select(s, "project_type", |v: &&'static str| v == project_type);
+    }
+    if let Some(edition) = &preset.edition {
+        select(s, "project_edition", |v: &&'static str| v == edition);
+    }
+    if let Some(template) = &preset.template {
+        select(s, "project_template", |v: &Option<Template>| {
+            v.as_ref().is_some_and(|t| &t.name == template)
+        });
+    }
+}
+
+/// What `preset` adds besides the type, edition and template, for the create form.
+fn preset_summary(preset: &Preset) -> String {
+    let mut parts = Vec::new();
+    if let Some(template) = &preset.template {
+        parts.push(format!("template {template}"));
+    }
+    if preset.ci {
+        parts.push("CI workflow".to_string());
+    }
+    if let Some(license) = &preset.license {
+        parts.push(format!("license {license}"));
+    }
+    if let Some(registry) = &preset.registry {
+        parts.push(format!("published to {registry}"));
+    }
+    let lints: usize = preset.lints.values().map(|l| l.len()).sum();
+    if lints > 0 {
+        parts.push(format!("{lints} lint level(s)"));
+    }
+    parts.join(", ")
+}
+
+/// Announce the created project and offer to open it.
+fn project_created(
+    siv: &mut Cursive,
+    config: &Config,
+    result: project::create::CreateProjectResult,
+) {
+    let project_path = result.project_path;
+    let config = config.clone();
+    let prep_path = project_path.clone();
+    let prep_config = config.clone();
+
+    ui::notify::toast(
+        siv,
+        format!("Project created at {}.", project_path.display()),
+    );
+    siv.add_layer(
+        Dialog::around(TextView::new("Open it in the editor?"))
+            .title("Project Created")
+            .button("Open", move |s2| {
+                s2.pop_layer();
+                ui::project_detail::open_project(s2, &config, &project_path);
+            })
+            .button("Open full workspace", move |s2| {
+                s2.pop_layer();
+                ui::project_detail::open_full_workspace(s2, &prep_config, &prep_path);
+            })
+            .dismiss_button("Skip"),
+    );
+    if !result.setup_failures.is_empty() {
+        ui::error_view::show_message(
+            siv,
+            "Project not fully set up",
+            &result.setup_failures.join("\n"),
+        );
+    }
+    if let Some(e) = &result.commit_error {
+        ui::error_view::show_message(siv, "Initial commit not made", e);
+    }
+    if let Some(url) = &result.remote_url {
+        ui::notify::toast(siv, format!("Remote repository created: {url}"));
+    }
+    if let Some(e) = &result.remote_error {
+        ui::error_view::show_message(siv, "Remote repository not set up", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cursive::event::Key;
+    use ui::testing::Harness;
+
+    fn form() -> Harness {
+        let config = Config::from_yaml(
+            "projects_directory: /projects\neditor_cmd: vim\ndefault_edition: 2021\ndefault_license: mit\n",
+        );
+        let mut h = Harness::new(80, 30);
+        create_project_form(h.siv(), config, Vec::new(), vec!["rustfmt.toml"]);
+        h
+    }
+
+    #[test]
+    fn create_form_shows_the_configured_defaults() {
+        let mut h = form();
+        h.type_text("demo");
+        h.assert_snapshot("create_form");
+    }
+
+    #[test]
+    fn create_form_requires_a_name() {
+        let mut h = form();
+        // From the name field, past the other fields to the "Create" button.
+        for _ in 0..11 {
+            h.press(Key::Tab);
         }
+        h.press(Key::Enter);
+        h.assert_snapshot("create_form_empty_name");
     }
 }
@@ -0,0 +1,437 @@
+//! Persistent project metadata store.
+//!
+//! A JSON file (`projects_cache.json`) next to `config.yaml` remembering what rustm
+//! knows about each project, keyed by project path:
+//! - Scan results (name, git dirty flag, branch and upstream tracking, default
+//!   branch divergence, package version) together with the filesystem
+//!   fingerprint (`mtime`) they were computed for. An entry whose
+//!   fingerprint still matches is fresh and need not be recomputed.
+//! - Results of on-demand actions (workspace status rollup, no_std probe,
+//!   clippy counts, contributors per time window).
+//! - User choices and activity: pinned projects, tags, untrusted (quarantined)
+//!   projects, editor chosen per project, last time a project was opened, order
+//!   of the project list.
+//!
+//! Scan results can be recomputed, user choices cannot: a file that does not
+//! parse is renamed aside (`projects_cache.json.bad`) before anything is saved
+//! over it, and one that cannot be read, or was written by a newer rustm, is
+//! left alone for the session (the store stays in memory). Access goes through a process-wide store (`snapshot` / `update`) so UI and
+//! background threads never race on the file; every `update` is persisted
+//! atomically (write to temp then rename).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::project::clippy::LintSummary;
+use crate::project::contributors::Contributors;
+use crate::project::kind::ProjectKind;
+use crate::project::list::SortMode;
+use crate::project::no_std::NoStdProbe;
+use crate::project::rollup::WorkspaceRollup;
+
+/// File name of the store, inside the configuration directory.
+const STORE_FILE: &str = "projects_cache.json";
+
+/// Current on-disk format version. Version 2 added the project kind to the
+/// scan results: version 1 stores are migrated by marking their scan results
+/// stale, the rest of what they remember being kept.
+const FORMAT_VERSION: u32 = 2;
+
+/// Process-wide store, loaded lazily on first access.
+static STORE: Mutex<Option<MetadataStore>> = Mutex::new(None);
+
+/// Everything known about one project.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectMetadata {
+    pub name: String,
+    /// Fingerprint (seconds since epoch) the scan fields were computed for.
+    pub mtime: u64,
+    pub has_uncommitted_changes: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ahead_behind: Option<(usize, usize)>,
+    /// Default branch name when it diverged from its upstream.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diverged_default: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ProjectKind>,
+    /// Commit time of `HEAD`, seconds since epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_commit: Option<i64>,
+    /// URL of the `origin` remote.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_url: Option<String>,
+    /// Last computed workspace rollup (kept across scans: it is only refreshed on demand).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollup: Option<WorkspaceRollup>,
+    /// Last no_std probe verdict (kept across scans: it is only refreshed on demand).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_std: Option<NoStdProbe>,
+    /// Counts of the last clippy run (kept across scans: it is only refreshed on demand).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lint: Option<LintSummary>,
+    /// Contributors summaries, at most one per time window.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub contributors: Vec<Contributors>,
+    /// Seconds since epoch of the last time the project was opened.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_opened: Option<u64>,
+    /// Pinned to the top of the project list.
+    pub pinned: bool,
+    /// User-defined tags, normalized (see `project::tags`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Name of the editor of `editors` last chosen for the project (`None`: the default one).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    /// Quarantined: nothing runs the project's code until it is trusted (see `ui::trust`).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub untrusted: bool,
+}
+
+/// All project metadata, keyed by project path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataStore {
+    version: u32,
+    projects: BTreeMap<String, ProjectMetadata>,
+    /// Order of the project list chosen by the user.
+    #[serde(default)]
+    sort_mode: SortMode,
+    /// The file exists but could not be loaded nor set aside: never save over it.
+    #[serde(skip)]
+    read_only: bool,
+    /// The file could not be loaded: projects missing from the store may be
+    /// quarantined ones, so they count as untrusted.
+    #[serde(skip)]
+    lost: bool,
+}
+
+impl Default for MetadataStore {
+    fn default() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            projects: BTreeMap::new(),
+            sort_mode: SortMode::default(),
+            read_only: false,
+            lost: false,
+        }
+    }
+}
+
+impl MetadataStore {
+    /// Metadata for `path`, if known.
+    pub fn get(&self, path: &Path) -> Option<&ProjectMetadata> {
+        self.projects.get(&key(path))
+    }
+
+    /// Metadata for `path`, created empty when unknown.
+    pub fn entry_mut(&mut self, path: &Path) -> &mut ProjectMetadata {
+        self.projects.entry(key(path)).or_default()
+    }
+
+    /// Forget `path`.
+    pub fn remove(&mut self, path: &Path) {
+        self.projects.remove(&key(path));
+    }
+
+    /// Move the metadata of `from` to `to` (project directory renamed).
+    pub fn rename(&mut self, from: &Path, to: &Path) {
+        if let Some(meta) = self.projects.remove(&key(from)) {
+            self.projects.insert(key(to), meta);
+        }
+    }
+
+    /// Is `path` quarantined (see `ui::trust`)? Unknown projects are, when the file was lost.
+    pub fn is_untrusted(&self, path: &Path) -> bool {
+        self.get(path).map_or(self.lost, |m| m.untrusted)
+    }
+
+    /// The file could not be loaded (see `is_untrusted`).
+    pub fn is_lost(&self) -> bool {
+        self.lost
+    }
+
+    pub fn sort_mode(&self) -> SortMode {
+        self.sort_mode
+    }
+
+    pub fn set_sort_mode(&mut self, mode: SortMode) {
+        self.sort_mode = mode;
+    }
+
+    /// All known projects.
+    pub fn iter(&self) -> impl Iterator<Item = (PathBuf, &ProjectMetadata)> {
+        self.projects.iter().map(|(k, v)| (PathBuf::from(k), v))
+    }
+
+    fn load_from(path: &Path) -> Self {
+        let raw = match fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                warn!(
+                    "Cannot read metadata store {}, it will not be saved: {e}",
+                    path.display()
+                );
+                return Self::read_only();
+            }
+        };
+        match serde_json::from_str::<Self>(&raw) {
+            Ok(store) if store.version == FORMAT_VERSION => store,
+            Ok(mut store) if store.version == 1 => {
+                for meta in store.projects.values_mut() {
+                    meta.mtime = 0;
+                }
+                store.version = FORMAT_VERSION;
+                store
+            }
+            Ok(store) => {
+                warn!(
+                    "Metadata store {} has unsupported version {}, it will not be saved",
+                    path.display(),
+                    store.version
+                );
+                Self::read_only()
+            }
+            Err(e) => {
+                let aside = path.with_extension("json.bad");
+                warn!(
+                    "Unreadable metadata store {}, moved to {}: {e}",
+                    path.display(),
+                    aside.display()
+                );
+                match fs::rename(path, &aside) {
+                    Ok(()) => Self {
+                        lost: true,
+                        ..Self::default()
+                    },
+                    Err(e) => {
+                        warn!(
+                            "Cannot move {} aside, it will not be saved: {e}",
+                            path.display()
+                        );
+                        Self::read_only()
+                    }
+                }
+            }
+        }
+    }
+
+    /// Empty store standing for a file it must not overwrite.
+    fn read_only() -> Self {
+        Self {
+            read_only: true,
+            lost: true,
+            ..Self::default()
+        }
+    }
+
+    /// Write the store to `path`, unless it is read-only.
+    fn save_to(&self, path: &Path) -> io::Result<()> {
+        if self.read_only {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        let tmp_path = path.with_extension("json.tmp");
+        {
+            let mut f = fs::File::create(&tmp_path)?;
+            f.write_all(json.as_bytes())?;
+            f.sync_all().ok();
+        }
+        fs::rename(&tmp_path, path)
+    }
+}
+
+/// Copy of the current store.
+pub fn snapshot() -> MetadataStore {
+    let mut guard = STORE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    guard
+        .get_or_insert_with(|| MetadataStore::load_from(&store_path()))
+        .clone()
+}
+
+/// Apply `f` to the store and persist the result (failures are logged, not fatal).
+pub fn update(f: impl FnOnce(&mut MetadataStore)) {
+    let mut guard = STORE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let store = guard.get_or_insert_with(|| MetadataStore::load_from(&store_path()));
+    f(store);
+    if let Err(e) = store.save_to(&store_path()) {
+        warn!("Failed to persist metadata store: {e}");
+    }
+}
+
+/// Remember that `path` was just opened.
+pub fn record_opened(path: &Path) {
+    update(|store| store.entry_mut(path).last_opened = Some(now()));
+}
+
+/// Is `path` quarantined (see `ui::trust`)?
+pub fn is_untrusted(path: &Path) -> bool {
+    snapshot().is_untrusted(path)
+}
+
+/// Quarantine `path`, or lift its quarantine.
+pub fn set_untrusted(path: &Path, untrusted: bool) {
+    update(|store| store.entry_mut(path).untrusted = untrusted);
+}
+
+/// Most recently opened project that still exists.
+pub fn last_opened_project() -> Option<PathBuf> {
+    snapshot()
+        .iter()
+        .filter_map(|(path, meta)| meta.last_opened.map(|at| (at, path)))
+        .filter(|(_, path)| path.is_dir())
+        .max_by_key(|(at, _)| *at)
+        .map(|(_, path)| path)
+}
+
+/// Current time in seconds since epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Fingerprint of a project directory: newest mtime among the files whose change
+/// invalidates cached scan results (project dir, manifest, git index, HEAD and
+/// its reflog which moves on commits, and `FETCH_HEAD` which moves on fetches).
+pub fn fingerprint(project_path: &Path) -> u64 {
+    [
+        project_path.to_path_buf(),
+        project_path.join("Cargo.toml"),
+        project_path.join("src"),
+        project_path.join(".git").join("index"),
+        project_path.join(".git").join("HEAD"),
+        project_path.join(".git").join("logs").join("HEAD"),
+        project_path.join(".git").join("FETCH_HEAD"),
+    ]
+    .iter()
+    .filter_map(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+    .filter_map(|t| t.duration_since(UNIX_EPOCH).ok())
+    .map(|d| d.as_secs())
+    .max()
+    .unwrap_or(0)
+}
+
+fn key(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+/// `<config dir>/projects_cache.json`.
+fn store_path() -> PathBuf {
+    // Tests never touch the store of the user.
+    if cfg!(test) {
+        return std::env::temp_dir()
+            .join(format!("rustm_test_{}_{STORE_FILE}", std::process::id()));
+    }
+    let cfg_file = Config::file_path();
+    cfg_file
+        .parent()
+        .map_or_else(Config::file_path, Path::to_path_buf)
+        .join(STORE_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn roundtrip_and_migration() {
+        let dir = TempDir::new("metadata");
+        let file = dir.join(STORE_FILE);
+        let mut store = MetadataStore::default();
+        let entry = store.entry_mut(Path::new("/p/demo"));
+        entry.name = "demo".into();
+        entry.package_version = Some("1.0.0".into());
+        entry.last_opened = Some(42);
+        store.set_sort_mode(SortMode::LastCommit);
+        store.save_to(&file).unwrap();
+
+        let loaded = MetadataStore::load_from(&file);
+        let entry = loaded.get(Path::new("/p/demo")).unwrap();
+        assert_eq!(entry.package_version.as_deref(), Some("1.0.0"));
+        assert_eq!(entry.last_opened, Some(42));
+        assert_eq!(loaded.sort_mode(), SortMode::LastCommit);
+
+        // Scan results of version 1 predate the kinds: stale, the rest is kept.
+        let v1 = r#"{"version": 1, "projects": {"/p/demo": {"name": "demo", "mtime": 7, "pinned": true}}}"#;
+        fs::write(&file, v1).unwrap();
+        let loaded = MetadataStore::load_from(&file);
+        let entry = loaded.get(Path::new("/p/demo")).unwrap();
+        assert_eq!((entry.mtime, entry.pinned), (0, true));
+        assert_eq!(loaded.version, FORMAT_VERSION);
+    }
+
+    #[test]
+    fn stores_that_cannot_be_loaded_are_not_overwritten() {
+        let dir = TempDir::new("metadata");
+        let file = dir.join(STORE_FILE);
+
+        // Unparseable: set aside, then saved afresh.
+        fs::write(&file, "{not json").unwrap();
+        let store = MetadataStore::load_from(&file);
+        assert!(store.projects.is_empty() && store.lost && !store.read_only);
+        assert_eq!(
+            fs::read_to_string(file.with_extension("json.bad")).unwrap(),
+            "{not json"
+        );
+        assert!(!file.exists());
+
+        // Written by a newer rustm: kept as is.
+        let newer = r#"{"version": 99, "projects": {}}"#;
+        fs::write(&file, newer).unwrap();
+        let mut store = MetadataStore::load_from(&file);
+        assert!(store.read_only);
+        store.entry_mut(Path::new("/p/demo")).pinned = true;
+        store.save_to(&file).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), newer);
+
+        // Not readable (a directory here): not saved for the session.
+        fs::remove_file(&file).unwrap();
+        fs::create_dir(&file).unwrap();
+        let store = MetadataStore::load_from(&file);
+        assert!(store.read_only);
+        store.save_to(&file).unwrap();
+        assert!(file.is_dir());
+    }
+
+    #[test]
+    fn quarantine_survives_a_corrupt_store() {
+        let dir = TempDir::new("metadata");
+        let file = dir.join(STORE_FILE);
+        let mut store = MetadataStore::default();
+        store.entry_mut(Path::new("/p/quarantined")).untrusted = true;
+        store.entry_mut(Path::new("/p/trusted")).name = "trusted".into();
+        store.save_to(&file).unwrap();
+        assert!(!MetadataStore::load_from(&file).is_untrusted(Path::new("/p/trusted")));
+
+        let raw = fs::read_to_string(&file).unwrap();
+        fs::write(&file, &raw[..raw.len() / 2]).unwrap();
+        let mut store = MetadataStore::load_from(&file);
+        assert!(store.is_untrusted(Path::new("/p/quarantined")));
+        assert!(store.is_untrusted(Path::new("/p/trusted")));
+        // Trusting again is remembered.
+        store.entry_mut(Path::new("/p/trusted")).untrusted = false;
+        assert!(!store.is_untrusted(Path::new("/p/trusted")));
+    }
+}
@@ -0,0 +1,300 @@
+//! Workspace metrics of `rustm daemon --metrics`, in the Prometheus text format.
+//!
+//! The daemon records every scan of the projects directory (project counts,
+//! git status, scan duration) and the `build_finished` events of the event
+//! stream (see `crate::events`), and serves them on `GET /metrics` from a
+//! plain `TcpListener`: one short request at a time is all a scraper needs.
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use log::warn;
+use serde::Deserialize;
+
+use crate::metadata;
+use crate::project::list::ProjectInfo;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Metrics shared by the daemon loop and the endpoint.
+pub type Shared = Arc<Mutex<Metrics>>;
+
+/// Values of the last scan, and counters since the daemon started.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    projects: usize,
+    dirty: usize,
+    untrusted: usize,
+    /// Projects with commits not pushed to their upstream.
+    ahead: usize,
+    /// Projects with upstream commits not pulled.
+    behind: usize,
+    scans: u64,
+    scan_errors: u64,
+    last_scan_seconds: f64,
+    last_scan_time: u64,
+    builds_succeeded: u64,
+    builds_failed: u64,
+    build_seconds: f64,
+}
+
+/// The fields of a `build_finished` event used here.
+#[derive(Deserialize)]
+struct BuildFinished {
+    event: String,
+    success: bool,
+    duration_ms: u64,
+}
+
+impl Metrics {
+    /// Record a successful scan that found `projects` in `took`.
+    pub fn record_scan(&mut self, projects: &[ProjectInfo], took: Duration) {
+        let count = |f: fn(&ProjectInfo) -> bool| projects.iter().filter(|p| f(p)).count();
+        self.projects = projects.len();
+        self.dirty = count(|p| p.has_uncommitted_changes);
+        self.untrusted = count(|p| p.untrusted);
+        self.ahead = count(|p| p.ahead_behind.is_some_and(|(ahead, _)| ahead > 0));
+        self.behind = count(|p| p.ahead_behind.is_some_and(|(_, behind)| behind > 0));
+        self.scans += 1;
+        self.last_scan_seconds = took.as_secs_f64();
+        self.last_scan_time = metadata::now();
+    }
+
+    /// Record a scan that failed: the project counts of the previous one are kept.
+    pub fn record_scan_error(&mut self) {
+        self.scan_errors += 1;
+    }
+
+    /// Record an event line of the event stream; only builds are counted.
+    pub fn record_event(&mut self, line: &str) {
+        let Ok(build) = serde_json::from_str::<BuildFinished>(line) else {
+            return;
+        };
+        if build.event != "build_finished" {
+            return;
+        }
+        if build.success {
+            self.builds_succeeded += 1;
+        } else {
+            self.builds_failed += 1;
+        }
+        self.build_seconds += build.duration_ms as f64 / 1000.0;
+    }
+
+    /// The metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, samples: &[(&str, String)]| {
+            let _ = writeln!(
+                out,
+                "# HELP rustm_{name} {help}\n# TYPE rustm_{name} {kind}"
+            );
+            for (suffix, value) in samples {
+                let _ = writeln!(out, "rustm_{name}{suffix} {value}");
+            }
+        };
+        let gauges = [
+            (
+                "projects",
+                "Projects found by the last scan.",
+                self.projects,
+            ),
+            (
+                "projects_dirty",
+                "Projects with uncommitted changes.",
+                self.dirty,
+            ),
+            (
+                "projects_untrusted",
+                "Quarantined projects.",
+                self.untrusted,
+            ),
+            (
+                "projects_ahead",
+                "Projects with unpushed commits.",
+                self.ahead,
+            ),
+            (
+                "projects_behind",
+                "Projects behind their upstream.",
+                self.behind,
+            ),
+        ];
+        for (name, help, value) in gauges {
+            metric(name, "gauge", help, &[("", value.to_string())]);
+        }
+        metric(
+            "scans_total",
+            "counter",
+            "Scans of the projects directory.",
+            &[("", self.scans.to_string())],
+        );
+        metric(
+            "scan_errors_total",
+            "counter",
+            "Scans of the projects directory that failed.",
+            &[("", self.scan_errors.to_string())],
+        );
+        metric(
+            "last_scan_duration_seconds",
+            "gauge",
+            "Duration of the last scan.",
+            &[("", self.last_scan_seconds.to_string())],
+        );
+        metric(
+            "last_scan_timestamp_seconds",
+            "gauge",
+            "Time of the last scan, in seconds since epoch.",
+            &[("", self.last_scan_time.to_string())],
+        );
+        metric(
+            "builds_total",
+            "counter",
+            "Cargo commands run from the TUI, by result.",
+            &[
+                ("{result=\"success\"}", self.builds_succeeded.to_string()),
+                ("{result=\"failure\"}", self.builds_failed.to_string()),
+            ],
+        );
+        metric(
+            "build_duration_seconds",
+            "summary",
+            "Duration of the cargo commands run from the TUI.",
+            &[
+                ("_sum", self.build_seconds.to_string()),
+                (
+                    "_count",
+                    (self.builds_succeeded + self.builds_failed).to_string(),
+                ),
+            ],
+        );
+        out
+    }
+}
+
+/// Serve `metrics` on `addr` from a background thread; returns the bound
+/// address (`addr` may ask for any port) for the caller to report.
+pub fn serve(addr: SocketAddr, metrics: Shared) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    let bound = listener.local_addr()?;
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let answered = stream.and_then(|stream| answer(stream, &metrics));
+            if let Err(e) = answered {
+                warn!("Metrics request failed: {e}");
+            }
+        }
+    });
+    Ok(bound)
+}
+
+/// Answer one HTTP request: the metrics for `GET /metrics`, 404 otherwise.
+fn answer(stream: TcpStream, metrics: &Mutex<Metrics>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // Skip the headers.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+    let mut parts = request.split_whitespace();
+    let found = parts.next() == Some("GET")
+        && parts
+            .next()
+            .is_some_and(|target| target.split('?').next() == Some("/metrics"));
+    let (status, body) = if found {
+        let body = metrics.lock().map(|m| m.render()).unwrap_or_default();
+        ("200 OK", body)
+    } else {
+        ("404 Not Found", "Not found: try /metrics\n".to_string())
+    };
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::path::PathBuf;
+
+    fn project(name: &str, dirty: bool, ahead_behind: Option<(usize, usize)>) -> ProjectInfo {
+        ProjectInfo {
+            name: name.into(),
+            path: PathBuf::from("/projects").join(name),
+            has_uncommitted_changes: dirty,
+            branch: Some("main".into()),
+            ahead_behind,
+            diverged_default: None,
+            rollup: None,
+            no_std: None,
+            version: None,
+            kind: None,
+            last_commit: None,
+            remote_url: None,
+            fingerprint: 0,
+            pinned: false,
+            last_opened: None,
+            tags: Vec::new(),
+            untrusted: false,
+        }
+    }
+
+    #[test]
+    fn scans_and_builds_are_served() {
+        let metrics: Shared = Arc::default();
+        {
+            let mut m = metrics.lock().unwrap();
+            m.record_scan(
+                &[
+                    project("a", true, Some((2, 0))),
+                    project("b", false, Some((0, 1))),
+                    project("c", true, None),
+                ],
+                Duration::from_millis(1500),
+            );
+            m.record_event(r#"{"time":1,"event":"build_finished","name":"a","path":"/a","command":"cargo build","success":true,"duration_ms":2500}"#);
+            m.record_event(r#"{"time":1,"event":"build_finished","name":"a","path":"/a","command":"cargo test","success":false,"duration_ms":500}"#);
+            m.record_event(r#"{"time":1,"event":"project_created","name":"d","path":"/d"}"#);
+        }
+        let addr = serve("127.0.0.1:0".parse().unwrap(), Arc::clone(&metrics)).unwrap();
+        let get = |target: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {target} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let response = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        for line in [
+            "rustm_projects 3\n",
+            "rustm_projects_dirty 2\n",
+            "rustm_projects_ahead 1\n",
+            "rustm_projects_behind 1\n",
+            "rustm_scans_total 1\n",
+            "rustm_last_scan_duration_seconds 1.5\n",
+            "rustm_builds_total{result=\"success\"} 1\n",
+            "rustm_builds_total{result=\"failure\"} 1\n",
+            "rustm_build_duration_seconds_sum 3\n",
+            "rustm_build_duration_seconds_count 2\n",
+            "# TYPE rustm_projects gauge\n",
+        ] {
+            assert!(response.contains(line), "{line}");
+        }
+        assert!(get("/").starts_with("HTTP/1.1 404"));
+    }
+}
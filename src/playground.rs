@@ -0,0 +1,254 @@
+//! Scratch snippet runner.
+//!
+//! Runs a Rust snippet either locally, in a hidden scratch crate under the
+//! cache directory (`<cache>/rustm/playground`, kept between runs so
+//! rebuilds are incremental), or online through the play.rust-lang.org
+//! `execute` API. Snippets without a `fn main` are wrapped in one.
+//!
+//! Compiler output and program output are returned separately. Locally the
+//! program is killed after `RUN_TIMEOUT`.
+
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::info;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::http::{self, HttpError};
+use crate::process::{self, CommandLine};
+
+const EXECUTE_URL: &str = "https://play.rust-lang.org/execute";
+const EDITION: &str = "2024";
+const CRATE_NAME: &str = "playground";
+/// Longest a locally compiled snippet may run.
+const RUN_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug)]
+pub enum PlaygroundError {
+    Io(std::io::Error),
+    CargoNotFound,
+    Http(HttpError),
+}
+
+impl fmt::Display for PlaygroundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::CargoNotFound => write!(f, "Unable to locate `cargo` in PATH"),
+            Self::Http(e) => write!(f, "play.rust-lang.org: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PlaygroundError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Http(e) => Some(e),
+            Self::CargoNotFound => None,
+        }
+    }
+}
+
+/// Result of a run.
+#[derive(Debug, Default)]
+pub struct RunOutput {
+    /// Compiler diagnostics (and, online, the program's stderr).
+    pub compiler: String,
+    /// Program output (stdout, then stderr when run locally).
+    pub program: String,
+    /// Compiled and exited successfully (within the timeout).
+    pub success: bool,
+}
+
+/// Compile and run `code` in the local scratch crate.
+pub fn run_local(code: &str) -> Result<RunOutput, PlaygroundError> {
+    let dir = scratch_dir();
+    fs::create_dir_all(dir.join("src")).map_err(PlaygroundError::Io)?;
+    fs::write(
+        dir.join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"{CRATE_NAME}\"\nversion = \"0.0.0\"\nedition = \"{EDITION}\"\npublish = false\n"
+        ),
+    )
+    .map_err(PlaygroundError::Io)?;
+    fs::write(dir.join("src/main.rs"), wrap_main(code)).map_err(PlaygroundError::Io)?;
+
+    info!("Building playground snippet in {}", dir.display());
+    let cmd = CommandLine::new("cargo", &dir).args([
+        "build",
+        "--quiet",
+        "--message-format",
+        "short",
+        "--color",
+        "never",
+    ]);
+    let build = process::with(|runner| runner.output(&cmd)).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            PlaygroundError::CargoNotFound
+        } else {
+            PlaygroundError::Io(e)
+        }
+    })?;
+    if !build.success() {
+        return Ok(RunOutput {
+            compiler: build.stderr,
+            ..RunOutput::default()
+        });
+    }
+
+    let binary = dir
+        .join("target/debug")
+        .join(format!("{CRATE_NAME}{}", std::env::consts::EXE_SUFFIX));
+    let (program, success) = run_with_timeout(Command::new(binary).current_dir(&dir))?;
+    Ok(RunOutput {
+        compiler: build.stderr,
+        program,
+        success,
+    })
+}
+
+#[derive(Deserialize)]
+struct ExecuteResponse {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+/// Compile and run `code` on play.rust-lang.org (stable channel, debug mode).
+pub fn run_online(code: &str) -> Result<RunOutput, PlaygroundError> {
+    info!("Running playground snippet on {EXECUTE_URL}");
+    let body = json!({
+        "channel": "stable",
+        "mode": "debug",
+        "edition": EDITION,
+        "crateType": "bin",
+        "tests": false,
+        "backtrace": false,
+        "code": wrap_main(code),
+    });
+    let response = http::agent()
+        .post(EXECUTE_URL)
+        .send_json(body)
+        .and_then(|mut r| r.body_mut().read_json::<ExecuteResponse>())
+        .map_err(|e| PlaygroundError::Http(e.into()))?;
+    Ok(RunOutput {
+        compiler: response.stderr,
+        program: response.stdout,
+        success: response.success,
+    })
+}
+
+/// `code` as a complete program: wrapped in `fn main` unless it defines one.
+fn wrap_main(code: &str) -> String {
+    if code.contains("fn main") {
+        return code.to_string();
+    }
+    let body: Vec<String> = code.lines().map(|l| format!("    {l}")).collect();
+    format!("fn main() {{\n{}\n}}\n", body.join("\n"))
+}
+
+fn scratch_dir() -> PathBuf {
+    // Tests never touch the cache of the user.
+    if cfg!(test) {
+        return std::env::temp_dir()
+            .join(format!("rustm_test_{}_{CRATE_NAME}", std::process::id()));
+    }
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("rustm")
+        .join(CRATE_NAME)
+}
+
+/// Run `cmd`, killing it after `RUN_TIMEOUT`; returns its stdout + stderr and success.
+fn run_with_timeout(cmd: &mut Command) -> Result<(String, bool), PlaygroundError> {
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(PlaygroundError::Io)?;
+
+    // Drain the pipes while waiting so a chatty program cannot block on a full pipe.
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let out_reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf);
+        buf
+    });
+    let err_reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stderr.read_to_end(&mut buf);
+        buf
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(PlaygroundError::Io)? {
+            break Some(status);
+        }
+        if started.elapsed() > RUN_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let mut output = String::from_utf8_lossy(&out_reader.join().unwrap_or_default()).into_owned();
+    output.push_str(&String::from_utf8_lossy(
+        &err_reader.join().unwrap_or_default(),
+    ));
+    match status {
+        Some(status) => Ok((output, status.success())),
+        None => {
+            output.push_str(&format!(
+                "\n[killed after {} seconds]",
+                RUN_TIMEOUT.as_secs()
+            ));
+            Ok((output, false))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::FakeRunner;
+    use std::rc::Rc;
+
+    #[test]
+    fn snippets_without_main_are_wrapped() {
+        assert_eq!(
+            wrap_main("let x = 1;\nprintln!(\"{x}\");"),
+            "fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n"
+        );
+        let full = "fn main() {}\n";
+        assert_eq!(wrap_main(full), full);
+    }
+
+    #[test]
+    fn compiler_errors_are_returned_without_running() {
+        let fake = Rc::new(FakeRunner::default());
+        let _runner = process::replace(fake.clone());
+        fake.fail(101, "error[E0425]: cannot find value `y`\n");
+
+        let output = run_local("println!(\"{y}\");").unwrap();
+        assert!(!output.success);
+        assert_eq!(output.compiler, "error[E0425]: cannot find value `y`\n");
+        assert!(output.program.is_empty());
+        let calls = fake.calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(
+            calls[0].to_string(),
+            "cargo build --quiet --message-format short --color never"
+        );
+    }
+}
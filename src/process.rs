@@ -0,0 +1,276 @@
+//! External process execution.
+//!
+//! `cargo`, `rustc`, `hg`, `rg`, `cargo audit`, workspace generators and the
+//! other tools rustm runs to completion go through a `ProcessRunner`.
+//! `System` spawns them, inside WSL for WSL paths (see `crate::wsl`). Tests install a
+//! `FakeRunner` on their thread with `replace`: it records the command lines
+//! instead of running them and answers with canned results.
+//!
+//! `crate::git_ops` uses libgit2 and runs no process, so there is nothing to
+//! stub there. Desktop launchers (`crate::desktop`, `crate::editor`) and the
+//! playground's snippet, killed on a timeout, spawn their process directly.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::wsl;
+
+thread_local! {
+    /// Runner replacing `System` on this thread (see `replace`).
+    static RUNNER: RefCell<Option<Rc<dyn ProcessRunner>>> = const { RefCell::new(None) };
+}
+
+/// A program to run, with its arguments, directory and extra environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandLine {
+    pub program: String,
+    pub args: Vec<String>,
+    pub dir: PathBuf,
+    pub envs: Vec<(String, String)>,
+}
+
+impl CommandLine {
+    /// `program` without arguments, to run in `dir`.
+    pub fn new(program: &str, dir: &Path) -> Self {
+        Self {
+            program: program.to_string(),
+            args: Vec::new(),
+            dir: dir.to_path_buf(),
+            envs: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<S: Into<String>>(mut self, args: impl IntoIterator<Item = S>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn envs(mut self, envs: &[(&str, &str)]) -> Self {
+        self.envs
+            .extend(envs.iter().map(|(k, v)| (k.to_string(), v.to_string())));
+        self
+    }
+}
+
+/// `program arg "arg with spaces"`.
+impl fmt::Display for CommandLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.program)?;
+        for arg in &self.args {
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                write!(f, " {arg:?}")?;
+            } else {
+                write!(f, " {arg}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Result of a process that ran to completion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessOutput {
+    /// Exit code; `None` when the process was killed by a signal.
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl ProcessOutput {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// Runs command lines.
+pub trait ProcessRunner {
+    /// Run `cmd` to completion, capturing its output.
+    fn output(&self, cmd: &CommandLine) -> io::Result<ProcessOutput>;
+
+    /// Run `cmd` without input, passing each stdout/stderr line to `line` as it
+    /// arrives; returns the exit code.
+    fn stream(&self, cmd: &CommandLine, line: &dyn Fn(String)) -> io::Result<Option<i32>>;
+}
+
+/// Runs the real programs.
+pub struct System;
+
+impl System {
+    fn command(cmd: &CommandLine) -> std::process::Command {
+        let mut command = wsl::command(&cmd.program, &cmd.dir);
+        command.args(&cmd.args);
+        let envs: Vec<(&str, &str)> = cmd
+            .envs
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        command.envs(envs.iter().copied());
+        wsl::forward_env(&mut command, &cmd.dir, &envs);
+        command
+    }
+}
+
+impl ProcessRunner for System {
+    fn output(&self, cmd: &CommandLine) -> io::Result<ProcessOutput> {
+        let output = Self::command(cmd).output()?;
+        Ok(ProcessOutput {
+            code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+
+    fn stream(&self, cmd: &CommandLine, line: &dyn Fn(String)) -> io::Result<Option<i32>> {
+        let mut child = Self::command(cmd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let (tx, rx) = mpsc::channel();
+        let readers: Vec<_> = [
+            child
+                .stdout
+                .take()
+                .map(|o| Box::new(o) as Box<dyn Read + Send>),
+            child
+                .stderr
+                .take()
+                .map(|e| Box::new(e) as Box<dyn Read + Send>),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|stream| {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                    if tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            })
+        })
+        .collect();
+        // Only the reader threads hold senders now: the loop ends when both streams close.
+        drop(tx);
+        for received in rx {
+            line(received);
+        }
+        for reader in readers {
+            let _ = reader.join();
+        }
+        Ok(child.wait()?.code())
+    }
+}
+
+/// Call `f` with the runner of this thread: `System` unless replaced.
+pub fn with<R>(f: impl FnOnce(&dyn ProcessRunner) -> R) -> R {
+    match RUNNER.with(|r| r.borrow().clone()) {
+        Some(runner) => f(runner.as_ref()),
+        None => f(&System),
+    }
+}
+
+/// Use `runner` on this thread until the returned guard is dropped.
+#[cfg(test)]
+pub fn replace(runner: Rc<dyn ProcessRunner>) -> Replaced {
+    Replaced {
+        previous: RUNNER.with(|r| r.borrow_mut().replace(runner)),
+    }
+}
+
+/// Restores the previous runner of the thread when dropped.
+#[cfg(test)]
+pub struct Replaced {
+    previous: Option<Rc<dyn ProcessRunner>>,
+}
+
+#[cfg(test)]
+impl Drop for Replaced {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        RUNNER.with(|r| *r.borrow_mut() = previous);
+    }
+}
+
+/// Runner recording command lines and answering with queued results (a
+/// successful empty output once the queue is empty).
+#[cfg(test)]
+#[derive(Default)]
+pub struct FakeRunner {
+    calls: RefCell<Vec<CommandLine>>,
+    replies: RefCell<std::collections::VecDeque<io::Result<ProcessOutput>>>,
+}
+
+#[cfg(test)]
+impl FakeRunner {
+    /// Answer the next command with a success printing `stdout`.
+    pub fn succeed(&self, stdout: &str) {
+        self.reply(Ok(ProcessOutput {
+            code: Some(0),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        }));
+    }
+
+    /// Answer the next command with exit code `code` and `stderr`.
+    pub fn fail(&self, code: i32, stderr: &str) {
+        self.reply(Ok(ProcessOutput {
+            code: Some(code),
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+        }));
+    }
+
+    /// Answer the next command as if its program was not installed.
+    pub fn not_found(&self) {
+        self.reply(Err(io::ErrorKind::NotFound.into()));
+    }
+
+    pub fn reply(&self, reply: io::Result<ProcessOutput>) {
+        self.replies.borrow_mut().push_back(reply);
+    }
+
+    /// Command lines run so far, in order.
+    pub fn calls(&self) -> Vec<CommandLine> {
+        self.calls.borrow().clone()
+    }
+
+    fn next(&self, cmd: &CommandLine) -> io::Result<ProcessOutput> {
+        self.calls.borrow_mut().push(cmd.clone());
+        self.replies.borrow_mut().pop_front().unwrap_or_else(|| {
+            Ok(ProcessOutput {
+                code: Some(0),
+                ..ProcessOutput::default()
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+impl ProcessRunner for FakeRunner {
+    fn output(&self, cmd: &CommandLine) -> io::Result<ProcessOutput> {
+        self.next(cmd)
+    }
+
+    fn stream(&self, cmd: &CommandLine, line: &dyn Fn(String)) -> io::Result<Option<i32>> {
+        let output = self.next(cmd)?;
+        output
+            .stdout
+            .lines()
+            .chain(output.stderr.lines())
+            .for_each(|l| line(l.to_string()));
+        Ok(output.code)
+    }
+}
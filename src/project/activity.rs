@@ -0,0 +1,41 @@
+//! Recent git activity across projects.
+//!
+//! Reads the latest commits of every project repository and merges them into
+//! a single feed, newest first. Projects that are not git repositories are
+//! skipped silently; other git failures are logged and skipped.
+
+use std::path::PathBuf;
+
+use log::warn;
+
+use crate::git_ops::{self, CommitSummary, GitError};
+use crate::project::list::ProjectInfo;
+
+/// A commit and the project it belongs to.
+#[derive(Debug, Clone)]
+pub struct Activity {
+    pub project: String,
+    pub path: PathBuf,
+    pub commit: CommitSummary,
+}
+
+/// The `limit` most recent commits over all `projects`, newest first.
+pub fn collect(projects: &[ProjectInfo], limit: usize, log: &dyn Fn(String)) -> Vec<Activity> {
+    let mut feed = Vec::new();
+    for project in projects {
+        log(format!("Reading {}...", project.name));
+        // No project can contribute more than `limit` entries to the merged feed.
+        match git_ops::recent_commits(&project.path, limit) {
+            Ok(commits) => feed.extend(commits.into_iter().map(|commit| Activity {
+                project: project.name.clone(),
+                path: project.path.clone(),
+                commit,
+            })),
+            Err(GitError::NotARepository(_)) => {}
+            Err(e) => warn!("Skipping activity of {}: {e}", project.path.display()),
+        }
+    }
+    feed.sort_by_key(|a| std::cmp::Reverse(a.commit.time));
+    feed.truncate(limit);
+    feed
+}
@@ -0,0 +1,271 @@
+//! Project archiving.
+//!
+//! Archived projects live in the `archive/` directory of the projects
+//! directory, which discovery skips (see `project::list`): either moved there
+//! as is, or compressed to `archive/<name>.tar.gz` with the system `tar`
+//! (shipped with Windows 10 and later too). Restoring brings a project back to
+//! the top of the projects directory; purging deletes it for good. The
+//! metadata cache is left to callers, as in `project::manage`.
+
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use log::info;
+
+use crate::cargo_ops::{self, CargoError};
+use crate::dir_walk;
+
+/// Directory of the projects directory holding the archived projects.
+pub const ARCHIVE_DIR: &str = "archive";
+
+const TAR_SUFFIX: &str = ".tar.gz";
+
+/// How a project is archived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Moved to `archive/<name>`.
+    Directory,
+    /// Compressed to `archive/<name>.tar.gz`.
+    TarGz,
+}
+
+/// An archived project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Archived {
+    pub name: String,
+    /// The directory or the tarball.
+    pub path: PathBuf,
+    pub format: Format,
+    /// Bytes on disk.
+    pub size: u64,
+}
+
+#[derive(Debug)]
+pub enum ArchiveError {
+    /// The path does not look like a cargo project; refuse to touch it.
+    NotAProject(PathBuf),
+    /// The project is not inside the projects directory.
+    OutsideProjects(PathBuf),
+    TargetExists(PathBuf),
+    Tar(CargoError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAProject(p) => write!(f, "{} has no Cargo.toml", p.display()),
+            Self::OutsideProjects(p) => {
+                write!(f, "{} is not in the projects directory", p.display())
+            }
+            Self::TargetExists(p) => write!(f, "{} already exists", p.display()),
+            Self::Tar(e) => write!(f, "tar failed: {e}"),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Tar(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Archive the project at `path`, below the projects directory `root`; returns where it went.
+pub fn archive(root: &Path, path: &Path, format: Format) -> Result<PathBuf, ArchiveError> {
+    if !path.join("Cargo.toml").is_file() {
+        return Err(ArchiveError::NotAProject(path.to_path_buf()));
+    }
+    let relative = path
+        .strip_prefix(root)
+        .ok()
+        .filter(|r| r.components().all(|c| matches!(c, Component::Normal(_))))
+        .filter(|r| !r.starts_with(ARCHIVE_DIR) && r.components().next().is_some())
+        .ok_or_else(|| ArchiveError::OutsideProjects(path.to_path_buf()))?;
+    let name = file_name(path);
+    let archive_dir = root.join(ARCHIVE_DIR);
+    fs::create_dir_all(&archive_dir)?;
+
+    let target = match format {
+        Format::Directory => archive_dir.join(&name),
+        Format::TarGz => archive_dir.join(format!("{name}{TAR_SUFFIX}")),
+    };
+    if target.exists() {
+        return Err(ArchiveError::TargetExists(target));
+    }
+    match format {
+        Format::Directory => fs::rename(path, &target)?,
+        Format::TarGz => {
+            // Relative paths only: under WSL, tar runs inside the distribution.
+            let parent = Path::new("..").join(relative.parent().unwrap_or(Path::new("")));
+            let parent = parent.to_string_lossy().replace('\\', "/");
+            let tarball = format!("{name}{TAR_SUFFIX}");
+            let created = cargo_ops::run_tool(
+                "tar",
+                &archive_dir,
+                &["-czf", &tarball, "-C", &parent, &name],
+                &[],
+            );
+            if let Err(e) = created {
+                let _ = fs::remove_file(&target);
+                return Err(ArchiveError::Tar(e));
+            }
+            fs::remove_dir_all(path)?;
+        }
+    }
+    info!("Archived {} to {}", path.display(), target.display());
+    Ok(target)
+}
+
+/// Projects archived in the projects directory `root`, by name.
+pub fn list(root: &Path) -> Result<Vec<Archived>, ArchiveError> {
+    let archive_dir = root.join(ARCHIVE_DIR);
+    if !archive_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut archived = Vec::new();
+    for entry in fs::read_dir(&archive_dir)? {
+        let path = entry?.path();
+        let file_name = file_name(&path);
+        if path.join("Cargo.toml").is_file() {
+            archived.push(Archived {
+                name: file_name,
+                path,
+                format: Format::Directory,
+                size: 0,
+            });
+        } else if let Some(name) = file_name.strip_suffix(TAR_SUFFIX)
+            && path.is_file()
+        {
+            archived.push(Archived {
+                name: name.to_string(),
+                size: path.metadata().map_or(0, |m| m.len()),
+                path,
+                format: Format::TarGz,
+            });
+        }
+    }
+    let dirs: Vec<PathBuf> = archived
+        .iter()
+        .filter(|a| a.format == Format::Directory)
+        .map(|a| a.path.clone())
+        .collect();
+    let mut sizes = dir_walk::sizes(&dirs).into_iter();
+    for a in archived
+        .iter_mut()
+        .filter(|a| a.format == Format::Directory)
+    {
+        a.size = sizes.next().unwrap_or(0);
+    }
+    archived.sort_by_key(|a| a.name.to_lowercase());
+    Ok(archived)
+}
+
+/// Bring `archived` back to the projects directory `root`; returns the project path.
+pub fn restore(root: &Path, archived: &Archived) -> Result<PathBuf, ArchiveError> {
+    let target = root.join(&archived.name);
+    if target.exists() {
+        return Err(ArchiveError::TargetExists(target));
+    }
+    match archived.format {
+        Format::Directory => fs::rename(&archived.path, &target)?,
+        Format::TarGz => {
+            let tarball = file_name(&archived.path);
+            cargo_ops::run_tool(
+                "tar",
+                &root.join(ARCHIVE_DIR),
+                &["-xzf", &tarball, "-C", ".."],
+                &[],
+            )
+            .map_err(ArchiveError::Tar)?;
+            if !target.join("Cargo.toml").is_file() {
+                return Err(ArchiveError::NotAProject(target));
+            }
+            fs::remove_file(&archived.path)?;
+        }
+    }
+    info!(
+        "Restored {} to {}",
+        archived.path.display(),
+        target.display()
+    );
+    Ok(target)
+}
+
+/// Delete `archived` for good.
+pub fn purge(archived: &Archived) -> Result<(), ArchiveError> {
+    match archived.format {
+        Format::Directory => fs::remove_dir_all(&archived.path)?,
+        Format::TarGz => fs::remove_file(&archived.path)?,
+    }
+    info!("Purged {}", archived.path.display());
+    Ok(())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn archives_restores_and_purges_directories() {
+        let root = TempDir::new("archive");
+        let project = root.join("group/old");
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::write(project.join("Cargo.toml"), "[package]\nname = \"old\"\n").unwrap();
+        fs::write(project.join("src/main.rs"), "fn main() {}\n").unwrap();
+
+        let archived_path = archive(&root, &project, Format::Directory).unwrap();
+        assert_eq!(archived_path, root.join("archive/old"));
+        assert!(!project.exists());
+        assert!(matches!(
+            archive(&root, &archived_path, Format::Directory),
+            Err(ArchiveError::OutsideProjects(_))
+        ));
+
+        let archived = list(&root).unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(
+            (archived[0].name.as_str(), archived[0].format),
+            ("old", Format::Directory)
+        );
+        assert!(archived[0].size > 0);
+
+        let restored = restore(&root, &archived[0]).unwrap();
+        assert_eq!(restored, root.join("old"));
+        assert!(restored.join("src/main.rs").is_file());
+        assert!(list(&root).unwrap().is_empty());
+
+        if cargo_ops::tool_available("tar") {
+            let nested = root.join("group/old");
+            fs::rename(&restored, &nested).unwrap();
+            let tarball = archive(&root, &nested, Format::TarGz).unwrap();
+            assert_eq!(tarball, root.join("archive/old.tar.gz"));
+            assert!(!nested.exists());
+            let restored = restore(&root, &list(&root).unwrap()[0]).unwrap();
+            assert!(restored.join("src/main.rs").is_file());
+            assert!(!tarball.exists());
+        }
+
+        archive(&root, &restored, Format::Directory).unwrap();
+        purge(&list(&root).unwrap()[0]).unwrap();
+        assert!(list(&root).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,311 @@
+//! Security audit through `cargo audit`.
+//!
+//! Runs `cargo audit --json` against the project's `Cargo.lock` and turns the
+//! report into a flat list of findings: vulnerabilities plus informational
+//! advisories (unmaintained, unsound) and yanked crates. cargo-audit only
+//! reports the CVSS vector of an advisory, so the severity is derived from its
+//! CVSS 3.x base score.
+
+use std::fmt;
+use std::path::Path;
+
+use log::info;
+use serde_json::Value;
+
+use crate::process::{self, CommandLine};
+
+#[derive(Debug)]
+pub enum AuditError {
+    /// `cargo audit` is not installed.
+    NotInstalled,
+    /// No `Cargo.lock`; auditing would have to create one.
+    NoLockfile,
+    Io(std::io::Error),
+    Failed(String),
+    Parse(serde_json::Error),
+}
+
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotInstalled => {
+                write!(
+                    f,
+                    "cargo-audit is not installed (cargo install cargo-audit)"
+                )
+            }
+            Self::NoLockfile => write!(f, "No Cargo.lock (run cargo generate-lockfile)"),
+            Self::Io(e) => write!(f, "Cannot run cargo audit: {e}"),
+            Self::Failed(msg) => write!(f, "cargo audit failed: {msg}"),
+            Self::Parse(e) => write!(f, "Unexpected cargo audit output: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for AuditError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Qualitative CVSS 3.x severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    None,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    fn from_score(score: f64) -> Self {
+        match score {
+            s if s >= 9.0 => Self::Critical,
+            s if s >= 7.0 => Self::High,
+            s if s >= 4.0 => Self::Medium,
+            s if s > 0.0 => Self::Low,
+            _ => Self::None,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::None => "none",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// One problem reported for a locked package.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// Advisory id (`RUSTSEC-...`), `None` for yanked crates.
+    pub id: Option<String>,
+    /// `vulnerability`, `unmaintained`, `unsound`, `yanked`, ...
+    pub kind: String,
+    pub package: String,
+    pub version: String,
+    pub title: String,
+    /// CVSS base score, when the advisory has a vector.
+    pub score: Option<f64>,
+    pub patched: Vec<String>,
+}
+
+impl Finding {
+    pub fn severity(&self) -> Option<Severity> {
+        self.score.map(Severity::from_score)
+    }
+
+    /// Page of the advisory on rustsec.org.
+    pub fn url(&self) -> Option<String> {
+        self.id
+            .as_ref()
+            .map(|id| format!("https://rustsec.org/advisories/{id}"))
+    }
+}
+
+/// Audit the project at `project_path`; findings are sorted by decreasing score.
+pub fn audit(project_path: &Path) -> Result<Vec<Finding>, AuditError> {
+    if !project_path.join("Cargo.lock").is_file() {
+        return Err(AuditError::NoLockfile);
+    }
+    info!("Running cargo audit in {}", project_path.display());
+    let cmd = CommandLine::new("cargo", project_path).args(["audit", "--json"]);
+    let output = process::with(|runner| runner.output(&cmd)).map_err(AuditError::Io)?;
+    // Exit code 1 with a report means "vulnerabilities found".
+    if output.stdout.trim().is_empty() {
+        let stderr = output.stderr.trim();
+        if stderr.contains("no such command") {
+            return Err(AuditError::NotInstalled);
+        }
+        return Err(AuditError::Failed(stderr.to_string()));
+    }
+    parse(&output.stdout)
+}
+
+/// Findings of a `cargo audit --json` report.
+fn parse(json: &str) -> Result<Vec<Finding>, AuditError> {
+    let report: Value = serde_json::from_str(json).map_err(AuditError::Parse)?;
+    let mut findings: Vec<Finding> = report["vulnerabilities"]["list"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|v| finding(v, "vulnerability"))
+        .collect();
+    if let Some(warnings) = report["warnings"].as_object() {
+        for (kind, list) in warnings {
+            for w in list.as_array().into_iter().flatten() {
+                findings.push(finding(w, w["kind"].as_str().unwrap_or(kind)));
+            }
+        }
+    }
+    findings.sort_by(|a, b| b.score.unwrap_or(-1.0).total_cmp(&a.score.unwrap_or(-1.0)));
+    Ok(findings)
+}
+
+fn finding(entry: &Value, kind: &str) -> Finding {
+    let advisory = &entry["advisory"];
+    let string = |v: &Value| v.as_str().unwrap_or_default().to_string();
+    Finding {
+        id: advisory["id"].as_str().map(ToString::to_string),
+        kind: kind.to_string(),
+        package: string(&entry["package"]["name"]),
+        version: string(&entry["package"]["version"]),
+        title: advisory["title"]
+            .as_str()
+            .map_or_else(|| kind.to_string(), ToString::to_string),
+        score: advisory["cvss"].as_str().and_then(cvss_score),
+        patched: entry["versions"]["patched"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(string)
+            .collect(),
+    }
+}
+
+/// CVSS 3.x base score of a vector such as `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`.
+fn cvss_score(vector: &str) -> Option<f64> {
+    let metric = |name: &str| {
+        vector
+            .split('/')
+            .find_map(|part| part.strip_prefix(name)?.strip_prefix(':'))
+    };
+    let changed = metric("S")? == "C";
+    let av = match metric("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match metric("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let pr = match (metric("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match metric("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let cia = |name: &str| match metric(name)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+    let iss = 1.0 - (1.0 - cia("C")?) * (1.0 - cia("I")?) * (1.0 - cia("A")?);
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02f64).powi(15)
+    } else {
+        6.42 * iss
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let exploitability = 8.22 * av * ac * pr * ui;
+    let base = if changed {
+        1.08 * (impact + exploitability)
+    } else {
+        impact + exploitability
+    };
+    Some(round_up(base.min(10.0)))
+}
+
+/// CVSS 3.1 "Roundup": smallest one-decimal number not below `value`.
+fn round_up(value: f64) -> f64 {
+    let scaled = (value * 100_000.0).round() as i64;
+    if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        (scaled / 10_000 + 1) as f64 / 10.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::FakeRunner;
+    use crate::temp_dir::TempDir;
+    use std::rc::Rc;
+
+    #[test]
+    fn cvss_vectors_score_like_the_specification() {
+        assert_eq!(
+            cvss_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+            Some(9.8)
+        );
+        assert_eq!(
+            cvss_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:H"),
+            Some(7.5)
+        );
+        assert_eq!(
+            cvss_score("CVSS:3.1/AV:N/AC:L/PR:L/UI:N/S:C/C:L/I:L/A:N"),
+            Some(6.4)
+        );
+        assert_eq!(cvss_score("garbage"), None);
+    }
+
+    #[test]
+    fn parses_vulnerabilities_and_warnings() {
+        let json = r#"{
+          "vulnerabilities": {"found": true, "count": 1, "list": [{
+            "advisory": {"id": "RUSTSEC-2024-0001", "title": "Overflow", "cvss": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:H"},
+            "versions": {"patched": [">=1.2.3"]},
+            "package": {"name": "foo", "version": "1.2.0"}
+          }]},
+          "warnings": {"unmaintained": [{
+            "kind": "unmaintained",
+            "advisory": {"id": "RUSTSEC-2023-0002", "title": "bar is unmaintained", "cvss": null},
+            "versions": null,
+            "package": {"name": "bar", "version": "0.1.0"}
+          }]}
+        }"#;
+
+        let findings = parse(json).unwrap();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].id.as_deref(), Some("RUSTSEC-2024-0001"));
+        assert_eq!(findings[0].severity(), Some(Severity::High));
+        assert_eq!(findings[0].patched, vec![">=1.2.3"]);
+        assert_eq!(findings[1].kind, "unmaintained");
+        assert_eq!(findings[1].severity(), None);
+    }
+
+    #[test]
+    fn runs_cargo_audit_and_reports_it_missing() {
+        let project = TempDir::new("audit");
+        std::fs::write(project.join("Cargo.lock"), "").unwrap();
+        let fake = Rc::new(FakeRunner::default());
+        let _runner = process::replace(fake.clone());
+
+        fake.fail(101, "error: no such command: `audit`");
+        assert!(matches!(audit(&project), Err(AuditError::NotInstalled)));
+        fake.fail(1, r#"{"vulnerabilities": {"list": []}}"#);
+        fake.succeed(r#"{"vulnerabilities": {"list": []}}"#);
+        assert!(matches!(audit(&project), Err(AuditError::Failed(_))));
+        assert!(audit(&project).unwrap().is_empty());
+        let calls = fake.calls();
+        assert_eq!(calls[0].to_string(), "cargo audit --json");
+        assert_eq!(calls[0].dir, project.path());
+    }
+}
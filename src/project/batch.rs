@@ -0,0 +1,137 @@
+//! Operations applied to many projects at once: the marked projects of the
+//! TUI (see `ui::batch`) and `rustm batch`.
+//!
+//! Each operation reports a short note per project, or why it failed; a
+//! failure never stops the others. None of them runs project code, so
+//! untrusted projects are included.
+
+use std::path::Path;
+
+use crate::git_ops::{self, PullOutcome};
+use crate::metadata;
+use crate::project::archive::{self, Format};
+use crate::project::clean;
+use crate::project::list::ProjectInfo;
+
+/// An operation on one project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Delete the build artifacts (see `project::clean`).
+    Clean,
+    /// `git fetch` the upstream's remote.
+    Fetch,
+    /// `git pull`, fast-forward only.
+    Pull,
+    /// Move to the archive (see `project::archive`).
+    Archive(Format),
+}
+
+impl Operation {
+    /// `Cleaning`, for progress lines and report titles.
+    pub const fn verb(self) -> &'static str {
+        match self {
+            Self::Clean => "Cleaning",
+            Self::Fetch => "Fetching",
+            Self::Pull => "Pulling",
+            Self::Archive(_) => "Archiving",
+        }
+    }
+
+    /// Apply to `project`, a project of the projects directory `root`: a short
+    /// note, or the error. Git progress goes to `log`.
+    pub fn apply(
+        self,
+        root: &Path,
+        project: &ProjectInfo,
+        log: &dyn Fn(String),
+    ) -> Result<String, String> {
+        match self {
+            Self::Clean => clean::clean(&project.path)
+                .map(|()| "cleaned".to_string())
+                .map_err(|e| e.to_string()),
+            Self::Fetch => git_ops::fetch(&project.path, log)
+                .map(|()| "fetched".to_string())
+                .map_err(|e| e.to_string()),
+            Self::Pull => match git_ops::pull(&project.path, log) {
+                Ok(PullOutcome::UpToDate) => Ok("up to date".to_string()),
+                Ok(PullOutcome::FastForwarded) => Ok("fast-forwarded".to_string()),
+                Err(e) => Err(e.to_string()),
+            },
+            Self::Archive(format) => {
+                let target =
+                    archive::archive(root, &project.path, format).map_err(|e| e.to_string())?;
+                metadata::update(|store| match format {
+                    Format::Directory => store.rename(&project.path, &target),
+                    Format::TarGz => store.remove(&project.path),
+                });
+                Ok(format!("archived to {}", target.display()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+    use std::fs;
+
+    fn project(root: &Path, name: &str) -> ProjectInfo {
+        let path = root.join(name);
+        fs::create_dir_all(path.join("target/debug")).unwrap();
+        fs::write(path.join("target/CACHEDIR.TAG"), "Signature").unwrap();
+        fs::write(
+            path.join("Cargo.toml"),
+            format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n"),
+        )
+        .unwrap();
+        ProjectInfo {
+            name: name.to_string(),
+            path,
+            has_uncommitted_changes: false,
+            branch: None,
+            ahead_behind: None,
+            diverged_default: None,
+            rollup: None,
+            no_std: None,
+            version: Some("0.1.0".into()),
+            kind: None,
+            last_commit: None,
+            remote_url: None,
+            fingerprint: 0,
+            pinned: false,
+            last_opened: None,
+            tags: Vec::new(),
+            untrusted: false,
+        }
+    }
+
+    #[test]
+    fn cleans_and_archives_projects() {
+        let root = TempDir::new("batch");
+        let log = |_: String| {};
+
+        let built = project(&root, "built");
+        assert_eq!(
+            Operation::Clean.apply(&root, &built, &log).as_deref(),
+            Ok("cleaned")
+        );
+        assert!(!built.path.join("target").exists());
+        assert!(built.path.join("Cargo.toml").is_file());
+
+        let old = project(&root, "old");
+        let archived = root.join(archive::ARCHIVE_DIR).join("old");
+        assert_eq!(
+            Operation::Archive(Format::Directory).apply(&root, &old, &log),
+            Ok(format!("archived to {}", archived.display()))
+        );
+        assert!(!old.path.exists());
+        assert!(archived.join("Cargo.toml").is_file());
+        // The archived copy is not a project of the projects directory anymore.
+        assert!(
+            Operation::Archive(Format::Directory)
+                .apply(&root, &old, &log)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Changelog parsing.
+//!
+//! Extracts the section describing one version from a Markdown `CHANGELOG.md`
+//! (Keep a Changelog style or similar): the section starts at the first heading
+//! mentioning the version and ends at the next heading of the same or a higher level.
+
+use std::fs;
+use std::path::Path;
+
+/// File names probed, in order.
+const CHANGELOG_FILES: [&str; 3] = ["CHANGELOG.md", "Changelog.md", "changelog.md"];
+
+/// Release notes for `version` (leading `v` ignored) from the project's changelog.
+pub fn notes_for(project_path: &Path, version: &str) -> Option<String> {
+    let text = CHANGELOG_FILES
+        .iter()
+        .find_map(|f| fs::read_to_string(project_path.join(f)).ok())?;
+    section_for(&text, version)
+}
+
+/// Body of the section whose heading mentions `version`, without the heading itself.
+pub fn section_for(text: &str, version: &str) -> Option<String> {
+    let version = version.trim_start_matches('v');
+    let mut lines = text.lines();
+
+    let level = lines.by_ref().find_map(|line| {
+        let level = heading_level(line)?;
+        mentions_version(line, version).then_some(level)
+    })?;
+
+    let body: Vec<&str> = lines
+        .take_while(|line| heading_level(line).is_none_or(|l| l > level))
+        .collect();
+    let body = body.join("\n").trim().to_string();
+    (!body.is_empty()).then_some(body)
+}
+
+fn heading_level(line: &str) -> Option<usize> {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    (hashes > 0 && line[hashes..].starts_with(' ')).then_some(hashes)
+}
+
+/// `true` when `version` appears in `line` as a whole token (`[1.2.0]` matches, `1.2.0-rc1` does not).
+fn mentions_version(line: &str, version: &str) -> bool {
+    line.split(|c: char| c.is_whitespace() || matches!(c, '[' | ']' | '(' | ')'))
+        .any(|token| token.trim_start_matches('v') == version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog
+
+## [Unreleased]
+- wip
+
+## [1.2.0] - 2025-01-01
+### Added
+- feature A
+
+### Fixed
+- bug B
+
+## [1.1.0] - 2024-06-01
+- older
+";
+
+    #[test]
+    fn extracts_version_section_including_subheadings() {
+        let notes = section_for(CHANGELOG, "v1.2.0").unwrap();
+        assert_eq!(notes, "### Added\n- feature A\n\n### Fixed\n- bug B");
+    }
+
+    #[test]
+    fn unknown_version_yields_none() {
+        assert_eq!(section_for(CHANGELOG, "2.0.0"), None);
+        assert_eq!(section_for(CHANGELOG, "1.2"), None);
+    }
+}
@@ -0,0 +1,229 @@
+//! Apply a commit of a project onto another branch of the same repository.
+//!
+//! The target is either a branch checked out in a worktree (the project itself
+//! or another worktree of its repository), where `git cherry-pick -x` runs
+//! directly, or a branch checked out nowhere, which gets a temporary worktree
+//! for the time of the cherry-pick. The git CLI does the work.
+//!
+//! A cherry-pick that stops (conflicts, nothing left to apply) is aborted: the
+//! target is left as it was and the conflicting files are reported.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::cargo_ops;
+use crate::git_ops::{self, GitError};
+use crate::project::list::scan_git_status;
+
+/// Where to apply the commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Whatever is checked out in the worktree at `path` (`branch` is `None` when detached).
+    Worktree {
+        path: PathBuf,
+        branch: Option<String>,
+    },
+    /// A local branch checked out nowhere.
+    Branch(String),
+}
+
+impl Target {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Worktree { path, branch } => format!(
+                "{}  (worktree {})",
+                branch.as_deref().unwrap_or("detached HEAD"),
+                path.display()
+            ),
+            Self::Branch(name) => name.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CherryPickError {
+    /// Uncommitted changes in the target worktree.
+    Dirty(PathBuf),
+    /// The cherry-pick stopped on conflicts in these files and was aborted.
+    Conflicts(Vec<String>),
+    Git(GitError),
+    Cli(String),
+}
+
+impl fmt::Display for CherryPickError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dirty(path) => write!(
+                f,
+                "Commit or stash the uncommitted changes in {} first",
+                path.display()
+            ),
+            Self::Conflicts(files) => write!(
+                f,
+                "Conflicts, the cherry-pick was aborted and nothing changed:\n{}",
+                files.join("\n")
+            ),
+            Self::Git(e) => write!(f, "{e}"),
+            Self::Cli(msg) => write!(f, "git failed: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for CherryPickError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Git(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Possible targets for a commit of the repository at `path`: its worktrees
+/// (this one first), then the local branches checked out nowhere.
+pub fn targets(path: &Path) -> Result<Vec<Target>, CherryPickError> {
+    let porcelain = git(path, &["worktree", "list", "--porcelain"])?;
+    let mut worktrees = parse_worktrees(&porcelain);
+    let here = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    worktrees.sort_by_key(|t| match t {
+        Target::Worktree { path, .. } => path.canonicalize().ok() != Some(here.clone()),
+        Target::Branch(_) => true,
+    });
+
+    let checked_out: Vec<String> = worktrees
+        .iter()
+        .filter_map(|t| match t {
+            Target::Worktree { branch, .. } => branch.clone(),
+            Target::Branch(_) => None,
+        })
+        .collect();
+    let branches = git_ops::local_branches(path).map_err(CherryPickError::Git)?;
+    worktrees.extend(
+        branches
+            .into_iter()
+            .filter(|b| !checked_out.contains(b))
+            .map(Target::Branch),
+    );
+    Ok(worktrees)
+}
+
+/// Worktrees of `git worktree list --porcelain` output (bare entries skipped).
+fn parse_worktrees(porcelain: &str) -> Vec<Target> {
+    porcelain
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut path = None;
+            let mut branch = None;
+            for line in block.lines() {
+                if line == "bare" {
+                    return None;
+                } else if let Some(p) = line.strip_prefix("worktree ") {
+                    path = Some(PathBuf::from(p));
+                } else if let Some(b) = line.strip_prefix("branch refs/heads/") {
+                    branch = Some(b.to_string());
+                }
+            }
+            Some(Target::Worktree {
+                path: path?,
+                branch,
+            })
+        })
+        .collect()
+}
+
+/// Cherry-pick `commit` of the repository at `path` onto `target`.
+pub fn apply(
+    path: &Path,
+    commit: &str,
+    target: &Target,
+    log: &dyn Fn(String),
+) -> Result<(), CherryPickError> {
+    match target {
+        Target::Worktree { path: worktree, .. } => {
+            if scan_git_status(worktree).map_err(|e| CherryPickError::Git(e.into()))? {
+                return Err(CherryPickError::Dirty(worktree.clone()));
+            }
+            cherry_pick(worktree, commit, log)
+        }
+        Target::Branch(branch) => {
+            let nonce = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos());
+            let worktree = std::env::temp_dir().join(format!("rustm-cherry-pick-{nonce}"));
+            let worktree_arg = worktree.to_string_lossy();
+            log(format!("git worktree add {worktree_arg} {branch}"));
+            git(path, &["worktree", "add", "--quiet", &worktree_arg, branch])?;
+            let result = cherry_pick(&worktree, commit, log);
+            if let Err(e) = git(path, &["worktree", "remove", "--force", &worktree_arg]) {
+                log(format!("Cannot remove the temporary worktree: {e}"));
+            }
+            result
+        }
+    }
+}
+
+fn cherry_pick(worktree: &Path, commit: &str, log: &dyn Fn(String)) -> Result<(), CherryPickError> {
+    log(format!("git cherry-pick -x {commit}"));
+    let Err(e) = git(worktree, &["cherry-pick", "-x", commit]) else {
+        return Ok(());
+    };
+    let conflicts = git(worktree, &["diff", "--name-only", "--diff-filter=U"]).unwrap_or_default();
+    log("Aborting the cherry-pick...".to_string());
+    let _ = git(worktree, &["cherry-pick", "--abort"]);
+    let files: Vec<String> = conflicts.lines().map(str::to_string).collect();
+    if files.is_empty() {
+        Err(e)
+    } else {
+        Err(CherryPickError::Conflicts(files))
+    }
+}
+
+fn git(path: &Path, args: &[&str]) -> Result<String, CherryPickError> {
+    cargo_ops::run_tool("git", path, args, &[]).map_err(|e| CherryPickError::Cli(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+    use std::fs;
+
+    fn commit(dir: &Path, file: &str, content: &str) -> String {
+        fs::write(dir.join(file), content).unwrap();
+        git(dir, &["add", file]).unwrap();
+        git(dir, &["commit", "-q", "-m", file]).unwrap();
+        git(dir, &["rev-parse", "HEAD"]).unwrap().trim().to_string()
+    }
+
+    #[test]
+    fn applies_onto_branch_and_aborts_on_conflicts() {
+        let dir = TempDir::new("cherry_pick");
+        git(&dir, &["init", "-q", "-b", "main"]).unwrap();
+        git(&dir, &["config", "user.name", "Test"]).unwrap();
+        git(&dir, &["config", "user.email", "test@example.com"]).unwrap();
+        commit(&dir, "shared", "base");
+        git(&dir, &["checkout", "-q", "-b", "feature"]).unwrap();
+        let fix = commit(&dir, "fix", "fix");
+        let clash = commit(&dir, "shared", "feature");
+        git(&dir, &["checkout", "-q", "main"]).unwrap();
+        commit(&dir, "shared", "main");
+        git(&dir, &["checkout", "-q", "feature"]).unwrap();
+
+        let found = targets(&dir).unwrap();
+        assert!(matches!(&found[0], Target::Worktree { branch: Some(b), .. } if b == "feature"));
+        let main = Target::Branch("main".into());
+        assert_eq!(&found[1..], std::slice::from_ref(&main));
+
+        apply(&dir, &fix, &main, &|_| {}).unwrap();
+        let files = git(&dir, &["ls-tree", "--name-only", "main"]).unwrap();
+        assert!(files.lines().any(|f| f == "fix"));
+
+        let head = git(&dir, &["rev-parse", "main"]).unwrap();
+        match apply(&dir, &clash, &main, &|_| {}) {
+            Err(CherryPickError::Conflicts(files)) => assert_eq!(files, ["shared"]),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert_eq!(git(&dir, &["rev-parse", "main"]).unwrap(), head);
+        assert_eq!(targets(&dir).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,80 @@
+//! Build artifact cleanup.
+//!
+//! Deletes the `target/` directory of projects, which is what `cargo clean`
+//! does for the default target directory, without starting cargo once per
+//! project. Only directories cargo created are deleted: they hold a
+//! `CACHEDIR.TAG` or `.rustc_info.json` file (a source directory that happens
+//! to be called `target` is left alone).
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use log::info;
+
+/// Files cargo writes at the root of a target directory.
+const CARGO_MARKERS: [&str; 2] = ["CACHEDIR.TAG", ".rustc_info.json"];
+
+/// Errors raised while cleaning a project.
+#[derive(Debug)]
+pub enum CleanError {
+    /// `target/` exists but was not created by cargo.
+    NotABuildDir,
+    Io(io::Error),
+}
+
+impl fmt::Display for CleanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotABuildDir => write!(f, "target/ was not created by cargo; left alone"),
+            Self::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for CleanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::NotABuildDir => None,
+        }
+    }
+}
+
+/// Delete the `target/` directory of the project at `project_path`; nothing to do without one.
+pub fn clean(project_path: &Path) -> Result<(), CleanError> {
+    let target = project_path.join("target");
+    if !target.is_dir() {
+        return Ok(());
+    }
+    if !CARGO_MARKERS.iter().any(|m| target.join(m).is_file()) {
+        return Err(CleanError::NotABuildDir);
+    }
+    fs::remove_dir_all(&target).map_err(CleanError::Io)?;
+    info!("Removed {}", target.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn removes_only_cargo_target_dirs() {
+        let root = TempDir::new("clean");
+        let built = root.join("built");
+        fs::create_dir_all(built.join("target/debug")).unwrap();
+        fs::write(built.join("target/CACHEDIR.TAG"), "Signature").unwrap();
+        let sources = root.join("sources");
+        fs::create_dir_all(sources.join("target")).unwrap();
+        fs::write(sources.join("target/mod.rs"), "").unwrap();
+
+        clean(&built).unwrap();
+        assert!(!built.join("target").exists());
+        assert!(matches!(clean(&sources), Err(CleanError::NotABuildDir)));
+        assert!(sources.join("target/mod.rs").exists());
+        clean(&root.join("missing")).unwrap();
+    }
+}
@@ -0,0 +1,330 @@
+//! Clippy diagnostics of a project.
+//!
+//! Runs `cargo clippy --all-targets --message-format=json` and keeps the
+//! errors and warnings of the compiler messages, grouped by file. Notes and
+//! help are part of the rendering of their diagnostic; the closing summaries
+//! ("aborting due to 2 previous errors"), which point at no source, are
+//! dropped. With `--all-targets`, a diagnostic of code shared by several
+//! targets is reported once per target: duplicates are merged.
+//!
+//! The counts of the last run are remembered in the metadata cache and shown in
+//! the project detail.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cargo_ops::{self, CargoError};
+use crate::metadata;
+
+const ARGS: [&str; 3] = ["clippy", "--all-targets", "--message-format=json"];
+
+#[derive(Debug)]
+pub enum ClippyError {
+    /// The clippy component is not installed.
+    NotInstalled,
+    Cargo(CargoError),
+    /// cargo failed without reporting a diagnostic (broken manifest, ...).
+    Failed(String),
+}
+
+impl fmt::Display for ClippyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotInstalled => {
+                write!(f, "Clippy is not installed (rustup component add clippy)")
+            }
+            Self::Cargo(e) => write!(f, "{e}"),
+            Self::Failed(msg) => write!(f, "cargo clippy failed: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ClippyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Cargo(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<CargoError> for ClippyError {
+    fn from(e: CargoError) -> Self {
+        Self::Cargo(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// One error or warning, at its primary location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub level: Level,
+    /// Lint or error code: `clippy::needless_return`, `unused_variables`, `E0308`.
+    pub code: Option<String>,
+    pub message: String,
+    pub line: u64,
+    pub column: u64,
+    /// The compiler's rendering, with the source excerpt, notes and suggestions.
+    pub rendered: String,
+}
+
+/// Diagnostics of one file, by position; `file` is relative to the project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiagnostics {
+    pub file: PathBuf,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Counts of a clippy run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintSummary {
+    pub errors: usize,
+    pub warnings: usize,
+    /// Seconds since epoch of the run.
+    pub at: u64,
+}
+
+impl LintSummary {
+    /// `2 errors, 5 warnings` / `1 warning` / `no warnings`.
+    pub fn counts(&self) -> String {
+        let plural = |n: usize, what: &str| format!("{n} {what}{}", if n == 1 { "" } else { "s" });
+        match (self.errors, self.warnings) {
+            (0, 0) => "no warnings".to_string(),
+            (0, warnings) => plural(warnings, "warning"),
+            (errors, 0) => plural(errors, "error"),
+            (errors, warnings) => format!(
+                "{}, {}",
+                plural(errors, "error"),
+                plural(warnings, "warning")
+            ),
+        }
+    }
+}
+
+/// Diagnostics of a run, by file path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintReport {
+    pub files: Vec<FileDiagnostics>,
+    pub summary: LintSummary,
+}
+
+/// Run clippy on the project at `path` and remember the counts.
+pub fn run(path: &Path) -> Result<LintReport, ClippyError> {
+    let output = cargo_ops::output(path, &ARGS)?;
+    if !output.success() && output.stderr.contains("no such command") {
+        return Err(ClippyError::NotInstalled);
+    }
+    let report = parse(&output.stdout, metadata::now());
+    if !output.success() && report.summary.errors == 0 {
+        let stderr = output.stderr.trim();
+        let last = stderr.lines().last().unwrap_or("no output");
+        return Err(ClippyError::Failed(last.to_string()));
+    }
+    metadata::update(|store| store.entry_mut(path).lint = Some(report.summary));
+    Ok(report)
+}
+
+/// Errors and warnings of the JSON messages of cargo, run at `at`.
+fn parse(json: &str, at: u64) -> LintReport {
+    let mut seen = BTreeSet::new();
+    let mut files: BTreeMap<PathBuf, Vec<Diagnostic>> = BTreeMap::new();
+    for line in json.lines() {
+        let Ok(message) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        if message["reason"] != "compiler-message" {
+            continue;
+        }
+        let Some((file, diagnostic)) = diagnostic(&message["message"]) else {
+            continue;
+        };
+        let key = (
+            file.clone(),
+            diagnostic.line,
+            diagnostic.column,
+            diagnostic.message.clone(),
+        );
+        if seen.insert(key) {
+            files.entry(file).or_default().push(diagnostic);
+        }
+    }
+
+    let mut summary = LintSummary {
+        at,
+        ..LintSummary::default()
+    };
+    let files = files
+        .into_iter()
+        .map(|(file, mut diagnostics)| {
+            diagnostics.sort_by_key(|d| (d.line, d.column, d.level));
+            for d in &diagnostics {
+                match d.level {
+                    Level::Error => summary.errors += 1,
+                    Level::Warning => summary.warnings += 1,
+                }
+            }
+            FileDiagnostics { file, diagnostics }
+        })
+        .collect();
+    LintReport { files, summary }
+}
+
+/// File and diagnostic of a compiler message; `None` for notes and messages
+/// without a primary span.
+fn diagnostic(message: &Value) -> Option<(PathBuf, Diagnostic)> {
+    let level = match message["level"].as_str()? {
+        "error" | "error: internal compiler error" => Level::Error,
+        "warning" => Level::Warning,
+        _ => return None,
+    };
+    let span = message["spans"]
+        .as_array()?
+        .iter()
+        .find(|s| s["is_primary"] == true)?;
+    let diagnostic = Diagnostic {
+        level,
+        code: message["code"]["code"].as_str().map(ToString::to_string),
+        message: message["message"].as_str()?.to_string(),
+        line: span["line_start"].as_u64()?,
+        column: span["column_start"].as_u64()?,
+        rendered: message["rendered"]
+            .as_str()
+            .unwrap_or_default()
+            .trim_end()
+            .to_string(),
+    };
+    Some((PathBuf::from(span["file_name"].as_str()?), diagnostic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{self, FakeRunner};
+    use serde_json::json;
+    use std::rc::Rc;
+
+    fn message(level: &str, code: Option<&str>, text: &str, at: Option<(&str, u64)>) -> String {
+        let spans = at.map_or_else(Vec::new, |(file, line)| {
+            vec![
+                json!({"file_name": file, "line_start": line + 3, "column_start": 1, "is_primary": false}),
+                json!({"file_name": file, "line_start": line, "column_start": 5, "is_primary": true}),
+            ]
+        });
+        json!({
+            "reason": "compiler-message",
+            "message": {
+                "level": level,
+                "code": code.map(|c| json!({"code": c})),
+                "message": text,
+                "spans": spans,
+                "rendered": format!("{level}: {text}\n"),
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn diagnostics_are_grouped_by_file() {
+        let needless = message(
+            "warning",
+            Some("clippy::needless_return"),
+            "unneeded `return` statement",
+            Some(("src/lib.rs", 12)),
+        );
+        let stdout = [
+            r#"{"reason":"compiler-artifact","target":{"name":"demo"}}"#.to_string(),
+            needless.clone(),
+            message(
+                "error",
+                Some("E0308"),
+                "mismatched types",
+                Some(("src/main.rs", 3)),
+            ),
+            // The same warning, reported again for the test target.
+            needless,
+            message(
+                "warning",
+                Some("unused_variables"),
+                "unused variable: `x`",
+                Some(("src/lib.rs", 2)),
+            ),
+            message(
+                "note",
+                None,
+                "see the lint documentation",
+                Some(("src/lib.rs", 1)),
+            ),
+            message("error", None, "aborting due to 1 previous error", None),
+            r#"{"reason":"build-finished","success":false}"#.to_string(),
+            "not json".to_string(),
+        ]
+        .join("\n");
+
+        let report = parse(&stdout, 42);
+        assert_eq!(
+            report.summary,
+            LintSummary {
+                errors: 1,
+                warnings: 2,
+                at: 42
+            }
+        );
+        assert_eq!(report.summary.counts(), "1 error, 2 warnings");
+        let files: Vec<_> = report.files.iter().map(|f| f.file.clone()).collect();
+        assert_eq!(
+            files,
+            [PathBuf::from("src/lib.rs"), PathBuf::from("src/main.rs")]
+        );
+        let lib = &report.files[0].diagnostics;
+        assert_eq!((lib[0].line, lib[0].column), (2, 5));
+        assert_eq!(lib[1].code.as_deref(), Some("clippy::needless_return"));
+        assert_eq!(lib[1].rendered, "warning: unneeded `return` statement");
+        assert_eq!(report.files[1].diagnostics[0].level, Level::Error);
+
+        assert_eq!(parse("", 0).summary.counts(), "no warnings");
+    }
+
+    #[test]
+    fn clippy_failures_are_reported() {
+        let fake = Rc::new(FakeRunner::default());
+        let _runner = process::replace(fake.clone());
+        fake.fail(101, "error: no such command: `clippy`\n");
+        fake.fail(
+            101,
+            "error: failed to parse manifest at `/projects/demo/Cargo.toml`\n",
+        );
+        fake.not_found();
+
+        let dir = Path::new("/projects/demo");
+        assert!(matches!(run(dir), Err(ClippyError::NotInstalled)));
+        assert!(
+            matches!(run(dir), Err(ClippyError::Failed(msg)) if msg.starts_with("error: failed to parse"))
+        );
+        assert!(matches!(
+            run(dir),
+            Err(ClippyError::Cargo(CargoError::NotFound(_)))
+        ));
+        assert_eq!(
+            fake.calls()[0].to_string(),
+            "cargo clippy --all-targets --message-format=json"
+        );
+    }
+}
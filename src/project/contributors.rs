@@ -0,0 +1,167 @@
+//! Contributors summary.
+//!
+//! Counts the commits reachable from `HEAD` per author (after `.mailmap`
+//! resolution) over a time window. Walking a long history is not free, so
+//! results are remembered in the metadata store, one per window: an entry is
+//! reused while `HEAD` has not moved and, for bounded windows, while it is less
+//! than an hour old (the window slides with time).
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::git_ops::{self, GitError};
+use crate::metadata;
+
+/// Selectable windows: label and length in days (`None`: whole history).
+pub const WINDOWS: [(&str, Option<u32>); 4] = [
+    ("Last 30 days", Some(30)),
+    ("Last 90 days", Some(90)),
+    ("Last year", Some(365)),
+    ("All time", None),
+];
+
+/// How long a bounded-window result stays valid, in seconds.
+const MAX_AGE: u64 = 3600;
+
+/// Commit counts per author over a window.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Contributors {
+    /// Window length in days (`None`: whole history).
+    pub days: Option<u32>,
+    /// `HEAD` commit id the counts were computed for.
+    pub head: String,
+    /// `(author, commits)`, most commits first.
+    pub authors: Vec<(String, usize)>,
+    /// Seconds since epoch when the counts were computed.
+    pub computed_at: u64,
+}
+
+impl Contributors {
+    /// Total number of commits in the window.
+    pub fn total(&self) -> usize {
+        self.authors.iter().map(|(_, n)| n).sum()
+    }
+
+    fn is_fresh(&self, head: &str, days: Option<u32>, now: u64) -> bool {
+        self.days == days
+            && self.head == head
+            && (days.is_none() || now.saturating_sub(self.computed_at) < MAX_AGE)
+    }
+}
+
+/// Contributors of the project at `path` over the last `days`, from the cache when fresh.
+pub fn summary(path: &Path, days: Option<u32>) -> Result<Contributors, GitError> {
+    let now = metadata::now();
+    let head = head_id(path)?;
+    let cached = metadata::snapshot().get(path).and_then(|m| {
+        m.contributors
+            .iter()
+            .find(|c| c.is_fresh(&head, days, now))
+            .cloned()
+    });
+    if let Some(cached) = cached {
+        return Ok(cached);
+    }
+
+    let computed = compute(path, days, now)?;
+    metadata::update(|store| {
+        let entries = &mut store.entry_mut(path).contributors;
+        entries.retain(|c| c.days != days);
+        entries.push(computed.clone());
+    });
+    Ok(computed)
+}
+
+/// Walk the history of `path` and count commits per author since `now - days`.
+fn compute(path: &Path, days: Option<u32>, now: u64) -> Result<Contributors, GitError> {
+    let repo = git_ops::open(path)?;
+    let head = head_id(path)?;
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    if !head.is_empty() {
+        let since = days
+            .map(|d| i64::try_from(now.saturating_sub(u64::from(d) * 86_400)).unwrap_or(i64::MAX));
+        let mailmap = repo.mailmap()?;
+        let mut walk = repo.revwalk()?;
+        walk.push_head()?;
+        for oid in walk {
+            let commit = repo.find_commit(oid?)?;
+            if since.is_some_and(|since| commit.time().seconds() < since) {
+                continue;
+            }
+            let author = commit.author_with_mailmap(&mailmap)?;
+            let name = author.name().unwrap_or("?").to_string();
+            *counts.entry(name).or_default() += 1;
+        }
+    }
+    let mut authors: Vec<(String, usize)> = counts.into_iter().collect();
+    authors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(Contributors {
+        days,
+        head,
+        authors,
+        computed_at: now,
+    })
+}
+
+/// `HEAD` commit id; empty for unborn branches.
+fn head_id(path: &Path) -> Result<String, GitError> {
+    let repo = git_ops::open(path)?;
+    match repo.head() {
+        Ok(head) => Ok(head.target().map(|oid| oid.to_string()).unwrap_or_default()),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    use git2::{Repository, Signature, Time};
+
+    fn commit_as(repo: &Repository, author: &str, time: i64) {
+        let sig = Signature::new(author, "dev@example.com", &Time::new(time, 0)).unwrap();
+        let tree = {
+            let mut index = repo.index().unwrap();
+            repo.find_tree(index.write_tree().unwrap()).unwrap()
+        };
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, "change", &tree, &parent_refs)
+            .unwrap();
+    }
+
+    #[test]
+    fn counts_commits_per_author_within_the_window() {
+        let dir = TempDir::new("contributors");
+        let repo = Repository::init(&dir).unwrap();
+        let now: u64 = 1_700_000_000;
+        let day = 86_400;
+        let at = |days_ago: i64| now as i64 - days_ago * day;
+        commit_as(&repo, "Old Timer", at(400));
+        commit_as(&repo, "Bob", at(10));
+        commit_as(&repo, "Alice", at(5));
+        commit_as(&repo, "Alice", at(1));
+
+        let recent = compute(&dir, Some(30), now).unwrap();
+        assert_eq!(
+            recent.authors,
+            vec![("Alice".to_string(), 2), ("Bob".to_string(), 1)]
+        );
+        assert_eq!(compute(&dir, None, now).unwrap().total(), 4);
+
+        assert!(recent.is_fresh(&recent.head, Some(30), now + 60));
+        assert!(!recent.is_fresh(&recent.head, Some(30), now + MAX_AGE));
+        assert!(!recent.is_fresh("other", Some(30), now));
+        assert!(!recent.is_fresh(&recent.head, Some(90), now));
+    }
+}
@@ -0,0 +1,227 @@
+//! Convert a single-crate project into a workspace.
+//!
+//! The crate moves to `crates/<name>` and the project root gets a virtual
+//! workspace manifest listing it:
+//! - crate items (`Cargo.toml`, `src`, `tests`, `benches`, `examples`,
+//!   `build.rs`) move; everything else (`.git`, `README`, licenses, CI files,
+//!   `Cargo.lock`, `.cargo/`, ...) stays at the root;
+//! - tracked items move with `git mv` so `git log --follow` keeps their
+//!   history, untracked ones with a plain rename;
+//! - relative paths of the moved manifest that point at files staying behind
+//!   (path dependencies, `readme`, `license-file`, ...) are rebased, and
+//!   `[patch]`, `[profile]` and `[replace]` move to the root manifest, the
+//!   only place cargo honours them.
+//!
+//! The working tree must be clean so the result can be reviewed (and undone)
+//! as a single change. Both manifests are staged; nothing is committed. A
+//! safety snapshot is taken first (see `git_ops::snapshot`), and a failure
+//! midway moves everything back (see `crate::transaction`).
+
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::cargo_ops;
+use crate::git_ops;
+use crate::project::list::scan_git_status;
+use crate::project::manifest::{Manifest, ManifestError};
+use crate::transaction::Transaction;
+
+/// Directory receiving the crate, relative to the project root.
+const MEMBERS_DIR: &str = "crates";
+/// Items that belong to the crate and move with it.
+const CRATE_ITEMS: [&str; 6] = [
+    "Cargo.toml",
+    "src",
+    "tests",
+    "benches",
+    "examples",
+    "build.rs",
+];
+
+#[derive(Debug)]
+pub enum ConvertError {
+    AlreadyWorkspace,
+    NotAPackage,
+    /// Uncommitted changes in the working tree.
+    Dirty,
+    TargetExists(PathBuf),
+    Manifest(ManifestError),
+    Io(std::io::Error),
+    Git(String),
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyWorkspace => write!(f, "The project is already a workspace"),
+            Self::NotAPackage => write!(f, "Cargo.toml has no [package] name"),
+            Self::Dirty => write!(f, "Commit or stash the uncommitted changes first"),
+            Self::TargetExists(p) => write!(f, "{} already exists", p.display()),
+            Self::Manifest(e) => write!(f, "Manifest error: {e}"),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Git(msg) => write!(f, "git failed: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Manifest(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Turn the crate at `path` into the single member of a new workspace; returns the member path.
+pub fn to_workspace(path: &Path, log: &dyn Fn(String)) -> Result<PathBuf, ConvertError> {
+    let manifest = Manifest::read(path).map_err(ConvertError::Manifest)?;
+    if manifest.is_workspace() {
+        return Err(ConvertError::AlreadyWorkspace);
+    }
+    let name = manifest
+        .package_name()
+        .ok_or(ConvertError::NotAPackage)?
+        .to_string();
+    if scan_git_status(path).map_err(|e| ConvertError::Git(e.message().to_string()))? {
+        return Err(ConvertError::Dirty);
+    }
+    git_ops::snapshot(path, "workspace conversion")
+        .map_err(|e| ConvertError::Git(e.to_string()))?;
+    let member_dir = format!("{MEMBERS_DIR}/{name}");
+    let member_path = path.join(&member_dir);
+    if member_path.exists() {
+        return Err(ConvertError::TargetExists(member_path));
+    }
+
+    let items: Vec<&str> = CRATE_ITEMS
+        .into_iter()
+        .filter(|item| path.join(item).exists())
+        .collect();
+    let (root, member) = manifest.split_into_workspace(&member_dir, |p| {
+        Path::new(p)
+            .components()
+            .find(|c| !matches!(c, Component::CurDir))
+            .is_some_and(|first| items.iter().any(|item| first.as_os_str() == *item))
+    });
+
+    let mut tx = Transaction::new(format!("Converting {} to a workspace", path.display()));
+    let in_git = path.join(".git").exists();
+    if in_git {
+        // The tree was clean: the index goes back to `HEAD`, undoing `git mv` and `git add`.
+        let repo_dir = path.to_path_buf();
+        tx.step(
+            "git index changes",
+            || Ok::<_, ConvertError>(()),
+            move || git(&repo_dir, &["reset", "-q"]).map_err(|e| e.to_string()),
+        )?;
+    }
+    tx.create_dir_all(&member_path).map_err(ConvertError::Io)?;
+    for item in &items {
+        let target = format!("{member_dir}/{item}");
+        if in_git && is_tracked(path, item) {
+            log(format!("git mv {item} {target}"));
+            let (from, to) = (path.join(&target), path.join(item));
+            tx.step(
+                format!("git mv {item} {target}"),
+                || git(path, &["mv", item, &target]),
+                move || fs::rename(&from, &to).map_err(|e| e.to_string()),
+            )?;
+        } else {
+            log(format!("mv {item} {target}"));
+            tx.rename(&path.join(item), &path.join(&target))
+                .map_err(ConvertError::Io)?;
+        }
+    }
+
+    log(format!(
+        "Writing {member_dir}/Cargo.toml and the workspace Cargo.toml"
+    ));
+    tx.preserve(&member_path.join("Cargo.toml"))
+        .map_err(ConvertError::Io)?;
+    member.write(&member_path).map_err(ConvertError::Manifest)?;
+    tx.preserve(&path.join("Cargo.toml"))
+        .map_err(ConvertError::Io)?;
+    root.write(path).map_err(ConvertError::Manifest)?;
+    if in_git {
+        git(
+            path,
+            &["add", "Cargo.toml", &format!("{member_dir}/Cargo.toml")],
+        )?;
+    }
+    tx.commit();
+    log(format!(
+        "{name} is now the member {member_dir} of the workspace"
+    ));
+    Ok(member_path)
+}
+
+fn is_tracked(repo_dir: &Path, item: &str) -> bool {
+    cargo_ops::run_tool("git", repo_dir, &["ls-files", "--", item], &[])
+        .is_ok_and(|out| !out.trim().is_empty())
+}
+
+fn git(repo_dir: &Path, args: &[&str]) -> Result<(), ConvertError> {
+    cargo_ops::run_tool("git", repo_dir, args, &[])
+        .map(|_| ())
+        .map_err(|e| ConvertError::Git(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn moves_the_crate_and_rebases_paths() {
+        let dir = TempDir::new("convert");
+        fs::write(
+            dir.join("Cargo.toml"),
+            r#"[package]
+name = "demo"
+version = "0.1.0"
+edition = "2021"
+readme = "README.md"
+
+[[bin]]
+name = "tool"
+path = "src/bin/tool.rs"
+
+[dependencies]
+other = { path = "../other" } # sibling checkout
+
+[profile.release]
+lto = true
+"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("src/bin")).unwrap();
+        fs::write(dir.join("src/lib.rs"), "").unwrap();
+        fs::write(dir.join("src/bin/tool.rs"), "fn main() {}").unwrap();
+        fs::write(dir.join("README.md"), "# demo").unwrap();
+
+        let member = to_workspace(&dir, &|_| {}).unwrap();
+        assert_eq!(member, dir.join("crates/demo"));
+        assert!(member.join("src/bin/tool.rs").is_file());
+        assert!(dir.join("README.md").is_file());
+        assert!(!dir.join("src").exists());
+
+        let root = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        assert!(root.contains("resolver = \"2\""));
+        assert!(root.contains("members = [\"crates/demo\"]"));
+        assert!(root.contains("[profile.release]"));
+
+        let moved = fs::read_to_string(member.join("Cargo.toml")).unwrap();
+        assert!(moved.contains("readme = \"../../README.md\""));
+        assert!(moved.contains("path = \"src/bin/tool.rs\""));
+        assert!(moved.contains("other = { path = \"../../../other\" } # sibling checkout"));
+        assert!(!moved.contains("[profile.release]"));
+
+        assert!(matches!(
+            to_workspace(&dir, &|_| {}),
+            Err(ConvertError::AlreadyWorkspace)
+        ));
+    }
+}
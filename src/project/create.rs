@@ -8,41 +8,87 @@
 //! 1. Validate supplied parameters (name format, edition, project type).
 //! 2. Re‑validate the configured projects directory (existence, permissions).
 //! 3. Ensure the target project path does NOT already exist.
-//! 4. Set `git config --global init.defaultBranch main` (best effort; warn on failure).
-//! 5. Invoke `cargo new` with the chosen edition and type.
-//! 6. (Optional) Open the project in the configured editor command.
+//! 4. Invoke `cargo new` with the chosen edition, type and version control
+//!    (`--vcs`), or copy the chosen template (see `project::templates`), set its
+//!    package name and `git init` / `hg init`.
+//! 5. Make a new git repository start on `DEFAULT_BRANCH` (best effort; warn on
+//!    failure). Only the project's own `HEAD` is set: the global git
+//!    configuration is never touched.
+//!    Then write the license files (see `project::license`), the description,
+//!    the README and the curated tooling files (see `project::tooling`) when
+//!    asked, apply the organization preset, if any (see
+//!    `project::presets`), and make the initial commit when asked (best effort,
+//!    failures reported in the result).
+//! 6. (Optional) Open the project in the configured editor command (see `crate::editor`).
 //!
 //! Logging:
 //! - Significant actions are logged at INFO.
 //! - Failures are logged at ERROR or WARN (non-fatal steps).
 //!
+//! Workspace members (`create_workspace_member`) skip steps 2-4: `cargo new`
+//! runs inside the workspace (so workspace-inherited fields are picked up) and
+//! the member is appended to `[workspace] members` unless a pattern already
+//! covers it.
+//!
 //! Integration guidance:
 //! - The TUI should build a `CreateProjectParams` from user inputs (applying defaults
 //!   when fields are omitted).
 //! - Call `create_project(&config, params)`.
 //! - If `open_in_editor` flag is chosen by the user, call `maybe_open_in_editor` on
 //!   the returned result (or use `create_and_optionally_open` helper).
-//!
-//! NOTE: Editor command tokenization here is intentionally simple (whitespace split)
-//! to avoid adding parsing dependencies. If you need robust shell-like parsing, you
-//! can introduce a crate (e.g. `shlex`) and adjust the implementation accordingly.
 
 use std::fmt;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 
 use log::{error, info, warn};
 
-use crate::config::{Config, validate_projects_directory};
+use crate::cargo_ops;
+use crate::config::{Config, Preset, validate_projects_directory};
+use crate::editor::{self, OpenEditorError};
+use crate::events::{self, Event};
+use crate::forge::{self, NewRepo, Provider};
+use crate::git_ops;
+use crate::metadata;
+use crate::process::{self, CommandLine};
+use crate::project::license::{self, License};
+use crate::project::manifest::{Manifest, ManifestError};
+use crate::project::presets;
+use crate::project::templates::{self, TemplateError};
+use crate::project::tooling;
+use crate::template;
+
+/// Branch the repository of a new project starts on.
+const DEFAULT_BRANCH: &str = "main";
+
+/// Message of the initial commit.
+const INITIAL_COMMIT_MESSAGE: &str = "Initial commit";
+
+const README_TEMPLATE: &str = "# {{name}}
+
+{{description}}
+{{license}}";
+
+/// Description of the README when none is given.
+const README_PLACEHOLDER: &str = "TODO: describe the project.";
 
 /// Supported project types (maps to `cargo new --bin/--lib`).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ProjectType {
+    #[default]
     Binary,
     Library,
 }
 
 impl ProjectType {
+    /// Parse `bin` / `lib`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "bin" => Some(Self::Binary),
+            "lib" => Some(Self::Library),
+            _ => None,
+        }
+    }
+
     const fn cargo_flag(self) -> &'static str {
         match self {
             Self::Binary => "--bin",
@@ -53,15 +99,23 @@ impl ProjectType {
 
 /// Supported Rust editions the UI can offer.
 /// (Spec: 2015, 2018, 2021, 2024 with default = latest stable (2024).)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ProjectEdition {
     E2015,
     E2018,
     E2021,
+    #[default]
     E2024,
 }
 
 impl ProjectEdition {
+    pub const ALL: [Self; 4] = [Self::E2015, Self::E2018, Self::E2021, Self::E2024];
+
+    /// Parse an edition year (`2021`).
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.as_str() == value.trim())
+    }
+
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::E2015 => "2015",
@@ -72,15 +126,29 @@ impl ProjectEdition {
     }
 }
 
-impl Default for ProjectEdition {
-    fn default() -> Self {
-        Self::E2024
-    }
+/// Version control of a new project (maps to `cargo new --vcs`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Vcs {
+    #[default]
+    Git,
+    Hg,
+    None,
 }
 
-impl Default for ProjectType {
-    fn default() -> Self {
-        Self::Binary
+impl Vcs {
+    pub const ALL: [Self; 3] = [Self::Git, Self::Hg, Self::None];
+
+    /// Parse `git` / `hg` / `none`.
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.as_str() == value.trim())
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Git => "git",
+            Self::Hg => "hg",
+            Self::None => "none",
+        }
     }
 }
 
@@ -90,30 +158,99 @@ pub struct CreateProjectParams {
     pub name: String,
     pub project_type: ProjectType,
     pub edition: ProjectEdition,
+    /// Fetched template copied instead of running `cargo new` (see `project::templates`).
+    pub template: Option<PathBuf>,
+    /// Organization preset applied once the project exists (see `project::presets`).
+    pub preset: Option<Preset>,
+    pub vcs: Vcs,
+    /// Commit everything once the project is scaffolded (ignored without version control).
+    pub initial_commit: bool,
+    /// License files to write (see `project::license`).
+    pub license: Option<License>,
+    /// `package.description` and README text; empty for none.
+    pub description: String,
+    /// Write a `README.md` with the name, description and license.
+    pub readme: bool,
+    /// Copy the curated `.gitignore`, `rustfmt.toml` and `clippy.toml` (see `project::tooling`).
+    pub tooling: bool,
+    /// Set as `package.authors` and license copyright holder (git `user.name` when unset).
+    pub author_name: Option<String>,
+    /// Added to the `package.authors` entry.
+    pub author_email: Option<String>,
+    /// Create a repository on this forge, add it as `origin` and push the
+    /// initial commit (see `crate::forge`; git only).
+    pub remote: Option<Provider>,
+    /// Make that repository public rather than private.
+    pub public_remote: bool,
 }
 
 impl CreateProjectParams {
-    /// Build with defaults (binary, 2024) for convenience.
+    /// Build with defaults (binary, 2024, git, no initial commit, license,
+    /// README, tooling files, author or remote) for convenience.
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
             project_type: ProjectType::default(),
             edition: ProjectEdition::default(),
+            template: None,
+            preset: None,
+            vcs: Vcs::default(),
+            initial_commit: false,
+            license: None,
+            description: String::new(),
+            readme: false,
+            tooling: false,
+            author_name: None,
+            author_email: None,
+            remote: None,
+            public_remote: false,
+        }
+    }
+
+    /// Defaults of `config`: its author, default edition and default license.
+    pub fn from_config(name: impl Into<String>, config: &Config) -> Self {
+        Self {
+            edition: config.default_edition(),
+            license: config.default_license(),
+            author_name: config.author_name().map(ToString::to_string),
+            author_email: config.author_email().map(ToString::to_string),
+            ..Self::new(name)
         }
     }
+
+    /// `package.authors` entry: `Name <email>`, or the name alone.
+    fn author(&self) -> Option<String> {
+        let name = self.author_name.as_deref()?;
+        Some(match self.author_email.as_deref() {
+            Some(email) => format!("{name} <{email}>"),
+            None => name.to_string(),
+        })
+    }
 }
 
 /// Result structure describing a successfully created project.
 #[derive(Debug, Clone)]
 pub struct CreateProjectResult {
     pub project_path: PathBuf,
-    pub params: CreateProjectParams,
+    /// Files and settings (license, README, preset) that could not be set up,
+    /// with the reason (the project is created).
+    pub setup_failures: Vec<String>,
+    /// Why the initial commit could not be made (the project is created).
+    pub commit_error: Option<String>,
+    /// Page of the remote repository created, when asked for.
+    pub remote_url: Option<String>,
+    /// Why the remote repository could not be created or pushed to.
+    pub remote_error: Option<String>,
 }
 
 impl CreateProjectResult {
-    /// Attempt to open the project in the configured editor.
+    /// Attempt to open the project in the configured editor (profile-aware, blocking for
+    /// terminal editors).
     pub fn maybe_open_in_editor(&self, config: &Config) -> Result<(), OpenEditorError> {
-        open_in_editor(config.editor_cmd(), &self.project_path)
+        for editor_cmd in editor::resolve_commands(config, &self.project_path) {
+            editor::open_in_editor(config, &editor_cmd, &self.project_path)?;
+        }
+        Ok(())
     }
 }
 /// Error category for project creation failures.
@@ -125,6 +262,8 @@ pub enum CreateProjectError {
     CargoNotFound,
     CargoFailed { status: i32, stderr: String },
     Io(std::io::Error),
+    Manifest(ManifestError),
+    Template(TemplateError),
 }
 
 impl fmt::Display for CreateProjectError {
@@ -146,6 +285,8 @@ impl fmt::Display for CreateProjectError {
                 write!(f, "`cargo new` failed (exit code {status}): {stderr}")
             }
             Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Manifest(e) => write!(f, "Cannot update the workspace manifest: {e}"),
+            Self::Template(e) => write!(f, "{e}"),
         }
     }
 }
@@ -154,6 +295,8 @@ impl std::error::Error for CreateProjectError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::Io(e) => Some(e),
+            Self::Manifest(e) => Some(e),
+            Self::Template(e) => Some(e),
             _ => None,
         }
     }
@@ -165,33 +308,6 @@ impl From<std::io::Error> for CreateProjectError {
     }
 }
 
-/// Error category for editor opening failures.
-#[derive(Debug)]
-pub enum OpenEditorError {
-    EditorCommandEmpty,
-    Spawn(std::io::Error),
-    Failed(i32),
-}
-
-impl fmt::Display for OpenEditorError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::EditorCommandEmpty => write!(f, "Editor command is empty"),
-            Self::Spawn(e) => write!(f, "Failed to spawn editor command: {e}"),
-            Self::Failed(code) => write!(f, "Editor command exited with status {code}"),
-        }
-    }
-}
-
-impl std::error::Error for OpenEditorError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            Self::Spawn(e) => Some(e),
-            _ => None,
-        }
-    }
-}
-
 /// Main entry point: create a new Rust project.
 ///
 /// This does not open the project in the editor. Use `CreateProjectResult::maybe_open_in_editor`
@@ -220,23 +336,286 @@ pub fn create_project(
         return Err(CreateProjectError::AlreadyExists(project_path));
     }
 
-    // Best effort: configure git default branch.
-    set_global_git_default_branch();
-
-    // Run cargo new
-    run_cargo_new(&project_path, &params).map_err(|e| {
-        error!("cargo new failed: {e}");
-        e
-    })?;
+    match &params.template {
+        Some(template) => copy_template(template, &project_path, &params.name, params.vcs)?,
+        None => {
+            run_cargo_new(&project_path, &params).map_err(|e| {
+                error!("cargo new failed: {e}");
+                e
+            })?;
+            if params.vcs == Vcs::Git {
+                set_initial_branch(&project_path);
+            }
+        }
+    }
 
     info!("Project successfully created at {}", project_path.display());
+    let mut setup_failures = write_project_files(&project_path, &params);
+    if params.tooling {
+        setup_failures.extend(tooling::copy(
+            &tooling::dir(),
+            &project_path,
+            params.vcs == Vcs::Git,
+        ));
+    }
+    if let Some(preset) = &params.preset {
+        setup_failures.extend(presets::apply(&project_path, preset));
+    }
+    let commit_error = if params.initial_commit {
+        initial_commit(&project_path, params.vcs).err()
+    } else {
+        None
+    };
+    let (remote_url, remote_error) = match params.remote {
+        Some(provider) => {
+            let committed = params.initial_commit && commit_error.is_none();
+            match create_remote(config, provider, &project_path, &params, committed) {
+                Ok(url) => (Some(url), None),
+                Err(e) => (None, Some(e)),
+            }
+        }
+        None => (None, None),
+    };
+    // Known from now on: not quarantined as a newcomer by the next scan.
+    metadata::update(|store| {
+        store.entry_mut(&project_path);
+    });
+    events::emit([Event::ProjectCreated {
+        name: params.name.clone(),
+        path: project_path.clone(),
+    }]);
 
     Ok(CreateProjectResult {
         project_path,
-        params,
+        setup_failures,
+        commit_error,
+        remote_url,
+        remote_error,
     })
 }
 
+/// Create the remote repository of the project on `provider`, pushing the
+/// initial commit when `committed`; returns its page.
+fn create_remote(
+    config: &Config,
+    provider: Provider,
+    project_path: &Path,
+    params: &CreateProjectParams,
+    committed: bool,
+) -> Result<String, String> {
+    if params.vcs != Vcs::Git {
+        return Err(format!("{} repositories need git", provider.label()));
+    }
+    let repo = NewRepo {
+        name: &params.name,
+        description: params.description.trim(),
+        private: !params.public_remote,
+    };
+    let log = |line: String| info!("{line}");
+    let created = forge::client(config, provider)
+        .and_then(|forge| {
+            forge::publish(
+                forge.as_ref(),
+                config.forge().protocol,
+                project_path,
+                &repo,
+                committed,
+                &log,
+            )
+        })
+        .map_err(|e| {
+            warn!("Remote of {} not set up: {e}", project_path.display());
+            e.to_string()
+        })?;
+    Ok(created.web_url)
+}
+
+/// Write the authors, description, license files and README asked for in `params`;
+/// returns what failed, with the reason.
+fn write_project_files(project_path: &Path, params: &CreateProjectParams) -> Vec<String> {
+    let mut failures = Vec::new();
+    let mut fail = |what: &str, reason: String| {
+        warn!("Cannot {what} in {}: {reason}", project_path.display());
+        failures.push(format!("{what}: {reason}"));
+    };
+    if let Some(author) = params.author() {
+        let authors = toml_edit::Array::from_iter([author]);
+        match Manifest::edit(project_path, |m| {
+            m.declares_package_key("authors") || m.set_package_value("authors", authors)
+        }) {
+            Ok(true) => {}
+            Ok(false) => fail("set the authors", "no [package] table".into()),
+            Err(e) => fail("set the authors", e.to_string()),
+        }
+    }
+    let description = params.description.trim();
+    if !description.is_empty() {
+        match Manifest::edit(project_path, |m| {
+            m.set_package_value("description", description)
+        }) {
+            Ok(true) => {}
+            Ok(false) => fail("set the description", "no [package] table".into()),
+            Err(e) => fail("set the description", e.to_string()),
+        }
+    }
+    if let Some(license) = params.license {
+        let written = license::write(
+            project_path,
+            license,
+            &license::holder(params.author_name.as_deref()),
+        )
+        .map_err(|e| e.to_string())
+        .and_then(|_| {
+            Manifest::edit(project_path, |m| {
+                if !m.declares_package_key("license") && !m.declares_package_key("license-file") {
+                    m.set_package_value("license", license.spdx());
+                }
+            })
+            .map_err(|e| e.to_string())
+        });
+        if let Err(e) = written {
+            fail("add the license", e);
+        }
+    }
+    if params.readme {
+        let vars = [
+            ("name", params.name.clone()),
+            (
+                "description",
+                if description.is_empty() {
+                    README_PLACEHOLDER
+                } else {
+                    description
+                }
+                .to_string(),
+            ),
+            (
+                "license",
+                params
+                    .license
+                    .map(|l| format!("\n## License\n\n{}\n", l.readme_notice()))
+                    .unwrap_or_default(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let written = template::render(README_TEMPLATE, &vars)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                std::fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(project_path.join("README.md"))
+                    .and_then(|mut f| std::io::Write::write_all(&mut f, text.as_bytes()))
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = written {
+            fail("create README.md", e);
+        }
+    }
+    failures
+}
+
+/// Commit every file of the new project at `project_path`.
+fn initial_commit(project_path: &Path, vcs: Vcs) -> Result<(), String> {
+    let result = match vcs {
+        Vcs::Git => git_ops::commit_all(project_path, INITIAL_COMMIT_MESSAGE)
+            .map(drop)
+            .map_err(|e| e.to_string()),
+        Vcs::Hg => cargo_ops::run_tool(
+            "hg",
+            project_path,
+            &["commit", "--addremove", "-m", INITIAL_COMMIT_MESSAGE],
+            &[],
+        )
+        .map(drop)
+        .map_err(|e| e.to_string()),
+        Vcs::None => return Ok(()),
+    };
+    match &result {
+        Ok(()) => info!("Initial commit made in {}", project_path.display()),
+        Err(e) => warn!("Initial commit in {} failed: {e}", project_path.display()),
+    }
+    result
+}
+
+/// Copy `template` to `project_path`, name its package and start a `vcs` repository.
+fn copy_template(
+    template: &Path,
+    project_path: &Path,
+    name: &str,
+    vcs: Vcs,
+) -> Result<(), CreateProjectError> {
+    info!(
+        "Creating {} from the template {}",
+        project_path.display(),
+        template.display()
+    );
+    templates::copy_tree(template, project_path).inspect_err(|e| {
+        error!("Copying the template failed: {e}");
+        let _ = std::fs::remove_dir_all(project_path);
+    })?;
+    if project_path.join("Cargo.toml").is_file() {
+        Manifest::edit(project_path, |m| m.set_package_name(name))
+            .map_err(CreateProjectError::Manifest)?;
+    }
+    let init = match vcs {
+        Vcs::Git => git_ops::init(project_path, DEFAULT_BRANCH).map_err(|e| e.to_string()),
+        Vcs::Hg => cargo_ops::run_tool("hg", project_path, &["init"], &[])
+            .map(drop)
+            .map_err(|e| e.to_string()),
+        Vcs::None => Ok(()),
+    };
+    if let Err(e) = init {
+        warn!("Cannot initialize a {} repository: {e}", vcs.as_str());
+    }
+    Ok(())
+}
+
+/// Create a member crate `name` in `<workspace_root>/<parent>` and register it as a workspace member.
+///
+/// `parent` is relative to the workspace root (empty for the root itself). Returns the member path.
+pub fn create_workspace_member(
+    workspace_root: &Path,
+    parent: &str,
+    name: &str,
+    project_type: ProjectType,
+) -> Result<PathBuf, CreateProjectError> {
+    validate_name(name).map_err(CreateProjectError::InvalidName)?;
+    let relative = Path::new(parent.trim_matches('/')).join(name);
+    let member_path = workspace_root.join(&relative);
+    if member_path.exists() {
+        return Err(CreateProjectError::AlreadyExists(member_path));
+    }
+    std::fs::create_dir_all(member_path.parent().unwrap_or(workspace_root))?;
+
+    let cmd = CommandLine::new("cargo", member_path.parent().unwrap_or(workspace_root)).args([
+        "new",
+        project_type.cargo_flag(),
+        name,
+    ]);
+    run_cargo(&cmd)?;
+
+    // Recent cargo versions register the member themselves; only add it when missing.
+    let member = relative.to_string_lossy().replace('\\', "/");
+    let added = Manifest::edit(workspace_root, |m| m.add_workspace_member(&member))
+        .map_err(CreateProjectError::Manifest)?;
+    if added {
+        info!("Added '{member}' to the workspace members");
+    }
+    info!("Workspace member created at {}", member_path.display());
+    Ok(member_path)
+}
+
+/// Directory new members go to: `X` when the workspace has an `X/*` members pattern, else the root.
+pub fn default_member_dir(manifest: &Manifest) -> String {
+    manifest
+        .workspace_members()
+        .iter()
+        .find_map(|m| m.strip_suffix("/*").map(ToString::to_string))
+        .unwrap_or_default()
+}
+
 /// Convenience function: create and optionally open the project in the editor
 /// depending on the `open_in_editor` flag.
 ///
@@ -248,10 +627,11 @@ pub fn create_and_optionally_open(
 ) -> Result<CreateProjectResult, CreateAndOpenError> {
     let result = create_project(config, params).map_err(CreateAndOpenError::CreateFailed)?;
 
-    if let Err(e) = result.maybe_open_in_editor(config)
-        && open_in_editor
-    {
-        return Err(CreateAndOpenError::OpenAfterCreate { result, error: e });
+    if open_in_editor && let Err(e) = result.maybe_open_in_editor(config) {
+        return Err(CreateAndOpenError::OpenAfterCreate {
+            result: Box::new(result),
+            error: e,
+        });
     }
     Ok(result)
 }
@@ -261,7 +641,7 @@ pub fn create_and_optionally_open(
 pub enum CreateAndOpenError {
     CreateFailed(CreateProjectError),
     OpenAfterCreate {
-        result: CreateProjectResult,
+        result: Box<CreateProjectResult>,
         error: OpenEditorError,
     },
 }
@@ -287,7 +667,7 @@ impl std::error::Error for CreateAndOpenError {
 }
 
 /// Validate crate / project name (simple heuristic).
-fn validate_name(name: &str) -> Result<(), String> {
+pub fn validate_name(name: &str) -> Result<(), String> {
     if name.trim().is_empty() {
         return Err("name cannot be blank".into());
     }
@@ -308,25 +688,14 @@ fn validate_name(name: &str) -> Result<(), String> {
     Ok(())
 }
 
-/// Attempt to set global git default branch, logging warnings on failure.
-fn set_global_git_default_branch() {
-    match Command::new("git")
-        .args(["config", "--global", "init.defaultBranch", "main"])
-        .status()
-    {
-        Ok(status) => {
-            if status.success() {
-                info!("Ensured global git default branch is 'main'");
-            } else {
-                warn!(
-                    "git config command exited with non-zero status: {:?}",
-                    status.code()
-                );
-            }
-        }
-        Err(e) => {
-            warn!("Unable to run git to set default branch: {e}");
-        }
+/// Make the repository `cargo new` created start on `DEFAULT_BRANCH`, logging failures.
+///
+/// Nothing to do when `cargo new` made no repository (e.g. inside another one).
+fn set_initial_branch(project_path: &Path) {
+    match git_ops::set_initial_branch(project_path, DEFAULT_BRANCH) {
+        Ok(true) => info!("New repository starts on '{DEFAULT_BRANCH}'"),
+        Ok(false) | Err(git_ops::GitError::NotARepository(_)) => {}
+        Err(e) => warn!("Cannot set the initial branch to '{DEFAULT_BRANCH}': {e}"),
     }
 }
 
@@ -335,21 +704,27 @@ fn run_cargo_new(
     project_path: &Path,
     params: &CreateProjectParams,
 ) -> Result<(), CreateProjectError> {
-    let mut cmd = Command::new("cargo");
-    cmd.arg("new")
-        .arg(params.project_type.cargo_flag())
-        .arg("--edition")
-        .arg(params.edition.as_str())
-        .arg(&params.name)
-        .current_dir(
-            project_path
-                .parent()
-                .expect("project path should have parent"),
-        );
+    let mut cmd = CommandLine::new(
+        "cargo",
+        project_path
+            .parent()
+            .expect("project path should have parent"),
+    )
+    .arg("new")
+    .arg(params.project_type.cargo_flag())
+    .args(["--edition", params.edition.as_str()]);
+    // Without `--vcs`, cargo skips git inside an existing repository: kept for git.
+    if params.vcs != Vcs::Git {
+        cmd = cmd.args(["--vcs", params.vcs.as_str()]);
+    }
+    run_cargo(&cmd.arg(&params.name))
+}
 
-    info!("Executing: {cmd:?}");
+/// Run a `cargo new` command, mapping failures to `CreateProjectError`.
+fn run_cargo(cmd: &CommandLine) -> Result<(), CreateProjectError> {
+    info!("Executing in {}: {cmd}", cmd.dir.display());
 
-    let output = cmd.output().map_err(|e| {
+    let output = process::with(|runner| runner.output(cmd)).map_err(|e| {
         if e.kind() == std::io::ErrorKind::NotFound {
             CreateProjectError::CargoNotFound
         } else {
@@ -357,56 +732,23 @@ fn run_cargo_new(
         }
     })?;
 
-    if !output.status.success() {
-        let code = output.status.code().unwrap_or(-1);
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    if !output.success() {
         return Err(CreateProjectError::CargoFailed {
-            status: code,
-            stderr,
+            status: output.code.unwrap_or(-1),
+            stderr: output.stderr,
         });
     }
 
     Ok(())
 }
 
-/// Open the project in the provided editor command (string).
-///
-/// Strategy:
-/// - Split editor command by ASCII whitespace (basic, not shell quoting aware).
-/// - First token is program, remainder are args.
-/// - Append the project directory path.
-/// - Spawn and wait (blocking). If asynchronous desired, adapt logic accordingly.
-fn open_in_editor(editor_cmd: &str, project_path: &Path) -> Result<(), OpenEditorError> {
-    if editor_cmd.trim().is_empty() {
-        return Err(OpenEditorError::EditorCommandEmpty);
-    }
-
-    let mut parts = editor_cmd.split_whitespace();
-    let program = parts.next().ok_or(OpenEditorError::EditorCommandEmpty)?;
-    let mut cmd = Command::new(program);
-    for arg in parts {
-        cmd.arg(arg);
-    }
-    cmd.arg(project_path);
-
-    info!(
-        "Opening project '{}' with editor command: {}",
-        project_path.display(),
-        editor_cmd
-    );
-
-    let status = cmd.status().map_err(OpenEditorError::Spawn)?;
-
-    if !status.success() {
-        return Err(OpenEditorError::Failed(status.code().unwrap_or(-1)));
-    }
-
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::process::FakeRunner;
+    use crate::temp_dir::TempDir;
+    use std::fs;
+    use std::rc::Rc;
 
     #[test]
     fn name_validation_ok() {
@@ -429,5 +771,105 @@ mod tests {
         let p = CreateProjectParams::new("abc");
         assert_eq!(p.project_type, ProjectType::Binary);
         assert_eq!(p.edition, ProjectEdition::E2024);
+        assert_eq!(p.vcs, Vcs::Git);
+        assert!(!p.initial_commit);
+        assert_eq!(Vcs::parse(" hg"), Some(Vcs::Hg));
+        assert_eq!(Vcs::parse("svn"), None);
+    }
+
+    #[test]
+    fn license_readme_and_description_are_written() {
+        let dir = TempDir::new("create");
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let mut params = CreateProjectParams::new("demo");
+        params.license = Some(License::Dual);
+        params.author_name = Some("Jo Doe".into());
+        params.author_email = Some("jo@example.com".into());
+        params.description = " A demo. ".into();
+        params.readme = true;
+        assert!(write_project_files(&dir, &params).is_empty());
+
+        let manifest = Manifest::read(&dir).unwrap();
+        assert_eq!(manifest.package_str("license"), Some("MIT OR Apache-2.0"));
+        assert_eq!(manifest.package_str("description"), Some("A demo."));
+        assert_eq!(
+            manifest.package_str_array("authors"),
+            ["Jo Doe <jo@example.com>"]
+        );
+        assert!(dir.join("LICENSE-APACHE").is_file());
+        assert!(
+            fs::read_to_string(dir.join("LICENSE-MIT"))
+                .unwrap()
+                .contains("Jo Doe")
+        );
+        let readme = fs::read_to_string(dir.join("README.md")).unwrap();
+        assert!(readme.starts_with("# demo\n\nA demo.\n\n## License\n"));
+        assert!(readme.contains("(LICENSE-MIT)"));
+
+        // Existing files are kept and reported.
+        let failures = write_project_files(&dir, &params);
+        assert_eq!(failures.len(), 2);
+    }
+
+    #[test]
+    fn cargo_and_hg_command_lines() {
+        let fake = Rc::new(FakeRunner::default());
+        let _runner = process::replace(fake.clone());
+        let dir = TempDir::new("create");
+        let mut params = CreateProjectParams::new("my demo");
+        params.project_type = ProjectType::Library;
+        params.edition = ProjectEdition::E2021;
+        params.vcs = Vcs::Hg;
+        run_cargo_new(&dir.join("my demo"), &params).unwrap();
+        params.vcs = Vcs::Git;
+        fake.fail(101, "error: destination already exists");
+        assert!(matches!(
+            run_cargo_new(&dir.join("my demo"), &params),
+            Err(CreateProjectError::CargoFailed { status: 101, .. })
+        ));
+        fake.not_found();
+        assert!(matches!(
+            run_cargo_new(&dir.join("my demo"), &params),
+            Err(CreateProjectError::CargoNotFound)
+        ));
+        initial_commit(&dir, Vcs::Hg).unwrap();
+
+        let calls = fake.calls();
+        let lines: Vec<String> = calls.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "cargo new --lib --edition 2021 --vcs hg \"my demo\"",
+                "cargo new --lib --edition 2021 \"my demo\"",
+                "cargo new --lib --edition 2021 \"my demo\"",
+                "hg commit --addremove -m \"Initial commit\"",
+            ]
+        );
+        assert_eq!(calls[0].dir, dir.path());
+    }
+
+    #[test]
+    fn workspace_member_is_created_and_registered_once() {
+        let root = TempDir::new("create");
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nresolver = \"2\"\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        let manifest = Manifest::read(&root).unwrap();
+        assert_eq!(default_member_dir(&manifest), "crates");
+
+        let path = create_workspace_member(&root, "crates", "core", ProjectType::Library).unwrap();
+        assert!(path.join("src/lib.rs").is_file());
+
+        let path = create_workspace_member(&root, "", "cli", ProjectType::Binary).unwrap();
+        assert!(path.join("src/main.rs").is_file());
+        let members = Manifest::read(&root).unwrap().workspace_members();
+        assert_eq!(members.iter().filter(|m| *m == "cli").count(), 1);
+        assert!(members.contains(&"crates/*".to_string()));
     }
 }
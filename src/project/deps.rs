@@ -0,0 +1,209 @@
+//! Dependency management for one project (add, remove, bump to latest).
+//!
+//! Adding and removing go through `cargo add` / `cargo remove` so features,
+//! default version requirements and the lockfile are handled exactly like on
+//! the command line. Bumping edits the manifest directly (`toml_edit`), since
+//! `cargo upgrade` is not part of cargo itself.
+
+use std::fmt;
+use std::path::Path;
+
+use log::info;
+
+use crate::cargo_ops::{self, CargoError};
+use crate::crates_io;
+use crate::http::HttpError;
+use crate::project::manifest::{DeclaredDependency, DependencyKind, Manifest, ManifestError};
+
+/// Errors raised by dependency actions.
+#[derive(Debug)]
+pub enum DepsError {
+    Cargo(CargoError),
+    Manifest(ManifestError),
+    Http(HttpError),
+    /// The dependency is not a plain registry dependency (path, git or inherited).
+    NotBumpable(String),
+}
+
+impl fmt::Display for DepsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cargo(e) => write!(f, "{e}"),
+            Self::Manifest(e) => write!(f, "{e}"),
+            Self::Http(e) => write!(f, "crates.io: {e}"),
+            Self::NotBumpable(name) => write!(
+                f,
+                "'{name}' is not a registry dependency with its own version (path, git or workspace-inherited)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DepsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Cargo(e) => Some(e),
+            Self::Manifest(e) => Some(e),
+            Self::Http(e) => Some(e),
+            Self::NotBumpable(_) => None,
+        }
+    }
+}
+
+/// Dependencies declared by the project at `project_path`.
+pub fn list(project_path: &Path) -> Result<Vec<DeclaredDependency>, DepsError> {
+    Manifest::read(project_path)
+        .map(|m| m.declared_dependencies())
+        .map_err(DepsError::Manifest)
+}
+
+/// `cargo add <name> [--dev|--build] [--target <target>]`.
+pub fn add(
+    project_path: &Path,
+    name: &str,
+    kind: DependencyKind,
+    target: Option<&str>,
+) -> Result<(), DepsError> {
+    let mut args = vec!["add", name];
+    args.extend(kind_flag(kind));
+    args.extend(target.into_iter().flat_map(|t| ["--target", t]));
+    cargo_ops::run(project_path, &args).map_err(DepsError::Cargo)?;
+    info!("Added dependency {name} to {}", project_path.display());
+    Ok(())
+}
+
+/// `cargo remove <name> [--dev|--build] [--target <target>]`, from the table declaring `dep`.
+pub fn remove(project_path: &Path, dep: &DeclaredDependency) -> Result<(), DepsError> {
+    let mut args = vec!["remove", dep.name.as_str()];
+    args.extend(kind_flag(dep.kind));
+    args.extend(dep.target.iter().flat_map(|t| ["--target", t.as_str()]));
+    cargo_ops::run(project_path, &args).map_err(DepsError::Cargo)?;
+    info!(
+        "Removed dependency {} from {}",
+        dep.name,
+        project_path.display()
+    );
+    Ok(())
+}
+
+/// Set the requirement of `dep` to the newest crates.io version.
+///
+/// Returns the new requirement, or `None` when the current one is already the latest.
+pub fn bump_to_latest(
+    project_path: &Path,
+    dep: &DeclaredDependency,
+) -> Result<Option<String>, DepsError> {
+    let current = match (&dep.req, &dep.source, dep.inherited) {
+        (Some(req), None, false) => req,
+        _ => return Err(DepsError::NotBumpable(dep.name.clone())),
+    };
+    let latest = crates_io::latest_version(&dep.name)
+        .map_err(DepsError::Http)?
+        .to_string();
+    if current.trim_start_matches(['^', '=']) == latest {
+        return Ok(None);
+    }
+
+    Manifest::edit(project_path, |m| {
+        m.set_dependency_version(dep.kind, dep.target.as_deref(), &dep.name, &latest)
+    })
+    .map_err(DepsError::Manifest)?;
+    info!(
+        "Bumped {} from {current} to {latest} in {}",
+        dep.name,
+        project_path.display()
+    );
+    Ok(Some(latest))
+}
+
+fn kind_flag(kind: DependencyKind) -> Option<&'static str> {
+    match kind {
+        DependencyKind::Normal => None,
+        DependencyKind::Dev => Some("--dev"),
+        DependencyKind::Build => Some("--build"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+    use std::fs;
+
+    /// Workspace of the members `app` and `helper`, where `cargo add helper`
+    /// resolves to the local member without the registry.
+    fn workspace() -> TempDir {
+        let root = TempDir::new("deps");
+        for (member, source) in [("app", "main.rs"), ("helper", "lib.rs")] {
+            fs::create_dir_all(root.join(member).join("src")).unwrap();
+            fs::write(
+                root.join(member).join("Cargo.toml"),
+                format!(
+                    "[package]\nname = \"{member}\"\nversion = \"0.1.0\"\nedition = \"2024\"\n"
+                ),
+            )
+            .unwrap();
+            fs::write(root.join(member).join("src").join(source), "").unwrap();
+        }
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"app\", \"helper\"]\nresolver = \"3\"\n",
+        )
+        .unwrap();
+        root
+    }
+
+    fn tables(deps: &[DeclaredDependency]) -> Vec<(DependencyKind, Option<&str>)> {
+        deps.iter().map(|d| (d.kind, d.target.as_deref())).collect()
+    }
+
+    #[test]
+    fn adds_and_removes_in_every_table() {
+        let root = workspace();
+        let app = root.join("app");
+        let added = [
+            (DependencyKind::Normal, None),
+            (DependencyKind::Dev, None),
+            (DependencyKind::Normal, Some("cfg(unix)")),
+            (DependencyKind::Dev, Some("cfg(windows)")),
+        ];
+        for (kind, target) in added {
+            add(&app, "helper", kind, target).unwrap();
+        }
+
+        let deps = list(&app).unwrap();
+        assert_eq!(tables(&deps), added);
+        assert!(deps.iter().all(|d| d.name == "helper"));
+        assert!(
+            deps.iter()
+                .all(|d| d.source.as_deref() == Some("../helper"))
+        );
+        let manifest = fs::read_to_string(app.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("[target.\"cfg(unix)\".dependencies]"));
+        assert!(manifest.contains("[target.\"cfg(windows)\".dev-dependencies]"));
+
+        remove(&app, &deps[2]).unwrap();
+        remove(&app, &deps[1]).unwrap();
+        assert_eq!(tables(&list(&app).unwrap()), [added[0], added[3]]);
+        remove(&app, &deps[3]).unwrap();
+        remove(&app, &deps[0]).unwrap();
+        assert!(list(&app).unwrap().is_empty());
+        assert!(
+            !fs::read_to_string(app.join("Cargo.toml"))
+                .unwrap()
+                .contains("helper")
+        );
+    }
+
+    #[test]
+    fn path_dependencies_are_not_bumped() {
+        let root = workspace();
+        let app = root.join("app");
+        add(&app, "helper", DependencyKind::Normal, None).unwrap();
+        let dep = list(&app).unwrap().remove(0);
+        assert!(matches!(
+            bump_to_latest(&app, &dep),
+            Err(DepsError::NotBumpable(name)) if name == "helper"
+        ));
+    }
+}
@@ -0,0 +1,65 @@
+//! Disk usage of the projects.
+//!
+//! Sizes every project directory and its `target/` directory (build
+//! artifacts, what `cargo clean` reclaims) with one parallel walk over all
+//! projects (see `crate::dir_walk`).
+
+use std::cmp::Reverse;
+use std::path::PathBuf;
+
+use crate::dir_walk;
+use crate::project::list::ProjectInfo;
+
+/// Sizes of a project, in bytes.
+#[derive(Debug, Clone)]
+pub struct Usage {
+    pub name: String,
+    pub path: PathBuf,
+    /// The whole directory, `target/` included.
+    pub total: u64,
+    /// `target/` alone (0 when missing).
+    pub target: u64,
+}
+
+/// Order of the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    Total,
+    Target,
+    Name,
+}
+
+/// Sizes of `projects`, largest first.
+pub fn collect(projects: &[ProjectInfo]) -> Vec<Usage> {
+    let roots: Vec<PathBuf> = projects
+        .iter()
+        .flat_map(|p| [p.path.clone(), p.path.join("target")])
+        .collect();
+    let sizes = dir_walk::sizes(&roots);
+    let mut usage: Vec<Usage> = projects
+        .iter()
+        .zip(sizes.chunks(2))
+        .map(|(project, sizes)| Usage {
+            name: project.name.clone(),
+            path: project.path.clone(),
+            total: sizes[0],
+            target: sizes[1],
+        })
+        .collect();
+    sort(&mut usage, SortBy::Total);
+    usage
+}
+
+/// Sort `usage` by `by`: sizes largest first, names alphabetically.
+pub fn sort(usage: &mut [Usage], by: SortBy) {
+    match by {
+        SortBy::Total => usage.sort_by_key(|u| Reverse(u.total)),
+        SortBy::Target => usage.sort_by_key(|u| Reverse(u.target)),
+        SortBy::Name => usage.sort_by_key(|u| u.name.to_lowercase()),
+    }
+}
+
+/// Space `cargo clean` would reclaim over all of `usage`.
+pub fn reclaimable(usage: &[Usage]) -> u64 {
+    usage.iter().map(|u| u.target).sum()
+}
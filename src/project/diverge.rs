@@ -0,0 +1,190 @@
+//! Reconcile a diverged default branch with its upstream.
+//!
+//! Three guided ways out, run with the git CLI on the checked-out default
+//! branch of a clean working tree:
+//! - rebase the local commits onto the upstream (linear history);
+//! - merge the upstream into the local branch (keeps both histories);
+//! - reset the local branch to the upstream, dropping the local commits; they
+//!   stay reachable from a `<branch>-backup-<timestamp>` branch.
+//!
+//! A rebase or merge that stops on conflicts is aborted, leaving the
+//! repository as it was: conflicts are for the editor, not for this screen.
+
+use std::fmt;
+use std::path::Path;
+
+use crate::cargo_ops;
+use crate::git_ops::{self, Divergence, GitError};
+use crate::metadata;
+use crate::project::list::scan_git_status;
+
+/// How to reconcile the local branch with its upstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reconcile {
+    Rebase,
+    Merge,
+    Reset,
+}
+
+impl Reconcile {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Rebase => "Rebase local commits onto the upstream",
+            Self::Merge => "Merge the upstream into the local branch",
+            Self::Reset => "Reset to the upstream (drop local commits)",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DivergeError {
+    /// The diverged branch must be checked out first.
+    NotCheckedOut(String),
+    /// Uncommitted changes in the working tree.
+    Dirty,
+    /// The rebase or merge stopped on conflicts and was aborted.
+    Conflicts(String),
+    Git(GitError),
+    Cli(String),
+}
+
+impl fmt::Display for DivergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotCheckedOut(branch) => write!(f, "Check out '{branch}' first"),
+            Self::Dirty => write!(f, "Commit or stash the uncommitted changes first"),
+            Self::Conflicts(msg) => write!(
+                f,
+                "Stopped on conflicts and aborted, nothing changed; resolve them in your editor:\n{msg}"
+            ),
+            Self::Git(e) => write!(f, "{e}"),
+            Self::Cli(msg) => write!(f, "git failed: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for DivergeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Git(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Apply `how` to the diverged branch of the repository at `path`.
+pub fn reconcile(
+    path: &Path,
+    divergence: &Divergence,
+    how: Reconcile,
+    log: &dyn Fn(String),
+) -> Result<(), DivergeError> {
+    let current = git_ops::current_branch(path).map_err(DivergeError::Git)?;
+    if current.as_deref() != Some(divergence.branch.as_str()) {
+        return Err(DivergeError::NotCheckedOut(divergence.branch.clone()));
+    }
+    if scan_git_status(path).map_err(|e| DivergeError::Git(e.into()))? {
+        return Err(DivergeError::Dirty);
+    }
+
+    let upstream = divergence.upstream.as_str();
+    match how {
+        Reconcile::Rebase => {
+            log(format!("git rebase {upstream}"));
+            if let Err(e) = git(path, &["rebase", upstream]) {
+                let _ = git(path, &["rebase", "--abort"]);
+                return Err(DivergeError::Conflicts(e));
+            }
+        }
+        Reconcile::Merge => {
+            log(format!("git merge {upstream}"));
+            if let Err(e) = git(path, &["merge", "--no-edit", upstream]) {
+                let _ = git(path, &["merge", "--abort"]);
+                return Err(DivergeError::Conflicts(e));
+            }
+        }
+        Reconcile::Reset => {
+            let backup = format!("{}-backup-{}", divergence.branch, metadata::now());
+            log(format!("git branch {backup}"));
+            git(path, &["branch", &backup]).map_err(DivergeError::Cli)?;
+            log(format!("git reset --hard {upstream}"));
+            git(path, &["reset", "--hard", upstream]).map_err(DivergeError::Cli)?;
+        }
+    }
+    Ok(())
+}
+
+fn git(path: &Path, args: &[&str]) -> Result<(), String> {
+    cargo_ops::run_tool("git", path, args, &[])
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn commit(dir: &Path, file: &str) {
+        fs::write(dir.join(file), file).unwrap();
+        git(dir, &["add", file]).unwrap();
+        git(dir, &["commit", "-q", "-m", file]).unwrap();
+    }
+
+    /// A clone whose checked-out branch diverged from `origin` by one commit each way,
+    /// and the directory holding it.
+    fn diverged_clone() -> (TempDir, PathBuf) {
+        let base = TempDir::new("diverge");
+        let remote = base.join("remote.git");
+        let (a, b) = (base.join("a"), base.join("b"));
+        git(&base, &["init", "-q", "--bare", remote.to_str().unwrap()]).unwrap();
+        let clone = |dir: &Path| {
+            git(
+                &base,
+                &[
+                    "clone",
+                    "-q",
+                    remote.to_str().unwrap(),
+                    dir.to_str().unwrap(),
+                ],
+            )
+            .unwrap();
+            git(dir, &["config", "user.name", "Test"]).unwrap();
+            git(dir, &["config", "user.email", "test@example.com"]).unwrap();
+        };
+        clone(&a);
+        commit(&a, "first");
+        git(&a, &["push", "-q", "origin", "HEAD"]).unwrap();
+        clone(&b);
+        commit(&a, "theirs");
+        git(&a, &["push", "-q", "origin", "HEAD"]).unwrap();
+        commit(&b, "ours");
+        git(&b, &["fetch", "-q"]).unwrap();
+        (base, b)
+    }
+
+    #[test]
+    fn rebase_leaves_only_local_commits_ahead() {
+        let (_base, dir) = diverged_clone();
+        let branch = git_ops::current_branch(&dir).unwrap().unwrap();
+        let divergence = Divergence {
+            branch: branch.clone(),
+            upstream: format!("origin/{branch}"),
+            ahead: 1,
+            behind: 1,
+        };
+
+        fs::write(dir.join("ours"), "edited").unwrap();
+        assert!(matches!(
+            reconcile(&dir, &divergence, Reconcile::Rebase, &|_| {}),
+            Err(DivergeError::Dirty)
+        ));
+        git(&dir, &["checkout", "--", "ours"]).unwrap();
+
+        reconcile(&dir, &divergence, Reconcile::Rebase, &|_| {}).unwrap();
+        assert_eq!(git_ops::ahead_behind(&dir).unwrap(), Some((1, 0)));
+        assert!(dir.join("theirs").exists());
+    }
+}
@@ -0,0 +1,206 @@
+//! Reproducible environment report.
+//!
+//! Captures what is needed to reproduce a build of a project on another
+//! machine: rustc / cargo versions, active toolchain (as resolved by rustup in
+//! the project directory, overrides included), host and configured target,
+//! declared and default features, OS, and a hash of `Cargo.lock`. Rendered as
+//! text (for issue bodies) and JSON (for tooling).
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::cargo_ops;
+use crate::project::manifest::{self, Manifest};
+use crate::project::release::sha256_hex;
+
+/// Directory (inside the project) receiving the report files; under `target/` so it is never committed.
+const REPORT_DIR: [&str; 2] = ["target", "rustm"];
+const REPORT_NAME: &str = "env-report";
+
+/// Everything captured by the report. Missing information is `None` (tool not installed, ...).
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvReport {
+    pub rustm_version: String,
+    pub project: PathBuf,
+    pub package: Option<String>,
+    pub package_version: Option<String>,
+    pub rustc: Option<String>,
+    pub rustc_commit: Option<String>,
+    pub llvm: Option<String>,
+    pub cargo: Option<String>,
+    pub toolchain: Option<String>,
+    pub host: Option<String>,
+    /// `build.target` from `.cargo/config.toml`.
+    pub configured_target: Option<String>,
+    pub features: Vec<String>,
+    pub default_features: Vec<String>,
+    pub os: String,
+    pub arch: String,
+    /// SHA-256 of `Cargo.lock`.
+    pub lockfile_sha256: Option<String>,
+}
+
+/// Collect the report for the project at `project_path` (runs rustc, cargo and rustup).
+pub fn collect(project_path: &Path) -> EnvReport {
+    let manifest = Manifest::read(project_path).ok();
+    let rustc = tool_output(project_path, "rustc", &["-vV"]);
+    let rustc_field = |key: &str| {
+        rustc.as_deref().and_then(|out| {
+            out.lines()
+                .find_map(|l| l.strip_prefix(key))
+                .map(|v| v.trim().to_string())
+        })
+    };
+
+    EnvReport {
+        rustm_version: env!("CARGO_PKG_VERSION").to_string(),
+        project: project_path.to_path_buf(),
+        package: manifest
+            .as_ref()
+            .and_then(|m| m.package_name().map(ToString::to_string)),
+        package_version: manifest
+            .as_ref()
+            .and_then(|m| m.package_version().map(ToString::to_string)),
+        rustc: rustc
+            .as_deref()
+            .and_then(|o| o.lines().next())
+            .map(ToString::to_string),
+        rustc_commit: rustc_field("commit-hash:"),
+        llvm: rustc_field("LLVM version:"),
+        cargo: first_line(tool_output(project_path, "cargo", &["-V"])),
+        toolchain: first_line(tool_output(
+            project_path,
+            "rustup",
+            &["show", "active-toolchain"],
+        )),
+        host: rustc_field("host:"),
+        configured_target: manifest::configured_build_target(project_path),
+        features: manifest
+            .as_ref()
+            .map(Manifest::feature_names)
+            .unwrap_or_default(),
+        default_features: manifest
+            .as_ref()
+            .map(Manifest::default_features)
+            .unwrap_or_default(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        lockfile_sha256: fs::read(project_path.join("Cargo.lock"))
+            .ok()
+            .map(|data| sha256_hex(&data)),
+    }
+}
+
+impl EnvReport {
+    /// Human readable report, one `key: value` per line.
+    pub fn to_text(&self) -> String {
+        let or_unknown = |v: &Option<String>| v.clone().unwrap_or_else(|| "unknown".into());
+        let list = |v: &[String]| {
+            if v.is_empty() {
+                "(none)".to_string()
+            } else {
+                v.join(", ")
+            }
+        };
+        let mut out = String::new();
+        writeln!(out, "rustm: {}", self.rustm_version).unwrap();
+        writeln!(out, "project: {}", self.project.display()).unwrap();
+        writeln!(
+            out,
+            "package: {} {}",
+            or_unknown(&self.package),
+            or_unknown(&self.package_version)
+        )
+        .unwrap();
+        writeln!(out, "rustc: {}", or_unknown(&self.rustc)).unwrap();
+        writeln!(out, "rustc commit: {}", or_unknown(&self.rustc_commit)).unwrap();
+        writeln!(out, "LLVM: {}", or_unknown(&self.llvm)).unwrap();
+        writeln!(out, "cargo: {}", or_unknown(&self.cargo)).unwrap();
+        writeln!(out, "toolchain: {}", or_unknown(&self.toolchain)).unwrap();
+        writeln!(out, "host: {}", or_unknown(&self.host)).unwrap();
+        writeln!(
+            out,
+            "configured target: {}",
+            self.configured_target.as_deref().unwrap_or("(none)")
+        )
+        .unwrap();
+        writeln!(out, "features: {}", list(&self.features)).unwrap();
+        writeln!(out, "default features: {}", list(&self.default_features)).unwrap();
+        writeln!(out, "os: {} ({})", self.os, self.arch).unwrap();
+        writeln!(
+            out,
+            "Cargo.lock sha256: {}",
+            self.lockfile_sha256.as_deref().unwrap_or("(no lockfile)")
+        )
+        .unwrap();
+        out
+    }
+
+    /// Pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Write `env-report.txt` and `env-report.json` under `<project>/target/rustm/`; returns both paths.
+pub fn write(report: &EnvReport) -> std::io::Result<(PathBuf, PathBuf)> {
+    let dir = REPORT_DIR
+        .iter()
+        .fold(report.project.clone(), |dir, part| dir.join(part));
+    fs::create_dir_all(&dir)?;
+    let text = dir.join(format!("{REPORT_NAME}.txt"));
+    let json = dir.join(format!("{REPORT_NAME}.json"));
+    fs::write(&text, report.to_text())?;
+    fs::write(&json, report.to_json())?;
+    Ok((text, json))
+}
+
+fn tool_output(project_path: &Path, program: &str, args: &[&str]) -> Option<String> {
+    cargo_ops::run_tool(program, project_path, args, &[]).ok()
+}
+
+fn first_line(output: Option<String>) -> Option<String> {
+    output
+        .as_deref()
+        .and_then(|o| o.lines().next())
+        .map(|l| l.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn captures_manifest_and_lockfile_information() {
+        let dir = TempDir::new("env_report");
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.3.0\"\n\n[features]\ndefault = [\"fast\"]\nfast = []\nslow = []\n",
+        )
+        .unwrap();
+        fs::write(dir.join("Cargo.lock"), "version = 4\n").unwrap();
+
+        let report = collect(&dir);
+        assert_eq!(report.package.as_deref(), Some("demo"));
+        assert_eq!(report.features, vec!["fast", "slow"]);
+        assert_eq!(report.default_features, vec!["fast"]);
+        assert_eq!(
+            report.lockfile_sha256.as_deref(),
+            Some(sha256_hex(b"version = 4\n").as_str())
+        );
+
+        let (text, json) = write(&report).unwrap();
+        assert!(
+            fs::read_to_string(text)
+                .unwrap()
+                .contains("default features: fast")
+        );
+        let parsed: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(json).unwrap()).unwrap();
+        assert_eq!(parsed["package_version"], "0.3.0");
+    }
+}
@@ -0,0 +1,191 @@
+//! Feature matrix: cargo commands over combinations of the package features.
+//!
+//! A single `cargo test` only builds the default features. Code that needs a
+//! feature it does not enable itself (an import behind `#[cfg(feature)]`
+//! used elsewhere, a dependency only pulled in by another feature) breaks as
+//! soon as someone picks a different set. The matrix runs the configured
+//! commands (`check` and `test` by default) for the default features,
+//! `--no-default-features`, each feature alone and `--all-features`.
+//!
+//! Commands run one after the other in the project directory, sharing its
+//! `target` directory.
+
+use std::path::Path;
+
+use crate::cargo_ops::{self, CargoError};
+use crate::config::FeatureMatrixSettings;
+use crate::project::manifest::{Manifest, ManifestError};
+
+/// Lines of error output kept per failed command.
+const ERROR_TAIL: usize = 40;
+
+/// A set of features, as cargo arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combination {
+    pub label: String,
+    pub args: Vec<String>,
+}
+
+/// Result of one command for one combination.
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Passed,
+    /// The end of the error output.
+    Failed(String),
+}
+
+impl Outcome {
+    pub fn passed(&self) -> bool {
+        matches!(self, Self::Passed)
+    }
+}
+
+/// Outcomes of the commands for one combination, in command order.
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub combination: Combination,
+    pub outcomes: Vec<Outcome>,
+}
+
+/// The pass / fail grid.
+#[derive(Debug, Clone)]
+pub struct Matrix {
+    pub commands: Vec<String>,
+    pub rows: Vec<Row>,
+}
+
+impl Matrix {
+    /// Number of failed commands.
+    pub fn failures(&self) -> usize {
+        self.rows
+            .iter()
+            .flat_map(|r| &r.outcomes)
+            .filter(|o| !o.passed())
+            .count()
+    }
+}
+
+/// Combinations to build for `manifest`: the defaults first.
+///
+/// Without any feature, the other combinations would build the same thing:
+/// only the defaults are returned.
+pub fn combinations(manifest: &Manifest, settings: &FeatureMatrixSettings) -> Vec<Combination> {
+    let combination = |label: &str, args: &[&str]| Combination {
+        label: label.to_string(),
+        args: args.iter().map(ToString::to_string).collect(),
+    };
+    let mut combinations = vec![combination("default features", &[])];
+    let features = manifest.feature_names();
+    if features.is_empty() {
+        return combinations;
+    }
+    if settings.no_default_features {
+        combinations.push(combination(
+            "no default features",
+            &["--no-default-features"],
+        ));
+    }
+    if settings.each_feature {
+        for feature in features.iter().filter(|f| !settings.skip.contains(f)) {
+            combinations.push(combination(
+                &format!("only {feature}"),
+                &["--no-default-features", "--features", feature],
+            ));
+        }
+    }
+    if settings.all_features {
+        combinations.push(combination("all features", &["--all-features"]));
+    }
+    combinations
+}
+
+/// Run every command of `settings` for every combination of the project at `path`.
+///
+/// `log` gets the command about to run.
+pub fn run(
+    path: &Path,
+    settings: &FeatureMatrixSettings,
+    log: &dyn Fn(String),
+) -> Result<Matrix, ManifestError> {
+    let manifest = Manifest::read(path)?;
+    let rows = combinations(&manifest, settings)
+        .into_iter()
+        .map(|combination| {
+            let outcomes = settings
+                .commands
+                .iter()
+                .map(|command| {
+                    let mut args: Vec<&str> = command.split_whitespace().collect();
+                    args.extend(combination.args.iter().map(String::as_str));
+                    log(format!("cargo {}", args.join(" ")));
+                    match cargo_ops::run(path, &args) {
+                        Ok(_) => Outcome::Passed,
+                        Err(CargoError::Failed { stderr, .. }) => Outcome::Failed(tail(&stderr)),
+                        Err(e) => Outcome::Failed(e.to_string()),
+                    }
+                })
+                .collect();
+            Row {
+                combination,
+                outcomes,
+            }
+        })
+        .collect();
+    Ok(Matrix {
+        commands: settings.commands.clone(),
+        rows,
+    })
+}
+
+/// Last `ERROR_TAIL` lines of `output`.
+fn tail(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    lines[lines.len().saturating_sub(ERROR_TAIL)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_the_combinations() {
+        let manifest = Manifest::parse(
+            "[package]\nname = \"demo\"\n\n[features]\ndefault = [\"std\"]\nstd = []\nserde = []\nnightly = []\n",
+        )
+        .unwrap();
+        let mut settings = FeatureMatrixSettings {
+            skip: vec!["nightly".into()],
+            ..FeatureMatrixSettings::default()
+        };
+        let labels = |settings: &FeatureMatrixSettings| -> Vec<String> {
+            combinations(&manifest, settings)
+                .into_iter()
+                .map(|c| c.label)
+                .collect()
+        };
+        assert_eq!(
+            labels(&settings),
+            [
+                "default features",
+                "no default features",
+                "only serde",
+                "only std",
+                "all features"
+            ]
+        );
+        assert_eq!(
+            combinations(&manifest, &settings)[2].args,
+            ["--no-default-features", "--features", "serde"]
+        );
+
+        settings.each_feature = false;
+        settings.all_features = false;
+        assert_eq!(
+            labels(&settings),
+            ["default features", "no default features"]
+        );
+
+        let plain = Manifest::parse("[package]\nname = \"demo\"\n").unwrap();
+        assert_eq!(combinations(&plain, &settings).len(), 1);
+    }
+}
@@ -0,0 +1,367 @@
+//! Filter expressions selecting projects (`rustm batch --filter`).
+//!
+//! An expression is a list of conditions separated by spaces or commas, all of
+//! which must hold. A condition is `field OP value`, `OP` being one of `=`,
+//! `!=`, `<`, `<=`, `>`, `>=`:
+//! - `name`, `branch`, `tag`: text, `=` / `!=` only (`tag=work`: has the tag).
+//! - `dirty`, `pinned`, `untrusted`: `true` / `false`; the field alone means `true`.
+//! - `size`, `target_size`: bytes, with an optional `K`, `M` or `G` unit
+//!   (`KB` / `KiB` and so on, powers of 1024 like the disk usage report).
+//! - `age`: days since the last commit, with an optional `d` (`age>90d`);
+//!   projects without commits never match.
+//!
+//! Sizes are only computed when a condition needs them (see `project::disk_usage`).
+
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::project::disk_usage;
+use crate::project::list::ProjectInfo;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Errors raised while parsing a filter expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterError {
+    UnknownField(String),
+    /// The operator does not apply to the field (`name>x`).
+    InvalidOperator {
+        field: String,
+        op: String,
+    },
+    InvalidValue {
+        field: String,
+        value: String,
+    },
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownField(field) => write!(
+                f,
+                "unknown filter field '{field}' (name, branch, tag, dirty, pinned, untrusted, \
+                 size, target_size, age)"
+            ),
+            Self::InvalidOperator { field, op } => {
+                write!(f, "'{op}' cannot be used with '{field}'")
+            }
+            Self::InvalidValue { field, value } => {
+                write!(f, "invalid value '{value}' for '{field}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    Branch,
+    Tag,
+    Dirty,
+    Pinned,
+    Untrusted,
+    Size,
+    TargetSize,
+    Age,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Operators by their text, two-character ones first.
+const OPS: [(&str, Op); 6] = [
+    ("!=", Op::Ne),
+    ("<=", Op::Le),
+    (">=", Op::Ge),
+    ("=", Op::Eq),
+    ("<", Op::Lt),
+    (">", Op::Gt),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Text(String),
+    Bool(bool),
+    Number(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Condition {
+    field: Field,
+    op: Op,
+    value: Value,
+}
+
+/// Conditions a project must all meet; the empty filter matches every project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    conditions: Vec<Condition>,
+}
+
+impl Filter {
+    /// Parse `expression` (see the module documentation).
+    pub fn parse(expression: &str) -> Result<Self, FilterError> {
+        let conditions = expression
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .map(parse_condition)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { conditions })
+    }
+
+    /// Also require the `tag` tag.
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.conditions.push(Condition {
+            field: Field::Tag,
+            op: Op::Eq,
+            value: Value::Text(tag.to_string()),
+        });
+        self
+    }
+
+    /// `true` when matching needs the disk usage of the projects.
+    pub fn needs_sizes(&self) -> bool {
+        self.conditions
+            .iter()
+            .any(|c| matches!(c.field, Field::Size | Field::TargetSize))
+    }
+
+    /// Does `project` meet every condition? `usage` is its disk usage, when
+    /// computed; `now` is in seconds since epoch.
+    pub fn matches(
+        &self,
+        project: &ProjectInfo,
+        usage: Option<&disk_usage::Usage>,
+        now: i64,
+    ) -> bool {
+        self.conditions
+            .iter()
+            .all(|c| c.matches(project, usage, now))
+    }
+
+    /// The `projects` this filter matches, in their order; sizes are computed when needed.
+    pub fn select(&self, projects: Vec<ProjectInfo>) -> Vec<ProjectInfo> {
+        let usage = if self.needs_sizes() {
+            disk_usage::collect(&projects)
+        } else {
+            Vec::new()
+        };
+        let usage_of = |path: &PathBuf| usage.iter().find(|u| &u.path == path);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        projects
+            .into_iter()
+            .filter(|p| self.matches(p, usage_of(&p.path), now))
+            .collect()
+    }
+}
+
+impl Condition {
+    fn matches(&self, project: &ProjectInfo, usage: Option<&disk_usage::Usage>, now: i64) -> bool {
+        let text = |actual: Option<&str>| {
+            let Value::Text(wanted) = &self.value else {
+                return false;
+            };
+            (actual == Some(wanted.as_str())) == (self.op == Op::Eq)
+        };
+        let flag = |actual: bool| (self.value == Value::Bool(actual)) == (self.op == Op::Eq);
+        let number = |actual: Option<u64>| match (actual, &self.value) {
+            (Some(actual), Value::Number(wanted)) => compare(actual, self.op, *wanted),
+            _ => false,
+        };
+        match self.field {
+            Field::Name => text(Some(&project.name)),
+            Field::Branch => text(project.branch.as_deref()),
+            Field::Tag => {
+                let Value::Text(wanted) = &self.value else {
+                    return false;
+                };
+                project.tags.contains(wanted) == (self.op == Op::Eq)
+            }
+            Field::Dirty => flag(project.has_uncommitted_changes),
+            Field::Pinned => flag(project.pinned),
+            Field::Untrusted => flag(project.untrusted),
+            Field::Size => number(usage.map(|u| u.total)),
+            Field::TargetSize => number(usage.map(|u| u.target)),
+            Field::Age => number(
+                project
+                    .last_commit
+                    .map(|time| (now - time).max(0) as u64 / SECONDS_PER_DAY as u64),
+            ),
+        }
+    }
+}
+
+fn compare(actual: u64, op: Op, wanted: u64) -> bool {
+    match op {
+        Op::Eq => actual == wanted,
+        Op::Ne => actual != wanted,
+        Op::Lt => actual < wanted,
+        Op::Le => actual <= wanted,
+        Op::Gt => actual > wanted,
+        Op::Ge => actual >= wanted,
+    }
+}
+
+fn parse_condition(token: &str) -> Result<Condition, FilterError> {
+    let found = token.find(['!', '=', '<', '>']).and_then(|at| {
+        OPS.iter()
+            .find(|(text, _)| token[at..].starts_with(text))
+            .map(|(text, op)| (at, *text, *op))
+    });
+    let (name, op_text, op, raw) = match found {
+        Some((at, text, op)) => (&token[..at], text, op, &token[at + text.len()..]),
+        // A flag alone: `dirty`.
+        None => (token, "=", Op::Eq, "true"),
+    };
+    let field = match name.to_lowercase().as_str() {
+        "name" => Field::Name,
+        "branch" => Field::Branch,
+        "tag" => Field::Tag,
+        "dirty" => Field::Dirty,
+        "pinned" => Field::Pinned,
+        "untrusted" => Field::Untrusted,
+        "size" => Field::Size,
+        "target_size" => Field::TargetSize,
+        "age" => Field::Age,
+        _ => return Err(FilterError::UnknownField(name.to_string())),
+    };
+    let invalid_value = || FilterError::InvalidValue {
+        field: name.to_string(),
+        value: raw.to_string(),
+    };
+    let value = match field {
+        Field::Name
+        | Field::Branch
+        | Field::Tag
+        | Field::Dirty
+        | Field::Pinned
+        | Field::Untrusted
+            if !matches!(op, Op::Eq | Op::Ne) =>
+        {
+            return Err(FilterError::InvalidOperator {
+                field: name.to_string(),
+                op: op_text.to_string(),
+            });
+        }
+        Field::Name | Field::Branch | Field::Tag if !raw.is_empty() => Value::Text(raw.to_string()),
+        Field::Dirty | Field::Pinned | Field::Untrusted => {
+            Value::Bool(raw.parse().map_err(|_| invalid_value())?)
+        }
+        Field::Size | Field::TargetSize => {
+            Value::Number(parse_size(raw).ok_or_else(invalid_value)?)
+        }
+        Field::Age => Value::Number(
+            raw.strip_suffix(['d', 'D'])
+                .unwrap_or(raw)
+                .parse()
+                .map_err(|_| invalid_value())?,
+        ),
+        Field::Name | Field::Branch | Field::Tag => return Err(invalid_value()),
+    };
+    Ok(Condition { field, op, value })
+}
+
+/// `1500`, `512K`, `1.5MB`, `1GiB` in bytes (units are powers of 1024).
+fn parse_size(raw: &str) -> Option<u64> {
+    let upper = raw.to_uppercase();
+    let digits = upper.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let factor: u64 = match &upper[digits.len()..] {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        _ => return None,
+    };
+    let number: f64 = digits.parse().ok().filter(|n: &f64| *n >= 0.0)?;
+    Some((number * factor as f64) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(name: &str, dirty: bool, tags: &[&str], last_commit: Option<i64>) -> ProjectInfo {
+        ProjectInfo {
+            name: name.into(),
+            path: PathBuf::from(format!("/projects/{name}")),
+            has_uncommitted_changes: dirty,
+            branch: Some("main".into()),
+            ahead_behind: None,
+            diverged_default: None,
+            rollup: None,
+            no_std: None,
+            version: None,
+            kind: None,
+            last_commit,
+            remote_url: None,
+            fingerprint: 0,
+            pinned: false,
+            last_opened: None,
+            tags: tags.iter().map(ToString::to_string).collect(),
+            untrusted: false,
+        }
+    }
+
+    #[test]
+    fn conditions_are_parsed_and_matched() {
+        let now = 100 * SECONDS_PER_DAY;
+        let old = project("old", false, &["work"], Some(now - 95 * SECONDS_PER_DAY));
+        let fresh = project("fresh", true, &[], Some(now - SECONDS_PER_DAY));
+        let usage = |target| disk_usage::Usage {
+            name: String::new(),
+            path: PathBuf::new(),
+            total: target * 2,
+            target,
+        };
+
+        let filter = Filter::parse("tag=work, age>90d").unwrap();
+        assert!(filter.matches(&old, None, now));
+        assert!(!filter.matches(&fresh, None, now));
+        assert!(!filter.needs_sizes());
+
+        let dirty = Filter::parse("dirty branch=main").unwrap();
+        assert!(dirty.matches(&fresh, None, now));
+        assert!(!dirty.matches(&old, None, now));
+        assert!(
+            Filter::parse("dirty!=true")
+                .unwrap()
+                .matches(&old, None, now)
+        );
+
+        let big = Filter::parse("target_size>1GB").unwrap();
+        assert!(big.needs_sizes());
+        assert!(big.matches(&old, Some(&usage(3 << 30)), now));
+        assert!(!big.matches(&old, Some(&usage(1 << 30)), now));
+        assert!(!big.matches(&old, None, now));
+        assert_eq!(parse_size("1.5M"), Some(3 << 19));
+
+        assert!(Filter::default().with_tag("work").matches(&old, None, now));
+        assert!(Filter::parse("").unwrap().matches(&fresh, None, now));
+        assert_eq!(
+            Filter::parse("colour=red"),
+            Err(FilterError::UnknownField("colour".into()))
+        );
+        assert!(matches!(
+            Filter::parse("name>a"),
+            Err(FilterError::InvalidOperator { .. })
+        ));
+        assert!(matches!(
+            Filter::parse("size>lots"),
+            Err(FilterError::InvalidValue { .. })
+        ));
+    }
+}
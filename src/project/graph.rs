@@ -0,0 +1,240 @@
+//! Path dependency graph across projects.
+//!
+//! Detects `path = "..."` dependencies pointing from one listed project into
+//! another (possibly into a member crate of it) so the user sees which projects
+//! are built from local checkouts of others, and is warned before changing or
+//! removing a project others depend on.
+//!
+//! Manifests considered for a project: its root `Cargo.toml` and, for
+//! workspaces, the members matched by `workspace.members` (plain paths and
+//! `dir/*` patterns).
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::warn;
+
+use crate::project::list::ProjectInfo;
+use crate::project::manifest::Manifest;
+
+/// A dependency of project `from` on project `to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    /// Dependency name as declared.
+    pub dependency: String,
+}
+
+/// Cross-project path dependency graph; nodes are indices into `projects`.
+#[derive(Debug)]
+pub struct DependencyGraph {
+    pub projects: Vec<ProjectInfo>,
+    pub edges: Vec<Edge>,
+}
+
+impl DependencyGraph {
+    /// Build the graph for `projects`, reading their manifests.
+    pub fn build(projects: Vec<ProjectInfo>) -> Self {
+        let roots: Vec<Option<PathBuf>> = projects
+            .iter()
+            .map(|p| fs::canonicalize(&p.path).ok())
+            .collect();
+
+        let mut edges = Vec::new();
+        for (from, project) in projects.iter().enumerate() {
+            for manifest_dir in manifest_dirs(&project.path) {
+                let manifest = match Manifest::read(&manifest_dir) {
+                    Ok(m) => m,
+                    Err(e) => {
+                        warn!(
+                            "Skipping {} in dependency graph: {e}",
+                            manifest_dir.display()
+                        );
+                        continue;
+                    }
+                };
+                for (dependency, rel) in manifest.path_dependencies() {
+                    let Ok(target) = fs::canonicalize(manifest_dir.join(&rel)) else {
+                        continue;
+                    };
+                    if let Some(to) = owner(&roots, &target)
+                        && to != from
+                    {
+                        let edge = Edge {
+                            from,
+                            to,
+                            dependency,
+                        };
+                        if !edges.contains(&edge) {
+                            edges.push(edge);
+                        }
+                    }
+                }
+            }
+        }
+
+        Self { projects, edges }
+    }
+
+    /// Projects depending on the project at `path`.
+    pub fn dependents(&self, path: &Path) -> Vec<&ProjectInfo> {
+        let Some(index) = self.projects.iter().position(|p| p.path == path) else {
+            return Vec::new();
+        };
+        let mut out: Vec<&ProjectInfo> = self
+            .edges
+            .iter()
+            .filter(|e| e.to == index)
+            .map(|e| &self.projects[e.from])
+            .collect();
+        out.dedup_by(|a, b| a.path == b.path);
+        out
+    }
+
+    /// Text rendering: one block per project having dependencies, then reverse edges.
+    pub fn render(&self) -> String {
+        if self.edges.is_empty() {
+            return "No path dependencies between projects.".to_string();
+        }
+
+        let mut out = String::new();
+        for (index, project) in self.projects.iter().enumerate() {
+            let deps: Vec<&Edge> = self.edges.iter().filter(|e| e.from == index).collect();
+            if deps.is_empty() {
+                continue;
+            }
+            writeln!(out, "{}", project.name).unwrap();
+            for (i, edge) in deps.iter().enumerate() {
+                let branch = if i + 1 == deps.len() {
+                    "└─"
+                } else {
+                    "├─"
+                };
+                let target = &self.projects[edge.to].name;
+                if *target == edge.dependency {
+                    writeln!(out, "  {branch} {target}").unwrap();
+                } else {
+                    writeln!(out, "  {branch} {target} ({})", edge.dependency).unwrap();
+                }
+            }
+        }
+
+        out.push_str("\nUsed by:\n");
+        for (index, project) in self.projects.iter().enumerate() {
+            let mut users: Vec<&str> = self
+                .edges
+                .iter()
+                .filter(|e| e.to == index)
+                .map(|e| self.projects[e.from].name.as_str())
+                .collect();
+            users.dedup();
+            if !users.is_empty() {
+                writeln!(out, "  {} <- {}", project.name, users.join(", ")).unwrap();
+            }
+        }
+        out
+    }
+}
+
+/// Directories whose `Cargo.toml` belongs to the project at `root`.
+pub fn manifest_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![root.to_path_buf()];
+    let Ok(manifest) = Manifest::read(root) else {
+        return dirs;
+    };
+    for pattern in manifest.workspace_members() {
+        if let Some(parent) = pattern.strip_suffix("/*") {
+            if let Ok(entries) = fs::read_dir(root.join(parent)) {
+                dirs.extend(
+                    entries
+                        .flatten()
+                        .map(|e| e.path())
+                        .filter(|p| p.join("Cargo.toml").is_file()),
+                );
+            }
+        } else if pattern != "." {
+            dirs.push(root.join(pattern));
+        }
+    }
+    dirs
+}
+
+/// Index of the project containing `target` (the deepest one when nested).
+fn owner(roots: &[Option<PathBuf>], target: &Path) -> Option<usize> {
+    roots
+        .iter()
+        .enumerate()
+        .filter_map(|(i, root)| root.as_ref().map(|r| (i, r)))
+        .filter(|(_, root)| target.starts_with(root))
+        .max_by_key(|(_, root)| root.components().count())
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    fn project(base: &Path, name: &str, manifest: &str) -> ProjectInfo {
+        let path = base.join(name);
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("Cargo.toml"), manifest).unwrap();
+        ProjectInfo {
+            name: name.to_string(),
+            path,
+            has_uncommitted_changes: false,
+            branch: None,
+            ahead_behind: None,
+            diverged_default: None,
+            rollup: None,
+            no_std: None,
+            version: None,
+            kind: None,
+            last_commit: None,
+            remote_url: None,
+            fingerprint: 0,
+            pinned: false,
+            last_opened: None,
+            tags: Vec::new(),
+            untrusted: false,
+        }
+    }
+
+    #[test]
+    fn detects_edges_into_projects_and_workspace_members() {
+        let base = TempDir::new("graph");
+        let core = project(&base, "core", "[workspace]\nmembers = [\"crates/*\"]\n");
+        fs::create_dir_all(core.path.join("crates/proto")).unwrap();
+        fs::write(
+            core.path.join("crates/proto/Cargo.toml"),
+            "[package]\nname = \"proto\"\n",
+        )
+        .unwrap();
+        let app = project(
+            &base,
+            "app",
+            "[package]\nname = \"app\"\n[dependencies]\nproto = { path = \"../core/crates/proto\" }\n",
+        );
+        let lonely = project(&base, "lonely", "[package]\nname = \"lonely\"\n");
+
+        let graph = DependencyGraph::build(vec![app, core.clone(), lonely.clone()]);
+        assert_eq!(
+            graph.edges,
+            vec![Edge {
+                from: 0,
+                to: 1,
+                dependency: "proto".into()
+            }]
+        );
+        let dependents: Vec<&str> = graph
+            .dependents(&core.path)
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(dependents, vec!["app"]);
+        assert!(graph.dependents(&lonely.path).is_empty());
+        assert!(graph.render().contains("core <- app"));
+    }
+}
@@ -0,0 +1,300 @@
+//! Workspace dependency hoisting.
+//!
+//! Finds registry dependencies declared by several members of a workspace with
+//! compatible version requirements, and moves them to
+//! `[workspace.dependencies]`: the root declares the requirement, the members
+//! switch to `workspace = true` (keeping their `features` and `optional`).
+//! Edits go through `Manifest::edit`, so formatting and comments survive, after
+//! a safety snapshot (see `git_ops::snapshot`); a manifest that cannot be
+//! edited restores the ones already edited (see `crate::transaction`).
+//!
+//! The hoisted requirement is the member requirement with the highest minimum
+//! version, provided every other member's requirement accepts that version
+//! (`1.0` and `1.2` hoist as `1.2`; `0.3` and `0.4` are left alone).
+//! Dependencies renamed (`package`), from another registry or without their
+//! default features in a member are left alone too: inheriting would change
+//! what the member builds.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use log::info;
+use semver::{BuildMetadata, Op, Version, VersionReq};
+
+use crate::git_ops::{self, GitError};
+use crate::project::manifest::{DependencyKind, Manifest, ManifestError};
+use crate::project::workspace::{self, WorkspaceError};
+use crate::transaction::Transaction;
+
+/// Member keys that make a dependency unsafe to inherit.
+const BLOCKING_KEYS: [&str; 4] = [
+    "package",
+    "registry",
+    "default-features",
+    "default_features",
+];
+
+/// A member's declaration of a dependency.
+#[derive(Debug, Clone)]
+pub struct Use {
+    pub member: String,
+    pub dir: PathBuf,
+    pub kind: DependencyKind,
+    pub req: String,
+}
+
+/// A dependency that can move to `[workspace.dependencies]`.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub name: String,
+    /// Requirement declared in the workspace.
+    pub req: String,
+    pub uses: Vec<Use>,
+}
+
+/// Outcome of the analysis.
+#[derive(Debug, Clone, Default)]
+pub struct Analysis {
+    pub candidates: Vec<Candidate>,
+    /// Dependencies shared by several members but not hoistable, with the reason.
+    pub skipped: Vec<String>,
+}
+
+/// Errors raised while analysing or hoisting.
+#[derive(Debug)]
+pub enum HoistError {
+    NotAWorkspace,
+    Workspace(WorkspaceError),
+    Manifest(ManifestError),
+    Git(GitError),
+}
+
+impl fmt::Display for HoistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAWorkspace => write!(f, "The project is not a workspace"),
+            Self::Workspace(e) => write!(f, "{e}"),
+            Self::Manifest(e) => write!(f, "{e}"),
+            Self::Git(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for HoistError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Workspace(e) => Some(e),
+            Self::Manifest(e) => Some(e),
+            Self::Git(e) => Some(e),
+            Self::NotAWorkspace => None,
+        }
+    }
+}
+
+impl From<ManifestError> for HoistError {
+    fn from(e: ManifestError) -> Self {
+        Self::Manifest(e)
+    }
+}
+
+/// Find the hoistable dependencies of the workspace at `root`.
+pub fn analyze(root: &Path) -> Result<Analysis, HoistError> {
+    let manifest = Manifest::read(root)?;
+    if !manifest.is_workspace() {
+        return Err(HoistError::NotAWorkspace);
+    }
+    let mut members = Vec::new();
+    for member in workspace::members(root).map_err(HoistError::Workspace)? {
+        let member_manifest = Manifest::read(&member.dir)?;
+        members.push((member.name, member.dir, member_manifest));
+    }
+    Ok(plan(&manifest, &members))
+}
+
+/// Analysis of the workspace manifest `root` and its `members` (name, directory, manifest).
+fn plan(root: &Manifest, members: &[(String, PathBuf, Manifest)]) -> Analysis {
+    let hoisted = root.workspace_dependency_names();
+    let mut by_name: BTreeMap<String, Vec<(Use, bool)>> = BTreeMap::new();
+    for (member, dir, manifest) in members {
+        for dep in manifest.declared_dependencies() {
+            // Target-specific declarations stay in the members.
+            if dep.inherited || dep.source.is_some() || dep.target.is_some() {
+                continue;
+            }
+            let blocked = manifest
+                .dependency_keys(dep.kind, &dep.name)
+                .iter()
+                .any(|key| BLOCKING_KEYS.contains(&key.as_str()));
+            let use_ = Use {
+                member: member.clone(),
+                dir: dir.clone(),
+                kind: dep.kind,
+                req: dep.req.unwrap_or_default(),
+            };
+            by_name.entry(dep.name).or_default().push((use_, blocked));
+        }
+    }
+
+    let mut analysis = Analysis::default();
+    for (name, uses) in by_name {
+        let mut declaring: Vec<&str> = uses.iter().map(|(u, _)| u.member.as_str()).collect();
+        declaring.dedup();
+        if declaring.len() < 2 {
+            continue;
+        }
+        if hoisted.contains(&name) {
+            analysis.skipped.push(format!(
+                "{name}: already in [workspace.dependencies], members declare their own version"
+            ));
+            continue;
+        }
+        if uses.iter().any(|(_, blocked)| *blocked) {
+            analysis.skipped.push(format!(
+                "{name}: renamed, from another registry or without default features in a member"
+            ));
+            continue;
+        }
+        let reqs: Vec<&str> = uses.iter().map(|(u, _)| u.req.as_str()).collect();
+        match common_requirement(&reqs) {
+            Some(req) => analysis.candidates.push(Candidate {
+                name,
+                req,
+                uses: uses.into_iter().map(|(u, _)| u).collect(),
+            }),
+            None => {
+                let declared: Vec<String> = uses
+                    .iter()
+                    .map(|(u, _)| format!("{} {}", u.member, u.req))
+                    .collect();
+                analysis.skipped.push(format!(
+                    "{name}: incompatible requirements ({})",
+                    declared.join(", ")
+                ));
+            }
+        }
+    }
+    analysis
+}
+
+/// The requirement of `reqs` whose minimum version every other one accepts, the highest such.
+fn common_requirement(reqs: &[&str]) -> Option<String> {
+    let parsed: Vec<(&str, VersionReq, Version)> = reqs
+        .iter()
+        .map(|req| {
+            let parsed = VersionReq::parse(req).ok()?;
+            let min = minimum(&parsed)?;
+            Some((*req, parsed, min))
+        })
+        .collect::<Option<_>>()?;
+    let (req, _, min) = parsed.iter().max_by(|a, b| a.2.cmp(&b.2))?;
+    parsed
+        .iter()
+        .all(|(_, other, _)| other.matches(min))
+        .then(|| req.trim().to_string())
+}
+
+/// Lowest version accepted by `req`, for caret, tilde, exact and `>=` requirements.
+fn minimum(req: &VersionReq) -> Option<Version> {
+    let first = req.comparators.first()?;
+    if !matches!(first.op, Op::Caret | Op::Tilde | Op::Exact | Op::GreaterEq) {
+        return None;
+    }
+    Some(Version {
+        major: first.major,
+        minor: first.minor.unwrap_or(0),
+        patch: first.patch.unwrap_or(0),
+        pre: first.pre.clone(),
+        build: BuildMetadata::EMPTY,
+    })
+}
+
+/// Hoist `candidates` into the workspace at `root`; returns the member entries rewritten.
+pub fn hoist(root: &Path, candidates: &[Candidate]) -> Result<usize, HoistError> {
+    git_ops::snapshot(root, "dependency hoisting").map_err(HoistError::Git)?;
+    let mut tx = Transaction::new(format!("Hoisting the dependencies of {}", root.display()));
+    tx.preserve(&root.join("Cargo.toml"))
+        .map_err(ManifestError::Io)?;
+    Manifest::edit(root, |m| {
+        for candidate in candidates {
+            m.add_workspace_dependency(&candidate.name, &candidate.req);
+        }
+    })?;
+
+    let mut by_dir: BTreeMap<&Path, Vec<(&str, DependencyKind)>> = BTreeMap::new();
+    for candidate in candidates {
+        for use_ in &candidate.uses {
+            by_dir
+                .entry(&use_.dir)
+                .or_default()
+                .push((&candidate.name, use_.kind));
+        }
+    }
+    let mut rewritten = 0;
+    for (dir, deps) in by_dir {
+        tx.preserve(&dir.join("Cargo.toml"))
+            .map_err(ManifestError::Io)?;
+        rewritten += Manifest::edit(dir, |m| {
+            deps.iter()
+                .filter(|(name, kind)| m.inherit_dependency(*kind, name))
+                .count()
+        })?;
+    }
+    tx.commit();
+    info!(
+        "Hoisted {} dependencies of {} ({rewritten} member entries)",
+        candidates.len(),
+        root.display()
+    );
+    Ok(rewritten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(name: &str, deps: &str) -> (String, PathBuf, Manifest) {
+        let manifest = Manifest::parse(&format!("[package]\nname = \"{name}\"\n\n{deps}")).unwrap();
+        (name.to_string(), PathBuf::from(name), manifest)
+    }
+
+    #[test]
+    fn finds_compatible_shared_dependencies() {
+        assert_eq!(
+            common_requirement(&["1.0", "^1.2.3"]).as_deref(),
+            Some("^1.2.3")
+        );
+        assert_eq!(common_requirement(&["0.3", "0.4"]), None);
+        assert_eq!(common_requirement(&["~1.2", "1.3"]), None);
+        assert_eq!(common_requirement(&["*", "1"]), None);
+
+        let root = Manifest::parse(
+            "[workspace]\nmembers = [\"a\", \"b\"]\n\n[workspace.dependencies]\nanyhow = \"1\"\n",
+        )
+        .unwrap();
+        let members = [
+            member(
+                "a",
+                "[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] }\nrand = \"0.8\"\nanyhow = \"1\"\nlocal = { path = \"../local\" }\nold = { version = \"1\", default-features = false }\n",
+            ),
+            member(
+                "b",
+                "[dependencies]\nrand = \"0.9\"\nanyhow = \"1\"\nlocal = { path = \"../local\" }\nold = \"1\"\n\n[dev-dependencies]\nserde = \"1.0.200\"\n",
+            ),
+        ];
+        let analysis = plan(&root, &members);
+
+        assert_eq!(analysis.candidates.len(), 1);
+        let serde = &analysis.candidates[0];
+        assert_eq!(
+            (serde.name.as_str(), serde.req.as_str()),
+            ("serde", "1.0.200")
+        );
+        assert_eq!(serde.uses.len(), 2);
+        assert_eq!(serde.uses[1].kind, DependencyKind::Dev);
+        assert_eq!(analysis.skipped.len(), 3);
+        assert!(analysis.skipped[0].starts_with("anyhow: already"));
+        assert!(analysis.skipped[1].starts_with("old: renamed"));
+        assert!(analysis.skipped[2].starts_with("rand: incompatible"));
+    }
+}
@@ -0,0 +1,407 @@
+//! Project hygiene checklist.
+//!
+//! What a project shared with others is expected to have: a README, a
+//! license, CI, tests, and the `Cargo.toml` metadata crates.io and readers
+//! look for (`description`, `keywords`, `repository`). Each missing item has
+//! a scaffolding action:
+//! - README: `README.md` with the package name and description;
+//! - LICENSE: the MIT license (`project::license`), for the git `user.name`
+//!   and the current year (`package.license` is set to `MIT` when not declared);
+//! - CI: a GitHub Actions workflow running fmt, clippy and the tests;
+//! - tests: an integration test or a unit test module (`project::scaffold`),
+//!   `tests/smoke.rs` by default;
+//! - manifest keys: set from user input (the repository defaults to the
+//!   `origin` remote).
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use toml_edit::Array;
+
+use crate::git_ops;
+use crate::github::RepoSlug;
+use crate::project::license::{self, License};
+use crate::project::manifest::{Manifest, ManifestError};
+use crate::project::package_metadata;
+use crate::project::scaffold::{self, ScaffoldError};
+
+/// Deepest directory searched for tests, below the project root.
+const MAX_TEST_DEPTH: usize = 6;
+
+/// CI configuration files of common services, relative to the project root.
+const CI_FILES: [&str; 6] = [
+    ".gitlab-ci.yml",
+    ".circleci/config.yml",
+    ".travis.yml",
+    "azure-pipelines.yml",
+    ".woodpecker.yml",
+    "bitbucket-pipelines.yml",
+];
+
+const CI_WORKFLOW: &str = "name: CI
+
+on:
+  push:
+  pull_request:
+
+jobs:
+  check:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - uses: dtolnay/rust-toolchain@stable
+        with:
+          components: clippy, rustfmt
+      - run: cargo fmt --all --check
+      - run: cargo clippy --workspace --all-targets -- -D warnings
+      - run: cargo test --workspace
+";
+
+/// An item of the checklist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    Readme,
+    License,
+    Ci,
+    Tests,
+    Description,
+    Keywords,
+    Repository,
+}
+
+impl Item {
+    pub const ALL: [Self; 7] = [
+        Self::Readme,
+        Self::License,
+        Self::Ci,
+        Self::Tests,
+        Self::Description,
+        Self::Keywords,
+        Self::Repository,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Readme => "README",
+            Self::License => "LICENSE",
+            Self::Ci => "CI configured",
+            Self::Tests => "Tests",
+            Self::Description => "package.description",
+            Self::Keywords => "package.keywords",
+            Self::Repository => "package.repository",
+        }
+    }
+
+    /// What the scaffolding action does.
+    pub fn action(self) -> &'static str {
+        match self {
+            Self::Readme => "create README.md",
+            Self::License => "add the MIT license",
+            Self::Ci => "add a GitHub Actions workflow",
+            Self::Tests => "scaffold tests",
+            Self::Description => "set the description",
+            Self::Keywords => "set the keywords",
+            Self::Repository => "set the repository URL",
+        }
+    }
+
+    /// The action needs a value from the user (see `suggestion`).
+    pub fn needs_input(self) -> bool {
+        matches!(self, Self::Description | Self::Keywords | Self::Repository)
+    }
+}
+
+/// An item and whether the project satisfies it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Status {
+    pub item: Item,
+    pub ok: bool,
+}
+
+#[derive(Debug)]
+pub enum HygieneError {
+    Io(io::Error),
+    Manifest(ManifestError),
+    /// Virtual workspace: the manifest keys and tests belong to the members.
+    NoPackage,
+    InvalidInput(String),
+    Scaffold(ScaffoldError),
+}
+
+impl fmt::Display for HygieneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Manifest(e) => write!(f, "{e}"),
+            Self::NoPackage => write!(
+                f,
+                "Cargo.toml has no [package] table (virtual workspace); fix the members instead"
+            ),
+            Self::InvalidInput(e) => write!(f, "{e}"),
+            Self::Scaffold(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for HygieneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Manifest(e) => Some(e),
+            Self::Scaffold(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for HygieneError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ScaffoldError> for HygieneError {
+    fn from(e: ScaffoldError) -> Self {
+        Self::Scaffold(e)
+    }
+}
+
+impl From<ManifestError> for HygieneError {
+    fn from(e: ManifestError) -> Self {
+        Self::Manifest(e)
+    }
+}
+
+/// The checklist of the project at `path`, in `Item::ALL` order.
+pub fn check(path: &Path) -> Vec<Status> {
+    let manifest = Manifest::read(path).ok();
+    let declares = |key: &str| {
+        manifest
+            .as_ref()
+            .is_some_and(|m| m.declares_package_key(key))
+    };
+    Item::ALL
+        .into_iter()
+        .map(|item| {
+            let ok = match item {
+                Item::Readme => has_root_file(path, &["README"]),
+                Item::License => has_root_file(path, &["LICENSE", "LICENCE", "COPYING"]),
+                Item::Ci => has_ci(path),
+                Item::Tests => has_tests(path, 0),
+                Item::Description => declares("description"),
+                Item::Keywords => declares("keywords"),
+                Item::Repository => declares("repository"),
+            };
+            Status { item, ok }
+        })
+        .collect()
+}
+
+/// Default input for the actions needing one: the repository URL of `origin`, else empty.
+pub fn suggestion(path: &Path, item: Item) -> String {
+    if item != Item::Repository {
+        return String::new();
+    }
+    let Ok(url) = git_ops::remote_url(path, "origin") else {
+        return String::new();
+    };
+    if let Some(slug) = RepoSlug::from_remote_url(&url) {
+        return format!("https://github.com/{}/{}", slug.owner, slug.repo);
+    }
+    if url.starts_with("https://") {
+        url.trim_end_matches(".git").to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Run the scaffolding action of `item` (`input` for those needing one, the
+/// copyright holder for the license); returns what was done.
+pub fn fix(path: &Path, item: Item, input: &str) -> Result<String, HygieneError> {
+    match item {
+        Item::Readme => {
+            let manifest = Manifest::read(path).ok();
+            let name = manifest
+                .as_ref()
+                .and_then(Manifest::package_name)
+                .map(ToString::to_string)
+                .or_else(|| Some(path.file_name()?.to_string_lossy().into_owned()))
+                .unwrap_or_default();
+            let description = manifest
+                .as_ref()
+                .and_then(|m| m.package_str("description"))
+                .unwrap_or("TODO: describe the project.");
+            create(path, "README.md", &format!("# {name}\n\n{description}\n"))?;
+            Ok("Created README.md".into())
+        }
+        Item::License => {
+            let author = Some(input.trim()).filter(|a| !a.is_empty());
+            license::write(path, License::Mit, &license::holder(author))?;
+            let set_license = Manifest::edit(path, |m| {
+                !m.declares_package_key("license")
+                    && !m.declares_package_key("license-file")
+                    && m.set_package_value("license", License::Mit.spdx())
+            })?;
+            if set_license {
+                return Ok("Created LICENSE (MIT) and set package.license".into());
+            }
+            Ok("Created LICENSE (MIT)".into())
+        }
+        Item::Ci => {
+            create(path, ".github/workflows/ci.yml", CI_WORKFLOW)?;
+            Ok("Created .github/workflows/ci.yml".into())
+        }
+        Item::Tests => {
+            let name = match input.trim() {
+                "" => "smoke",
+                name => name,
+            };
+            let created = scaffold::integration_test(path, name)?;
+            Ok(format!("Created {created}"))
+        }
+        Item::Description => {
+            let description = input.trim();
+            if description.is_empty() {
+                return Err(HygieneError::InvalidInput(
+                    "The description cannot be empty".into(),
+                ));
+            }
+            set_package_value(path, "description", description)?;
+            Ok("Set package.description".into())
+        }
+        Item::Keywords => {
+            let keywords =
+                package_metadata::parse_keywords(input).map_err(HygieneError::InvalidInput)?;
+            if keywords.is_empty() {
+                return Err(HygieneError::InvalidInput("No keyword given".into()));
+            }
+            set_package_value(path, "keywords", keywords.iter().collect::<Array>())?;
+            Ok("Set package.keywords".into())
+        }
+        Item::Repository => {
+            let url = package_metadata::parse_url(input, "repository")
+                .map_err(HygieneError::InvalidInput)?;
+            set_package_value(path, "repository", url)?;
+            Ok("Set package.repository".into())
+        }
+    }
+}
+
+fn set_package_value(
+    path: &Path,
+    key: &str,
+    new: impl Into<toml_edit::Value>,
+) -> Result<(), HygieneError> {
+    if Manifest::edit(path, |m| m.set_package_value(key, new))? {
+        Ok(())
+    } else {
+        Err(HygieneError::NoPackage)
+    }
+}
+
+/// Write `relative` under `path`, creating parent directories; never overwrites.
+fn create(path: &Path, relative: &str, content: &str) -> io::Result<()> {
+    let file = path.join(relative);
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(file)
+        .and_then(|mut f| io::Write::write_all(&mut f, content.as_bytes()))
+}
+
+/// `true` when a file at the root of `path` starts with one of `prefixes` (`LICENSE-MIT`, `README.md`, ...).
+fn has_root_file(path: &Path, prefixes: &[&str]) -> bool {
+    fs::read_dir(path).is_ok_and(|entries| {
+        entries.flatten().any(|e| {
+            let name = e.file_name().to_string_lossy().to_uppercase();
+            e.path().is_file() && prefixes.iter().any(|p| name.starts_with(p))
+        })
+    })
+}
+
+fn has_ci(path: &Path) -> bool {
+    let workflows = fs::read_dir(path.join(".github/workflows")).is_ok_and(|entries| {
+        entries.flatten().any(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            name.ends_with(".yml") || name.ends_with(".yaml")
+        })
+    });
+    workflows || CI_FILES.iter().any(|f| path.join(f).is_file())
+}
+
+/// `true` when a `.rs` file below `dir` (outside `target` and hidden directories) declares a test.
+fn has_tests(dir: &Path, depth: usize) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    entries.flatten().any(|e| {
+        let name = e.file_name().to_string_lossy().into_owned();
+        let path = e.path();
+        if path.is_dir() {
+            depth < MAX_TEST_DEPTH
+                && name != "target"
+                && !name.starts_with('.')
+                && has_tests(&path, depth + 1)
+        } else {
+            name.ends_with(".rs")
+                && fs::read_to_string(&path).is_ok_and(|code| {
+                    code.contains("#[test]")
+                        || code.contains("#[cfg(test)]")
+                        || code.contains("::test]")
+                })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    fn missing(path: &Path) -> Vec<Item> {
+        check(path)
+            .into_iter()
+            .filter(|s| !s.ok)
+            .map(|s| s.item)
+            .collect()
+    }
+
+    #[test]
+    fn scaffolds_every_missing_item() {
+        let dir = TempDir::new("hygiene");
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\ndescription = \"\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+        assert_eq!(missing(&dir), Item::ALL);
+
+        for item in [Item::Readme, Item::License, Item::Ci, Item::Tests] {
+            fix(&dir, item, "").unwrap();
+        }
+        assert!(fix(&dir, Item::Readme, "").is_err());
+        assert!(fix(&dir, Item::Keywords, "tui, way-too-long-for-a-keyword").is_err());
+        assert!(fix(&dir, Item::Repository, "git@github.com:o/r.git").is_err());
+        fix(&dir, Item::Description, "A demo").unwrap();
+        fix(&dir, Item::Keywords, "TUI, cli cli").unwrap();
+        fix(&dir, Item::Repository, "https://github.com/o/demo").unwrap();
+        assert!(missing(&dir).is_empty());
+
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("license = \"MIT\""));
+        assert!(manifest.contains("keywords = [\"tui\", \"cli\"]"));
+        assert!(manifest.contains("description = \"A demo\""));
+        let readme = fs::read_to_string(dir.join("README.md")).unwrap();
+        assert!(readme.starts_with("# demo\n"));
+        let license = fs::read_to_string(dir.join("LICENSE")).unwrap();
+        assert!(license.contains(&format!("Copyright (c) {}", license::current_year())));
+        assert!(license::current_year() >= 2025);
+    }
+}
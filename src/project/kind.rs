@@ -0,0 +1,129 @@
+//! Project kind detection.
+//!
+//! Classifies a project directory from its manifest and surrounding files so
+//! other features (e.g. editor launch profiles) can react to "what kind of
+//! project is this" without re-implementing the heuristics.
+//!
+//! Precedence (first match wins):
+//! 1. `Workspace`: manifest declares `[workspace]`.
+//! 2. `Embedded`: bare-metal build target in `.cargo/config.toml` or a known embedded dependency.
+//! 3. `Wasm`: `wasm32` build target, `Trunk.toml`, or a known wasm dependency.
+//! 4. `Library`: `src/lib.rs` or `[lib]` without `src/main.rs`.
+//! 5. `Binary`: everything else.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::manifest::{Manifest, configured_build_target};
+
+/// Dependencies that mark a crate as targeting embedded hardware.
+const EMBEDDED_DEPENDENCIES: [&str; 6] = [
+    "cortex-m",
+    "cortex-m-rt",
+    "embedded-hal",
+    "embassy-executor",
+    "esp-hal",
+    "riscv-rt",
+];
+
+/// Target triple prefixes for bare-metal targets.
+const EMBEDDED_TARGET_PREFIXES: [&str; 4] = ["thumb", "riscv32", "xtensa", "avr"];
+
+/// Dependencies that mark a crate as targeting WebAssembly.
+const WASM_DEPENDENCIES: [&str; 5] = ["wasm-bindgen", "web-sys", "yew", "leptos", "dioxus-web"];
+
+/// Broad classification of a Rust project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectKind {
+    Binary,
+    Library,
+    Workspace,
+    Wasm,
+    Embedded,
+}
+
+/// Classify the project located at `dir` using its already-parsed manifest.
+pub fn detect(dir: &Path, manifest: &Manifest) -> ProjectKind {
+    if manifest.is_workspace() {
+        return ProjectKind::Workspace;
+    }
+
+    let target = configured_build_target(dir);
+    let target_starts_with = |prefixes: &[&str]| {
+        target
+            .as_deref()
+            .is_some_and(|t| prefixes.iter().any(|p| t.starts_with(p)))
+    };
+
+    if target_starts_with(&EMBEDDED_TARGET_PREFIXES)
+        || EMBEDDED_DEPENDENCIES
+            .iter()
+            .any(|d| manifest.has_dependency(d))
+    {
+        return ProjectKind::Embedded;
+    }
+
+    if target_starts_with(&["wasm32"])
+        || dir.join("Trunk.toml").is_file()
+        || WASM_DEPENDENCIES.iter().any(|d| manifest.has_dependency(d))
+    {
+        return ProjectKind::Wasm;
+    }
+
+    let has_main = dir.join("src").join("main.rs").is_file();
+    let has_lib = dir.join("src").join("lib.rs").is_file() || manifest.has_lib_section();
+    if has_lib && !has_main {
+        return ProjectKind::Library;
+    }
+
+    ProjectKind::Binary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+    use std::fs;
+
+    /// Project directory with `src/`.
+    fn project_dir() -> TempDir {
+        let d = TempDir::new("kind");
+        fs::create_dir_all(d.join("src")).unwrap();
+        d
+    }
+
+    #[test]
+    fn detects_library_and_binary() {
+        let d = project_dir();
+        let m = Manifest::parse("[package]\nname = 'x'\n").unwrap();
+
+        fs::write(d.join("src/lib.rs"), "").unwrap();
+        assert_eq!(detect(&d, &m), ProjectKind::Library);
+
+        fs::write(d.join("src/main.rs"), "fn main() {}").unwrap();
+        assert_eq!(detect(&d, &m), ProjectKind::Binary);
+    }
+
+    #[test]
+    fn detects_embedded_from_cargo_config_target() {
+        let d = project_dir();
+        fs::create_dir_all(d.join(".cargo")).unwrap();
+        fs::write(
+            d.join(".cargo/config.toml"),
+            "[build]\ntarget = \"thumbv7em-none-eabihf\"\n",
+        )
+        .unwrap();
+        let m = Manifest::parse("[package]\nname = 'fw'\n").unwrap();
+        assert_eq!(detect(&d, &m), ProjectKind::Embedded);
+    }
+
+    #[test]
+    fn detects_wasm_from_dependency() {
+        let d = project_dir();
+        let m = Manifest::parse("[package]\nname = 'w'\n[dependencies]\nwasm-bindgen = '0.2'\n")
+            .unwrap();
+        assert_eq!(detect(&d, &m), ProjectKind::Wasm);
+    }
+}
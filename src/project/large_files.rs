@@ -0,0 +1,141 @@
+//! Large staged files and Git LFS.
+//!
+//! The commit screen reports staged files above `commit.large_file_kib`
+//! before committing. Binary ones are better kept out of the history: when
+//! `git lfs` is installed, they can be moved to LFS tracking, by file
+//! extension (`*.png`) or by path for files without one. Tracking goes
+//! through the `git` CLI, as LFS works with clean/smudge filters that only
+//! `git add` runs.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::cargo_ops::{self, CargoError};
+use crate::format;
+use crate::git_ops::{self, GitError};
+
+/// A staged file above the size threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargeFile {
+    /// Path relative to the repository root.
+    pub path: String,
+    /// Size of the staged content, in bytes.
+    pub size: u64,
+    pub binary: bool,
+}
+
+impl LargeFile {
+    /// `path  1.5 MiB  binary` style line for listings.
+    pub fn label(&self) -> String {
+        let kind = if self.binary { "binary" } else { "text" };
+        format!("{}  {}  {kind}", self.path, format::size(self.size))
+    }
+
+    /// LFS pattern covering the file: `*.<extension>`, else its path.
+    pub fn lfs_pattern(&self) -> String {
+        let name = self.path.rsplit('/').next().unwrap_or(&self.path);
+        match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => format!("*.{extension}"),
+            _ => self.path.clone(),
+        }
+    }
+}
+
+/// Staged files (added or modified) of the repository at `path` larger than `threshold_kib`.
+///
+/// Files already in LFS are staged as small pointers, so they are never reported.
+pub fn staged(path: &Path, threshold_kib: u64) -> Result<Vec<LargeFile>, GitError> {
+    if threshold_kib == 0 {
+        return Ok(Vec::new());
+    }
+    let repo = git_ops::open(path)?;
+    let head = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree()?),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e.into()),
+    };
+    let diff = repo.diff_tree_to_index(head.as_ref(), None, None)?;
+    let mut found = Vec::new();
+    for delta in diff.deltas() {
+        if delta.status() == git2::Delta::Deleted {
+            continue;
+        }
+        let file = delta.new_file();
+        let Some(file_path) = file.path() else {
+            continue;
+        };
+        let blob = repo.find_blob(file.id())?;
+        let size = blob.size() as u64;
+        if size > threshold_kib * 1024 {
+            found.push(LargeFile {
+                path: file_path.to_string_lossy().into_owned(),
+                size,
+                binary: blob.is_binary(),
+            });
+        }
+    }
+    Ok(found)
+}
+
+/// `true` when `git lfs` is installed.
+pub fn lfs_available(path: &Path) -> bool {
+    cargo_ops::run_tool("git", path, &["lfs", "version"], &[]).is_ok()
+}
+
+/// Track `files` with LFS and stage them again through the LFS filter; returns the patterns added.
+///
+/// Sets up the LFS filters for the repository (`git lfs install --local`) and
+/// stages `.gitattributes` along with the files, whole.
+pub fn track(path: &Path, files: &[LargeFile]) -> Result<Vec<String>, CargoError> {
+    let patterns: BTreeSet<String> = files.iter().map(LargeFile::lfs_pattern).collect();
+    let git = |args: &[&str]| cargo_ops::run_tool("git", path, args, &[]);
+    git(&["lfs", "install", "--local"])?;
+    for pattern in &patterns {
+        git(&["lfs", "track", "--", pattern])?;
+    }
+    let mut add = vec!["add", "--", ".gitattributes"];
+    add.extend(files.iter().map(|f| f.path.as_str()));
+    git(&add)?;
+    Ok(patterns.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+    use std::fs;
+
+    #[test]
+    fn reports_staged_files_above_threshold() {
+        let dir = TempDir::new("large_files");
+        let repo = git2::Repository::init(&dir).unwrap();
+        fs::create_dir_all(dir.join("assets")).unwrap();
+        fs::write(dir.join("assets/logo.png"), vec![0u8; 3000]).unwrap();
+        fs::write(dir.join("notes.txt"), "small\n").unwrap();
+        fs::write(dir.join("big.txt"), "x".repeat(3000)).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("assets/logo.png")).unwrap();
+        index.add_path(Path::new("notes.txt")).unwrap();
+        index.write().unwrap();
+
+        let found = staged(&dir, 2).unwrap();
+        assert_eq!(
+            found,
+            [LargeFile {
+                path: "assets/logo.png".into(),
+                size: 3000,
+                binary: true,
+            }]
+        );
+        assert_eq!(found[0].label(), "assets/logo.png  2.9 KiB  binary");
+        assert_eq!(found[0].lfs_pattern(), "*.png");
+        assert!(staged(&dir, 0).unwrap().is_empty());
+
+        let no_extension = LargeFile {
+            path: "data/.blob".into(),
+            size: 1,
+            binary: true,
+        };
+        assert_eq!(no_extension.lfs_pattern(), "data/.blob");
+    }
+}
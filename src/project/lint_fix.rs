@@ -0,0 +1,176 @@
+//! Batch `cargo clippy --fix` and `cargo fmt`.
+//!
+//! For each project: from a clean working tree, create a branch
+//! (`rustm/lint-fix`, suffixed `-2`, `-3`, ... when taken), run
+//! `cargo clippy --fix` then `cargo fmt --all`, and commit whatever they
+//! changed with `COMMIT_MESSAGE`. The project is then switched back to its
+//! branch, so that the fixes wait on their own branch for review. A branch
+//! left without changes is deleted; when clippy fails (the project does not
+//! build), its edits are discarded and the branch deleted too.
+//!
+//! A safety snapshot is taken first (see `git_ops::snapshot`).
+//!
+//! clippy runs build scripts and procedural macros: callers only pass trusted
+//! projects (see `ui::trust`).
+
+use std::fmt;
+use std::path::Path;
+
+use log::{info, warn};
+
+use crate::cargo_ops::{self, CargoError};
+use crate::git_ops::{self, GitError};
+
+/// Branch the fixes are committed on (a suffix is added when taken).
+pub const BRANCH: &str = "rustm/lint-fix";
+
+/// Message of the commits.
+pub const COMMIT_MESSAGE: &str = "Apply cargo clippy --fix and cargo fmt";
+
+/// What the batch did to a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The fixes were committed on `branch`.
+    Changed {
+        branch: String,
+        files: usize,
+        commit: String,
+    },
+    Unchanged,
+    /// Not attempted, with the reason.
+    Skipped(String),
+}
+
+/// Errors raised while fixing a project.
+#[derive(Debug)]
+pub enum LintFixError {
+    Git(GitError),
+    Cargo(CargoError),
+    /// `cargo clippy --fix` failed (usually: the project does not build).
+    ClippyFailed,
+}
+
+impl fmt::Display for LintFixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Git(e) => write!(f, "{e}"),
+            Self::Cargo(e) => write!(f, "{e}"),
+            Self::ClippyFailed => write!(f, "cargo clippy --fix failed; changes discarded"),
+        }
+    }
+}
+
+impl std::error::Error for LintFixError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Git(e) => Some(e),
+            Self::Cargo(e) => Some(e),
+            Self::ClippyFailed => None,
+        }
+    }
+}
+
+impl From<GitError> for LintFixError {
+    fn from(e: GitError) -> Self {
+        Self::Git(e)
+    }
+}
+
+impl From<CargoError> for LintFixError {
+    fn from(e: CargoError) -> Self {
+        Self::Cargo(e)
+    }
+}
+
+/// Fix the project at `path`, streaming the cargo output to `log`.
+pub fn run(path: &Path, log: &dyn Fn(String)) -> Result<Outcome, LintFixError> {
+    match git_ops::status_lines(path) {
+        Ok(lines) if !lines.is_empty() => {
+            return Ok(Outcome::Skipped("uncommitted changes".into()));
+        }
+        Ok(_) => {}
+        Err(GitError::NotARepository(_)) => {
+            return Ok(Outcome::Skipped("not a git repository".into()));
+        }
+        Err(e) => return Err(e.into()),
+    }
+    let Some(original) = git_ops::current_branch(path)? else {
+        return Ok(Outcome::Skipped("HEAD is detached".into()));
+    };
+    git_ops::snapshot(path, "clippy fix and fmt")?;
+    let taken = git_ops::local_branches(path)?;
+    let branch = free_branch_name(&taken);
+    git_ops::create_branch(path, &branch)?;
+
+    let result = fix_and_commit(path, &branch, log);
+    // Back to where the project was, whatever happened.
+    if let Err(e) = git_ops::checkout_branch(path, &original) {
+        warn!("Cannot switch {} back to {original}: {e}", path.display());
+        return result;
+    }
+    if !matches!(result, Ok(Outcome::Changed { .. }))
+        && let Err(e) = git_ops::delete_branch(path, &branch)
+    {
+        warn!("Cannot delete {branch} in {}: {e}", path.display());
+    }
+    result
+}
+
+fn fix_and_commit(
+    path: &Path,
+    branch: &str,
+    log: &dyn Fn(String),
+) -> Result<Outcome, LintFixError> {
+    let fixed = cargo_ops::run_tool_streaming(
+        "cargo",
+        path,
+        &["clippy", "--fix", "--allow-dirty", "--all-targets"],
+        log,
+    )?;
+    if !fixed {
+        git_ops::reset_hard(path)?;
+        return Err(LintFixError::ClippyFailed);
+    }
+    if !cargo_ops::run_tool_streaming("cargo", path, &["fmt", "--all"], log)? {
+        // clippy's fixes stand on their own.
+        warn!("cargo fmt failed in {}", path.display());
+    }
+
+    let files = git_ops::status_lines(path)?.len();
+    if files == 0 {
+        return Ok(Outcome::Unchanged);
+    }
+    let commit = git_ops::commit_all(path, COMMIT_MESSAGE)?;
+    info!(
+        "Committed lint fixes of {} on {branch} ({files} files)",
+        path.display()
+    );
+    Ok(Outcome::Changed {
+        branch: branch.to_string(),
+        files,
+        commit,
+    })
+}
+
+/// `BRANCH`, or `BRANCH-<n>` with the lowest `n` not in `taken`.
+fn free_branch_name(taken: &[String]) -> String {
+    (1..)
+        .map(|n| match n {
+            1 => BRANCH.to_string(),
+            n => format!("{BRANCH}-{n}"),
+        })
+        .find(|name| !taken.contains(name))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_a_free_branch_name() {
+        assert_eq!(free_branch_name(&[]), "rustm/lint-fix");
+        let taken = ["main".to_string(), "rustm/lint-fix".to_string()];
+        assert_eq!(free_branch_name(&taken), "rustm/lint-fix-2");
+    }
+}
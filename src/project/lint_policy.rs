@@ -0,0 +1,155 @@
+//! Central lint policy.
+//!
+//! `lint_policy` in the configuration holds canonical lint levels per tool
+//! (`rust`, `clippy`, `rustdoc`, ...). Applying it to a project sets each of
+//! them in `[lints.<tool>]` of its manifest, or `[workspace.lints.<tool>]` of a
+//! virtual workspace, through `Manifest::set_lint` (formatting and comments
+//! survive). Lints the policy does not mention are left alone. A package
+//! inheriting its lints (`lints.workspace = true`) is not changed: the policy
+//! belongs in its workspace root.
+//!
+//! `preview` renders the change as a unified diff of `Cargo.toml` before
+//! anything is written.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use git2::Patch;
+
+use crate::git_ops::{self, GitError};
+use crate::project::manifest::{Manifest, ManifestError};
+
+/// Tool, lint and level, as in the configuration.
+pub type Policy = BTreeMap<String, BTreeMap<String, String>>;
+
+/// What applying the policy would do to a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Preview {
+    /// Unified diff of `Cargo.toml`.
+    Change(String),
+    /// The manifest already follows the policy.
+    Compliant,
+    /// The package inherits the lints of its workspace.
+    Inherited,
+}
+
+#[derive(Debug)]
+pub enum LintPolicyError {
+    Manifest(ManifestError),
+    Diff(git2::Error),
+    Git(GitError),
+}
+
+impl fmt::Display for LintPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Manifest(e) => write!(f, "{e}"),
+            Self::Diff(e) => write!(f, "Cannot compute the diff: {e}"),
+            Self::Git(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for LintPolicyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Manifest(e) => Some(e),
+            Self::Diff(e) => Some(e),
+            Self::Git(e) => Some(e),
+        }
+    }
+}
+
+impl From<ManifestError> for LintPolicyError {
+    fn from(e: ManifestError) -> Self {
+        Self::Manifest(e)
+    }
+}
+
+/// What applying `policy` to the project at `project_dir` would change.
+pub fn preview(project_dir: &Path, policy: &Policy) -> Result<Preview, LintPolicyError> {
+    let before = fs::read_to_string(project_dir.join("Cargo.toml"))
+        .map_err(|e| LintPolicyError::Manifest(ManifestError::Io(e)))?;
+    let mut manifest = Manifest::parse(&before)?;
+    if !set_all(&mut manifest, policy) {
+        return Ok(Preview::Inherited);
+    }
+    let after = manifest.to_string();
+    if after == before {
+        return Ok(Preview::Compliant);
+    }
+    unified_diff(&before, &after)
+        .map(Preview::Change)
+        .map_err(LintPolicyError::Diff)
+}
+
+/// Apply `policy` to the project at `project_dir`; `false` when it inherits its lints.
+///
+/// A safety snapshot is taken first (see `git_ops::snapshot`).
+pub fn apply(project_dir: &Path, policy: &Policy) -> Result<bool, LintPolicyError> {
+    git_ops::snapshot(project_dir, "lint policy").map_err(LintPolicyError::Git)?;
+    Ok(Manifest::edit(project_dir, |m| set_all(m, policy))?)
+}
+
+/// Set every lint of `policy` in `manifest`; `false` (nothing set) when it inherits its lints.
+fn set_all(manifest: &mut Manifest, policy: &Policy) -> bool {
+    policy.iter().all(|(tool, lints)| {
+        lints
+            .iter()
+            .all(|(lint, level)| manifest.set_lint(tool, lint, level))
+    })
+}
+
+/// Unified diff of `Cargo.toml` from `before` to `after`.
+fn unified_diff(before: &str, after: &str) -> Result<String, git2::Error> {
+    let path = Path::new("Cargo.toml");
+    let mut patch = Patch::from_buffers(
+        before.as_bytes(),
+        Some(path),
+        after.as_bytes(),
+        Some(path),
+        None,
+    )?;
+    let buf = patch.to_buf()?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn previews_and_applies_the_policy() {
+        let dir = TempDir::new("lint_policy");
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\n\n[lints.clippy]\npedantic = \"allow\" # for now\nunwrap_used = \"deny\"\n",
+        )
+        .unwrap();
+        let policy: Policy =
+            serde_norway::from_str("clippy: {pedantic: warn}\nrust: {unsafe_code: forbid}\n")
+                .unwrap();
+
+        let Preview::Change(diff) = preview(&dir, &policy).unwrap() else {
+            panic!("no change previewed");
+        };
+        assert!(diff.contains("-pedantic = \"allow\" # for now"));
+        assert!(diff.contains("+pedantic = \"warn\" # for now"));
+        assert!(diff.contains("+unsafe_code = \"forbid\""));
+
+        assert!(apply(&dir, &policy).unwrap());
+        let raw = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        assert!(raw.contains("unwrap_used = \"deny\""));
+        assert_eq!(preview(&dir, &policy).unwrap(), Preview::Compliant);
+
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"member\"\n\n[lints]\nworkspace = true\n",
+        )
+        .unwrap();
+        assert_eq!(preview(&dir, &policy).unwrap(), Preview::Inherited);
+    }
+}
@@ -1,12 +1,27 @@
 use std::fs;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 
-use crate::config::{Config, validate_projects_directory};
+use crate::config::{Config, ScanSettings, validate_projects_directory};
+use crate::events::{self, Event};
+use crate::git_ops::{self, GitError};
+use crate::metadata::{self, MetadataStore, ProjectMetadata};
+use crate::project::archive;
+use crate::project::kind::{self, ProjectKind};
+use crate::project::manifest::Manifest;
+use crate::project::no_std::NoStdProbe;
+use crate::project::rollup::WorkspaceRollup;
 use git2::{Repository, StatusOptions};
 use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on concurrent git status workers.
+const MAX_SCAN_WORKERS: usize = 8;
 
 /// Information about a discovered Rust project.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ProjectInfo {
     /// Directory name (project name).
     pub name: String,
@@ -14,6 +29,79 @@ pub struct ProjectInfo {
     pub path: PathBuf,
     /// Simple indicator: does the repository have any uncommitted changes?
     pub has_uncommitted_changes: bool,
+    /// Current git branch (`None` outside git repositories or with a detached `HEAD`).
+    pub branch: Option<String>,
+    /// Commits `(ahead, behind)` the upstream of the current branch, when it has one.
+    pub ahead_behind: Option<(usize, usize)>,
+    /// Default branch name when it has diverged from its upstream (both ahead and behind).
+    pub diverged_default: Option<String>,
+    /// Last computed workspace status rollup (workspaces only, see `project::rollup`).
+    pub rollup: Option<WorkspaceRollup>,
+    /// Last no_std probe verdict (libraries only, see `project::no_std`).
+    pub no_std: Option<NoStdProbe>,
+    /// `package.version` from the manifest, when declared.
+    pub version: Option<String>,
+    /// Kind of the project (see `project::kind`), when its manifest is readable.
+    pub kind: Option<ProjectKind>,
+    /// Commit time (seconds since epoch) of `HEAD`, for git repositories with commits.
+    pub last_commit: Option<i64>,
+    /// URL of the `origin` remote, to recognize the project once moved (see `project::vanished`).
+    pub remote_url: Option<String>,
+    /// Filesystem fingerprint the fields above were computed for (see `metadata::fingerprint`).
+    #[serde(skip)]
+    pub fingerprint: u64,
+    /// Pinned by the user: listed before the other projects.
+    pub pinned: bool,
+    /// Seconds since epoch of the last time the project was opened from rustm.
+    pub last_opened: Option<u64>,
+    /// User-defined tags, normalized (see `project::tags`).
+    pub tags: Vec<String>,
+    /// Quarantined until trusted (see `ui::trust`).
+    pub untrusted: bool,
+}
+
+/// Order of the project list. Pinned projects always come first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    /// By name (case-insensitive).
+    #[default]
+    Name,
+    /// Most recently opened first; never opened projects last, by name.
+    Recent,
+    /// Most recent `HEAD` commit first; projects without commits last, by name.
+    LastCommit,
+    /// Most recently modified on disk first (see `metadata::fingerprint`).
+    Modified,
+    /// Projects with uncommitted changes first, then by name.
+    Dirty,
+    /// Grouped by first tag, untagged projects last, then by name.
+    Tag,
+}
+
+impl SortMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Recent => "recent",
+            Self::LastCommit => "last commit",
+            Self::Modified => "modified",
+            Self::Dirty => "dirty first",
+            Self::Tag => "tag",
+        }
+    }
+
+    /// The mode after `self` when cycling through the modes.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Recent,
+            Self::Recent => Self::LastCommit,
+            Self::LastCommit => Self::Modified,
+            Self::Modified => Self::Dirty,
+            Self::Dirty => Self::Tag,
+            Self::Tag => Self::Name,
+        }
+    }
 }
 /// Errors that may occur while listing projects.
 #[derive(Debug)]
@@ -53,13 +141,15 @@ impl From<std::io::Error> for ListProjectsError {
 /// List all Rust projects in the configured projects directory.
 ///
 /// Rules (per spec):
-/// - A "Rust project" is any immediate subdirectory containing a `Cargo.toml`.
+/// - A "Rust project" is any subdirectory (up to `scan.max_depth` levels deep) containing a `Cargo.toml`.
+/// - Discovery does not descend into a project once found, nor into ignored or hidden directories,
+///   nor into the `archive/` directory of archived projects.
 /// - Include all such directories, even if not a git repository.
 /// - Provide indicator `*` (represented here by `has_uncommitted_changes = true`)
 ///   when repo has uncommitted changes.
 /// - If git-related checks fail for a given project, log and treat as non-git or clean.
 ///
-/// Returns projects sorted by name (case-insensitive).
+/// Returns pinned projects first, then the others, each sorted by name (case-insensitive).
 pub fn list_projects(config: &Config) -> Result<Vec<ProjectInfo>, ListProjectsError> {
     let root = Path::new(config.projects_directory());
 
@@ -69,9 +159,254 @@ pub fn list_projects(config: &Config) -> Result<Vec<ProjectInfo>, ListProjectsEr
 
     info!("Listing Rust projects in {}", root.display());
 
+    let cache = metadata::snapshot();
+    let mut projects = scan_root(root, config.scan(), Some(&cache))?;
+    for p in &mut projects {
+        // Not scan results: carried over whatever the fingerprint says.
+        let meta = cache.get(&p.path);
+        p.rollup = meta.and_then(|m| m.rollup.clone());
+        p.no_std = meta.and_then(|m| m.no_std.clone());
+        p.pinned = meta.is_some_and(|m| m.pinned);
+        p.last_opened = meta.and_then(|m| m.last_opened);
+        p.tags = meta.map(|m| m.tags.clone()).unwrap_or_default();
+        // Projects appearing after the first scan were cloned or copied in, not created by
+        // rustm. A lost store may have forgotten any quarantine: trust nothing it does not list.
+        p.untrusted = match meta {
+            Some(meta) => meta.untrusted,
+            None => {
+                cache.is_lost() || config.quarantine_new_projects() && cache.iter().next().is_some()
+            }
+        };
+    }
+    sort_projects(&mut projects, SortMode::Name);
+
+    let mut changes = Vec::new();
+    metadata::update(|store| {
+        for p in &projects {
+            let quarantined = p.untrusted && store.get(&p.path).is_none();
+            let entry = store.entry_mut(&p.path);
+            entry.untrusted |= quarantined;
+            entry.name.clone_from(&p.name);
+            changes.extend(store_scan_results(entry, p));
+        }
+    });
+    events::emit(changes);
+
+    Ok(projects)
+}
+
+/// Recompute the git status, branch, tracking, last commit and version of
+/// `projects`, whatever their fingerprint says, and update the metadata cache.
+///
+/// Much cheaper than `list_projects` (no discovery), and catches what the
+/// fingerprint misses, such as edits to existing files. Projects whose
+/// directory vanished are dropped.
+pub fn rescan_git_status(config: &Config, mut projects: Vec<ProjectInfo>) -> Vec<ProjectInfo> {
+    let root = Path::new(config.projects_directory());
+    projects.retain(|p| p.path.join("Cargo.toml").is_file());
+    refresh_all(&mut projects, &|p| config.scan().git_status_at(root, p));
+    let mut changes = Vec::new();
+    metadata::update(|store| {
+        for p in &projects {
+            changes.extend(store_scan_results(store.entry_mut(&p.path), p));
+        }
+    });
+    events::emit(changes);
+    projects
+}
+
+/// Recompute the fingerprint and scan results of every project.
+fn refresh_all(projects: &mut [ProjectInfo], git_status: &(dyn Fn(&Path) -> bool + Sync)) {
+    for p in projects.iter_mut() {
+        p.fingerprint = metadata::fingerprint(&p.path);
+    }
+    let all: Vec<usize> = (0..projects.len()).collect();
+    refresh_stale(projects, &all, git_status);
+}
+
+/// Record the scan results of `p` in its cache `entry`; the `StatusChanged`
+/// event when an earlier scan saw another git status, branch or tracking.
+fn store_scan_results(entry: &mut ProjectMetadata, p: &ProjectInfo) -> Option<Event> {
+    let scanned_before = entry.mtime != 0;
+    let changed = entry.has_uncommitted_changes != p.has_uncommitted_changes
+        || entry.branch != p.branch
+        || entry.ahead_behind != p.ahead_behind;
+    entry.mtime = p.fingerprint;
+    entry.has_uncommitted_changes = p.has_uncommitted_changes;
+    entry.branch.clone_from(&p.branch);
+    entry.ahead_behind = p.ahead_behind;
+    entry.diverged_default.clone_from(&p.diverged_default);
+    entry.package_version.clone_from(&p.version);
+    entry.kind = p.kind;
+    entry.last_commit = p.last_commit;
+    entry.remote_url.clone_from(&p.remote_url);
+    (scanned_before && changed).then(|| Event::StatusChanged {
+        name: p.name.clone(),
+        path: p.path.clone(),
+        dirty: p.has_uncommitted_changes,
+        branch: p.branch.clone(),
+        ahead_behind: p.ahead_behind,
+    })
+}
+
+/// Projects remembered by the metadata cache under the configured projects directory.
+///
+/// Instant (no git or manifest access) but possibly stale; entries whose
+/// directory vanished are skipped. `list_projects` gives up-to-date results.
+pub fn cached_projects(config: &Config) -> Vec<ProjectInfo> {
+    let root = Path::new(config.projects_directory());
+    let mut projects: Vec<ProjectInfo> = metadata::snapshot()
+        .iter()
+        .filter(|(path, meta)| {
+            !meta.name.is_empty()
+                && path.starts_with(root)
+                && !path.starts_with(root.join(archive::ARCHIVE_DIR))
+                && path.is_dir()
+        })
+        .map(|(path, meta)| ProjectInfo {
+            name: meta.name.clone(),
+            path,
+            has_uncommitted_changes: meta.has_uncommitted_changes,
+            branch: meta.branch.clone(),
+            ahead_behind: meta.ahead_behind,
+            diverged_default: meta.diverged_default.clone(),
+            rollup: meta.rollup.clone(),
+            no_std: meta.no_std.clone(),
+            version: meta.package_version.clone(),
+            kind: meta.kind,
+            last_commit: meta.last_commit,
+            remote_url: meta.remote_url.clone(),
+            fingerprint: meta.mtime,
+            pinned: meta.pinned,
+            last_opened: meta.last_opened,
+            tags: meta.tags.clone(),
+            untrusted: meta.untrusted,
+        })
+        .collect();
+    sort_projects(&mut projects, SortMode::Name);
+    projects
+}
+
+/// Display order: pinned projects first, then according to `mode`.
+pub fn sort_projects(projects: &mut [ProjectInfo], mode: SortMode) {
+    match mode {
+        SortMode::Name => projects.sort_by_key(|p| (!p.pinned, p.name.to_lowercase())),
+        SortMode::Recent => projects.sort_by_key(|p| {
+            (
+                !p.pinned,
+                std::cmp::Reverse(p.last_opened),
+                p.name.to_lowercase(),
+            )
+        }),
+        SortMode::LastCommit => projects.sort_by_key(|p| {
+            (
+                !p.pinned,
+                std::cmp::Reverse(p.last_commit),
+                p.name.to_lowercase(),
+            )
+        }),
+        SortMode::Modified => projects.sort_by_key(|p| {
+            (
+                !p.pinned,
+                std::cmp::Reverse(p.fingerprint),
+                p.name.to_lowercase(),
+            )
+        }),
+        SortMode::Dirty => {
+            projects.sort_by_key(|p| (!p.pinned, !p.has_uncommitted_changes, p.name.to_lowercase()))
+        }
+        SortMode::Tag => projects.sort_by_key(|p| {
+            (
+                !p.pinned,
+                p.tags.is_empty(),
+                p.tags.first().cloned(),
+                p.name.to_lowercase(),
+            )
+        }),
+    }
+}
+
+/// Discover projects below `root` according to `settings`.
+///
+/// Projects whose fingerprint matches their `cache` entry reuse the cached git
+/// status and version; the others are refreshed concurrently.
+pub fn scan_root(
+    root: &Path,
+    settings: &ScanSettings,
+    cache: Option<&MetadataStore>,
+) -> Result<Vec<ProjectInfo>, ListProjectsError> {
     let mut projects = Vec::new();
+    let skip = skipped(root, settings);
+    for (dir, scan) in settings.scan_roots(root) {
+        if let Err(e) = discover(&dir, 1, &scan, &skip, &mut projects) {
+            if dir == root {
+                return Err(e);
+            }
+            warn!("Skipping {} during discovery: {e}", dir.display());
+        }
+    }
+
+    let mut stale = Vec::new();
+    for (i, p) in projects.iter_mut().enumerate() {
+        p.fingerprint = metadata::fingerprint(&p.path);
+        match cache.and_then(|c| c.get(&p.path)) {
+            Some(meta) if meta.mtime == p.fingerprint && !meta.name.is_empty() => {
+                p.has_uncommitted_changes = meta.has_uncommitted_changes;
+                p.branch.clone_from(&meta.branch);
+                p.ahead_behind = meta.ahead_behind;
+                p.diverged_default.clone_from(&meta.diverged_default);
+                p.version.clone_from(&meta.package_version);
+                p.kind = meta.kind;
+                p.last_commit = meta.last_commit;
+                p.remote_url.clone_from(&meta.remote_url);
+            }
+            _ => stale.push(i),
+        }
+    }
+    refresh_stale(&mut projects, &stale, &|p| settings.git_status_at(root, p));
 
-    for entry_res in fs::read_dir(root)? {
+    // Sort by lowercased name to provide deterministic order.
+    projects.sort_by_key(|p| p.name.to_lowercase());
+    Ok(projects)
+}
+
+/// Directories discovery never enters from their parent: `archive/` and the
+/// directories of `scan.roots`, which are scanned on their own.
+fn skipped<'a>(root: &'a Path, settings: &'a ScanSettings) -> impl Fn(&Path) -> bool + 'a {
+    let archive_dir = root.join(archive::ARCHIVE_DIR);
+    move |dir| dir == archive_dir || settings.excluded(root, dir)
+}
+
+/// Whether `entry` is a directory discovery enters (symbolic links per `settings`).
+fn is_scanned_dir(entry: &fs::DirEntry, settings: &ScanSettings) -> bool {
+    match entry.file_type() {
+        Ok(t) if t.is_symlink() => {
+            settings.follow_symlinks && fs::metadata(entry.path()).is_ok_and(|m| m.is_dir())
+        }
+        Ok(t) => t.is_dir(),
+        Err(e) => {
+            warn!(
+                "Skipping {:?} (file_type error: {e})",
+                entry.path().display()
+            );
+            false
+        }
+    }
+}
+
+/// Recursive discovery step: inspect the children of `dir`, which sit at `depth`.
+fn discover(
+    dir: &Path,
+    depth: usize,
+    settings: &ScanSettings,
+    skip: &dyn Fn(&Path) -> bool,
+    projects: &mut Vec<ProjectInfo>,
+) -> Result<(), ListProjectsError> {
+    if depth > settings.max_depth {
+        return Ok(());
+    }
+
+    for entry_res in fs::read_dir(dir)? {
         let entry = match entry_res {
             Ok(e) => e,
             Err(e) => {
@@ -81,20 +416,7 @@ pub fn list_projects(config: &Config) -> Result<Vec<ProjectInfo>, ListProjectsEr
         };
 
         let path = entry.path();
-        let file_type = match entry.file_type() {
-            Ok(t) => t,
-            Err(e) => {
-                warn!("Skipping {:?} (file_type error: {e})", path.display());
-                continue;
-            }
-        };
-
-        if !file_type.is_dir() {
-            continue;
-        }
-
-        let cargo_toml = path.join("Cargo.toml");
-        if !cargo_toml.is_file() {
+        if !is_scanned_dir(&entry, settings) {
             continue;
         }
 
@@ -104,32 +426,237 @@ pub fn list_projects(config: &Config) -> Result<Vec<ProjectInfo>, ListProjectsEr
             .unwrap_or_default()
             .to_string();
 
-        // Determine git status if applicable.
-        let has_uncommitted_changes = match scan_git_status(&path) {
-            Ok(res) => res,
-            Err(e) => {
-                // Log and degrade gracefully.
-                warn!("Git status check failed for {}: {e}", path.display());
-                false
+        // Archived projects are listed apart (see `project::archive`).
+        if is_ignored(&name, settings) || skip(&path) {
+            continue;
+        }
+
+        let cargo_toml = path.join("Cargo.toml");
+        if !cargo_toml.is_file() {
+            if let Err(e) = discover(&path, depth + 1, settings, skip, projects) {
+                warn!("Skipping {} during discovery: {e}", path.display());
             }
-        };
+            continue;
+        }
 
+        // Git status and version are filled in afterwards (see `scan_root`).
         projects.push(ProjectInfo {
             name,
             path,
-            has_uncommitted_changes,
+            has_uncommitted_changes: false,
+            branch: None,
+            ahead_behind: None,
+            diverged_default: None,
+            rollup: None,
+            no_std: None,
+            version: None,
+            kind: None,
+            last_commit: None,
+            remote_url: None,
+            fingerprint: 0,
+            pinned: false,
+            last_opened: None,
+            tags: Vec::new(),
+            untrusted: false,
         });
     }
 
-    // Sort by lowercased name to provide deterministic order.
-    projects.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    Ok(projects)
+    Ok(())
+}
+
+/// Directories whose entries decide which projects `scan_root` finds: `root`,
+/// the directories of `scan.roots` and, down to their `max_depth`, the
+/// directories that are not projects (ignored ones and `archive/` excluded).
+///
+/// A project appears or disappears when one of their entries does, or when a
+/// `Cargo.toml` is added to one of them (see `projects_watch`).
+pub fn discovery_dirs(root: &Path, settings: &ScanSettings) -> Vec<PathBuf> {
+    let skip = skipped(root, settings);
+    let mut dirs = Vec::new();
+    for (dir, scan) in settings.scan_roots(root) {
+        dirs.push(dir.clone());
+        collect_discovery_dirs(&dir, 1, &scan, &skip, &mut dirs);
+    }
+    dirs
+}
+
+fn collect_discovery_dirs(
+    dir: &Path,
+    depth: usize,
+    settings: &ScanSettings,
+    skip: &dyn Fn(&Path) -> bool,
+    dirs: &mut Vec<PathBuf>,
+) {
+    if depth > settings.max_depth {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !is_scanned_dir(&entry, settings)
+            || is_ignored(&name, settings)
+            || skip(&path)
+            || path.join("Cargo.toml").is_file()
+        {
+            continue;
+        }
+        dirs.push(path.clone());
+        collect_discovery_dirs(&path, depth + 1, settings, skip, dirs);
+    }
+}
+
+/// Recompute the git and manifest derived fields of the projects at `stale` indices using a small worker pool.
+///
+/// Git status is by far the most expensive part of a scan, so the work is
+/// shared between `available_parallelism` scoped threads pulling the next index
+/// from an atomic counter (slow repositories do not hold up a whole chunk).
+fn refresh_stale(
+    projects: &mut [ProjectInfo],
+    stale: &[usize],
+    git_status: &(dyn Fn(&Path) -> bool + Sync),
+) {
+    let workers = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(MAX_SCAN_WORKERS)
+        .min(stale.len());
+    if workers == 0 {
+        return;
+    }
+
+    let next = AtomicUsize::new(0);
+    let shared: &[ProjectInfo] = projects;
+    let results: Vec<(usize, ProjectInfo)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut local = Vec::new();
+                    loop {
+                        let n = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(&i) = stale.get(n) else {
+                            break;
+                        };
+                        let mut project = shared[i].clone();
+                        let status = git_status(&project.path);
+                        refresh(&mut project, status);
+                        local.push((i, project));
+                    }
+                    local
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_default())
+            .collect()
+    });
+
+    for (i, project) in results {
+        projects[i] = project;
+    }
+}
+
+/// Recompute git status, branch, upstream tracking, default branch divergence,
+/// last commit time, `origin` URL, version and kind of `project`.
+///
+/// Without `git_status`, the working tree status, upstream tracking and
+/// divergence (the walks that are slow on network drives) are left unknown.
+fn refresh(project: &mut ProjectInfo, git_status: bool) {
+    let path = &project.path;
+    project.branch = branch_or_log(path);
+    if git_status {
+        project.has_uncommitted_changes = git_dirty_or_log(path);
+        project.ahead_behind = if project.branch.is_some() {
+            ahead_behind_or_log(path)
+        } else {
+            None
+        };
+        project.diverged_default = divergence_or_log(path);
+    } else {
+        project.has_uncommitted_changes = false;
+        project.ahead_behind = None;
+        project.diverged_default = None;
+    }
+    project.last_commit = last_commit_or_log(path);
+    // No remote (or no repository) is common: nothing worth logging.
+    project.remote_url = git_ops::remote_url(path, "origin").ok();
+    let manifest = Manifest::read(path).ok();
+    project.version = manifest
+        .as_ref()
+        .and_then(|m| m.package_version().map(ToString::to_string));
+    project.kind = manifest.as_ref().map(|m| kind::detect(path, m));
+}
+
+/// Git dirty state of `path`; errors are logged and treated as clean.
+fn git_dirty_or_log(path: &Path) -> bool {
+    match scan_git_status(path) {
+        Ok(res) => res,
+        Err(e) => {
+            // Log and degrade gracefully.
+            warn!("Git status check failed for {}: {e}", path.display());
+            false
+        }
+    }
+}
+
+/// Current branch of `path`; non-repositories yield `None`, other errors are logged.
+fn branch_or_log(path: &Path) -> Option<String> {
+    match git_ops::current_branch(path) {
+        Ok(branch) => branch,
+        Err(GitError::NotARepository(_)) => None,
+        Err(e) => {
+            warn!("Branch lookup failed for {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Ahead/behind counts of `path` against its upstream; errors are logged.
+fn ahead_behind_or_log(path: &Path) -> Option<(usize, usize)> {
+    match git_ops::ahead_behind(path) {
+        Ok(counts) => counts,
+        Err(e) => {
+            warn!("Upstream comparison failed for {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Name of the default branch of `path` when it diverged from its upstream; errors are logged.
+fn divergence_or_log(path: &Path) -> Option<String> {
+    match git_ops::default_branch_divergence(path) {
+        Ok(divergence) => divergence.map(|d| d.branch),
+        Err(GitError::NotARepository(_)) => None,
+        Err(e) => {
+            warn!("Divergence check failed for {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Commit time of `HEAD` in `path`; non-repositories yield `None`, other errors are logged.
+fn last_commit_or_log(path: &Path) -> Option<i64> {
+    match git_ops::recent_commits(path, 1) {
+        Ok(commits) => commits.first().map(|c| c.time),
+        Err(GitError::NotARepository(_)) => None,
+        Err(e) => {
+            warn!("Last commit lookup failed for {}: {e}", path.display());
+            None
+        }
+    }
 }
 
-/// Internal helper: examine a directory for git status.
+/// `true` when a directory named `name` must not be scanned.
+fn is_ignored(name: &str, settings: &ScanSettings) -> bool {
+    (settings.skip_hidden && name.starts_with('.')) || settings.ignore.iter().any(|i| i == name)
+}
+
+/// Examine a directory for git status.
 ///
 /// Returns `true` if `dir` is a Git repository that has any uncommitted (including untracked) changes; otherwise returns `false`.
-fn scan_git_status(dir: &Path) -> Result<bool, git2::Error> {
+pub fn scan_git_status(dir: &Path) -> Result<bool, git2::Error> {
     // Quick existence check for .git to reduce error noise.
     if !dir.join(".git").exists() {
         return Ok(false);
@@ -167,78 +694,22 @@ fn scan_git_status(dir: &Path) -> Result<bool, git2::Error> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::project::tags;
+    use crate::temp_dir::TempDir;
     use std::io::Write;
-    use std::time::{SystemTime, UNIX_EPOCH};
 
-    fn temp_dir() -> PathBuf {
-        let mut d = std::env::temp_dir();
-        let nonce = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        d.push(format!("rustm_list_projects_test_{nonce}"));
-        fs::create_dir_all(&d).unwrap();
-        d
-    }
-
-    // Minimal in-memory Config substitute for tests (we only need its accessor).
-    struct DummyConfig {
-        dir: String,
-    }
-    impl DummyConfig {
-        fn new(dir: String) -> Self {
-            Self { dir }
-        }
-    }
-    impl DummyConfig {
-        fn as_config_like(&self) -> TestConfigLike<'_> {
-            TestConfigLike { dir: &self.dir }
-        }
-    }
-    struct TestConfigLike<'a> {
-        dir: &'a str,
-    }
-    impl TestConfigLike<'_> {
-        fn projects_directory(&self) -> &str {
-            self.dir
-        }
-    }
-
-    // Adapter so we can reuse list_projects logic with a fake config.
-    // (We don't want to pull full real Config in unit tests.)
-    fn list_with_fake(config_like: &TestConfigLike) -> Result<Vec<ProjectInfo>, ListProjectsError> {
-        // Inline duplicate of list_projects first lines (subset) to avoid coupling to real Config.
-        let root = Path::new(config_like.projects_directory());
-        if !root.exists() {
-            return Err(ListProjectsError::ProjectsDirInvalid(
-                "does not exist".into(),
-            ));
-        }
-        let mut projects = Vec::new();
-        for entry_res in fs::read_dir(root)? {
-            let entry = entry_res?;
-            let path = entry.path();
-            if !entry.file_type()?.is_dir() {
-                continue;
-            }
-            if !path.join("Cargo.toml").is_file() {
-                continue;
-            }
-            let name = path.file_name().unwrap().to_string_lossy().into_owned();
-            let has_uncommitted_changes = scan_git_status(&path).unwrap_or(false);
-            projects.push(ProjectInfo {
-                name,
-                path,
-                has_uncommitted_changes,
-            });
-        }
-        projects.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-        Ok(projects)
+    fn write_manifest(dir: &Path, name: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            format!("[package]\nname='{name}'\nversion='0.1.0'\nedition='2021'\n"),
+        )
+        .unwrap();
     }
 
     #[test]
     fn lists_simple_projects() {
-        let base = temp_dir();
+        let base = TempDir::new("list_projects");
 
         // project1 (non-git)
         let p1 = base.join("project1");
@@ -257,16 +728,242 @@ mod tests {
             b"[package]\nname='project2'\nversion='0.1.0'\nedition='2021'\n",
         )
         .unwrap();
+        Repository::init(&p2).unwrap();
         // Create an untracked file
         let mut f = fs::File::create(p2.join("src_lib.rs")).unwrap();
         write!(f, "pub fn x() -> i32 {{ 1 }}").unwrap();
         // Do not add/commit to keep it untracked (dirty)
 
-        let cfg = DummyConfig::new(base.to_string_lossy().into_owned());
-        let list = list_with_fake(&cfg.as_config_like()).unwrap();
+        let list = scan_root(&base, &ScanSettings::default(), None).unwrap();
 
         assert_eq!(list.len(), 2);
         let p2i = list.iter().find(|p| p.name == "project2").unwrap();
         assert!(p2i.has_uncommitted_changes); // Should detect untracked file
     }
+
+    #[test]
+    fn recursive_scan_respects_depth_and_ignores() {
+        let base = TempDir::new("list_projects");
+        write_manifest(&base.join("work").join("service"), "service");
+        write_manifest(
+            &base.join("oss").join("deep").join("nested").join("lib"),
+            "lib",
+        );
+        write_manifest(&base.join("node_modules").join("pkg"), "pkg");
+        write_manifest(&base.join(".cache").join("hidden"), "hidden");
+        // Crates inside a project are not listed separately.
+        write_manifest(&base.join("mono"), "mono");
+        write_manifest(&base.join("mono").join("member"), "member");
+
+        let settings = ScanSettings {
+            max_depth: 2,
+            ..ScanSettings::default()
+        };
+        let names: Vec<String> = scan_root(&base, &settings, None)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["mono", "service"]);
+
+        let settings = ScanSettings {
+            max_depth: 4,
+            ..ScanSettings::default()
+        };
+        let names: Vec<String> = scan_root(&base, &settings, None)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["lib", "mono", "service"]);
+    }
+
+    #[test]
+    fn roots_are_scanned_with_their_own_settings() {
+        let base = TempDir::new("list_projects");
+        let outside = TempDir::new("list_projects");
+        write_manifest(&base.join("a").join("b").join("deep"), "deep");
+        write_manifest(&base.join("mirror").join("x").join("y").join("far"), "far");
+        write_manifest(
+            &base.join("mirror").join("vendor").join("skipped"),
+            "skipped",
+        );
+        write_manifest(&outside.join("linked"), "linked");
+        Repository::init(base.join("mirror").join("x").join("y").join("far")).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&outside, base.join("link")).unwrap();
+
+        let mut settings: ScanSettings = serde_norway::from_str(
+            "max_depth: 2\nroots:\n  mirror: {max_depth: 3, ignore: [vendor], git_status: false}\n",
+        )
+        .unwrap();
+        let names = |settings: &ScanSettings| -> Vec<(String, bool)> {
+            scan_root(&base, settings, None)
+                .unwrap()
+                .into_iter()
+                .map(|p| (p.name, p.has_uncommitted_changes))
+                .collect()
+        };
+        // `deep` is below max_depth; `far` is within the depth of `mirror`, whose
+        // status is not computed.
+        assert_eq!(names(&settings), [("far".to_string(), false)]);
+
+        settings.follow_symlinks = true;
+        let expected = if cfg!(unix) {
+            vec![("far".to_string(), false), ("linked".to_string(), false)]
+        } else {
+            vec![("far".to_string(), false)]
+        };
+        assert_eq!(names(&settings), expected);
+        let dirs = discovery_dirs(&base, &settings);
+        assert!(dirs.contains(&base.join("mirror").join("x").join("y")));
+        assert!(!dirs.contains(&base.join("mirror").join("vendor")));
+    }
+
+    #[test]
+    fn discovery_dirs_are_the_non_project_dirs_within_depth() {
+        let base = TempDir::new("list_projects");
+        write_manifest(&base.join("work").join("service"), "service");
+        write_manifest(&base.join("mono"), "mono");
+        fs::create_dir_all(base.join("work").join("empty").join("deeper")).unwrap();
+        fs::create_dir_all(base.join("target").join("debug")).unwrap();
+        fs::create_dir_all(base.join("archive").join("old")).unwrap();
+
+        let settings = ScanSettings {
+            max_depth: 2,
+            ..ScanSettings::default()
+        };
+        let mut dirs = discovery_dirs(&base, &settings);
+        dirs.sort();
+        assert_eq!(
+            dirs,
+            [
+                base.to_path_buf(),
+                base.join("work"),
+                base.join("work").join("empty")
+            ]
+        );
+    }
+
+    #[test]
+    fn parallel_git_status_matches_each_project() {
+        let base = TempDir::new("list_projects");
+        for i in 0..12 {
+            let dir = base.join(format!("p{i:02}"));
+            write_manifest(&dir, &format!("p{i:02}"));
+            if i % 3 == 0 {
+                // Initialised repo with an untracked Cargo.toml => dirty.
+                Repository::init(&dir).unwrap();
+            }
+        }
+
+        let list = scan_root(&base, &ScanSettings::default(), None).unwrap();
+        assert_eq!(list.len(), 12);
+        for (i, p) in list.iter().enumerate() {
+            assert_eq!(p.has_uncommitted_changes, i % 3 == 0, "{}", p.name);
+        }
+    }
+
+    #[test]
+    fn fresh_cache_entries_are_reused() {
+        let base = TempDir::new("list_projects");
+        let dir = base.join("cached");
+        write_manifest(&dir, "cached");
+
+        let mut cache = MetadataStore::default();
+        let entry = cache.entry_mut(&dir);
+        entry.name = "cached".into();
+        entry.mtime = metadata::fingerprint(&dir);
+        entry.has_uncommitted_changes = true; // differs from reality on purpose
+        entry.package_version = Some("9.9.9".into());
+        entry.kind = Some(ProjectKind::Library);
+
+        let list = scan_root(&base, &ScanSettings::default(), Some(&cache)).unwrap();
+        assert!(list[0].has_uncommitted_changes);
+        assert_eq!(list[0].version.as_deref(), Some("9.9.9"));
+        assert_eq!(list[0].kind, Some(ProjectKind::Library));
+
+        // No kind (unreadable manifest) is a result too: not recomputed.
+        cache.entry_mut(&dir).kind = None;
+        let list = scan_root(&base, &ScanSettings::default(), Some(&cache)).unwrap();
+        assert_eq!(list[0].kind, None);
+        assert_eq!(list[0].version.as_deref(), Some("9.9.9"));
+
+        cache.entry_mut(&dir).mtime = 0; // stale => recomputed
+        let list = scan_root(&base, &ScanSettings::default(), Some(&cache)).unwrap();
+        assert!(!list[0].has_uncommitted_changes);
+        assert_eq!(list[0].version.as_deref(), Some("0.1.0"));
+    }
+
+    #[test]
+    fn refresh_all_ignores_the_fingerprint() {
+        let base = TempDir::new("list_projects");
+        let dir = base.join("edited");
+        write_manifest(&dir, "edited");
+        Repository::init(&dir).unwrap();
+
+        let mut list = scan_root(&base, &ScanSettings::default(), None).unwrap();
+        assert!(list[0].has_uncommitted_changes);
+        // Pretend the cached status is clean: a fingerprint match would keep it.
+        list[0].has_uncommitted_changes = false;
+        refresh_all(&mut list, &|_| true);
+        assert!(list[0].has_uncommitted_changes);
+        assert_eq!(list[0].fingerprint, metadata::fingerprint(&dir));
+    }
+
+    #[test]
+    fn pinned_first_then_by_mode() {
+        let project = |name: &str, pinned: bool, last_opened: Option<u64>, tag: &str| ProjectInfo {
+            name: name.into(),
+            path: PathBuf::from(name),
+            has_uncommitted_changes: false,
+            branch: None,
+            ahead_behind: None,
+            diverged_default: None,
+            rollup: None,
+            no_std: None,
+            version: None,
+            kind: None,
+            last_commit: None,
+            remote_url: None,
+            fingerprint: 0,
+            pinned,
+            last_opened,
+            tags: tags::parse(tag),
+            untrusted: false,
+        };
+        let mut projects = vec![
+            project("alpha", false, Some(10), ""),
+            project("beta", false, None, "work"),
+            project("gamma", true, None, ""),
+            project("Delta", false, Some(20), "oss work"),
+        ];
+        let names = |projects: &[ProjectInfo]| -> Vec<String> {
+            projects.iter().map(|p| p.name.clone()).collect()
+        };
+
+        sort_projects(&mut projects, SortMode::Name);
+        assert_eq!(names(&projects), ["gamma", "alpha", "beta", "Delta"]);
+        sort_projects(&mut projects, SortMode::Recent);
+        assert_eq!(names(&projects), ["gamma", "Delta", "alpha", "beta"]);
+        sort_projects(&mut projects, SortMode::Tag);
+        assert_eq!(names(&projects), ["gamma", "Delta", "beta", "alpha"]);
+
+        for (p, (commit, mtime, dirty)) in projects.iter_mut().zip([
+            (Some(5), 1, false),
+            (None, 3, true),
+            (Some(9), 2, false),
+            (Some(7), 4, true),
+        ]) {
+            p.last_commit = commit;
+            p.fingerprint = mtime;
+            p.has_uncommitted_changes = dirty;
+        }
+        sort_projects(&mut projects, SortMode::LastCommit);
+        assert_eq!(names(&projects), ["gamma", "beta", "alpha", "Delta"]);
+        sort_projects(&mut projects, SortMode::Modified);
+        assert_eq!(names(&projects), ["gamma", "alpha", "Delta", "beta"]);
+        sort_projects(&mut projects, SortMode::Dirty);
+        assert_eq!(names(&projects), ["gamma", "alpha", "Delta", "beta"]);
+    }
 }
@@ -0,0 +1,133 @@
+//! Rename and delete projects.
+//!
+//! Renaming moves the project directory to a sibling with the new name and can
+//! update `[package] name` to match. Deleting removes the project directory
+//! recursively; the UI asks for the project name to be typed first. Neither
+//! touches the metadata cache: callers move or drop the entry afterwards.
+//!
+//! A rename whose manifest cannot be updated moves the directory back (see
+//! `crate::transaction`).
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::project::create::validate_name;
+use crate::project::manifest::{Manifest, ManifestError};
+use crate::transaction::Transaction;
+
+#[derive(Debug)]
+pub enum ManageError {
+    InvalidName(String),
+    /// The path does not look like a cargo project; refuse to touch it.
+    NotAProject(PathBuf),
+    TargetExists(PathBuf),
+    Manifest(ManifestError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for ManageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidName(msg) => write!(f, "Invalid name: {msg}"),
+            Self::NotAProject(p) => write!(f, "{} has no Cargo.toml", p.display()),
+            Self::TargetExists(p) => write!(f, "{} already exists", p.display()),
+            Self::Manifest(e) => write!(f, "Manifest error: {e}"),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ManageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Manifest(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Rename the directory of the project at `path` to `new_name`, optionally
+/// updating `[package] name` too. Returns the new project path.
+pub fn rename(path: &Path, new_name: &str, rename_package: bool) -> Result<PathBuf, ManageError> {
+    validate_name(new_name).map_err(ManageError::InvalidName)?;
+    ensure_project(path)?;
+    let target = path
+        .parent()
+        .map_or_else(|| PathBuf::from(new_name), |parent| parent.join(new_name));
+    if target.exists() {
+        return Err(ManageError::TargetExists(target));
+    }
+    let mut tx = Transaction::new(format!("Renaming {}", path.display()));
+    tx.rename(path, &target).map_err(ManageError::Io)?;
+
+    if rename_package {
+        tx.preserve(&target.join("Cargo.toml"))
+            .map_err(ManageError::Io)?;
+        Manifest::edit(&target, |m| m.set_package_name(new_name)).map_err(ManageError::Manifest)?;
+    }
+    tx.commit();
+    Ok(target)
+}
+
+/// Remove the project at `path` and everything below it.
+pub fn delete(path: &Path) -> Result<(), ManageError> {
+    ensure_project(path)?;
+    fs::remove_dir_all(path).map_err(ManageError::Io)
+}
+
+fn ensure_project(path: &Path) -> Result<(), ManageError> {
+    if path.join("Cargo.toml").is_file() {
+        Ok(())
+    } else {
+        Err(ManageError::NotAProject(path.to_path_buf()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn rename_moves_directory_and_package_name() {
+        let dir = TempDir::new("manage");
+        let project = dir.join("old");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"old\" # keep me\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+
+        let renamed = rename(&project, "new-name", true).unwrap();
+        assert_eq!(renamed, dir.join("new-name"));
+        assert!(!project.exists());
+        let raw = fs::read_to_string(renamed.join("Cargo.toml")).unwrap();
+        assert!(raw.contains("name = \"new-name\" # keep me"));
+
+        assert!(matches!(
+            rename(&renamed, "bad name", false),
+            Err(ManageError::InvalidName(_))
+        ));
+
+        // A manifest that cannot be edited: the directory moves back.
+        fs::write(renamed.join("Cargo.toml"), "[package\nname = ").unwrap();
+        assert!(matches!(
+            rename(&renamed, "third", true),
+            Err(ManageError::Manifest(_))
+        ));
+        assert!(renamed.join("Cargo.toml").is_file());
+        assert!(!dir.join("third").exists());
+    }
+
+    #[test]
+    fn delete_refuses_non_projects() {
+        let dir = TempDir::new("manage");
+        assert!(matches!(delete(&dir), Err(ManageError::NotAProject(_))));
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"x\"\n").unwrap();
+        delete(&dir).unwrap();
+        assert!(!dir.exists());
+    }
+}
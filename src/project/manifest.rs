@@ -0,0 +1,1114 @@
+//! Cargo manifest access.
+//!
+//! Thin wrapper around `toml_edit` used to inspect a project's `Cargo.toml`
+//! (and the optional `.cargo/config.toml`). Parsing goes through `toml_edit`
+//! rather than plain `toml` so edits (`set_patch`, ...) preserve formatting and
+//! comments when written back with `write`.
+//!
+//! This is the edit layer every feature changing a manifest goes through:
+//! `Manifest::edit` reads, applies the mutations and writes the file back
+//! atomically only when something changed. Replaced values keep their
+//! decoration (trailing comments, spacing), added entries keep a sorted
+//! table sorted, and keys inherited from the workspace are never overwritten.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, value};
+
+/// Dependency tables inspected by `dependency_names`.
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Tables cargo only honours in the workspace root manifest.
+const ROOT_ONLY_TABLES: [&str; 3] = ["patch", "profile", "replace"];
+
+/// `[package]` keys holding a path relative to the manifest.
+const PACKAGE_PATH_KEYS: [&str; 4] = ["readme", "license-file", "build", "workspace"];
+
+/// Cargo target sections (`[lib]`, `[[bin]]`, ...) that may set a `path`.
+const TARGET_SECTIONS: [&str; 5] = ["lib", "bin", "example", "test", "bench"];
+
+/// Levels a lint can be set to in `[lints]`.
+pub const LINT_LEVELS: [&str; 5] = ["allow", "expect", "warn", "deny", "forbid"];
+
+/// Dependency table a dependency is declared in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    Normal,
+    Dev,
+    Build,
+}
+
+impl DependencyKind {
+    pub const ALL: [Self; 3] = [Self::Normal, Self::Dev, Self::Build];
+
+    /// Manifest table name.
+    pub fn table(self) -> &'static str {
+        match self {
+            Self::Normal => "dependencies",
+            Self::Dev => "dev-dependencies",
+            Self::Build => "build-dependencies",
+        }
+    }
+
+    /// Short label used in lists (`dev`, `build`; empty for normal dependencies).
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Normal => "",
+            Self::Dev => "dev",
+            Self::Build => "build",
+        }
+    }
+}
+
+/// A dependency as declared in one of the dependency tables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeclaredDependency {
+    pub name: String,
+    pub kind: DependencyKind,
+    /// Key of the `[target.<target>]` table declaring it (`cfg(unix)`, a triple), if any.
+    pub target: Option<String>,
+    /// Version requirement, when declared (`None` for path/git-only or inherited deps).
+    pub req: Option<String>,
+    /// `path` or `git` source (`None` for registry dependencies).
+    pub source: Option<String>,
+    /// `workspace = true`.
+    pub inherited: bool,
+}
+
+/// Parsed `Cargo.toml` of a project.
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    doc: DocumentMut,
+}
+
+/// Errors that can occur while reading a manifest.
+#[derive(Debug)]
+pub enum ManifestError {
+    Io(std::io::Error),
+    Parse(String),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error reading Cargo.toml: {e}"),
+            Self::Parse(e) => write!(f, "Invalid Cargo.toml: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse(_) => None,
+        }
+    }
+}
+
+/// The document as `write` would save it.
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.doc)
+    }
+}
+
+impl Manifest {
+    /// Read and parse `<project_dir>/Cargo.toml`.
+    pub fn read(project_dir: &Path) -> Result<Self, ManifestError> {
+        let raw = fs::read_to_string(project_dir.join("Cargo.toml")).map_err(ManifestError::Io)?;
+        Self::parse(&raw)
+    }
+
+    /// Parse manifest contents.
+    pub fn parse(raw: &str) -> Result<Self, ManifestError> {
+        let doc = raw
+            .parse::<DocumentMut>()
+            .map_err(|e| ManifestError::Parse(e.to_string()))?;
+        Ok(Self { doc })
+    }
+
+    /// `package.name`, when declared as a plain string.
+    pub fn package_name(&self) -> Option<&str> {
+        self.doc.get("package")?.get("name")?.as_str()
+    }
+
+    /// `package.version`, when declared as a plain string (not workspace-inherited).
+    pub fn package_version(&self) -> Option<&str> {
+        self.doc.get("package")?.get("version")?.as_str()
+    }
+
+    /// `package.<key>`, when declared as a plain string (e.g. `description`, `license`).
+    pub fn package_str(&self, key: &str) -> Option<&str> {
+        self.doc.get("package")?.get(key)?.as_str()
+    }
+
+    /// `true` when `package.<key>` is set and not blank, workspace-inherited values included.
+    ///
+    /// Virtual workspaces have no `[package]`: `[workspace.package]` is checked instead.
+    pub fn declares_package_key(&self, key: &str) -> bool {
+        let package = self
+            .doc
+            .get("package")
+            .or_else(|| self.doc.get("workspace")?.get("package"));
+        package
+            .and_then(|p| p.get(key))
+            .is_some_and(|v| match (v.as_str(), v.as_array()) {
+                (Some(s), _) => !s.trim().is_empty(),
+                (_, Some(array)) => !array.is_empty(),
+                _ => true,
+            })
+    }
+
+    /// Set `package.<key>` (e.g. `version` for a bump), keeping its decoration.
+    ///
+    /// Returns `false` without a `[package]` table, or when the key is inherited from the
+    /// workspace (`version.workspace = true`).
+    pub fn set_package_value(&mut self, key: &str, new: impl Into<toml_edit::Value>) -> bool {
+        if self.package_key_inherited(key) {
+            return false;
+        }
+        let Some(package) = self
+            .doc
+            .get_mut("package")
+            .and_then(Item::as_table_like_mut)
+        else {
+            return false;
+        };
+        match package.get_mut(key) {
+            Some(old) => replace_value(old, new.into()),
+            None => {
+                package.insert(key, value(new));
+            }
+        }
+        true
+    }
+
+    /// Strings of the `package.<key>` array (e.g. `keywords`); empty when absent or not an array.
+    pub fn package_str_array(&self, key: &str) -> Vec<String> {
+        self.doc
+            .get("package")
+            .and_then(|p| p.get(key))
+            .and_then(Item::as_array)
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// `true` when `package.<key>` is inherited from the workspace (`<key>.workspace = true`).
+    pub fn package_key_inherited(&self, key: &str) -> bool {
+        self.doc
+            .get("package")
+            .and_then(|p| p.get(key))
+            .and_then(|v| v.get("workspace"))
+            .and_then(Item::as_bool)
+            == Some(true)
+    }
+
+    /// Remove `package.<key>`; returns `false` when it was not set.
+    pub fn remove_package_key(&mut self, key: &str) -> bool {
+        self.doc
+            .get_mut("package")
+            .and_then(Item::as_table_like_mut)
+            .and_then(|p| p.remove(key))
+            .is_some()
+    }
+
+    /// Names of explicit `[[bin]]` targets.
+    pub fn bin_target_names(&self) -> Vec<String> {
+        self.target_names("bin")
+    }
+
+    /// Names of the explicit targets of a `[[<section>]]` array (`bin`, `bench`, `example`, ...).
+    pub fn target_names(&self, section: &str) -> Vec<String> {
+        self.doc
+            .get(section)
+            .and_then(Item::as_array_of_tables)
+            .map(|targets| {
+                targets
+                    .iter()
+                    .filter_map(|t| t.get("name").and_then(Item::as_str))
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Append a `[[<section>]]` target named `name` with the extra `keys` (e.g. `harness = false`).
+    ///
+    /// Returns `false` when the section already has a target of that name.
+    pub fn add_target(
+        &mut self,
+        section: &str,
+        name: &str,
+        keys: &[(&str, toml_edit::Value)],
+    ) -> bool {
+        if self.target_names(section).iter().any(|n| n == name) {
+            return false;
+        }
+        let Some(targets) = self
+            .doc
+            .entry(section)
+            .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+            .as_array_of_tables_mut()
+        else {
+            return false;
+        };
+        let mut target = Table::new();
+        target.insert("name", value(name));
+        for (key, v) in keys {
+            target.insert(key, value(v.clone()));
+        }
+        targets.push(target);
+        true
+    }
+
+    /// `true` when the manifest declares a `[workspace]` table.
+    pub fn is_workspace(&self) -> bool {
+        self.doc.get("workspace").is_some()
+    }
+
+    /// `true` when an explicit `[lib]` section is present.
+    pub fn has_lib_section(&self) -> bool {
+        self.doc.get("lib").is_some()
+    }
+
+    /// Names of all declared dependencies (normal, dev, build and target-specific).
+    pub fn dependency_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        collect_dependency_names(self.doc.as_item(), &mut names);
+
+        if let Some(targets) = self.doc.get("target").and_then(Item::as_table_like) {
+            for (_, target) in targets.iter() {
+                collect_dependency_names(target, &mut names);
+            }
+        }
+
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// `true` when `name` appears in any dependency table.
+    pub fn has_dependency(&self, name: &str) -> bool {
+        self.dependency_names().iter().any(|d| d == name)
+    }
+
+    /// Dependencies of the `[dependencies]`, `[dev-dependencies]` and
+    /// `[build-dependencies]` tables, top-level first then per target, in
+    /// declaration order.
+    pub fn declared_dependencies(&self) -> Vec<DeclaredDependency> {
+        let mut out = Vec::new();
+        collect_declared_dependencies(self.doc.as_item(), None, &mut out);
+        if let Some(targets) = self.doc.get("target").and_then(Item::as_table_like) {
+            for (target, tables) in targets.iter() {
+                collect_declared_dependencies(tables, Some(target), &mut out);
+            }
+        }
+        out
+    }
+
+    /// Replace the version requirement of `name` in the `kind` table (of `target`,
+    /// for a target-specific one), keeping its other keys.
+    /// Returns `false` when the dependency is not declared there.
+    pub fn set_dependency_version(
+        &mut self,
+        kind: DependencyKind,
+        target: Option<&str>,
+        name: &str,
+        req: &str,
+    ) -> bool {
+        let parent = match target {
+            Some(target) => self
+                .doc
+                .get_mut("target")
+                .and_then(Item::as_table_like_mut)
+                .and_then(|t| t.get_mut(target)),
+            None => Some(self.doc.as_item_mut()),
+        };
+        let Some(spec) = parent
+            .and_then(|p| p.get_mut(kind.table()))
+            .and_then(Item::as_table_like_mut)
+            .and_then(|t| t.get_mut(name))
+        else {
+            return false;
+        };
+        if spec.is_str() {
+            replace_value(spec, req.into());
+            return true;
+        }
+        match spec.as_table_like_mut() {
+            Some(table) => {
+                table.insert("version", value(req));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Declare `name = "<req>"` in the `kind` table, creating the table when missing.
+    ///
+    /// Returns `false` when `name` is already declared there. A table whose
+    /// entries were sorted stays sorted.
+    pub fn add_dependency(&mut self, kind: DependencyKind, name: &str, req: &str) -> bool {
+        let Some(table) = self
+            .doc
+            .entry(kind.table())
+            .or_insert(Item::Table(Table::new()))
+            .as_table_like_mut()
+        else {
+            return false;
+        };
+        if table.contains_key(name) {
+            return false;
+        }
+        let sorted = table.iter().map(|(key, _)| key).is_sorted();
+        table.insert(name, value(req));
+        if sorted {
+            table.sort_values();
+        }
+        true
+    }
+
+    /// Keys of the `kind` table entry of `name` (`version`, `features`, ...); empty for a plain requirement.
+    pub fn dependency_keys(&self, kind: DependencyKind, name: &str) -> Vec<String> {
+        self.doc
+            .get(kind.table())
+            .and_then(|t| t.get(name))
+            .and_then(Item::as_table_like)
+            .map(|spec| spec.iter().map(|(key, _)| key.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Names declared in `[workspace.dependencies]`.
+    pub fn workspace_dependency_names(&self) -> Vec<String> {
+        self.doc
+            .get("workspace")
+            .and_then(|w| w.get("dependencies"))
+            .and_then(Item::as_table_like)
+            .map(|t| t.iter().map(|(name, _)| name.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Declare `name = "<req>"` in `[workspace.dependencies]`, creating the table when missing.
+    ///
+    /// Returns `false` without `[workspace]` or when `name` is already declared.
+    /// A table whose entries were sorted stays sorted.
+    pub fn add_workspace_dependency(&mut self, name: &str, req: &str) -> bool {
+        let Some(workspace) = self
+            .doc
+            .get_mut("workspace")
+            .and_then(Item::as_table_like_mut)
+        else {
+            return false;
+        };
+        let Some(table) = workspace
+            .entry("dependencies")
+            .or_insert(Item::Table(Table::new()))
+            .as_table_like_mut()
+        else {
+            return false;
+        };
+        if table.contains_key(name) {
+            return false;
+        }
+        let sorted = table.iter().map(|(key, _)| key).is_sorted();
+        table.insert(name, value(req));
+        if sorted {
+            table.sort_values();
+        }
+        true
+    }
+
+    /// Make `name` of the `kind` table inherit from the workspace (`workspace = true`).
+    ///
+    /// The version requirement is dropped; other keys (`features`, `optional`)
+    /// are kept. Returns `false` when the dependency is not declared there.
+    pub fn inherit_dependency(&mut self, kind: DependencyKind, name: &str) -> bool {
+        let Some(spec) = self
+            .doc
+            .get_mut(kind.table())
+            .and_then(Item::as_table_like_mut)
+            .and_then(|t| t.get_mut(name))
+        else {
+            return false;
+        };
+        if let Some(table) = spec.as_table_mut() {
+            table.remove("version");
+            table.insert("workspace", value(true));
+            return true;
+        }
+        let mut inherited = InlineTable::new();
+        inherited.insert("workspace", true.into());
+        if let Some(old) = spec.as_inline_table() {
+            for (key, v) in old.iter().filter(|(key, _)| *key != "version") {
+                inherited.insert(key, v.clone());
+            }
+        } else if !spec.is_str() {
+            return false;
+        }
+        replace_value(spec, inherited.into());
+        true
+    }
+
+    /// Names of the features declared in `[features]` (sorted, `default` excluded).
+    pub fn feature_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .doc
+            .get("features")
+            .and_then(Item::as_table_like)
+            .map(|t| {
+                t.iter()
+                    .map(|(name, _)| name.to_string())
+                    .filter(|n| n != "default")
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    /// Entries of `features.default`.
+    pub fn default_features(&self) -> Vec<String> {
+        self.doc
+            .get("features")
+            .and_then(|f| f.get("default"))
+            .and_then(Item::as_array)
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// `(name, path)` of every dependency declared with `path = "..."` (paths as written),
+    /// including target-specific tables and `[workspace.dependencies]`.
+    pub fn path_dependencies(&self) -> Vec<(String, PathBuf)> {
+        let mut deps = Vec::new();
+        for table in DEPENDENCY_TABLES {
+            collect_path_dependencies(self.doc.get(table), &mut deps);
+        }
+        if let Some(targets) = self.doc.get("target").and_then(Item::as_table_like) {
+            for (_, target) in targets.iter() {
+                for table in DEPENDENCY_TABLES {
+                    collect_path_dependencies(target.get(table), &mut deps);
+                }
+            }
+        }
+        if let Some(workspace) = self.doc.get("workspace") {
+            collect_path_dependencies(workspace.get("dependencies"), &mut deps);
+        }
+        deps.sort();
+        deps.dedup();
+        deps
+    }
+
+    /// `(name, path)` of the path entries of `[patch.<registry>]`.
+    pub fn path_patches(&self, registry: &str) -> Vec<(String, PathBuf)> {
+        let mut out = Vec::new();
+        collect_path_dependencies(
+            self.doc.get("patch").and_then(|p| p.get(registry)),
+            &mut out,
+        );
+        out
+    }
+
+    /// Set `[patch.<registry>] <name> = { path = "<path>" }`, replacing any previous entry.
+    pub fn set_patch(&mut self, registry: &str, name: &str, path: &str) {
+        let patch = self.doc.entry("patch").or_insert_with(implicit_table);
+        let Some(patch) = patch.as_table_like_mut() else {
+            return;
+        };
+        let registry_table = patch.entry(registry).or_insert(Item::Table(Table::new()));
+        if let Some(table) = registry_table.as_table_like_mut() {
+            let mut spec = InlineTable::new();
+            spec.insert("path", path.into());
+            table.insert(name, value(spec));
+        }
+    }
+
+    /// Set `<lint> = "<level>"` in `[lints.<tool>]` (`[workspace.lints.<tool>]` without a package).
+    ///
+    /// Returns `false` when the package inherits its lints (`lints.workspace = true`).
+    pub fn set_lint(&mut self, tool: &str, lint: &str, level: &str) -> bool {
+        let lints = if self.doc.contains_key("package") {
+            let inherited = self
+                .doc
+                .get("lints")
+                .and_then(|l| l.get("workspace"))
+                .and_then(Item::as_bool);
+            if inherited == Some(true) {
+                return false;
+            }
+            self.doc.entry("lints").or_insert_with(implicit_table)
+        } else {
+            let workspace = self.doc.entry("workspace").or_insert_with(implicit_table);
+            let Some(workspace) = workspace.as_table_like_mut() else {
+                return false;
+            };
+            workspace.entry("lints").or_insert_with(implicit_table)
+        };
+        let Some(lints) = lints.as_table_like_mut() else {
+            return false;
+        };
+        let tool = lints.entry(tool).or_insert(Item::Table(Table::new()));
+        let Some(table) = tool.as_table_like_mut() else {
+            return false;
+        };
+        match table.get_mut(lint) {
+            Some(old) => replace_value(old, level.into()),
+            None => {
+                table.insert(lint, value(level));
+            }
+        }
+        true
+    }
+
+    /// Set `[package] name`, keeping its decoration. Returns `false` without a `[package]` name.
+    pub fn set_package_name(&mut self, name: &str) -> bool {
+        self.package_name().is_some() && self.set_package_value("name", name)
+    }
+
+    /// Remove `[patch.<registry>] <name>`, dropping tables left empty. Returns `true` if removed.
+    pub fn remove_patch(&mut self, registry: &str, name: &str) -> bool {
+        let Some(patch) = self.doc.get_mut("patch").and_then(Item::as_table_like_mut) else {
+            return false;
+        };
+        let Some(table) = patch.get_mut(registry).and_then(Item::as_table_like_mut) else {
+            return false;
+        };
+        let removed = table.remove(name).is_some();
+        if table.is_empty() {
+            patch.remove(registry);
+        }
+        if patch.is_empty() {
+            self.doc.remove("patch");
+        }
+        removed
+    }
+
+    /// Write the (possibly edited) manifest to `<project_dir>/Cargo.toml`.
+    ///
+    /// The file is replaced atomically (temporary file, then rename), so an
+    /// interrupted write never leaves a truncated manifest.
+    pub fn write(&self, project_dir: &Path) -> Result<(), ManifestError> {
+        let target = project_dir.join("Cargo.toml");
+        let tmp = project_dir.join("Cargo.toml.rustm-tmp");
+        fs::write(&tmp, self.doc.to_string())
+            .and_then(|()| fs::rename(&tmp, &target))
+            .map_err(|e| {
+                let _ = fs::remove_file(&tmp);
+                ManifestError::Io(e)
+            })
+    }
+
+    /// Read `<project_dir>/Cargo.toml`, apply `change`, and write it back when the document changed.
+    ///
+    /// Returns what `change` returned.
+    pub fn edit<T>(
+        project_dir: &Path,
+        change: impl FnOnce(&mut Self) -> T,
+    ) -> Result<T, ManifestError> {
+        let mut manifest = Self::read(project_dir)?;
+        let before = manifest.doc.to_string();
+        let out = change(&mut manifest);
+        if manifest.doc.to_string() != before {
+            manifest.write(project_dir)?;
+        }
+        Ok(out)
+    }
+
+    /// `workspace.members` patterns as written (e.g. `crates/*`).
+    pub fn workspace_members(&self) -> Vec<String> {
+        self.doc
+            .get("workspace")
+            .and_then(|w| w.get("members"))
+            .and_then(Item::as_array)
+            .map(|members| {
+                members
+                    .iter()
+                    .filter_map(|m| m.as_str())
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Split a single-crate manifest for moving the crate into `member_dir` (relative, `/`-separated).
+    ///
+    /// Returns `(root, member)`. `root` is a virtual manifest listing `member_dir`, with the
+    /// tables cargo only honours at the root (`[patch]`, `[profile]`, `[replace]`) moved into it
+    /// unchanged. `member` is this manifest with every relative path that does not move along
+    /// with the crate (`moves(path)` is `false`) rebased onto the new location.
+    pub fn split_into_workspace(
+        mut self,
+        member_dir: &str,
+        moves: impl Fn(&str) -> bool,
+    ) -> (Self, Self) {
+        let up = "../".repeat(member_dir.split('/').filter(|s| !s.is_empty()).count());
+        let rebase = |item: Option<&mut Item>| {
+            let Some(v) = item.and_then(Item::as_value_mut) else {
+                return;
+            };
+            let Some(path) = v.as_str() else {
+                return;
+            };
+            if Path::new(path).is_absolute() || moves(path) {
+                return;
+            }
+            let decor = v.decor().clone();
+            *v = format!("{up}{path}").into();
+            *v.decor_mut() = decor;
+        };
+
+        if let Some(package) = self
+            .doc
+            .get_mut("package")
+            .and_then(Item::as_table_like_mut)
+        {
+            for key in PACKAGE_PATH_KEYS {
+                rebase(package.get_mut(key));
+            }
+        }
+        for section in TARGET_SECTIONS {
+            match self.doc.get_mut(section) {
+                Some(Item::Table(t)) => rebase(t.get_mut("path")),
+                Some(Item::ArrayOfTables(tables)) => {
+                    for t in tables.iter_mut() {
+                        rebase(t.get_mut("path"));
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut dependency_tables: Vec<&mut Item> = Vec::new();
+        for (key, item) in self.doc.iter_mut() {
+            if DEPENDENCY_TABLES.contains(&key.get()) {
+                dependency_tables.push(item);
+            } else if key.get() == "target"
+                && let Some(targets) = item.as_table_like_mut()
+            {
+                for (_, platform) in targets.iter_mut() {
+                    let Some(platform) = platform.as_table_like_mut() else {
+                        continue;
+                    };
+                    for (key, item) in platform.iter_mut() {
+                        if DEPENDENCY_TABLES.contains(&key.get()) {
+                            dependency_tables.push(item);
+                        }
+                    }
+                }
+            }
+        }
+        for deps in dependency_tables {
+            if let Some(deps) = deps.as_table_like_mut() {
+                for (_, spec) in deps.iter_mut() {
+                    if let Some(spec) = spec.as_table_like_mut() {
+                        rebase(spec.get_mut("path"));
+                    }
+                }
+            }
+        }
+
+        let package = self
+            .doc
+            .get_mut("package")
+            .and_then(Item::as_table_like_mut);
+        let explicit_resolver = package.and_then(|p| p.remove("resolver"));
+        let resolver = explicit_resolver
+            .as_ref()
+            .and_then(Item::as_str)
+            .map(ToString::to_string)
+            .unwrap_or_else(|| {
+                match self.package_str("edition") {
+                    Some("2024") => "3",
+                    Some("2021") => "2",
+                    _ => "1",
+                }
+                .to_string()
+            });
+
+        let mut root = Self {
+            doc: DocumentMut::new(),
+        };
+        let mut workspace = Table::new();
+        workspace.insert("resolver", value(resolver));
+        let mut members = Array::new();
+        members.push(member_dir);
+        workspace.insert("members", value(members));
+        root.doc.insert("workspace", Item::Table(workspace));
+        for table in ROOT_ONLY_TABLES {
+            if let Some(item) = self.doc.remove(table) {
+                root.doc.insert(table, item);
+            }
+        }
+        (root, self)
+    }
+
+    /// `true` when `member` (relative to the workspace root) matches a `workspace.members` pattern.
+    pub fn has_workspace_member(&self, member: &str) -> bool {
+        let member = member.trim_start_matches("./").trim_end_matches('/');
+        self.workspace_members().iter().any(|pattern| {
+            glob_matches(
+                pattern.trim_start_matches("./").trim_end_matches('/'),
+                member,
+            )
+        })
+    }
+
+    /// Append `member` to `workspace.members` unless a pattern already covers it.
+    ///
+    /// Returns `true` when the manifest changed.
+    pub fn add_workspace_member(&mut self, member: &str) -> bool {
+        if self.has_workspace_member(member) {
+            return false;
+        }
+        let workspace = self
+            .doc
+            .entry("workspace")
+            .or_insert(Item::Table(Table::new()));
+        let Some(workspace) = workspace.as_table_like_mut() else {
+            return false;
+        };
+        let members = workspace.entry("members").or_insert(value(Array::new()));
+        let Some(members) = members.as_array_mut() else {
+            return false;
+        };
+        members.push(member);
+        true
+    }
+}
+
+/// Path glob as used by `workspace.members`: `*` and `?` within a path segment.
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    let patterns: Vec<&str> = pattern.split('/').collect();
+    let segments: Vec<&str> = path.split('/').collect();
+    patterns.len() == segments.len()
+        && patterns
+            .iter()
+            .zip(&segments)
+            .all(|(p, s)| segment_matches(p.as_bytes(), s.as_bytes()))
+}
+
+fn segment_matches(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.split_first(), text.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            segment_matches(rest, text)
+                || (!text.is_empty() && segment_matches(pattern, &text[1..]))
+        }
+        (Some((b'?', rest)), Some((_, text_rest))) => segment_matches(rest, text_rest),
+        (Some((p, rest)), Some((t, text_rest))) if p == t => segment_matches(rest, text_rest),
+        _ => false,
+    }
+}
+
+/// Read `build.target` from `<project_dir>/.cargo/config.toml` (or legacy `.cargo/config`).
+///
+/// Missing or unparsable files are treated as "no explicit target".
+pub fn configured_build_target(project_dir: &Path) -> Option<String> {
+    let cargo_dir = project_dir.join(".cargo");
+    let raw = fs::read_to_string(cargo_dir.join("config.toml"))
+        .or_else(|_| fs::read_to_string(cargo_dir.join("config")))
+        .ok()?;
+    let doc = raw.parse::<DocumentMut>().ok()?;
+    doc.get("build")?
+        .get("target")?
+        .as_str()
+        .map(ToString::to_string)
+}
+
+/// Dependencies declared in the dependency tables of `parent` (the document or a `[target.<target>]` table).
+fn collect_declared_dependencies(
+    parent: &Item,
+    target: Option<&str>,
+    out: &mut Vec<DeclaredDependency>,
+) {
+    for kind in DependencyKind::ALL {
+        let Some(deps) = parent.get(kind.table()).and_then(Item::as_table_like) else {
+            continue;
+        };
+        for (name, spec) in deps.iter() {
+            let field = |key: &str| {
+                spec.get(key)
+                    .and_then(Item::as_str)
+                    .map(ToString::to_string)
+            };
+            out.push(DeclaredDependency {
+                name: name.to_string(),
+                kind,
+                target: target.map(ToString::to_string),
+                req: spec
+                    .as_str()
+                    .map(ToString::to_string)
+                    .or_else(|| field("version")),
+                source: field("path").or_else(|| field("git")),
+                inherited: spec
+                    .get("workspace")
+                    .and_then(Item::as_bool)
+                    .unwrap_or(false),
+            });
+        }
+    }
+}
+
+fn collect_dependency_names(parent: &Item, out: &mut Vec<String>) {
+    for table in DEPENDENCY_TABLES {
+        if let Some(deps) = parent.get(table).and_then(Item::as_table_like) {
+            out.extend(deps.iter().map(|(name, _)| name.to_string()));
+        }
+    }
+}
+
+/// Parent table that is not printed as a `[header]` of its own (`[patch.crates-io]` only).
+/// Replace the value of `item` by `new`, keeping the decoration (comments, spacing) of the old one.
+fn replace_value(item: &mut Item, mut new: toml_edit::Value) {
+    if let Some(old) = item.as_value() {
+        *new.decor_mut() = old.decor().clone();
+    }
+    *item = value(new);
+}
+
+fn implicit_table() -> Item {
+    let mut table = Table::new();
+    table.set_implicit(true);
+    Item::Table(table)
+}
+
+fn collect_path_dependencies(table: Option<&Item>, out: &mut Vec<(String, PathBuf)>) {
+    let Some(deps) = table.and_then(Item::as_table_like) else {
+        return;
+    };
+    for (name, spec) in deps.iter() {
+        if let Some(path) = spec.get("path").and_then(Item::as_str) {
+            out.push((name.to_string(), PathBuf::from(path)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn collects_dependencies_from_all_tables() {
+        let m = Manifest::parse(
+            r#"
+[package]
+name = "demo"
+
+[dependencies]
+serde = "1"
+
+[dev-dependencies]
+tempfile = "3"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+sys = { path = "../sys" }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            m.dependency_names(),
+            vec!["libc", "serde", "sys", "tempfile"]
+        );
+        assert_eq!(
+            m.path_dependencies(),
+            vec![("sys".to_string(), PathBuf::from("../sys"))]
+        );
+        assert!(!m.is_workspace());
+        assert_eq!(m.package_name(), Some("demo"));
+    }
+
+    #[test]
+    fn reads_explicit_bin_targets() {
+        let m = Manifest::parse(
+            "[package]\nname = 'tool'\nversion = '1.2.3'\n[[bin]]\nname = 'a'\n[[bin]]\nname = 'b'\npath = 'src/b.rs'\n",
+        )
+        .unwrap();
+        assert_eq!(m.bin_target_names(), vec!["a", "b"]);
+        assert_eq!(m.package_version(), Some("1.2.3"));
+    }
+
+    #[test]
+    fn lists_and_bumps_declared_dependencies() {
+        let mut m = Manifest::parse(
+            "[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] }\nlog = \"0.4\" # logging\nlocal = { path = \"../local\" }\n\n[dev-dependencies]\nshared = { workspace = true }\n\n[target.'cfg(unix)'.dependencies]\nlibc = \"0.2\"\n",
+        )
+        .unwrap();
+
+        let deps = m.declared_dependencies();
+        assert_eq!(deps.len(), 5);
+        assert_eq!(deps[1].req.as_deref(), Some("0.4"));
+        assert_eq!(deps[2].source.as_deref(), Some("../local"));
+        assert!(deps[3].inherited && deps[3].kind == DependencyKind::Dev);
+        assert_eq!(deps[4].target.as_deref(), Some("cfg(unix)"));
+
+        assert!(m.set_dependency_version(DependencyKind::Normal, None, "serde", "1.0.228"));
+        assert!(m.set_dependency_version(DependencyKind::Normal, None, "log", "0.4.28"));
+        assert!(!m.set_dependency_version(DependencyKind::Build, None, "log", "1"));
+        assert!(!m.set_dependency_version(DependencyKind::Normal, None, "libc", "0.2.177"));
+        assert!(m.set_dependency_version(
+            DependencyKind::Normal,
+            Some("cfg(unix)"),
+            "libc",
+            "0.2.177"
+        ));
+        let out = m.doc.to_string();
+        assert!(out.contains("serde = { version = \"1.0.228\", features = [\"derive\"] }"));
+        assert!(out.contains("log = \"0.4.28\" # logging"));
+        assert!(out.contains("libc = \"0.2.177\""));
+    }
+
+    #[test]
+    fn adds_workspace_members_not_covered_by_a_pattern() {
+        let mut m =
+            Manifest::parse("[workspace]\nmembers = [\"crates/*\", \"tools/gen\"]\n").unwrap();
+        assert!(m.has_workspace_member("crates/core"));
+        assert!(m.has_workspace_member("./tools/gen/"));
+        assert!(!m.add_workspace_member("crates/core"));
+
+        assert!(m.add_workspace_member("cli"));
+        assert_eq!(m.workspace_members(), vec!["crates/*", "tools/gen", "cli"]);
+        assert!(!m.has_workspace_member("crates/core/nested"));
+    }
+
+    #[test]
+    fn bumps_the_package_version_keeping_comments() {
+        let mut m = Manifest::parse(
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\" # release-please\nedition.workspace = true\n",
+        )
+        .unwrap();
+        assert!(m.set_package_value("version", "0.2.0"));
+        assert!(!m.set_package_value("edition", "2024"));
+        assert_eq!(m.package_version(), Some("0.2.0"));
+        assert_eq!(
+            m.doc.to_string(),
+            "[package]\nname = \"demo\"\nversion = \"0.2.0\" # release-please\nedition.workspace = true\n"
+        );
+        assert!(
+            !Manifest::parse("[workspace]\n")
+                .unwrap()
+                .set_package_value("version", "1.0.0")
+        );
+    }
+
+    #[test]
+    fn adds_dependencies_in_order() {
+        let mut m = Manifest::parse(
+            "[package]\nname = \"demo\"\n\n[dependencies]\n# serialization\nlog = \"0.4\"\nserde = \"1\"\n",
+        )
+        .unwrap();
+        assert!(m.add_dependency(DependencyKind::Normal, "anyhow", "1"));
+        assert!(!m.add_dependency(DependencyKind::Normal, "serde", "2"));
+        assert!(m.add_dependency(DependencyKind::Dev, "criterion", "0.5"));
+        assert_eq!(
+            m.doc.to_string(),
+            "[package]\nname = \"demo\"\n\n[dependencies]\nanyhow = \"1\"\n# serialization\nlog = \"0.4\"\nserde = \"1\"\n\n[dev-dependencies]\ncriterion = \"0.5\"\n"
+        );
+
+        let mut unsorted =
+            Manifest::parse("[dependencies]\nserde = \"1\"\nlog = \"0.4\"\n").unwrap();
+        assert!(unsorted.add_dependency(DependencyKind::Normal, "anyhow", "1"));
+        assert_eq!(unsorted.dependency_names(), vec!["anyhow", "log", "serde"]);
+        assert!(
+            unsorted
+                .doc
+                .to_string()
+                .ends_with("log = \"0.4\"\nanyhow = \"1\"\n")
+        );
+    }
+
+    #[test]
+    fn edits_metadata_and_writes_only_changes() {
+        let dir = TempDir::new("manifest");
+        let raw = "[package]\nname = \"demo\" # crate name\nkeywords = [\"a\"]\nhomepage = \"https://x\"\n";
+        fs::write(dir.join("Cargo.toml"), raw).unwrap();
+
+        let set = Manifest::edit(&dir, |m| {
+            m.set_package_value("description", "Demo crate")
+                && m.set_package_value("keywords", ["cli", "tui"].into_iter().collect::<Array>())
+                && m.remove_package_key("homepage")
+        })
+        .unwrap();
+        assert!(set);
+        assert_eq!(
+            fs::read_to_string(dir.join("Cargo.toml")).unwrap(),
+            "[package]\nname = \"demo\" # crate name\nkeywords = [\"cli\", \"tui\"]\ndescription = \"Demo crate\"\n"
+        );
+        assert!(!dir.join("Cargo.toml.rustm-tmp").exists());
+
+        let before = fs::metadata(dir.join("Cargo.toml"))
+            .unwrap()
+            .modified()
+            .unwrap();
+        let renamed = Manifest::edit(&dir, |m| m.set_package_name("demo")).unwrap();
+        assert!(renamed);
+        let after = fs::metadata(dir.join("Cargo.toml"))
+            .unwrap()
+            .modified()
+            .unwrap();
+        assert_eq!(before, after);
+        assert!(Manifest::edit(&dir.join("missing"), |_| ()).is_err());
+    }
+
+    #[test]
+    fn sets_lints_of_packages_and_workspaces() {
+        let mut m = Manifest::parse("[package]\nname = \"demo\"\n").unwrap();
+        assert!(m.set_lint("clippy", "pedantic", "warn"));
+        assert!(m.set_lint("rust", "unsafe_code", "forbid"));
+        assert!(m.set_lint("clippy", "pedantic", "deny"));
+        assert_eq!(
+            m.doc.to_string(),
+            "[package]\nname = \"demo\"\n\n[lints.clippy]\npedantic = \"deny\"\n\n[lints.rust]\nunsafe_code = \"forbid\"\n"
+        );
+
+        let mut m =
+            Manifest::parse("[package]\nname = \"demo\"\n\n[lints]\nworkspace = true\n").unwrap();
+        assert!(!m.set_lint("clippy", "pedantic", "warn"));
+
+        let mut m = Manifest::parse("[workspace]\nmembers = [\"a\"]\n").unwrap();
+        assert!(m.set_lint("rust", "unsafe_code", "forbid"));
+        assert!(
+            m.doc
+                .to_string()
+                .contains("[workspace.lints.rust]\nunsafe_code = \"forbid\"")
+        );
+    }
+
+    #[test]
+    fn hoists_dependencies_to_the_workspace() {
+        let mut root = Manifest::parse("[workspace]\nmembers = [\"a\"]\n").unwrap();
+        assert!(root.add_workspace_dependency("serde", "1.0"));
+        assert!(!root.add_workspace_dependency("serde", "1.2"));
+        assert_eq!(root.workspace_dependency_names(), ["serde"]);
+
+        let mut member = Manifest::parse(
+            "[package]\nname = \"a\"\n\n[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] } # derive\nlog = \"0.4\"\n\n[dev-dependencies.tokio]\nversion = \"1\"\nfeatures = [\"macros\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            member.dependency_keys(DependencyKind::Normal, "serde"),
+            ["version", "features"]
+        );
+        assert!(member.inherit_dependency(DependencyKind::Normal, "serde"));
+        assert!(member.inherit_dependency(DependencyKind::Normal, "log"));
+        assert!(member.inherit_dependency(DependencyKind::Dev, "tokio"));
+        assert!(!member.inherit_dependency(DependencyKind::Build, "log"));
+        assert_eq!(
+            member.doc.to_string(),
+            "[package]\nname = \"a\"\n\n[dependencies]\nserde = { workspace = true, features = [\"derive\"] } # derive\nlog = { workspace = true }\n\n[dev-dependencies.tokio]\nfeatures = [\"macros\"]\nworkspace = true\n"
+        );
+    }
+}
@@ -0,0 +1,302 @@
+//! Minimal-versions check.
+//!
+//! Version requirements in `Cargo.toml` are lower bounds, but builds always
+//! resolve to the newest matching versions, so a requirement that is too low
+//! (`serde = "1.0"` while code uses an API added in 1.0.100) goes unnoticed
+//! until a downstream user resolves an older version. The check copies the
+//! project to a temporary directory (`target` and `.git` left out, so the
+//! project's own `Cargo.lock` is untouched), resolves every dependency to the
+//! lowest version its requirement allows with
+//! `cargo +nightly update -Z minimal-versions`, then runs `cargo check`.
+//!
+//! The report lists the version each direct dependency resolved to and the
+//! requirements blamed for a failure: dependencies whose own build failed, or
+//! that cargo named while resolving. When only the project itself fails to
+//! build, the culprit is a requirement below a version whose API it uses; the
+//! compiler errors tell which one.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use toml_edit::DocumentMut;
+
+use crate::cargo_ops::{self, CargoError};
+use crate::project::manifest::{Manifest, ManifestError};
+
+/// Directories not copied to the temporary clone.
+const SKIPPED_DIRS: [&str; 2] = ["target", ".git"];
+
+/// Lines of cargo output kept in the report.
+const OUTPUT_TAIL: usize = 60;
+
+#[derive(Debug)]
+pub enum MinimalVersionsError {
+    Io(io::Error),
+    Manifest(ManifestError),
+    /// No nightly toolchain: `-Z minimal-versions` is unstable.
+    NightlyMissing,
+    Cargo(CargoError),
+}
+
+impl fmt::Display for MinimalVersionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Cannot copy the project: {e}"),
+            Self::Manifest(e) => write!(f, "{e}"),
+            Self::NightlyMissing => write!(
+                f,
+                "A nightly toolchain is needed (rustup toolchain install nightly)"
+            ),
+            Self::Cargo(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for MinimalVersionsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Manifest(e) => Some(e),
+            Self::Cargo(e) => Some(e),
+            Self::NightlyMissing => None,
+        }
+    }
+}
+
+impl From<io::Error> for MinimalVersionsError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ManifestError> for MinimalVersionsError {
+    fn from(e: ManifestError) -> Self {
+        Self::Manifest(e)
+    }
+}
+
+/// A direct dependency and what its requirement resolved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolved {
+    pub name: String,
+    /// Requirement from `Cargo.toml` (`None` for path / git / inherited ones).
+    pub req: Option<String>,
+    /// Versions in the minimal lockfile (several when the graph needs more than one).
+    pub versions: Vec<String>,
+}
+
+/// Outcome of the check.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// Resolution and `cargo check` both succeeded.
+    pub passed: bool,
+    /// Direct dependencies, sorted by name (empty when the resolution failed).
+    pub resolved: Vec<Resolved>,
+    /// `name = "req"` of the direct dependencies blamed for the failure.
+    pub offending: Vec<String>,
+    /// End of the failing command's output (empty on success).
+    pub output: String,
+}
+
+/// Run the check for the project at `path`; `log` gets each step.
+pub fn check(path: &Path, log: &dyn Fn(String)) -> Result<Report, MinimalVersionsError> {
+    let manifest = Manifest::read(path)?;
+    let clone = clone_dir(path);
+    log(format!("Copying the project to {}", clone.display()));
+    copy_tree(path, &clone)?;
+    let result = check_clone(&clone, &manifest, log);
+    if let Err(e) = fs::remove_dir_all(&clone) {
+        log(format!("Cannot remove {}: {e}", clone.display()));
+    }
+    result
+}
+
+fn check_clone(
+    clone: &Path,
+    manifest: &Manifest,
+    log: &dyn Fn(String),
+) -> Result<Report, MinimalVersionsError> {
+    let declared: Vec<(String, Option<String>)> = manifest
+        .declared_dependencies()
+        .into_iter()
+        .map(|d| (d.name, d.req))
+        .collect();
+
+    log("cargo +nightly update -Z minimal-versions".to_string());
+    match cargo_ops::run(clone, &["+nightly", "update", "-Z", "minimal-versions"]) {
+        Ok(_) => {}
+        Err(CargoError::Failed { stderr, .. }) if nightly_missing(&stderr) => {
+            return Err(MinimalVersionsError::NightlyMissing);
+        }
+        Err(CargoError::Failed { stderr, .. }) => {
+            return Ok(Report {
+                passed: false,
+                resolved: Vec::new(),
+                offending: offending(&declared, &stderr),
+                output: tail(&stderr),
+            });
+        }
+        Err(e) => return Err(MinimalVersionsError::Cargo(e)),
+    }
+    let lockfile = fs::read_to_string(clone.join("Cargo.lock")).unwrap_or_default();
+    let resolved = resolve(&declared, &lockfile);
+
+    log("cargo check".to_string());
+    match cargo_ops::run(clone, &["check"]) {
+        Ok(_) => Ok(Report {
+            passed: true,
+            resolved,
+            offending: Vec::new(),
+            output: String::new(),
+        }),
+        Err(CargoError::Failed { stderr, .. }) => Ok(Report {
+            passed: false,
+            resolved,
+            offending: offending(&declared, &stderr),
+            output: tail(&stderr),
+        }),
+        Err(e) => Err(MinimalVersionsError::Cargo(e)),
+    }
+}
+
+/// Versions of the `declared` dependencies in `lockfile`, sorted by name.
+fn resolve(declared: &[(String, Option<String>)], lockfile: &str) -> Vec<Resolved> {
+    let mut locked: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    if let Ok(doc) = lockfile.parse::<DocumentMut>()
+        && let Some(packages) = doc.get("package").and_then(|p| p.as_array_of_tables())
+    {
+        for package in packages {
+            if let (Some(name), Some(version)) = (
+                package.get("name").and_then(|v| v.as_str()),
+                package.get("version").and_then(|v| v.as_str()),
+            ) {
+                locked
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(version.to_string());
+            }
+        }
+    }
+    let mut resolved: Vec<Resolved> = declared
+        .iter()
+        .map(|(name, req)| Resolved {
+            name: name.clone(),
+            req: req.clone(),
+            versions: locked.get(name).cloned().unwrap_or_default(),
+        })
+        .collect();
+    resolved.sort_by(|a, b| a.name.cmp(&b.name));
+    resolved.dedup_by(|a, b| a.name == b.name);
+    resolved
+}
+
+/// `name = "req"` of the declared dependencies named by cargo in `output`.
+///
+/// Matches `could not compile `name`` (their build failed) and the
+/// `` `name` `` / `` `name = "req"` `` mentions of resolution errors.
+fn offending(declared: &[(String, Option<String>)], output: &str) -> Vec<String> {
+    let mut offending: Vec<String> = declared
+        .iter()
+        .filter(|(name, _)| {
+            output.contains(&format!("could not compile `{name}`"))
+                || output.contains(&format!("requirement `{name} "))
+                || output.contains(&format!("for `{name}`"))
+        })
+        .map(|(name, req)| match req {
+            Some(req) => format!("{name} = \"{req}\""),
+            None => name.clone(),
+        })
+        .collect();
+    offending.sort();
+    offending.dedup();
+    offending
+}
+
+fn nightly_missing(stderr: &str) -> bool {
+    stderr.contains("toolchain 'nightly")
+        || stderr.contains("no such command: `+nightly`")
+        || stderr.contains("the `-Z` flag is only accepted on the nightly channel")
+}
+
+/// Last `OUTPUT_TAIL` lines of `output`.
+fn tail(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    lines[lines.len().saturating_sub(OUTPUT_TAIL)..].join("\n")
+}
+
+fn clone_dir(path: &Path) -> PathBuf {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let name = path
+        .file_name()
+        .map_or_else(|| "project".into(), |n| n.to_string_lossy());
+    std::env::temp_dir().join(format!("rustm-minimal-versions-{name}-{nonce}"))
+}
+
+/// Copy `from` into `to`, leaving out `SKIPPED_DIRS` and symbolic links.
+fn copy_tree(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = to.join(entry.file_name());
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.iter().any(|d| entry.file_name() == *d) {
+                copy_tree(&entry.path(), &target)?;
+            }
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn reports_resolved_versions_and_offenders() {
+        let declared = vec![
+            ("serde".to_string(), Some("1.0".to_string())),
+            ("log".to_string(), Some("0.4".to_string())),
+            ("local".to_string(), None),
+        ];
+        let lockfile = "version = 4\n\n[[package]]\nname = \"log\"\nversion = \"0.4.0\"\n\n[[package]]\nname = \"serde\"\nversion = \"1.0.0\"\n";
+        let resolved = resolve(&declared, lockfile);
+        assert_eq!(
+            resolved.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(),
+            ["local", "log", "serde"]
+        );
+        assert_eq!(resolved[2].versions, ["1.0.0"]);
+        assert!(resolved[0].versions.is_empty());
+
+        let output = "   Compiling log v0.4.0\nerror[E0425]: cannot find function\nerror: could not compile `log` (lib) due to 1 previous error\n";
+        assert_eq!(offending(&declared, output), ["log = \"0.4\""]);
+        assert!(offending(&declared, "error: could not compile `demo`").is_empty());
+    }
+
+    #[test]
+    fn copies_the_tree_without_target_and_git() {
+        let from = TempDir::new("minimal_versions");
+        for dir in ["src", "target/debug", ".git"] {
+            fs::create_dir_all(from.join(dir)).unwrap();
+        }
+        fs::write(from.join("Cargo.toml"), "[package]\n").unwrap();
+        fs::write(from.join("src/lib.rs"), "").unwrap();
+        fs::write(from.join("target/debug/big"), "").unwrap();
+
+        let to = TempDir::new("minimal_versions");
+        copy_tree(&from, &to).unwrap();
+        assert!(to.join("Cargo.toml").is_file());
+        assert!(to.join("src/lib.rs").is_file());
+        assert!(!to.join("target").exists());
+        assert!(!to.join(".git").exists());
+    }
+}
@@ -0,0 +1,166 @@
+//! no_std compatibility probe.
+//!
+//! A library is usable on bare-metal targets (and by `#![no_std]` crates)
+//! when it builds without `std`. Reading `#![no_std]` in `lib.rs` is not
+//! enough: a default feature or a dependency can still pull `std` in. The
+//! probe builds the library for a target that has no `std` at all
+//! (`thumbv7m-none-eabi` unless `no_std_target` says otherwise):
+//! `cargo check --lib --no-default-features --target <target>`. Anything
+//! needing `std` fails with "can't find crate for `std`", naming the crate
+//! that asked for it.
+//!
+//! The verdict is remembered in the metadata cache and shown as a badge in the
+//! project list and the project detail.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cargo_ops::{self, CargoError};
+use crate::project::manifest::{Manifest, ManifestError};
+
+/// Verdict of the last probe of a project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoStdProbe {
+    /// Target the library was built for.
+    pub target: String,
+    pub compatible: bool,
+}
+
+impl NoStdProbe {
+    /// `compatible (thumbv7m-none-eabi)` / `needs std (thumbv7m-none-eabi)`.
+    pub fn summary(&self) -> String {
+        let verdict = if self.compatible {
+            "compatible"
+        } else {
+            "needs std"
+        };
+        format!("{verdict} ({})", self.target)
+    }
+}
+
+/// What the probe found.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub probe: NoStdProbe,
+    /// Crates whose build failed (the project itself or a dependency needing `std`).
+    pub blocking: Vec<String>,
+    /// cargo's error output (empty when compatible).
+    pub output: String,
+}
+
+#[derive(Debug)]
+pub enum NoStdError {
+    /// Only library targets are probed.
+    NotLibrary,
+    Manifest(ManifestError),
+    /// The target's `core` is not installed.
+    TargetMissing(String),
+    Cargo(CargoError),
+}
+
+impl fmt::Display for NoStdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotLibrary => write!(f, "The project has no library target"),
+            Self::Manifest(e) => write!(f, "{e}"),
+            Self::TargetMissing(target) => write!(
+                f,
+                "The {target} target is not installed (rustup target add {target})"
+            ),
+            Self::Cargo(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for NoStdError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Manifest(e) => Some(e),
+            Self::Cargo(e) => Some(e),
+            Self::NotLibrary | Self::TargetMissing(_) => None,
+        }
+    }
+}
+
+impl From<ManifestError> for NoStdError {
+    fn from(e: ManifestError) -> Self {
+        Self::Manifest(e)
+    }
+}
+
+/// Does the project at `path` have a library target (workspaces excluded)?
+pub fn is_library(path: &Path, manifest: &Manifest) -> bool {
+    !manifest.is_workspace() && (path.join("src/lib.rs").is_file() || manifest.has_lib_section())
+}
+
+/// Probe the library at `path` for `target`; `log` gets the command.
+pub fn probe(path: &Path, target: &str, log: &dyn Fn(String)) -> Result<Outcome, NoStdError> {
+    let manifest = Manifest::read(path)?;
+    if !is_library(path, &manifest) {
+        return Err(NoStdError::NotLibrary);
+    }
+    let args = [
+        "check",
+        "--lib",
+        "--no-default-features",
+        "--target",
+        target,
+    ];
+    log(format!("cargo {}", args.join(" ")));
+    match cargo_ops::run(path, &args) {
+        Ok(_) => Ok(Outcome {
+            probe: NoStdProbe {
+                target: target.to_string(),
+                compatible: true,
+            },
+            blocking: Vec::new(),
+            output: String::new(),
+        }),
+        Err(CargoError::Failed { stderr, .. }) => failed(target, &stderr),
+        Err(e) => Err(NoStdError::Cargo(e)),
+    }
+}
+
+/// Outcome of a failed build: incompatible, unless the target itself is missing.
+fn failed(target: &str, stderr: &str) -> Result<Outcome, NoStdError> {
+    if stderr.contains("can't find crate for `core`") || stderr.contains("may not be installed") {
+        return Err(NoStdError::TargetMissing(target.to_string()));
+    }
+    let mut blocking: Vec<String> = stderr
+        .lines()
+        .filter_map(|l| l.split("could not compile `").nth(1))
+        .filter_map(|rest| rest.split('`').next())
+        .map(ToString::to_string)
+        .collect();
+    blocking.dedup();
+    Ok(Outcome {
+        probe: NoStdProbe {
+            target: target.to_string(),
+            compatible: false,
+        },
+        blocking,
+        output: stderr.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_build_failures() {
+        let missing = "error[E0463]: can't find crate for `core`\n  = note: the `thumbv7m-none-eabi` target may not be installed\n";
+        assert!(matches!(
+            failed("thumbv7m-none-eabi", missing),
+            Err(NoStdError::TargetMissing(_))
+        ));
+
+        let needs_std = "error[E0463]: can't find crate for `std`\n  = note: the `thumbv7m-none-eabi` target may not support the standard library\nerror: could not compile `memchr` (lib) due to 1 previous error\n";
+        let outcome = failed("thumbv7m-none-eabi", needs_std).unwrap();
+        assert!(!outcome.probe.compatible);
+        assert_eq!(outcome.blocking, ["memchr"]);
+        assert_eq!(outcome.probe.summary(), "needs std (thumbv7m-none-eabi)");
+    }
+}
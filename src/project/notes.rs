@@ -0,0 +1,92 @@
+//! Project notes and README preview.
+//!
+//! Quick notes live in a `NOTES.md` file at the project root, written by the
+//! notes editor of the detail dialog (an empty note removes the file). The
+//! preview shows the first lines of the notes when there are some, else of
+//! `README.md`, lightly rendered: heading markers are dropped, badge and
+//! image lines skipped.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// File holding the notes, at the project root.
+pub const NOTES_FILE: &str = "NOTES.md";
+
+const README_FILE: &str = "README.md";
+
+/// Content of the notes of the project at `path`; empty when there are none.
+pub fn read(path: &Path) -> io::Result<String> {
+    match fs::read_to_string(path.join(NOTES_FILE)) {
+        Ok(text) => Ok(text),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Replace the notes of the project at `path`; blank `text` removes the file.
+pub fn save(path: &Path, text: &str) -> io::Result<()> {
+    let file = path.join(NOTES_FILE);
+    if text.trim().is_empty() {
+        return match fs::remove_file(&file) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    let mut text = text.trim_end().to_string();
+    text.push('\n');
+    fs::write(file, text)
+}
+
+/// File name and first `lines` rendered lines of the notes, else of the README.
+pub fn preview(path: &Path, lines: usize) -> Option<(&'static str, String)> {
+    [NOTES_FILE, README_FILE].into_iter().find_map(|name| {
+        let text = fs::read_to_string(path.join(name)).ok()?;
+        let rendered = render(&text, lines);
+        (!rendered.is_empty()).then_some((name, rendered))
+    })
+}
+
+/// First `lines` lines of markdown `text`, without heading markers, badges and images.
+fn render(text: &str, lines: usize) -> String {
+    text.lines()
+        .map(str::trim_end)
+        .filter(|line| !line.starts_with("[![") && !line.starts_with("!["))
+        .map(|line| line.trim_start_matches('#').trim_start())
+        .skip_while(|line| line.is_empty())
+        .take(lines)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn notes_take_precedence_over_readme() {
+        let dir = TempDir::new("notes");
+        assert!(preview(&dir, 3).is_none());
+
+        fs::write(
+            dir.join(README_FILE),
+            "[![CI](badge.svg)](ci)\n\n# demo\n\nA demo.\n\n## Usage\nRun it.\n",
+        )
+        .unwrap();
+        assert_eq!(
+            preview(&dir, 3),
+            Some((README_FILE, "demo\n\nA demo.".to_string()))
+        );
+
+        save(&dir, "todo: release\n\n").unwrap();
+        assert_eq!(read(&dir).unwrap(), "todo: release\n");
+        assert_eq!(preview(&dir, 3), Some((NOTES_FILE, "todo: release".into())));
+
+        save(&dir, "  \n").unwrap();
+        assert!(!dir.join(NOTES_FILE).exists());
+        assert_eq!(preview(&dir, 1).map(|(name, _)| name), Some(README_FILE));
+    }
+}
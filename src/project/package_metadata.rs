@@ -0,0 +1,432 @@
+//! crates.io-facing package metadata.
+//!
+//! The `[package]` keys shown on a crate's crates.io page: `description`,
+//! `keywords`, `categories`, `repository` and `homepage`. Values are edited
+//! as text (lists comma-separated) and checked against what crates.io accepts
+//! before publishing: at most 5 keywords of at most 20 characters, at most 5
+//! categories among the known category slugs, http(s) URLs. Only the keys
+//! that changed are written back, through `Manifest`, so formatting and
+//! comments are kept; keys inherited from the workspace are left alone.
+
+use std::fmt;
+use std::path::Path;
+
+use toml_edit::Array;
+
+use crate::project::manifest::{Manifest, ManifestError};
+
+/// Most keywords crates.io accepts.
+const MAX_KEYWORDS: usize = 5;
+
+/// Longest keyword crates.io accepts.
+const MAX_KEYWORD_LEN: usize = 20;
+
+/// Most categories crates.io accepts.
+const MAX_CATEGORIES: usize = 5;
+
+/// Category slugs known to crates.io (<https://crates.io/category_slugs>).
+pub const CATEGORIES: [&str; 90] = [
+    "accessibility",
+    "aerospace",
+    "aerospace::drones",
+    "aerospace::protocols",
+    "aerospace::simulation",
+    "aerospace::space-protocols",
+    "aerospace::unmanned-aerial-vehicles",
+    "algorithms",
+    "api-bindings",
+    "asynchronous",
+    "authentication",
+    "caching",
+    "command-line-interface",
+    "command-line-utilities",
+    "compilers",
+    "compression",
+    "computer-vision",
+    "concurrency",
+    "config",
+    "cryptography",
+    "cryptography::cryptocurrencies",
+    "data-structures",
+    "database",
+    "database-implementations",
+    "date-and-time",
+    "development-tools",
+    "development-tools::build-utils",
+    "development-tools::cargo-plugins",
+    "development-tools::debugging",
+    "development-tools::ffi",
+    "development-tools::procedural-macro-helpers",
+    "development-tools::profiling",
+    "development-tools::testing",
+    "email",
+    "embedded",
+    "emulators",
+    "encoding",
+    "external-ffi-bindings",
+    "filesystem",
+    "finance",
+    "game-development",
+    "game-engines",
+    "games",
+    "graphics",
+    "gui",
+    "hardware-support",
+    "internationalization",
+    "localization",
+    "mathematics",
+    "memory-management",
+    "multimedia",
+    "multimedia::audio",
+    "multimedia::encoding",
+    "multimedia::images",
+    "multimedia::video",
+    "network-programming",
+    "no-std",
+    "no-std::no-alloc",
+    "os",
+    "os::android-apis",
+    "os::freebsd-apis",
+    "os::linux-apis",
+    "os::macos-apis",
+    "os::unix-apis",
+    "os::windows-apis",
+    "parser-implementations",
+    "parsing",
+    "rendering",
+    "rendering::data-formats",
+    "rendering::engine",
+    "rendering::graphics-api",
+    "rust-patterns",
+    "science",
+    "science::bioinformatics",
+    "science::geo",
+    "science::neuroscience",
+    "science::robotics",
+    "security",
+    "simulation",
+    "template-engine",
+    "text-editors",
+    "text-processing",
+    "value-formatting",
+    "virtualization",
+    "visualization",
+    "wasm",
+    "web-programming",
+    "web-programming::http-client",
+    "web-programming::http-server",
+    "web-programming::websocket",
+];
+
+/// A crates.io-facing `[package]` key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Description,
+    Keywords,
+    Categories,
+    Repository,
+    Homepage,
+}
+
+impl Field {
+    pub const ALL: [Self; 5] = [
+        Self::Description,
+        Self::Keywords,
+        Self::Categories,
+        Self::Repository,
+        Self::Homepage,
+    ];
+
+    /// Manifest key.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Description => "description",
+            Self::Keywords => "keywords",
+            Self::Categories => "categories",
+            Self::Repository => "repository",
+            Self::Homepage => "homepage",
+        }
+    }
+
+    fn is_list(self) -> bool {
+        matches!(self, Self::Keywords | Self::Categories)
+    }
+}
+
+/// Current value of a field, as edited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldValue {
+    pub field: Field,
+    /// Text of the value, lists joined with `, `; empty when unset.
+    pub text: String,
+    /// Inherited from `[workspace.package]`: not editable here.
+    pub inherited: bool,
+}
+
+#[derive(Debug)]
+pub enum MetadataError {
+    Manifest(ManifestError),
+    /// The manifest has no `[package]` (virtual workspace).
+    NoPackage,
+    /// One message per invalid value.
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Manifest(e) => write!(f, "{e}"),
+            Self::NoPackage => write!(f, "Cargo.toml has no [package] section"),
+            Self::Invalid(problems) => write!(f, "{}", problems.join("\n")),
+        }
+    }
+}
+
+impl std::error::Error for MetadataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Manifest(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ManifestError> for MetadataError {
+    fn from(e: ManifestError) -> Self {
+        Self::Manifest(e)
+    }
+}
+
+/// Values of every field in the manifest of the project at `path`.
+pub fn read(path: &Path) -> Result<Vec<FieldValue>, MetadataError> {
+    let manifest = Manifest::read(path)?;
+    if manifest.package_name().is_none() {
+        return Err(MetadataError::NoPackage);
+    }
+    Ok(Field::ALL
+        .into_iter()
+        .map(|field| FieldValue {
+            field,
+            text: current_text(&manifest, field),
+            inherited: manifest.package_key_inherited(field.key()),
+        })
+        .collect())
+}
+
+/// Validate `inputs` (field, text) and write the changed ones; empty text unsets the key.
+///
+/// Nothing is written unless every value is valid. Returns the keys written.
+pub fn write(path: &Path, inputs: &[(Field, String)]) -> Result<Vec<&'static str>, MetadataError> {
+    Manifest::edit(path, |manifest| apply(manifest, inputs))?
+}
+
+/// Changes `inputs` in `manifest`; leaves it untouched on error.
+fn apply(
+    manifest: &mut Manifest,
+    inputs: &[(Field, String)],
+) -> Result<Vec<&'static str>, MetadataError> {
+    if manifest.package_name().is_none() {
+        return Err(MetadataError::NoPackage);
+    }
+    let mut problems = Vec::new();
+    let mut changes = Vec::new();
+    for (field, input) in inputs {
+        let key = field.key();
+        if manifest.package_key_inherited(key) {
+            continue;
+        }
+        match parse(*field, input) {
+            Ok(value) if canonical(&value) != current_text(manifest, *field) => {
+                changes.push((key, value));
+            }
+            Ok(_) => {}
+            Err(problem) => problems.push(problem),
+        }
+    }
+    if !problems.is_empty() {
+        return Err(MetadataError::Invalid(problems));
+    }
+    let mut written = Vec::new();
+    for (key, value) in changes {
+        match value {
+            Parsed::Unset => manifest.remove_package_key(key),
+            Parsed::Text(text) => manifest.set_package_value(key, text),
+            Parsed::List(items) => manifest.set_package_value(key, items.iter().collect::<Array>()),
+        };
+        written.push(key);
+    }
+    Ok(written)
+}
+
+/// Keywords separated by commas or whitespace, lowercased and deduplicated, within crates.io limits.
+pub fn parse_keywords(input: &str) -> Result<Vec<String>, String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for keyword in split_list(input) {
+        let keyword = keyword.to_lowercase();
+        let valid = keyword.len() <= MAX_KEYWORD_LEN
+            && keyword.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && keyword
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '+');
+        if !valid {
+            return Err(format!(
+                "Invalid keyword '{keyword}': ASCII letters, digits, '-', '_' and '+', at most {MAX_KEYWORD_LEN} characters"
+            ));
+        }
+        if !keywords.contains(&keyword) {
+            keywords.push(keyword);
+        }
+    }
+    if keywords.len() > MAX_KEYWORDS {
+        return Err(format!("crates.io accepts at most {MAX_KEYWORDS} keywords"));
+    }
+    Ok(keywords)
+}
+
+/// Category slugs separated by commas or whitespace, each one of `CATEGORIES`.
+pub fn parse_categories(input: &str) -> Result<Vec<String>, String> {
+    let mut categories: Vec<String> = Vec::new();
+    for category in split_list(input) {
+        let category = category.to_lowercase();
+        if !CATEGORIES.contains(&category.as_str()) {
+            let close: Vec<&str> = CATEGORIES
+                .iter()
+                .copied()
+                .filter(|slug| slug.contains(&category))
+                .take(3)
+                .collect();
+            return Err(if close.is_empty() {
+                format!("Unknown category '{category}'")
+            } else {
+                format!(
+                    "Unknown category '{category}' (did you mean {}?)",
+                    close.join(", ")
+                )
+            });
+        }
+        if !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+    if categories.len() > MAX_CATEGORIES {
+        return Err(format!(
+            "crates.io accepts at most {MAX_CATEGORIES} categories"
+        ));
+    }
+    Ok(categories)
+}
+
+/// `input` trimmed, when it is an http(s) URL.
+pub fn parse_url<'a>(input: &'a str, what: &str) -> Result<&'a str, String> {
+    let url = input.trim();
+    let valid = (url.starts_with("https://") || url.starts_with("http://"))
+        && !url.contains(char::is_whitespace);
+    if valid {
+        Ok(url)
+    } else {
+        Err(format!("The {what} must be an http(s) URL"))
+    }
+}
+
+enum Parsed {
+    Unset,
+    Text(String),
+    List(Vec<String>),
+}
+
+fn parse(field: Field, input: &str) -> Result<Parsed, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(Parsed::Unset);
+    }
+    Ok(match field {
+        Field::Description => Parsed::Text(input.to_string()),
+        Field::Keywords => Parsed::List(parse_keywords(input)?),
+        Field::Categories => Parsed::List(parse_categories(input)?),
+        Field::Repository | Field::Homepage => Parsed::Text(parse_url(input, field.key())?.into()),
+    })
+}
+
+/// Text the parsed value reads back as, to detect unchanged fields.
+fn canonical(value: &Parsed) -> String {
+    match value {
+        Parsed::Unset => String::new(),
+        Parsed::Text(text) => text.clone(),
+        Parsed::List(items) => items.join(", "),
+    }
+}
+
+fn current_text(manifest: &Manifest, field: Field) -> String {
+    if field.is_list() {
+        manifest.package_str_array(field.key()).join(", ")
+    } else {
+        manifest
+            .package_str(field.key())
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
+fn split_list(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+    use std::fs;
+
+    #[test]
+    fn validates_and_writes_changed_fields_only() {
+        let dir = TempDir::new("package_metadata");
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\ndescription = \"A demo\" # shown on crates.io\nhomepage = \"https://example.com\"\nrepository.workspace = true\n",
+        )
+        .unwrap();
+        let values = read(&dir).unwrap();
+        assert_eq!(values[0].text, "A demo");
+        assert!(values[3].inherited);
+
+        let input = |description: &str, keywords: &str, categories: &str, homepage: &str| {
+            vec![
+                (Field::Description, description.to_string()),
+                (Field::Keywords, keywords.to_string()),
+                (Field::Categories, categories.to_string()),
+                (Field::Repository, "not a url".to_string()),
+                (Field::Homepage, homepage.to_string()),
+            ]
+        };
+        let Err(MetadataError::Invalid(problems)) = write(
+            &dir,
+            &input("A demo", "a b c d e f", "command-line", "ftp://x"),
+        ) else {
+            panic!("invalid values accepted");
+        };
+        assert_eq!(problems.len(), 3);
+        assert!(problems[1].contains("command-line-interface"));
+
+        let written = write(
+            &dir,
+            &input("A demo", "TUI, cli", "command-line-utilities", ""),
+        )
+        .unwrap();
+        assert_eq!(written, ["keywords", "categories", "homepage"]);
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("description = \"A demo\" # shown on crates.io"));
+        assert!(manifest.contains("keywords = [\"tui\", \"cli\"]"));
+        assert!(manifest.contains("repository.workspace = true"));
+        assert!(!manifest.contains("homepage"));
+        assert!(
+            write(
+                &dir,
+                &input("A demo", "tui cli", "command-line-utilities", "")
+            )
+            .unwrap()
+            .is_empty()
+        );
+    }
+}
@@ -0,0 +1,506 @@
+//! Packaging scaffolds (Homebrew formula, AUR PKGBUILD, scoop manifest).
+//!
+//! Generates a starting point for distributing a binary project through a
+//! package manager, written to `<project>/packaging/`:
+//! - Metadata (description, license, homepage) comes from `Cargo.toml`.
+//! - The version is the latest git tag (falling back to `package.version`).
+//! - Download URLs point at the GitHub release assets produced by the release
+//!   artifact builder; checksums are read from `dist/SHA256SUMS` when the
+//!   matching artifact was built, otherwise left as `FILL_ME`.
+//!
+//! Values are escaped for the strings of each format, so a crafted
+//! description cannot run code when the PKGBUILD is sourced or the formula
+//! loaded. Existing files are never overwritten.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::info;
+
+use crate::git_ops;
+use crate::github::RepoSlug;
+use crate::project::manifest::{Manifest, ManifestError};
+use crate::project::release::{self, CHECKSUMS_FILE, DIST_DIR};
+use crate::template::{self, TemplateError};
+
+/// Output directory inside the project.
+pub const PACKAGING_DIR: &str = "packaging";
+
+/// Placeholder for values the user has to provide.
+const FILL_ME: &str = "FILL_ME";
+
+/// Release targets referenced by the templates: `(url variable, checksum variable, triple)`.
+const TARGETS: [(&str, &str, &str); 4] = [
+    (
+        "url_macos_x86_64",
+        "sha256_macos_x86_64",
+        "x86_64-apple-darwin",
+    ),
+    (
+        "url_macos_aarch64",
+        "sha256_macos_aarch64",
+        "aarch64-apple-darwin",
+    ),
+    (
+        "url_linux_x86_64",
+        "sha256_linux_x86_64",
+        "x86_64-unknown-linux-gnu",
+    ),
+    (
+        "url_windows_x86_64",
+        "sha256_windows_x86_64",
+        "x86_64-pc-windows-msvc",
+    ),
+];
+
+const HOMEBREW_TEMPLATE: &str = r##"class {{class_name}} < Formula
+  desc "{{description}}"
+  homepage "{{homepage}}"
+  version "{{version}}"
+  license {{license}}
+
+  on_macos do
+    on_arm do
+      url "{{url_macos_aarch64}}"
+      sha256 "{{sha256_macos_aarch64}}"
+    end
+    on_intel do
+      url "{{url_macos_x86_64}}"
+      sha256 "{{sha256_macos_x86_64}}"
+    end
+  end
+
+  on_linux do
+    on_intel do
+      url "{{url_linux_x86_64}}"
+      sha256 "{{sha256_linux_x86_64}}"
+    end
+  end
+
+  def install
+    bin.install Dir["{{bin}}-*"].first => "{{bin}}"
+  end
+
+  test do
+    system "#{bin}/{{bin}}", "--version"
+  end
+end
+"##;
+
+const AUR_TEMPLATE: &str = r#"# Maintainer: FILL_ME
+pkgname="{{name}}-bin"
+pkgver="{{version}}"
+pkgrel=1
+pkgdesc="{{description}}"
+arch=('x86_64')
+url="{{homepage}}"
+license=("{{license}}")
+provides=("{{bin}}")
+conflicts=("{{bin}}")
+source_x86_64=("{{bin}}-${pkgver}::{{url_linux_x86_64}}")
+sha256sums_x86_64=("{{sha256_linux_x86_64}}")
+
+package() {
+    install -Dm755 "${srcdir}/{{bin}}-${pkgver}" "${pkgdir}/usr/bin/{{bin}}"
+}
+"#;
+
+const SCOOP_TEMPLATE: &str = r#"{
+    "version": "{{version}}",
+    "description": "{{description}}",
+    "homepage": "{{homepage}}",
+    "license": "{{license}}",
+    "architecture": {
+        "64bit": {
+            "url": "{{url_windows_x86_64}}#/{{bin}}.exe",
+            "hash": "{{sha256_windows_x86_64}}"
+        }
+    },
+    "bin": "{{bin}}.exe"
+}
+"#;
+
+/// Supported package managers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackagingFormat {
+    Homebrew,
+    Aur,
+    Scoop,
+}
+
+impl PackagingFormat {
+    pub const ALL: [Self; 3] = [Self::Homebrew, Self::Aur, Self::Scoop];
+
+    /// Path of the generated file, relative to the project.
+    fn relative_path(self, name: &str) -> PathBuf {
+        let dir = Path::new(PACKAGING_DIR);
+        match self {
+            Self::Homebrew => dir.join("homebrew").join(format!("{name}.rb")),
+            Self::Aur => dir.join("aur").join("PKGBUILD"),
+            Self::Scoop => dir.join("scoop").join(format!("{name}.json")),
+        }
+    }
+
+    /// `value` escaped for the double-quoted strings of the format: Ruby
+    /// (where `#{` interpolates), bash (where `$`, backticks and `\` expand)
+    /// or JSON.
+    fn escape(self, value: &str) -> String {
+        let special: &[char] = match self {
+            Self::Homebrew => &['\\', '"', '#'],
+            Self::Aur => &['\\', '"', '$', '`'],
+            Self::Scoop => {
+                let json = serde_json::Value::from(value).to_string();
+                return json[1..json.len() - 1].to_string();
+            }
+        };
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if special.contains(&c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    /// `license` (an SPDX expression) as the format writes it: Homebrew takes
+    /// `any_of:` / `all_of:` arrays instead of `OR` / `AND`.
+    fn license(self, license: &str) -> String {
+        match self {
+            Self::Homebrew => homebrew_license(license),
+            Self::Aur | Self::Scoop => self.escape(license),
+        }
+    }
+
+    fn template(self) -> &'static str {
+        match self {
+            Self::Homebrew => HOMEBREW_TEMPLATE,
+            Self::Aur => AUR_TEMPLATE,
+            Self::Scoop => SCOOP_TEMPLATE,
+        }
+    }
+}
+
+impl fmt::Display for PackagingFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Homebrew => write!(f, "Homebrew formula"),
+            Self::Aur => write!(f, "AUR PKGBUILD"),
+            Self::Scoop => write!(f, "Scoop manifest"),
+        }
+    }
+}
+
+/// A generated scaffold.
+#[derive(Debug)]
+pub struct Scaffold {
+    pub path: PathBuf,
+    /// Targets whose checksum could not be filled in (artifact not built yet).
+    pub missing_checksums: Vec<String>,
+}
+
+/// Errors preventing scaffold generation.
+#[derive(Debug)]
+pub enum PackagingError {
+    Manifest(ManifestError),
+    Workspace,
+    NoBinaries,
+    NoRepository,
+    AlreadyExists(PathBuf),
+    Template(TemplateError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for PackagingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Manifest(e) => write!(f, "{e}"),
+            Self::Workspace => write!(
+                f,
+                "Virtual workspaces are not supported; package a member crate instead"
+            ),
+            Self::NoBinaries => write!(f, "Project has no binary targets"),
+            Self::NoRepository => write!(
+                f,
+                "No repository URL: set `package.repository` or a GitHub 'origin' remote"
+            ),
+            Self::AlreadyExists(p) => write!(f, "{} already exists", p.display()),
+            Self::Template(e) => write!(f, "{e}"),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PackagingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Manifest(e) => Some(e),
+            Self::Template(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Generate the `format` scaffold for the project at `project_path`.
+pub fn generate(project_path: &Path, format: PackagingFormat) -> Result<Scaffold, PackagingError> {
+    let manifest = Manifest::read(project_path).map_err(PackagingError::Manifest)?;
+    let name = manifest
+        .package_name()
+        .ok_or(PackagingError::Workspace)?
+        .to_string();
+
+    let target = project_path.join(format.relative_path(&name));
+    if target.exists() {
+        return Err(PackagingError::AlreadyExists(target));
+    }
+
+    let (vars, missing_checksums) = variables(project_path, &manifest, &name, format)?;
+    let contents = template::render(format.template(), &vars).map_err(PackagingError::Template)?;
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(PackagingError::Io)?;
+    }
+    fs::write(&target, contents).map_err(PackagingError::Io)?;
+    info!("Generated {format} at {}", target.display());
+
+    Ok(Scaffold {
+        path: target,
+        missing_checksums,
+    })
+}
+
+/// Template variables escaped for `format`, plus the targets whose checksum is unknown.
+fn variables(
+    project_path: &Path,
+    manifest: &Manifest,
+    name: &str,
+    format: PackagingFormat,
+) -> Result<(BTreeMap<&'static str, String>, Vec<String>), PackagingError> {
+    let bins = release::binary_names(project_path, manifest, name);
+    let bin = if bins.iter().any(|b| b == name) {
+        name.to_string()
+    } else {
+        bins.into_iter().next().ok_or(PackagingError::NoBinaries)?
+    };
+
+    let tag = git_ops::latest_tag(project_path).ok().flatten();
+    let version = match &tag {
+        Some(tag) => tag.trim_start_matches('v').to_string(),
+        None => manifest.package_version().unwrap_or("0.0.0").to_string(),
+    };
+    let tag = tag.unwrap_or_else(|| format!("v{version}"));
+
+    let repository = repository_url(project_path, manifest).ok_or(PackagingError::NoRepository)?;
+    let homepage = manifest
+        .package_str("homepage")
+        .map_or_else(|| repository.clone(), ToString::to_string);
+    let description = manifest.package_str("description").unwrap_or(name);
+    let license = manifest.package_str("license").unwrap_or(FILL_ME);
+
+    let checksums = read_checksums(project_path);
+    let mut missing = Vec::new();
+    let mut vars = BTreeMap::from([
+        ("name", name.to_string()),
+        ("class_name", class_name(name)),
+        ("bin", bin.clone()),
+        ("version", version.clone()),
+        ("description", description.to_string()),
+        ("homepage", homepage),
+    ]);
+    for (url_var, sha_var, triple) in TARGETS {
+        let artifact = release::artifact_name(&bin, &version, triple);
+        let sha = checksums.get(&artifact).cloned().unwrap_or_else(|| {
+            missing.push(triple.to_string());
+            FILL_ME.to_string()
+        });
+        vars.insert(
+            url_var,
+            format!("{repository}/releases/download/{tag}/{artifact}"),
+        );
+        vars.insert(sha_var, sha);
+    }
+    for value in vars.values_mut() {
+        *value = format.escape(value);
+    }
+    vars.insert("license", format.license(license));
+    Ok((vars, missing))
+}
+
+/// `package.repository`, or the GitHub URL of the `origin` remote.
+fn repository_url(project_path: &Path, manifest: &Manifest) -> Option<String> {
+    if let Some(url) = manifest.package_str("repository") {
+        return Some(
+            url.trim_end_matches('/')
+                .trim_end_matches(".git")
+                .to_string(),
+        );
+    }
+    let remote = git_ops::remote_url(project_path, "origin").ok()?;
+    let slug = RepoSlug::from_remote_url(&remote)?;
+    Some(format!("https://github.com/{}/{}", slug.owner, slug.repo))
+}
+
+/// `file name -> digest` from `dist/SHA256SUMS` (empty when missing).
+fn read_checksums(project_path: &Path) -> BTreeMap<String, String> {
+    let raw =
+        fs::read_to_string(project_path.join(DIST_DIR).join(CHECKSUMS_FILE)).unwrap_or_default();
+    raw.lines()
+        .filter_map(|line| line.split_once("  "))
+        .map(|(digest, file)| (file.trim().to_string(), digest.trim().to_string()))
+        .collect()
+}
+
+/// Homebrew `license` argument of an SPDX expression: `"MIT"`,
+/// `any_of: ["MIT", "Apache-2.0"]`, `all_of: [...]`, `AND` binding tighter
+/// than `OR`. The old `MIT/Apache-2.0` form of Cargo means `OR`; parentheses
+/// give `:cannot_represent`.
+fn homebrew_license(expression: &str) -> String {
+    if expression.contains(['(', ')']) {
+        return ":cannot_represent".to_string();
+    }
+    let quote = |id: &str| format!("\"{}\"", PackagingFormat::Homebrew.escape(id.trim()));
+    // `"Apache-2.0" => { with: "LLVM-exception" }`, braced inside arrays.
+    let license = |id: &str, nested: bool| match id.split_once(" WITH ") {
+        Some((id, exception)) if nested => {
+            format!("{{ {} => {{ with: {} }} }}", quote(id), quote(exception))
+        }
+        Some((id, exception)) => format!("{} => {{ with: {} }}", quote(id), quote(exception)),
+        None => quote(id),
+    };
+    let list = |ids: &[&str]| {
+        ids.iter()
+            .map(|id| license(id, true))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let alternatives: Vec<Vec<&str>> = expression
+        .split(" OR ")
+        .flat_map(|part| part.split('/'))
+        .map(|alternative| alternative.split(" AND ").collect())
+        .collect();
+    match alternatives.as_slice() {
+        [all] if all.len() == 1 => license(all[0], false),
+        [all] => format!("all_of: [{}]", list(all)),
+        _ => {
+            let any: Vec<String> = alternatives
+                .iter()
+                .map(|all| match all.as_slice() {
+                    [id] => license(id, true),
+                    all => format!("{{ all_of: [{}] }}", list(all)),
+                })
+                .collect();
+            format!("any_of: [{}]", any.join(", "))
+        }
+    }
+}
+
+/// Homebrew class name: `my-tool` -> `MyTool`; other characters than letters
+/// and digits are dropped.
+fn class_name(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    /// Project directory with `src/`.
+    fn project_dir() -> TempDir {
+        let d = TempDir::new("packaging");
+        fs::create_dir_all(d.join("src")).unwrap();
+        d
+    }
+
+    #[test]
+    fn homebrew_formula_uses_manifest_and_checksums() {
+        let dir = project_dir();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"my-tool\"\nversion = \"1.2.0\"\ndescription = \"A \\\"tool\\\"\"\nlicense = \"MIT\"\nrepository = \"https://github.com/o/my-tool\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::create_dir_all(dir.join(DIST_DIR)).unwrap();
+        fs::write(
+            dir.join(DIST_DIR).join(CHECKSUMS_FILE),
+            "abc123  my-tool-1.2.0-aarch64-apple-darwin\n",
+        )
+        .unwrap();
+
+        let scaffold = generate(&dir, PackagingFormat::Homebrew).unwrap();
+        let formula = fs::read_to_string(&scaffold.path).unwrap();
+        assert!(formula.starts_with("class MyTool < Formula"));
+        assert!(formula.contains(r#"desc "A \"tool\"""#));
+        assert!(formula.contains("license \"MIT\"\n"));
+        assert!(formula.contains(
+            "url \"https://github.com/o/my-tool/releases/download/v1.2.0/my-tool-1.2.0-aarch64-apple-darwin\""
+        ));
+        assert!(formula.contains("sha256 \"abc123\""));
+        assert_eq!(scaffold.missing_checksums.len(), 3);
+
+        assert!(matches!(
+            generate(&dir, PackagingFormat::Homebrew),
+            Err(PackagingError::AlreadyExists(_))
+        ));
+    }
+
+    #[test]
+    fn values_are_escaped_per_format() {
+        let crafted = r#"a "b" $(rm -rf ~) `id` #{system("id")} \"#;
+        assert_eq!(
+            PackagingFormat::Aur.escape(crafted),
+            r#"a \"b\" \$(rm -rf ~) \`id\` #{system(\"id\")} \\"#
+        );
+        assert_eq!(
+            PackagingFormat::Homebrew.escape(crafted),
+            r#"a \"b\" $(rm -rf ~) `id` \#{system(\"id\")} \\"#
+        );
+        assert_eq!(
+            PackagingFormat::Scoop.escape("a \"b\"\n\\"),
+            r#"a \"b\"\n\\"#
+        );
+        assert_eq!(class_name("my-tool$x"), "MyToolX");
+    }
+
+    #[test]
+    fn homebrew_licenses_use_any_of_and_all_of() {
+        assert_eq!(homebrew_license("MIT"), r#""MIT""#);
+        assert_eq!(
+            homebrew_license("MIT OR Apache-2.0"),
+            r#"any_of: ["MIT", "Apache-2.0"]"#
+        );
+        assert_eq!(
+            homebrew_license("MIT/Apache-2.0"),
+            r#"any_of: ["MIT", "Apache-2.0"]"#
+        );
+        assert_eq!(
+            homebrew_license("MIT AND Unicode-3.0"),
+            r#"all_of: ["MIT", "Unicode-3.0"]"#
+        );
+        assert_eq!(
+            homebrew_license("Apache-2.0 WITH LLVM-exception OR MIT AND BSD-3-Clause"),
+            r#"any_of: [{ "Apache-2.0" => { with: "LLVM-exception" } }, { all_of: ["MIT", "BSD-3-Clause"] }]"#
+        );
+        assert_eq!(
+            homebrew_license("Apache-2.0 WITH LLVM-exception"),
+            r#""Apache-2.0" => { with: "LLVM-exception" }"#
+        );
+        assert_eq!(
+            homebrew_license("(MIT OR Apache-2.0) AND BSD-3-Clause"),
+            ":cannot_represent"
+        );
+    }
+}
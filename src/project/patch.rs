@@ -0,0 +1,195 @@
+//! Local development overrides through `[patch.crates-io]`.
+//!
+//! Points a project at a local checkout of another listed project instead of
+//! the crates.io release: `[patch.crates-io] foo = { path = "../foo" }`.
+//! Paths are written relative to the project when possible so the manifest
+//! stays portable. `remove_all` drops every path patch again, to be run before
+//! committing or publishing.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use log::info;
+
+use crate::project::graph;
+use crate::project::list::ProjectInfo;
+use crate::project::manifest::{Manifest, ManifestError};
+
+/// Registry patched by this helper.
+pub const REGISTRY: &str = "crates-io";
+
+/// A crate of another project that can replace a dependency.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub crate_name: String,
+    /// Name of the project providing the crate.
+    pub project: String,
+    /// Directory of the crate's `Cargo.toml`.
+    pub dir: PathBuf,
+}
+
+/// Crates provided by `projects` that the project at `project_path` depends on.
+///
+/// Workspace members count on both sides. Crates already patched are skipped.
+pub fn candidates(project_path: &Path, projects: &[ProjectInfo]) -> Vec<Candidate> {
+    let dependencies: BTreeSet<String> = graph::manifest_dirs(project_path)
+        .iter()
+        .filter_map(|dir| Manifest::read(dir).ok())
+        .flat_map(|m| m.dependency_names())
+        .collect();
+    let patched: BTreeSet<String> = active(project_path).into_iter().map(|(n, _)| n).collect();
+
+    let mut out = Vec::new();
+    for project in projects.iter().filter(|p| p.path != project_path) {
+        for dir in graph::manifest_dirs(&project.path) {
+            let Some(name) = Manifest::read(&dir)
+                .ok()
+                .and_then(|m| m.package_name().map(ToString::to_string))
+            else {
+                continue;
+            };
+            if dependencies.contains(&name) && !patched.contains(&name) {
+                out.push(Candidate {
+                    crate_name: name,
+                    project: project.name.clone(),
+                    dir,
+                });
+            }
+        }
+    }
+    out.sort_by(|a, b| a.crate_name.cmp(&b.crate_name));
+    out
+}
+
+/// Path patches currently declared in the project's `[patch.crates-io]`.
+pub fn active(project_path: &Path) -> Vec<(String, PathBuf)> {
+    Manifest::read(project_path)
+        .map(|m| m.path_patches(REGISTRY))
+        .unwrap_or_default()
+}
+
+/// Patch `candidate.crate_name` to the local checkout in `candidate.dir`.
+pub fn add(project_path: &Path, candidate: &Candidate) -> Result<(), ManifestError> {
+    let path = relative_path(project_path, &candidate.dir);
+    Manifest::edit(project_path, |m| {
+        m.set_patch(REGISTRY, &candidate.crate_name, &path.to_string_lossy());
+    })?;
+    info!(
+        "Patched {} in {} to {}",
+        candidate.crate_name,
+        project_path.display(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Remove every path patch of `[patch.crates-io]`; returns the unpatched crate names.
+pub fn remove_all(project_path: &Path) -> Result<Vec<String>, ManifestError> {
+    let names: Vec<String> = Manifest::edit(project_path, |m| {
+        let names: Vec<String> = m
+            .path_patches(REGISTRY)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        for name in &names {
+            m.remove_patch(REGISTRY, name);
+        }
+        names
+    })?;
+    if !names.is_empty() {
+        info!(
+            "Removed local patches from {}: {names:?}",
+            project_path.display()
+        );
+    }
+    Ok(names)
+}
+
+/// `to` relative to `from` (`../sibling/crate`), or `to` itself when no common root exists.
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let (Ok(from), Ok(to)) = (fs::canonicalize(from), fs::canonicalize(to)) else {
+        return to.to_path_buf();
+    };
+    let from: Vec<Component> = from.components().collect();
+    let to_components: Vec<Component> = to.components().collect();
+    let common = from
+        .iter()
+        .zip(&to_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common <= 1 {
+        return to;
+    }
+    let mut rel = PathBuf::new();
+    for _ in common..from.len() {
+        rel.push("..");
+    }
+    for c in &to_components[common..] {
+        rel.push(c);
+    }
+    rel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    fn project(base: &Path, name: &str, manifest: &str) -> ProjectInfo {
+        let path = base.join(name);
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("Cargo.toml"), manifest).unwrap();
+        ProjectInfo {
+            name: name.to_string(),
+            path,
+            has_uncommitted_changes: false,
+            branch: None,
+            ahead_behind: None,
+            diverged_default: None,
+            rollup: None,
+            no_std: None,
+            version: None,
+            kind: None,
+            last_commit: None,
+            remote_url: None,
+            fingerprint: 0,
+            pinned: false,
+            last_opened: None,
+            tags: Vec::new(),
+            untrusted: false,
+        }
+    }
+
+    #[test]
+    fn add_and_remove_local_patch() {
+        let base = TempDir::new("patch");
+        let app = project(
+            &base,
+            "app",
+            "[package]\nname = \"app\"\n\n[dependencies]\n# keep me\nserde = \"1\"\nfoo = \"0.3\"\n",
+        );
+        let foo = project(&base, "foo", "[package]\nname = \"foo\"\n");
+        let other = project(&base, "other", "[package]\nname = \"other\"\n");
+        let original = fs::read_to_string(app.path.join("Cargo.toml")).unwrap();
+
+        let found = candidates(&app.path, &[app.clone(), foo, other]);
+        assert_eq!(found.len(), 1);
+        add(&app.path, &found[0]).unwrap();
+
+        let patched = fs::read_to_string(app.path.join("Cargo.toml")).unwrap();
+        assert!(patched.contains("# keep me"));
+        assert!(patched.contains("[patch.crates-io]\nfoo = { path = \"../foo\" }"));
+        assert!(!patched.contains("[patch]\n"));
+        assert_eq!(
+            active(&app.path),
+            vec![("foo".into(), PathBuf::from("../foo"))]
+        );
+
+        assert_eq!(remove_all(&app.path).unwrap(), vec!["foo"]);
+        assert_eq!(
+            fs::read_to_string(app.path.join("Cargo.toml")).unwrap(),
+            original
+        );
+    }
+}
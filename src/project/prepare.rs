@@ -0,0 +1,200 @@
+//! Workspace preparation ("Open full workspace").
+//!
+//! Before handing a project to the editor, make sure rust-analyzer has what it
+//! needs so the IDE is usable immediately:
+//! 1. Generated code: run the configured generator command (e.g. `cargo xtask codegen`).
+//! 2. Warm build: `cargo check --all-targets` (runs build scripts & proc macros).
+//! 3. `.vscode/settings.json`: written from a template when missing (never overwritten).
+//!
+//! Every step is controlled by the `workspace_prep` configuration section. The module is
+//! UI-agnostic: the caller iterates `plan` and reports each `run_step` outcome.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::info;
+
+use crate::cargo_ops::{self, CargoError};
+use crate::config::WorkspacePrep;
+use crate::process::{self, CommandLine};
+
+/// Template used when no `vscode_settings_template` is configured.
+const DEFAULT_VSCODE_SETTINGS: &str = r#"{
+    "rust-analyzer.cargo.buildScripts.enable": true,
+    "rust-analyzer.procMacro.enable": true,
+    "rust-analyzer.check.command": "clippy"
+}
+"#;
+
+/// A single preparation step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrepStep {
+    Generate(String),
+    CargoCheck,
+    VscodeSettings(Option<PathBuf>),
+}
+
+impl fmt::Display for PrepStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Generate(cmd) => write!(f, "Generate code ({cmd})"),
+            Self::CargoCheck => write!(f, "Warm up build (cargo check)"),
+            Self::VscodeSettings(_) => write!(f, "Write .vscode/settings.json"),
+        }
+    }
+}
+
+/// Errors that can occur while running a preparation step.
+#[derive(Debug)]
+pub enum PrepError {
+    Generate { status: i32, stderr: String },
+    Cargo(CargoError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for PrepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Generate { status, stderr } => {
+                write!(f, "Generator command failed (exit code {status}): {stderr}")
+            }
+            Self::Cargo(e) => write!(f, "{e}"),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PrepError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Cargo(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::Generate { .. } => None,
+        }
+    }
+}
+
+/// Build the ordered list of enabled steps.
+pub fn plan(prep: &WorkspacePrep) -> Vec<PrepStep> {
+    let mut steps = Vec::new();
+    if let Some(cmd) = prep
+        .generate_cmd
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+    {
+        steps.push(PrepStep::Generate(cmd.to_string()));
+    }
+    if prep.cargo_check {
+        steps.push(PrepStep::CargoCheck);
+    }
+    if prep.vscode_settings {
+        steps.push(PrepStep::VscodeSettings(
+            prep.vscode_settings_template.as_ref().map(PathBuf::from),
+        ));
+    }
+    steps
+}
+
+/// Execute one step inside `project_path`, returning a short outcome description.
+pub fn run_step(step: &PrepStep, project_path: &Path) -> Result<String, PrepError> {
+    info!("Workspace prep '{step}' for {}", project_path.display());
+    match step {
+        PrepStep::Generate(cmd) => run_generator(cmd, project_path).map(|()| "done".into()),
+        PrepStep::CargoCheck => cargo_ops::run(project_path, &["check", "--all-targets"])
+            .map(|_| "done".into())
+            .map_err(PrepError::Cargo),
+        PrepStep::VscodeSettings(template) => {
+            write_vscode_settings(project_path, template.as_deref())
+        }
+    }
+}
+
+fn run_generator(cmd: &str, project_path: &Path) -> Result<(), PrepError> {
+    let mut parts = cmd.split_whitespace();
+    let Some(program) = parts.next() else {
+        return Ok(());
+    };
+    let cmd = CommandLine::new(program, project_path).args(parts);
+    let output = process::with(|runner| runner.output(&cmd)).map_err(PrepError::Io)?;
+    if !output.success() {
+        return Err(PrepError::Generate {
+            status: output.code.unwrap_or(-1),
+            stderr: output.stderr.trim().to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn write_vscode_settings(
+    project_path: &Path,
+    template: Option<&Path>,
+) -> Result<String, PrepError> {
+    let target = project_path.join(".vscode").join("settings.json");
+    if target.exists() {
+        return Ok("kept existing file".into());
+    }
+    let contents = match template {
+        Some(path) => fs::read_to_string(path).map_err(PrepError::Io)?,
+        None => DEFAULT_VSCODE_SETTINGS.to_string(),
+    };
+    fs::create_dir_all(project_path.join(".vscode")).map_err(PrepError::Io)?;
+    fs::write(&target, contents).map_err(PrepError::Io)?;
+    Ok("created".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::FakeRunner;
+    use crate::temp_dir::TempDir;
+    use std::rc::Rc;
+
+    #[test]
+    fn plan_respects_configuration() {
+        let prep = WorkspacePrep {
+            generate_cmd: Some("cargo xtask codegen".into()),
+            cargo_check: false,
+            vscode_settings: true,
+            vscode_settings_template: None,
+        };
+        assert_eq!(
+            plan(&prep),
+            vec![
+                PrepStep::Generate("cargo xtask codegen".into()),
+                PrepStep::VscodeSettings(None)
+            ]
+        );
+    }
+
+    #[test]
+    fn vscode_settings_never_overwritten() {
+        let d = TempDir::new("prepare");
+        let step = PrepStep::VscodeSettings(None);
+        assert_eq!(run_step(&step, &d).unwrap(), "created");
+
+        fs::write(d.join(".vscode/settings.json"), "{}").unwrap();
+        assert_eq!(run_step(&step, &d).unwrap(), "kept existing file");
+        assert_eq!(
+            fs::read_to_string(d.join(".vscode/settings.json")).unwrap(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn generator_failures_carry_their_status() {
+        let d = TempDir::new("prepare");
+        let fake = Rc::new(FakeRunner::default());
+        let _runner = process::replace(fake.clone());
+        fake.fail(3, "codegen: schema missing\n");
+
+        let step = PrepStep::Generate("cargo xtask codegen".into());
+        match run_step(&step, &d) {
+            Err(PrepError::Generate { status, stderr }) => {
+                assert_eq!((status, stderr.as_str()), (3, "codegen: schema missing"));
+            }
+            other => panic!("unexpected outcome: {other:?}"),
+        }
+        assert_eq!(fake.calls()[0].to_string(), "cargo xtask codegen");
+    }
+}
@@ -0,0 +1,110 @@
+//! Organization presets for new projects.
+//!
+//! A preset (`presets` in `config.yaml`) bundles the choices every project of
+//! an organization starts with. The create dialog applies its project type,
+//! edition and template; the rest is applied here once the project exists:
+//! the GitHub Actions workflow of the hygiene checklist, `package.license`,
+//! `package.publish` restricted to the preset's registry, and the `[lints]`
+//! levels.
+
+use std::path::Path;
+
+use log::{info, warn};
+use toml_edit::Array;
+
+use crate::config::Preset;
+use crate::project::hygiene::{self, Item};
+use crate::project::manifest::Manifest;
+
+/// Apply the files and manifest settings of `preset` to the new project at `path`.
+///
+/// Every setting is tried; returns those that failed, with the reason.
+pub fn apply(path: &Path, preset: &Preset) -> Vec<String> {
+    info!("Applying preset '{}' to {}", preset.name, path.display());
+    let mut failures = Vec::new();
+    let mut fail = |what: &str, reason: String| {
+        warn!("Preset '{}': cannot {what}: {reason}", preset.name);
+        failures.push(format!("{what}: {reason}"));
+    };
+
+    let has_ci = hygiene::check(path)
+        .iter()
+        .any(|status| status.item == Item::Ci && status.ok);
+    if preset.ci
+        && !has_ci
+        && let Err(e) = hygiene::fix(path, Item::Ci, "")
+    {
+        fail("add the CI workflow", e.to_string());
+    }
+
+    if preset.license.is_none() && preset.registry.is_none() && preset.lints.is_empty() {
+        return failures;
+    }
+    let edited = Manifest::edit(path, |m| {
+        let mut skipped = Vec::new();
+        if let Some(license) = &preset.license
+            && !m.set_package_value("license", license.as_str())
+        {
+            skipped.push("set package.license");
+        }
+        if let Some(registry) = &preset.registry
+            && !m.set_package_value("publish", Array::from_iter([registry.as_str()]))
+        {
+            skipped.push("set package.publish");
+        }
+        for (tool, lints) in &preset.lints {
+            for (lint, level) in lints {
+                if !m.set_lint(tool, lint, level) {
+                    skipped.push("set the lints");
+                }
+            }
+        }
+        skipped.dedup();
+        skipped
+    });
+    match edited {
+        Ok(skipped) => {
+            for what in skipped {
+                fail(
+                    what,
+                    "not settable in this manifest (inherited or no [package])".into(),
+                );
+            }
+        }
+        Err(e) => fail("edit Cargo.toml", e.to_string()),
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+    use std::collections::BTreeMap;
+    use std::fs;
+
+    #[test]
+    fn applies_manifest_settings_and_ci() {
+        let dir = TempDir::new("presets");
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"svc\"\n").unwrap();
+
+        let preset = Preset {
+            name: "company-service".into(),
+            ci: true,
+            license: Some("MIT OR Apache-2.0".into()),
+            registry: Some("company".into()),
+            lints: BTreeMap::from([(
+                "clippy".to_string(),
+                BTreeMap::from([("pedantic".to_string(), "warn".to_string())]),
+            )]),
+            ..Preset::default()
+        };
+        assert!(apply(&dir, &preset).is_empty());
+
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("license = \"MIT OR Apache-2.0\""));
+        assert!(manifest.contains("publish = [\"company\"]"));
+        assert!(manifest.contains("[lints.clippy]\npedantic = \"warn\""));
+        assert!(dir.join(".github/workflows/ci.yml").is_file());
+    }
+}
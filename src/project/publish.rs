@@ -0,0 +1,173 @@
+//! Publish release artifacts to GitHub Releases.
+//!
+//! Optional follow-up to the release artifact builder:
+//! 1. Resolve the tag pointing at `HEAD` (publishing untagged commits is refused).
+//! 2. Resolve `owner/repo` from the `origin` remote.
+//! 3. Release notes: the changelog section for the tag, or a short default.
+//! 4. Create (or reuse) the GitHub release and upload every artifact. Assets
+//!    of the same name left by an earlier publish of the tag are replaced.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use log::info;
+
+use crate::git_ops::{self, GitError};
+use crate::github::{self, GitHubClient, RepoSlug};
+use crate::http::HttpError;
+use crate::project::changelog;
+
+/// Errors that abort publishing.
+#[derive(Debug)]
+pub enum PublishError {
+    MissingToken,
+    NoTagAtHead,
+    NotGitHub(String),
+    Git(GitError),
+    Http(HttpError),
+}
+
+impl fmt::Display for PublishError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingToken => write!(
+                f,
+                "No GitHub token configured (set `github_token` or GITHUB_TOKEN)"
+            ),
+            Self::NoTagAtHead => write!(f, "HEAD is not tagged; tag the release commit first"),
+            Self::NotGitHub(url) => write!(f, "Remote 'origin' is not a GitHub URL: {url}"),
+            Self::Git(e) => write!(f, "{e}"),
+            Self::Http(e) => write!(f, "GitHub API: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PublishError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Git(e) => Some(e),
+            Self::Http(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Publish `artifacts` as a GitHub release for the tag at `HEAD`; returns the release URL.
+pub fn publish_github(
+    project_path: &Path,
+    artifacts: &[PathBuf],
+    token: Option<&str>,
+    log: &dyn Fn(String),
+) -> Result<String, PublishError> {
+    let token = token.ok_or(PublishError::MissingToken)?;
+    let tag = git_ops::current_tag(project_path)
+        .map_err(PublishError::Git)?
+        .ok_or(PublishError::NoTagAtHead)?;
+    let url = git_ops::remote_url(project_path, "origin").map_err(PublishError::Git)?;
+    let slug = RepoSlug::from_remote_url(&url).ok_or(PublishError::NotGitHub(url))?;
+
+    let notes = changelog::notes_for(project_path, &tag).unwrap_or_else(|| {
+        log("No changelog section found; using default release notes".into());
+        format!("Release {tag}")
+    });
+
+    let client = GitHubClient::new(token);
+    log(format!(
+        "Creating release {tag} on {}/{}...",
+        slug.owner, slug.repo
+    ));
+    let release = client
+        .create_or_get_release(&slug, &tag, &notes)
+        .map_err(PublishError::Http)?;
+
+    let existing = client
+        .list_assets(&slug, &release)
+        .map_err(PublishError::Http)?;
+    for file in artifacts {
+        let name = github::asset_name(file);
+        if let Some(asset) = existing.iter().find(|a| a.name == name) {
+            log(format!("Replacing existing asset {name}..."));
+            client
+                .delete_asset(&slug, asset)
+                .map_err(PublishError::Http)?;
+        }
+        log(format!("Uploading {}...", file.display()));
+        client
+            .upload_asset(&release, file)
+            .map_err(PublishError::Http)?;
+    }
+
+    info!("Published {tag} with {} asset(s)", artifacts.len());
+    Ok(release.html_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::FakeServer;
+    use crate::temp_dir::TempDir;
+    use std::fs;
+
+    /// A tagged project with a GitHub origin and two artifacts.
+    fn tagged_project() -> (TempDir, Vec<PathBuf>) {
+        let project = TempDir::new("publish");
+        fs::create_dir_all(project.join("dist")).unwrap();
+        let repo = git2::Repository::init(&project).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        fs::write(project.join("README.md"), "demo").unwrap();
+        git_ops::commit_all(&project, "Initial commit").unwrap();
+        let head = repo.head().unwrap().peel(git2::ObjectType::Commit).unwrap();
+        repo.tag_lightweight("v0.1.0", &head, false).unwrap();
+        repo.remote("origin", "git@github.com:sub07/demo.git")
+            .unwrap();
+
+        let artifacts = ["demo-linux.tar.gz", "SHA256SUMS"].map(|name| {
+            let path = project.join("dist").join(name);
+            fs::write(&path, name).unwrap();
+            path
+        });
+        (project, artifacts.to_vec())
+    }
+
+    #[test]
+    fn republishing_replaces_existing_assets() {
+        let (project, artifacts) = tagged_project();
+        let release = r#"{"id": 7, "html_url": "https://github.com/sub07/demo/releases/tag/v0.1.0",
+            "upload_url": "$SERVER/uploads/7/assets{?name,label}"}"#;
+        // The release exists already, with the archive of an earlier publish.
+        let server = FakeServer::start(vec![
+            (422, "{}".into()),
+            (200, release.into()),
+            (200, r#"[{"id": 70, "name": "demo-linux.tar.gz"}]"#.into()),
+            (204, String::new()),
+            (201, "{}".into()),
+            (201, "{}".into()),
+        ]);
+        github::use_api_root(&server.url);
+
+        let log = |_: String| {};
+        let published = publish_github(&project, &artifacts, Some("token"), &log).unwrap();
+        assert_eq!(
+            published,
+            "https://github.com/sub07/demo/releases/tag/v0.1.0"
+        );
+        assert_eq!(
+            server.requests(),
+            [
+                "POST /repos/sub07/demo/releases",
+                "GET /repos/sub07/demo/releases/tags/v0.1.0",
+                "GET /repos/sub07/demo/releases/7/assets?per_page=100",
+                "DELETE /repos/sub07/demo/releases/assets/70",
+                "POST /uploads/7/assets?name=demo-linux.tar.gz",
+                "POST /uploads/7/assets?name=SHA256SUMS",
+            ]
+        );
+
+        assert!(matches!(
+            publish_github(&project, &artifacts, None, &log),
+            Err(PublishError::MissingToken)
+        ));
+    }
+}
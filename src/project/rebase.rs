@@ -0,0 +1,283 @@
+//! Simplified interactive rebase of the current branch.
+//!
+//! The commits since the upstream are listed oldest first; each one is picked
+//! as is, squashed into the previous one (messages combined), or reworded.
+//! The plan is turned into a `git rebase -i` todo list and run with the git
+//! CLI, non-interactively:
+//! - `GIT_SEQUENCE_EDITOR` copies the prepared todo list over git's;
+//! - `GIT_EDITOR=true` accepts the combined squash messages as git prepares them;
+//! - rewording is an `exec git commit --amend -F <file>` line after the pick.
+//!
+//! The working tree must be clean. If git stops (conflicts, failing exec), the
+//! rebase is aborted and the branch is back where it was. The previous tip is
+//! also available as `ORIG_HEAD` after a successful run, and as a safety
+//! snapshot (see `git_ops::snapshot`).
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cargo_ops;
+use crate::git_ops::{self, GitError};
+use crate::project::list::scan_git_status;
+
+/// What to do with a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    Pick,
+    /// Meld into the previous commit, combining the messages.
+    Squash,
+    /// Keep the changes, replace the message.
+    Reword(String),
+}
+
+/// A commit of the plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedCommit {
+    pub id: String,
+    pub summary: String,
+    pub message: String,
+    pub step: Step,
+}
+
+impl PlannedCommit {
+    /// `pick    1a2b3c4d summary` style line for listings.
+    pub fn label(&self) -> String {
+        let step = match self.step {
+            Step::Pick => "pick",
+            Step::Squash => "squash",
+            Step::Reword(_) => "reword",
+        };
+        let summary = match &self.step {
+            Step::Reword(message) => message.lines().next().unwrap_or_default(),
+            _ => &self.summary,
+        };
+        format!("{step:<7} {:.8} {summary}", self.id)
+    }
+}
+
+#[derive(Debug)]
+pub enum RebaseError {
+    NoUpstream,
+    NothingToRebase,
+    /// Merge commits cannot be replayed by this simplified rebase.
+    HasMerges,
+    /// The first commit has no previous commit to be squashed into.
+    SquashFirst,
+    EmptyMessage,
+    Dirty,
+    /// git stopped; the rebase was aborted.
+    Aborted(String),
+    Git(GitError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for RebaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoUpstream => write!(f, "The current branch has no upstream"),
+            Self::NothingToRebase => write!(f, "No commit since the upstream"),
+            Self::HasMerges => write!(
+                f,
+                "Merge commits since the upstream; use git rebase -i --rebase-merges yourself"
+            ),
+            Self::SquashFirst => write!(f, "The first commit cannot be squashed"),
+            Self::EmptyMessage => write!(f, "A commit message cannot be empty"),
+            Self::Dirty => write!(f, "Commit or stash the uncommitted changes first"),
+            Self::Aborted(msg) => write!(f, "git stopped, the rebase was aborted:\n{msg}"),
+            Self::Git(e) => write!(f, "{e}"),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for RebaseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Git(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<git2::Error> for RebaseError {
+    fn from(e: git2::Error) -> Self {
+        Self::Git(e.into())
+    }
+}
+
+/// Upstream of the current branch and the commits since it, oldest first, all picked.
+pub fn plan(path: &Path) -> Result<(String, Vec<PlannedCommit>), RebaseError> {
+    let repo = git_ops::open(path).map_err(RebaseError::Git)?;
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Err(RebaseError::Git(GitError::DetachedHead));
+    }
+    let upstream = match git2::Branch::wrap(head).upstream() {
+        Ok(upstream) => upstream,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Err(RebaseError::NoUpstream),
+        Err(e) => return Err(e.into()),
+    };
+    let upstream_name = upstream.name()?.unwrap_or_default().to_string();
+    let upstream_tip = upstream.get().target().ok_or(RebaseError::NoUpstream)?;
+
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    walk.hide(upstream_tip)?;
+    walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+    let mut commits = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            return Err(RebaseError::HasMerges);
+        }
+        commits.push(PlannedCommit {
+            id: commit.id().to_string(),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            message: commit.message().unwrap_or_default().trim_end().to_string(),
+            step: Step::Pick,
+        });
+    }
+    if commits.is_empty() {
+        return Err(RebaseError::NothingToRebase);
+    }
+    Ok((upstream_name, commits))
+}
+
+/// Rewrite the history of the current branch on top of `upstream` following `commits`.
+pub fn execute(
+    path: &Path,
+    upstream: &str,
+    commits: &[PlannedCommit],
+    log: &dyn Fn(String),
+) -> Result<(), RebaseError> {
+    if commits.first().is_some_and(|c| c.step == Step::Squash) {
+        return Err(RebaseError::SquashFirst);
+    }
+    if commits
+        .iter()
+        .any(|c| matches!(&c.step, Step::Reword(m) if m.trim().is_empty()))
+    {
+        return Err(RebaseError::EmptyMessage);
+    }
+    if scan_git_status(path)? {
+        return Err(RebaseError::Dirty);
+    }
+    git_ops::snapshot(path, "rewrite commits").map_err(RebaseError::Git)?;
+
+    let git_dir = git_ops::open(path)
+        .map_err(RebaseError::Git)?
+        .path()
+        .to_path_buf();
+    let work_dir = git_dir.join("rustm-rebase");
+    fs::create_dir_all(&work_dir).map_err(RebaseError::Io)?;
+    let todo = todo_list(commits, &work_dir)?;
+    let todo_path = work_dir.join("todo");
+    fs::write(&todo_path, &todo).map_err(RebaseError::Io)?;
+    for line in todo.lines() {
+        log(line.to_string());
+    }
+
+    let sequence_editor = format!("cp {}", shell_quote(&todo_path));
+    let result = cargo_ops::run_tool(
+        "git",
+        path,
+        &["rebase", "-i", upstream],
+        &[
+            ("GIT_SEQUENCE_EDITOR", sequence_editor.as_str()),
+            ("GIT_EDITOR", "true"),
+        ],
+    );
+    let outcome = match result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            if git_dir.join("rebase-merge").exists() {
+                log("Aborting the rebase...".to_string());
+                let _ = cargo_ops::run_tool("git", path, &["rebase", "--abort"], &[]);
+            }
+            Err(RebaseError::Aborted(e.to_string()))
+        }
+    };
+    let _ = fs::remove_dir_all(&work_dir);
+    outcome
+}
+
+/// `git rebase -i` todo list for `commits`; reworded messages are written to `work_dir`.
+fn todo_list(commits: &[PlannedCommit], work_dir: &Path) -> Result<String, RebaseError> {
+    let mut todo = String::new();
+    for (i, commit) in commits.iter().enumerate() {
+        match &commit.step {
+            Step::Pick => todo.push_str(&format!("pick {} {}\n", commit.id, commit.summary)),
+            Step::Squash => todo.push_str(&format!("squash {} {}\n", commit.id, commit.summary)),
+            Step::Reword(message) => {
+                let file: PathBuf = work_dir.join(format!("message-{i}"));
+                fs::write(&file, message).map_err(RebaseError::Io)?;
+                todo.push_str(&format!("pick {} {}\n", commit.id, commit.summary));
+                todo.push_str(&format!(
+                    "exec git commit --amend --quiet --allow-empty -F {}\n",
+                    shell_quote(&file)
+                ));
+            }
+        }
+    }
+    Ok(todo)
+}
+
+/// Single-quote `path` for the shell git runs editors and `exec` lines with.
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.display().to_string().replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        cargo_ops::run_tool("git", dir, args, &[]).unwrap()
+    }
+
+    fn commit(dir: &Path, file: &str) {
+        fs::write(dir.join(file), file).unwrap();
+        git(dir, &["add", file]);
+        git(dir, &["commit", "-q", "-m", file]);
+    }
+
+    #[test]
+    fn reorders_squashes_and_rewords() {
+        let dir = TempDir::new("rebase");
+        git(&dir, &["init", "-q"]);
+        git(&dir, &["config", "user.name", "Test"]);
+        git(&dir, &["config", "user.email", "test@example.com"]);
+        commit(&dir, "base");
+        git(&dir, &["branch", "upstream"]);
+        git(&dir, &["branch", "-q", "--set-upstream-to=upstream"]);
+        commit(&dir, "one");
+        commit(&dir, "two");
+        commit(&dir, "three");
+
+        let (upstream, mut commits) = plan(&dir).unwrap();
+        assert_eq!(upstream, "upstream");
+        let summaries: Vec<&str> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, ["one", "two", "three"]);
+
+        // three, then one + two squashed, with three reworded.
+        commits.swap(0, 2);
+        commits.swap(1, 2);
+        commits[0].step = Step::Reword("third".into());
+        commits[2].step = Step::Squash;
+        assert!(matches!(
+            execute(&dir, &upstream, &[commits[2].clone()], &|_| {}),
+            Err(RebaseError::SquashFirst)
+        ));
+        execute(&dir, &upstream, &commits, &|_| {}).unwrap();
+
+        let log = git(&dir, &["log", "--format=%s", "upstream..HEAD"]);
+        assert_eq!(log.lines().collect::<Vec<_>>(), ["one", "third"]);
+        let squashed = git(&dir, &["log", "-1", "--format=%B"]);
+        assert!(squashed.contains("one") && squashed.contains("two"));
+        assert!(!squashed.contains('#'));
+        assert!(dir.join("two").exists());
+    }
+}
@@ -0,0 +1,444 @@
+//! Release artifact builder ("Build release artifacts").
+//!
+//! Builds a binary project for every configured target triple and collects the
+//! results into `<project>/dist/`:
+//! 1. Pick a build tool per target (`cargo` for the host; `cross` or `cargo-zigbuild`
+//!    when available for foreign targets, see `ReleaseBuilder`).
+//! 2. Build with `--release --target <triple>`, forcing symbol stripping through
+//!    `CARGO_PROFILE_RELEASE_STRIP` so every tool produces stripped binaries.
+//! 3. Copy each binary from cargo's target directory (as `cargo metadata`
+//!    reports it) to `dist/<bin>-<version>-<triple>[.exe]`. A binary the build
+//!    did not produce fails the target.
+//! 4. Write `dist/SHA256SUMS` for all collected files.
+//!
+//! A failing target does not abort the others; the caller receives one
+//! `TargetReport` per target. The files written to `dist/` are journaled in a
+//! `Transaction`: when the build itself fails (checksums cannot be written,
+//! panic), `dist/` is put back as it was instead of mixing new binaries with a
+//! stale `SHA256SUMS`.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+
+use crate::cargo_ops::{self, CargoError};
+use crate::config::{ReleaseBuilder, ReleaseSettings};
+use crate::project::manifest::{Manifest, ManifestError};
+use crate::project::workspace::{self, WorkspaceError};
+use crate::transaction::Transaction;
+
+/// Name of the output directory inside the project.
+pub const DIST_DIR: &str = "dist";
+
+/// Name of the checksum file inside `dist/`.
+pub const CHECKSUMS_FILE: &str = "SHA256SUMS";
+
+/// Concrete tool used for one target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildTool {
+    Cargo,
+    Cross,
+    Zigbuild,
+}
+
+impl BuildTool {
+    /// Program and leading arguments for `<tool> build`.
+    const fn invocation(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Cargo => ("cargo", "build"),
+            Self::Cross => ("cross", "build"),
+            Self::Zigbuild => ("cargo", "zigbuild"),
+        }
+    }
+}
+
+impl fmt::Display for BuildTool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cargo => write!(f, "cargo"),
+            Self::Cross => write!(f, "cross"),
+            Self::Zigbuild => write!(f, "cargo-zigbuild"),
+        }
+    }
+}
+
+/// Outcome of building one target.
+#[derive(Debug)]
+pub struct TargetReport {
+    pub target: String,
+    pub tool: BuildTool,
+    pub result: Result<Vec<PathBuf>, String>,
+}
+
+/// Full outcome of a release build.
+#[derive(Debug)]
+pub struct ReleaseReport {
+    pub targets: Vec<TargetReport>,
+    pub checksums: Option<PathBuf>,
+}
+
+/// Errors preventing the release build from starting at all.
+#[derive(Debug)]
+pub enum ReleaseError {
+    Manifest(ManifestError),
+    Workspace,
+    NoBinaries,
+    /// The target directory could not be determined.
+    TargetDirectory(WorkspaceError),
+    /// The build succeeded without producing these binaries.
+    MissingBinaries(Vec<String>),
+    Cargo(CargoError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for ReleaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Manifest(e) => write!(f, "{e}"),
+            Self::Workspace => write!(
+                f,
+                "Virtual workspaces are not supported; build a member crate instead"
+            ),
+            Self::NoBinaries => write!(f, "Project has no binary targets"),
+            Self::TargetDirectory(e) => write!(f, "Cannot locate the target directory: {e}"),
+            Self::MissingBinaries(bins) => {
+                write!(f, "The build did not produce {}", bins.join(", "))
+            }
+            Self::Cargo(e) => write!(f, "{e}"),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ReleaseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Manifest(e) => Some(e),
+            Self::TargetDirectory(e) => Some(e),
+            Self::Cargo(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::Workspace | Self::NoBinaries | Self::MissingBinaries(_) => None,
+        }
+    }
+}
+
+/// Build all configured targets, reporting progress lines through `log`.
+pub fn build_all(
+    project_path: &Path,
+    settings: &ReleaseSettings,
+    log: &dyn Fn(String),
+) -> Result<ReleaseReport, ReleaseError> {
+    let manifest = Manifest::read(project_path).map_err(ReleaseError::Manifest)?;
+    let Some(package) = manifest.package_name() else {
+        return Err(ReleaseError::Workspace);
+    };
+    let bins = binary_names(project_path, &manifest, package);
+    if bins.is_empty() {
+        return Err(ReleaseError::NoBinaries);
+    }
+    let version = manifest.package_version().unwrap_or("0.0.0");
+
+    let host = cargo_ops::host_triple().map_err(ReleaseError::Cargo)?;
+    let targets = if settings.targets.is_empty() {
+        vec![host.clone()]
+    } else {
+        settings.targets.clone()
+    };
+    let target_dir =
+        workspace::target_directory(project_path).map_err(ReleaseError::TargetDirectory)?;
+
+    let dist = project_path.join(DIST_DIR);
+    let mut transaction = Transaction::new(format!("Release build of {}", project_path.display()));
+    transaction
+        .create_dir_all(&dist)
+        .map_err(ReleaseError::Io)?;
+
+    let mut reports = Vec::new();
+    for target in targets {
+        let tool = choose_tool(
+            settings.builder,
+            &target,
+            &host,
+            cargo_ops::tool_available("cross"),
+            cargo_ops::tool_available("cargo-zigbuild"),
+        );
+        log(format!("Building {target} with {tool}..."));
+        let result = build_target(project_path, &target, tool)
+            .and_then(|()| {
+                collect_binaries(
+                    &mut transaction,
+                    &target_dir,
+                    &dist,
+                    &bins,
+                    version,
+                    &target,
+                )
+            })
+            .map_err(|e| e.to_string());
+        match &result {
+            Ok(files) => log(format!("  ok ({} file(s))", files.len())),
+            Err(e) => {
+                warn!("Release build for {target} failed: {e}");
+                log(format!("  failed: {e}"));
+            }
+        }
+        reports.push(TargetReport {
+            target,
+            tool,
+            result,
+        });
+    }
+
+    let artifacts: Vec<PathBuf> = reports
+        .iter()
+        .filter_map(|r| r.result.as_ref().ok())
+        .flatten()
+        .cloned()
+        .collect();
+    let checksums = if artifacts.is_empty() {
+        None
+    } else {
+        Some(write_checksums(&mut transaction, &dist, &artifacts).map_err(ReleaseError::Io)?)
+    };
+    transaction.commit();
+
+    info!(
+        "Release build finished for {}: {} artifact(s)",
+        project_path.display(),
+        artifacts.len()
+    );
+    Ok(ReleaseReport {
+        targets: reports,
+        checksums,
+    })
+}
+
+/// Decide which tool builds `target`.
+pub fn choose_tool(
+    builder: ReleaseBuilder,
+    target: &str,
+    host: &str,
+    cross_available: bool,
+    zigbuild_available: bool,
+) -> BuildTool {
+    match builder {
+        ReleaseBuilder::Cargo => BuildTool::Cargo,
+        ReleaseBuilder::Cross => BuildTool::Cross,
+        ReleaseBuilder::Zigbuild => BuildTool::Zigbuild,
+        ReleaseBuilder::Auto if target == host => BuildTool::Cargo,
+        ReleaseBuilder::Auto if cross_available => BuildTool::Cross,
+        ReleaseBuilder::Auto if zigbuild_available => BuildTool::Zigbuild,
+        ReleaseBuilder::Auto => BuildTool::Cargo,
+    }
+}
+
+/// Binary names produced by the package: `[[bin]]` entries, `src/main.rs` and `src/bin/*.rs`.
+pub fn binary_names(project_path: &Path, manifest: &Manifest, package: &str) -> Vec<String> {
+    let mut names = manifest.bin_target_names();
+    if project_path.join("src").join("main.rs").is_file() {
+        names.push(package.to_string());
+    }
+    if let Ok(entries) = fs::read_dir(project_path.join("src").join("bin")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|e| e == "rs")
+                && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+            {
+                names.push(stem.to_string());
+            }
+        }
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn build_target(project_path: &Path, target: &str, tool: BuildTool) -> Result<(), ReleaseError> {
+    let (program, subcommand) = tool.invocation();
+    cargo_ops::run_tool(
+        program,
+        project_path,
+        &[subcommand, "--release", "--target", target],
+        &[("CARGO_PROFILE_RELEASE_STRIP", "symbols")],
+    )
+    .map(|_| ())
+    .map_err(ReleaseError::Cargo)
+}
+
+/// Copy the `bins` built for `target` under `target_dir` to `dist`.
+fn collect_binaries(
+    transaction: &mut Transaction,
+    target_dir: &Path,
+    dist: &Path,
+    bins: &[String],
+    version: &str,
+    target: &str,
+) -> Result<Vec<PathBuf>, ReleaseError> {
+    let release_dir = target_dir.join(target).join("release");
+    let suffix = if target.contains("windows") {
+        ".exe"
+    } else {
+        ""
+    };
+
+    let missing: Vec<String> = bins
+        .iter()
+        .filter(|bin| !release_dir.join(format!("{bin}{suffix}")).is_file())
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        return Err(ReleaseError::MissingBinaries(missing));
+    }
+
+    let mut files = Vec::new();
+    for bin in bins {
+        let built = release_dir.join(format!("{bin}{suffix}"));
+        let dest = dist.join(artifact_name(bin, version, target));
+        transaction.preserve(&dest).map_err(ReleaseError::Io)?;
+        fs::copy(&built, &dest).map_err(ReleaseError::Io)?;
+        files.push(dest);
+    }
+    Ok(files)
+}
+
+/// `dist/` file name for a binary.
+pub fn artifact_name(bin: &str, version: &str, target: &str) -> String {
+    let suffix = if target.contains("windows") {
+        ".exe"
+    } else {
+        ""
+    };
+    format!("{bin}-{version}-{target}{suffix}")
+}
+
+/// Write `SHA256SUMS` (`<hex digest>  <file name>` per line) for `files` into `dist`.
+fn write_checksums(
+    transaction: &mut Transaction,
+    dist: &Path,
+    files: &[PathBuf],
+) -> std::io::Result<PathBuf> {
+    let mut out = String::new();
+    for file in files {
+        let digest = sha256_hex(&fs::read(file)?);
+        let name = file
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        out.push_str(&format!("{digest}  {name}\n"));
+    }
+    let path = dist.join(CHECKSUMS_FILE);
+    transaction.preserve(&path)?;
+    fs::write(&path, out)?;
+    Ok(path)
+}
+
+/// Lowercase hex SHA-256 digest of `data`.
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{self, FakeRunner};
+    use crate::temp_dir::TempDir;
+    use std::rc::Rc;
+
+    #[test]
+    fn auto_builder_prefers_native_then_cross_then_zigbuild() {
+        let host = "x86_64-unknown-linux-gnu";
+        let auto = ReleaseBuilder::Auto;
+        assert_eq!(choose_tool(auto, host, host, true, true), BuildTool::Cargo);
+        assert_eq!(
+            choose_tool(auto, "aarch64-unknown-linux-gnu", host, true, true),
+            BuildTool::Cross
+        );
+        assert_eq!(
+            choose_tool(auto, "aarch64-unknown-linux-gnu", host, false, true),
+            BuildTool::Zigbuild
+        );
+        assert_eq!(
+            choose_tool(auto, "aarch64-unknown-linux-gnu", host, false, false),
+            BuildTool::Cargo
+        );
+    }
+
+    #[test]
+    fn artifact_names_and_checksums() {
+        assert_eq!(
+            artifact_name("tool", "1.0.0", "x86_64-pc-windows-msvc"),
+            "tool-1.0.0-x86_64-pc-windows-msvc.exe"
+        );
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    /// Binary project `demo` in a temp dir, with cargo's replies up to the
+    /// build queued on `fake`: its release dir stays inside the project.
+    fn demo_project(fake: &FakeRunner, host: &str) -> TempDir {
+        let project = TempDir::new("release");
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(project.join("src/main.rs"), "fn main() {}").unwrap();
+        fake.succeed(&format!("rustc 1.90.0\nhost: {host}\n"));
+        let metadata = serde_json::json!({
+            "packages": [],
+            "target_directory": project.join("target"),
+        });
+        fake.succeed(&metadata.to_string());
+        fake.succeed("");
+        project
+    }
+
+    #[test]
+    fn failed_build_restores_dist() {
+        let host = "x86_64-unknown-linux-gnu";
+        let fake = Rc::new(FakeRunner::default());
+        let _runner = process::replace(fake.clone());
+        let project = demo_project(&fake, host);
+        let release_dir = project.join("target").join(host).join("release");
+        fs::create_dir_all(&release_dir).unwrap();
+        fs::write(release_dir.join("demo"), "new binary").unwrap();
+        // An earlier build of the same version, and a checksum file that
+        // cannot be replaced.
+        let dist = project.join(DIST_DIR);
+        let artifact = dist.join(artifact_name("demo", "0.1.0", host));
+        fs::create_dir_all(dist.join(CHECKSUMS_FILE)).unwrap();
+        fs::write(&artifact, "old binary").unwrap();
+
+        let settings = ReleaseSettings::default();
+        let log = |_: String| {};
+        assert!(matches!(
+            build_all(&project, &settings, &log),
+            Err(ReleaseError::Io(_))
+        ));
+        assert_eq!(fs::read_to_string(&artifact).unwrap(), "old binary");
+    }
+
+    #[test]
+    fn binaries_missing_after_the_build_fail_the_target() {
+        let host = "x86_64-unknown-linux-gnu";
+        let fake = Rc::new(FakeRunner::default());
+        let _runner = process::replace(fake.clone());
+        let project = demo_project(&fake, host);
+
+        let settings = ReleaseSettings::default();
+        let log = |_: String| {};
+        let report = build_all(&project, &settings, &log).unwrap();
+        let error = report.targets[0].result.as_ref().unwrap_err();
+        assert_eq!(error, "The build did not produce demo");
+        assert!(report.checksums.is_none());
+    }
+}
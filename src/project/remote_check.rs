@@ -0,0 +1,187 @@
+//! Checks on remote build hosts.
+//!
+//! A poor man's cross-platform CI: the configured runners (`remote_runners`,
+//! an SSH host and a directory on it) each get a copy of the project with
+//! `rsync` (`target` and `.git` left out; the copy is reused and updated by
+//! the next check), then run `cargo check` in it over `ssh`. The runners are
+//! checked one after the other and every outcome is reported, a failing
+//! runner not stopping the others.
+//!
+//! `ssh` runs in batch mode: a host needing a password or an unknown host key
+//! fails instead of waiting on a prompt the TUI cannot show. `~/.cargo/bin`
+//! is added to the remote `PATH`, which non-interactive shells often lack.
+
+use std::fmt;
+use std::path::Path;
+
+use crate::cargo_ops::{self, CargoError};
+use crate::config::RemoteRunner;
+
+/// Directories not copied to the runners.
+const EXCLUDED: [&str; 2] = ["/target", "/.git"];
+
+/// Lines of error output kept per runner.
+const ERROR_TAIL: usize = 40;
+
+/// Options making `ssh` fail instead of prompting.
+const SSH_OPTIONS: [&str; 4] = ["-o", "BatchMode=yes", "-o", "ConnectTimeout=10"];
+
+/// Prefix of remote commands needing the toolchain.
+const CARGO_PATH: &str = "PATH=\"$HOME/.cargo/bin:$PATH\"";
+
+/// Exit code of `ssh` itself failing (connection, authentication).
+const SSH_FAILURE: i32 = 255;
+
+#[derive(Debug)]
+pub enum RemoteCheckError {
+    /// `ssh` or `rsync` is not installed locally.
+    ToolMissing(&'static str),
+}
+
+impl fmt::Display for RemoteCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ToolMissing(tool) => write!(f, "`{tool}` is needed to reach the runners"),
+        }
+    }
+}
+
+impl std::error::Error for RemoteCheckError {}
+
+/// How the check went on one runner.
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Passed,
+    /// `cargo check` failed: the end of its output.
+    Failed(String),
+    /// The host could not be reached, or the copy failed.
+    Unreachable(String),
+}
+
+/// Result of one runner.
+#[derive(Debug, Clone)]
+pub struct RunnerResult {
+    pub runner: RemoteRunner,
+    /// Host triple of the runner's toolchain, when reached.
+    pub platform: Option<String>,
+    pub outcome: Outcome,
+}
+
+/// Check the project at `path` on every runner; `log` gets each step.
+pub fn check(
+    path: &Path,
+    runners: &[RemoteRunner],
+    log: &dyn Fn(String),
+) -> Result<Vec<RunnerResult>, RemoteCheckError> {
+    for tool in ["ssh", "rsync"] {
+        if !cargo_ops::tool_available(tool) {
+            return Err(RemoteCheckError::ToolMissing(tool));
+        }
+    }
+    let name = path
+        .file_name()
+        .map_or_else(|| "project".into(), |n| n.to_string_lossy());
+    Ok(runners
+        .iter()
+        .map(|runner| check_runner(path, &name, runner, log))
+        .collect())
+}
+
+fn check_runner(
+    path: &Path,
+    name: &str,
+    runner: &RemoteRunner,
+    log: &dyn Fn(String),
+) -> RunnerResult {
+    let dir = remote_dir(&runner.path, name);
+    let result = |platform, outcome| RunnerResult {
+        runner: runner.clone(),
+        platform,
+        outcome,
+    };
+
+    log(format!("{}: preparing {dir}", runner.name));
+    let prepare = format!("mkdir -p {} && {CARGO_PATH} rustc -vV", shell_quote(&dir));
+    let platform = match ssh(path, &runner.host, &prepare) {
+        Ok(version) => host_triple(&version),
+        Err(e) => return result(None, Outcome::Unreachable(error_text(&e))),
+    };
+
+    log(format!("{}: copying the project", runner.name));
+    let mut args = vec!["-az", "--delete", "-e", "ssh -o BatchMode=yes"];
+    for excluded in EXCLUDED {
+        args.extend(["--exclude", excluded]);
+    }
+    let destination = format!("{}:{dir}/", runner.host);
+    args.extend(["./", &destination]);
+    if let Err(e) = cargo_ops::run_tool("rsync", path, &args, &[]) {
+        return result(platform, Outcome::Unreachable(error_text(&e)));
+    }
+
+    log(format!("{}: cargo check", runner.name));
+    let check = format!("cd {} && {CARGO_PATH} cargo check", shell_quote(&dir));
+    let outcome = match ssh(path, &runner.host, &check) {
+        Ok(_) => Outcome::Passed,
+        Err(CargoError::Failed { status, stderr }) if status != SSH_FAILURE => {
+            Outcome::Failed(tail(&stderr))
+        }
+        Err(e) => Outcome::Unreachable(error_text(&e)),
+    };
+    result(platform, outcome)
+}
+
+fn ssh(path: &Path, host: &str, command: &str) -> Result<String, CargoError> {
+    let mut args = SSH_OPTIONS.to_vec();
+    args.extend([host, command]);
+    cargo_ops::run_tool("ssh", path, &args, &[])
+}
+
+/// Copy of `project` under the runner's `path` (relative paths are in the remote home).
+fn remote_dir(path: &str, project: &str) -> String {
+    format!("{}/{project}", path.trim().trim_end_matches('/'))
+}
+
+/// `value` quoted for a POSIX shell; a leading `~/` is left to the shell.
+fn shell_quote(value: &str) -> String {
+    match value.strip_prefix("~/") {
+        Some(rest) => format!("~/{}", shell_quote(rest)),
+        None => format!("'{}'", value.replace('\'', r"'\''")),
+    }
+}
+
+/// `host: x86_64-apple-darwin` line of `rustc -vV`.
+fn host_triple(version: &str) -> Option<String> {
+    version
+        .lines()
+        .find_map(|l| l.strip_prefix("host: "))
+        .map(|t| t.trim().to_string())
+}
+
+fn error_text(e: &CargoError) -> String {
+    match e {
+        CargoError::Failed { stderr, .. } => tail(stderr),
+        e => e.to_string(),
+    }
+}
+
+/// Last `ERROR_TAIL` lines of `output`.
+fn tail(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().collect();
+    lines[lines.len().saturating_sub(ERROR_TAIL)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_remote_commands() {
+        assert_eq!(remote_dir("builds/", "demo"), "builds/demo");
+        assert_eq!(shell_quote("builds/it's"), r"'builds/it'\''s'");
+        assert_eq!(shell_quote("~/builds/demo"), "~/'builds/demo'");
+        assert_eq!(
+            host_triple("rustc 1.90.0\nbinary: rustc\nhost: aarch64-apple-darwin\n").as_deref(),
+            Some("aarch64-apple-darwin")
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Workspace status rollup.
+//!
+//! Summarises the health of every member of a workspace so a large monorepo is
+//! described by a single list entry:
+//! - members failing `cargo check`,
+//! - outdated registry dependencies (requirement not matching the newest
+//!   crates.io release), summed over members,
+//! - members without any test (no `tests/` directory, no `#[test]` in `src/`).
+//!
+//! Computing it runs cargo and queries crates.io, so it is an explicit action;
+//! the result is remembered in the metadata store and shown by the project list.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use log::warn;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::cargo_ops;
+use crate::crates_io;
+use crate::metadata;
+use crate::project::workspace::{self, WorkspaceError};
+
+/// Aggregated status of a workspace.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceRollup {
+    pub members: usize,
+    /// Names of members whose `cargo check` failed.
+    pub failing_check: Vec<String>,
+    /// Outdated registry dependencies, summed over members.
+    pub outdated_deps: usize,
+    /// Names of members without tests.
+    pub missing_tests: Vec<String>,
+    /// Seconds since epoch when the rollup was computed.
+    pub computed_at: u64,
+}
+
+impl WorkspaceRollup {
+    /// One-line summary, e.g. `4 members: 1 failing check, 3 outdated deps, 2 without tests`.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.failing_check.is_empty() {
+            parts.push(format!("{} failing check", self.failing_check.len()));
+        }
+        if self.outdated_deps > 0 {
+            parts.push(format!("{} outdated deps", self.outdated_deps));
+        }
+        if !self.missing_tests.is_empty() {
+            parts.push(format!("{} without tests", self.missing_tests.len()));
+        }
+        if parts.is_empty() {
+            parts.push("all good".into());
+        }
+        format!("{} members: {}", self.members, parts.join(", "))
+    }
+}
+
+/// Compute the rollup for the workspace at `project_path`, reporting progress through `log`.
+pub fn compute(
+    project_path: &Path,
+    log: &dyn Fn(String),
+) -> Result<WorkspaceRollup, WorkspaceError> {
+    let members = workspace::members(project_path)?;
+    let mut rollup = WorkspaceRollup {
+        members: members.len(),
+        computed_at: metadata::now(),
+        ..WorkspaceRollup::default()
+    };
+    // Several members usually share dependencies: look each crate up once.
+    let mut latest: HashMap<String, Option<Version>> = HashMap::new();
+
+    for member in &members {
+        log(format!("Checking {}...", member.name));
+        if let Err(e) = cargo_ops::run(
+            project_path,
+            &["check", "--all-targets", "-p", &member.name],
+        ) {
+            warn!("cargo check failed for member {}: {e}", member.name);
+            rollup.failing_check.push(member.name.clone());
+        }
+
+        for dep in member.dependencies.iter().filter(|d| d.from_registry) {
+            let newest = latest.entry(dep.name.clone()).or_insert_with(|| {
+                crates_io::latest_version(&dep.name)
+                    .inspect_err(|e| warn!("Cannot look up {} on crates.io: {e}", dep.name))
+                    .ok()
+            });
+            if newest.as_ref().is_some_and(|v| is_outdated(&dep.req, v)) {
+                rollup.outdated_deps += 1;
+            }
+        }
+
+        if !has_tests(&member.dir) {
+            rollup.missing_tests.push(member.name.clone());
+        }
+    }
+
+    Ok(rollup)
+}
+
+/// `true` when `req` does not accept `latest` (unparsable requirements are not counted).
+fn is_outdated(req: &str, latest: &Version) -> bool {
+    VersionReq::parse(req).is_ok_and(|r| !r.matches(latest))
+}
+
+/// `true` when the crate at `dir` has integration tests or unit tests in `src/`.
+fn has_tests(dir: &Path) -> bool {
+    let integration =
+        fs::read_dir(dir.join("tests")).is_ok_and(|mut entries| entries.next().is_some());
+    integration || contains_test_attr(&dir.join("src"))
+}
+
+fn contains_test_attr(dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        if path.is_dir() {
+            contains_test_attr(&path)
+        } else {
+            path.extension().is_some_and(|e| e == "rs")
+                && fs::read_to_string(&path).is_ok_and(|src| src.contains("#[test]"))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    /// Project directory with `src/nested/`.
+    fn project_dir() -> TempDir {
+        let d = TempDir::new("rollup");
+        fs::create_dir_all(d.join("src").join("nested")).unwrap();
+        d
+    }
+
+    #[test]
+    fn outdated_requirements_and_test_detection() {
+        let latest = Version::new(2, 1, 0);
+        assert!(is_outdated("^1.0", &latest));
+        assert!(!is_outdated("2", &latest));
+        assert!(!is_outdated("not a req", &latest));
+
+        let dir = project_dir();
+        fs::write(dir.join("src/lib.rs"), "pub fn f() {}").unwrap();
+        assert!(!has_tests(&dir));
+        fs::write(dir.join("src/nested/m.rs"), "#[test]\nfn t() {}").unwrap();
+        assert!(has_tests(&dir));
+    }
+}
@@ -0,0 +1,193 @@
+//! Container build sandbox.
+//!
+//! With `sandbox.enabled`, the builds and tests of the project menu run in a
+//! throwaway container of `sandbox.image` (`docker run --rm`, or `podman`)
+//! instead of on the host: a build script or proc macro of untrusted code only
+//! reaches the project directory, and every machine builds with the same
+//! toolchain.
+//!
+//! The project is mounted read-write at `/work`. Builds go to
+//! `target/sandbox`, apart from the host's own builds. The cargo registry and
+//! git checkouts are kept across runs in `CARGO_HOME`: a directory of the user
+//! cache per project (`<cache>/rustm/sandbox-cargo/<name>-<hash of the path>`),
+//! or `target/sandbox/cargo` for projects in WSL (see `crate::wsl`), where the
+//! containers run inside the distribution. It is never shared: a build script
+//! could otherwise rewrite the registry sources other projects compile. Files are written as the user
+//! owning the project (`--user` for docker, `--userns=keep-id` for podman).
+
+use std::path::{Path, PathBuf};
+
+use crate::cargo_ops::{self, CargoError};
+use crate::config::{ContainerRuntime, SandboxSettings};
+use crate::project::release;
+use crate::wsl;
+
+/// Mount point of the project in the container.
+const WORKDIR: &str = "/work";
+
+/// Mount point of the cargo cache of the project in the container.
+const CARGO_HOME: &str = "/cargo";
+
+/// Program running the containers: `podman` when installed for `Auto`, else `docker`.
+pub fn runtime(settings: &SandboxSettings) -> &'static str {
+    match settings.runtime {
+        ContainerRuntime::Docker => "docker",
+        ContainerRuntime::Podman => "podman",
+        ContainerRuntime::Auto if cargo_ops::tool_available("podman") => "podman",
+        ContainerRuntime::Auto => "docker",
+    }
+}
+
+/// Run `cargo <args>` for the project at `path` in a container, streaming its output to `log`.
+///
+/// Returns whether the command succeeded, as `cargo_ops::run_tool_streaming`.
+pub fn run_cargo_streaming(
+    settings: &SandboxSettings,
+    path: &Path,
+    args: &[&str],
+    log: &dyn Fn(String),
+) -> Result<bool, CargoError> {
+    let runtime = runtime(settings);
+    let (project, cache) = match wsl::locate(path) {
+        Some(wsl) => (wsl.path, None),
+        None => {
+            let cache = cache_dir(path);
+            std::fs::create_dir_all(&cache).map_err(CargoError::Io)?;
+            (
+                path.display().to_string(),
+                Some(cache.display().to_string()),
+            )
+        }
+    };
+    let run = run_args(
+        runtime,
+        &settings.image,
+        &project,
+        cache.as_deref(),
+        owner(path),
+        args,
+    );
+    log(format!("{runtime} {}", run.join(" ")));
+    let run: Vec<&str> = run.iter().map(String::as_str).collect();
+    cargo_ops::run_tool_streaming(runtime, path, &run, log)
+}
+
+/// Arguments of `<runtime> run` building in `project` (a host path) with `cargo <args>`.
+fn run_args(
+    runtime: &str,
+    image: &str,
+    project: &str,
+    cache: Option<&str>,
+    owner: Option<(u32, u32)>,
+    args: &[&str],
+) -> Vec<String> {
+    // SELinux hosts only let podman containers read relabeled mounts.
+    let mount = |source: &str, target: &str| match runtime {
+        "podman" => format!("{source}:{target}:z"),
+        _ => format!("{source}:{target}"),
+    };
+    let mut run: Vec<String> = vec!["run".into(), "--rm".into()];
+    match (runtime, owner) {
+        ("podman", _) => run.push("--userns=keep-id".into()),
+        (_, Some((uid, gid))) => run.extend(["--user".into(), format!("{uid}:{gid}")]),
+        (_, None) => {}
+    }
+    run.extend([
+        "-v".into(),
+        mount(project, WORKDIR),
+        "-w".into(),
+        WORKDIR.into(),
+        "-e".into(),
+        format!("CARGO_TARGET_DIR={WORKDIR}/target/sandbox"),
+    ]);
+    match cache {
+        Some(cache) => run.extend([
+            "-v".into(),
+            mount(cache, CARGO_HOME),
+            "-e".into(),
+            format!("CARGO_HOME={CARGO_HOME}"),
+        ]),
+        None => run.extend([
+            "-e".into(),
+            format!("CARGO_HOME={WORKDIR}/target/sandbox/cargo"),
+        ]),
+    }
+    run.extend([image.trim().to_string(), "cargo".into()]);
+    run.extend(args.iter().map(ToString::to_string));
+    run
+}
+
+/// Cargo cache of the containers building the project at `path`.
+fn cache_dir(path: &Path) -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("rustm")
+        .join("sandbox-cargo")
+        .join(cache_name(path))
+}
+
+/// `<name>-<hash>`: projects of the same name in different directories get
+/// different caches.
+fn cache_name(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let hash = release::sha256_hex(path.to_string_lossy().as_bytes());
+    format!("{name}-{}", &hash[..12])
+}
+
+/// User and group owning `path`, which the container runs as.
+#[cfg(unix)]
+fn owner(path: &Path) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.uid(), metadata.gid()))
+}
+
+/// Docker Desktop maps the files to the user itself.
+#[cfg(not(unix))]
+fn owner(_path: &Path) -> Option<(u32, u32)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_container_arguments() {
+        let docker = run_args(
+            "docker",
+            "rust:1.90",
+            "/home/me/demo",
+            Some("/home/me/.cache/rustm/sandbox-cargo/demo-1a2b"),
+            Some((1000, 1000)),
+            &["test"],
+        );
+        assert_eq!(
+            docker.join(" "),
+            "run --rm --user 1000:1000 -v /home/me/demo:/work -w /work \
+             -e CARGO_TARGET_DIR=/work/target/sandbox \
+             -v /home/me/.cache/rustm/sandbox-cargo/demo-1a2b:/cargo -e CARGO_HOME=/cargo \
+             rust:1.90 cargo test"
+        );
+
+        let podman = run_args("podman", "rust", "/home/me/demo", None, None, &["build"]);
+        assert_eq!(
+            podman.join(" "),
+            "run --rm --userns=keep-id -v /home/me/demo:/work:z -w /work \
+             -e CARGO_TARGET_DIR=/work/target/sandbox \
+             -e CARGO_HOME=/work/target/sandbox/cargo rust cargo build"
+        );
+    }
+
+    #[test]
+    fn each_project_gets_its_own_cargo_cache() {
+        let first = cache_dir(Path::new("/home/me/a/demo"));
+        let second = cache_dir(Path::new("/home/me/b/demo"));
+        assert_ne!(first, second);
+        assert!(first.ends_with(cache_name(Path::new("/home/me/a/demo"))));
+        assert!(cache_name(Path::new("/home/me/a/demo")).starts_with("demo-"));
+    }
+}
@@ -0,0 +1,648 @@
+//! Code scaffolding for an existing project.
+//!
+//! Generates skeleton files from templates (`crate::template`):
+//! - an integration test, `tests/<name>.rs`: it calls into the library when
+//!   the package has one, otherwise runs the binary
+//!   (`CARGO_BIN_EXE_<bin>`);
+//! - a `#[cfg(test)] mod tests` block appended to a chosen source file;
+//! - a Criterion benchmark, `benches/<name>.rs`, with its `[[bench]]` entry
+//!   (`harness = false`) and the `criterion` dev-dependency;
+//! - an example, `examples/<name>.rs`, or an extra binary, `src/bin/<name>.rs`,
+//!   with its `[[example]]` / `[[bin]]` entry;
+//! - a module, `src/<name>.rs` or `src/<name>/mod.rs`, declared in the crate
+//!   root (`lib.rs`, else `main.rs`) next to the other `mod` declarations.
+//!
+//! Existing files, targets and test modules are never overwritten. Manifest
+//! changes go through `Manifest::edit`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use log::warn;
+
+use crate::config::ModuleStyle;
+use crate::crates_io;
+use crate::project::manifest::{DependencyKind, Manifest, ManifestError};
+use crate::template::{self, TemplateError};
+
+const LIB_TEST_TEMPLATE: &str = "//! Integration tests: {{name}}.
+
+use {{crate}} as _;
+
+#[test]
+fn {{name}}() {
+    // Call the public API of `{{crate}}` and assert on the results.
+}
+";
+
+const BIN_TEST_TEMPLATE: &str = "//! Integration tests: {{name}}.
+
+use std::process::Command;
+
+#[test]
+fn {{name}}() {
+    let output = Command::new(env!(\"CARGO_BIN_EXE_{{bin}}\"))
+        .output()
+        .expect(\"failed to run {{bin}}\");
+    assert!(
+        output.status.success(),
+        \"{}\",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+";
+
+const UNIT_TESTS_TEMPLATE: &str = "
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn {{name}}() {
+    }
+}
+";
+
+const BENCH_TEMPLATE: &str = "use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+
+fn {{name}}(c: &mut Criterion) {
+    // Replace the closure body with the code to measure.
+    c.bench_function(\"{{name}}\", |b| b.iter(|| black_box(2 + 2)));
+}
+
+criterion_group!(benches, {{name}});
+criterion_main!(benches);
+";
+
+const EXAMPLE_TEMPLATE: &str = "//! Example {{name}}: run with `cargo run --example {{name}}`.
+
+use {{crate}} as _;
+
+fn main() {
+    // Show how to use the public API of `{{crate}}`.
+}
+";
+
+const MAIN_TEMPLATE: &str = "//! {{name}}: run with `cargo run --{{kind}} {{name}}`.
+
+fn main() {
+    println!(\"Hello from {{name}}!\");
+}
+";
+
+const MODULE_TEMPLATE: &str = "//! {{name}} module.
+";
+
+/// Crate roots a module can be declared in, by preference.
+const CRATE_ROOTS: [&str; 2] = ["src/lib.rs", "src/main.rs"];
+
+/// Rust keywords, not usable as names.
+const KEYWORDS: [&str; 51] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// `criterion` requirement used when crates.io cannot be reached.
+const CRITERION_FALLBACK_REQ: &str = "0.7";
+
+/// Errors raised while scaffolding.
+#[derive(Debug)]
+pub enum ScaffoldError {
+    Io(io::Error),
+    Manifest(ManifestError),
+    Template(TemplateError),
+    /// The manifest has no `[package]` (virtual workspace).
+    NoPackage,
+    /// Not a valid Rust identifier.
+    InvalidName(String),
+    /// The file to create already exists.
+    Exists(PathBuf),
+    /// The source file already has a test module.
+    HasTestModule(PathBuf),
+    /// `Cargo.toml` already declares a target of that name (`[[bench]]`, ...).
+    TargetExists(String),
+    /// Neither `src/lib.rs` nor `src/main.rs` exists.
+    NoCrateRoot,
+    /// The crate root already declares a module of that name.
+    ModuleDeclared(String),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Manifest(e) => write!(f, "{e}"),
+            Self::Template(e) => write!(f, "{e}"),
+            Self::NoPackage => write!(f, "Cargo.toml has no [package] section"),
+            Self::InvalidName(name) => write!(
+                f,
+                "'{name}' is not a valid name: lowercase letters, digits and '_', not starting with a digit"
+            ),
+            Self::Exists(p) => write!(f, "{} already exists", p.display()),
+            Self::HasTestModule(p) => write!(f, "{} already has a test module", p.display()),
+            Self::TargetExists(name) => write!(f, "Cargo.toml already declares a target '{name}'"),
+            Self::NoCrateRoot => write!(f, "Neither src/lib.rs nor src/main.rs exists"),
+            Self::ModuleDeclared(name) => write!(f, "A module '{name}' is already declared"),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Manifest(e) => Some(e),
+            Self::Template(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ScaffoldError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ManifestError> for ScaffoldError {
+    fn from(e: ManifestError) -> Self {
+        Self::Manifest(e)
+    }
+}
+
+impl From<TemplateError> for ScaffoldError {
+    fn from(e: TemplateError) -> Self {
+        Self::Template(e)
+    }
+}
+
+/// Create `tests/<name>.rs` in the project at `path`; returns the path relative to the project.
+pub fn integration_test(path: &Path, name: &str) -> Result<String, ScaffoldError> {
+    validate_ident(name)?;
+    let manifest = Manifest::read(path)?;
+    let package = manifest.package_name().ok_or(ScaffoldError::NoPackage)?;
+    let mut vars = BTreeMap::from([("name", name.to_string())]);
+    let template = if path.join("src/lib.rs").is_file() || manifest.has_lib_section() {
+        vars.insert("crate", package.replace('-', "_"));
+        LIB_TEST_TEMPLATE
+    } else {
+        let bin = manifest
+            .bin_target_names()
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| package.to_string());
+        vars.insert("bin", bin);
+        BIN_TEST_TEMPLATE
+    };
+    let relative = format!("tests/{name}.rs");
+    create_new(&path.join(&relative), &template::render(template, &vars)?)?;
+    Ok(relative)
+}
+
+/// Append a `#[cfg(test)] mod tests` block with a `name` test to `file` (relative to the project).
+pub fn unit_tests(path: &Path, file: &str, name: &str) -> Result<(), ScaffoldError> {
+    validate_ident(name)?;
+    let target = path.join(file);
+    let code = fs::read_to_string(&target)?;
+    if code.contains("#[cfg(test)]") || code.contains("mod tests") {
+        return Err(ScaffoldError::HasTestModule(PathBuf::from(file)));
+    }
+    let vars = BTreeMap::from([("name", name.to_string())]);
+    let block = template::render(UNIT_TESTS_TEMPLATE, &vars)?;
+    let mut out = fs::OpenOptions::new().append(true).open(&target)?;
+    if !code.is_empty() && !code.ends_with('\n') {
+        out.write_all(b"\n")?;
+    }
+    out.write_all(block.as_bytes())?;
+    Ok(())
+}
+
+/// Set up the Criterion benchmark `name` in the project at `path`; returns what was done.
+///
+/// `criterion_req` is the version requirement added when `criterion` is not a dependency yet.
+pub fn benchmark(path: &Path, name: &str, criterion_req: &str) -> Result<String, ScaffoldError> {
+    validate_ident(name)?;
+    let manifest = Manifest::read(path)?;
+    if manifest.package_name().is_none() {
+        return Err(ScaffoldError::NoPackage);
+    }
+    if manifest.target_names("bench").iter().any(|n| n == name) {
+        return Err(ScaffoldError::TargetExists(name.to_string()));
+    }
+    let relative = format!("benches/{name}.rs");
+    let vars = BTreeMap::from([("name", name.to_string())]);
+    create_new(
+        &path.join(&relative),
+        &template::render(BENCH_TEMPLATE, &vars)?,
+    )?;
+    let added_dependency = Manifest::edit(path, |m| {
+        let added = !m.has_dependency("criterion")
+            && m.add_dependency(DependencyKind::Dev, "criterion", criterion_req);
+        m.add_target("bench", name, &[("harness", false.into())]);
+        added
+    })?;
+    let mut done = format!("Created {relative} and its [[bench]] entry");
+    if added_dependency {
+        done.push_str(&format!(
+            ", added criterion {criterion_req} to [dev-dependencies]"
+        ));
+    }
+    Ok(done)
+}
+
+/// Create `examples/<name>.rs` and its `[[example]]` entry; returns the path relative to the project.
+pub fn example(path: &Path, name: &str) -> Result<String, ScaffoldError> {
+    add_target(path, "example", name, &format!("examples/{name}.rs"))
+}
+
+/// Create `src/bin/<name>.rs` and its `[[bin]]` entry; returns the path relative to the project.
+pub fn binary(path: &Path, name: &str) -> Result<String, ScaffoldError> {
+    add_target(path, "bin", name, &format!("src/bin/{name}.rs"))
+}
+
+/// Create the `section` target `name` at `relative` and declare it in `Cargo.toml`.
+fn add_target(
+    path: &Path,
+    section: &str,
+    name: &str,
+    relative: &str,
+) -> Result<String, ScaffoldError> {
+    validate_ident(name)?;
+    let manifest = Manifest::read(path)?;
+    let package = manifest.package_name().ok_or(ScaffoldError::NoPackage)?;
+    // `src/main.rs` is an implicit binary named after the package.
+    let implicit_main = section == "bin" && name == package && path.join("src/main.rs").is_file();
+    if implicit_main || manifest.target_names(section).iter().any(|n| n == name) {
+        return Err(ScaffoldError::TargetExists(name.to_string()));
+    }
+    let mut vars = BTreeMap::from([("name", name.to_string()), ("kind", section.to_string())]);
+    let template = if section == "example"
+        && (path.join("src/lib.rs").is_file() || manifest.has_lib_section())
+    {
+        vars.insert("crate", package.replace('-', "_"));
+        EXAMPLE_TEMPLATE
+    } else {
+        MAIN_TEMPLATE
+    };
+    create_new(&path.join(relative), &template::render(template, &vars)?)?;
+    Manifest::edit(path, |m| {
+        m.add_target(section, name, &[("path", relative.into())]);
+    })?;
+    Ok(relative.to_string())
+}
+
+/// Create the module `name` and declare it in the crate root.
+///
+/// Returns the new file and the crate root, both relative to the project.
+pub fn module(
+    path: &Path,
+    name: &str,
+    style: ModuleStyle,
+) -> Result<(String, &'static str), ScaffoldError> {
+    validate_ident(name)?;
+    let root = CRATE_ROOTS
+        .into_iter()
+        .find(|f| path.join(f).is_file())
+        .ok_or(ScaffoldError::NoCrateRoot)?;
+    let code = fs::read_to_string(path.join(root))?;
+    if code
+        .lines()
+        .filter_map(mod_declaration)
+        .any(|(_, declared)| declared == name)
+    {
+        return Err(ScaffoldError::ModuleDeclared(name.to_string()));
+    }
+    for existing in [format!("src/{name}.rs"), format!("src/{name}/mod.rs")] {
+        if path.join(&existing).exists() {
+            return Err(ScaffoldError::Exists(PathBuf::from(existing)));
+        }
+    }
+    let relative = match style {
+        ModuleStyle::File => format!("src/{name}.rs"),
+        ModuleStyle::ModRs => format!("src/{name}/mod.rs"),
+    };
+    let vars = BTreeMap::from([("name", name.to_string())]);
+    create_new(
+        &path.join(&relative),
+        &template::render(MODULE_TEMPLATE, &vars)?,
+    )?;
+    fs::write(path.join(root), declare_module(&code, name))?;
+    Ok((relative, root))
+}
+
+/// `code` with `mod <name>;` added.
+///
+/// The declaration goes among the existing top-level ones, in alphabetical
+/// order when they are sorted (after the last one otherwise), with their
+/// visibility when they all share it. Without any, it goes after the inner
+/// doc comments and attributes at the top of the file.
+fn declare_module(code: &str, name: &str) -> String {
+    let mut lines: Vec<String> = code.lines().map(ToString::to_string).collect();
+    let declared: Vec<(usize, &str, &str)> = code
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| mod_declaration(line).map(|(vis, n)| (i, vis, n)))
+        .collect();
+    match declared.as_slice() {
+        [] => {
+            let header = lines
+                .iter()
+                .rposition(|l| l.starts_with("//!") || l.starts_with("#!["))
+                .map_or(0, |i| i + 1);
+            let mut block = vec![format!("mod {name};")];
+            if header > 0 {
+                block.insert(0, String::new());
+            }
+            if lines.get(header).is_some_and(|l| !l.trim().is_empty()) {
+                block.push(String::new());
+            }
+            lines.splice(header..header, block);
+        }
+        [first, ..] => {
+            let visibility = if declared.iter().all(|(_, vis, _)| *vis == first.1) {
+                first.1
+            } else {
+                ""
+            };
+            let sorted = declared.windows(2).all(|w| w[0].2 <= w[1].2);
+            let next = declared
+                .iter()
+                .find(|(_, _, n)| *n > name)
+                .filter(|_| sorted);
+            let at = match next {
+                // Keep the attributes and doc comments of the next declaration with it.
+                Some((i, _, _)) => {
+                    let mut at = *i;
+                    while at > 0 && {
+                        let previous = lines[at - 1].trim_start();
+                        previous.starts_with("#[") || previous.starts_with("///")
+                    } {
+                        at -= 1;
+                    }
+                    at
+                }
+                None => declared.last().map_or(0, |(i, _, _)| i + 1),
+            };
+            lines.insert(at, format!("{visibility}mod {name};"));
+        }
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// Visibility (`""`, `"pub "`, `"pub(crate) "`) and name of a top-level `mod <name>;` line.
+fn mod_declaration(line: &str) -> Option<(&str, &str)> {
+    let rest = if line.starts_with("pub(") {
+        &line[line.find(") ")? + 2..]
+    } else {
+        line.strip_prefix("pub ").unwrap_or(line)
+    };
+    let visibility = &line[..line.len() - rest.len()];
+    let name = rest
+        .strip_prefix("mod ")?
+        .trim_end()
+        .strip_suffix(';')?
+        .trim();
+    name.chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        .then_some((visibility, name))
+}
+
+/// Requirement for the newest `criterion` on crates.io (`0.7` style), else a known version.
+pub fn criterion_requirement() -> String {
+    match crates_io::latest_version("criterion") {
+        Ok(v) if v.major == 0 => format!("0.{}", v.minor),
+        Ok(v) => v.major.to_string(),
+        Err(e) => {
+            warn!("Cannot look up the latest criterion version: {e}");
+            CRITERION_FALLBACK_REQ.to_string()
+        }
+    }
+}
+
+/// `.rs` files under `src/` of the project at `path`, relative to it and sorted.
+pub fn source_files(path: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    collect_sources(path, &path.join("src"), &mut files);
+    files.sort();
+    files
+}
+
+fn collect_sources(root: &Path, dir: &Path, out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_sources(root, &path, out);
+        } else if path.extension().is_some_and(|e| e == "rs")
+            && let Ok(relative) = path.strip_prefix(root)
+        {
+            out.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
+
+/// Names of generated items: snake_case identifiers.
+fn validate_ident(name: &str) -> Result<(), ScaffoldError> {
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && name != "_"
+        && !KEYWORDS.contains(&name);
+    if valid {
+        Ok(())
+    } else {
+        Err(ScaffoldError::InvalidName(name.to_string()))
+    }
+}
+
+/// Write `contents` to `file`, creating parent directories; fails when `file` exists.
+fn create_new(file: &Path, contents: &str) -> Result<(), ScaffoldError> {
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(file)
+    {
+        Ok(mut f) => Ok(f.write_all(contents.as_bytes())?),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            Err(ScaffoldError::Exists(file.to_path_buf()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    /// Project directory with `src/`.
+    fn project_dir() -> TempDir {
+        let d = TempDir::new("scaffold");
+        fs::create_dir_all(d.join("src")).unwrap();
+        d
+    }
+
+    #[test]
+    fn scaffolds_integration_and_unit_tests() {
+        let dir = project_dir();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"my-tool\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+
+        assert_eq!(integration_test(&dir, "cli").unwrap(), "tests/cli.rs");
+        let test = fs::read_to_string(dir.join("tests/cli.rs")).unwrap();
+        assert!(test.contains("env!(\"CARGO_BIN_EXE_my-tool\")"));
+        assert!(test.contains("fn cli()"));
+        assert!(matches!(
+            integration_test(&dir, "cli"),
+            Err(ScaffoldError::Exists(_))
+        ));
+        assert!(matches!(
+            integration_test(&dir, "Bad-Name"),
+            Err(ScaffoldError::InvalidName(_))
+        ));
+
+        fs::write(dir.join("src/lib.rs"), "pub fn add() {}\n").unwrap();
+        integration_test(&dir, "api").unwrap();
+        let test = fs::read_to_string(dir.join("tests/api.rs")).unwrap();
+        assert!(test.contains("use my_tool as _;"));
+
+        assert_eq!(source_files(&dir), ["src/lib.rs", "src/main.rs"]);
+        unit_tests(&dir, "src/main.rs", "parses_args").unwrap();
+        let main = fs::read_to_string(dir.join("src/main.rs")).unwrap();
+        assert!(main.starts_with("fn main() {}\n\n#[cfg(test)]\nmod tests {"));
+        assert!(main.contains("    fn parses_args() {"));
+        assert!(matches!(
+            unit_tests(&dir, "src/main.rs", "again"),
+            Err(ScaffoldError::HasTestModule(_))
+        ));
+    }
+
+    #[test]
+    fn scaffolds_a_criterion_benchmark() {
+        let dir = project_dir();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = \"1\"\n",
+        )
+        .unwrap();
+
+        let done = benchmark(&dir, "parse", "0.7").unwrap();
+        assert!(done.contains("criterion 0.7"));
+        let bench = fs::read_to_string(dir.join("benches/parse.rs")).unwrap();
+        assert!(bench.contains("criterion_group!(benches, parse);"));
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        assert!(manifest.ends_with(
+            "serde = \"1\"\n\n[dev-dependencies]\ncriterion = \"0.7\"\n\n[[bench]]\nname = \"parse\"\nharness = false\n"
+        ));
+
+        assert!(
+            !benchmark(&dir, "render", "0.7")
+                .unwrap()
+                .contains("criterion")
+        );
+        assert!(matches!(
+            benchmark(&dir, "parse", "0.7"),
+            Err(ScaffoldError::TargetExists(_))
+        ));
+    }
+
+    #[test]
+    fn scaffolds_examples_and_binaries() {
+        let dir = project_dir();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.join("src/lib.rs"), "").unwrap();
+
+        assert_eq!(example(&dir, "basic").unwrap(), "examples/basic.rs");
+        let code = fs::read_to_string(dir.join("examples/basic.rs")).unwrap();
+        assert!(code.contains("use demo as _;"));
+        assert_eq!(binary(&dir, "worker").unwrap(), "src/bin/worker.rs");
+        let code = fs::read_to_string(dir.join("src/bin/worker.rs")).unwrap();
+        assert!(code.contains("`cargo run --bin worker`"));
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        assert!(manifest.ends_with(
+            "[[example]]\nname = \"basic\"\npath = \"examples/basic.rs\"\n\n[[bin]]\nname = \"worker\"\npath = \"src/bin/worker.rs\"\n"
+        ));
+
+        assert!(matches!(
+            binary(&dir, "worker"),
+            Err(ScaffoldError::TargetExists(_))
+        ));
+        assert!(matches!(
+            binary(&dir, "demo"),
+            Err(ScaffoldError::TargetExists(_))
+        ));
+    }
+
+    #[test]
+    fn declares_new_modules_in_the_crate_root() {
+        let dir = project_dir();
+        fs::write(
+            dir.join("src/lib.rs"),
+            "//! Demo.\n\npub mod alpha;\n/// Gamma.\npub mod gamma;\n\npub fn run() {}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            module(&dir, "beta", ModuleStyle::File).unwrap(),
+            ("src/beta.rs".to_string(), "src/lib.rs")
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("src/lib.rs")).unwrap(),
+            "//! Demo.\n\npub mod alpha;\npub mod beta;\n/// Gamma.\npub mod gamma;\n\npub fn run() {}\n"
+        );
+        assert!(dir.join("src/beta.rs").is_file());
+        module(&dir, "zeta", ModuleStyle::ModRs).unwrap();
+        assert!(dir.join("src/zeta/mod.rs").is_file());
+        assert!(
+            fs::read_to_string(dir.join("src/lib.rs"))
+                .unwrap()
+                .contains("pub mod gamma;\npub mod zeta;\n")
+        );
+        assert!(matches!(
+            module(&dir, "beta", ModuleStyle::File),
+            Err(ScaffoldError::ModuleDeclared(_))
+        ));
+        assert!(matches!(
+            module(&dir, "type", ModuleStyle::File),
+            Err(ScaffoldError::InvalidName(_))
+        ));
+
+        assert_eq!(
+            declare_module("//! App.\n#![allow(unused)]\nuse std::fs;\n", "cli"),
+            "//! App.\n#![allow(unused)]\n\nmod cli;\n\nuse std::fs;\n"
+        );
+        assert_eq!(
+            declare_module("fn main() {}\n", "cli"),
+            "mod cli;\n\nfn main() {}\n"
+        );
+        assert_eq!(
+            declare_module("mod b;\nmod a;\n", "c"),
+            "mod b;\nmod a;\nmod c;\n"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Find in project.
+//!
+//! Runs ripgrep (`rg --json`) scoped to one project and groups the matches by
+//! file. ripgrep honours `.gitignore`, so `target/` and other ignored files are
+//! skipped. The pattern is a ripgrep regular expression.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use log::info;
+use serde_json::Value;
+
+use crate::process::{self, CommandLine};
+
+/// Matches kept per file.
+const MAX_MATCHES_PER_FILE: &str = "50";
+/// Matches kept overall; the rest are dropped (`SearchResults::truncated`).
+const MAX_MATCHES: usize = 1000;
+
+#[derive(Debug)]
+pub enum SearchError {
+    /// `rg` is not installed.
+    NotInstalled,
+    Io(std::io::Error),
+    /// ripgrep exited with an error (invalid pattern, ...).
+    Failed(String),
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotInstalled => write!(f, "ripgrep (rg) is not installed"),
+            Self::Io(e) => write!(f, "Cannot run ripgrep: {e}"),
+            Self::Failed(msg) => write!(f, "ripgrep failed: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// One matching line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+    pub line: u64,
+    pub text: String,
+}
+
+/// Matches of one file; `file` is relative to the project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMatches {
+    pub file: PathBuf,
+    pub matches: Vec<LineMatch>,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchResults {
+    pub files: Vec<FileMatches>,
+    /// More than `MAX_MATCHES` matches were found.
+    pub truncated: bool,
+}
+
+/// Search `pattern` in the project at `project_path`.
+pub fn search(project_path: &Path, pattern: &str) -> Result<SearchResults, SearchError> {
+    info!("Searching '{pattern}' in {}", project_path.display());
+    let cmd = CommandLine::new("rg", project_path).args([
+        "--json",
+        "--max-count",
+        MAX_MATCHES_PER_FILE,
+        "--",
+        pattern,
+        ".",
+    ]);
+    let output = process::with(|runner| runner.output(&cmd)).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            SearchError::NotInstalled
+        } else {
+            SearchError::Io(e)
+        }
+    })?;
+
+    // 0: matches, 1: no match, 2: error (possibly with partial results).
+    if output.code == Some(2) && output.stdout.is_empty() {
+        return Err(SearchError::Failed(output.stderr.trim().to_string()));
+    }
+    Ok(parse_json(&output.stdout))
+}
+
+/// Group the `match` messages of `rg --json` output by file, in output order.
+fn parse_json(output: &str) -> SearchResults {
+    let mut results = SearchResults::default();
+    let mut total = 0;
+    for message in output
+        .lines()
+        .filter_map(|l| serde_json::from_str::<Value>(l).ok())
+    {
+        if message["type"] != "match" {
+            continue;
+        }
+        if total == MAX_MATCHES {
+            results.truncated = true;
+            break;
+        }
+        let data = &message["data"];
+        let (Some(path), Some(line)) =
+            (data["path"]["text"].as_str(), data["line_number"].as_u64())
+        else {
+            continue;
+        };
+        let file = Path::new(path)
+            .strip_prefix(".")
+            .unwrap_or(Path::new(path))
+            .to_path_buf();
+        let text = data["lines"]["text"]
+            .as_str()
+            .unwrap_or_default()
+            .trim_end()
+            .to_string();
+
+        let entry = match results.files.last_mut() {
+            Some(last) if last.file == file => last,
+            _ => {
+                results.files.push(FileMatches {
+                    file,
+                    matches: Vec::new(),
+                });
+                results.files.last_mut().unwrap()
+            }
+        };
+        entry.matches.push(LineMatch { line, text });
+        total += 1;
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::FakeRunner;
+    use std::rc::Rc;
+
+    #[test]
+    fn groups_matches_by_file() {
+        let output = r#"{"type":"begin","data":{"path":{"text":"./src/main.rs"}}}
+{"type":"match","data":{"path":{"text":"./src/main.rs"},"lines":{"text":"fn main() {\n"},"line_number":3}}
+{"type":"match","data":{"path":{"text":"./src/main.rs"},"lines":{"text":"    main_menu();\n"},"line_number":9}}
+{"type":"end","data":{"path":{"text":"./src/main.rs"}}}
+{"type":"match","data":{"path":{"text":"./README.md"},"lines":{"text":"main entry\n"},"line_number":1}}
+{"type":"summary","data":{}}"#;
+
+        let results = parse_json(output);
+        assert!(!results.truncated);
+        assert_eq!(results.files.len(), 2);
+        assert_eq!(results.files[0].file, PathBuf::from("src/main.rs"));
+        assert_eq!(
+            results.files[0].matches,
+            vec![
+                LineMatch {
+                    line: 3,
+                    text: "fn main() {".into()
+                },
+                LineMatch {
+                    line: 9,
+                    text: "    main_menu();".into()
+                },
+            ]
+        );
+        assert_eq!(results.files[1].file, PathBuf::from("README.md"));
+    }
+
+    #[test]
+    fn reports_missing_rg_and_its_errors() {
+        let fake = Rc::new(FakeRunner::default());
+        let _runner = process::replace(fake.clone());
+        fake.not_found();
+        fake.fail(2, "regex parse error\n");
+        fake.reply(Ok(process::ProcessOutput {
+            code: Some(1),
+            ..Default::default()
+        }));
+
+        let project = Path::new("/projects/demo");
+        assert!(matches!(
+            search(project, "main("),
+            Err(SearchError::NotInstalled)
+        ));
+        assert!(matches!(
+            search(project, "main("),
+            Err(SearchError::Failed(e)) if e == "regex parse error"
+        ));
+        assert!(search(project, "main").unwrap().files.is_empty());
+        assert_eq!(
+            fake.calls()[0].to_string(),
+            "rg --json --max-count 50 -- main( ."
+        );
+    }
+}
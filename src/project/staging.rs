@@ -0,0 +1,395 @@
+//! Hunk-level staging and committing.
+//!
+//! The changes of a file are split into hunks: the staged ones (`HEAD` to
+//! index) and the unstaged ones (index to working tree, untracked files
+//! included). Toggling a hunk applies just that hunk to the index with
+//! `git2`'s apply API: forwards to stage it, reversed to unstage it (a new
+//! file, being a single hunk, is simply added). The working tree is never
+//! modified.
+//!
+//! Single lines are toggled with a patch built from the hunk: the selected
+//! `+` and `-` lines are kept, and the others are made neutral (a line that
+//! is in the index becomes context, a line that is not is dropped).
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fmt::Write;
+use std::path::Path;
+
+use git2::{ApplyLocation, ApplyOptions, Diff, DiffOptions, Patch, Repository};
+
+use crate::git_ops::{self, GitError};
+
+/// A hunk of the changes of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// `@@ -1,3 +1,4 @@` header line.
+    pub header: String,
+    /// Lines prefixed with `+`, `-` or a space.
+    pub lines: Vec<String>,
+    /// Already in the index.
+    pub staged: bool,
+    /// Position among the hunks of the same kind (staged or not).
+    pub index: usize,
+}
+
+impl Hunk {
+    /// `staged  @@ -1,3 +1,4 @@  +2 -1` style line for listings.
+    pub fn label(&self) -> String {
+        let count = |prefix: char| self.lines.iter().filter(|l| l.starts_with(prefix)).count();
+        format!(
+            "{:<8}{}  +{} -{}",
+            if self.staged { "staged" } else { "" },
+            self.header,
+            count('+'),
+            count('-'),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum StagingError {
+    /// The index matches `HEAD`.
+    NothingStaged,
+    EmptyMessage,
+    Git(GitError),
+}
+
+impl fmt::Display for StagingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NothingStaged => write!(f, "Nothing is staged"),
+            Self::EmptyMessage => write!(f, "The commit message cannot be empty"),
+            Self::Git(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for StagingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Git(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<git2::Error> for StagingError {
+    fn from(e: git2::Error) -> Self {
+        Self::Git(e.into())
+    }
+}
+
+/// Staged hunks of `file` (relative to the repository at `path`), then the unstaged ones.
+pub fn hunks(path: &Path, file: &str) -> Result<Vec<Hunk>, GitError> {
+    let repo = git_ops::open(path)?;
+    let mut hunks = collect(&diff(&repo, file, true, false)?, true)?;
+    hunks.extend(collect(&diff(&repo, file, false, false)?, false)?);
+    Ok(hunks)
+}
+
+/// Stage `hunk` of `file` when it is unstaged, unstage it otherwise.
+pub fn toggle(path: &Path, file: &str, hunk: &Hunk) -> Result<(), GitError> {
+    let repo = git_ops::open(path)?;
+    if !hunk.staged && repo.status_file(Path::new(file))?.is_wt_new() {
+        // A new file is a single hunk, and the apply API needs a preimage in the index.
+        let mut index = repo.index()?;
+        index.add_path(Path::new(file))?;
+        index.write()?;
+        return Ok(());
+    }
+    // Unstaging applies the index-to-HEAD diff, which has the same hunks reversed.
+    let diff = diff(&repo, file, hunk.staged, hunk.staged)?;
+    let mut current = 0;
+    let mut opts = ApplyOptions::new();
+    opts.hunk_callback(|_| {
+        current += 1;
+        current - 1 == hunk.index
+    });
+    repo.apply(&diff, ApplyLocation::Index, Some(&mut opts))?;
+    Ok(())
+}
+
+/// Stage the `selected` lines (indices in `hunk.lines`) of `hunk` of `file`
+/// when it is unstaged, unstage them otherwise. Lines of a new file are staged
+/// with the whole file.
+pub fn toggle_lines(
+    path: &Path,
+    file: &str,
+    hunk: &Hunk,
+    selected: &BTreeSet<usize>,
+) -> Result<(), GitError> {
+    let repo = git_ops::open(path)?;
+    if !hunk.staged && repo.status_file(Path::new(file))?.is_wt_new() {
+        return toggle(path, file, hunk);
+    }
+    let diff = diff(&repo, file, hunk.staged, false)?;
+    let Some(patch) = Patch::from_diff(&diff, 0)? else {
+        return Ok(());
+    };
+    let text = line_patch(&patch, file, hunk, selected)?;
+    let reduced = Diff::from_buffer(text.as_bytes())?;
+    repo.apply(&reduced, ApplyLocation::Index, None)?;
+    Ok(())
+}
+
+/// Patch of the index applying the `selected` lines of `hunk`, from the
+/// (forward) `patch` of its file.
+fn line_patch(
+    patch: &Patch,
+    file: &str,
+    hunk: &Hunk,
+    selected: &BTreeSet<usize>,
+) -> Result<String, git2::Error> {
+    // Lines of the index are the old side of an unstaged hunk, the new side
+    // of a staged one.
+    let (indexed, other) = if hunk.staged { ('+', '-') } else { ('-', '+') };
+    let (header, line_count) = patch.hunk(hunk.index)?;
+    let (start, indexed_count) = if hunk.staged {
+        (header.new_start(), header.new_lines())
+    } else {
+        (header.old_start(), header.old_lines())
+    };
+
+    let mut body = String::new();
+    let (mut old_count, mut new_count) = (0, 0);
+    // Whether the previous line was kept, for its "no newline" marker.
+    let mut kept = false;
+    for l in 0..line_count {
+        let line = patch.line_in_hunk(hunk.index, l)?;
+        let origin = line.origin();
+        let prefix = match origin {
+            ' ' => ' ',
+            '=' | '>' | '<' => {
+                if kept {
+                    body.push_str("\\ No newline at end of file\n");
+                }
+                continue;
+            }
+            _ if origin == indexed && selected.contains(&l) => '-',
+            _ if origin == indexed => ' ',
+            _ if origin == other && selected.contains(&l) => '+',
+            _ => {
+                kept = false;
+                continue;
+            }
+        };
+        kept = true;
+        if prefix != '+' {
+            old_count += 1;
+        }
+        if prefix != '-' {
+            new_count += 1;
+        }
+        let content = String::from_utf8_lossy(line.content());
+        body.push(prefix);
+        body.push_str(content.trim_end_matches('\n'));
+        body.push('\n');
+    }
+
+    // An empty side starts at the line before the change.
+    let first = if indexed_count == 0 { start + 1 } else { start };
+    let new_start = if new_count == 0 { first - 1 } else { first };
+    let mut text = format!("diff --git a/{file} b/{file}\n--- a/{file}\n+++ b/{file}\n");
+    writeln!(text, "@@ -{start},{old_count} +{new_start},{new_count} @@").unwrap();
+    text.push_str(&body);
+    Ok(text)
+}
+
+/// Commit the index on top of `HEAD`; returns the abbreviated id of the commit.
+pub fn commit(path: &Path, message: &str) -> Result<String, StagingError> {
+    if message.trim().is_empty() {
+        return Err(StagingError::EmptyMessage);
+    }
+    let repo = git_ops::open(path).map_err(StagingError::Git)?;
+    let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+    let parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit()?),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e.into()),
+    };
+    if parent.as_ref().map(|p| p.tree_id()) == Some(tree.id()) {
+        return Err(StagingError::NothingStaged);
+    }
+    let signature = repo.signature()?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let id = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message.trim_end(),
+        &tree,
+        &parents,
+    )?;
+    Ok(id.to_string()[..8].to_string())
+}
+
+/// Changes of `file`: `HEAD` to index when `staged`, else index to working tree.
+fn diff<'r>(
+    repo: &'r Repository,
+    file: &str,
+    staged: bool,
+    reverse: bool,
+) -> Result<Diff<'r>, git2::Error> {
+    let mut opts = DiffOptions::new();
+    opts.pathspec(file)
+        .disable_pathspec_match(true)
+        .reverse(reverse);
+    if staged {
+        let head = match repo.head() {
+            Ok(head) => Some(head.peel_to_tree()?),
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+            Err(e) => return Err(e),
+        };
+        repo.diff_tree_to_index(head.as_ref(), None, Some(&mut opts))
+    } else {
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        repo.diff_index_to_workdir(None, Some(&mut opts))
+    }
+}
+
+fn collect(diff: &Diff, staged: bool) -> Result<Vec<Hunk>, git2::Error> {
+    let mut hunks = Vec::new();
+    for delta in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(diff, delta)? else {
+            continue;
+        };
+        for h in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(h)?;
+            let mut lines = Vec::with_capacity(line_count);
+            for l in 0..line_count {
+                let line = patch.line_in_hunk(h, l)?;
+                let content = String::from_utf8_lossy(line.content());
+                lines.push(format!(
+                    "{}{}",
+                    line.origin(),
+                    content.trim_end_matches('\n')
+                ));
+            }
+            hunks.push(Hunk {
+                header: String::from_utf8_lossy(hunk.header())
+                    .trim_end()
+                    .to_string(),
+                lines,
+                staged,
+                index: hunks.len(),
+            });
+        }
+    }
+    Ok(hunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+    use std::fs;
+
+    fn lines(edits: &[(usize, &str)]) -> String {
+        (0..20)
+            .map(|i| {
+                edits
+                    .iter()
+                    .find(|(at, _)| *at == i)
+                    .map_or(format!("line {i}\n"), |(_, text)| format!("{text}\n"))
+            })
+            .collect()
+    }
+
+    fn indexed(dir: &Path, file: &str) -> String {
+        let repo = Repository::open(dir).unwrap();
+        let entry = repo.index().unwrap().get_path(Path::new(file), 0).unwrap();
+        String::from_utf8(repo.find_blob(entry.id).unwrap().content().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn stages_and_unstages_single_hunks() {
+        let dir = TempDir::new("staging");
+        let repo = Repository::init(&dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        fs::write(dir.join("a.txt"), lines(&[])).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        commit(&dir, "base").unwrap();
+
+        fs::write(dir.join("a.txt"), lines(&[(1, "first"), (18, "second")])).unwrap();
+        let found = hunks(&dir, "a.txt").unwrap();
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().all(|h| !h.staged));
+
+        toggle(&dir, "a.txt", &found[0]).unwrap();
+        assert_eq!(indexed(&dir, "a.txt"), lines(&[(1, "first")]));
+        let found = hunks(&dir, "a.txt").unwrap();
+        assert_eq!(
+            found.iter().map(|h| h.staged).collect::<Vec<_>>(),
+            [true, false]
+        );
+        assert!(found[0].lines.contains(&"+first".to_string()));
+
+        toggle(&dir, "a.txt", &found[0]).unwrap();
+        assert_eq!(indexed(&dir, "a.txt"), lines(&[]));
+        assert!(matches!(
+            commit(&dir, "nothing"),
+            Err(StagingError::NothingStaged)
+        ));
+
+        fs::write(dir.join("new.txt"), "new\n").unwrap();
+        let found = hunks(&dir, "new.txt").unwrap();
+        toggle(&dir, "new.txt", &found[0]).unwrap();
+        let found = hunks(&dir, "new.txt").unwrap();
+        assert!(found[0].staged);
+        toggle(&dir, "new.txt", &found[0]).unwrap();
+        let found = hunks(&dir, "new.txt").unwrap();
+        assert!(!found[0].staged);
+        toggle(&dir, "new.txt", &found[0]).unwrap();
+        commit(&dir, "add new").unwrap();
+        assert_eq!(indexed(&dir, "new.txt"), "new\n");
+        assert!(hunks(&dir, "new.txt").unwrap().is_empty());
+        assert_eq!(hunks(&dir, "a.txt").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn stages_and_unstages_single_lines() {
+        let dir = TempDir::new("staging");
+        let repo = Repository::init(&dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        fs::write(dir.join("a.txt"), lines(&[])).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        commit(&dir, "base").unwrap();
+
+        // One hunk adding two lines: stage the second one.
+        fs::write(dir.join("a.txt"), lines(&[(9, "line 9\nextra a\nextra b")])).unwrap();
+        let found = hunks(&dir, "a.txt").unwrap();
+        assert_eq!(found.len(), 1);
+        let line = |hunk: &Hunk, text: &str| hunk.lines.iter().position(|l| l == text).unwrap();
+        let selected = BTreeSet::from([line(&found[0], "+extra b")]);
+        toggle_lines(&dir, "a.txt", &found[0], &selected).unwrap();
+        assert_eq!(indexed(&dir, "a.txt"), lines(&[(9, "line 9\nextra b")]));
+
+        let found = hunks(&dir, "a.txt").unwrap();
+        assert_eq!(
+            found.iter().map(|h| h.staged).collect::<Vec<_>>(),
+            [true, false]
+        );
+        assert!(found[1].lines.contains(&"+extra a".to_string()));
+        let selected = BTreeSet::from([line(&found[0], "+extra b")]);
+        toggle_lines(&dir, "a.txt", &found[0], &selected).unwrap();
+        assert_eq!(indexed(&dir, "a.txt"), lines(&[]));
+
+        // A replaced line: stage the removal without the addition.
+        fs::write(dir.join("a.txt"), lines(&[(9, "nine")])).unwrap();
+        let found = hunks(&dir, "a.txt").unwrap();
+        let selected = BTreeSet::from([line(&found[0], "-line 9")]);
+        toggle_lines(&dir, "a.txt", &found[0], &selected).unwrap();
+        assert_eq!(indexed(&dir, "a.txt"), lines(&[]).replace("line 9\n", ""));
+    }
+}
@@ -0,0 +1,51 @@
+//! User-defined project tags ("work", "oss", "experiments", ...).
+//!
+//! Tags are stored in the metadata cache (see `metadata`) and edited as a
+//! comma or space separated line. They are kept normalized: lowercase, without
+//! leading `#`, sorted and unique, so the first tag is the project's group
+//! when the list is sorted by tag.
+
+use std::collections::BTreeSet;
+
+use crate::project::list::ProjectInfo;
+
+/// Tags typed by the user (`"Work, oss #rust"`) as a normalized list (`["oss", "rust", "work"]`).
+pub fn parse(input: &str) -> Vec<String> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|tag| tag.trim_start_matches('#').to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Every tag used by `projects`, sorted.
+pub fn all(projects: &[ProjectInfo]) -> Vec<String> {
+    projects
+        .iter()
+        .flat_map(|p| p.tags.iter().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// `#oss #work` style label; empty without tags.
+pub fn label(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| format!("#{tag}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_normalizes_and_deduplicates() {
+        assert_eq!(parse(" Work, oss #rust,,work "), ["oss", "rust", "work"]);
+        assert!(parse(" , # ").is_empty());
+        assert_eq!(label(&parse("oss work")), "#oss #work");
+    }
+}
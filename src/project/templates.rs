@@ -0,0 +1,282 @@
+//! Project templates from a curated index.
+//!
+//! `template_index` points at a JSON or YAML document (an `http(s)://` URL or
+//! a local file) listing templates, each a git repository:
+//!
+//! ```yaml
+//! templates:
+//!   - name: axum-service
+//!     description: HTTP service with axum, tracing and a Dockerfile
+//!     repository: https://github.com/example/axum-template
+//!     subdirectory: template   # optional
+//! ```
+//!
+//! A bare list of templates is accepted too. The index and the templates are
+//! cached under `<cache dir>/rustm/templates`: a fetched index replaces the
+//! cached copy, which is used when the network is unavailable; a template is
+//! cloned on first use and fast-forwarded afterwards, keeping the cached clone
+//! when that fails. A new project is a copy of the template without `.git`
+//! and `target`, with its `[package] name` set (see `project::create`).
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use log::{info, warn};
+use serde::Deserialize;
+
+use crate::git_ops::{self, GitError};
+use crate::http::{self, HttpError};
+
+/// Directories never copied from a template.
+const SKIPPED: [&str; 2] = [".git", "target"];
+
+/// File name of the cached index.
+const INDEX_FILE: &str = "index.yaml";
+
+/// A template listed by the index.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Template {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Git repository holding the template.
+    pub repository: String,
+    /// Directory of the repository holding the template, its root when unset.
+    #[serde(default)]
+    pub subdirectory: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IndexDocument {
+    Wrapped { templates: Vec<Template> },
+    List(Vec<Template>),
+}
+
+/// Templates of the index.
+#[derive(Debug, Clone)]
+pub struct Index {
+    pub templates: Vec<Template>,
+    /// Read from the cache: the index could not be fetched.
+    pub cached: bool,
+}
+
+/// Template index and download failures.
+#[derive(Debug)]
+pub enum TemplateError {
+    Http(HttpError),
+    Io(io::Error),
+    /// The index is neither a list of templates nor a `templates` map.
+    InvalidIndex(String),
+    /// `subdirectory` is not a directory inside the template repository.
+    InvalidSubdirectory(String),
+    Git(GitError),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "Cannot fetch the template index: {e}"),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::InvalidIndex(e) => write!(f, "Invalid template index: {e}"),
+            Self::InvalidSubdirectory(dir) => {
+                write!(f, "The template has no directory '{dir}'")
+            }
+            Self::Git(e) => write!(f, "Cannot download the template: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(e) => Some(e),
+            Self::Io(e) => Some(e),
+            Self::Git(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TemplateError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<GitError> for TemplateError {
+    fn from(e: GitError) -> Self {
+        Self::Git(e)
+    }
+}
+
+/// Read the index at `location`, falling back to the cached copy when a URL cannot be fetched.
+pub fn load_index(location: &str) -> Result<Index, TemplateError> {
+    if !(location.starts_with("http://") || location.starts_with("https://")) {
+        let templates = parse_index(&fs::read_to_string(location)?)?;
+        return Ok(Index {
+            templates,
+            cached: false,
+        });
+    }
+
+    let cache = cache_dir().join(INDEX_FILE);
+    match download(location) {
+        Ok(text) => {
+            let templates = parse_index(&text)?;
+            if let Err(e) = fs::create_dir_all(cache_dir()).and_then(|()| fs::write(&cache, text)) {
+                warn!("Cannot cache the template index: {e}");
+            }
+            Ok(Index {
+                templates,
+                cached: false,
+            })
+        }
+        Err(e) => {
+            warn!("Cannot fetch the template index {location}: {e}");
+            let Ok(text) = fs::read_to_string(&cache) else {
+                return Err(TemplateError::Http(e));
+            };
+            info!("Using the cached template index");
+            Ok(Index {
+                templates: parse_index(&text)?,
+                cached: true,
+            })
+        }
+    }
+}
+
+fn download(url: &str) -> Result<String, HttpError> {
+    let text = http::agent().get(url).call()?.body_mut().read_to_string()?;
+    Ok(text)
+}
+
+fn parse_index(text: &str) -> Result<Vec<Template>, TemplateError> {
+    // JSON is valid YAML: one parser reads both.
+    let document: IndexDocument =
+        serde_norway::from_str(text).map_err(|e| TemplateError::InvalidIndex(e.to_string()))?;
+    let (IndexDocument::Wrapped { templates } | IndexDocument::List(templates)) = document;
+    Ok(templates)
+}
+
+/// Clone or update the cached copy of `template`; returns the directory to copy.
+///
+/// An update failure (offline, ...) keeps the cached copy.
+pub fn fetch(template: &Template, log: &dyn Fn(String)) -> Result<PathBuf, TemplateError> {
+    let dir = cache_dir().join(dir_name(&template.name));
+    if dir.exists() {
+        if let Err(e) = git_ops::pull(&dir, log) {
+            warn!("Cannot update the template {}: {e}", template.name);
+            log(format!("Cannot update ({e}); using the cached copy"));
+        }
+    } else {
+        fs::create_dir_all(cache_dir())?;
+        // Cloned aside first: an interrupted clone is never taken for a cached copy.
+        let partial = dir.with_extension("partial");
+        let _ = fs::remove_dir_all(&partial);
+        git_ops::clone(&template.repository, &partial, log)?;
+        fs::rename(&partial, &dir)?;
+    }
+    source_dir(&dir, template.subdirectory.as_deref())
+}
+
+/// `subdirectory` of the clone `dir`, refusing paths leaving it.
+fn source_dir(dir: &Path, subdirectory: Option<&str>) -> Result<PathBuf, TemplateError> {
+    let Some(sub) = subdirectory.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(dir.to_path_buf());
+    };
+    let inside = Path::new(sub)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    let source = dir.join(sub);
+    if !inside || !source.is_dir() {
+        return Err(TemplateError::InvalidSubdirectory(sub.to_string()));
+    }
+    Ok(source)
+}
+
+/// Copy the template `source` to `dest` (created), without `.git` and `target`.
+pub fn copy_tree(source: &Path, dest: &Path) -> io::Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if SKIPPED.iter().any(|s| name == *s) {
+                continue;
+            }
+            copy_tree(&entry.path(), &dest.join(&name))?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), dest.join(&name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Cache directory of a template: its name, reduced to safe characters.
+fn dir_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("rustm")
+        .join("templates")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn parses_json_and_yaml_indexes() {
+        let yaml = "templates:\n  - name: cli\n    description: Command line tool\n    repository: https://example.com/cli.git\n    subdirectory: template\n";
+        let json = r#"[{"name": "cli", "repository": "https://example.com/cli.git"}]"#;
+
+        let from_yaml = parse_index(yaml).unwrap();
+        assert_eq!(from_yaml[0].description, "Command line tool");
+        assert_eq!(from_yaml[0].subdirectory.as_deref(), Some("template"));
+        let from_json = parse_index(json).unwrap();
+        assert_eq!(from_json[0].repository, "https://example.com/cli.git");
+        assert!(from_json[0].description.is_empty());
+        assert!(parse_index("templates: 3").is_err());
+
+        assert_eq!(dir_name("axum/service 2"), "axum_service_2");
+    }
+
+    #[test]
+    fn copies_templates_without_git_and_target() {
+        let root = TempDir::new("templates");
+        let source = root.join("source");
+        for dir in [".git", "target", "src"] {
+            fs::create_dir_all(source.join(dir)).unwrap();
+        }
+        fs::write(source.join("Cargo.toml"), "[package]\nname = \"x\"\n").unwrap();
+        fs::write(source.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(source.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+
+        let dest = root.join("dest");
+        copy_tree(&source, &dest).unwrap();
+        assert!(dest.join("Cargo.toml").is_file());
+        assert!(dest.join("src/main.rs").is_file());
+        assert!(!dest.join(".git").exists());
+        assert!(!dest.join("target").exists());
+
+        assert!(source_dir(&source, Some("src")).is_ok());
+        assert!(source_dir(&source, Some("../source")).is_err());
+        assert!(source_dir(&source, Some("missing")).is_err());
+    }
+}
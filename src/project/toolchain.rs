@@ -0,0 +1,488 @@
+//! Rust toolchain of a project, as rustup resolves it.
+//!
+//! rustup picks the toolchain of a directory from, in order: the
+//! `RUSTUP_TOOLCHAIN` environment variable, a directory override (`rustup
+//! override set`), a `rust-toolchain.toml` (or legacy `rust-toolchain`) file in
+//! the directory or a parent, then the default toolchain. `detect` asks rustup
+//! (`rustup show active-toolchain`, without installing anything) and which of
+//! the components the project needs are missing: clippy and rustfmt, plus the
+//! `components` of its toolchain file.
+//!
+//! The actions go through rustup too (`rustup override`, `rustup toolchain
+//! install`, `rustup component add`), except `pin`, which writes the channel
+//! to the project's toolchain file, keeping its other settings.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use toml_edit::{DocumentMut, value};
+
+use crate::cargo_ops::{self, CargoError};
+
+/// Toolchain files, in the order rustup reads them.
+const TOOLCHAIN_FILES: [&str; 2] = ["rust-toolchain", "rust-toolchain.toml"];
+
+/// Components every project is expected to have.
+const EXPECTED_COMPONENTS: [&str; 2] = ["clippy", "rustfmt"];
+
+/// Where the active toolchain comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// `RUSTUP_TOOLCHAIN`.
+    Environment,
+    /// `rustup override set` for this directory (or a parent).
+    Override(PathBuf),
+    /// A toolchain file.
+    File(PathBuf),
+    /// The default toolchain.
+    Default,
+    /// A reason this version of rustup gives that is not recognized.
+    Other(String),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Environment => write!(f, "RUSTUP_TOOLCHAIN"),
+            Self::Override(dir) => write!(f, "override for {}", dir.display()),
+            Self::File(path) => match path.file_name() {
+                Some(name) => write!(f, "{}", name.to_string_lossy()),
+                None => write!(f, "{}", path.display()),
+            },
+            Self::Default => write!(f, "default"),
+            Self::Other(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+/// The active toolchain of a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toolchain {
+    /// Full name, as rustup gives it (`stable-x86_64-unknown-linux-gnu`).
+    pub name: String,
+    pub source: Source,
+    pub installed: bool,
+    /// Expected components the toolchain does not have.
+    pub missing_components: Vec<String>,
+}
+
+impl Toolchain {
+    /// `stable-x86_64-unknown-linux-gnu (rust-toolchain.toml)`, with what is missing.
+    pub fn summary(&self) -> String {
+        let mut text = format!("{} ({})", self.name, self.source);
+        if !self.installed {
+            text.push_str(", not installed");
+        } else if !self.missing_components.is_empty() {
+            text.push_str(&format!(", missing {}", self.missing_components.join(", ")));
+        }
+        text
+    }
+}
+
+/// The toolchain file of a project, read by `ToolchainFile::find`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolchainFile {
+    pub path: PathBuf,
+    pub channel: Option<String>,
+    pub components: Vec<String>,
+}
+
+impl ToolchainFile {
+    /// The toolchain file rustup uses for `dir`: the first one found in `dir` or its parents.
+    pub fn find(dir: &Path) -> Option<Self> {
+        dir.ancestors()
+            .flat_map(|d| TOOLCHAIN_FILES.map(|name| d.join(name)))
+            .find(|path| path.is_file())
+            .map(|path| Self::read(&path))
+    }
+
+    /// Read `path`: TOML, or the legacy format holding only a channel name.
+    fn read(path: &Path) -> Self {
+        let raw = fs::read_to_string(path).unwrap_or_default();
+        let mut file = Self {
+            path: path.to_path_buf(),
+            ..Self::default()
+        };
+        match raw.parse::<DocumentMut>() {
+            Ok(doc) if doc.contains_key("toolchain") => {
+                let toolchain = &doc["toolchain"];
+                file.channel = toolchain
+                    .get("channel")
+                    .and_then(|c| c.as_str())
+                    .map(ToString::to_string);
+                file.components = toolchain
+                    .get("components")
+                    .and_then(|c| c.as_array())
+                    .map(|a| {
+                        a.iter()
+                            .filter_map(|c| c.as_str().map(ToString::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+            }
+            _ => file.channel = Some(raw.trim().to_string()).filter(|c| !c.is_empty()),
+        }
+        file
+    }
+}
+
+/// Errors of rustup and of the toolchain file.
+#[derive(Debug)]
+pub enum ToolchainError {
+    Rustup(CargoError),
+    /// A streamed rustup command failed; its output went to the log.
+    Failed(String),
+    /// `rustup` answered something this module does not understand.
+    Unexpected(String),
+    /// The toolchain file is not valid TOML.
+    InvalidFile(PathBuf, String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for ToolchainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rustup(CargoError::NotFound(_)) => {
+                write!(
+                    f,
+                    "rustup is not installed: toolchains are managed by rustup"
+                )
+            }
+            Self::Rustup(e) => write!(f, "rustup: {e}"),
+            Self::Failed(command) => write!(f, "`{command}` failed"),
+            Self::Unexpected(out) => write!(f, "Unexpected rustup output: {out}"),
+            Self::InvalidFile(path, e) => write!(f, "{}: {e}", path.display()),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ToolchainError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Rustup(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<CargoError> for ToolchainError {
+    fn from(e: CargoError) -> Self {
+        Self::Rustup(e)
+    }
+}
+
+/// Detect the active toolchain of the project at `path` and its missing components.
+pub fn detect(path: &Path) -> Result<Toolchain, ToolchainError> {
+    // Recent rustup versions install a missing toolchain on first use unless told not to.
+    let shown = cargo_ops::run_tool(
+        "rustup",
+        path,
+        &["show", "active-toolchain"],
+        &[("RUSTUP_AUTO_INSTALL", "0")],
+    );
+    let (name, source, installed) = match shown {
+        Ok(out) => {
+            let line = out.lines().next().unwrap_or_default();
+            let (name, source) =
+                parse_active(line).ok_or_else(|| ToolchainError::Unexpected(line.to_string()))?;
+            (name, source, true)
+        }
+        Err(CargoError::Failed { status, stderr }) => match parse_not_installed(&stderr) {
+            Some((name, source)) => (name, source, false),
+            None => {
+                // Without the backtrace some rustup versions print.
+                let stderr = stderr.lines().next().unwrap_or_default().to_string();
+                return Err(CargoError::Failed { status, stderr }.into());
+            }
+        },
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut missing_components = Vec::new();
+    if installed {
+        let listed = cargo_ops::run_tool(
+            "rustup",
+            path,
+            &["component", "list", "--installed", "--toolchain", &name],
+            &[],
+        )?;
+        let installed_components: Vec<&str> = listed.lines().map(str::trim).collect();
+        missing_components = wanted_components(path)
+            .into_iter()
+            .filter(|component| {
+                // Components are listed with their target: `clippy-x86_64-unknown-linux-gnu`.
+                !installed_components.iter().any(|c| {
+                    c == component
+                        || c.strip_prefix(component.as_str())
+                            .is_some_and(|rest| rest.starts_with('-'))
+                })
+            })
+            .collect();
+    }
+
+    Ok(Toolchain {
+        name,
+        source,
+        installed,
+        missing_components,
+    })
+}
+
+/// Parse `stable-x86_64-unknown-linux-gnu (default)`.
+fn parse_active(line: &str) -> Option<(String, Source)> {
+    let (name, reason) = line.trim().split_once(' ')?;
+    let reason = reason.trim().strip_prefix('(')?.strip_suffix(')')?;
+    let quoted = || reason.split('\'').nth(1).map(PathBuf::from);
+    let source = if reason == "default" {
+        Source::Default
+    } else if reason.contains("RUSTUP_TOOLCHAIN") {
+        Source::Environment
+    } else if reason.starts_with("directory override") {
+        Source::Override(quoted()?)
+    } else if reason.starts_with("overridden by '") {
+        Source::File(quoted()?)
+    } else {
+        Source::Other(reason.to_string())
+    };
+    Some((name.to_string(), source))
+}
+
+/// Parse the error of `rustup show active-toolchain` for a toolchain that is not
+/// installed: `error: override toolchain '<name>' is not installed`, followed by
+/// where it comes from.
+fn parse_not_installed(stderr: &str) -> Option<(String, Source)> {
+    let first = stderr.lines().next()?;
+    if !first.contains("is not installed") {
+        return None;
+    }
+    let name = first.split('\'').nth(1)?.to_string();
+    let source = stderr
+        .lines()
+        .find_map(|l| {
+            let l = l.trim();
+            if l.contains("toolchain file at '") {
+                l.split('\'').nth(1).map(|p| Source::File(PathBuf::from(p)))
+            } else if l.contains("RUSTUP_TOOLCHAIN") {
+                Some(Source::Environment)
+            } else if l.contains("directory override") {
+                l.split('\'')
+                    .nth(1)
+                    .map(|p| Source::Override(PathBuf::from(p)))
+            } else {
+                None
+            }
+        })
+        .unwrap_or(Source::Default);
+    Some((name, source))
+}
+
+/// Pin `channel` (`stable`, `1.85.0`, `nightly-2025-06-01`) in the toolchain
+/// file of the project at `path`, creating `rust-toolchain.toml` when it has
+/// none; returns the file written.
+pub fn pin(path: &Path, channel: &str) -> Result<PathBuf, ToolchainError> {
+    let file = TOOLCHAIN_FILES
+        .iter()
+        .map(|name| path.join(name))
+        .find(|p| p.is_file())
+        .unwrap_or_else(|| path.join("rust-toolchain.toml"));
+    let raw = fs::read_to_string(&file).unwrap_or_default();
+    // The legacy `rust-toolchain` may hold a channel name only.
+    let is_toml = file.extension().is_some_and(|e| e == "toml") || raw.contains("[toolchain]");
+    let content = if is_toml {
+        let mut doc = raw
+            .parse::<DocumentMut>()
+            .map_err(|e| ToolchainError::InvalidFile(file.clone(), e.to_string()))?;
+        doc["toolchain"]["channel"] = value(channel);
+        doc.to_string()
+    } else {
+        format!("{channel}\n")
+    };
+    fs::write(&file, content).map_err(ToolchainError::Io)?;
+    Ok(file)
+}
+
+/// Make `toolchain` the toolchain of `path` for this machine only (`rustup override set`).
+pub fn set_override(path: &Path, toolchain: &str) -> Result<(), ToolchainError> {
+    cargo_ops::run_tool("rustup", path, &["override", "set", toolchain], &[])?;
+    Ok(())
+}
+
+/// Remove the directory override of `path` (`rustup override unset`).
+pub fn unset_override(path: &Path) -> Result<(), ToolchainError> {
+    cargo_ops::run_tool("rustup", path, &["override", "unset"], &[])?;
+    Ok(())
+}
+
+/// Install `toolchain` when missing, then the components it misses, passing
+/// rustup's output to `log`.
+pub fn install(
+    path: &Path,
+    toolchain: &Toolchain,
+    log: &dyn Fn(String),
+) -> Result<(), ToolchainError> {
+    let components = if toolchain.installed {
+        toolchain.missing_components.clone()
+    } else {
+        run_rustup(path, &["toolchain", "install", &toolchain.name], log)?;
+        wanted_components(path)
+    };
+    if components.is_empty() {
+        return Ok(());
+    }
+    let mut args = vec!["component", "add", "--toolchain", &toolchain.name];
+    args.extend(components.iter().map(String::as_str));
+    run_rustup(path, &args, log)
+}
+
+/// Components the project at `path` needs: the expected ones and those of its toolchain file.
+fn wanted_components(path: &Path) -> Vec<String> {
+    let mut wanted: Vec<String> = EXPECTED_COMPONENTS.map(ToString::to_string).to_vec();
+    for component in ToolchainFile::find(path)
+        .map(|f| f.components)
+        .unwrap_or_default()
+    {
+        if !wanted.contains(&component) {
+            wanted.push(component);
+        }
+    }
+    wanted
+}
+
+fn run_rustup(path: &Path, args: &[&str], log: &dyn Fn(String)) -> Result<(), ToolchainError> {
+    if cargo_ops::run_tool_streaming("rustup", path, args, log)? {
+        Ok(())
+    } else {
+        Err(ToolchainError::Failed(format!("rustup {}", args.join(" "))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{self, FakeRunner};
+    use crate::temp_dir::TempDir;
+    use std::rc::Rc;
+
+    #[test]
+    fn active_toolchain_sources_are_parsed() {
+        let parse = |line: &str| parse_active(line).unwrap();
+        assert_eq!(
+            parse("stable-x86_64-unknown-linux-gnu (default)"),
+            ("stable-x86_64-unknown-linux-gnu".into(), Source::Default)
+        );
+        assert_eq!(
+            parse("nightly-x86_64-unknown-linux-gnu (overridden by '/p/demo/rust-toolchain.toml')")
+                .1,
+            Source::File("/p/demo/rust-toolchain.toml".into())
+        );
+        assert_eq!(
+            parse("nightly-x86_64-unknown-linux-gnu (directory override for '/p/demo')").1,
+            Source::Override("/p/demo".into())
+        );
+        assert_eq!(
+            parse("beta (overridden by environment variable RUSTUP_TOOLCHAIN)").1,
+            Source::Environment
+        );
+        assert_eq!(parse_active("no toolchain"), None);
+        assert_eq!(
+            parse_not_installed(
+                "error: override toolchain '1.50.0-x86_64-unknown-linux-gnu' is not installed\n\nCaused by:\n    the toolchain file at '/p/demo/rust-toolchain.toml' specifies an uninstalled toolchain\n"
+            ),
+            Some((
+                "1.50.0-x86_64-unknown-linux-gnu".into(),
+                Source::File("/p/demo/rust-toolchain.toml".into())
+            ))
+        );
+    }
+
+    #[test]
+    fn detection_and_installation_go_through_rustup() {
+        let dir = TempDir::new("toolchain");
+        fs::write(
+            dir.join("rust-toolchain.toml"),
+            "[toolchain]\nchannel = \"nightly\"\ncomponents = [\"miri\", \"clippy\"]\n",
+        )
+        .unwrap();
+        let fake = Rc::new(FakeRunner::default());
+        let _runner = process::replace(fake.clone());
+        let file = dir.join("rust-toolchain.toml").display().to_string();
+        fake.succeed(&format!(
+            "nightly-x86_64-unknown-linux-gnu (overridden by '{file}')\n"
+        ));
+        fake.succeed("cargo-x86_64-unknown-linux-gnu\nclippy-x86_64-unknown-linux-gnu\nrust-std-x86_64-unknown-linux-gnu\n");
+
+        let detected = detect(&dir).unwrap();
+        assert_eq!(detected.name, "nightly-x86_64-unknown-linux-gnu");
+        assert!(detected.installed);
+        assert_eq!(detected.missing_components, ["rustfmt", "miri"]);
+        assert_eq!(
+            detected.summary(),
+            "nightly-x86_64-unknown-linux-gnu (rust-toolchain.toml), missing rustfmt, miri"
+        );
+        install(&dir, &detected, &|_| {}).unwrap();
+
+        let not_installed = Toolchain {
+            installed: false,
+            ..detected
+        };
+        install(&dir, &not_installed, &|_| {}).unwrap();
+
+        let calls = fake.calls();
+        let lines: Vec<String> = calls.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "rustup show active-toolchain",
+                "rustup component list --installed --toolchain nightly-x86_64-unknown-linux-gnu",
+                "rustup component add --toolchain nightly-x86_64-unknown-linux-gnu rustfmt miri",
+                "rustup toolchain install nightly-x86_64-unknown-linux-gnu",
+                "rustup component add --toolchain nightly-x86_64-unknown-linux-gnu clippy rustfmt miri",
+            ]
+        );
+        assert_eq!(
+            calls[0].envs,
+            [("RUSTUP_AUTO_INSTALL".to_string(), "0".to_string())]
+        );
+    }
+
+    #[test]
+    fn pin_keeps_the_other_settings() {
+        let dir = TempDir::new("toolchain");
+        assert_eq!(
+            pin(&dir, "1.85.0").unwrap(),
+            dir.join("rust-toolchain.toml")
+        );
+        let file = ToolchainFile::find(&dir).unwrap();
+        assert_eq!(file.channel.as_deref(), Some("1.85.0"));
+
+        fs::write(
+            dir.join("rust-toolchain.toml"),
+            "# Pinned for the MSRV\n[toolchain]\nchannel = \"stable\"\ncomponents = [\"rust-src\"]\n",
+        )
+        .unwrap();
+        pin(&dir, "nightly").unwrap();
+        let raw = fs::read_to_string(dir.join("rust-toolchain.toml")).unwrap();
+        assert!(raw.starts_with("# Pinned for the MSRV\n"));
+        let file = ToolchainFile::find(&dir).unwrap();
+        assert_eq!(file.channel.as_deref(), Some("nightly"));
+        assert_eq!(file.components, ["rust-src"]);
+
+        // The legacy file is read before the TOML one, and keeps its format.
+        fs::write(dir.join("rust-toolchain"), "beta\n").unwrap();
+        pin(&dir, "stable").unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("rust-toolchain")).unwrap(),
+            "stable\n"
+        );
+        assert_eq!(
+            ToolchainFile::find(&dir.join("src"))
+                .unwrap()
+                .channel
+                .as_deref(),
+            Some("stable")
+        );
+    }
+}
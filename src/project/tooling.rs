@@ -0,0 +1,92 @@
+//! Curated tooling files of new projects.
+//!
+//! `<config dir>/templates/` may hold a `.gitignore`, a `rustfmt.toml` and a
+//! `clippy.toml`. When asked, project creation copies the ones present into
+//! the new project, replacing what `cargo new` or the template wrote, so that
+//! every project starts with the same tooling. The `.gitignore` is only copied
+//! into git repositories.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+
+use crate::config::Config;
+
+/// Name of the directory of the configuration directory holding the files.
+const DIR_NAME: &str = "templates";
+
+/// The files copied, by name.
+pub const FILES: [&str; 3] = [".gitignore", "rustfmt.toml", "clippy.toml"];
+
+/// `<config dir>/templates`.
+pub fn dir() -> PathBuf {
+    let cfg_file = Config::file_path();
+    cfg_file
+        .parent()
+        .map_or_else(Config::file_path, Path::to_path_buf)
+        .join(DIR_NAME)
+}
+
+/// The files of `FILES` present in `dir`.
+pub fn available(dir: &Path) -> Vec<&'static str> {
+    FILES
+        .into_iter()
+        .filter(|name| dir.join(name).is_file())
+        .collect()
+}
+
+/// Copy the files of `dir` into the project at `project_path`, replacing its
+/// own; the `.gitignore` only when `git`. Returns what failed, with the reason.
+pub fn copy(dir: &Path, project_path: &Path, git: bool) -> Vec<String> {
+    available(dir)
+        .into_iter()
+        .filter(|name| git || *name != ".gitignore")
+        .filter_map(|name| {
+            let copied = fs::copy(dir.join(name), project_path.join(name));
+            match copied {
+                Ok(_) => {
+                    info!("Copied {name} into {}", project_path.display());
+                    None
+                }
+                Err(e) => {
+                    warn!("Cannot copy {name} into {}: {e}", project_path.display());
+                    Some(format!("copy {name}: {e}"))
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn present_files_replace_the_project_ones() {
+        let base = TempDir::new("tooling");
+        let (templates, project) = (base.join("templates"), base.join("project"));
+        fs::create_dir_all(&templates).unwrap();
+        fs::create_dir_all(&project).unwrap();
+        fs::write(templates.join(".gitignore"), "/target\n*.swp\n").unwrap();
+        fs::write(templates.join("rustfmt.toml"), "max_width = 120\n").unwrap();
+        fs::write(project.join(".gitignore"), "/target\n").unwrap();
+        assert_eq!(available(&templates), [".gitignore", "rustfmt.toml"]);
+
+        assert!(copy(&templates, &project, false).is_empty());
+        assert_eq!(
+            fs::read_to_string(project.join(".gitignore")).unwrap(),
+            "/target\n"
+        );
+        assert!(project.join("rustfmt.toml").is_file());
+        assert!(!project.join("clippy.toml").exists());
+
+        assert!(copy(&templates, &project, true).is_empty());
+        assert!(
+            fs::read_to_string(project.join(".gitignore"))
+                .unwrap()
+                .contains("*.swp")
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Read-only file tree of a project.
+//!
+//! Directories are listed lazily: only expanded directories are read. Entries
+//! ignored by git (`.gitignore`, `.git/info/exclude`, global excludes) and the
+//! `.git` directory are hidden; outside a repository only `target/` is.
+//! Directories come first, then files, each sorted by name.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use git2::Repository;
+use log::warn;
+
+/// One visible line of the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Path relative to the project root.
+    pub path: PathBuf,
+    pub is_dir: bool,
+    /// Nesting level, 0 for the project root's children.
+    pub depth: usize,
+}
+
+impl Entry {
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+pub struct FileTree {
+    root: PathBuf,
+    repo: Option<Repository>,
+    expanded: BTreeSet<PathBuf>,
+}
+
+impl FileTree {
+    /// Tree of `root` with every directory collapsed.
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            repo: Repository::discover(root).ok(),
+            expanded: BTreeSet::new(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn is_expanded(&self, dir: &Path) -> bool {
+        self.expanded.contains(dir)
+    }
+
+    /// Expand a collapsed directory or collapse an expanded one (`dir` is relative).
+    pub fn toggle(&mut self, dir: &Path) {
+        if !self.expanded.remove(dir) {
+            self.expanded.insert(dir.to_path_buf());
+        }
+    }
+
+    /// Entries currently visible, in display order.
+    pub fn visible(&self) -> Vec<Entry> {
+        let mut out = Vec::new();
+        self.push_children(Path::new(""), 0, &mut out);
+        out
+    }
+
+    fn push_children(&self, dir: &Path, depth: usize, out: &mut Vec<Entry>) {
+        let read = match fs::read_dir(self.root.join(dir)) {
+            Ok(read) => read,
+            Err(e) => {
+                warn!("Cannot list {}: {e}", self.root.join(dir).display());
+                return;
+            }
+        };
+        let mut children: Vec<Entry> = read
+            .flatten()
+            .map(|e| Entry {
+                path: dir.join(e.file_name()),
+                is_dir: e.file_type().is_ok_and(|t| t.is_dir()),
+                depth,
+            })
+            .filter(|e| !self.is_hidden(e))
+            .collect();
+        children.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.path.cmp(&b.path)));
+
+        for child in children {
+            let expand = child.is_dir && self.expanded.contains(&child.path);
+            let path = child.path.clone();
+            out.push(child);
+            if expand {
+                self.push_children(&path, depth + 1, out);
+            }
+        }
+    }
+
+    fn is_hidden(&self, entry: &Entry) -> bool {
+        if entry.path.file_name().is_some_and(|n| n == ".git") {
+            return true;
+        }
+        let Some(repo) = &self.repo else {
+            return entry.is_dir && entry.path == Path::new("target");
+        };
+        let absolute = self.root.join(&entry.path);
+        repo.workdir()
+            .and_then(|workdir| absolute.strip_prefix(workdir).ok())
+            .is_some_and(|relative| repo.is_path_ignored(relative).unwrap_or(false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn lists_expanded_directories_and_skips_ignored_entries() {
+        let dir = TempDir::new("tree");
+        Repository::init(&dir).unwrap();
+        fs::write(dir.join(".gitignore"), "/target\n*.log\n").unwrap();
+        fs::write(dir.join("Cargo.toml"), "").unwrap();
+        fs::write(dir.join("debug.log"), "").unwrap();
+        fs::create_dir_all(dir.join("src/bin")).unwrap();
+        fs::write(dir.join("src/main.rs"), "").unwrap();
+        fs::create_dir_all(dir.join("target/debug")).unwrap();
+
+        let mut tree = FileTree::new(&dir);
+        let names = |tree: &FileTree| -> Vec<String> {
+            tree.visible()
+                .iter()
+                .map(|e| e.path.display().to_string())
+                .collect()
+        };
+        assert_eq!(names(&tree), vec!["src", ".gitignore", "Cargo.toml"]);
+
+        tree.toggle(Path::new("src"));
+        assert_eq!(
+            names(&tree),
+            vec!["src", "src/bin", "src/main.rs", ".gitignore", "Cargo.toml"]
+        );
+        assert_eq!(tree.visible()[2].depth, 1);
+
+        tree.toggle(Path::new("src"));
+        assert_eq!(tree.visible().len(), 3);
+    }
+}
@@ -0,0 +1,206 @@
+//! Projects that disappeared behind rustm's back.
+//!
+//! rustm forgets or moves the metadata of the projects it deletes, renames or
+//! archives itself. A metadata cache entry under the projects directory whose
+//! directory is gone therefore belongs to a project deleted or moved outside
+//! rustm (file manager, terminal), possibly while rustm was not running.
+//!
+//! `in_trash` recognizes a project sent to the desktop trash (the freedesktop
+//! trash of the home directory on Linux, `~/.Trash` on macOS); `candidates`
+//! lists the projects it may have been moved to, matched by `origin` URL first
+//! and then by directory name.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::metadata::MetadataStore;
+use crate::project::archive;
+use crate::project::list::ProjectInfo;
+
+/// A project whose directory is gone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vanished {
+    pub name: String,
+    pub path: PathBuf,
+    /// `origin` URL recorded by the last scan.
+    pub remote_url: Option<String>,
+}
+
+/// Where a project may have been moved, and why it is a candidate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub name: String,
+    pub path: PathBuf,
+    /// Same `origin` URL (else: same name).
+    pub same_remote: bool,
+}
+
+/// Projects known to `store` under `root` whose directory no longer exists.
+pub fn find(store: &MetadataStore, root: &Path) -> Vec<Vanished> {
+    store
+        .iter()
+        .filter(|(path, meta)| {
+            !meta.name.is_empty()
+                && path.starts_with(root)
+                && !path.starts_with(root.join(archive::ARCHIVE_DIR))
+                && !path.exists()
+        })
+        .map(|(path, meta)| Vanished {
+            name: meta.name.clone(),
+            path,
+            remote_url: meta.remote_url.clone(),
+        })
+        .collect()
+}
+
+/// Location of `path` in the desktop trash, when it was sent there.
+pub fn in_trash(path: &Path) -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        // The Finder keeps no record of the original location: go by name.
+        let trashed = dirs::home_dir()?.join(".Trash").join(path.file_name()?);
+        return trashed.join("Cargo.toml").is_file().then_some(trashed);
+    }
+    find_in_trash(&dirs::data_dir()?.join("Trash"), path)
+}
+
+/// Entry of the freedesktop `trash` directory whose recorded original path is `path`.
+fn find_in_trash(trash: &Path, path: &Path) -> Option<PathBuf> {
+    let original = path.to_string_lossy();
+    fs::read_dir(trash.join("info"))
+        .ok()?
+        .flatten()
+        .find_map(|entry| {
+            let info = entry.path();
+            let stem = info
+                .file_name()?
+                .to_str()?
+                .strip_suffix(".trashinfo")?
+                .to_string();
+            let content = fs::read_to_string(&info).ok()?;
+            let recorded = content.lines().find_map(|l| l.strip_prefix("Path="))?;
+            (percent_decode(recorded) == original).then(|| trash.join("files").join(stem))
+        })
+}
+
+/// Decode the `%XX` escapes of a trash info path.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Listed `projects` the vanished one may have become: same `origin` URL
+/// first, then same directory name.
+pub fn candidates(lost: &Vanished, projects: &[ProjectInfo]) -> Vec<Candidate> {
+    let lost_url = lost.remote_url.as_deref().map(normalize_url);
+    let mut found: Vec<Candidate> = projects
+        .iter()
+        .filter(|p| p.path != lost.path)
+        .filter_map(|p| {
+            let same_remote =
+                lost_url.is_some() && p.remote_url.as_deref().map(normalize_url) == lost_url;
+            (same_remote || p.name == lost.name).then(|| Candidate {
+                name: p.name.clone(),
+                path: p.path.clone(),
+                same_remote,
+            })
+        })
+        .collect();
+    found.sort_by_key(|c| !c.same_remote);
+    found
+}
+
+/// `url` without the trailing `/` or `.git`, so that clones of one repository compare equal.
+fn normalize_url(url: &str) -> &str {
+    let url = url.trim_end_matches('/');
+    url.strip_suffix(".git").unwrap_or(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    fn project(name: &str, path: &str, remote_url: Option<&str>) -> ProjectInfo {
+        ProjectInfo {
+            name: name.into(),
+            path: PathBuf::from(path),
+            has_uncommitted_changes: false,
+            branch: None,
+            ahead_behind: None,
+            diverged_default: None,
+            rollup: None,
+            no_std: None,
+            version: None,
+            kind: None,
+            last_commit: None,
+            remote_url: remote_url.map(ToString::to_string),
+            fingerprint: 0,
+            pinned: false,
+            last_opened: None,
+            tags: Vec::new(),
+            untrusted: false,
+        }
+    }
+
+    #[test]
+    fn finds_trashed_projects_and_move_candidates() {
+        let base = TempDir::new("vanished");
+        let root = base.join("my projects");
+        fs::create_dir_all(root.join("kept")).unwrap();
+        let mut store = MetadataStore::default();
+        for name in ["kept", "gone"] {
+            store.entry_mut(&root.join(name)).name = name.into();
+        }
+        store.entry_mut(&root.join("gone")).remote_url = Some("git@host:me/gone.git".into());
+        store.entry_mut(&root.join("archive").join("old")).name = "old".into();
+        let vanished = find(&store, &root);
+        assert_eq!(vanished.len(), 1);
+        assert_eq!(vanished[0].path, root.join("gone"));
+
+        let trash = base.join("Trash");
+        fs::create_dir_all(trash.join("info")).unwrap();
+        let encoded = root.join("gone").to_string_lossy().replace(' ', "%20");
+        fs::write(
+            trash.join("info").join("gone.2.trashinfo"),
+            format!("[Trash Info]\nPath={encoded}\nDeletionDate=2026-10-17T10:00:00\n"),
+        )
+        .unwrap();
+        assert_eq!(
+            find_in_trash(&trash, &root.join("gone")),
+            Some(trash.join("files").join("gone.2"))
+        );
+        assert_eq!(find_in_trash(&trash, &root.join("kept")), None);
+
+        let projects = [
+            project("gone", "/elsewhere/gone", None),
+            project("renamed", "/work/renamed", Some("git@host:me/gone")),
+            project("other", "/work/other", Some("git@host:me/other.git")),
+        ];
+        let found: Vec<(String, bool)> = candidates(&vanished[0], &projects)
+            .into_iter()
+            .map(|c| (c.name, c.same_remote))
+            .collect();
+        assert_eq!(
+            found,
+            [("renamed".to_string(), true), ("gone".to_string(), false)]
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Workspace introspection through `cargo metadata`.
+//!
+//! `cargo metadata --no-deps` resolves member globs, exclusions and inherited
+//! fields exactly like cargo does, so features working on "every member of a
+//! workspace" use it instead of interpreting `[workspace]` themselves. A
+//! single-package project yields one member.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::cargo_ops::{self, CargoError};
+
+/// A workspace member package.
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub name: String,
+    /// Directory containing the member's `Cargo.toml`.
+    pub dir: PathBuf,
+    pub dependencies: Vec<Dependency>,
+}
+
+/// A dependency declared by a member.
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    pub name: String,
+    /// Version requirement as written (`*` when none).
+    pub req: String,
+    /// `true` for crates.io / registry dependencies (not path or git).
+    pub from_registry: bool,
+}
+
+/// Errors raised while inspecting a workspace.
+#[derive(Debug)]
+pub enum WorkspaceError {
+    Cargo(CargoError),
+    Parse(String),
+}
+
+impl fmt::Display for WorkspaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cargo(e) => write!(f, "{e}"),
+            Self::Parse(e) => write!(f, "Unexpected `cargo metadata` output: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for WorkspaceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Cargo(e) => Some(e),
+            Self::Parse(_) => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawMetadata {
+    packages: Vec<RawPackage>,
+}
+
+#[derive(Deserialize)]
+struct RawTargetDirectory {
+    target_directory: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct RawPackage {
+    name: String,
+    manifest_path: PathBuf,
+    dependencies: Vec<RawDependency>,
+}
+
+#[derive(Deserialize)]
+struct RawDependency {
+    name: String,
+    req: String,
+    source: Option<String>,
+}
+
+/// Members of the workspace (or single package) at `project_path`, sorted by name.
+pub fn members(project_path: &Path) -> Result<Vec<Member>, WorkspaceError> {
+    let out = cargo_ops::run(
+        project_path,
+        &["metadata", "--format-version", "1", "--no-deps"],
+    )
+    .map_err(WorkspaceError::Cargo)?;
+    parse_members(&out)
+}
+
+/// Directory cargo builds the project at `project_path` into: the workspace's
+/// `target/`, unless `build.target-dir` or `CARGO_TARGET_DIR` moves it.
+pub fn target_directory(project_path: &Path) -> Result<PathBuf, WorkspaceError> {
+    let out = cargo_ops::run(
+        project_path,
+        &["metadata", "--format-version", "1", "--no-deps"],
+    )
+    .map_err(WorkspaceError::Cargo)?;
+    serde_json::from_str::<RawTargetDirectory>(&out)
+        .map(|raw| raw.target_directory)
+        .map_err(|e| WorkspaceError::Parse(e.to_string()))
+}
+
+fn parse_members(json: &str) -> Result<Vec<Member>, WorkspaceError> {
+    let raw: RawMetadata =
+        serde_json::from_str(json).map_err(|e| WorkspaceError::Parse(e.to_string()))?;
+    let mut members: Vec<Member> = raw
+        .packages
+        .into_iter()
+        .map(|p| Member {
+            name: p.name,
+            dir: p
+                .manifest_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            dependencies: p
+                .dependencies
+                .into_iter()
+                .map(|d| Dependency {
+                    name: d.name,
+                    req: d.req,
+                    from_registry: d.source.is_some_and(|s| s.starts_with("registry+")),
+                })
+                .collect(),
+        })
+        .collect();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(members)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cargo_metadata_output() {
+        let json = r#"{
+            "packages": [
+                {
+                    "name": "core",
+                    "version": "0.2.0",
+                    "manifest_path": "/ws/core/Cargo.toml",
+                    "dependencies": [
+                        {"name": "serde", "req": "^1.0", "source": "registry+https://github.com/rust-lang/crates.io-index", "kind": null},
+                        {"name": "util", "req": "*", "source": null, "kind": null}
+                    ]
+                },
+                {"name": "app", "version": "0.1.0", "manifest_path": "/ws/app/Cargo.toml", "dependencies": []}
+            ],
+            "workspace_members": []
+        }"#;
+        let members = parse_members(json).unwrap();
+        assert_eq!(members[0].name, "app");
+        assert_eq!(members[1].dir, PathBuf::from("/ws/core"));
+        let deps = &members[1].dependencies;
+        assert!(deps[0].from_registry && !deps[1].from_registry);
+        assert_eq!(deps[0].req, "^1.0");
+    }
+}
@@ -0,0 +1,157 @@
+//! Watch mode of the project list.
+//!
+//! While the project list is open, a `notify` watcher follows the directories
+//! discovery reads (see `project::list::discovery_dirs`), non-recursively:
+//! project directories created, deleted or renamed there, and `Cargo.toml`
+//! files appearing in them, trigger `on_change` on the UI thread (a rescan).
+//! Build output and the files inside projects are never watched. Events come in
+//! bursts (`cargo new`, `git clone`), so `on_change` runs once they settle; the
+//! watched directories are then updated to the new tree.
+//!
+//! Only one watcher runs: `spawn` stops the previous one, and so does `stop`.
+
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, mpsc};
+use std::thread;
+use std::time::Duration;
+
+use cursive::{CbSink, Cursive};
+use log::{info, warn};
+use notify::event::{CreateKind, ModifyKind, RemoveKind};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::config::ScanSettings;
+use crate::project::list::discovery_dirs;
+
+/// Quiet period after the last event before reporting a change.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// How often an idle watcher checks whether it was stopped.
+const POLL: Duration = Duration::from_secs(1);
+
+/// Generation of the running watcher; bumping it stops it.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Called on the UI thread when projects may have appeared or disappeared.
+pub type OnChange = Arc<dyn Fn(&mut Cursive) + Send + Sync>;
+
+/// Watch the projects below `root` until `stop` or the next `spawn`.
+pub fn spawn(cb_sink: CbSink, root: PathBuf, settings: ScanSettings, on_change: OnChange) {
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let running = move || GENERATION.load(Ordering::SeqCst) == generation;
+    thread::spawn(move || {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(tx) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                warn!("Project watch disabled: {e}");
+                return;
+            }
+        };
+        let mut watched = BTreeSet::new();
+        rewatch(&mut watcher, &mut watched, &root, &settings);
+        info!("Watching {} for new and removed projects", root.display());
+        loop {
+            match rx.recv_timeout(POLL) {
+                Ok(Ok(event)) if changes_projects(&event) => {}
+                Ok(_) | Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if running() {
+                        continue;
+                    }
+                    return;
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+            if !running() {
+                return;
+            }
+            rewatch(&mut watcher, &mut watched, &root, &settings);
+            let on_change = Arc::clone(&on_change);
+            if cb_sink.send(Box::new(move |s| on_change(s))).is_err() {
+                // The TUI is gone.
+                return;
+            }
+        }
+    });
+}
+
+/// Stop the running watcher, if any.
+pub fn stop() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Watch the current discovery directories of `root`, and only them.
+fn rewatch(
+    watcher: &mut RecommendedWatcher,
+    watched: &mut BTreeSet<PathBuf>,
+    root: &Path,
+    settings: &ScanSettings,
+) {
+    let dirs: BTreeSet<PathBuf> = discovery_dirs(root, settings).into_iter().collect();
+    for gone in watched.difference(&dirs) {
+        // Fails when the directory was deleted, which already dropped the watch.
+        let _ = watcher.unwatch(gone);
+    }
+    for dir in dirs.difference(watched) {
+        if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            warn!("Cannot watch {}: {e}", dir.display());
+        }
+    }
+    *watched = dirs;
+}
+
+/// `true` when `event` may add or remove a project: a directory created,
+/// deleted or renamed, or a `Cargo.toml` created or deleted.
+fn changes_projects(event: &Event) -> bool {
+    let any_entry = match event.kind {
+        EventKind::Create(CreateKind::File) | EventKind::Remove(RemoveKind::File) => false,
+        EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_)) => {
+            true
+        }
+        _ => return false,
+    };
+    any_entry
+        || event
+            .paths
+            .iter()
+            .any(|p| p.file_name() == Some(OsStr::new("Cargo.toml")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{DataChange, RenameMode};
+
+    #[test]
+    fn only_project_level_changes_count() {
+        let event = |kind, path: &str| Event::new(kind).add_path(PathBuf::from(path));
+        assert!(changes_projects(&event(
+            EventKind::Create(CreateKind::Folder),
+            "/projects/new"
+        )));
+        assert!(changes_projects(&event(
+            EventKind::Remove(RemoveKind::Folder),
+            "/projects/old"
+        )));
+        assert!(changes_projects(&event(
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)),
+            "/projects/renamed"
+        )));
+        assert!(changes_projects(&event(
+            EventKind::Create(CreateKind::File),
+            "/projects/cloned/Cargo.toml"
+        )));
+        assert!(!changes_projects(&event(
+            EventKind::Create(CreateKind::File),
+            "/projects/notes.md"
+        )));
+        assert!(!changes_projects(&event(
+            EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+            "/projects/cloned/Cargo.toml"
+        )));
+    }
+}
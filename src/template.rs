@@ -0,0 +1,64 @@
+//! Minimal text templates.
+//!
+//! Placeholders are written `{{name}}` (surrounding spaces allowed). Every
+//! placeholder must have a value: an unknown name is an error rather than an
+//! empty string, so generated files never silently miss a field.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Errors raised while rendering a template.
+#[derive(Debug, PartialEq, Eq)]
+pub enum TemplateError {
+    UnknownVariable(String),
+    Unterminated,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVariable(name) => write!(f, "Unknown template variable '{name}'"),
+            Self::Unterminated => write!(f, "Unterminated '{{{{' in template"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Replace every `{{name}}` in `template` with `vars[name]`.
+pub fn render(template: &str, vars: &BTreeMap<&str, String>) -> Result<String, TemplateError> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or(TemplateError::Unterminated)?;
+        let name = after[..end].trim();
+        let value = vars
+            .get(name)
+            .ok_or_else(|| TemplateError::UnknownVariable(name.to_string()))?;
+        out.push_str(value);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitutes_and_rejects_unknown_variables() {
+        let vars = BTreeMap::from([("name", "demo".to_string()), ("version", "1.0".into())]);
+        assert_eq!(
+            render("{{name}} v{{ version }}!", &vars).unwrap(),
+            "demo v1.0!"
+        );
+        assert_eq!(
+            render("{{missing}}", &vars),
+            Err(TemplateError::UnknownVariable("missing".into()))
+        );
+        assert_eq!(render("{{name", &vars), Err(TemplateError::Unterminated));
+    }
+}
@@ -1,32 +1,253 @@
-//! Theme module: provides a modern dark palette for the `rustm` TUI.
+//! Theme module: palettes for the `rustm` TUI.
 //!
 //! Only the palette entries supported by Cursive 0.21 are customized:
 //! `Background`, `Shadow`, `View`, `Primary`, `Secondary`, `Tertiary`,
 //! `TitlePrimary`, `TitleSecondary`, `Highlight`, `HighlightInactive`, `HighlightText`.
+//! Two custom entries, `age_fresh` and `age_weeks`, color the recent times of
+//! the project list (see `Age`); older ones use `Tertiary`.
 //!
-//! Goals:
+//! Goals of the default dark theme:
 //! - Dark, low-glare backgrounds (neutral blue‑gray range).
 //! - High‑contrast accent (purple) for focus & selection.
 //! - Clear hierarchy of text brightness (`Primary` > `Secondary` > `Tertiary`).
 //! - Soft, readable titles (slightly brighter than body text).
 //! - Avoid pure white to reduce eye strain.
 //!
-//! Public API:
-//! - `apply_theme(&mut Cursive)` to set the theme on the root.
-//! - `modern_theme()` returns the configured `Theme` (for further user tweaking).
+//! Themes are named: `dark`, `light` and `solarized` are built in, and every
+//! `themes/<name>.yaml` in the configuration directory adds (or replaces) one.
+//! A theme file starts from a built-in theme and overrides palette entries:
+//!
+//! ```yaml
+//! base: solarized        # optional, dark by default
+//! borders: outset        # simple, outset or none
+//! shadow: false
+//! palette:
+//!   highlight: "#d33682"
+//!   title_primary: light yellow
+//!   age_fresh: "#859900"
+//! ```
 //!
-//! Future extensions (not implemented here):
-//! - Light theme variant.
-//! - Dynamically loading theme from a user config file.
-//! - Allow runtime switching.
+//! Colors are `#rrggbb`, a base color name (`red`, `light blue`) or `default`.
 //!
-//! This file is deliberately dependency‑light and UI‑agnostic.
+//! `auto` (the default) picks `light` or `dark` from the terminal background
+//! reported by `COLORFGBG` (`fg;bg`, set by rxvt, Konsole, iTerm2 and others);
+//! `dark` when it is unset or unparsable.
+//!
+//! Public API:
+//! - `apply_theme(&mut Cursive, name)` to set a theme on the root (dark when it cannot be loaded).
+//! - `load(name)` returns a named `Theme` (used by the settings screen for its preview).
+//! - `names()` lists the available themes.
+//! - `modern_theme()` returns the dark `Theme`.
+//! - `age_color(&Palette, Age)` is the color of a time of the project list.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use cursive::theme::{BaseColor, BorderStyle, Color, Palette, PaletteColor, Theme};
+use log::warn;
+use serde::Deserialize;
+
+use crate::config::Config;
+
+/// Directory of theme files, inside the configuration directory.
+const THEMES_DIR: &str = "themes";
+
+/// Theme used when none is configured: `light` or `dark`, after the terminal background.
+pub const AUTO_THEME: &str = "auto";
+
+/// Theme used when the configured one cannot be loaded.
+pub const DEFAULT_THEME: &str = "dark";
+
+/// Themes available without a file.
+const BUILT_IN: [&str; 3] = ["dark", "light", "solarized"];
+
+/// Custom palette entries: time of the last week, of the last weeks.
+const AGE_FRESH: &str = "age_fresh";
+const AGE_WEEKS: &str = "age_weeks";
+
+/// Environment variable describing the terminal colors, `fg;bg` or `fg;default;bg`.
+const COLORFGBG_VAR: &str = "COLORFGBG";
+
+/// Content of `themes/<name>.yaml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    /// Built-in theme the file starts from.
+    base: Option<String>,
+    borders: Option<String>,
+    shadow: Option<bool>,
+    /// Palette entry (`highlight`, `view`, ...) to color.
+    palette: BTreeMap<String, String>,
+}
+
+/// Age bucket of a time of the project list, after the `age_colors` thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Age {
+    /// Green by default.
+    Fresh,
+    /// Yellow by default.
+    Weeks,
+    /// Dimmed (`Tertiary`).
+    Months,
+}
+
+/// Color of a time in the `age` bucket.
+pub fn age_color(palette: &Palette, age: Age) -> Color {
+    let custom = |key, fallback| palette.custom(key).copied().unwrap_or(fallback);
+    match age {
+        Age::Fresh => custom(AGE_FRESH, Color::Dark(BaseColor::Green)),
+        Age::Weeks => custom(AGE_WEEKS, Color::Dark(BaseColor::Yellow)),
+        Age::Months => palette[PaletteColor::Tertiary],
+    }
+}
+
+/// Errors raised while loading a theme.
+#[derive(Debug)]
+pub enum ThemeError {
+    /// No built-in theme and no file of that name.
+    Unknown(String),
+    Io(PathBuf, io::Error),
+    Invalid(PathBuf, String),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(name) => write!(f, "Unknown theme '{name}'"),
+            Self::Io(path, e) => write!(f, "Cannot read {}: {e}", path.display()),
+            Self::Invalid(path, msg) => write!(f, "Invalid theme {}: {msg}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Apply the theme `name` to a `Cursive` root, the default one when it cannot be loaded.
+pub fn apply_theme(siv: &mut cursive::Cursive, name: &str) {
+    match load(name) {
+        Ok(theme) => siv.set_theme(theme),
+        Err(e) => {
+            warn!("{e}; using the {DEFAULT_THEME} theme");
+            siv.set_theme(modern_theme());
+        }
+    }
+}
+
+/// Theme `name`: `themes/<name>.yaml` when it exists, else the built-in theme.
+///
+/// `auto` is the built-in theme matching the terminal background.
+pub fn load(name: &str) -> Result<Theme, ThemeError> {
+    if name == AUTO_THEME {
+        return load(detect());
+    }
+    let file = themes_dir().join(format!("{name}.yaml"));
+    if file.is_file() {
+        return load_file(&file);
+    }
+    built_in(name).ok_or_else(|| ThemeError::Unknown(name.to_string()))
+}
+
+/// `auto`, the built-in themes, then the theme files, sorted and without duplicates.
+pub fn names() -> Vec<String> {
+    let mut files = BTreeSet::new();
+    if let Ok(entries) = fs::read_dir(themes_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|e| e == "yaml")
+                && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+                && stem != AUTO_THEME
+                && !BUILT_IN.contains(&stem)
+            {
+                files.insert(stem.to_string());
+            }
+        }
+    }
+    std::iter::once(AUTO_THEME)
+        .chain(BUILT_IN)
+        .map(ToString::to_string)
+        .chain(files)
+        .collect()
+}
+
+/// `light` when `COLORFGBG` reports a light background, else `dark`.
+pub fn detect() -> &'static str {
+    let colorfgbg = std::env::var(COLORFGBG_VAR).unwrap_or_default();
+    if light_background(&colorfgbg) {
+        "light"
+    } else {
+        "dark"
+    }
+}
+
+/// `true` when the background of a `COLORFGBG` value is white or a bright color.
+fn light_background(colorfgbg: &str) -> bool {
+    colorfgbg
+        .rsplit(';')
+        .next()
+        .and_then(|bg| bg.trim().parse::<u8>().ok())
+        .is_some_and(|bg| matches!(bg, 7 | 9..=15))
+}
+
+/// `<config dir>/themes`.
+pub fn themes_dir() -> PathBuf {
+    let cfg_file = Config::file_path();
+    cfg_file
+        .parent()
+        .map_or_else(Config::file_path, Path::to_path_buf)
+        .join(THEMES_DIR)
+}
+
+fn built_in(name: &str) -> Option<Theme> {
+    match name {
+        "dark" => Some(modern_theme()),
+        "light" => Some(light_theme()),
+        "solarized" => Some(solarized_theme()),
+        _ => None,
+    }
+}
 
-use cursive::theme::{BorderStyle, Color, Palette, PaletteColor, Theme};
+fn load_file(path: &Path) -> Result<Theme, ThemeError> {
+    let yaml = fs::read_to_string(path).map_err(|e| ThemeError::Io(path.to_path_buf(), e))?;
+    from_yaml(&yaml).map_err(|msg| ThemeError::Invalid(path.to_path_buf(), msg))
+}
 
-/// Apply the modern theme directly to a `Cursive` root.
-pub fn apply_theme(siv: &mut cursive::Cursive) {
-    siv.set_theme(modern_theme());
+/// Theme described by the YAML of a theme file.
+fn from_yaml(yaml: &str) -> Result<Theme, String> {
+    let file = serde_norway::from_str::<Option<ThemeFile>>(yaml)
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+    let base = file.base.as_deref().unwrap_or(DEFAULT_THEME);
+    let mut theme = built_in(base).ok_or_else(|| format!("unknown base theme '{base}'"))?;
+    if let Some(borders) = file.borders {
+        theme.borders = match borders.as_str() {
+            "simple" => BorderStyle::Simple,
+            "outset" => BorderStyle::Outset,
+            "none" => BorderStyle::None,
+            other => return Err(format!("unknown border style '{other}'")),
+        };
+    }
+    if let Some(shadow) = file.shadow {
+        theme.shadow = shadow;
+    }
+    for (entry, value) in file.palette {
+        if entry != AGE_FRESH && entry != AGE_WEEKS && entry.parse::<PaletteColor>().is_err() {
+            return Err(format!("unknown palette entry '{entry}'"));
+        }
+        let color = Color::parse(value.trim())
+            .ok_or_else(|| format!("invalid color '{value}' for {entry}"))?;
+        theme.palette.set_color(&entry, color);
+    }
+    Ok(theme)
 }
 
 /// Construct and return the modern dark theme.
@@ -38,6 +259,64 @@ pub fn modern_theme() -> Theme {
     }
 }
 
+/// Light theme for terminals with a light background, same purple accent as the dark one.
+fn light_theme() -> Theme {
+    let mut p = Palette::default();
+
+    p[PaletteColor::Background] = rgb(236, 238, 242); // Light gray page.
+    p[PaletteColor::Shadow] = rgb(200, 204, 212);
+    p[PaletteColor::View] = rgb(250, 250, 252); // Panels, almost white.
+
+    p[PaletteColor::Primary] = rgb(28, 30, 34); // Near black.
+    p[PaletteColor::Secondary] = rgb(74, 80, 92);
+    p[PaletteColor::Tertiary] = rgb(112, 118, 130); // Hints, still readable on white.
+
+    p[PaletteColor::TitlePrimary] = rgb(18, 20, 24);
+    p[PaletteColor::TitleSecondary] = rgb(84, 90, 102);
+
+    p[PaletteColor::Highlight] = rgb(112, 48, 220); // Deeper purple for contrast on white.
+    p[PaletteColor::HighlightInactive] = rgb(150, 120, 210);
+    p[PaletteColor::HighlightText] = rgb(255, 255, 255);
+
+    p.set_color(AGE_FRESH, rgb(30, 130, 60));
+    p.set_color(AGE_WEEKS, rgb(160, 110, 0));
+
+    Theme {
+        borders: BorderStyle::Simple,
+        shadow: false,
+        palette: p,
+    }
+}
+
+/// Solarized (dark variant), with the blue accent for focus & selection.
+fn solarized_theme() -> Theme {
+    let mut p = Palette::default();
+
+    p[PaletteColor::Background] = rgb(0, 43, 54); // base03
+    p[PaletteColor::Shadow] = rgb(0, 30, 38);
+    p[PaletteColor::View] = rgb(7, 54, 66); // base02
+
+    p[PaletteColor::Primary] = rgb(147, 161, 161); // base1
+    p[PaletteColor::Secondary] = rgb(131, 148, 150); // base0
+    p[PaletteColor::Tertiary] = rgb(88, 110, 117); // base01
+
+    p[PaletteColor::TitlePrimary] = rgb(181, 137, 0); // yellow
+    p[PaletteColor::TitleSecondary] = rgb(131, 148, 150); // base0
+
+    p[PaletteColor::Highlight] = rgb(38, 139, 210); // blue
+    p[PaletteColor::HighlightInactive] = rgb(88, 110, 117); // base01
+    p[PaletteColor::HighlightText] = rgb(253, 246, 227); // base3
+
+    p.set_color(AGE_FRESH, rgb(133, 153, 0)); // green
+    p.set_color(AGE_WEEKS, rgb(181, 137, 0)); // yellow
+
+    Theme {
+        borders: BorderStyle::Simple,
+        shadow: false,
+        palette: p,
+    }
+}
+
 /// Build the palette with valid `PaletteColor` variants only.
 fn build_palette() -> Palette {
     let mut p = Palette::default();
@@ -64,6 +343,10 @@ fn build_palette() -> Palette {
     p[PaletteColor::HighlightInactive] = accent_inactive;
     p[PaletteColor::HighlightText] = rgb(255, 255, 255); // Text on highlighted background.
 
+    // Ages of the project list (custom entries).
+    p.set_color(AGE_FRESH, rgb(120, 200, 130)); // Soft green.
+    p.set_color(AGE_WEEKS, rgb(230, 190, 90)); // Amber.
+
     p
 }
 
@@ -113,4 +396,73 @@ mod tests {
             contrast_ratio
         );
     }
+
+    #[test]
+    fn theme_files_override_a_base_theme() {
+        let theme = from_yaml(
+            "base: solarized\nborders: outset\npalette:\n  highlight: \"#d33682\"\n  title_primary: light yellow\n",
+        )
+        .unwrap();
+        assert_eq!(theme.borders, BorderStyle::Outset);
+        assert_eq!(theme.palette[PaletteColor::Highlight], rgb(211, 54, 130));
+        assert_eq!(
+            theme.palette[PaletteColor::TitlePrimary],
+            Color::Light(cursive::theme::BaseColor::Yellow)
+        );
+        assert_eq!(theme.palette[PaletteColor::View], rgb(7, 54, 66));
+        assert_eq!(age_color(&theme.palette, Age::Weeks), rgb(181, 137, 0));
+
+        let theme = from_yaml("palette:\n  age_fresh: \"#00ff00\"\n  tertiary: red\n").unwrap();
+        assert_eq!(age_color(&theme.palette, Age::Fresh), rgb(0, 255, 0));
+        assert_eq!(
+            age_color(&theme.palette, Age::Months),
+            Color::Dark(BaseColor::Red)
+        );
+        assert_eq!(
+            age_color(&Palette::default(), Age::Fresh),
+            Color::Dark(BaseColor::Green)
+        );
+
+        assert_eq!(from_yaml("").unwrap().palette, modern_theme().palette);
+        assert!(from_yaml("base: neon\n").is_err());
+        assert!(from_yaml("palette:\n  glow: red\n").is_err());
+        assert!(from_yaml("palette:\n  view: reddish\n").is_err());
+    }
+
+    #[test]
+    fn light_palette_is_readable_and_detected() {
+        for theme in [light_theme(), solarized_theme()] {
+            let primary = theme.palette[PaletteColor::Primary];
+            let view = theme.palette[PaletteColor::View];
+            let ratio = contrast(primary, view);
+            assert!(ratio > 4.5, "Contrast ratio too low: {ratio:.2}");
+        }
+
+        assert!(light_background("0;15"));
+        assert!(light_background("0;default;7"));
+        assert!(!light_background("15;0"));
+        assert!(!light_background("7;8"));
+        assert!(!light_background(""));
+        assert!(!light_background("default;default"));
+    }
+
+    /// WCAG contrast ratio of two RGB colors.
+    fn contrast(a: Color, b: Color) -> f32 {
+        let luminance = |c: Color| -> f32 {
+            let Color::Rgb(r, g, b) = c else {
+                return 0.0;
+            };
+            let channel = |v: u8| {
+                let v = f32::from(v) / 255.0;
+                if v <= 0.039_28 {
+                    v / 12.92
+                } else {
+                    ((v + 0.055) / 1.055).powf(2.4)
+                }
+            };
+            0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+        };
+        let (la, lb) = (luminance(a), luminance(b));
+        (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+    }
 }
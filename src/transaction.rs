@@ -0,0 +1,173 @@
+//! All-or-nothing multi-step operations.
+//!
+//! Operations touching several files and git state (rename, workspace
+//! conversion, dependency hoisting) run their steps in a `Transaction`. Each
+//! step that succeeded leaves an undo closure in the journal. `commit` forgets
+//! them once the whole operation succeeded. Otherwise, when the transaction is
+//! dropped (an early `?` return, or a panic), the steps are undone in reverse
+//! order, so a failure midway restores the previous state instead of leaving a
+//! half-renamed project.
+//!
+//! Undo failures cannot be reported to the caller, which is already returning
+//! the original error: they are logged with what is left to fix by hand.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use log::{error, info, warn};
+
+type Undo = Box<dyn FnOnce() -> Result<(), String>>;
+
+/// Journal of the steps of one operation.
+pub struct Transaction {
+    /// What the operation does, for the log.
+    name: String,
+    /// Undo of each step performed, in order.
+    journal: Vec<(String, Undo)>,
+}
+
+impl Transaction {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            journal: Vec::new(),
+        }
+    }
+
+    /// Run `step`, described by `what`; when it succeeds, journal `undo` to
+    /// revert it.
+    pub fn step<T, E>(
+        &mut self,
+        what: impl Into<String>,
+        step: impl FnOnce() -> Result<T, E>,
+        undo: impl FnOnce() -> Result<(), String> + 'static,
+    ) -> Result<T, E> {
+        let value = step()?;
+        self.journal.push((what.into(), Box::new(undo)));
+        Ok(value)
+    }
+
+    /// Rename `from` to `to`; undone by renaming back.
+    pub fn rename(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        let (back_from, back_to) = (to.to_path_buf(), from.to_path_buf());
+        self.step(
+            format!("rename {} to {}", from.display(), to.display()),
+            || fs::rename(from, to),
+            move || fs::rename(&back_from, &back_to).map_err(|e| e.to_string()),
+        )
+    }
+
+    /// Create `dir` and its missing parents; undone by removing the outermost
+    /// directory created, with everything below it.
+    pub fn create_dir_all(&mut self, dir: &Path) -> io::Result<()> {
+        let created = dir
+            .ancestors()
+            .take_while(|d| !d.exists())
+            .last()
+            .map(Path::to_path_buf);
+        self.step(
+            format!("create {}", dir.display()),
+            || fs::create_dir_all(dir),
+            move || match created {
+                Some(created) => fs::remove_dir_all(&created).map_err(|e| e.to_string()),
+                None => Ok(()),
+            },
+        )
+    }
+
+    /// Save the content of `path` (or its absence) before it is written;
+    /// undone by putting it back.
+    pub fn preserve(&mut self, path: &Path) -> io::Result<()> {
+        let path = path.to_path_buf();
+        let saved = match fs::read(&path) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        self.step(
+            format!("write {}", path.display()),
+            || Ok::<_, io::Error>(()),
+            move || restore(&path, saved).map_err(|e| e.to_string()),
+        )
+    }
+
+    /// The operation succeeded: keep every step.
+    pub fn commit(mut self) {
+        self.journal.clear();
+    }
+
+    /// Undo the steps performed, most recent first.
+    fn rollback(&mut self) {
+        if self.journal.is_empty() {
+            return;
+        }
+        warn!("{} failed: rolling back", self.name);
+        while let Some((what, undo)) = self.journal.pop() {
+            match undo() {
+                Ok(()) => info!("Undone: {what}"),
+                Err(e) => error!("Cannot undo '{what}' of {}: {e}", self.name),
+            }
+        }
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        self.rollback();
+    }
+}
+
+/// Put `content` back at `path`; `None` removes the file.
+fn restore(path: &Path, content: Option<Vec<u8>>) -> io::Result<()> {
+    match content {
+        Some(content) => fs::write(path, content),
+        None => match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    fn run(dir: &Path, fail: bool) -> Result<(), String> {
+        let mut tx = Transaction::new("test operation");
+        tx.create_dir_all(&dir.join("a/b"))
+            .map_err(|e| e.to_string())?;
+        tx.rename(&dir.join("file"), &dir.join("a/b/file"))
+            .map_err(|e| e.to_string())?;
+        tx.preserve(&dir.join("kept")).map_err(|e| e.to_string())?;
+        fs::write(dir.join("kept"), "changed").unwrap();
+        tx.preserve(&dir.join("new")).map_err(|e| e.to_string())?;
+        fs::write(dir.join("new"), "created").unwrap();
+        tx.step(
+            "fail",
+            || if fail { Err("step failed") } else { Ok(()) },
+            || Ok(()),
+        )?;
+        tx.commit();
+        Ok(())
+    }
+
+    #[test]
+    fn failed_operations_are_rolled_back() {
+        let dir = TempDir::new("transaction");
+        fs::write(dir.join("file"), "moved").unwrap();
+        fs::write(dir.join("kept"), "original").unwrap();
+
+        assert_eq!(run(&dir, true), Err("step failed".to_string()));
+        assert_eq!(fs::read_to_string(dir.join("file")).unwrap(), "moved");
+        assert_eq!(fs::read_to_string(dir.join("kept")).unwrap(), "original");
+        assert!(!dir.join("new").exists());
+        assert!(!dir.join("a").exists());
+
+        run(&dir, false).unwrap();
+        assert!(dir.join("a/b/file").is_file());
+        assert_eq!(fs::read_to_string(dir.join("kept")).unwrap(), "changed");
+        assert!(dir.join("new").is_file());
+    }
+}
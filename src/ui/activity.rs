@@ -0,0 +1,92 @@
+//! Recent activity screen (global mode).
+//!
+//! Lists the most recent commits across every project, newest first, with
+//! their age, project, author and summary. Enter on a commit shows its full
+//! message and offers "Cherry-pick onto..." (see `cherry_pick`).
+
+use cursive::Cursive;
+use cursive::view::{Resizable, Scrollable};
+use cursive::views::{Dialog, SelectView, TextView};
+
+use crate::config::Config;
+use crate::format;
+use crate::metadata;
+use crate::project::activity::{self, Activity};
+use crate::project::list::{cached_projects, list_projects};
+use crate::ui::{cherry_pick, error_view, progress};
+
+/// Commits shown in the feed.
+const FEED_SIZE: usize = 100;
+
+/// Collect the feed in the background, then show it.
+pub fn show(s: &mut Cursive, config: &Config) {
+    let config = config.clone();
+    progress::run(
+        s,
+        "Recent activity",
+        move |p| {
+            let mut projects = cached_projects(&config);
+            if projects.is_empty() {
+                p.log("Scanning projects...");
+                projects = list_projects(&config).map_err(|e| e.to_string())?;
+            }
+            Ok(activity::collect(&projects, FEED_SIZE, &|line| p.log(line)))
+        },
+        |s, result: Result<Vec<Activity>, String>| match result {
+            Ok(feed) if feed.is_empty() => {
+                s.add_layer(Dialog::info("No commits found in the projects."));
+            }
+            Ok(feed) => show_feed(s, feed),
+            Err(e) => error_view::show_message(s, "Failed to list projects", &e),
+        },
+    );
+}
+
+fn show_feed(s: &mut Cursive, feed: Vec<Activity>) {
+    let now = i64::try_from(metadata::now()).unwrap_or(i64::MAX);
+    let project_width = feed.iter().map(|a| a.project.len()).max().unwrap_or(0);
+    let author_width = feed
+        .iter()
+        .map(|a| a.commit.author.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(20);
+
+    let mut select = SelectView::<Activity>::new();
+    for entry in feed {
+        let label = format!(
+            "{:>4}  {:<project_width$}  {:<author_width$.author_width$}  {}",
+            format::timestamp_column(entry.commit.time, now),
+            entry.project,
+            entry.commit.author,
+            entry.commit.summary,
+        );
+        select.add_item(label, entry);
+    }
+    select.set_on_submit(move |s, entry: &Activity| {
+        let text = format!(
+            "{}  {}\nAuthor: {}\n{}\n\n{}",
+            entry.commit.id,
+            entry.path.display(),
+            entry.commit.author,
+            format::timestamp(entry.commit.time, now),
+            entry.commit.message
+        );
+        let entry = entry.clone();
+        s.add_layer(
+            Dialog::around(TextView::new(text).scrollable().max_size((80, 20)))
+                .title(entry.project.clone())
+                .button("Cherry-pick onto...", move |s| {
+                    s.pop_layer();
+                    cherry_pick::show(s, &entry.path, &entry.commit);
+                })
+                .dismiss_button("Close"),
+        );
+    });
+
+    s.add_layer(
+        Dialog::around(select.scrollable().fixed_size((100, 22)))
+            .title("Recent activity")
+            .dismiss_button("Close"),
+    );
+}
@@ -0,0 +1,234 @@
+//! Project archiving dialogs.
+//!
+//! "Archive..." in the project menu moves the project to the `archive/`
+//! directory or compresses it to a `.tar.gz` there (see `project::archive`);
+//! it disappears from the project list. "Archived projects" in the main menu
+//! lists the archived projects with their size; Enter on one offers to
+//! restore it to the projects directory or, after typing its name, to purge
+//! it. The metadata cache entry (tags, pin, trust) follows a project moved as
+//! a directory; it is dropped with a compressed one.
+
+use std::path::Path;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, EditView, LinearLayout, SelectView, TextView};
+use log::error;
+
+use crate::config::Config;
+use crate::format;
+use crate::metadata;
+use crate::project::archive::{self, Archived, Format};
+use crate::project::list::ProjectInfo;
+use crate::ui::{error_view, notify, progress, project_list};
+
+const ARCHIVED_VIEW: &str = "archived_projects";
+const PURGE_INPUT: &str = "archived_purge";
+
+/// Ask how to archive `project`, then archive it.
+pub fn confirm(s: &mut Cursive, config: &Config, project: &ProjectInfo) {
+    let mut text = format!(
+        "Archive {}?\n\nIt moves to {}/ in the projects directory and leaves the project list; \
+         \"Archived projects\" in the main menu restores it.",
+        project.name,
+        archive::ARCHIVE_DIR
+    );
+    if project.has_uncommitted_changes {
+        text.push_str("\n\nNote: the project has uncommitted changes.");
+    }
+    let choice = |format: Format| {
+        let config = config.clone();
+        let project = project.clone();
+        move |s: &mut Cursive| {
+            s.pop_layer();
+            run_archive(s, &config, &project, format);
+        }
+    };
+    s.add_layer(
+        Dialog::text(text)
+            .title("Archive project")
+            .button("Move", choice(Format::Directory))
+            .button("Compress (.tar.gz)", choice(Format::TarGz))
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn run_archive(s: &mut Cursive, config: &Config, project: &ProjectInfo, format: Format) {
+    let root = Path::new(config.projects_directory()).to_path_buf();
+    let path = project.path.clone();
+    let config = config.clone();
+    progress::run(
+        s,
+        &format!("Archiving {}", project.name),
+        {
+            let path = path.clone();
+            move |_| archive::archive(&root, &path, format)
+        },
+        move |s, result| match result {
+            Ok(target) => {
+                metadata::update(|store| match format {
+                    Format::Directory => store.rename(&path, &target),
+                    Format::TarGz => store.remove(&path),
+                });
+                project_list::refresh(s, &config);
+                notify::toast(s, format!("Archived to {}.", target.display()));
+            }
+            Err(e) => {
+                error!("Archiving {} failed: {e}", path.display());
+                error_view::show(s, "Cannot archive", &e);
+            }
+        },
+    );
+}
+
+/// List the archived projects.
+pub fn show(s: &mut Cursive, config: &Config) {
+    let root = Path::new(config.projects_directory()).to_path_buf();
+    let config = config.clone();
+    progress::run(
+        s,
+        "Archived projects",
+        move |_| archive::list(&root),
+        move |s, result| match result {
+            Ok(archived) if archived.is_empty() => {
+                s.add_layer(Dialog::info("No archived project."));
+            }
+            Ok(archived) => show_list(s, &config, archived),
+            Err(e) => {
+                error!("Listing archived projects failed: {e}");
+                error_view::show(s, "Cannot list the archived projects", &e);
+            }
+        },
+    );
+}
+
+fn show_list(s: &mut Cursive, config: &Config, archived: Vec<Archived>) {
+    let name_width = archived.iter().map(|a| a.name.len()).max().unwrap_or(0);
+    let mut select = SelectView::<Archived>::new();
+    for a in archived {
+        let label = format!(
+            "{:<name_width$}  {:>10}  {}",
+            a.name,
+            format::size(a.size),
+            match a.format {
+                Format::Directory => "directory",
+                Format::TarGz => ".tar.gz",
+            }
+        );
+        select.add_item(label, a);
+    }
+    let config = config.clone();
+    select.set_on_submit(move |s, a: &Archived| actions(s, &config, a));
+    s.add_layer(
+        Dialog::around(
+            select
+                .with_name(ARCHIVED_VIEW)
+                .scrollable()
+                .fixed_size((70, 16)),
+        )
+        .title("Archived projects")
+        .dismiss_button("Close"),
+    );
+}
+
+fn actions(s: &mut Cursive, config: &Config, archived: &Archived) {
+    let restore_config = config.clone();
+    let restore_archived = archived.clone();
+    let purge_config = config.clone();
+    let purge_archived = archived.clone();
+    s.add_layer(
+        Dialog::text(format!("{}\n{}", archived.name, archived.path.display()))
+            .title("Archived project")
+            .button("Restore", move |s| {
+                s.pop_layer();
+                restore(s, &restore_config, &restore_archived);
+            })
+            .button("Purge...", move |s| {
+                s.pop_layer();
+                confirm_purge(s, &purge_config, &purge_archived);
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn restore(s: &mut Cursive, config: &Config, archived: &Archived) {
+    let root = Path::new(config.projects_directory()).to_path_buf();
+    let config = config.clone();
+    let archived = archived.clone();
+    progress::run(
+        s,
+        &format!("Restoring {}", archived.name),
+        {
+            let archived = archived.clone();
+            move |_| archive::restore(&root, &archived)
+        },
+        move |s, result| match result {
+            Ok(path) => {
+                metadata::update(|store| match archived.format {
+                    Format::Directory => store.rename(&archived.path, &path),
+                    // The user archived it: not a stranger to quarantine.
+                    Format::TarGz => {
+                        store.entry_mut(&path);
+                    }
+                });
+                reload(s, &config);
+                project_list::refresh(s, &config);
+                notify::toast(s, format!("Restored to {}.", path.display()));
+            }
+            Err(e) => {
+                error!("Restoring {} failed: {e}", archived.path.display());
+                error_view::show(s, "Cannot restore", &e);
+            }
+        },
+    );
+}
+
+fn confirm_purge(s: &mut Cursive, config: &Config, archived: &Archived) {
+    let config = config.clone();
+    let archived = archived.clone();
+    let text = format!(
+        "Permanently delete {}?\n\nType \"{}\" to confirm:",
+        archived.path.display(),
+        archived.name
+    );
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(text))
+                .child(EditView::new().with_name(PURGE_INPUT).fixed_width(30)),
+        )
+        .title("Purge archived project")
+        .button("Purge", move |s| {
+            let typed = s
+                .call_on_name(PURGE_INPUT, |v: &mut EditView| v.get_content())
+                .unwrap_or_default();
+            if typed.trim() != archived.name {
+                s.add_layer(Dialog::info(
+                    "The name does not match; nothing was deleted.",
+                ));
+                return;
+            }
+            match archive::purge(&archived) {
+                Ok(()) => {
+                    metadata::update(|store| store.remove(&archived.path));
+                    s.pop_layer();
+                    reload(s, &config);
+                    notify::toast(s, format!("Purged {}.", archived.path.display()));
+                }
+                Err(e) => {
+                    error!("Purging {} failed: {e}", archived.path.display());
+                    error_view::show(s, "Cannot purge", &e);
+                }
+            }
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+/// Replace the open list of archived projects with a fresh one.
+fn reload(s: &mut Cursive, config: &Config) {
+    if s.find_name::<SelectView<Archived>>(ARCHIVED_VIEW).is_some() {
+        s.pop_layer();
+        show(s, config);
+    }
+}
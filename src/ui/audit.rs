@@ -0,0 +1,160 @@
+//! Security audit results.
+//!
+//! Runs `cargo audit` for one project or for every listed project in the
+//! background, then lists the findings (most severe first) with severity,
+//! advisory id, package and title. Enter on a finding shows the advisory
+//! details and can open it on rustsec.org.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use cursive::Cursive;
+use cursive::view::{Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, SelectView, TextView};
+use log::{error, warn};
+
+use crate::desktop;
+use crate::project::audit::{self, AuditError, Finding};
+use crate::project::list::ProjectInfo;
+use crate::ui::{error_view, progress};
+
+/// A finding and the project it was reported for.
+type ProjectFinding = (String, Finding);
+
+/// Audit the project at `path`.
+pub fn audit_project(s: &mut Cursive, name: &str, path: &Path) {
+    let name = name.to_string();
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        "Security audit",
+        move |p| {
+            p.log("Running cargo audit...");
+            audit::audit(&path)
+        },
+        move |s, result| match result {
+            Ok(findings) => {
+                let findings = findings.into_iter().map(|f| (name.clone(), f)).collect();
+                show_results(s, &format!("Audited {name}."), findings);
+            }
+            Err(e) => {
+                error!("Security audit failed: {e}");
+                error_view::show(s, "Security audit failed", &e);
+            }
+        },
+    );
+}
+
+/// Audit every project of `projects`, one after the other.
+pub fn audit_all(s: &mut Cursive, projects: Vec<ProjectInfo>) {
+    progress::run(
+        s,
+        "Security audit (all projects)",
+        move |p| {
+            let mut findings = Vec::new();
+            let mut audited = 0;
+            let mut skipped: Vec<(String, AuditError)> = Vec::new();
+            for project in &projects {
+                p.log(format!("Auditing {}...", project.name));
+                match audit::audit(&project.path) {
+                    Ok(found) => {
+                        audited += 1;
+                        findings.extend(found.into_iter().map(|f| (project.name.clone(), f)));
+                    }
+                    Err(e @ AuditError::NotInstalled) => return Err(e),
+                    Err(e) => {
+                        warn!("Audit of {} skipped: {e}", project.path.display());
+                        skipped.push((project.name.clone(), e));
+                    }
+                }
+            }
+            findings.sort_by(|a: &ProjectFinding, b| {
+                b.1.score
+                    .unwrap_or(-1.0)
+                    .total_cmp(&a.1.score.unwrap_or(-1.0))
+            });
+            Ok((audited, skipped, findings))
+        },
+        |s, result| match result {
+            Ok((audited, skipped, findings)) => {
+                let mut summary = format!("Audited {audited} project(s).");
+                for (name, e) in &skipped {
+                    write!(summary, "\nSkipped {name}: {e}").unwrap();
+                }
+                show_results(s, &summary, findings);
+            }
+            Err(e) => {
+                error!("Security audit failed: {e}");
+                error_view::show(s, "Security audit failed", &e);
+            }
+        },
+    );
+}
+
+fn show_results(s: &mut Cursive, summary: &str, findings: Vec<ProjectFinding>) {
+    if findings.is_empty() {
+        s.add_layer(
+            Dialog::info(format!("{summary}\nNo known vulnerability.")).title("Security audit"),
+        );
+        return;
+    }
+
+    let mut select = SelectView::<ProjectFinding>::new();
+    for (project, f) in findings {
+        let severity = match (f.severity(), f.score) {
+            (Some(severity), Some(score)) => format!("{severity} {score:.1}"),
+            _ => f.kind.clone(),
+        };
+        select.add_item(
+            format!(
+                "[{severity}] {}  {project}: {} {}  {}",
+                f.id.as_deref().unwrap_or("-"),
+                f.package,
+                f.version,
+                f.title
+            ),
+            (project, f),
+        );
+    }
+    select.set_on_submit(|s, (project, f): &ProjectFinding| show_finding(s, project, f));
+
+    let count = select.len();
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(summary))
+                .child(select.scrollable().fixed_size((100, 18))),
+        )
+        .title(format!("Security audit: {count} finding(s)"))
+        .dismiss_button("Close"),
+    );
+}
+
+fn show_finding(s: &mut Cursive, project: &str, f: &Finding) {
+    let mut text = format!(
+        "{}\n\nProject: {project}\nPackage: {} {}\nKind: {}",
+        f.title, f.package, f.version, f.kind
+    );
+    if let (Some(severity), Some(score)) = (f.severity(), f.score) {
+        write!(text, "\nSeverity: {severity} (CVSS {score:.1})").unwrap();
+    }
+    if !f.patched.is_empty() {
+        write!(text, "\nPatched: {}", f.patched.join(", ")).unwrap();
+    }
+
+    let mut dialog = Dialog::text(text).title(f.id.clone().unwrap_or_else(|| f.kind.clone()));
+    if let Some(url) = f.url() {
+        dialog.add_button("Open advisory", move |s| {
+            if let Err(e) = desktop::open_url(&url) {
+                warn!("Cannot open browser: {e}");
+                s.add_layer(Dialog::info(format!(
+                    "Cannot open a browser ({e}).\nOpen:\n{url}"
+                )));
+            }
+        });
+    }
+    dialog.add_button("Close", |s| {
+        s.pop_layer();
+    });
+    s.add_layer(dialog);
+}
@@ -0,0 +1,239 @@
+//! Batch actions on the marked projects.
+//!
+//! Projects are marked in the project list (`mark` key, space by default);
+//! "Marked..." opens this menu of actions applied to all of them: clean
+//! build artifacts, archive, `git fetch`, `git pull` (fast-forward only; these
+//! four are shared with `rustm batch`, see `project::batch`), add tags and
+//! apply the lint policy (see
+//! `project::lint_policy`; the manifest diffs are shown first). The projects are processed
+//! one after the other behind a progress dialog, then a report shows the
+//! outcome of each, failures first. None of these actions runs project code,
+//! so untrusted projects are included.
+
+use std::path::Path;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, EditView, LinearLayout, SelectView, TextView};
+use log::warn;
+
+use crate::config::Config;
+use crate::metadata;
+use crate::project::archive::Format;
+use crate::project::batch::Operation;
+use crate::project::lint_policy::{self, Preview};
+use crate::project::list::ProjectInfo;
+use crate::project::tags;
+use crate::ui::{progress, project_list};
+
+const TAGS_INPUT: &str = "batch_tags";
+
+#[derive(Clone)]
+enum BatchAction {
+    Operation(Operation),
+    Tag,
+    LintPolicy,
+    Unmark,
+}
+
+/// What happened to one project: `Ok` with a short note, or the error.
+type Outcome = (String, Result<String, String>);
+
+/// Offer the batch actions for the marked `projects`.
+pub fn show(s: &mut Cursive, config: &Config, projects: Vec<ProjectInfo>) {
+    let mut menu = SelectView::<BatchAction>::new()
+        .item(
+            "Clean build artifacts",
+            BatchAction::Operation(Operation::Clean),
+        )
+        .item(
+            "Archive (move)",
+            BatchAction::Operation(Operation::Archive(Format::Directory)),
+        )
+        .item(
+            "Archive (.tar.gz)",
+            BatchAction::Operation(Operation::Archive(Format::TarGz)),
+        )
+        .item("Git fetch", BatchAction::Operation(Operation::Fetch))
+        .item("Git pull", BatchAction::Operation(Operation::Pull))
+        .item("Add tags...", BatchAction::Tag)
+        .item("Apply lint policy...", BatchAction::LintPolicy)
+        .item("Unmark all", BatchAction::Unmark);
+    let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+    let intro = format!("{}\n", names.join(", "));
+    let title = format!("{} marked projects", projects.len());
+
+    let config = config.clone();
+    menu.set_on_submit(move |s, action: &BatchAction| {
+        s.pop_layer();
+        match action {
+            BatchAction::Operation(operation) => {
+                let operation = *operation;
+                let root = Path::new(config.projects_directory()).to_path_buf();
+                run(s, &config, operation.verb(), projects.clone(), move |p| {
+                    operation.apply(&root, p, &|_| {})
+                });
+            }
+            BatchAction::Tag => ask_tags(s, &config, projects.clone()),
+            BatchAction::LintPolicy => preview_lint_policy(s, &config, projects.clone()),
+            BatchAction::Unmark => project_list::unmark_all(s),
+        }
+    });
+
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(intro).scrollable().max_height(4))
+                .child(menu),
+        )
+        .title(title)
+        .dismiss_button("Cancel"),
+    );
+}
+
+fn ask_tags(s: &mut Cursive, config: &Config, projects: Vec<ProjectInfo>) {
+    let config = config.clone();
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new("Tags to add, separated by commas or spaces:"))
+                .child(EditView::new().with_name(TAGS_INPUT).fixed_width(40)),
+        )
+        .title("Add tags")
+        .button("Add", move |s| {
+            let added = s
+                .call_on_name(TAGS_INPUT, |v: &mut EditView| tags::parse(&v.get_content()))
+                .unwrap_or_default();
+            if added.is_empty() {
+                s.add_layer(Dialog::info("No tag entered."));
+                return;
+            }
+            s.pop_layer();
+            run(s, &config, "Tagging", projects.clone(), move |p| {
+                let mut tags = p.tags.clone();
+                tags.extend(added.iter().filter(|t| !p.tags.contains(t)).cloned());
+                metadata::update(|store| store.entry_mut(&p.path).tags.clone_from(&tags));
+                Ok(tags::label(&tags))
+            });
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+/// Show what the lint policy would change in each of `projects`, then offer to apply it.
+fn preview_lint_policy(s: &mut Cursive, config: &Config, projects: Vec<ProjectInfo>) {
+    let policy = config.lint_policy().clone();
+    if policy.is_empty() {
+        s.add_layer(Dialog::info(
+            "No lint policy configured.\nSet `lint_policy` in the settings, e.g. \
+             {clippy: {pedantic: warn}}.",
+        ));
+        return;
+    }
+    let config = config.clone();
+    progress::run(
+        s,
+        "Lint policy",
+        {
+            let policy = policy.clone();
+            move |_| {
+                projects
+                    .into_iter()
+                    .map(|p| {
+                        let preview = lint_policy::preview(&p.path, &policy);
+                        (p, preview.map_err(|e| e.to_string()))
+                    })
+                    .collect::<Vec<_>>()
+            }
+        },
+        move |s, previews| {
+            let mut text = String::new();
+            let mut changed = Vec::new();
+            for (project, preview) in previews {
+                let note = match preview {
+                    Ok(Preview::Change(diff)) => {
+                        changed.push(project.clone());
+                        diff
+                    }
+                    Ok(Preview::Compliant) => "already follows the policy\n".to_string(),
+                    Ok(Preview::Inherited) => {
+                        "inherits its lints from the workspace: left alone\n".to_string()
+                    }
+                    Err(e) => format!("cannot be read: {e}\n"),
+                };
+                text.push_str(&format!("== {} ==\n{note}\n", project.name));
+            }
+            let mut dialog = Dialog::around(TextView::new(text).scrollable().max_size((100, 24)))
+                .title("Lint policy: changes");
+            if !changed.is_empty() {
+                dialog.add_button(format!("Apply to {}", changed.len()), move |s| {
+                    s.pop_layer();
+                    let policy = policy.clone();
+                    run(s, &config, "Updating", changed.clone(), move |p| {
+                        lint_policy::apply(&p.path, &policy)
+                            .map(|_| "Cargo.toml updated".to_string())
+                            .map_err(|e| e.to_string())
+                    });
+                });
+            }
+            s.add_layer(dialog.dismiss_button("Close"));
+        },
+    );
+}
+
+/// Apply `action` to each of `projects` in the background, then report and rescan.
+fn run(
+    s: &mut Cursive,
+    config: &Config,
+    what: &'static str,
+    projects: Vec<ProjectInfo>,
+    action: impl Fn(&ProjectInfo) -> Result<String, String> + Send + 'static,
+) {
+    let config = config.clone();
+    progress::run(
+        s,
+        &format!("{what} {} projects", projects.len()),
+        move |p| {
+            projects
+                .iter()
+                .map(|project| {
+                    p.log(format!("{what} {}...", project.name));
+                    let result = action(project);
+                    if let Err(e) = &result {
+                        warn!("{what} {} failed: {e}", project.path.display());
+                    }
+                    (project.name.clone(), result)
+                })
+                .collect::<Vec<Outcome>>()
+        },
+        move |s, outcomes| {
+            project_list::refresh(s, &config);
+            show_report(s, what, outcomes);
+        },
+    );
+}
+
+fn show_report(s: &mut Cursive, what: &str, mut outcomes: Vec<Outcome>) {
+    let failed = outcomes.iter().filter(|(_, r)| r.is_err()).count();
+    outcomes.sort_by_key(|(_, r)| r.is_ok());
+    let lines: Vec<String> = outcomes
+        .iter()
+        .map(|(name, result)| match result {
+            Ok(note) => format!("✓ {name}: {note}"),
+            Err(e) => format!("✗ {name}: {e}"),
+        })
+        .collect();
+    let summary = format!(
+        "{} succeeded, {failed} failed.\n\n",
+        outcomes.len() - failed
+    );
+    s.add_layer(
+        Dialog::around(
+            TextView::new(summary + &lines.join("\n"))
+                .scrollable()
+                .max_size((90, 20)),
+        )
+        .title(format!("{what}: results"))
+        .dismiss_button("Close"),
+    );
+}
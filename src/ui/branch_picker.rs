@@ -0,0 +1,116 @@
+//! Branch picker dialog.
+//!
+//! Lists the local branches of a project (current one marked with `*`).
+//! Selecting a branch checks it out; "New branch..." creates a branch at `HEAD`
+//! and switches to it. `on_switched` is called with the new branch name so the
+//! caller can refresh what it displays.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, EditView, SelectView};
+use log::{error, info};
+
+use crate::git_ops::{self, GitError};
+use crate::ui::error_view;
+
+const NEW_BRANCH_INPUT: &str = "new_branch_name";
+
+type OnSwitched = Arc<dyn Fn(&mut Cursive, &str) + Send + Sync>;
+
+/// Show the branch picker for the repository at `path`.
+pub fn show(
+    s: &mut Cursive,
+    path: &Path,
+    on_switched: impl Fn(&mut Cursive, &str) + Send + Sync + 'static,
+) {
+    let branches = match git_ops::local_branches(path) {
+        Ok(branches) => branches,
+        Err(e) => {
+            error_view::show(s, "Cannot list branches", &e);
+            return;
+        }
+    };
+    let current = git_ops::current_branch(path).ok().flatten();
+    let on_switched: OnSwitched = Arc::new(on_switched);
+
+    let mut select = SelectView::<String>::new();
+    for name in branches {
+        let marker = if current.as_deref() == Some(name.as_str()) {
+            "* "
+        } else {
+            "  "
+        };
+        select.add_item(format!("{marker}{name}"), name);
+    }
+    if let Some(i) = current
+        .as_ref()
+        .and_then(|c| select.iter().position(|(_, name)| name == c))
+    {
+        select.set_selection(i);
+    }
+
+    let checkout_path = path.to_path_buf();
+    let checkout_cb = Arc::clone(&on_switched);
+    select.set_on_submit(move |s, name: &String| {
+        let result = git_ops::checkout_branch(&checkout_path, name);
+        finish(s, &checkout_path, name, result, checkout_cb.as_ref());
+    });
+
+    let new_path = path.to_path_buf();
+    s.add_layer(
+        Dialog::around(select.scrollable().fixed_size((40, 12)))
+            .title("Branches")
+            .button("New branch...", move |s| {
+                prompt_new_branch(s, new_path.clone(), Arc::clone(&on_switched));
+            })
+            .button("Cancel", |s| {
+                s.pop_layer();
+            }),
+    );
+}
+
+fn prompt_new_branch(s: &mut Cursive, path: PathBuf, on_switched: OnSwitched) {
+    s.add_layer(
+        Dialog::around(EditView::new().with_name(NEW_BRANCH_INPUT).fixed_width(30))
+            .title("New branch")
+            .button("Create", move |s| {
+                let name = s
+                    .call_on_name(NEW_BRANCH_INPUT, |v: &mut EditView| v.get_content())
+                    .map(|c| c.trim().to_string())
+                    .unwrap_or_default();
+                let result = git_ops::create_branch(&path, &name);
+                if result.is_ok() {
+                    // Close the prompt; `finish` closes the picker.
+                    s.pop_layer();
+                }
+                finish(s, &path, &name, result, on_switched.as_ref());
+            })
+            .button("Cancel", |s| {
+                s.pop_layer();
+            }),
+    );
+}
+
+/// Close the picker on success, otherwise report the error on top of it.
+fn finish(
+    s: &mut Cursive,
+    path: &Path,
+    name: &str,
+    result: Result<(), GitError>,
+    on_switched: &dyn Fn(&mut Cursive, &str),
+) {
+    match result {
+        Ok(()) => {
+            info!("Switched {} to branch {name}", path.display());
+            s.pop_layer();
+            on_switched(s, name);
+        }
+        Err(e) => {
+            error!("Branch switch failed for {}: {e}", path.display());
+            s.add_layer(Dialog::info(e.to_string()));
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! "Cherry-pick onto..." dialog.
+//!
+//! Lists the worktrees and the other local branches of the repository (see
+//! `project::cherry_pick`); choosing one applies the commit there in the
+//! background. Conflicts abort the cherry-pick and list the files involved.
+
+use std::path::{Path, PathBuf};
+
+use cursive::Cursive;
+use cursive::view::{Resizable, Scrollable};
+use cursive::views::{Dialog, SelectView};
+use log::error;
+
+use crate::git_ops::CommitSummary;
+use crate::project::cherry_pick::{self, Target};
+use crate::ui::{error_view, notify, progress};
+
+/// Let the user pick where to apply `commit` of the repository at `path`.
+pub fn show(s: &mut Cursive, path: &Path, commit: &CommitSummary) {
+    let targets = match cherry_pick::targets(path) {
+        Ok(targets) => targets,
+        Err(e) => {
+            error_view::show(s, "Cannot list the branches", &e);
+            return;
+        }
+    };
+    let mut select = SelectView::<Target>::new();
+    for target in targets {
+        select.add_item(target.label(), target);
+    }
+    let title = format!("Cherry-pick {} onto", commit.id);
+    let path = path.to_path_buf();
+    let id = commit.id.clone();
+    select.set_on_submit(move |s, target: &Target| {
+        s.pop_layer();
+        run(s, path.clone(), id.clone(), target.clone());
+    });
+    s.add_layer(
+        Dialog::around(select.scrollable().max_size((80, 16)))
+            .title(title)
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn run(s: &mut Cursive, path: PathBuf, id: String, target: Target) {
+    let label = target.label();
+    progress::run(
+        s,
+        "Cherry-pick",
+        move |p| cherry_pick::apply(&path, &id, &target, &|line| p.log(line)),
+        move |s, result| match result {
+            Ok(()) => notify::toast(s, format!("Applied onto {label}.")),
+            Err(e) => {
+                error!("Cherry-pick onto {label} failed: {e}");
+                error_view::show(s, "Cannot cherry-pick", &e);
+            }
+        },
+    );
+}
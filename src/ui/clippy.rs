@@ -0,0 +1,161 @@
+//! Clippy diagnostics of a project ("Lint" in the project detail).
+//!
+//! Runs clippy in the background (see `project::clippy`) and lists the errors
+//! and warnings grouped by file, next to the compiler's rendering of the
+//! selected one. Enter on a diagnostic opens the file at its line in the
+//! editor. The caller's `OnLinted` gets the counts of the run.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, SelectView, TextView};
+use log::error;
+
+use crate::config::Config;
+use crate::editor;
+use crate::project::clippy::{self, Diagnostic, LintReport, LintSummary};
+use crate::ui::{error_view, notify, progress, suspend};
+
+const RENDERED: &str = "clippy_rendered";
+
+/// Called with the counts of a successful run.
+pub type OnLinted = Arc<dyn Fn(&mut Cursive, LintSummary) + Send + Sync>;
+
+/// Run clippy on the project at `path`, then show the diagnostics.
+pub fn show(s: &mut Cursive, config: &Config, path: &Path, on_linted: OnLinted) {
+    let config = config.clone();
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        "cargo clippy",
+        {
+            let path = path.clone();
+            move |_| clippy::run(&path)
+        },
+        move |s, result| match result {
+            Ok(report) => {
+                on_linted(s, report.summary);
+                if report.files.is_empty() {
+                    notify::toast(s, "Clippy found nothing to report.");
+                } else {
+                    show_report(s, &config, &path, report);
+                }
+            }
+            Err(e) => {
+                error!("Clippy failed on {}: {e}", path.display());
+                error_view::show(s, "Cannot run clippy", &e);
+            }
+        },
+    );
+}
+
+fn show_report(s: &mut Cursive, config: &Config, path: &Path, report: LintReport) {
+    // File headers carry `None` and do nothing on Enter.
+    let mut select = SelectView::<Option<(PathBuf, Diagnostic)>>::new();
+    for file in report.files {
+        select.add_item(
+            format!("{} ({})", file.file.display(), file.diagnostics.len()),
+            None,
+        );
+        for d in file.diagnostics {
+            select.add_item(
+                format!("  {:>5}  {:<7}  {}", d.line, d.level, d.message),
+                Some((file.file.clone(), d)),
+            );
+        }
+    }
+
+    // Start on the first diagnostic (index 0 is its file header).
+    let first = select
+        .get_item(1)
+        .and_then(|(_, target)| target.as_ref())
+        .map(|(_, d)| d.rendered.clone())
+        .unwrap_or_default();
+    let _ = select.set_selection(1);
+
+    select.set_on_select(|s, target: &Option<(PathBuf, Diagnostic)>| {
+        let rendered = target
+            .as_ref()
+            .map(|(_, d)| d.rendered.clone())
+            .unwrap_or_default();
+        s.call_on_name(RENDERED, |v: &mut TextView| v.set_content(rendered));
+    });
+
+    let config = config.clone();
+    let project = path.to_path_buf();
+    select.set_on_submit(move |s, target: &Option<(PathBuf, Diagnostic)>| {
+        let Some((file, d)) = target else {
+            return;
+        };
+        match editor::launch_at(&config, &project, file, d.line) {
+            Ok(cmd) => suspend::run(s, cmd.into_iter().collect()),
+            Err(e) => {
+                error!("Failed to open {}: {e}", file.display());
+                error_view::show(s, "Failed to open editor", &e);
+            }
+        }
+    });
+
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::horizontal()
+                .child(select.scrollable().fixed_size((60, 24)))
+                .child(
+                    TextView::new(first)
+                        .with_name(RENDERED)
+                        .scrollable()
+                        .fixed_size((80, 24)),
+                ),
+        )
+        .title(format!("Clippy: {}", report.summary.counts()))
+        .dismiss_button("Close"),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::clippy::{FileDiagnostics, Level};
+    use crate::ui::testing::Harness;
+
+    fn diagnostic(level: Level, line: u64, message: &str) -> Diagnostic {
+        Diagnostic {
+            level,
+            code: None,
+            message: message.to_string(),
+            line,
+            column: 5,
+            rendered: format!("{level}: {message}\n --> src/main.rs:{line}:5"),
+        }
+    }
+
+    #[test]
+    fn lint_list_groups_diagnostics_by_file() {
+        let config = Config::from_yaml("projects_directory: /projects\neditor_cmd: vim\n");
+        let report = LintReport {
+            files: vec![
+                FileDiagnostics {
+                    file: PathBuf::from("src/main.rs"),
+                    diagnostics: vec![
+                        diagnostic(Level::Error, 3, "mismatched types"),
+                        diagnostic(Level::Warning, 12, "unneeded `return` statement"),
+                    ],
+                },
+                FileDiagnostics {
+                    file: PathBuf::from("src/lib.rs"),
+                    diagnostics: vec![diagnostic(Level::Warning, 7, "unused variable: `x`")],
+                },
+            ],
+            summary: LintSummary {
+                errors: 1,
+                warnings: 2,
+                at: 0,
+            },
+        };
+        let mut h = Harness::new(150, 30);
+        show_report(h.siv(), &config, Path::new("/projects/demo"), report);
+        h.assert_snapshot("lint_list");
+    }
+}
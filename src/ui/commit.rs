@@ -0,0 +1,418 @@
+//! Commit screen.
+//!
+//! Lists the changed files (`git status --short` style). Enter on a file shows
+//! its hunks, staged ones first, with the diff of the selected hunk below;
+//! Enter on a hunk stages or unstages just that hunk (see `project::staging`);
+//! "Lines..." lists its added and removed lines to pick single ones.
+//! "Commit..." asks for a message and commits what is staged, after checking
+//! the staged changes for likely secrets (see `project::secrets`) and for
+//! files above `commit.large_file_kib` (see `project::large_files`, which can
+//! move binary ones to Git LFS). Findings are listed and committing then takes
+//! an explicit "Commit anyway".
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, SelectView, TextArea, TextView};
+use log::{error, info, warn};
+
+use crate::config::Config;
+use crate::git_ops;
+use crate::project::large_files::{self, LargeFile};
+use crate::project::secrets::{self, Finding};
+use crate::project::staging::{self, Hunk};
+use crate::ui::{error_view, notify};
+
+const FILE_LIST: &str = "commit_files";
+const HUNK_LIST: &str = "commit_hunks";
+const HUNK_DIFF: &str = "commit_hunk_diff";
+const LINE_LIST: &str = "commit_lines";
+const MESSAGE_INPUT: &str = "commit_message";
+
+/// Show the changed files of the repository at `path`.
+pub fn show(s: &mut Cursive, config: &Config, path: &Path) {
+    let mut files = SelectView::<String>::new();
+    let root = path.to_path_buf();
+    files.set_on_submit(move |s, file: &String| show_hunks(s, &root, file));
+    let commit_path = path.to_path_buf();
+    let threshold_kib = config.commit().large_file_kib;
+    s.add_layer(
+        Dialog::around(files.with_name(FILE_LIST).scrollable().fixed_size((80, 16)))
+            .title("Commit")
+            .button("Commit...", move |s| {
+                ask_message(s, &commit_path, threshold_kib)
+            })
+            .dismiss_button("Close"),
+    );
+    reload_files(s, path);
+}
+
+/// Refresh the file list from `git status`.
+fn reload_files(s: &mut Cursive, path: &Path) {
+    let lines = match git_ops::status_lines(path) {
+        Ok(lines) => lines,
+        Err(e) => {
+            error_view::show(s, "Cannot read the status", &e);
+            return;
+        }
+    };
+    s.call_on_name(FILE_LIST, |v: &mut SelectView<String>| {
+        let selected = v.selected_id().unwrap_or(0);
+        v.clear();
+        for line in lines {
+            // `XY path`
+            let file = line.get(3..).unwrap_or_default().to_string();
+            v.add_item(line, file);
+        }
+        v.set_selection(selected.min(v.len().saturating_sub(1)));
+    });
+}
+
+fn show_hunks(s: &mut Cursive, path: &Path, file: &str) {
+    let mut hunks = SelectView::<Hunk>::new();
+    hunks.set_on_select(|s, hunk: &Hunk| {
+        let text = hunk.lines.join("\n");
+        s.call_on_name(HUNK_DIFF, |v: &mut TextView| v.set_content(text));
+    });
+    let root = path.to_path_buf();
+    let toggled = file.to_string();
+    hunks.set_on_submit(move |s, hunk: &Hunk| {
+        if let Err(e) = staging::toggle(&root, &toggled, hunk) {
+            error!("Toggling a hunk of {toggled} failed: {e}");
+            error_view::show(s, "Cannot update the index", &e);
+            return;
+        }
+        reload_hunks(s, &root, &toggled);
+        reload_files(s, &root);
+    });
+
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(
+                    "Enter stages or unstages the selected hunk, \"Lines...\" single lines of it.",
+                ))
+                .child(hunks.with_name(HUNK_LIST).scrollable().fixed_size((100, 6)))
+                .child(
+                    TextView::new("")
+                        .with_name(HUNK_DIFF)
+                        .scrollable()
+                        .fixed_size((100, 16)),
+                ),
+        )
+        .title(file.to_string())
+        .button("Lines...", {
+            let root = path.to_path_buf();
+            let file = file.to_string();
+            move |s| {
+                let hunk = s
+                    .call_on_name(HUNK_LIST, |v: &mut SelectView<Hunk>| v.selection())
+                    .flatten();
+                if let Some(hunk) = hunk {
+                    show_lines(s, &root, &file, &hunk);
+                }
+            }
+        })
+        .dismiss_button("Close"),
+    );
+    reload_hunks(s, path, file);
+}
+
+/// Pick the added and removed lines of `hunk` to stage (or unstage, when it is staged).
+fn show_lines(s: &mut Cursive, path: &Path, file: &str, hunk: &Hunk) {
+    let selected = Arc::new(Mutex::new(BTreeSet::new()));
+    let mut lines = SelectView::<usize>::new();
+    for (i, line) in hunk.lines.iter().enumerate() {
+        if line.starts_with(['+', '-']) {
+            lines.add_item(format!("[ ] {line}"), i);
+        }
+    }
+    lines.set_on_submit({
+        let selected = selected.clone();
+        move |s, i: &usize| {
+            let mut selected = selected.lock().unwrap();
+            let mark = if selected.insert(*i) {
+                "[x]"
+            } else {
+                selected.remove(i);
+                "[ ]"
+            };
+            let i = *i;
+            s.call_on_name(LINE_LIST, |v: &mut SelectView<usize>| {
+                // The item is borrowed by this callback: replace it rather than relabel it.
+                if let Some(id) = v.selected_id()
+                    && let Some((label, _)) = v.get_item(id)
+                {
+                    let label = format!("{mark}{}", &label[3..]);
+                    v.remove_item(id);
+                    v.insert_item(id, label, i);
+                    v.set_selection(id);
+                }
+            });
+        }
+    });
+
+    let action = if hunk.staged { "Unstage" } else { "Stage" };
+    let root = path.to_path_buf();
+    let file = file.to_string();
+    let hunk = hunk.clone();
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new("Enter selects or deselects a line."))
+                .child(
+                    lines
+                        .with_name(LINE_LIST)
+                        .scrollable()
+                        .fixed_size((100, 16)),
+                ),
+        )
+        .title(format!("{} {}", hunk.header, file))
+        .button(format!("{action} lines"), move |s| {
+            let selected = selected.lock().unwrap().clone();
+            if selected.is_empty() {
+                return;
+            }
+            if let Err(e) = staging::toggle_lines(&root, &file, &hunk, &selected) {
+                error!("Toggling lines of {file} failed: {e}");
+                error_view::show(s, "Cannot update the index", &e);
+                return;
+            }
+            s.pop_layer();
+            reload_hunks(s, &root, &file);
+            reload_files(s, &root);
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+/// Refresh the hunks of `file`, keeping the cursor where it was.
+fn reload_hunks(s: &mut Cursive, path: &Path, file: &str) {
+    let hunks = match staging::hunks(path, file) {
+        Ok(hunks) => hunks,
+        Err(e) => {
+            error_view::show(s, "Cannot read the changes", &e);
+            return;
+        }
+    };
+    let diff = if hunks.is_empty() {
+        "No text changes (binary file, or nothing left to stage).".to_string()
+    } else {
+        String::new()
+    };
+    let callback = s.call_on_name(HUNK_LIST, |v: &mut SelectView<Hunk>| {
+        let selected = v.selected_id().unwrap_or(0);
+        v.clear();
+        for hunk in hunks {
+            v.add_item(hunk.label(), hunk);
+        }
+        v.set_selection(selected.min(v.len().saturating_sub(1)))
+    });
+    s.call_on_name(HUNK_DIFF, |v: &mut TextView| v.set_content(diff));
+    if let Some(callback) = callback {
+        callback(s);
+    }
+}
+
+fn ask_message(s: &mut Cursive, path: &Path, threshold_kib: u64) {
+    let path: PathBuf = path.to_path_buf();
+    s.add_layer(
+        Dialog::around(
+            TextArea::new()
+                .with_name(MESSAGE_INPUT)
+                .fixed_size((72, 10)),
+        )
+        .title("Commit message")
+        .button("Commit", move |s| {
+            let message = s
+                .call_on_name(MESSAGE_INPUT, |v: &mut TextArea| {
+                    v.get_content().to_string()
+                })
+                .unwrap_or_default();
+            guard_secrets(s, &path, &message, threshold_kib);
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+/// Go on with the large file check, unless the staged changes look like they
+/// leak secrets: then list the matches and only go on with "Commit anyway".
+fn guard_secrets(s: &mut Cursive, path: &Path, message: &str, threshold_kib: u64) {
+    let findings = match secrets::scan_staged(path) {
+        Ok(findings) => findings,
+        Err(e) => {
+            error_view::show(s, "Cannot scan the staged changes", &e);
+            return;
+        }
+    };
+    if findings.is_empty() {
+        guard_large_files(s, path, message, threshold_kib);
+        return;
+    }
+    warn!(
+        "{} possible secret(s) staged in {}",
+        findings.len(),
+        path.display()
+    );
+    let listing = findings
+        .iter()
+        .map(Finding::describe)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let path = path.to_path_buf();
+    let message = message.to_string();
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(
+                    "The staged changes look like they contain secrets:",
+                ))
+                .child(TextView::new(listing).scrollable().max_height(16))
+                .child(TextView::new(
+                    "\nUnstage them (or add the files to .gitignore) unless they are safe to publish.",
+                )),
+        )
+        .title("Possible secrets")
+        .button("Commit anyway", move |s| {
+            s.pop_layer();
+            guard_large_files(s, &path, &message, threshold_kib);
+        })
+        .dismiss_button("Cancel")
+        .max_width(100),
+    );
+}
+
+/// Commit, unless files above `threshold_kib` are staged: then list them and
+/// only commit on "Commit anyway", offering to track binary ones with Git LFS.
+fn guard_large_files(s: &mut Cursive, path: &Path, message: &str, threshold_kib: u64) {
+    let large = match large_files::staged(path, threshold_kib) {
+        Ok(large) => large,
+        Err(e) => {
+            error_view::show(s, "Cannot check the staged file sizes", &e);
+            return;
+        }
+    };
+    if large.is_empty() {
+        commit(s, path, message);
+        return;
+    }
+    warn!("{} large file(s) staged in {}", large.len(), path.display());
+    let listing = large
+        .iter()
+        .map(LargeFile::label)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let binaries: Vec<LargeFile> = large.into_iter().filter(|f| f.binary).collect();
+    let lfs = !binaries.is_empty() && large_files::lfs_available(path);
+    let hint = if lfs {
+        "\nBinary files are better tracked with Git LFS."
+    } else if !binaries.is_empty() {
+        "\nBinary files are better tracked with Git LFS (not installed, see https://git-lfs.com)."
+    } else {
+        ""
+    };
+    let mut dialog = Dialog::around(
+        LinearLayout::vertical()
+            .child(TextView::new(format!(
+                "These staged files are larger than {threshold_kib} KiB:"
+            )))
+            .child(TextView::new(listing).scrollable().max_height(12))
+            .child(TextView::new(hint)),
+    )
+    .title("Large files");
+    if lfs {
+        let lfs_path = path.to_path_buf();
+        let lfs_message = message.to_string();
+        dialog.add_button("Track with LFS", move |s| {
+            s.pop_layer();
+            match large_files::track(&lfs_path, &binaries) {
+                Ok(patterns) => {
+                    info!("Tracking {} with Git LFS", patterns.join(", "));
+                    reload_files(s, &lfs_path);
+                    // Check again: what is left is text, or files LFS did not pick up.
+                    guard_large_files(s, &lfs_path, &lfs_message, threshold_kib);
+                }
+                Err(e) => {
+                    error!("Git LFS tracking in {} failed: {e}", lfs_path.display());
+                    error_view::show(s, "Cannot track with Git LFS", &e);
+                }
+            }
+        });
+    }
+    let path = path.to_path_buf();
+    let message = message.to_string();
+    s.add_layer(
+        dialog
+            .button("Commit anyway", move |s| {
+                s.pop_layer();
+                commit(s, &path, &message);
+            })
+            .dismiss_button("Cancel")
+            .max_width(100),
+    );
+}
+
+fn commit(s: &mut Cursive, path: &Path, message: &str) {
+    match staging::commit(path, message) {
+        Ok(id) => {
+            s.pop_layer();
+            reload_files(s, path);
+            notify::toast(s, format!("Committed {id}."));
+        }
+        Err(e) => {
+            error!("Commit in {} failed: {e}", path.display());
+            error_view::show(s, "Cannot commit", &e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cargo_ops;
+    use crate::temp_dir::TempDir;
+    use crate::ui::testing::Harness;
+    use cursive::event::Key;
+    use std::fs;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        cargo_ops::run_tool("git", dir, args, &[]).unwrap()
+    }
+
+    /// Repository with one change in `a.txt`: "two" replaced by "2" and "2b".
+    fn changed_repo() -> TempDir {
+        let dir = TempDir::new("commit_ui");
+        git(&dir, &["init", "-q"]);
+        git(&dir, &["config", "user.name", "Test"]);
+        git(&dir, &["config", "user.email", "test@example.com"]);
+        fs::write(dir.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        git(&dir, &["add", "a.txt"]);
+        git(&dir, &["commit", "-q", "-m", "base"]);
+        fs::write(dir.join("a.txt"), "one\n2\n2b\nthree\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn commit_screen_lists_the_hunks_of_a_file() {
+        let dir = changed_repo();
+        let config = Config::from_yaml("projects_directory: /projects\neditor_cmd: vim\n");
+        let mut h = Harness::new(110, 34);
+        show(h.siv(), &config, &dir);
+        h.press(Key::Enter);
+        h.assert_snapshot("commit_hunks");
+    }
+
+    #[test]
+    fn line_picker_marks_the_selected_lines() {
+        let dir = changed_repo();
+        let hunk = staging::hunks(&dir, "a.txt").unwrap().remove(0);
+        let mut h = Harness::new(110, 34);
+        show_lines(h.siv(), &dir, "a.txt", &hunk);
+        h.press(Key::Down);
+        h.press(Key::Enter);
+        h.assert_snapshot("commit_lines");
+    }
+}
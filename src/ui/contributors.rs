@@ -0,0 +1,76 @@
+//! Contributors breakdown dialog.
+//!
+//! Shows the commits per author of a project over the window picked in the
+//! popup at the top (last 30 days by default). Counts come from
+//! `project::contributors`, which caches them in the metadata store.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, SelectView, TextView};
+use log::error;
+
+use crate::project::contributors::{self, Contributors};
+use crate::ui::progress;
+
+const BREAKDOWN: &str = "contributors_breakdown";
+
+/// Show the contributors of the project `name` at `path`.
+pub fn show(s: &mut Cursive, name: &str, path: &Path) {
+    let mut window = SelectView::<Option<u32>>::new().popup();
+    for (label, days) in contributors::WINDOWS {
+        window.add_item(label, days);
+    }
+    let select_path = path.to_path_buf();
+    window.set_on_submit(move |s, days: &Option<u32>| load(s, select_path.clone(), *days));
+
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(window.fixed_width(20))
+                .child(TextView::new("").with_name(BREAKDOWN).scrollable())
+                .fixed_size((60, 20)),
+        )
+        .title(format!("Contributors: {name}"))
+        .dismiss_button("Close"),
+    );
+    load(s, path.to_path_buf(), contributors::WINDOWS[0].1);
+}
+
+/// Compute (or fetch from the cache) the counts for `days` and display them.
+fn load(s: &mut Cursive, path: PathBuf, days: Option<u32>) {
+    progress::run(
+        s,
+        "Contributors",
+        move |p| {
+            p.log("Reading git history...");
+            contributors::summary(&path, days)
+        },
+        |s, result| {
+            let text = match result {
+                Ok(summary) => breakdown(&summary),
+                Err(e) => {
+                    error!("Contributors summary failed: {e}");
+                    format!("Cannot read the git history:\n{e}")
+                }
+            };
+            s.call_on_name(BREAKDOWN, |v: &mut TextView| v.set_content(text));
+        },
+    );
+}
+
+/// One `commits  share  author` line per author.
+fn breakdown(summary: &Contributors) -> String {
+    let total = summary.total();
+    if total == 0 {
+        return "No commits in this window.".to_string();
+    }
+    let mut text = format!("{total} commit(s), {} author(s)\n\n", summary.authors.len());
+    for (author, commits) in &summary.authors {
+        let share = commits * 100 / total;
+        writeln!(text, "{commits:>6}  {share:>3}%  {author}").unwrap();
+    }
+    text
+}
@@ -0,0 +1,234 @@
+//! Dependencies panel of the project detail dialog.
+//!
+//! Lists the dependencies declared in `Cargo.toml` and offers "Add..."
+//! (crates.io search, then `cargo add`), "Remove" (`cargo remove`) and "Bump to
+//! latest". Every action runs in the background; the list is reloaded afterwards.
+
+use std::path::{Path, PathBuf};
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, EditView, SelectView};
+use log::error;
+
+use crate::crates_io;
+use crate::project::deps;
+use crate::project::manifest::{DeclaredDependency, DependencyKind};
+use crate::ui::{error_view, notify, progress};
+
+const DEPS_LIST: &str = "dependencies_list";
+const SEARCH_INPUT: &str = "dependencies_search";
+const SEARCH_LIMIT: usize = 20;
+
+/// Show the dependencies panel for the project at `path`.
+pub fn show(s: &mut Cursive, path: &Path) {
+    let deps = match deps::list(path) {
+        Ok(deps) => deps,
+        Err(e) => {
+            error_view::show(s, "Cannot read dependencies", &e);
+            return;
+        }
+    };
+    let mut select = SelectView::<DeclaredDependency>::new();
+    fill(&mut select, deps);
+
+    let add_path = path.to_path_buf();
+    let remove_path = path.to_path_buf();
+    let bump_path = path.to_path_buf();
+    s.add_layer(
+        Dialog::around(
+            select
+                .with_name(DEPS_LIST)
+                .scrollable()
+                .fixed_size((60, 15)),
+        )
+        .title("Dependencies")
+        .button("Add...", move |s| prompt_search(s, add_path.clone()))
+        .button("Remove", move |s| {
+            if let Some(dep) = selected(s) {
+                confirm_remove(s, remove_path.clone(), dep);
+            }
+        })
+        .button("Bump to latest", move |s| {
+            if let Some(dep) = selected(s) {
+                bump(s, bump_path.clone(), dep);
+            }
+        })
+        .dismiss_button("Close"),
+    );
+}
+
+fn fill(select: &mut SelectView<DeclaredDependency>, deps: Vec<DeclaredDependency>) {
+    select.clear();
+    let width = deps.iter().map(|d| d.name.len()).max().unwrap_or(0);
+    for dep in deps {
+        let version = if dep.inherited {
+            "(workspace)".to_string()
+        } else {
+            match (&dep.req, &dep.source) {
+                (Some(req), _) => req.clone(),
+                (None, Some(source)) => source.clone(),
+                (None, None) => "*".to_string(),
+            }
+        };
+        let scope = match &dep.target {
+            Some(target) => format!("{} ({target})", dep.kind.label()),
+            None => dep.kind.label().to_string(),
+        };
+        let label = format!(
+            "{:<width$}  {version:<12}  {}",
+            dep.name,
+            scope.trim_start()
+        );
+        select.add_item(label, dep);
+    }
+}
+
+fn selected(s: &mut Cursive) -> Option<DeclaredDependency> {
+    s.call_on_name(DEPS_LIST, |v: &mut SelectView<DeclaredDependency>| {
+        v.selection().map(|d| (*d).clone())
+    })
+    .flatten()
+}
+
+/// Re-read the manifest into the open panel.
+fn reload(s: &mut Cursive, path: &Path) {
+    match deps::list(path) {
+        Ok(deps) => {
+            s.call_on_name(DEPS_LIST, |v: &mut SelectView<DeclaredDependency>| {
+                fill(v, deps)
+            });
+        }
+        Err(e) => error!("Reloading dependencies failed: {e}"),
+    }
+}
+
+fn prompt_search(s: &mut Cursive, path: PathBuf) {
+    s.add_layer(
+        Dialog::around(EditView::new().with_name(SEARCH_INPUT).fixed_width(30))
+            .title("Search crates.io")
+            .button("Search", move |s| {
+                let query = s
+                    .call_on_name(SEARCH_INPUT, |v: &mut EditView| v.get_content())
+                    .map(|c| c.trim().to_string())
+                    .unwrap_or_default();
+                if query.is_empty() {
+                    return;
+                }
+                s.pop_layer();
+                search(s, path.clone(), query);
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn search(s: &mut Cursive, path: PathBuf, query: String) {
+    progress::run(
+        s,
+        "Searching crates.io",
+        move |_| crates_io::search(&query, SEARCH_LIMIT),
+        move |s, result| match result {
+            Ok(hits) if hits.is_empty() => s.add_layer(Dialog::info("No crate found.")),
+            Ok(hits) => {
+                let mut select = SelectView::<String>::new();
+                for hit in hits {
+                    let version = hit.max_stable_version.unwrap_or(hit.max_version);
+                    let description = hit.description.unwrap_or_default();
+                    let description = description.lines().next().unwrap_or_default();
+                    select.add_item(format!("{} {version}  {description}", hit.name), hit.name);
+                }
+                select.set_on_submit(move |s, name: &String| {
+                    s.pop_layer();
+                    choose_kind(s, path.clone(), name.clone());
+                });
+                s.add_layer(
+                    Dialog::around(select.scrollable().fixed_size((70, 15)))
+                        .title("Select a crate")
+                        .dismiss_button("Cancel"),
+                );
+            }
+            Err(e) => {
+                error!("crates.io search failed: {e}");
+                error_view::show(s, "Search failed", &e);
+            }
+        },
+    );
+}
+
+fn choose_kind(s: &mut Cursive, path: PathBuf, name: String) {
+    let select = SelectView::<DependencyKind>::new()
+        .item("Normal dependency", DependencyKind::Normal)
+        .item("Dev dependency", DependencyKind::Dev)
+        .item("Build dependency", DependencyKind::Build)
+        .on_submit(move |s, kind: &DependencyKind| {
+            s.pop_layer();
+            let work_path = path.clone();
+            let done_path = path.clone();
+            let name = name.clone();
+            let kind = *kind;
+            progress::run(
+                s,
+                "cargo add",
+                move |_| deps::add(&work_path, &name, kind, None),
+                move |s, result| finish(s, &done_path, result.map(|()| None)),
+            );
+        });
+    s.add_layer(
+        Dialog::around(select)
+            .title("Add as")
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn confirm_remove(s: &mut Cursive, path: PathBuf, dep: DeclaredDependency) {
+    s.add_layer(
+        Dialog::text(format!("Remove {} from Cargo.toml?", dep.name))
+            .button("Remove", move |s| {
+                s.pop_layer();
+                let work_path = path.clone();
+                let done_path = path.clone();
+                let dep = dep.clone();
+                progress::run(
+                    s,
+                    "cargo remove",
+                    move |_| deps::remove(&work_path, &dep),
+                    move |s, result| finish(s, &done_path, result.map(|()| None)),
+                );
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn bump(s: &mut Cursive, path: PathBuf, dep: DeclaredDependency) {
+    let work_path = path.clone();
+    let name = dep.name.clone();
+    progress::run(
+        s,
+        "Bump to latest",
+        move |_| deps::bump_to_latest(&work_path, &dep),
+        move |s, result| {
+            let message = match &result {
+                Ok(Some(version)) => Some(format!("{name} now requires {version}.")),
+                Ok(None) => Some(format!("{name} already requires the latest version.")),
+                Err(_) => None,
+            };
+            finish(s, &path, result.map(|_| message));
+        },
+    );
+}
+
+/// Reload the panel and report the outcome of an action.
+fn finish(s: &mut Cursive, path: &Path, result: Result<Option<String>, deps::DepsError>) {
+    match result {
+        Ok(message) => {
+            reload(s, path);
+            if let Some(message) = message {
+                notify::toast(s, message);
+            }
+        }
+        Err(e) => {
+            error!("Dependency action failed: {e}");
+            error_view::show(s, "Dependency action failed", &e);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! Disk usage screen (global mode).
+//!
+//! Lists every project with the size of its directory and of its `target/`
+//! directory, largest first, and the space `cargo clean` would reclaim over
+//! all of them. "Sort by ..." reorders the table. "Clean..." deletes the
+//! `target/` directories of the checked projects (see `project::clean`);
+//! "Select above" checks those whose `target/` exceeds a size, and the space
+//! to be freed is confirmed first.
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Checkbox, Dialog, EditView, LinearLayout, SelectView, TextView};
+use log::warn;
+
+use crate::config::Config;
+use crate::format;
+use crate::project::clean;
+use crate::project::disk_usage::{self, SortBy, Usage};
+use crate::project::list::{cached_projects, list_projects};
+use crate::ui::{error_view, notify, progress};
+
+const ROWS: &str = "disk_usage_rows";
+const THRESHOLD_INPUT: &str = "disk_usage_clean_threshold";
+
+/// Preset of "Select above", in MiB.
+const DEFAULT_THRESHOLD_MIB: u64 = 500;
+
+/// Width of the size columns.
+const SIZE_WIDTH: usize = 10;
+
+/// Size the projects in the background, then show the table.
+pub fn show(s: &mut Cursive, config: &Config) {
+    let table_config = config.clone();
+    let config = config.clone();
+    progress::run(
+        s,
+        "Disk usage",
+        move |p| {
+            let mut projects = cached_projects(&config);
+            if projects.is_empty() {
+                p.log("Scanning projects...");
+                projects = list_projects(&config).map_err(|e| e.to_string())?;
+            }
+            p.log(format!("Sizing {} projects...", projects.len()));
+            Ok(disk_usage::collect(&projects))
+        },
+        move |s, result: Result<Vec<Usage>, String>| match result {
+            Ok(usage) if usage.is_empty() => {
+                s.add_layer(Dialog::info("No projects found."));
+            }
+            Ok(usage) => show_table(s, &table_config, usage),
+            Err(e) => error_view::show_message(s, "Failed to list projects", &e),
+        },
+    );
+}
+
+fn show_table(s: &mut Cursive, config: &Config, usage: Vec<Usage>) {
+    let name_width = usage.iter().map(|u| u.name.len()).max().unwrap_or(0);
+    let total: u64 = usage.iter().map(|u| u.total).sum();
+    let summary = format!(
+        "{} projects, {} in total; {} reclaimable in target/ directories.",
+        usage.len(),
+        format::size(total),
+        format::size(disk_usage::reclaimable(&usage))
+    );
+    let header = format!(
+        "{:<name_width$}  {:>SIZE_WIDTH$}  {:>SIZE_WIDTH$}  Path",
+        "Project", "Total", "target/"
+    );
+
+    let cleanable: Vec<Usage> = usage.iter().filter(|u| u.target > 0).cloned().collect();
+    let mut rows = SelectView::<Usage>::new();
+    fill(&mut rows, usage, name_width);
+
+    let sort_button = move |by: SortBy| {
+        move |s: &mut Cursive| {
+            s.call_on_name(ROWS, |rows: &mut SelectView<Usage>| {
+                let mut usage: Vec<Usage> = rows.iter().map(|(_, u)| u.clone()).collect();
+                disk_usage::sort(&mut usage, by);
+                fill(rows, usage, name_width);
+            });
+        }
+    };
+
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(summary))
+                .child(TextView::new(""))
+                .child(TextView::new(header))
+                .child(rows.with_name(ROWS).scrollable().fixed_size((100, 18))),
+        )
+        .title("Disk usage")
+        .button("Sort by total", sort_button(SortBy::Total))
+        .button("Sort by target/", sort_button(SortBy::Target))
+        .button("Sort by name", sort_button(SortBy::Name))
+        .button("Clean...", {
+            let config = config.clone();
+            move |s| choose_cleanup(s, &config, cleanable.clone())
+        })
+        .dismiss_button("Close"),
+    );
+}
+
+/// Name of the checkbox of the project at an index of the cleanup list.
+fn checkbox_name(index: usize) -> String {
+    format!("disk_usage_clean_{index}")
+}
+
+/// Pick the projects whose `target/` to delete.
+fn choose_cleanup(s: &mut Cursive, config: &Config, usage: Vec<Usage>) {
+    if usage.is_empty() {
+        s.add_layer(Dialog::info("No project has build artifacts."));
+        return;
+    }
+    let name_width = usage.iter().map(|u| u.name.len()).max().unwrap_or(0);
+    let mut list = LinearLayout::vertical();
+    for (i, u) in usage.iter().enumerate() {
+        list.add_child(
+            LinearLayout::horizontal()
+                .child(Checkbox::new().with_name(checkbox_name(i)))
+                .child(TextView::new(format!(
+                    " {:<name_width$}  {:>SIZE_WIDTH$}",
+                    u.name,
+                    format::size(u.target)
+                ))),
+        );
+    }
+    let threshold = LinearLayout::horizontal()
+        .child(TextView::new("target/ above (MiB): "))
+        .child(
+            EditView::new()
+                .content(DEFAULT_THRESHOLD_MIB.to_string())
+                .with_name(THRESHOLD_INPUT)
+                .fixed_width(8),
+        );
+
+    let select_usage = usage.clone();
+    let config = config.clone();
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(threshold)
+                .child(TextView::new(""))
+                .child(list.scrollable().max_size((70, 16))),
+        )
+        .title("Clean build artifacts")
+        .button("Select above", move |s| {
+            let input = s
+                .call_on_name(THRESHOLD_INPUT, |v: &mut EditView| v.get_content())
+                .unwrap_or_default();
+            let Ok(mib) = input.trim().parse::<u64>() else {
+                s.add_layer(Dialog::info("The threshold must be a number of MiB."));
+                return;
+            };
+            for (i, u) in select_usage.iter().enumerate() {
+                s.call_on_name(&checkbox_name(i), |c: &mut Checkbox| {
+                    c.set_checked(u.target > mib * 1024 * 1024);
+                });
+            }
+        })
+        .button("Clean", move |s| {
+            let chosen: Vec<Usage> = usage
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| {
+                    s.call_on_name(&checkbox_name(*i), |c: &mut Checkbox| c.is_checked())
+                        .unwrap_or(false)
+                })
+                .map(|(_, u)| u.clone())
+                .collect();
+            if chosen.is_empty() {
+                s.add_layer(Dialog::info("No project selected."));
+                return;
+            }
+            confirm_cleanup(s, &config, chosen);
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+fn confirm_cleanup(s: &mut Cursive, config: &Config, chosen: Vec<Usage>) {
+    let names: Vec<&str> = chosen.iter().map(|u| u.name.as_str()).collect();
+    let text = format!(
+        "Delete the target/ directories of {} projects, freeing {}?\n\n{}",
+        chosen.len(),
+        format::size(disk_usage::reclaimable(&chosen)),
+        names.join(", ")
+    );
+    let config = config.clone();
+    s.add_layer(
+        Dialog::around(TextView::new(text).scrollable().max_size((70, 12)))
+            .title("Clean build artifacts")
+            .button("Clean", move |s| {
+                // The confirmation and the selection.
+                s.pop_layer();
+                s.pop_layer();
+                run_cleanup(s, &config, chosen.clone());
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+/// Delete the `target/` directories of `chosen`, then size the projects again.
+fn run_cleanup(s: &mut Cursive, config: &Config, chosen: Vec<Usage>) {
+    let config = config.clone();
+    progress::run(
+        s,
+        "Cleaning build artifacts",
+        move |p| {
+            let mut freed = 0;
+            let mut failures = Vec::new();
+            for u in &chosen {
+                p.log(format!("Cleaning {}...", u.name));
+                match clean::clean(&u.path) {
+                    Ok(()) => freed += u.target,
+                    Err(e) => {
+                        warn!("Cannot clean {}: {e}", u.path.display());
+                        failures.push(format!("{}: {e}", u.name));
+                    }
+                }
+            }
+            (chosen.len() - failures.len(), freed, failures)
+        },
+        move |s, (cleaned, freed, failures)| {
+            // The table shows the sizes before cleaning.
+            s.pop_layer();
+            show(s, &config);
+            notify::toast(
+                s,
+                format!("Freed {} in {cleaned} projects.", format::size(freed)),
+            );
+            if !failures.is_empty() {
+                error_view::show_message(s, "Some projects were not cleaned", &failures.join("\n"));
+            }
+        },
+    );
+}
+
+/// Replace the rows of `rows` with `usage`, in order.
+fn fill(rows: &mut SelectView<Usage>, usage: Vec<Usage>, name_width: usize) {
+    rows.clear();
+    for u in usage {
+        let label = format!(
+            "{:<name_width$}  {:>SIZE_WIDTH$}  {:>SIZE_WIDTH$}  {}",
+            u.name,
+            format::size(u.total),
+            format::size(u.target),
+            u.path.display()
+        );
+        rows.add_item(label, u);
+    }
+}
@@ -0,0 +1,113 @@
+//! Guided resolution of a diverged default branch.
+//!
+//! Explains the situation (commits on each side), then offers rebase, merge or
+//! reset (see `project::diverge`). Reset asks for confirmation since it drops
+//! the local commits from the branch.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use cursive::Cursive;
+use cursive::views::{Dialog, LinearLayout, SelectView, TextView};
+use log::error;
+
+use crate::git_ops::Divergence;
+use crate::project::diverge::{self, Reconcile};
+use crate::ui::git_actions::OnBranchChanged;
+use crate::ui::{error_view, notify, progress};
+
+/// Show the options for `divergence` in the repository at `path`.
+pub fn show(s: &mut Cursive, path: &Path, divergence: &Divergence, on_changed: OnBranchChanged) {
+    let text = format!(
+        "{branch} has {ahead} local commit(s) that {upstream} does not have, \
+         and {upstream} has {behind} commit(s) that {branch} does not have.\n",
+        branch = divergence.branch,
+        upstream = divergence.upstream,
+        ahead = divergence.ahead,
+        behind = divergence.behind,
+    );
+    let mut options = SelectView::<Reconcile>::new();
+    for how in [Reconcile::Rebase, Reconcile::Merge, Reconcile::Reset] {
+        options.add_item(how.label(), how);
+    }
+    let title = format!("{} diverged", divergence.branch);
+    let path = path.to_path_buf();
+    let divergence = divergence.clone();
+    options.set_on_submit(move |s, how: &Reconcile| {
+        s.pop_layer();
+        if *how == Reconcile::Reset {
+            confirm_reset(s, path.clone(), divergence.clone(), Arc::clone(&on_changed));
+        } else {
+            run(
+                s,
+                path.clone(),
+                divergence.clone(),
+                *how,
+                Arc::clone(&on_changed),
+            );
+        }
+    });
+
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(text))
+                .child(options),
+        )
+        .title(title)
+        .dismiss_button("Cancel"),
+    );
+}
+
+fn confirm_reset(
+    s: &mut Cursive,
+    path: PathBuf,
+    divergence: Divergence,
+    on_changed: OnBranchChanged,
+) {
+    let text = format!(
+        "Drop the {} local commit(s) of {} and reset it to {}?\n\n\
+         They stay reachable from a {}-backup-<timestamp> branch.",
+        divergence.ahead, divergence.branch, divergence.upstream, divergence.branch
+    );
+    s.add_layer(
+        Dialog::text(text)
+            .title("Reset")
+            .button("Reset", move |s| {
+                s.pop_layer();
+                run(
+                    s,
+                    path.clone(),
+                    divergence.clone(),
+                    Reconcile::Reset,
+                    Arc::clone(&on_changed),
+                );
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn run(
+    s: &mut Cursive,
+    path: PathBuf,
+    divergence: Divergence,
+    how: Reconcile,
+    on_changed: OnBranchChanged,
+) {
+    let work_path = path.clone();
+    progress::run(
+        s,
+        "Resolve divergence",
+        move |p| diverge::reconcile(&work_path, &divergence, how, &|line| p.log(line)),
+        move |s, result| match result {
+            Ok(()) => {
+                on_changed(s);
+                notify::toast(s, "Divergence resolved. Push when ready.");
+            }
+            Err(e) => {
+                error!("Resolving divergence in {} failed: {e}", path.display());
+                error_view::show(s, "Cannot resolve the divergence", &e);
+            }
+        },
+    );
+}
@@ -0,0 +1,86 @@
+//! Error report view.
+//!
+//! Shown when an operation fails: what failed, the error with each of its
+//! causes (the `source()` chain), and the end of `rustm.log`, where the
+//! commands run just before the failure and their output are logged. "Copy to
+//! clipboard" copies the whole report, for an issue or a colleague.
+
+use std::error::Error;
+
+use cursive::Cursive;
+use cursive::theme::PaletteColor;
+use cursive::view::{Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, TextView};
+use log::warn;
+
+use crate::desktop;
+use crate::logging;
+use crate::ui::notify;
+
+/// Number of `rustm.log` lines shown.
+const LOG_LINES: usize = 20;
+
+/// Report that `what` failed ("Cannot commit") with `error`.
+pub fn show(s: &mut Cursive, what: &str, error: &dyn Error) {
+    show_lines(s, what, chain(error));
+}
+
+/// Report that `what` failed with an error only known by its `message`.
+pub fn show_message(s: &mut Cursive, what: &str, message: &str) {
+    show_lines(s, what, vec![message.to_string()]);
+}
+
+fn show_lines(s: &mut Cursive, what: &str, chain: Vec<String>) {
+    let log = logging::relevant_tail(LOG_LINES);
+    let report = format!(
+        "{what}\n\n{}\n\nrustm.log:\n{}",
+        chain.join("\n"),
+        log.join("\n")
+    );
+    let log_text = if log.is_empty() {
+        "(rustm.log is empty or cannot be read)".to_string()
+    } else {
+        log.join("\n")
+    };
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(chain.join("\n")))
+                .child(TextView::new("\nLast lines of rustm.log:").style(PaletteColor::Secondary))
+                .child(TextView::new(log_text).scrollable().max_height(12)),
+        )
+        .title(what)
+        .button("Copy to clipboard", move |s| copy(s, &report))
+        .dismiss_button("Close")
+        .max_width(100),
+    );
+}
+
+/// `error` then its causes, as `caused by: ...` lines.
+///
+/// Many errors repeat their source in their own message: a cause already
+/// contained in the previous line is left out.
+fn chain(error: &dyn Error) -> Vec<String> {
+    let mut lines = vec![error.to_string()];
+    let mut previous = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let message = cause.to_string();
+        if !previous.contains(&message) {
+            lines.push(format!("caused by: {message}"));
+        }
+        previous = message;
+        source = cause.source();
+    }
+    lines
+}
+
+fn copy(s: &mut Cursive, report: &str) {
+    match desktop::copy_to_clipboard(report) {
+        Ok(_) => notify::toast(s, "Error report copied to the clipboard."),
+        Err(e) => {
+            warn!("Clipboard unavailable: {e}");
+            s.add_layer(Dialog::info(format!("Cannot copy the report:\n{e}")));
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Feature matrix results.
+//!
+//! Runs `project::feature_matrix` in the background, the command being run
+//! shown in the progress dialog, then shows the pass / fail grid: one line
+//! per feature combination, one column per command. Enter on a line shows the
+//! errors of its failed commands.
+
+use std::path::Path;
+
+use cursive::Cursive;
+use cursive::view::{Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, SelectView, TextView};
+use log::{error, info};
+
+use crate::config::Config;
+use crate::project::feature_matrix::{self, Matrix, Outcome, Row};
+use crate::ui::{error_view, notify, progress};
+
+/// Width of the combinations column.
+const LABEL_WIDTH: usize = 32;
+
+/// Run the feature matrix of the project at `path`.
+pub fn show(s: &mut Cursive, config: &Config, name: &str, path: &Path) {
+    let settings = config.feature_matrix().clone();
+    let name = name.to_string();
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        &format!("Feature matrix: {name}"),
+        move |p| feature_matrix::run(&path, &settings, &|line| p.log(line)),
+        move |s, result| match result {
+            Ok(matrix) => {
+                info!("Feature matrix of {name}: {} failure(s)", matrix.failures());
+                show_grid(s, &name, matrix);
+            }
+            Err(e) => {
+                error!("Feature matrix of {name} failed: {e}");
+                error_view::show(s, "Cannot run the feature matrix", &e);
+            }
+        },
+    );
+}
+
+fn show_grid(s: &mut Cursive, name: &str, matrix: Matrix) {
+    let widths: Vec<usize> = matrix.commands.iter().map(|c| c.len().max(4)).collect();
+    let mut header = format!("{:<LABEL_WIDTH$}", "features");
+    for (command, width) in matrix.commands.iter().zip(&widths) {
+        header.push_str(&format!("  {command:<width$}"));
+    }
+    let mut select = SelectView::<Row>::new();
+    for row in matrix.rows.iter().cloned() {
+        let mut line = format!("{:<LABEL_WIDTH$}", row.combination.label);
+        for (outcome, width) in row.outcomes.iter().zip(&widths) {
+            let cell = if outcome.passed() { "ok" } else { "FAIL" };
+            line.push_str(&format!("  {cell:<width$}"));
+        }
+        select.add_item(line, row);
+    }
+    let commands = matrix.commands.clone();
+    select.set_on_submit(move |s, row: &Row| show_errors(s, &commands, row));
+    let failures = matrix.failures();
+    let summary = if failures == 0 {
+        "Every combination passed.".to_string()
+    } else {
+        format!("{failures} command(s) failed. Enter shows the errors.")
+    };
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(summary))
+                .child(TextView::new(format!("\n{header}")))
+                .child(select.scrollable().max_height(16)),
+        )
+        .title(format!("Feature matrix: {name}"))
+        .dismiss_button("Close")
+        .max_width(100),
+    );
+}
+
+fn show_errors(s: &mut Cursive, commands: &[String], row: &Row) {
+    let errors: Vec<String> = commands
+        .iter()
+        .zip(&row.outcomes)
+        .filter_map(|(command, outcome)| match outcome {
+            Outcome::Passed => None,
+            Outcome::Failed(output) => Some(format!(
+                "cargo {command} {}\n\n{output}",
+                row.combination.args.join(" ")
+            )),
+        })
+        .collect();
+    if errors.is_empty() {
+        notify::toast(s, "Every command passed.");
+        return;
+    }
+    s.add_layer(
+        Dialog::around(
+            TextView::new(errors.join("\n\n"))
+                .scrollable()
+                .max_size((100, 25)),
+        )
+        .title(row.combination.label.clone())
+        .dismiss_button("Close"),
+    );
+}
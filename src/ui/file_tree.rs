@@ -0,0 +1,95 @@
+//! File tree dialog.
+//!
+//! Read-only, git-ignore aware tree of a project, with a preview of the
+//! selected file. Enter on a directory expands or collapses it; Enter on a file
+//! opens it in the editor.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, SelectView};
+use log::error;
+
+use crate::config::Config;
+use crate::editor;
+use crate::project::tree::{Entry, FileTree};
+use crate::ui::{error_view, preview, suspend};
+
+const TREE_VIEW: &str = "file_tree_view";
+const PREVIEW: &str = "file_tree_preview";
+
+/// Show the file tree of the project at `path`.
+pub fn show(s: &mut Cursive, config: &Config, path: &Path) {
+    let tree = Arc::new(Mutex::new(FileTree::new(path)));
+    let mut select = SelectView::<Entry>::new();
+    fill(&mut select, &tree.lock().unwrap());
+
+    let root = path.to_path_buf();
+    select.set_on_select(move |s, entry: &Entry| {
+        if entry.is_dir {
+            preview::show_message(s, PREVIEW, "Directory (Enter to expand or collapse)");
+        } else {
+            preview::show_file(s, PREVIEW, &root.join(&entry.path), None);
+        }
+    });
+
+    let config = config.clone();
+    select.set_on_submit(move |s, entry: &Entry| {
+        let mut tree = tree.lock().unwrap();
+        if entry.is_dir {
+            tree.toggle(&entry.path);
+            s.call_on_name(TREE_VIEW, |v: &mut SelectView<Entry>| {
+                let selected = v.selected_id().unwrap_or(0);
+                fill(v, &tree);
+                v.set_selection(selected);
+            });
+        } else {
+            match editor::launch_at(&config, tree.root(), &entry.path, 1) {
+                Ok(cmd) => suspend::run(s, cmd.into_iter().collect()),
+                Err(e) => {
+                    error!("Failed to open {}: {e}", entry.path.display());
+                    error_view::show(s, "Failed to open editor", &e);
+                }
+            }
+        }
+    });
+
+    let title = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    );
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::horizontal()
+                .child(
+                    select
+                        .with_name(TREE_VIEW)
+                        .scrollable()
+                        .fixed_size((36, 24)),
+                )
+                .child(preview::pane(PREVIEW, 80, 24)),
+        )
+        .title(format!("Files: {title}"))
+        .dismiss_button("Close"),
+    );
+}
+
+/// Replace the content of `select` with the visible entries of `tree`.
+fn fill(select: &mut SelectView<Entry>, tree: &FileTree) {
+    select.clear();
+    for entry in tree.visible() {
+        let label = if entry.is_dir {
+            let marker = if tree.is_expanded(&entry.path) {
+                "▾"
+            } else {
+                "▸"
+            };
+            format!("{}{marker} {}/", "  ".repeat(entry.depth), entry.name())
+        } else {
+            format!("{}  {}", "  ".repeat(entry.depth), entry.name())
+        };
+        select.add_item(label, entry);
+    }
+}
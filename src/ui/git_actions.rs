@@ -0,0 +1,127 @@
+//! Git actions menu of the project detail dialog.
+//!
+//! "Switch branch" (see `branch_picker`), "Commit" (hunk-level staging, see
+//! `commit`), "Pull" (fetch + fast-forward), "Push", "Log" (with cherry-pick,
+//! see `git_log`), "Rewrite commits" (reorder / squash / reword, see
+//! `rebase`) and "Snapshots" (restore the state before a risky workflow, see
+//! `snapshots`). Network operations run in the background with progress lines;
+//! failures are shown with a hint on how to fix them, and a rejected push
+//! offers to pull first.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use cursive::Cursive;
+use cursive::views::{Dialog, SelectView};
+use log::error;
+
+use crate::config::Config;
+use crate::git_ops::{self, GitError, PullOutcome};
+use crate::ui::{branch_picker, commit, git_log, notify, progress, rebase, snapshots};
+
+/// Called after any action that may have moved `HEAD` (branch switch, pull).
+pub type OnBranchChanged = Arc<dyn Fn(&mut Cursive) + Send + Sync>;
+
+#[derive(Clone, Copy)]
+enum GitAction {
+    SwitchBranch,
+    Commit,
+    Pull,
+    Push,
+    Log,
+    Rewrite,
+    Snapshots,
+}
+
+/// Show the git actions menu for the repository at `path`.
+pub fn show(s: &mut Cursive, config: &Config, path: &Path, on_changed: OnBranchChanged) {
+    let mut select = SelectView::<GitAction>::new()
+        .item("Switch branch", GitAction::SwitchBranch)
+        .item("Commit", GitAction::Commit)
+        .item("Pull", GitAction::Pull)
+        .item("Push", GitAction::Push)
+        .item("Log", GitAction::Log)
+        .item("Rewrite commits", GitAction::Rewrite)
+        .item("Snapshots", GitAction::Snapshots);
+    let config = config.clone();
+    let path = path.to_path_buf();
+    select.set_on_submit(move |s, action: &GitAction| {
+        s.pop_layer();
+        match action {
+            GitAction::SwitchBranch => {
+                let on_changed = Arc::clone(&on_changed);
+                branch_picker::show(s, &path, move |s, _| on_changed(s));
+            }
+            GitAction::Commit => commit::show(s, &config, &path),
+            GitAction::Pull => pull(s, &path, Arc::clone(&on_changed)),
+            GitAction::Push => push(s, &path, Arc::clone(&on_changed)),
+            GitAction::Log => git_log::show(s, &path),
+            GitAction::Rewrite => rebase::show(s, &path, Arc::clone(&on_changed)),
+            GitAction::Snapshots => snapshots::show(s, &path, Arc::clone(&on_changed)),
+        }
+    });
+    s.add_layer(Dialog::around(select).title("Git").dismiss_button("Cancel"));
+}
+
+fn pull(s: &mut Cursive, path: &Path, on_changed: OnBranchChanged) {
+    let work_path = path.to_path_buf();
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        "Pull",
+        move |p| git_ops::pull(&work_path, &|line| p.log(line)),
+        move |s, result| match result {
+            Ok(PullOutcome::UpToDate) => notify::toast(s, "Already up to date."),
+            Ok(PullOutcome::FastForwarded) => {
+                on_changed(s);
+                notify::toast(s, "Pulled (fast-forward).");
+            }
+            Err(e) => show_error(s, "Pull failed", &path, &e, on_changed),
+        },
+    );
+}
+
+fn push(s: &mut Cursive, path: &Path, on_changed: OnBranchChanged) {
+    let work_path = path.to_path_buf();
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        "Push",
+        move |p| git_ops::push(&work_path, &|line| p.log(line)),
+        move |s, result| match result {
+            Ok(()) => notify::toast(s, "Pushed."),
+            Err(e) => show_error(s, "Push failed", &path, &e, on_changed),
+        },
+    );
+}
+
+/// Error dialog with a hint (and a follow-up action when there is an obvious one).
+fn show_error(
+    s: &mut Cursive,
+    title: &str,
+    path: &Path,
+    e: &GitError,
+    on_changed: OnBranchChanged,
+) {
+    error!("{title} for {}: {e}", path.display());
+    let hint = match e {
+        GitError::AuthFailed(_) => {
+            "\n\nSSH remotes: load your key into the agent (`ssh-add`).\nHTTPS remotes: configure a git credential helper (`git config credential.helper`)."
+        }
+        GitError::NotFastForward => {
+            "\n\nOpen the project and merge or rebase onto the remote branch, then push."
+        }
+        GitError::PushRejected(_) => "\n\nThe remote has commits you do not have: pull first.",
+        GitError::CheckoutConflict(_) => "\n\nCommit or stash your local changes, then retry.",
+        _ => "",
+    };
+    let mut dialog = Dialog::text(format!("{e}{hint}")).title(title);
+    if matches!(e, GitError::PushRejected(_)) {
+        let path: PathBuf = path.to_path_buf();
+        dialog.add_button("Pull now", move |s| {
+            s.pop_layer();
+            pull(s, &path, Arc::clone(&on_changed));
+        });
+    }
+    s.add_layer(dialog.dismiss_button("Close"));
+}
@@ -0,0 +1,84 @@
+//! Git log of a project.
+//!
+//! The most recent commits reachable from `HEAD`, newest first, with their age,
+//! author and summary. Enter on a commit shows its full message and offers
+//! "Cherry-pick onto..." (see `cherry_pick`).
+
+use std::path::Path;
+
+use cursive::Cursive;
+use cursive::view::{Resizable, Scrollable};
+use cursive::views::{Dialog, SelectView, TextView};
+
+use crate::format;
+use crate::git_ops::{self, CommitSummary};
+use crate::metadata;
+use crate::ui::{cherry_pick, error_view};
+
+/// Commits shown in the log.
+const LOG_SIZE: usize = 200;
+
+/// Show the log of the repository at `path`.
+pub fn show(s: &mut Cursive, path: &Path) {
+    let commits = match git_ops::recent_commits(path, LOG_SIZE) {
+        Ok(commits) if commits.is_empty() => {
+            s.add_layer(Dialog::info("No commits yet."));
+            return;
+        }
+        Ok(commits) => commits,
+        Err(e) => {
+            error_view::show(s, "Cannot read the log", &e);
+            return;
+        }
+    };
+
+    let now = i64::try_from(metadata::now()).unwrap_or(i64::MAX);
+    let author_width = commits
+        .iter()
+        .map(|c| c.author.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(20);
+    let mut select = SelectView::<CommitSummary>::new();
+    for commit in commits {
+        let label = format!(
+            "{}  {:>4}  {:<author_width$.author_width$}  {}",
+            commit.id,
+            format::timestamp_column(commit.time, now),
+            commit.author,
+            commit.summary,
+        );
+        select.add_item(label, commit);
+    }
+    let path = path.to_path_buf();
+    select.set_on_submit(move |s, commit: &CommitSummary| {
+        show_commit(s, &path, commit, now);
+    });
+
+    s.add_layer(
+        Dialog::around(select.scrollable().fixed_size((100, 22)))
+            .title("Log")
+            .dismiss_button("Close"),
+    );
+}
+
+fn show_commit(s: &mut Cursive, path: &Path, commit: &CommitSummary, now: i64) {
+    let text = format!(
+        "{}\nAuthor: {}\n{}\n\n{}",
+        commit.id,
+        commit.author,
+        format::timestamp(commit.time, now),
+        commit.message
+    );
+    let path = path.to_path_buf();
+    let picked = commit.clone();
+    s.add_layer(
+        Dialog::around(TextView::new(text).scrollable().max_size((80, 20)))
+            .title(commit.summary.clone())
+            .button("Cherry-pick onto...", move |s| {
+                s.pop_layer();
+                cherry_pick::show(s, &path, &picked);
+            })
+            .dismiss_button("Close"),
+    );
+}
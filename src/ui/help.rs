@@ -0,0 +1,100 @@
+//! Help menu.
+//!
+//! "Keybindings" lists the keyboard shortcuts (see `keymap`) and where to
+//! change them. "Report a problem" previews a pre-filled, redacted GitHub issue body for
+//! rustm itself (version, OS, terminal, end of `rustm.log`), then copies it to
+//! the clipboard or opens the new-issue page.
+
+use cursive::Cursive;
+use cursive::view::{Resizable, Scrollable};
+use cursive::views::{Dialog, SelectView, TextView};
+use log::{error, warn};
+
+use crate::bug_report;
+use crate::config::Config;
+use crate::desktop;
+use crate::keymap::{self, Action};
+use crate::ui::{error_view, notify};
+
+/// Show the help menu.
+pub fn show(s: &mut Cursive, config: &Config) {
+    let config = config.clone();
+    let mut menu = SelectView::<&'static str>::new()
+        .item("Keybindings", "keys")
+        .item("Report a problem", "report");
+    menu.set_on_submit(move |s, choice: &&str| match *choice {
+        "keys" => keybindings(s),
+        "report" => {
+            s.pop_layer();
+            report_problem(s, &config);
+        }
+        _ => {}
+    });
+    s.add_layer(Dialog::around(menu).title("Help").dismiss_button("Close"));
+}
+
+fn keybindings(s: &mut Cursive) {
+    let keys = keymap::get();
+    let mut text = String::new();
+    for action in Action::ALL {
+        text.push_str(&format!(
+            "{:<8} {:<12} {}\n",
+            keys.key(action),
+            action.name(),
+            action.description()
+        ));
+    }
+    text.push_str(&format!(
+        "\nTo change them, map action names to keys in\n{}\n(e.g. `quit: ctrl+q`) and restart rustm.",
+        keymap::file_path().display()
+    ));
+    s.add_layer(
+        Dialog::around(TextView::new(text).scrollable().max_size((90, 20)))
+            .title("Keybindings")
+            .dismiss_button("Close"),
+    );
+}
+
+fn report_problem(s: &mut Cursive, config: &Config) {
+    let size = s.screen_size();
+    let body = bug_report::issue_body(config, (size.x, size.y));
+
+    let copy_body = body.clone();
+    let mut dialog = Dialog::around(TextView::new(body.clone()).scrollable().max_size((80, 20)))
+        .title("Report a problem")
+        .button("Copy issue body", move |s| copy_issue_body(s, &copy_body));
+    if let Some(url) = bug_report::new_issue_url(&body) {
+        dialog.add_button("Open new issue", move |s| {
+            if let Err(e) = desktop::open_url(&url) {
+                warn!("Cannot open browser: {e}");
+                s.add_layer(Dialog::info(format!(
+                    "Cannot open a browser ({e}).\nOpen:\n{url}"
+                )));
+            }
+        });
+    }
+    dialog.add_button("Close", |s| {
+        s.pop_layer();
+    });
+    s.add_layer(dialog);
+}
+
+/// Copy `body` to the clipboard, falling back to a file when no clipboard tool exists.
+fn copy_issue_body(s: &mut Cursive, body: &str) {
+    match desktop::copy_to_clipboard(body) {
+        Ok(_) => notify::toast(s, "Issue body copied to the clipboard."),
+        Err(e) => {
+            warn!("Clipboard unavailable: {e}");
+            match bug_report::save(body) {
+                Ok(path) => s.add_layer(Dialog::info(format!(
+                    "No clipboard ({e}).\nIssue body written to {}",
+                    path.display()
+                ))),
+                Err(e) => {
+                    error!("Cannot save bug report: {e}");
+                    error_view::show(s, "Cannot save the issue body", &e);
+                }
+            }
+        }
+    }
+}
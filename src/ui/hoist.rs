@@ -0,0 +1,116 @@
+//! Workspace dependency hoisting dialog.
+//!
+//! Runs `project::hoist::analyze` in the background, then lists the
+//! dependencies that can move to `[workspace.dependencies]`, each with its
+//! requirement and the members declaring it, all checked. "Hoist" rewrites the
+//! manifests for the checked ones. Shared dependencies that cannot be hoisted
+//! are listed below with the reason.
+
+use std::path::Path;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Checkbox, Dialog, LinearLayout, TextView};
+use log::error;
+
+use crate::project::hoist::{self, Analysis, Candidate};
+use crate::ui::{error_view, notify, progress};
+
+/// Name of the checkbox of the candidate at an index.
+fn checkbox_name(index: usize) -> String {
+    format!("hoist_candidate_{index}")
+}
+
+/// Analyse the workspace at `path`.
+pub fn show(s: &mut Cursive, name: &str, path: &Path) {
+    let name = name.to_string();
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        &format!("Shared dependencies: {name}"),
+        {
+            let path = path.clone();
+            move |_| hoist::analyze(&path)
+        },
+        move |s, result| match result {
+            Ok(analysis) if analysis.candidates.is_empty() => {
+                let mut text = "No dependency can be hoisted.".to_string();
+                if !analysis.skipped.is_empty() {
+                    text.push_str(&format!("\n\n{}", analysis.skipped.join("\n")));
+                }
+                s.add_layer(Dialog::info(text).title(format!("Hoist dependencies: {name}")));
+            }
+            Ok(analysis) => show_candidates(s, &name, &path, analysis),
+            Err(e) => {
+                error!("Dependency analysis of {name} failed: {e}");
+                error_view::show(s, "Cannot analyse the workspace dependencies", &e);
+            }
+        },
+    );
+}
+
+fn show_candidates(s: &mut Cursive, name: &str, path: &Path, analysis: Analysis) {
+    let mut list = LinearLayout::vertical();
+    for (i, candidate) in analysis.candidates.iter().enumerate() {
+        let declared: Vec<String> = candidate
+            .uses
+            .iter()
+            .map(|u| format!("{} {}", u.member, u.req))
+            .collect();
+        list.add_child(
+            LinearLayout::horizontal()
+                .child(Checkbox::new().checked().with_name(checkbox_name(i)))
+                .child(TextView::new(format!(
+                    " {} = \"{}\"  ({})",
+                    candidate.name,
+                    candidate.req,
+                    declared.join(", ")
+                ))),
+        );
+    }
+    if !analysis.skipped.is_empty() {
+        list.add_child(TextView::new(format!(
+            "\nLeft alone:\n{}",
+            analysis.skipped.join("\n")
+        )));
+    }
+
+    let path = path.to_path_buf();
+    let candidates = analysis.candidates;
+    s.add_layer(
+        Dialog::around(list.scrollable().max_size((100, 20)))
+            .title(format!("Hoist dependencies: {name}"))
+            .button("Hoist", move |s| {
+                let chosen: Vec<Candidate> = candidates
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| {
+                        s.call_on_name(&checkbox_name(*i), |c: &mut Checkbox| c.is_checked())
+                            .unwrap_or(false)
+                    })
+                    .map(|(_, c)| c.clone())
+                    .collect();
+                if chosen.is_empty() {
+                    s.add_layer(Dialog::info("No dependency selected."));
+                    return;
+                }
+                match hoist::hoist(&path, &chosen) {
+                    Ok(rewritten) => {
+                        s.pop_layer();
+                        notify::toast(
+                            s,
+                            format!(
+                                "Hoisted {} dependencies into [workspace.dependencies] ({rewritten} member entries).",
+                                chosen.len()
+                            ),
+                        );
+                    }
+                    Err(e) => {
+                        error!("Hoisting in {} failed: {e}", path.display());
+                        error_view::show(s, "Cannot hoist the dependencies", &e);
+                    }
+                }
+            })
+            .dismiss_button("Cancel"),
+    );
+}
@@ -0,0 +1,124 @@
+//! Project hygiene checklist dialog.
+//!
+//! Shows the items of `project::hygiene`, satisfied ones marked `[x]`. Enter
+//! on a missing item runs its scaffolding action, asking first for the value
+//! of the manifest keys (the repository URL is prefilled from `origin`);
+//! missing tests open the test generators of `ui::scaffold`.
+
+use std::path::{Path, PathBuf};
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, EditView, LinearLayout, SelectView, TextView};
+use log::{error, info};
+
+use crate::config::Config;
+use crate::project::hygiene::{self, Item, Status};
+use crate::ui::{error_view, scaffold};
+
+const CHECKLIST: &str = "hygiene_checklist";
+const DIALOG: &str = "hygiene_dialog";
+const VALUE_INPUT: &str = "hygiene_value";
+
+/// Show the checklist of the project at `path`.
+pub fn show(s: &mut Cursive, config: &Config, path: &Path) {
+    let mut select = SelectView::<Status>::new();
+    let root = path.to_path_buf();
+    let config = config.clone();
+    select.set_on_submit(move |s, status: &Status| {
+        if status.ok {
+            return;
+        }
+        if status.item == Item::Tests {
+            scaffold::show(s, &config, &root, reload);
+        } else if status.item.needs_input() {
+            ask_value(s, &root, status.item);
+        } else {
+            let holder = match status.item {
+                Item::License => config.author_name().unwrap_or_default(),
+                _ => "",
+            };
+            apply(s, &root, status.item, holder);
+        }
+    });
+    s.add_layer(
+        Dialog::around(select.with_name(CHECKLIST).scrollable().fixed_size((70, 9)))
+            .title("Project hygiene")
+            .dismiss_button("Close")
+            .with_name(DIALOG),
+    );
+    reload(s, path);
+}
+
+/// Refresh the checklist and the count in the title.
+fn reload(s: &mut Cursive, path: &Path) {
+    let statuses = hygiene::check(path);
+    let done = statuses.iter().filter(|st| st.ok).count();
+    let total = statuses.len();
+    s.call_on_name(CHECKLIST, |v: &mut SelectView<Status>| {
+        let selected = v.selected_id().unwrap_or(0);
+        v.clear();
+        for status in statuses {
+            v.add_item(row_label(status), status);
+        }
+        v.set_selection(selected);
+    });
+    s.call_on_name(DIALOG, |d: &mut Dialog| {
+        d.set_title(format!("Project hygiene ({done}/{total})"));
+    });
+}
+
+/// `[x] README` or `[ ] LICENSE  (Enter: add the MIT license)`.
+fn row_label(status: Status) -> String {
+    if status.ok {
+        format!("[x] {}", status.item.label())
+    } else {
+        format!(
+            "[ ] {:<20}  (Enter: {})",
+            status.item.label(),
+            status.item.action()
+        )
+    }
+}
+
+fn ask_value(s: &mut Cursive, path: &Path, item: Item) {
+    let path: PathBuf = path.to_path_buf();
+    let hint = match item {
+        Item::Keywords => "Up to 5 keywords, separated by commas:",
+        Item::Repository => "Repository URL:",
+        _ => "One-line description of the crate:",
+    };
+    s.add_layer(
+        Dialog::new()
+            .title(item.label())
+            .content(
+                LinearLayout::vertical().child(TextView::new(hint)).child(
+                    EditView::new()
+                        .content(hygiene::suggestion(&path, item))
+                        .with_name(VALUE_INPUT)
+                        .fixed_width(60),
+                ),
+            )
+            .button("Set", move |s| {
+                let value = s
+                    .call_on_name(VALUE_INPUT, |v: &mut EditView| v.get_content())
+                    .unwrap_or_default();
+                s.pop_layer();
+                apply(s, &path, item, &value);
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn apply(s: &mut Cursive, path: &Path, item: Item, input: &str) {
+    match hygiene::fix(path, item, input) {
+        Ok(done) => {
+            info!("{done} in {}", path.display());
+            reload(s, path);
+        }
+        Err(e) => {
+            error!("Cannot {} in {}: {e}", item.action(), path.display());
+            error_view::show(s, &format!("Cannot {}", item.action()), &e);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Bulk `cargo clippy --fix` / `cargo fmt` dialog.
+//!
+//! Lists the listed projects with a checkbox each; untrusted ones are left
+//! out (clippy runs their build scripts). "Fix" runs `project::lint_fix` on
+//! the checked projects one after the other, then reports per project the
+//! branch holding the fixes, "no changes", or why it was skipped or failed.
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Checkbox, Dialog, LinearLayout, TextView};
+use log::warn;
+
+use crate::project::lint_fix::{self, Outcome};
+use crate::project::list::ProjectInfo;
+use crate::ui::progress;
+
+/// Name of the checkbox of the project at an index.
+fn checkbox_name(index: usize) -> String {
+    format!("lint_fix_project_{index}")
+}
+
+/// Pick among `projects` the ones to fix.
+pub fn show(s: &mut Cursive, projects: Vec<ProjectInfo>) {
+    let (projects, untrusted): (Vec<ProjectInfo>, Vec<ProjectInfo>) =
+        projects.into_iter().partition(|p| !p.untrusted);
+    if projects.is_empty() {
+        s.add_layer(Dialog::info("No trusted project listed."));
+        return;
+    }
+    let mut list = LinearLayout::vertical();
+    for (i, p) in projects.iter().enumerate() {
+        let mut label = format!(" {}", p.name);
+        if p.has_uncommitted_changes {
+            label.push_str("  (uncommitted changes: skipped)");
+        }
+        list.add_child(
+            LinearLayout::horizontal()
+                .child(
+                    Checkbox::new()
+                        .with_checked(!p.has_uncommitted_changes)
+                        .with_name(checkbox_name(i)),
+                )
+                .child(TextView::new(label)),
+        );
+    }
+    let mut intro = format!(
+        "Fixes are committed on a new {} branch per project; the current branch is left as is.",
+        lint_fix::BRANCH
+    );
+    if !untrusted.is_empty() {
+        intro.push_str(&format!(
+            "\n{} untrusted projects are not offered.",
+            untrusted.len()
+        ));
+    }
+
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(intro))
+                .child(TextView::new(""))
+                .child(list.scrollable().max_size((70, 16))),
+        )
+        .title("Clippy fix & fmt")
+        .button("Fix", move |s| {
+            let chosen: Vec<ProjectInfo> = projects
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| {
+                    s.call_on_name(&checkbox_name(*i), |c: &mut Checkbox| c.is_checked())
+                        .unwrap_or(false)
+                })
+                .map(|(_, p)| p.clone())
+                .collect();
+            if chosen.is_empty() {
+                s.add_layer(Dialog::info("No project selected."));
+                return;
+            }
+            s.pop_layer();
+            run(s, chosen);
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+fn run(s: &mut Cursive, projects: Vec<ProjectInfo>) {
+    progress::run(
+        s,
+        "Clippy fix & fmt",
+        move |p| {
+            projects
+                .iter()
+                .map(|project| {
+                    p.log(format!("Fixing {}...", project.name));
+                    let line = match lint_fix::run(&project.path, &|line| p.log(line)) {
+                        Ok(Outcome::Changed {
+                            branch,
+                            files,
+                            commit,
+                        }) => format!("{files} files changed, committed {commit} on {branch}"),
+                        Ok(Outcome::Unchanged) => "no changes".to_string(),
+                        Ok(Outcome::Skipped(reason)) => format!("skipped: {reason}"),
+                        Err(e) => {
+                            warn!("Lint fix of {} failed: {e}", project.path.display());
+                            format!("failed: {e}")
+                        }
+                    };
+                    format!("{}: {line}", project.name)
+                })
+                .collect::<Vec<_>>()
+        },
+        |s, report| {
+            s.add_layer(
+                Dialog::around(
+                    TextView::new(report.join("\n"))
+                        .scrollable()
+                        .max_size((90, 20)),
+                )
+                .title("Clippy fix & fmt")
+                .dismiss_button("Close"),
+            );
+        },
+    );
+}
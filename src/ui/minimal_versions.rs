@@ -0,0 +1,89 @@
+//! Minimal-versions check results.
+//!
+//! Runs `project::minimal_versions` in the background, then shows whether the
+//! project builds with its dependencies at their lowest allowed versions: the
+//! requirements blamed for a failure first, then the version each direct
+//! dependency resolved to, then the end of the cargo output.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use cursive::Cursive;
+use cursive::view::{Resizable, Scrollable};
+use cursive::views::{Dialog, TextView};
+use log::{error, info};
+
+use crate::project::minimal_versions::{self, Report};
+use crate::ui::{error_view, progress};
+
+/// Check the project at `path`.
+pub fn show(s: &mut Cursive, name: &str, path: &Path) {
+    let name = name.to_string();
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        &format!("Minimal versions: {name}"),
+        move |p| minimal_versions::check(&path, &|line| p.log(line)),
+        move |s, result| match result {
+            Ok(report) => {
+                info!(
+                    "Minimal-versions check of {name}: {}",
+                    if report.passed { "passed" } else { "failed" }
+                );
+                s.add_layer(
+                    Dialog::around(
+                        TextView::new(render(&report))
+                            .scrollable()
+                            .max_size((100, 25)),
+                    )
+                    .title(format!("Minimal versions: {name}"))
+                    .dismiss_button("Close"),
+                );
+            }
+            Err(e) => {
+                error!("Minimal-versions check of {name} failed: {e}");
+                error_view::show(s, "Cannot run the minimal-versions check", &e);
+            }
+        },
+    );
+}
+
+fn render(report: &Report) -> String {
+    let mut text = if report.passed {
+        "The project builds with every dependency at its minimal version.\n".to_string()
+    } else {
+        "The project does not build with its dependencies at their minimal versions.\n".to_string()
+    };
+    if !report.passed {
+        if report.offending.is_empty() {
+            text.push_str(
+                "\nNo dependency failed on its own: a requirement is below the version whose API the project uses (see the errors below).\n",
+            );
+        } else {
+            text.push_str("\nRequirements to raise:\n");
+            for requirement in &report.offending {
+                let _ = writeln!(text, "  {requirement}");
+            }
+        }
+    }
+    if !report.resolved.is_empty() {
+        text.push_str("\nResolved:\n");
+        for dependency in &report.resolved {
+            let _ = writeln!(
+                text,
+                "  {:<24} {:<12} -> {}",
+                dependency.name,
+                dependency.req.as_deref().unwrap_or("-"),
+                if dependency.versions.is_empty() {
+                    "(not from a registry)".to_string()
+                } else {
+                    dependency.versions.join(", ")
+                }
+            );
+        }
+    }
+    if !report.output.is_empty() {
+        let _ = write!(text, "\n{}", report.output);
+    }
+    text
+}
@@ -0,0 +1,98 @@
+//! "New member crate" dialog for workspace roots.
+//!
+//! Asks for the crate name, its type (binary / library, like the create
+//! project dialog) and the directory it goes to (prefilled from a `X/*`
+//! members pattern), then runs `cargo new` inside the workspace.
+
+use std::path::{Path, PathBuf};
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable};
+use cursive::views::{Dialog, EditView, LinearLayout, SelectView, TextView};
+use log::error;
+
+use crate::project::create::{self, ProjectType};
+use crate::project::manifest::Manifest;
+use crate::ui::{error_view, notify, progress};
+
+const NAME_INPUT: &str = "new_member_name";
+const TYPE_SELECT: &str = "new_member_type";
+const DIR_INPUT: &str = "new_member_dir";
+
+/// Show the dialog for the workspace at `root`.
+pub fn show(s: &mut Cursive, root: &Path) {
+    let default_dir = Manifest::read(root)
+        .map(|m| create::default_member_dir(&m))
+        .unwrap_or_default();
+
+    let mut type_select = SelectView::<ProjectType>::new()
+        .popup()
+        .item("Binary (--bin)", ProjectType::Binary)
+        .item("Library (--lib)", ProjectType::Library);
+    type_select.set_selection(1);
+
+    let form = LinearLayout::vertical()
+        .child(TextView::new("Crate name:"))
+        .child(EditView::new().with_name(NAME_INPUT).fixed_width(30))
+        .child(TextView::new("Crate type:"))
+        .child(type_select.with_name(TYPE_SELECT).fixed_width(24))
+        .child(TextView::new("Directory (relative to the workspace root):"))
+        .child(
+            EditView::new()
+                .content(default_dir)
+                .with_name(DIR_INPUT)
+                .fixed_width(30),
+        );
+
+    let root = root.to_path_buf();
+    s.add_layer(
+        Dialog::around(form)
+            .title("New member crate")
+            .button("Create", move |s| {
+                let name = s
+                    .call_on_name(NAME_INPUT, |v: &mut EditView| v.get_content())
+                    .map(|c| c.trim().to_string())
+                    .unwrap_or_default();
+                let dir = s
+                    .call_on_name(DIR_INPUT, |v: &mut EditView| v.get_content())
+                    .map(|c| c.trim().to_string())
+                    .unwrap_or_default();
+                let project_type = s
+                    .call_on_name(TYPE_SELECT, |v: &mut SelectView<ProjectType>| {
+                        v.selection().map(|t| *t)
+                    })
+                    .flatten()
+                    .unwrap_or_default();
+                s.pop_layer();
+                create_member(s, root.clone(), dir, name, project_type);
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn create_member(
+    s: &mut Cursive,
+    root: PathBuf,
+    dir: String,
+    name: String,
+    project_type: ProjectType,
+) {
+    progress::run(
+        s,
+        "New member crate",
+        move |p| {
+            p.log(format!("cargo new {name}"));
+            create::create_workspace_member(&root, &dir, &name, project_type)
+        },
+        |s, result| match result {
+            Ok(path) => notify::toast(
+                s,
+                format!("Created {} and added it to the workspace.", path.display()),
+            ),
+            Err(e) => {
+                error!("Member creation failed: {e}");
+                error_view::show(s, "Cannot create the member crate", &e);
+            }
+        },
+    );
+}
@@ -0,0 +1,84 @@
+//! Notifications.
+//!
+//! Successes ("Editor launched", "Committed 1a2b3c4") are reported with a
+//! toast: a line right above the status bar that goes away after
+//! `TOAST_DURATION`. It does not take the focus, so the user carries on
+//! without dismissing anything. Modal dialogs stay for errors and for
+//! outcomes the user has to act on or read in full.
+//!
+//! The toasts live in the bottom layer with the status bar (`area`), under
+//! every dialog; the newest `MAX_TOASTS` are shown, oldest first.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use cursive::Cursive;
+use cursive::align::HAlign;
+use cursive::theme::PaletteColor;
+use cursive::view::{Nameable, Resizable, View};
+use cursive::views::TextView;
+
+const TOASTS: &str = "notify_toasts";
+
+/// How long a toast stays on screen.
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Toasts shown at once; older ones are dropped.
+const MAX_TOASTS: usize = 3;
+
+static STATE: Mutex<State> = Mutex::new(State {
+    toasts: Vec::new(),
+    next_toast: 0,
+});
+
+#[derive(Debug)]
+struct State {
+    /// Toasts on screen, oldest first.
+    toasts: Vec<(u64, String)>,
+    next_toast: u64,
+}
+
+/// The view showing the toasts, for the status bar layer.
+pub fn area() -> impl View {
+    TextView::new("")
+        .h_align(HAlign::Right)
+        .style(PaletteColor::TitlePrimary)
+        .with_name(TOASTS)
+        .full_width()
+}
+
+/// Show `message` for `TOAST_DURATION`.
+pub fn toast(s: &mut Cursive, message: impl Into<String>) {
+    let id = {
+        let mut state = lock();
+        state.next_toast += 1;
+        let id = state.next_toast;
+        state.toasts.push((id, message.into()));
+        let excess = state.toasts.len().saturating_sub(MAX_TOASTS);
+        state.toasts.drain(..excess);
+        id
+    };
+    refresh(s);
+    let sink = s.cb_sink().clone();
+    thread::spawn(move || {
+        thread::sleep(TOAST_DURATION);
+        let _ = sink.send(Box::new(move |s: &mut Cursive| {
+            lock().toasts.retain(|(toast, _)| *toast != id);
+            refresh(s);
+        }));
+    });
+}
+
+fn refresh(s: &mut Cursive) {
+    let lines: Vec<String> = lock()
+        .toasts
+        .iter()
+        .map(|(_, message)| format!("{message} "))
+        .collect();
+    s.call_on_name(TOASTS, |v: &mut TextView| v.set_content(lines.join("\n")));
+}
+
+fn lock() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
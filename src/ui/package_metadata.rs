@@ -0,0 +1,126 @@
+//! crates.io metadata form.
+//!
+//! Edits the `[package]` keys of `project::package_metadata` in one dialog.
+//! Keywords and categories are comma-separated; "Add category" picks from
+//! the known crates.io categories. Save validates every value and lists all
+//! problems at once; keys inherited from the workspace are shown read-only.
+
+use std::path::Path;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, EditView, LinearLayout, SelectView, TextView};
+use log::{error, info};
+
+use crate::project::package_metadata::{self, CATEGORIES, Field, FieldValue};
+use crate::ui::{error_view, notify};
+
+/// Width of the labels column.
+const LABEL_WIDTH: usize = 13;
+
+/// Show the form for the project at `path`.
+pub fn show(s: &mut Cursive, path: &Path) {
+    let values = match package_metadata::read(path) {
+        Ok(values) => values,
+        Err(e) => {
+            error_view::show(s, "Cannot read the package metadata", &e);
+            return;
+        }
+    };
+    let mut form = LinearLayout::vertical();
+    for value in &values {
+        form.add_child(row(value));
+    }
+    form.add_child(TextView::new(
+        "\nLists are comma-separated: at most 5 keywords (20 characters each) and 5 categories.\nEmpty values are removed from Cargo.toml.",
+    ));
+    let save_path = path.to_path_buf();
+    let mut dialog = Dialog::around(form)
+        .title("crates.io metadata")
+        .button("Save", move |s| save(s, &save_path));
+    if values
+        .iter()
+        .any(|v| v.field == Field::Categories && !v.inherited)
+    {
+        dialog.add_button("Add category", pick_category);
+    }
+    s.add_layer(dialog.dismiss_button("Cancel").max_width(100));
+}
+
+/// `label  [input]`, or `label  (inherited from the workspace)`.
+fn row(value: &FieldValue) -> LinearLayout {
+    let row = LinearLayout::horizontal().child(TextView::new(format!(
+        "{:<LABEL_WIDTH$}",
+        value.field.key()
+    )));
+    if value.inherited {
+        row.child(TextView::new("(inherited from the workspace)"))
+    } else {
+        row.child(
+            EditView::new()
+                .content(value.text.clone())
+                .with_name(input_name(value.field))
+                .fixed_width(72),
+        )
+    }
+}
+
+fn input_name(field: Field) -> String {
+    format!("package_metadata_{}", field.key())
+}
+
+/// Append a known category to the categories input.
+fn pick_category(s: &mut Cursive) {
+    let mut select = SelectView::<&'static str>::new().autojump();
+    select.add_all(CATEGORIES.map(|c| (c, c)));
+    select.set_on_submit(|s, category: &str| {
+        s.pop_layer();
+        s.call_on_name(&input_name(Field::Categories), |v: &mut EditView| {
+            let current = v.get_content();
+            let current = current.trim().trim_end_matches(',');
+            if current.is_empty() {
+                v.set_content(category);
+            } else {
+                v.set_content(format!("{current}, {category}"));
+            }
+        });
+    });
+    s.add_layer(
+        Dialog::around(select.scrollable().fixed_size((50, 16)))
+            .title("Add category")
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn save(s: &mut Cursive, path: &Path) {
+    let inputs: Vec<(Field, String)> = Field::ALL
+        .into_iter()
+        .filter_map(|field| {
+            let text = s.call_on_name(&input_name(field), |v: &mut EditView| {
+                v.get_content().to_string()
+            })?;
+            Some((field, text))
+        })
+        .collect();
+    match package_metadata::write(path, &inputs) {
+        Ok(written) if written.is_empty() => {
+            s.pop_layer();
+        }
+        Ok(written) => {
+            info!(
+                "Updated package {} in {}",
+                written.join(", "),
+                path.display()
+            );
+            s.pop_layer();
+            notify::toast(s, format!("Updated in Cargo.toml: {}", written.join(", ")));
+        }
+        Err(e) => {
+            error!(
+                "Cannot update the package metadata of {}: {e}",
+                path.display()
+            );
+            s.add_layer(Dialog::info(format!("Cannot save:\n{e}")).title("Invalid metadata"));
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Local patches dialog (`[patch.crates-io]` overrides).
+//!
+//! Lists the active path patches of a project and the crates of other listed
+//! projects it could be pointed at. Enter on a candidate adds the patch;
+//! "Remove all" drops every path patch (before committing or publishing).
+
+use std::path::Path;
+
+use cursive::Cursive;
+use cursive::view::{Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, SelectView, TextView};
+use log::error;
+
+use crate::config::Config;
+use crate::project::list;
+use crate::project::patch::{self, Candidate};
+use crate::ui::error_view;
+
+/// Show the local patches dialog for the project at `path`.
+pub fn show(s: &mut Cursive, config: &Config, path: &Path) {
+    let active = patch::active(path);
+    let candidates = patch::candidates(path, &list::cached_projects(config));
+
+    let active_text = if active.is_empty() {
+        "Active patches: none".to_string()
+    } else {
+        let lines: Vec<String> = active
+            .iter()
+            .map(|(name, p)| format!("  {name} -> {}", p.display()))
+            .collect();
+        format!("Active patches:\n{}", lines.join("\n"))
+    };
+
+    let mut select = SelectView::<Candidate>::new();
+    for c in candidates {
+        select.add_item(
+            format!("{} (from {}, {})", c.crate_name, c.project, c.dir.display()),
+            c,
+        );
+    }
+    let candidates_title = if select.is_empty() {
+        "No other project provides a dependency of this one."
+    } else {
+        "Patch a dependency to a local checkout:"
+    };
+
+    let add_path = path.to_path_buf();
+    select.set_on_submit(move |s, c: &Candidate| {
+        s.pop_layer();
+        match patch::add(&add_path, c) {
+            Ok(()) => s.add_layer(Dialog::info(format!(
+                "{} now builds from {}.\nRemove the patch before committing or publishing.",
+                c.crate_name,
+                c.dir.display()
+            ))),
+            Err(e) => {
+                error!("Adding patch failed: {e}");
+                error_view::show(s, "Cannot add patch", &e);
+            }
+        }
+    });
+
+    let mut dialog = Dialog::around(
+        LinearLayout::vertical()
+            .child(TextView::new(active_text))
+            .child(TextView::new(format!("\n{candidates_title}")))
+            .child(select.scrollable().max_height(10)),
+    )
+    .title("Local patches");
+    if !active.is_empty() {
+        let remove_path = path.to_path_buf();
+        dialog.add_button("Remove all", move |s| {
+            s.pop_layer();
+            match patch::remove_all(&remove_path) {
+                Ok(names) => s.add_layer(Dialog::info(format!(
+                    "Removed patches: {}",
+                    names.join(", ")
+                ))),
+                Err(e) => {
+                    error!("Removing patches failed: {e}");
+                    error_view::show(s, "Cannot remove patches", &e);
+                }
+            }
+        });
+    }
+    s.add_layer(dialog.dismiss_button("Close"));
+}
@@ -0,0 +1,104 @@
+//! Playground screen.
+//!
+//! A code editor, an "Online" toggle and an output pane: "Run" compiles and
+//! runs the snippet in the background (locally or on play.rust-lang.org) and
+//! shows compiler output and program output below the code.
+
+use cursive::Cursive;
+use cursive::theme::{BaseColor, Color, PaletteColor};
+use cursive::utils::markup::StyledString;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Checkbox, Dialog, LinearLayout, TextArea, TextView};
+use log::error;
+
+use crate::playground::{self, RunOutput};
+use crate::ui::progress;
+
+const CODE_INPUT: &str = "playground_code";
+const ONLINE_TOGGLE: &str = "playground_online";
+const OUTPUT: &str = "playground_output";
+
+const STARTER: &str = "let greeting = \"Hello from rustm\";\nprintln!(\"{greeting}\");\n";
+
+/// Show the playground.
+pub fn show(s: &mut Cursive) {
+    let layout = LinearLayout::vertical()
+        .child(
+            TextArea::new()
+                .content(STARTER)
+                .with_name(CODE_INPUT)
+                .fixed_size((90, 14)),
+        )
+        .child(
+            LinearLayout::horizontal()
+                .child(Checkbox::new().with_name(ONLINE_TOGGLE))
+                .child(TextView::new(" Online (play.rust-lang.org)")),
+        )
+        .child(TextView::new("Output:"))
+        .child(
+            TextView::new("")
+                .with_name(OUTPUT)
+                .scrollable()
+                .fixed_size((90, 10)),
+        );
+
+    s.add_layer(
+        Dialog::around(layout)
+            .title("Playground")
+            .button("Run", run)
+            .dismiss_button("Close"),
+    );
+}
+
+fn run(s: &mut Cursive) {
+    let code = s
+        .call_on_name(CODE_INPUT, |v: &mut TextArea| v.get_content().to_string())
+        .unwrap_or_default();
+    let online = s
+        .call_on_name(ONLINE_TOGGLE, |v: &mut Checkbox| v.is_checked())
+        .unwrap_or(false);
+    let title = if online {
+        "Running on play.rust-lang.org"
+    } else {
+        "Compiling snippet"
+    };
+    progress::run(
+        s,
+        title,
+        move |_| {
+            if online {
+                playground::run_online(&code)
+            } else {
+                playground::run_local(&code)
+            }
+        },
+        |s, result| {
+            let content = match result {
+                Ok(output) => render(&output),
+                Err(e) => {
+                    error!("Playground run failed: {e}");
+                    StyledString::styled(format!("Run failed: {e}"), Color::Dark(BaseColor::Red))
+                }
+            };
+            s.call_on_name(OUTPUT, |v: &mut TextView| v.set_content(content));
+        },
+    );
+}
+
+fn render(output: &RunOutput) -> StyledString {
+    let mut text = StyledString::new();
+    if !output.compiler.trim().is_empty() {
+        text.append_styled("── compiler ──\n", PaletteColor::Tertiary);
+        text.append_plain(output.compiler.trim_end());
+        text.append_plain("\n");
+    }
+    text.append_styled("── program ──\n", PaletteColor::Tertiary);
+    text.append_plain(output.program.trim_end());
+    let status = if output.success {
+        "\n[ok]"
+    } else {
+        "\n[failed]"
+    };
+    text.append_styled(status, PaletteColor::Secondary);
+    text
+}
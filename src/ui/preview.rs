@@ -0,0 +1,92 @@
+//! File preview pane.
+//!
+//! A read-only, syntax highlighted view of a file, meant to sit next to a list
+//! (file tree, search results) and follow its selection. When a line is given,
+//! the preview starts a few lines above it and marks it. Large and binary
+//! files are not rendered.
+
+use std::fs;
+use std::path::Path;
+
+use cursive::View;
+use cursive::theme::PaletteColor;
+use cursive::utils::markup::StyledString;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{NamedView, ScrollView, TextView};
+
+use crate::highlight;
+
+/// Files larger than this are not previewed.
+const MAX_PREVIEW_BYTES: u64 = 1024 * 1024;
+/// Lines shown above the target line.
+const CONTEXT_BEFORE: u64 = 5;
+/// Lines rendered from the first shown line.
+const PREVIEW_LINES: usize = 400;
+
+/// Build an empty preview pane named `name`, `width` x `height` cells.
+pub fn pane(name: &str, width: usize, height: usize) -> impl View {
+    TextView::new("")
+        .with_name(name)
+        .scrollable()
+        .with_name(scroll_name(name))
+        .fixed_size((width, height))
+}
+
+/// Show `file` in the pane `name`, around `line` (1-based) when given.
+pub fn show_file(s: &mut cursive::Cursive, name: &str, file: &Path, line: Option<u64>) {
+    set_content(s, name, render(file, line));
+}
+
+/// Show a short message instead of a file (directories, headers, ...).
+pub fn show_message(s: &mut cursive::Cursive, name: &str, message: &str) {
+    set_content(
+        s,
+        name,
+        StyledString::styled(message, PaletteColor::Tertiary),
+    );
+}
+
+fn set_content(s: &mut cursive::Cursive, name: &str, content: StyledString) {
+    s.call_on_name(name, |v: &mut TextView| v.set_content(content));
+    s.call_on_name(
+        &scroll_name(name),
+        |v: &mut ScrollView<NamedView<TextView>>| v.scroll_to_top(),
+    );
+}
+
+fn scroll_name(name: &str) -> String {
+    format!("{name}_scroll")
+}
+
+fn render(file: &Path, line: Option<u64>) -> StyledString {
+    let message = |text: String| StyledString::styled(text, PaletteColor::Tertiary);
+    match fs::metadata(file) {
+        Ok(meta) if meta.len() > MAX_PREVIEW_BYTES => {
+            return message(format!("File too large to preview ({} bytes)", meta.len()));
+        }
+        Ok(_) => {}
+        Err(e) => return message(format!("Cannot read {}: {e}", file.display())),
+    }
+    let content = match fs::read(file).map(String::from_utf8) {
+        Ok(Ok(content)) if !content.contains('\0') => content,
+        Ok(_) => return message("Binary file".to_string()),
+        Err(e) => return message(format!("Cannot read {}: {e}", file.display())),
+    };
+
+    let first = line.map_or(1, |l| l.saturating_sub(CONTEXT_BEFORE).max(1));
+    let skip = usize::try_from(first - 1).unwrap_or(usize::MAX);
+    let lines = highlight::highlight_lines(file, &content, skip.saturating_add(PREVIEW_LINES));
+    let width = (first + lines.len() as u64).to_string().len();
+
+    let mut out = StyledString::new();
+    for (number, text) in (first..).zip(lines.into_iter().skip(skip)) {
+        let marker = if Some(number) == line { "▶" } else { " " };
+        out.append_styled(
+            format!("{marker}{number:>width$} │ "),
+            PaletteColor::Tertiary,
+        );
+        out.append(text);
+        out.append_plain("\n");
+    }
+    out
+}
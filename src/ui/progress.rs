@@ -0,0 +1,114 @@
+//! Background work with a progress dialog.
+//!
+//! Long operations (cargo builds, git network calls, ...) must not run on the
+//! cursive event loop. `run` shows a modal dialog, executes the work on a
+//! separate thread and hands the result back to the UI thread through the
+//! callback sink. The worker can append lines to the dialog via `Progress::log`;
+//! a spinner animates at the top of the dialog while the work is in flight.
+//! The work is also listed in the status bar until it finishes, and its records
+//! in `rustm.log` carry `operation=<title>` (plus the context of the caller).
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, TextView};
+use cursive::{CbSink, Cursive};
+
+use crate::logging;
+use crate::ui::status_bar;
+
+const PROGRESS_LOG: &str = "progress_log";
+const PROGRESS_SPINNER: &str = "progress_spinner";
+
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Handle given to the worker to report progress.
+pub struct Progress {
+    sink: CbSink,
+}
+
+impl Progress {
+    /// Append a line to the progress dialog.
+    pub fn log(&self, line: impl Into<String>) {
+        let line = line.into();
+        let _ = self.sink.send(Box::new(move |s: &mut Cursive| {
+            s.call_on_name(PROGRESS_LOG, |v: &mut TextView| {
+                v.append(format!("{line}\n"));
+            });
+        }));
+    }
+}
+
+/// Run `work` in the background while a progress dialog titled `title` is shown.
+///
+/// When the work finishes the dialog is closed and `done` is invoked on the UI
+/// thread with the work's result.
+pub fn run<T, W, D>(siv: &mut Cursive, title: &str, work: W, done: D)
+where
+    T: Send + 'static,
+    W: FnOnce(&Progress) -> T + Send + 'static,
+    D: FnOnce(&mut Cursive, T) + Send + 'static,
+{
+    siv.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(spinner_line(0)).with_name(PROGRESS_SPINNER))
+                .child(
+                    TextView::new("")
+                        .with_name(PROGRESS_LOG)
+                        .scrollable()
+                        .fixed_size((60, 12)),
+                ),
+        )
+        .title(title),
+    );
+
+    let task = status_bar::task_started(siv, title);
+    let sink = siv.cb_sink().clone();
+    let finished = Arc::new(AtomicBool::new(false));
+    spawn_spinner(sink.clone(), Arc::clone(&finished));
+
+    let context = logging::context();
+    let operation = title.to_string();
+    thread::spawn(move || {
+        let _context = logging::restore(context);
+        let _operation = logging::scope("operation", operation);
+        let progress = Progress { sink: sink.clone() };
+        let result = work(&progress);
+        finished.store(true, Ordering::Relaxed);
+        let _ = sink.send(Box::new(move |s: &mut Cursive| {
+            s.pop_layer();
+            status_bar::task_finished(s, task);
+            done(s, result);
+        }));
+    });
+}
+
+/// Animate the spinner line until `finished` is set.
+fn spawn_spinner(sink: CbSink, finished: Arc<AtomicBool>) {
+    thread::spawn(move || {
+        let mut frame = 0;
+        while !finished.load(Ordering::Relaxed) {
+            thread::sleep(SPINNER_INTERVAL);
+            frame += 1;
+            let line = spinner_line(frame);
+            let sent = sink.send(Box::new(move |s: &mut Cursive| {
+                s.call_on_name(PROGRESS_SPINNER, |v: &mut TextView| v.set_content(line));
+            }));
+            if sent.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+fn spinner_line(frame: usize) -> String {
+    format!(
+        "{} Working...",
+        SPINNER_FRAMES[frame % SPINNER_FRAMES.len()]
+    )
+}
@@ -0,0 +1,831 @@
+//! Project detail dialog.
+//!
+//! Entry point for per-project actions:
+//! - "Open" (editor) and "Open full workspace" (prepare rust-analyzer
+//!   prerequisites, then open);
+//! - "Files" (file tree) and "Find in project" (ripgrep, jump to the match in
+//!   the editor);
+//! - "Notes" (quick notes kept in `NOTES.md`, previewed with the README at the
+//!   bottom of the dialog) and "Hygiene" (README / license / CI / tests /
+//!   manifest metadata checklist with scaffolding actions), "crates.io
+//!   metadata" (description / keywords / categories / links form) and
+//!   "Scaffold" (test, benchmark, example, binary and module skeletons);
+//! - "Git..." (switch branch, commit with hunk staging, pull, push, log with
+//!   cherry-pick, rewrite commits) and "Contributors" (commits per author
+//!   over a time window);
+//! - when the default branch diverged from its upstream, "Resolve divergence"
+//!   (rebase, merge or reset);
+//! - "Build release artifacts" (optionally followed by publishing a GitHub
+//!   release) and "Packaging scaffold" (Homebrew / AUR / scoop files);
+//! - "Dependencies" (add / remove / bump) and "Local patches"
+//!   (`[patch.crates-io]` overrides);
+//! - "Lint" (clippy diagnostics by file, counts of the last run shown in the
+//!   dialog);
+//! - "Feature matrix" (cargo commands over feature combinations) and
+//!   "Minimal versions" (build with the lowest allowed dependency versions);
+//!   for libraries, "no_std probe" (build for a target without `std`); with
+//!   `remote_runners` configured, "Check on other platforms" (`cargo check`
+//!   on SSH hosts);
+//! - "Toolchain..." (the active rustup toolchain, also shown in the dialog:
+//!   install it or its missing components, pin a channel, override);
+//! - "Security audit" (`cargo audit`) and "Environment report" (toolchain /
+//!   lockfile snapshot for bug reports);
+//! - for workspaces, "Workspace status" (member status rollup), "New member
+//!   crate" and "Hoist dependencies" (move shared dependencies to
+//!   `[workspace.dependencies]`); for single crates, "Convert to workspace".
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, SelectView, TextArea, TextView};
+use log::{error, warn};
+
+use crate::config::Config;
+use crate::editor;
+use crate::format;
+use crate::git_ops::{self, Divergence};
+use crate::metadata;
+use crate::project::clippy::LintSummary;
+use crate::project::convert;
+use crate::project::env_report;
+use crate::project::graph::DependencyGraph;
+use crate::project::list::{self, ProjectInfo};
+use crate::project::manifest::Manifest;
+use crate::project::no_std::{self, NoStdProbe};
+use crate::project::notes;
+use crate::project::packaging::{self, PackagingFormat};
+use crate::project::patch;
+use crate::project::prepare;
+use crate::project::publish;
+use crate::project::release::{self, ReleaseReport};
+use crate::project::rollup::{self, WorkspaceRollup};
+use crate::project::toolchain as project_toolchain;
+use crate::ui::{
+    audit, clippy, contributors, dependencies, diverge, error_view, feature_matrix, file_tree,
+    git_actions, hoist, hygiene, minimal_versions, new_member, notify, package_metadata, patches,
+    progress, project_list, remote_check, scaffold, search, suspend, toolchain, trust,
+};
+
+const BRANCH_LINE: &str = "project_detail_branch";
+const ROLLUP_LINE: &str = "project_detail_rollup";
+const NO_STD_LINE: &str = "project_detail_no_std";
+const DIVERGENCE_LINE: &str = "project_detail_divergence";
+const NOTES_PREVIEW: &str = "project_detail_notes";
+const NOTES_INPUT: &str = "project_detail_notes_input";
+const TOOLCHAIN_LINE: &str = "project_detail_toolchain";
+const CLIPPY_LINE: &str = "project_detail_clippy";
+
+/// Lines of notes or README shown in the dialog.
+const PREVIEW_LINES: usize = 8;
+
+/// Show the detail dialog for `project`.
+pub fn show(s: &mut Cursive, config: &Config, project: &ProjectInfo) {
+    let mut text = format!("Path: {}\n", project.path.display());
+    let now = i64::try_from(metadata::now()).unwrap_or(i64::MAX);
+    if let Some(at) = project.last_commit {
+        writeln!(text, "Last commit: {}", format::timestamp(at, now)).unwrap();
+    }
+    if let Some(at) = project.last_opened.and_then(|at| i64::try_from(at).ok()) {
+        writeln!(text, "Last opened: {}", format::timestamp(at, now)).unwrap();
+    }
+    if project.has_uncommitted_changes {
+        text.push_str("Uncommitted changes: yes\n");
+    }
+    let dependents = DependencyGraph::build(list::cached_projects(config))
+        .dependents(&project.path)
+        .iter()
+        .map(|p| p.name.clone())
+        .collect::<Vec<_>>();
+    if !dependents.is_empty() {
+        // Editing this project changes the build of these ones.
+        writeln!(
+            text,
+            "Warning: used as a path dependency by {}",
+            dependents.join(", ")
+        )
+        .unwrap();
+    }
+
+    let active_patches: Vec<String> = patch::active(&project.path)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    if !active_patches.is_empty() {
+        writeln!(text, "Local patches active: {}", active_patches.join(", ")).unwrap();
+    }
+
+    let open_config = config.clone();
+    let open_path = project.path.clone();
+    let prep_config = config.clone();
+    let prep_path = project.path.clone();
+    let release_config = config.clone();
+    let release_path = project.path.clone();
+    let git_config = config.clone();
+    let branch_path = project.path.clone();
+    let packaging_path = project.path.clone();
+    let deps_path = project.path.clone();
+    let patches_config = config.clone();
+    let patches_path = project.path.clone();
+    let report_path = project.path.clone();
+    let toolchain_path = project.path.clone();
+    let matrix_config = config.clone();
+    let matrix_name = project.name.clone();
+    let matrix_path = project.path.clone();
+    let minimal_name = project.name.clone();
+    let minimal_path = project.path.clone();
+    let lint_config = config.clone();
+    let lint_path = project.path.clone();
+    let audit_name = project.name.clone();
+    let audit_path = project.path.clone();
+    let files_config = config.clone();
+    let files_path = project.path.clone();
+    let search_config = config.clone();
+    let search_path = project.path.clone();
+    let contributors_name = project.name.clone();
+    let contributors_path = project.path.clone();
+    let notes_path = project.path.clone();
+    let hygiene_config = config.clone();
+    let hygiene_path = project.path.clone();
+    let metadata_path = project.path.clone();
+    let scaffold_config = config.clone();
+    let scaffold_path = project.path.clone();
+    let manifest = Manifest::read(&project.path).ok();
+    let is_workspace = manifest.as_ref().is_some_and(Manifest::is_workspace);
+    let is_library = manifest
+        .as_ref()
+        .is_some_and(|m| no_std::is_library(&project.path, m));
+
+    // Read live rather than from `project`: the branch may change from this dialog.
+    let branch = git_ops::current_branch(&project.path).ok().flatten();
+
+    let mut body = LinearLayout::vertical()
+        .child(TextView::new(branch_line(branch.as_deref())).with_name(BRANCH_LINE))
+        .child(TextView::new(text))
+        .child(TextView::new("Toolchain: detecting...").with_name(TOOLCHAIN_LINE));
+    let lint = metadata::snapshot().get(&project.path).and_then(|m| m.lint);
+    body.add_child(TextView::new(clippy_line(lint.as_ref())).with_name(CLIPPY_LINE));
+    let divergence = git_ops::default_branch_divergence(&project.path)
+        .ok()
+        .flatten();
+    if let Some(divergence) = &divergence {
+        body.add_child(TextView::new(divergence_line(Some(divergence))).with_name(DIVERGENCE_LINE));
+    }
+    if is_workspace {
+        body.add_child(TextView::new(rollup_line(project.rollup.as_ref())).with_name(ROLLUP_LINE));
+    }
+    if is_library {
+        body.add_child(TextView::new(no_std_line(project.no_std.as_ref())).with_name(NO_STD_LINE));
+    }
+    body.add_child(TextView::new(notes_preview(&project.path)).with_name(NOTES_PREVIEW));
+
+    let mut dialog = Dialog::around(body)
+        .title(project.name.clone())
+        .button("Open", move |s| open_project(s, &open_config, &open_path))
+        .button(
+            "Open full workspace",
+            trust::guarded(&project.path, "preparing the workspace", move |s| {
+                open_full_workspace(s, &prep_config, &prep_path);
+            }),
+        )
+        .button("Files", move |s| {
+            file_tree::show(s, &files_config, &files_path)
+        })
+        .button("Find in project", move |s| {
+            search::show(s, &search_config, &search_path);
+        })
+        .button("Notes", move |s| edit_notes(s, &notes_path))
+        .button("Hygiene", move |s| {
+            hygiene::show(s, &hygiene_config, &hygiene_path)
+        })
+        .button("crates.io metadata", move |s| {
+            package_metadata::show(s, &metadata_path)
+        })
+        .button("Scaffold", move |s| {
+            scaffold::show(s, &scaffold_config, &scaffold_path, |_, _| {});
+        })
+        .button("Git...", move |s| {
+            let path = branch_path.clone();
+            git_actions::show(
+                s,
+                &git_config,
+                &branch_path,
+                Arc::new(move |s| {
+                    let branch = git_ops::current_branch(&path).ok().flatten();
+                    s.call_on_name(BRANCH_LINE, |v: &mut TextView| {
+                        v.set_content(branch_line(branch.as_deref()));
+                    });
+                }),
+            );
+        })
+        .button("Contributors", move |s| {
+            contributors::show(s, &contributors_name, &contributors_path);
+        })
+        .button(
+            "Build release artifacts",
+            trust::guarded(&project.path, "building", move |s| {
+                build_release_artifacts(s, &release_config, &release_path);
+            }),
+        )
+        .button("Packaging scaffold", move |s| {
+            choose_packaging_format(s, &packaging_path);
+        })
+        .button("Dependencies", move |s| dependencies::show(s, &deps_path))
+        .button("Local patches", move |s| {
+            patches::show(s, &patches_config, &patches_path);
+        })
+        .button(
+            "Lint",
+            trust::guarded(&project.path, "clippy", move |s| {
+                clippy::show(
+                    s,
+                    &lint_config,
+                    &lint_path,
+                    Arc::new(|s, summary| {
+                        s.call_on_name(CLIPPY_LINE, |v: &mut TextView| {
+                            v.set_content(clippy_line(Some(&summary)));
+                        });
+                    }),
+                );
+            }),
+        )
+        .button(
+            "Feature matrix",
+            trust::guarded(&project.path, "the feature matrix", move |s| {
+                feature_matrix::show(s, &matrix_config, &matrix_name, &matrix_path);
+            }),
+        )
+        .button(
+            "Minimal versions",
+            trust::guarded(&project.path, "checking", move |s| {
+                minimal_versions::show(s, &minimal_name, &minimal_path);
+            }),
+        )
+        .button("Security audit", move |s| {
+            audit::audit_project(s, &audit_name, &audit_path);
+        })
+        .button("Toolchain...", move |s| {
+            let refresh_path = toolchain_path.clone();
+            toolchain::show(
+                s,
+                &toolchain_path,
+                Arc::new(move |s| detect_toolchain(s, &refresh_path)),
+            );
+        })
+        .button("Environment report", move |s| {
+            environment_report(s, &report_path)
+        });
+    if is_workspace {
+        let rollup_path = project.path.clone();
+        dialog.add_button(
+            "Workspace status",
+            trust::guarded(&project.path, "checking the members", move |s| {
+                compute_rollup(s, &rollup_path);
+            }),
+        );
+        let member_root = project.path.clone();
+        dialog.add_button("New member crate", move |s| {
+            new_member::show(s, &member_root)
+        });
+        let hoist_name = project.name.clone();
+        let hoist_path = project.path.clone();
+        dialog.add_button("Hoist dependencies", move |s| {
+            hoist::show(s, &hoist_name, &hoist_path);
+        });
+    } else {
+        let convert_config = config.clone();
+        let convert_project = project.clone();
+        dialog.add_button("Convert to workspace", move |s| {
+            confirm_convert(s, &convert_config, &convert_project);
+        });
+    }
+    if is_library {
+        let no_std_config = config.clone();
+        let no_std_path = project.path.clone();
+        dialog.add_button(
+            "no_std probe",
+            trust::guarded(&project.path, "the no_std probe", move |s| {
+                probe_no_std(s, &no_std_config, &no_std_path);
+            }),
+        );
+    }
+    if !config.remote_runners().is_empty() {
+        let remote_config = config.clone();
+        let remote_name = project.name.clone();
+        let remote_path = project.path.clone();
+        dialog.add_button(
+            "Check on other platforms",
+            trust::guarded(&project.path, "checking on the runners", move |s| {
+                remote_check::show(s, &remote_config, &remote_name, &remote_path);
+            }),
+        );
+    }
+    if let Some(divergence) = divergence {
+        let path = project.path.clone();
+        dialog.add_button("Resolve divergence", move |s| {
+            let refresh_path = path.clone();
+            diverge::show(
+                s,
+                &path,
+                &divergence,
+                Arc::new(move |s| {
+                    let branch = git_ops::current_branch(&refresh_path).ok().flatten();
+                    let divergence = git_ops::default_branch_divergence(&refresh_path)
+                        .ok()
+                        .flatten();
+                    s.call_on_name(BRANCH_LINE, |v: &mut TextView| {
+                        v.set_content(branch_line(branch.as_deref()));
+                    });
+                    s.call_on_name(DIVERGENCE_LINE, |v: &mut TextView| {
+                        v.set_content(divergence_line(divergence.as_ref()));
+                    });
+                }),
+            );
+        });
+    }
+    dialog.add_button("Close", |s| {
+        s.pop_layer();
+    });
+    s.add_layer(dialog);
+    detect_toolchain(s, &project.path);
+}
+
+/// Detect the toolchain of `path` without blocking the UI and show it in the dialog.
+fn detect_toolchain(s: &mut Cursive, path: &Path) {
+    let sink = s.cb_sink().clone();
+    let path = path.to_path_buf();
+    thread::spawn(move || {
+        let line = match project_toolchain::detect(&path) {
+            Ok(detected) => format!("Toolchain: {}", detected.summary()),
+            Err(e) => {
+                warn!("Cannot detect the toolchain of {}: {e}", path.display());
+                format!("Toolchain: unknown ({e})")
+            }
+        };
+        let _ = sink.send(Box::new(move |s: &mut Cursive| {
+            s.call_on_name(TOOLCHAIN_LINE, |v: &mut TextView| v.set_content(line));
+        }));
+    });
+}
+
+fn branch_line(branch: Option<&str>) -> String {
+    format!("Branch: {}", branch.unwrap_or("-"))
+}
+
+fn divergence_line(divergence: Option<&Divergence>) -> String {
+    match divergence {
+        Some(d) => format!(
+            "Warning: {} diverged from {} (↑{} ↓{})",
+            d.branch, d.upstream, d.ahead, d.behind
+        ),
+        None => "Default branch: in sync with its upstream".to_string(),
+    }
+}
+
+/// `--- NOTES.md ---` header and the first lines of the notes or README; empty without either.
+fn notes_preview(path: &Path) -> String {
+    match notes::preview(path, PREVIEW_LINES) {
+        Some((file, text)) => format!("\n--- {file} ---\n{text}"),
+        None => String::new(),
+    }
+}
+
+fn edit_notes(s: &mut Cursive, path: &Path) {
+    let current = match notes::read(path) {
+        Ok(text) => text,
+        Err(e) => {
+            error!("Cannot read the notes of {}: {e}", path.display());
+            error_view::show(s, "Cannot read the notes", &e);
+            return;
+        }
+    };
+    let path = path.to_path_buf();
+    s.add_layer(
+        Dialog::around(
+            TextArea::new()
+                .content(current)
+                .with_name(NOTES_INPUT)
+                .fixed_size((72, 16)),
+        )
+        .title(notes::NOTES_FILE)
+        .button("Save", move |s| {
+            let text = s
+                .call_on_name(NOTES_INPUT, |v: &mut TextArea| v.get_content().to_string())
+                .unwrap_or_default();
+            match notes::save(&path, &text) {
+                Ok(()) => {
+                    s.pop_layer();
+                    let preview = notes_preview(&path);
+                    s.call_on_name(NOTES_PREVIEW, |v: &mut TextView| v.set_content(preview));
+                }
+                Err(e) => {
+                    error!("Cannot save the notes of {}: {e}", path.display());
+                    error_view::show(s, "Cannot save the notes", &e);
+                }
+            }
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+fn rollup_line(rollup: Option<&WorkspaceRollup>) -> String {
+    match rollup {
+        Some(r) => format!("Workspace: {}", r.summary()),
+        None => "Workspace: status not computed yet".to_string(),
+    }
+}
+
+/// Compute the workspace rollup in the background, remember it and show the details.
+fn compute_rollup(s: &mut Cursive, path: &Path) {
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        "Workspace status",
+        {
+            let path = path.clone();
+            move |p| rollup::compute(&path, &|line| p.log(line))
+        },
+        move |s, result| match result {
+            Ok(rollup) => {
+                metadata::update(|store| store.entry_mut(&path).rollup = Some(rollup.clone()));
+                s.call_on_name(ROLLUP_LINE, |v: &mut TextView| {
+                    v.set_content(rollup_line(Some(&rollup)));
+                });
+                let mut text = rollup.summary();
+                if !rollup.failing_check.is_empty() {
+                    write!(
+                        text,
+                        "\n\nFailing check:\n{}",
+                        rollup.failing_check.join("\n")
+                    )
+                    .unwrap();
+                }
+                if !rollup.missing_tests.is_empty() {
+                    write!(
+                        text,
+                        "\n\nWithout tests:\n{}",
+                        rollup.missing_tests.join("\n")
+                    )
+                    .unwrap();
+                }
+                s.add_layer(Dialog::info(text).title("Workspace status"));
+            }
+            Err(e) => {
+                error!("Workspace rollup failed: {e}");
+                error_view::show(s, "Cannot compute workspace status", &e);
+            }
+        },
+    );
+}
+
+/// `Clippy: 1 error, 2 warnings (3 days ago)`.
+fn clippy_line(summary: Option<&LintSummary>) -> String {
+    match summary {
+        Some(summary) => {
+            let now = i64::try_from(metadata::now()).unwrap_or(i64::MAX);
+            let at = i64::try_from(summary.at).unwrap_or(now);
+            format!(
+                "Clippy: {} ({})",
+                summary.counts(),
+                format::timestamp(at, now)
+            )
+        }
+        None => "Clippy: not run yet".to_string(),
+    }
+}
+
+fn no_std_line(probe: Option<&NoStdProbe>) -> String {
+    match probe {
+        Some(p) => format!("no_std: {}", p.summary()),
+        None => "no_std: not probed yet".to_string(),
+    }
+}
+
+/// Build the library without `std` in the background, remember the verdict and report it.
+fn probe_no_std(s: &mut Cursive, config: &Config, path: &Path) {
+    let target = config.no_std_target().to_string();
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        "no_std probe",
+        {
+            let path = path.clone();
+            move |p| no_std::probe(&path, &target, &|line| p.log(line))
+        },
+        move |s, result| match result {
+            Ok(outcome) => {
+                let probe = outcome.probe;
+                metadata::update(|store| store.entry_mut(&path).no_std = Some(probe.clone()));
+                s.call_on_name(NO_STD_LINE, |v: &mut TextView| {
+                    v.set_content(no_std_line(Some(&probe)));
+                });
+                if probe.compatible {
+                    notify::toast(s, format!("Builds without std for {}.", probe.target));
+                    return;
+                }
+                let mut text = format!("The library does not build for {}.", probe.target);
+                if !outcome.blocking.is_empty() {
+                    write!(text, "\nFailed to build: {}", outcome.blocking.join(", ")).unwrap();
+                }
+                write!(text, "\n\n{}", outcome.output).unwrap();
+                s.add_layer(
+                    Dialog::around(TextView::new(text).scrollable().max_size((100, 25)))
+                        .title("no_std probe")
+                        .dismiss_button("Close"),
+                );
+            }
+            Err(e) => {
+                error!("no_std probe of {} failed: {e}", path.display());
+                error_view::show(s, "Cannot run the no_std probe", &e);
+            }
+        },
+    );
+}
+
+/// Open `path` in an editor, first asking which one when `editors` are configured.
+///
+/// The choice is remembered for the project and preselected the next time.
+pub fn open_project(s: &mut Cursive, config: &Config, path: &Path) {
+    if config.editors().is_empty() {
+        launch_editor(s, config, path);
+        return;
+    }
+    let chosen = editor::chosen_editor(config, path).map(|e| e.name.clone());
+    let default = editor::default_commands(config, path).join(" + ");
+    let mut select = SelectView::<Option<String>>::new().item(format!("Default ({default})"), None);
+    for (i, named) in config.editors().iter().enumerate() {
+        select.add_item(
+            format!("{} ({})", named.name, named.command),
+            Some(named.name.clone()),
+        );
+        if chosen.as_ref() == Some(&named.name) {
+            select.set_selection(i + 1);
+        }
+    }
+    let config = config.clone();
+    let path = path.to_path_buf();
+    select.set_on_submit(move |s, choice: &Option<String>| {
+        s.pop_layer();
+        metadata::update(|store| store.entry_mut(&path).editor.clone_from(choice));
+        launch_editor(s, &config, &path);
+    });
+    s.add_layer(
+        Dialog::around(select)
+            .title("Open with")
+            .dismiss_button("Cancel"),
+    );
+}
+
+/// Launch the editor for `path`: a toast on success, a dialog on failure.
+fn launch_editor(s: &mut Cursive, config: &Config, path: &Path) {
+    match editor::launch(config, path) {
+        Ok(foreground) => {
+            project_list::record_opened(s, path);
+            if foreground.is_empty() {
+                notify::toast(s, "Editor launched.");
+            }
+            suspend::run(s, foreground);
+        }
+        Err(e) => {
+            error!("Failed to launch editor for {}: {e}", path.display());
+            error_view::show(s, "Failed to launch editor", &e);
+        }
+    }
+}
+
+/// Run the configured workspace preparation steps in the background, then open the editor.
+///
+/// If a step fails the user may still open the project as-is.
+pub fn open_full_workspace(s: &mut Cursive, config: &Config, path: &Path) {
+    let steps = prepare::plan(config.workspace_prep());
+    let work_path = path.to_path_buf();
+    let path = path.to_path_buf();
+    let config = config.clone();
+
+    progress::run(
+        s,
+        "Preparing workspace",
+        move |p| {
+            for step in &steps {
+                p.log(format!("{step}..."));
+                match prepare::run_step(step, &work_path) {
+                    Ok(outcome) => p.log(format!("  {outcome}")),
+                    Err(e) => return Err(format!("{step}: {e}")),
+                }
+            }
+            Ok(())
+        },
+        move |s, result: Result<(), String>| match result {
+            Ok(()) => open_project(s, &config, &path),
+            Err(msg) => {
+                warn!("Workspace preparation failed: {msg}");
+                s.add_layer(
+                    Dialog::around(TextView::new(format!(
+                        "Workspace preparation failed:\n{msg}\n\nOpen the project anyway?"
+                    )))
+                    .title("Preparation failed")
+                    .button("Open anyway", move |s| {
+                        s.pop_layer();
+                        open_project(s, &config, &path);
+                    })
+                    .button("Cancel", |s| {
+                        s.pop_layer();
+                    }),
+                );
+            }
+        },
+    );
+}
+
+/// Build release artifacts for every configured target in the background and show a report.
+fn build_release_artifacts(s: &mut Cursive, config: &Config, path: &Path) {
+    let settings = config.release().clone();
+    let work_path = path.to_path_buf();
+    let path = path.to_path_buf();
+    let config = config.clone();
+
+    progress::run(
+        s,
+        "Building release artifacts",
+        move |p| release::build_all(&work_path, &settings, &|line| p.log(line)),
+        move |s, result| match result {
+            Ok(report) => show_release_report(s, &config, &path, &report),
+            Err(e) => {
+                error!("Release build failed: {e}");
+                error_view::show(s, "Release build failed", &e);
+            }
+        },
+    );
+}
+
+fn show_release_report(s: &mut Cursive, config: &Config, path: &Path, report: &ReleaseReport) {
+    let mut text = String::new();
+    for t in &report.targets {
+        match &t.result {
+            Ok(files) => {
+                writeln!(
+                    text,
+                    "[ok]     {} ({}, {} file(s))",
+                    t.target,
+                    t.tool,
+                    files.len()
+                )
+                .unwrap();
+            }
+            Err(e) => writeln!(text, "[failed] {} ({}): {e}", t.target, t.tool).unwrap(),
+        }
+    }
+    if let Some(sums) = &report.checksums {
+        write!(text, "\nChecksums: {}", sums.display()).unwrap();
+    }
+
+    let mut artifacts: Vec<PathBuf> = report
+        .targets
+        .iter()
+        .filter_map(|t| t.result.as_ref().ok())
+        .flatten()
+        .cloned()
+        .collect();
+    artifacts.extend(report.checksums.clone());
+
+    let mut dialog = Dialog::info(text).title("Release artifacts");
+    if !artifacts.is_empty() {
+        let config = config.clone();
+        let path = path.to_path_buf();
+        dialog.add_button("Publish GitHub release", move |s| {
+            publish_github_release(s, &config, &path, artifacts.clone());
+        });
+    }
+    s.add_layer(dialog);
+}
+
+/// Explain the conversion, then move the crate into `crates/<name>` and reopen the dialog.
+fn confirm_convert(s: &mut Cursive, config: &Config, project: &ProjectInfo) {
+    let config = config.clone();
+    let project = project.clone();
+    let text = format!(
+        "Move {} into crates/{} and create a workspace Cargo.toml at the root?\n\n\
+         Tracked files are moved with git mv and both manifests are staged; nothing is committed.",
+        project.path.display(),
+        project.name
+    );
+    s.add_layer(
+        Dialog::text(text)
+            .title("Convert to workspace")
+            .button("Convert", move |s| {
+                s.pop_layer();
+                let path = project.path.clone();
+                let config = config.clone();
+                let project = project.clone();
+                progress::run(
+                    s,
+                    "Convert to workspace",
+                    move |p| convert::to_workspace(&path, &|line| p.log(line)),
+                    move |s, result| match result {
+                        Ok(member) => {
+                            // The open detail dialog still offers single-crate actions.
+                            s.pop_layer();
+                            show(s, &config, &project);
+                            notify::toast(
+                                s,
+                                format!("The crate now lives in {}.", member.display()),
+                            );
+                        }
+                        Err(e) => {
+                            error!("Conversion to workspace failed: {e}");
+                            error_view::show(s, "Cannot convert", &e);
+                        }
+                    },
+                );
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+/// Collect the environment report in the background, write it next to the build output and show it.
+fn environment_report(s: &mut Cursive, path: &Path) {
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        "Environment report",
+        move |p| {
+            p.log("Querying rustc, cargo and rustup...");
+            let report = env_report::collect(&path);
+            let written = env_report::write(&report);
+            (report, written)
+        },
+        |s, (report, written)| {
+            let mut text = report.to_text();
+            match written {
+                Ok((txt, json)) => {
+                    write!(text, "\nWritten {}\nand {}", txt.display(), json.display()).unwrap();
+                }
+                Err(e) => {
+                    error!("Cannot write environment report: {e}");
+                    write!(text, "\nCannot write the report files: {e}").unwrap();
+                }
+            }
+            s.add_layer(Dialog::info(text).title("Environment report"));
+        },
+    );
+}
+
+/// Let the user pick a package manager, then write its scaffold into `packaging/`.
+fn choose_packaging_format(s: &mut Cursive, path: &Path) {
+    let mut select = SelectView::<PackagingFormat>::new();
+    for format in PackagingFormat::ALL {
+        select.add_item(format.to_string(), format);
+    }
+    let path = path.to_path_buf();
+    select.set_on_submit(move |s, format: &PackagingFormat| {
+        s.pop_layer();
+        match packaging::generate(&path, *format) {
+            Ok(scaffold) => {
+                let mut text = format!("Written {}", scaffold.path.display());
+                if !scaffold.missing_checksums.is_empty() {
+                    write!(
+                        text,
+                        "\n\nChecksums left as FILL_ME (build the release artifacts first):\n{}",
+                        scaffold.missing_checksums.join("\n")
+                    )
+                    .unwrap();
+                }
+                s.add_layer(Dialog::info(text).title(format.to_string()));
+            }
+            Err(e) => {
+                error!("Packaging scaffold failed: {e}");
+                s.add_layer(Dialog::info(format!("Cannot generate {format}:\n{e}")));
+            }
+        }
+    });
+
+    s.add_layer(
+        Dialog::around(select)
+            .title("Packaging scaffold")
+            .dismiss_button("Cancel"),
+    );
+}
+
+/// Upload `artifacts` to a GitHub release for the tag at `HEAD`.
+fn publish_github_release(s: &mut Cursive, config: &Config, path: &Path, artifacts: Vec<PathBuf>) {
+    let token = config.github_token();
+    let path = path.to_path_buf();
+
+    progress::run(
+        s,
+        "Publishing GitHub release",
+        move |p| publish::publish_github(&path, &artifacts, token.as_deref(), &|line| p.log(line)),
+        |s, result| match result {
+            Ok(url) => {
+                s.add_layer(Dialog::info(format!("Release published:\n{url}")));
+            }
+            Err(e) => {
+                error!("GitHub release failed: {e}");
+                error_view::show(s, "Publishing failed", &e);
+            }
+        },
+    );
+}
@@ -0,0 +1,640 @@
+//! Project list screen (global mode).
+//!
+//! One row per discovered project, rendered by `row_label`. Enter opens its
+//! action menu (`project_menu`), "Marked..." the batch actions (`batch`);
+//! untrusted projects are guarded by `trust`. Cached projects are shown at
+//! once and refreshed in place after a background scan, after which vanished
+//! projects are asked about (`vanished`).
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use cursive::Cursive;
+use cursive::theme::Palette;
+use cursive::utils::markup::StyledString;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, OnEventView, SelectView, TextView};
+use log::warn;
+
+use crate::config::{AgeColors, Config, RenderingMode};
+use crate::format;
+use crate::keymap::{self, Action};
+use crate::metadata;
+use crate::project::graph::DependencyGraph;
+use crate::project::kind::ProjectKind;
+use crate::project::list::{
+    ProjectInfo, SortMode, cached_projects, list_projects, rescan_git_status, sort_projects,
+};
+use crate::project::tags;
+use crate::projects_watch;
+use crate::theme;
+use crate::ui::{
+    audit, batch, error_view, lint_fix, progress, project_detail, project_menu, search, status_bar,
+    unpushed, vanished,
+};
+
+const LIST_DIALOG: &str = "project_list_dialog";
+const LIST_VIEW: &str = "project_list";
+const TITLE: &str = "Projects";
+const TITLE_REFRESHING: &str = "Projects (refreshing...)";
+const SORT_BUTTON_PREFIX: &str = "<Sort:";
+const TAG_BUTTON_PREFIX: &str = "<Tag:";
+
+/// Current order of the list, loaded from the metadata store on first use.
+static SORT_MODE: Mutex<Option<SortMode>> = Mutex::new(None);
+
+/// Tag the listed projects must have (`None`: all projects), kept while rustm runs.
+static TAG_FILTER: Mutex<Option<String>> = Mutex::new(None);
+
+/// Age thresholds of the time columns, from the configuration of the open list.
+static AGE_COLORS: Mutex<Option<AgeColors>> = Mutex::new(None);
+
+/// Glyph set of the kind column, from the configuration of the open list.
+static RENDERING_MODE: Mutex<RenderingMode> = Mutex::new(RenderingMode::Ascii);
+
+/// Every project of the list, including the ones hidden by the tag filter.
+static PROJECTS: Mutex<Vec<ProjectInfo>> = Mutex::new(Vec::new());
+
+/// Paths of the projects marked for a batch action (see `ui::batch`).
+static MARKED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// Generation of the auto-refresh timer; bumping it stops the running one.
+static AUTO_REFRESH: AtomicU64 = AtomicU64::new(0);
+
+/// Whether a git status rescan is running.
+static STATUS_REFRESHING: AtomicBool = AtomicBool::new(false);
+
+/// Show the project list: cached entries right away when available, else after a scan.
+pub fn show(s: &mut Cursive, config: &Config) {
+    let cached = cached_projects(config);
+    if !cached.is_empty() {
+        show_projects(s, config, cached, TITLE_REFRESHING);
+        refresh_in_background(s, config);
+        return;
+    }
+
+    let scan_config = config.clone();
+    let config = config.clone();
+    progress::run(
+        s,
+        "Scanning projects",
+        move |_| list_projects(&scan_config),
+        move |s, result| match result {
+            Ok(projects) => {
+                vanished::check(s, &config, &projects);
+                show_projects(s, &config, projects, TITLE);
+            }
+            Err(e) => {
+                error_view::show(s, "Failed to list projects", &e);
+            }
+        },
+    );
+}
+
+/// Rescan the listed projects (after a rename or delete), keeping the list usable meanwhile.
+pub fn refresh(s: &mut Cursive, config: &Config) {
+    s.call_on_name(LIST_DIALOG, |d: &mut Dialog| d.set_title(TITLE_REFRESHING));
+    refresh_in_background(s, config);
+}
+
+/// Mark the listed project at `path` as (un)pinned and move it accordingly.
+pub fn set_pinned(s: &mut Cursive, path: &Path, pinned: bool) {
+    update_listed(s, path, |p| p.pinned = pinned);
+}
+
+/// Mark the listed project at `path` as (un)trusted.
+pub fn set_untrusted(s: &mut Cursive, path: &Path, untrusted: bool) {
+    update_listed(s, path, |p| p.untrusted = untrusted);
+}
+
+/// Replace the tags of the listed project at `path`.
+pub fn set_tags(s: &mut Cursive, path: &Path, tags: Vec<String>) {
+    update_listed(s, path, |p| p.tags.clone_from(&tags));
+}
+
+/// Remember that the project at `path` was just opened (editor, terminal, build, ...).
+pub fn record_opened(s: &mut Cursive, path: &Path) {
+    metadata::record_opened(path);
+    let now = metadata::now();
+    update_listed(s, path, |p| p.last_opened = Some(now));
+}
+
+/// Apply `f` to the project at `path`, then re-sort the list.
+fn update_listed(s: &mut Cursive, path: &Path, f: impl Fn(&mut ProjectInfo)) {
+    let mut projects = all_projects();
+    projects.iter_mut().filter(|p| p.path == path).for_each(f);
+    set_projects(s, projects);
+}
+
+/// Unmark every project.
+pub fn unmark_all(s: &mut Cursive) {
+    lock(&MARKED).clear();
+    set_projects(s, all_projects());
+}
+
+/// Mark the selected project, or unmark it, then move to the next one.
+fn toggle_mark(s: &mut Cursive) {
+    let Some(project) = selected_project(s) else {
+        return;
+    };
+    {
+        let mut marked = lock(&MARKED);
+        if !marked.remove(&project.path) {
+            marked.insert(project.path);
+        }
+    }
+    set_projects(s, all_projects());
+    s.call_on_name(LIST_VIEW, |v: &mut SelectView<ProjectInfo>| {
+        v.select_down(1)
+    });
+}
+
+/// Marked projects, in list order.
+fn marked_projects(s: &mut Cursive) -> Vec<ProjectInfo> {
+    let marked = lock(&MARKED).clone();
+    listed_projects(s)
+        .into_iter()
+        .filter(|p| marked.contains(&p.path))
+        .collect()
+}
+
+fn lock<T>(state: &Mutex<T>) -> MutexGuard<'_, T> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn sort_mode() -> SortMode {
+    *lock(&SORT_MODE).get_or_insert_with(|| metadata::snapshot().sort_mode())
+}
+
+fn all_projects() -> Vec<ProjectInfo> {
+    lock(&PROJECTS).clone()
+}
+
+/// Switch to the next sort mode, remember it and reorder the list.
+fn cycle_sort_mode(s: &mut Cursive) {
+    let mode = sort_mode().next();
+    *lock(&SORT_MODE) = Some(mode);
+    metadata::update(|store| store.set_sort_mode(mode));
+    relabel_button(s, SORT_BUTTON_PREFIX, sort_label(mode));
+    set_projects(s, all_projects());
+}
+
+fn sort_label(mode: SortMode) -> String {
+    format!("Sort: {}", mode.label())
+}
+
+fn tag_label(filter: Option<&str>) -> String {
+    format!("Tag: {}", filter.unwrap_or("all"))
+}
+
+/// Set the label of the list button whose label starts with `prefix`.
+fn relabel_button(s: &mut Cursive, prefix: &str, label: String) {
+    s.call_on_name(LIST_DIALOG, |d: &mut Dialog| {
+        if let Some(button) = d.buttons_mut().find(|b| b.label().starts_with(prefix)) {
+            button.set_label(label);
+        }
+    });
+}
+
+/// Let the user pick the tag the listed projects must have.
+fn pick_tag_filter(s: &mut Cursive) {
+    let tags = tags::all(&all_projects());
+    if tags.is_empty() {
+        s.add_layer(Dialog::info(
+            "No project is tagged yet.\nUse \"Tags...\" in the project menu.",
+        ));
+        return;
+    }
+    let mut select = SelectView::<Option<String>>::new().item("All projects", None);
+    for tag in tags {
+        select.add_item(format!("#{tag}"), Some(tag));
+    }
+    select.set_on_submit(|s, filter: &Option<String>| {
+        s.pop_layer();
+        set_tag_filter(s, filter.clone());
+        set_projects(s, all_projects());
+    });
+    s.add_layer(
+        Dialog::around(select.scrollable().max_size((40, 16)))
+            .title("Show projects tagged")
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn set_tag_filter(s: &mut Cursive, filter: Option<String>) {
+    relabel_button(s, TAG_BUTTON_PREFIX, tag_label(filter.as_deref()));
+    *lock(&TAG_FILTER) = filter;
+}
+
+/// Rescan without blocking the UI and replace the visible entries with the result.
+fn refresh_in_background(s: &mut Cursive, config: &Config) {
+    let task = status_bar::task_started(s, "Refreshing projects");
+    let sink = s.cb_sink().clone();
+    let config = config.clone();
+    thread::spawn(move || {
+        let result = list_projects(&config);
+        let _ = sink.send(Box::new(move |s: &mut Cursive| {
+            status_bar::task_finished(s, task);
+            let projects = match result {
+                Ok(projects) => projects,
+                Err(e) => {
+                    warn!("Background project refresh failed: {e}");
+                    s.call_on_name(LIST_DIALOG, |d: &mut Dialog| d.set_title(TITLE));
+                    return;
+                }
+            };
+            vanished::check(s, &config, &projects);
+            set_projects(s, projects);
+            s.call_on_name(LIST_DIALOG, |d: &mut Dialog| d.set_title(TITLE));
+        }));
+    });
+}
+
+/// Rescan the git status of the listed projects without blocking the UI.
+///
+/// Does nothing while a previous rescan runs. Only the scan results of the
+/// projects are replaced, so pins or tags changed meanwhile are kept.
+fn refresh_status(s: &mut Cursive, config: &Config) {
+    if STATUS_REFRESHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let task = status_bar::task_started(s, "Refreshing git status");
+    let sink = s.cb_sink().clone();
+    let projects = all_projects();
+    let config = config.clone();
+    thread::spawn(move || {
+        let scanned = rescan_git_status(&config, projects);
+        let _ = sink.send(Box::new(move |s: &mut Cursive| {
+            STATUS_REFRESHING.store(false, Ordering::SeqCst);
+            status_bar::task_finished(s, task);
+            let mut projects = all_projects();
+            for p in &mut projects {
+                if let Some(fresh) = scanned.iter().find(|f| f.path == p.path) {
+                    p.has_uncommitted_changes = fresh.has_uncommitted_changes;
+                    p.branch.clone_from(&fresh.branch);
+                    p.ahead_behind = fresh.ahead_behind;
+                    p.diverged_default.clone_from(&fresh.diverged_default);
+                    p.version.clone_from(&fresh.version);
+                    p.last_commit = fresh.last_commit;
+                    p.fingerprint = fresh.fingerprint;
+                }
+            }
+            set_projects(s, projects);
+        }));
+    });
+}
+
+/// Rescan the git status every `scan.status_refresh_secs` until the list is closed.
+///
+/// A timer started by a previous opening of the list stops.
+fn start_auto_refresh(s: &mut Cursive, config: &Config) {
+    let generation = AUTO_REFRESH.fetch_add(1, Ordering::SeqCst) + 1;
+    let secs = config.scan().status_refresh_secs;
+    if secs == 0 {
+        return;
+    }
+    let sink = s.cb_sink().clone();
+    let config = config.clone();
+    thread::spawn(move || {
+        loop {
+            thread::sleep(Duration::from_secs(secs));
+            let config = config.clone();
+            if AUTO_REFRESH.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            let sent = sink.send(Box::new(move |s: &mut Cursive| {
+                if s.find_name::<SelectView<ProjectInfo>>(LIST_VIEW).is_none() {
+                    // Closed: stop this timer, unless the list was reopened since.
+                    let _ = AUTO_REFRESH.compare_exchange(
+                        generation,
+                        generation + 1,
+                        Ordering::SeqCst,
+                        Ordering::SeqCst,
+                    );
+                    return;
+                }
+                refresh_status(s, &config);
+            }));
+            if sent.is_err() {
+                return;
+            }
+        }
+    });
+}
+
+/// Replace the entries, showing the ones with the filtered tag sorted by the
+/// current mode, and keeping the selected project selected.
+///
+/// A filter on a tag no project has anymore is dropped.
+fn set_projects(s: &mut Cursive, projects: Vec<ProjectInfo>) {
+    let filter = lock(&TAG_FILTER).clone();
+    let filter = filter.filter(|tag| projects.iter().any(|p| p.tags.contains(tag)));
+    set_tag_filter(s, filter.clone());
+    status_bar::set_projects(s, &projects);
+    lock(&MARKED).retain(|path| projects.iter().any(|p| &p.path == path));
+    *lock(&PROJECTS) = projects.clone();
+    let marked = lock(&MARKED).clone();
+
+    let mut projects: Vec<ProjectInfo> = projects
+        .into_iter()
+        .filter(|p| filter.as_ref().is_none_or(|tag| p.tags.contains(tag)))
+        .collect();
+    sort_projects(&mut projects, sort_mode());
+    let name_width = name_width(&projects);
+    let times = Times {
+        now: i64::try_from(metadata::now()).unwrap_or(i64::MAX),
+        ages: lock(&AGE_COLORS).unwrap_or_default(),
+        palette: s.current_theme().palette.clone(),
+    };
+    let mode = *lock(&RENDERING_MODE);
+    s.call_on_name(LIST_VIEW, |v: &mut SelectView<ProjectInfo>| {
+        let selected = v.selection().map(|p| p.path.clone());
+        v.clear();
+        for p in projects {
+            v.add_item(
+                row_label(&p, name_width, marked.contains(&p.path), mode, &times),
+                p,
+            );
+        }
+        let index = selected.and_then(|path| v.iter().position(|(_, p)| p.path == path));
+        v.set_selection(index.unwrap_or(0));
+    });
+}
+
+fn show_projects(s: &mut Cursive, config: &Config, projects: Vec<ProjectInfo>, title: &str) {
+    if projects.is_empty() {
+        s.add_layer(Dialog::info("No Rust projects found."));
+        return;
+    }
+
+    let mut select = SelectView::<ProjectInfo>::new();
+    let menu_config = config.clone();
+    select.set_on_submit(move |s, p: &ProjectInfo| project_menu::show(s, &menu_config, p));
+
+    let keys = keymap::get();
+    let (refresh_config, status_config, search_config, open_config) = (
+        config.clone(),
+        config.clone(),
+        config.clone(),
+        config.clone(),
+    );
+    let dialog = OnEventView::new(
+        Dialog::around(
+            select
+                .with_name(LIST_VIEW)
+                .scrollable()
+                .fixed_size((70, 20)),
+        )
+        .title(title)
+        .button(sort_label(sort_mode()), cycle_sort_mode)
+        .button(tag_label(lock(&TAG_FILTER).as_deref()), pick_tag_filter)
+        .button("Actions...", show_actions)
+        .button("Marked...", {
+            let config = config.clone();
+            move |s| {
+                let projects = marked_projects(s);
+                if projects.is_empty() {
+                    s.add_layer(Dialog::info(format!(
+                        "No project marked.\nPress {} on a project to mark it.",
+                        keymap::get().key(Action::Mark)
+                    )));
+                    return;
+                }
+                batch::show(s, &config, projects);
+            }
+        })
+        .button("Close", |siv| {
+            siv.pop_layer();
+        })
+        .with_name(LIST_DIALOG),
+    )
+    .on_event(keys.event(Action::Refresh), move |s| {
+        refresh(s, &refresh_config);
+    })
+    .on_event(keys.event(Action::RefreshStatus), move |s| {
+        refresh_status(s, &status_config);
+    })
+    .on_event(keys.event(Action::Mark), toggle_mark)
+    .on_event(keys.event(Action::Search), move |s| {
+        if let Some(p) = selected_project(s) {
+            search::show(s, &search_config, &p.path);
+        }
+    })
+    .on_event(keys.event(Action::OpenEditor), move |s| {
+        if let Some(p) = selected_project(s) {
+            project_detail::open_project(s, &open_config, &p.path);
+        }
+    });
+    s.add_layer(dialog);
+    *lock(&AGE_COLORS) = Some(config.age_colors());
+    *lock(&RENDERING_MODE) = config.rendering_mode();
+    set_projects(s, projects);
+    start_auto_refresh(s, config);
+    watch_projects(s, config);
+}
+
+/// Rescan when projects appear or disappear, until the list is closed.
+fn watch_projects(s: &mut Cursive, config: &Config) {
+    let root = PathBuf::from(config.projects_directory());
+    let config = config.clone();
+    projects_watch::spawn(
+        s.cb_sink().clone(),
+        root,
+        config.scan().clone(),
+        Arc::new(move |s| {
+            if s.find_name::<SelectView<ProjectInfo>>(LIST_VIEW).is_none() {
+                projects_watch::stop();
+                return;
+            }
+            refresh(s, &config);
+        }),
+    );
+}
+
+/// Render the path dependency graph of the listed projects.
+/// Actions of the "Actions..." menu, run on every listed project.
+#[derive(Clone, Copy)]
+enum ListAction {
+    DependencyGraph,
+    Audit,
+    Unpushed,
+    LintFix,
+}
+
+fn show_actions(s: &mut Cursive) {
+    let menu = SelectView::new()
+        .item("Dependency graph", ListAction::DependencyGraph)
+        .item("Security audit", ListAction::Audit)
+        .item("Unpushed work", ListAction::Unpushed)
+        .item("Clippy fix & fmt", ListAction::LintFix)
+        .on_submit(|s, action: &ListAction| {
+            s.pop_layer();
+            match action {
+                ListAction::DependencyGraph => show_dependency_graph(s),
+                ListAction::Audit => {
+                    let projects = listed_projects(s);
+                    audit::audit_all(s, projects);
+                }
+                ListAction::Unpushed => {
+                    let projects = listed_projects(s);
+                    unpushed::show(s, projects);
+                }
+                ListAction::LintFix => {
+                    let projects = listed_projects(s);
+                    lint_fix::show(s, projects);
+                }
+            }
+        });
+    s.add_layer(
+        Dialog::around(menu)
+            .title("Listed projects")
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn show_dependency_graph(s: &mut Cursive) {
+    let projects = listed_projects(s);
+    progress::run(
+        s,
+        "Reading manifests",
+        move |_| DependencyGraph::build(projects).render(),
+        |s, text| {
+            s.add_layer(
+                Dialog::around(TextView::new(text).scrollable().max_size((70, 20)))
+                    .title("Path dependencies")
+                    .dismiss_button("Close"),
+            );
+        },
+    );
+}
+
+fn selected_project(s: &mut Cursive) -> Option<ProjectInfo> {
+    s.call_on_name(LIST_VIEW, |v: &mut SelectView<ProjectInfo>| {
+        v.selection().map(|p| (*p).clone())
+    })
+    .flatten()
+}
+
+/// Projects currently shown in the list.
+fn listed_projects(s: &mut Cursive) -> Vec<ProjectInfo> {
+    s.call_on_name(LIST_VIEW, |v: &mut SelectView<ProjectInfo>| {
+        v.iter().map(|(_, p)| p.clone()).collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Width of the name column (room for the `✓★ `, kind glyph and ` *` markers).
+fn name_width(projects: &[ProjectInfo]) -> usize {
+    projects
+        .iter()
+        .map(|p| p.name.chars().count() + 7)
+        .max()
+        .unwrap_or(0)
+}
+
+/// `↑2 ↓1` style upstream indicator; `None` when in sync or without upstream.
+fn tracking_label(ahead_behind: Option<(usize, usize)>) -> Option<String> {
+    match ahead_behind? {
+        (0, 0) => None,
+        (ahead, 0) => Some(format!("↑{ahead}")),
+        (0, behind) => Some(format!("↓{behind}")),
+        (ahead, behind) => Some(format!("↑{ahead} ↓{behind}")),
+    }
+}
+
+/// `✓★ K name *  version  branch ↑n ↓m  commit  opened  path  #tags  [untrusted]  [no_std]  [⚠ main diverged]  [rollup]` with the name column padded to `name_width`.
+///
+/// `✓` marks the projects marked for a batch action, `K` is the glyph of the
+/// kind of the project (see `kind_glyph`). `commit` and `opened`
+/// are the times of the last commit and of the last opening from rustm (see
+/// `crate::format`), `-` when unknown, colored by age.
+fn row_label(
+    p: &ProjectInfo,
+    name_width: usize,
+    marked: bool,
+    mode: RenderingMode,
+    times: &Times,
+) -> StyledString {
+    let mut name = if marked { "✓" } else { " " }.to_string();
+    name.push_str(if p.pinned { "★ " } else { "  " });
+    name.push_str(kind_glyph(p, mode));
+    name.push(' ');
+    name.push_str(&p.name);
+    if p.has_uncommitted_changes {
+        name.push_str(" *");
+    }
+    let version = p.version.as_deref().unwrap_or("-");
+    let mut branch = p.branch.clone().unwrap_or_else(|| "-".into());
+    if let Some(tracking) = tracking_label(p.ahead_behind) {
+        branch.push(' ');
+        branch.push_str(&tracking);
+    }
+    let mut row = StyledString::plain(format!("{name:<name_width$}  {version:<8}  {branch:<18}  "));
+    row.append(times.column(p.last_commit));
+    row.append_plain("  ");
+    row.append(times.column(p.last_opened.and_then(|at| i64::try_from(at).ok())));
+    let mut label = format!("  {}", p.path.display());
+    if !p.tags.is_empty() {
+        label.push_str(&format!("  {}", tags::label(&p.tags)));
+    }
+    if p.untrusted {
+        label.push_str("  [untrusted]");
+    }
+    if p.no_std.as_ref().is_some_and(|n| n.compatible) {
+        label.push_str("  [no_std]");
+    }
+    if let Some(default) = &p.diverged_default {
+        label.push_str(&format!("  [⚠ {default} diverged]"));
+    }
+    if let Some(rollup) = &p.rollup {
+        label.push_str(&format!("  [{}]", rollup.summary()));
+    }
+    row.append_plain(label);
+    row
+}
+
+/// Glyph of the kind of `p` in `mode`; external for quarantined projects (they
+/// come from outside rustm, see `ui::trust`), blank when the kind is unknown.
+///
+/// Nerd Font glyphs: `nf-oct-terminal`, `nf-oct-package`,
+/// `nf-oct-file_directory`, `nf-fa-globe`, `nf-oct-cpu`, `nf-oct-link_external`.
+fn kind_glyph(p: &ProjectInfo, mode: RenderingMode) -> &'static str {
+    let (ascii, nerd_font) = match p.kind {
+        _ if p.untrusted => ("X", "\u{f465}"),
+        Some(ProjectKind::Binary) => ("B", "\u{f489}"),
+        Some(ProjectKind::Library) => ("L", "\u{f487}"),
+        Some(ProjectKind::Workspace) => ("W", "\u{f413}"),
+        Some(ProjectKind::Wasm) => ("@", "\u{f0ac}"),
+        Some(ProjectKind::Embedded) => ("E", "\u{f4bc}"),
+        None => (" ", " "),
+    };
+    match mode {
+        RenderingMode::Ascii => ascii,
+        RenderingMode::NerdFont => nerd_font,
+    }
+}
+
+/// What the time columns of the rows are computed against.
+struct Times {
+    now: i64,
+    ages: AgeColors,
+    palette: Palette,
+}
+
+impl Times {
+    /// Column of the time `at`, colored by age; `-` when unknown.
+    fn column(&self, at: Option<i64>) -> StyledString {
+        let width = format::column_width();
+        match at {
+            Some(at) => StyledString::styled(
+                format!("{:>width$}", format::timestamp_column(at, self.now)),
+                theme::age_color(&self.palette, self.ages.age(self.now - at)),
+            ),
+            None => StyledString::plain(format!("{:>width$}", "-")),
+        }
+    }
+}
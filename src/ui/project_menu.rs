@@ -0,0 +1,399 @@
+//! Per-project action menu.
+//!
+//! Opened with Enter on the project list. Offers the everyday actions without
+//! going through the detail dialog: open in the editor or a terminal, `cargo
+//! build` / `cargo test` (output streamed into the progress dialog; in a
+//! container with `sandbox.enabled`, see `project::sandbox`), git
+//! status, the repository page of the remote in a browser (see
+//! `forge::web_page`), rename (directory and optionally `[package] name`), archive (see
+//! `ui::archive`), delete (the project name must be typed), tags (see `project::tags`), pin / unpin and
+//! trust / mark as untrusted (see `ui::trust`: builds, tests and custom
+//! actions of untrusted projects ask first). "Details" opens the full detail
+//! dialog. The project's `.rustm.yaml` can set the cargo profile of "Build"
+//! and add custom actions, listed last.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Checkbox, Dialog, EditView, LinearLayout, SelectView, TextView};
+use log::{error, warn};
+
+use crate::cargo_ops;
+use crate::config::{Config, CustomAction, SandboxSettings};
+use crate::desktop;
+use crate::events::{self, Event};
+use crate::forge;
+use crate::git_ops;
+use crate::metadata;
+use crate::project::list::ProjectInfo;
+use crate::project::{manage, sandbox, tags};
+use crate::ui::{archive, error_view, notify, progress, project_detail, project_list, trust};
+
+const RENAME_INPUT: &str = "project_menu_rename";
+const RENAME_PACKAGE: &str = "project_menu_rename_package";
+const DELETE_INPUT: &str = "project_menu_delete";
+const TAGS_INPUT: &str = "project_menu_tags";
+
+/// Lines of cargo output kept in the result dialog.
+const OUTPUT_TAIL: usize = 200;
+
+#[derive(Clone)]
+enum Action {
+    Details,
+    Open,
+    Terminal,
+    Build,
+    Test,
+    GitStatus,
+    Browse,
+    Rename,
+    Archive,
+    Delete,
+    Tags,
+    TogglePin,
+    ToggleTrust,
+    Custom(CustomAction),
+}
+
+/// Show the action menu for `project`.
+pub fn show(s: &mut Cursive, config: &Config, project: &ProjectInfo) {
+    let pin_label = if project.pinned { "Unpin" } else { "Pin" };
+    let trust_label = if project.untrusted {
+        "Trust"
+    } else {
+        "Mark as untrusted"
+    };
+    let mut menu = SelectView::<Action>::new()
+        .item("Details", Action::Details)
+        .item("Open in editor", Action::Open)
+        .item("Open terminal", Action::Terminal)
+        .item("Build", Action::Build)
+        .item("Test", Action::Test)
+        .item("Git status", Action::GitStatus);
+    if project.remote_url.is_some() {
+        menu.add_item("Open repository in browser", Action::Browse);
+    }
+    menu = menu
+        .item("Rename...", Action::Rename)
+        .item("Archive...", Action::Archive)
+        .item("Delete...", Action::Delete)
+        .item("Tags...", Action::Tags)
+        .item(pin_label, Action::TogglePin)
+        .item(trust_label, Action::ToggleTrust);
+    for action in config.for_project(&project.path).actions() {
+        menu.add_item(format!("> {}", action.name), Action::Custom(action.clone()));
+    }
+
+    let title = project.name.clone();
+    let config = config.clone();
+    let project = project.clone();
+    menu.set_on_submit(move |s, action: &Action| {
+        s.pop_layer();
+        run(s, &config, &project, action.clone());
+    });
+
+    s.add_layer(Dialog::around(menu).title(title).dismiss_button("Cancel"));
+}
+
+fn run(s: &mut Cursive, config: &Config, project: &ProjectInfo, action: Action) {
+    let path = &project.path;
+    match action {
+        Action::Details => project_detail::show(s, config, project),
+        Action::Open => project_detail::open_project(s, config, path),
+        Action::Terminal => match desktop::open_terminal(path, config.terminal_cmd()) {
+            Ok(_) => project_list::record_opened(s, path),
+            Err(e) => {
+                error!("Failed to open a terminal in {}: {e}", path.display());
+                error_view::show(s, "Cannot open a terminal", &e);
+            }
+        },
+        Action::Build => {
+            let mut args = vec!["build".to_string()];
+            if let Some(profile) = config.for_project(path).build_profile() {
+                args.extend(["--profile".to_string(), profile.to_string()]);
+            }
+            run_cargo(s, config, project, "building", args);
+        }
+        Action::Test => run_cargo(s, config, project, "testing", vec!["test".to_string()]),
+        Action::GitStatus => git_status(s, &project.name, path),
+        Action::Browse => browse(s, config, project),
+        Action::Rename => rename(s, config, project),
+        Action::Archive => archive::confirm(s, config, project),
+        Action::Delete => delete(s, config, project),
+        Action::Tags => edit_tags(s, project),
+        Action::TogglePin => {
+            let pinned = !project.pinned;
+            metadata::update(|store| store.entry_mut(path).pinned = pinned);
+            project_list::set_pinned(s, path, pinned);
+        }
+        Action::ToggleTrust => trust::set_trusted(s, path, project.untrusted),
+        Action::Custom(action) => {
+            let mut parts = action.command.split_whitespace().map(ToString::to_string);
+            let Some(program) = parts.next() else {
+                return;
+            };
+            let name = project.name.clone();
+            let path = path.clone();
+            let args: Vec<String> = parts.collect();
+            trust::guard(s, &path.clone(), "this action", move |s| {
+                project_list::record_opened(s, &path);
+                run_streamed(s, &name, &path, &program, args.clone(), None);
+            });
+        }
+    }
+}
+
+/// Run `cargo <args>`, in the container sandbox when enabled, as `run_streamed`.
+///
+/// Outside the sandbox, an untrusted project must be trusted first (`what` it is about to do).
+fn run_cargo(
+    s: &mut Cursive,
+    config: &Config,
+    project: &ProjectInfo,
+    what: &str,
+    args: Vec<String>,
+) {
+    let sandbox = config.sandbox();
+    let sandbox = sandbox.enabled.then(|| sandbox.clone());
+    let name = project.name.clone();
+    let path = project.path.clone();
+    let run = move |s: &mut Cursive| {
+        project_list::record_opened(s, &path);
+        run_streamed(s, &name, &path, "cargo", args.clone(), sandbox.clone());
+    };
+    if config.sandbox().enabled {
+        run(s);
+    } else {
+        trust::guard(s, &project.path, what, run);
+    }
+}
+
+/// Run `program <args>` streaming its output, then show the end of it.
+///
+/// With a `sandbox`, `cargo <args>` runs in its container.
+fn run_streamed(
+    s: &mut Cursive,
+    name: &str,
+    path: &Path,
+    program: &str,
+    args: Vec<String>,
+    sandbox: Option<SandboxSettings>,
+) {
+    let path = path.to_path_buf();
+    let command = format!("{program} {}", args.join(" "));
+    let title = match sandbox {
+        Some(_) => format!("{command} (sandbox): {name}"),
+        None => format!("{command}: {name}"),
+    };
+    let program = program.to_string();
+    let (name, event_command) = (name.to_string(), command.clone());
+    progress::run(
+        s,
+        &title.clone(),
+        move |p| {
+            let lines = RefCell::new(Vec::new());
+            let started = Instant::now();
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            let log = |line: String| {
+                p.log(line.clone());
+                lines.borrow_mut().push(line);
+            };
+            let result = match &sandbox {
+                Some(sandbox) => sandbox::run_cargo_streaming(sandbox, &path, &args, &log),
+                None => cargo_ops::run_tool_streaming(&program, &path, &args, &log),
+            };
+            if program == "cargo" {
+                events::emit([Event::BuildFinished {
+                    name,
+                    path,
+                    command: event_command,
+                    success: matches!(result, Ok(true)),
+                    duration_ms: started.elapsed().as_millis() as u64,
+                }]);
+            }
+            (result, lines.into_inner())
+        },
+        move |s, (result, lines)| {
+            let status = match result {
+                Ok(true) => "Succeeded.".to_string(),
+                Ok(false) => "Failed.".to_string(),
+                Err(e) => {
+                    error!("{command} failed to start: {e}");
+                    format!("Cannot run {command}: {e}")
+                }
+            };
+            let start = lines.len().saturating_sub(OUTPUT_TAIL);
+            let text = format!("{status}\n\n{}", lines[start..].join("\n"));
+            s.add_layer(
+                Dialog::around(TextView::new(text).scrollable().max_size((90, 25)))
+                    .title(title)
+                    .dismiss_button("Close"),
+            );
+        },
+    );
+}
+
+fn git_status(s: &mut Cursive, name: &str, path: &Path) {
+    let text = match git_ops::status_lines(path) {
+        Ok(lines) if lines.is_empty() => "Working tree clean.".to_string(),
+        Ok(lines) => lines.join("\n"),
+        Err(e) => format!("Cannot read the git status:\n{e}"),
+    };
+    s.add_layer(
+        Dialog::around(TextView::new(text).scrollable().max_size((80, 20)))
+            .title(format!("Git status: {name}"))
+            .dismiss_button("Close"),
+    );
+}
+
+/// Open the web page of the project's remote, on the current branch once it
+/// has been pushed (it has an upstream).
+fn browse(s: &mut Cursive, config: &Config, project: &ProjectInfo) {
+    let Some(remote) = &project.remote_url else {
+        return;
+    };
+    let branch = project
+        .branch
+        .as_deref()
+        .filter(|_| project.ahead_behind.is_some());
+    let Some(url) = forge::web_page(remote, branch, &config.forge().gitlab_url) else {
+        error_view::show_message(
+            s,
+            "No repository page",
+            &format!("The remote {remote} is not on a web forge."),
+        );
+        return;
+    };
+    if let Err(e) = desktop::open_url(&url) {
+        warn!("Cannot open browser: {e}");
+        s.add_layer(Dialog::info(format!(
+            "Cannot open a browser ({e}).\nOpen:\n{url}"
+        )));
+    }
+}
+
+fn rename(s: &mut Cursive, config: &Config, project: &ProjectInfo) {
+    let dir_name = project
+        .path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let form = LinearLayout::vertical()
+        .child(TextView::new("New directory name:"))
+        .child(
+            EditView::new()
+                .content(dir_name)
+                .with_name(RENAME_INPUT)
+                .fixed_width(30),
+        )
+        .child(
+            LinearLayout::horizontal()
+                .child(Checkbox::new().checked().with_name(RENAME_PACKAGE))
+                .child(TextView::new(" Also rename [package] name")),
+        );
+
+    let config = config.clone();
+    let path = project.path.clone();
+    s.add_layer(
+        Dialog::around(form)
+            .title(format!("Rename {}", project.name))
+            .button("Rename", move |s| {
+                let new_name = s
+                    .call_on_name(RENAME_INPUT, |v: &mut EditView| v.get_content())
+                    .map(|c| c.trim().to_string())
+                    .unwrap_or_default();
+                let rename_package = s
+                    .call_on_name(RENAME_PACKAGE, |v: &mut Checkbox| v.is_checked())
+                    .unwrap_or(false);
+                match manage::rename(&path, &new_name, rename_package) {
+                    Ok(new_path) => {
+                        metadata::update(|store| store.rename(&path, &new_path));
+                        s.pop_layer();
+                        project_list::refresh(s, &config);
+                        notify::toast(s, format!("Renamed to {}.", new_path.display()));
+                    }
+                    Err(e) => {
+                        error!("Rename of {} failed: {e}", path.display());
+                        error_view::show(s, "Cannot rename", &e);
+                    }
+                }
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn edit_tags(s: &mut Cursive, project: &ProjectInfo) {
+    let path = project.path.clone();
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new("Tags, separated by commas or spaces:"))
+                .child(
+                    EditView::new()
+                        .content(project.tags.join(", "))
+                        .with_name(TAGS_INPUT)
+                        .fixed_width(40),
+                ),
+        )
+        .title(format!("Tags of {}", project.name))
+        .button("Save", move |s| {
+            let tags = s
+                .call_on_name(TAGS_INPUT, |v: &mut EditView| tags::parse(&v.get_content()))
+                .unwrap_or_default();
+            metadata::update(|store| store.entry_mut(&path).tags.clone_from(&tags));
+            s.pop_layer();
+            project_list::set_tags(s, &path, tags);
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+fn delete(s: &mut Cursive, config: &Config, project: &ProjectInfo) {
+    let mut text = format!(
+        "Permanently delete {} and everything in it?\n",
+        project.path.display()
+    );
+    if project.has_uncommitted_changes {
+        text.push_str("\nWarning: the project has uncommitted changes.\n");
+    }
+    text.push_str(&format!("\nType \"{}\" to confirm:", project.name));
+
+    let config = config.clone();
+    let name = project.name.clone();
+    let path: PathBuf = project.path.clone();
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(text))
+                .child(EditView::new().with_name(DELETE_INPUT).fixed_width(30)),
+        )
+        .title("Delete project")
+        .button("Delete", move |s| {
+            let typed = s
+                .call_on_name(DELETE_INPUT, |v: &mut EditView| v.get_content())
+                .unwrap_or_default();
+            if typed.trim() != name {
+                s.add_layer(Dialog::info(
+                    "The name does not match; nothing was deleted.",
+                ));
+                return;
+            }
+            match manage::delete(&path) {
+                Ok(()) => {
+                    metadata::update(|store| store.remove(&path));
+                    s.pop_layer();
+                    project_list::refresh(s, &config);
+                    notify::toast(s, format!("Deleted {}.", path.display()));
+                }
+                Err(e) => {
+                    error!("Deletion of {} failed: {e}", path.display());
+                    error_view::show(s, "Cannot delete", &e);
+                }
+            }
+        })
+        .dismiss_button("Cancel"),
+    );
+}
@@ -0,0 +1,219 @@
+//! Rewrite commits screen (simplified interactive rebase).
+//!
+//! Lists the commits of the current branch since its upstream, oldest first.
+//! "Up" / "Down" reorder them, "Squash" toggles melding a commit into the one
+//! above it and "Reword" edits its message. "Run" executes the plan (see
+//! `project::rebase`); "Cancel" leaves the branch untouched.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, SelectView, TextArea, TextView};
+use log::error;
+
+use crate::project::rebase::{self, PlannedCommit, Step};
+use crate::ui::git_actions::OnBranchChanged;
+use crate::ui::{error_view, notify, progress};
+
+const COMMIT_LIST: &str = "rebase_commits";
+const MESSAGE_INPUT: &str = "rebase_message";
+
+/// Show the commits of the current branch of the repository at `path`.
+pub fn show(s: &mut Cursive, path: &Path, on_changed: OnBranchChanged) {
+    let (upstream, commits) = match rebase::plan(path) {
+        Ok(plan) => plan,
+        Err(e) => {
+            error_view::show(s, "Cannot rewrite commits", &e);
+            return;
+        }
+    };
+
+    let mut list = SelectView::<PlannedCommit>::new();
+    for commit in commits {
+        list.add_item(commit.label(), commit);
+    }
+
+    let path = path.to_path_buf();
+    let title = format!("Rewrite commits since {upstream}");
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(
+                    "Oldest first. Squash melds a commit into the one above it.",
+                ))
+                .child(
+                    list.with_name(COMMIT_LIST)
+                        .scrollable()
+                        .fixed_size((80, 14)),
+                ),
+        )
+        .title(title)
+        .button("Up", |s| move_selected(s, -1))
+        .button("Down", |s| move_selected(s, 1))
+        .button("Squash", toggle_squash)
+        .button("Reword", reword)
+        .button("Run", move |s| {
+            let commits: Vec<PlannedCommit> = s
+                .call_on_name(COMMIT_LIST, |v: &mut SelectView<PlannedCommit>| {
+                    v.iter().map(|(_, c)| c.clone()).collect()
+                })
+                .unwrap_or_default();
+            s.pop_layer();
+            run(
+                s,
+                path.clone(),
+                upstream.clone(),
+                commits,
+                Arc::clone(&on_changed),
+            );
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+/// Move the selected commit `offset` rows (-1: up, 1: down).
+fn move_selected(s: &mut Cursive, offset: isize) {
+    s.call_on_name(COMMIT_LIST, |v: &mut SelectView<PlannedCommit>| {
+        let Some(from) = v.selected_id() else {
+            return;
+        };
+        let Some(to) = from.checked_add_signed(offset).filter(|&to| to < v.len()) else {
+            return;
+        };
+        let Some(commit) = v.get_item(from).map(|(_, c)| c.clone()) else {
+            return;
+        };
+        v.remove_item(from);
+        v.insert_item(to, commit.label(), commit);
+        v.set_selection(to);
+    });
+}
+
+fn toggle_squash(s: &mut Cursive) {
+    update_selected(s, |commit| {
+        commit.step = match commit.step {
+            Step::Squash => Step::Pick,
+            _ => Step::Squash,
+        };
+    });
+}
+
+fn reword(s: &mut Cursive) {
+    let Some(current) = s
+        .call_on_name(COMMIT_LIST, |v: &mut SelectView<PlannedCommit>| {
+            v.selection().map(|c| match &c.step {
+                Step::Reword(message) => message.clone(),
+                _ => c.message.clone(),
+            })
+        })
+        .flatten()
+    else {
+        return;
+    };
+    s.add_layer(
+        Dialog::around(
+            TextArea::new()
+                .content(current)
+                .with_name(MESSAGE_INPUT)
+                .fixed_size((72, 10)),
+        )
+        .title("Commit message")
+        .button("OK", |s| {
+            let message = s
+                .call_on_name(MESSAGE_INPUT, |v: &mut TextArea| {
+                    v.get_content().to_string()
+                })
+                .unwrap_or_default();
+            s.pop_layer();
+            update_selected(s, |commit| {
+                commit.step = if message.trim_end() == commit.message {
+                    Step::Pick
+                } else {
+                    Step::Reword(message.clone())
+                };
+            });
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+/// Apply `f` to the selected commit and refresh its label.
+fn update_selected(s: &mut Cursive, f: impl FnOnce(&mut PlannedCommit)) {
+    s.call_on_name(COMMIT_LIST, |v: &mut SelectView<PlannedCommit>| {
+        let Some(index) = v.selected_id() else {
+            return;
+        };
+        if let Some((label, commit)) = v.get_item_mut(index) {
+            f(commit);
+            *label = commit.label().into();
+        }
+    });
+}
+
+fn run(
+    s: &mut Cursive,
+    path: PathBuf,
+    upstream: String,
+    commits: Vec<PlannedCommit>,
+    on_changed: OnBranchChanged,
+) {
+    let work_path = path.clone();
+    progress::run(
+        s,
+        "Rewrite commits",
+        move |p| rebase::execute(&work_path, &upstream, &commits, &|line| p.log(line)),
+        move |s, result| match result {
+            Ok(()) => {
+                on_changed(s);
+                notify::toast(s, "History rewritten. The previous tip is ORIG_HEAD.");
+            }
+            Err(e) => {
+                error!("Rebase of {} failed: {e}", path.display());
+                error_view::show(s, "Cannot rewrite commits", &e);
+            }
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cargo_ops;
+    use crate::temp_dir::TempDir;
+    use crate::ui::testing::Harness;
+    use cursive::event::Key;
+    use std::fs;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        cargo_ops::run_tool("git", dir, args, &[]).unwrap()
+    }
+
+    #[test]
+    fn lists_the_commits_to_rewrite() {
+        let dir = TempDir::new("rebase_ui");
+        git(&dir, &["init", "-q"]);
+        git(&dir, &["config", "user.name", "Test"]);
+        git(&dir, &["config", "user.email", "test@example.com"]);
+        for file in ["base", "one", "two", "three"] {
+            fs::write(dir.join(file), file).unwrap();
+            git(&dir, &["add", file]);
+            git(&dir, &["commit", "-q", "-m", file]);
+            if file == "base" {
+                git(&dir, &["branch", "upstream"]);
+                git(&dir, &["branch", "-q", "--set-upstream-to=upstream"]);
+            }
+        }
+
+        let mut h = Harness::new(100, 30);
+        for id in git(&dir, &["log", "--format=%H", "upstream..HEAD"]).lines() {
+            h.redact(&id[..8], "<commit>");
+        }
+        show(h.siv(), &dir, Arc::new(|_| {}));
+        h.press(Key::Down);
+        h.press(Key::Down);
+        toggle_squash(h.siv());
+        h.assert_snapshot("rebase");
+    }
+}
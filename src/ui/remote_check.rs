@@ -0,0 +1,98 @@
+//! "Check on other platforms" results.
+//!
+//! Runs `project::remote_check` on the configured runners in the background,
+//! each step shown in the progress dialog, then lists one line per runner:
+//! its name, host, platform and outcome. Enter on a line shows the errors.
+
+use std::path::Path;
+
+use cursive::Cursive;
+use cursive::view::{Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, SelectView, TextView};
+use log::{error, info};
+
+use crate::config::Config;
+use crate::project::remote_check::{self, Outcome, RunnerResult};
+use crate::ui::{error_view, notify, progress};
+
+/// Check the project at `path` on every runner of `config`.
+pub fn show(s: &mut Cursive, config: &Config, name: &str, path: &Path) {
+    let runners = config.remote_runners().to_vec();
+    let name = name.to_string();
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        &format!("Check on other platforms: {name}"),
+        move |p| remote_check::check(&path, &runners, &|line| p.log(line)),
+        move |s, result| match result {
+            Ok(results) => {
+                let failures = results
+                    .iter()
+                    .filter(|r| !matches!(r.outcome, Outcome::Passed))
+                    .count();
+                info!("Remote check of {name}: {failures} failure(s)");
+                show_results(s, &name, results, failures);
+            }
+            Err(e) => {
+                error!("Remote check of {name} failed: {e}");
+                error_view::show(s, "Cannot check on other platforms", &e);
+            }
+        },
+    );
+}
+
+fn show_results(s: &mut Cursive, name: &str, results: Vec<RunnerResult>, failures: usize) {
+    let mut select = SelectView::<RunnerResult>::new();
+    for result in results {
+        let outcome = match result.outcome {
+            Outcome::Passed => "ok",
+            Outcome::Failed(_) => "FAIL",
+            Outcome::Unreachable(_) => "unreachable",
+        };
+        let line = format!(
+            "{:<12}  {:<24}  {:<28}  {outcome}",
+            result.runner.name,
+            result.runner.host,
+            result.platform.as_deref().unwrap_or("-")
+        );
+        select.add_item(line, result);
+    }
+    select.set_on_submit(show_errors);
+    let summary = if failures == 0 {
+        "Every runner passed.".to_string()
+    } else {
+        format!("{failures} runner(s) failed. Enter shows the errors.")
+    };
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(summary))
+                .child(TextView::new(format!(
+                    "\n{:<12}  {:<24}  {:<28}  result",
+                    "runner", "host", "platform"
+                )))
+                .child(select.scrollable().max_height(12)),
+        )
+        .title(format!("Other platforms: {name}"))
+        .dismiss_button("Close")
+        .max_width(100),
+    );
+}
+
+fn show_errors(s: &mut Cursive, result: &RunnerResult) {
+    let text = match &result.outcome {
+        Outcome::Passed => {
+            notify::toast(s, format!("cargo check passed on {}.", result.runner.name));
+            return;
+        }
+        Outcome::Failed(output) => format!("cargo check failed:\n\n{output}"),
+        Outcome::Unreachable(output) => {
+            format!("Cannot reach {}:\n\n{output}", result.runner.host)
+        }
+    };
+    s.add_layer(
+        Dialog::around(TextView::new(text).scrollable().max_size((100, 25)))
+            .title(result.runner.name.clone())
+            .dismiss_button("Close"),
+    );
+}
@@ -0,0 +1,265 @@
+//! Scaffolding dialogs.
+//!
+//! Lists the generators of `project::scaffold`, asks for what each one needs
+//! (a name, a source file) and reports the files created. Generation runs in
+//! the background (the benchmark looks up the latest `criterion` on
+//! crates.io). The caller is notified after each generation to refresh its
+//! own view. New examples, binaries and modules are opened in the editor.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, EditView, LinearLayout, SelectView, TextView};
+use log::{error, info};
+
+use crate::config::Config;
+use crate::editor;
+use crate::project::scaffold::{self, ScaffoldError};
+use crate::ui::{error_view, notify, progress, suspend};
+
+const NAME_INPUT: &str = "scaffold_name";
+
+/// Called with the project path after something was generated.
+pub type OnDone = fn(&mut Cursive, &Path);
+
+/// Generation from a project path and a name.
+type Generate = Arc<dyn Fn(&Path, &str) -> Result<Generated, ScaffoldError> + Send + Sync>;
+
+/// What a generation did.
+struct Generated {
+    summary: String,
+    /// File to open in the editor, relative to the project.
+    open: Option<String>,
+}
+
+impl Generated {
+    fn summary(summary: String) -> Self {
+        Self {
+            summary,
+            open: None,
+        }
+    }
+
+    /// `file` was created and declared in `Cargo.toml`.
+    fn target(file: String, section: &str) -> Self {
+        Self {
+            summary: format!("Created {file} and its [[{section}]] entry"),
+            open: Some(file),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Generator {
+    IntegrationTest,
+    UnitTests,
+    Benchmark,
+    Example,
+    Binary,
+    Module,
+}
+
+/// Show the generators for the project at `path`.
+pub fn show(s: &mut Cursive, config: &Config, path: &Path, on_done: OnDone) {
+    let mut select = SelectView::<Generator>::new()
+        .item(
+            "Integration test (tests/<name>.rs)",
+            Generator::IntegrationTest,
+        )
+        .item("Unit test module in a source file", Generator::UnitTests)
+        .item(
+            "Criterion benchmark (benches/<name>.rs)",
+            Generator::Benchmark,
+        )
+        .item("Example (examples/<name>.rs)", Generator::Example)
+        .item("Binary target (src/bin/<name>.rs)", Generator::Binary)
+        .item("Module (declared in lib.rs / main.rs)", Generator::Module);
+    let root = path.to_path_buf();
+    let config = config.clone();
+    select.set_on_submit(move |s, generator: &Generator| {
+        s.pop_layer();
+        match generator {
+            Generator::IntegrationTest => ask_name(
+                s,
+                &config,
+                &root,
+                "Name of the integration test (tests/<name>.rs):",
+                "smoke",
+                on_done,
+                Arc::new(|path, name| {
+                    let created = scaffold::integration_test(path, name)?;
+                    Ok(Generated::summary(format!("Created {created}")))
+                }),
+            ),
+            Generator::UnitTests => pick_source_file(s, &config, &root, on_done),
+            Generator::Benchmark => ask_name(
+                s,
+                &config,
+                &root,
+                "Name of the benchmark (benches/<name>.rs):",
+                "bench",
+                on_done,
+                Arc::new(|path, name| {
+                    scaffold::benchmark(path, name, &scaffold::criterion_requirement())
+                        .map(Generated::summary)
+                }),
+            ),
+            Generator::Example => ask_name(
+                s,
+                &config,
+                &root,
+                "Name of the example (examples/<name>.rs):",
+                "basic",
+                on_done,
+                Arc::new(|path, name| {
+                    Ok(Generated::target(scaffold::example(path, name)?, "example"))
+                }),
+            ),
+            Generator::Binary => ask_name(
+                s,
+                &config,
+                &root,
+                "Name of the binary (src/bin/<name>.rs):",
+                "",
+                on_done,
+                Arc::new(|path, name| Ok(Generated::target(scaffold::binary(path, name)?, "bin"))),
+            ),
+            Generator::Module => {
+                let style = config.scaffold().module_style;
+                ask_name(
+                    s,
+                    &config,
+                    &root,
+                    "Name of the module:",
+                    "",
+                    on_done,
+                    Arc::new(move |path, name| {
+                        let (file, crate_root) = scaffold::module(path, name, style)?;
+                        Ok(Generated {
+                            summary: format!("Created {file} and declared it in {crate_root}"),
+                            open: Some(file),
+                        })
+                    }),
+                )
+            }
+        }
+    });
+    s.add_layer(
+        Dialog::around(select.fixed_width(50))
+            .title("Scaffold")
+            .dismiss_button("Cancel"),
+    );
+}
+
+/// Source file to add a test module to.
+fn pick_source_file(s: &mut Cursive, config: &Config, path: &Path, on_done: OnDone) {
+    let files = scaffold::source_files(path);
+    if files.is_empty() {
+        s.add_layer(Dialog::info("No source file under src/."));
+        return;
+    }
+    let mut select = SelectView::<String>::new();
+    select.add_all_str(files);
+    let root = path.to_path_buf();
+    let config = config.clone();
+    select.set_on_submit(move |s, file: &String| {
+        s.pop_layer();
+        let file = file.clone();
+        ask_name(
+            s,
+            &config,
+            &root,
+            &format!("Name of the first test in {file}:"),
+            "it_works",
+            on_done,
+            Arc::new(move |path, name| {
+                scaffold::unit_tests(path, &file, name)?;
+                Ok(Generated::summary(format!("Added a test module to {file}")))
+            }),
+        );
+    });
+    s.add_layer(
+        Dialog::around(select.scrollable().fixed_size((60, 12)))
+            .title("Unit test module")
+            .dismiss_button("Cancel"),
+    );
+}
+
+/// Ask for a name, then run `generate` with it; it returns what was done.
+fn ask_name(
+    s: &mut Cursive,
+    config: &Config,
+    path: &Path,
+    hint: &str,
+    default: &str,
+    on_done: OnDone,
+    generate: Generate,
+) {
+    let root: PathBuf = path.to_path_buf();
+    let config = config.clone();
+    s.add_layer(
+        Dialog::new()
+            .title("Scaffold")
+            .content(
+                LinearLayout::vertical().child(TextView::new(hint)).child(
+                    EditView::new()
+                        .content(default)
+                        .with_name(NAME_INPUT)
+                        .fixed_width(40),
+                ),
+            )
+            .button("Create", move |s| {
+                let name = s
+                    .call_on_name(NAME_INPUT, |v: &mut EditView| v.get_content())
+                    .unwrap_or_default();
+                let generate = Arc::clone(&generate);
+                let work_root = root.clone();
+                let done_root = root.clone();
+                let config = config.clone();
+                progress::run(
+                    s,
+                    "Scaffolding",
+                    move |_| generate(&work_root, name.trim()),
+                    move |s, result| finish(s, &config, &done_root, result, on_done),
+                );
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+fn finish(
+    s: &mut Cursive,
+    config: &Config,
+    path: &Path,
+    result: Result<Generated, ScaffoldError>,
+    on_done: OnDone,
+) {
+    match result {
+        Ok(done) => {
+            info!("{} in {}", done.summary, path.display());
+            // The name dialog.
+            s.pop_layer();
+            on_done(s, path);
+            if let Some(file) = done.open {
+                match editor::launch_at(config, path, &path.join(&file), 1) {
+                    Ok(cmd) => suspend::run(s, cmd.into_iter().collect()),
+                    Err(e) => {
+                        error!("Failed to open {file} in the editor: {e}");
+                        s.add_layer(Dialog::info(format!(
+                            "{}\n\nCannot open it in the editor: {e}",
+                            done.summary
+                        )));
+                        return;
+                    }
+                }
+            }
+            notify::toast(s, done.summary);
+        }
+        Err(e) => {
+            error!("Scaffolding failed in {}: {e}", path.display());
+            error_view::show(s, "Cannot scaffold", &e);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Find in project dialog.
+//!
+//! Prompts for a pattern, runs ripgrep in the background and lists the matches
+//! grouped by file, next to a preview of the selected match. Enter on a match
+//! opens the file at that line in the editor.
+
+use std::path::{Path, PathBuf};
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, EditView, LinearLayout, SelectView};
+use log::error;
+
+use crate::config::Config;
+use crate::editor;
+use crate::project::search::{self, SearchResults};
+use crate::ui::{error_view, preview, progress, suspend};
+
+const PATTERN_INPUT: &str = "search_pattern";
+const PREVIEW: &str = "search_preview";
+
+/// Ask for a pattern, then search it in the project at `path`.
+pub fn show(s: &mut Cursive, config: &Config, path: &Path) {
+    let config = config.clone();
+    let path = path.to_path_buf();
+    let submit = move |s: &mut Cursive| {
+        let pattern = s
+            .call_on_name(PATTERN_INPUT, |v: &mut EditView| v.get_content())
+            .map(|c| c.to_string())
+            .unwrap_or_default();
+        if pattern.is_empty() {
+            return;
+        }
+        s.pop_layer();
+        run(s, config.clone(), path.clone(), pattern);
+    };
+    let on_enter = submit.clone();
+    s.add_layer(
+        Dialog::around(
+            EditView::new()
+                .on_submit(move |s, _| on_enter(s))
+                .with_name(PATTERN_INPUT)
+                .fixed_width(40),
+        )
+        .title("Find in project (regex)")
+        .button("Search", submit)
+        .dismiss_button("Cancel"),
+    );
+}
+
+fn run(s: &mut Cursive, config: Config, path: PathBuf, pattern: String) {
+    let title = format!("Searching '{pattern}'");
+    progress::run(
+        s,
+        &title,
+        {
+            let path = path.clone();
+            let pattern = pattern.clone();
+            move |_| search::search(&path, &pattern)
+        },
+        move |s, result| match result {
+            Ok(results) if results.files.is_empty() => {
+                s.add_layer(Dialog::info(format!("No match for '{pattern}'.")));
+            }
+            Ok(results) => show_results(s, &config, &path, &pattern, results),
+            Err(e) => {
+                error!("Search failed: {e}");
+                error_view::show(s, "Search failed", &e);
+            }
+        },
+    );
+}
+
+fn show_results(
+    s: &mut Cursive,
+    config: &Config,
+    path: &Path,
+    pattern: &str,
+    results: SearchResults,
+) {
+    // File headers carry `None` and do nothing on Enter.
+    let mut select = SelectView::<Option<(PathBuf, u64)>>::new();
+    let mut total = 0;
+    for file in results.files {
+        select.add_item(
+            format!("{} ({})", file.file.display(), file.matches.len()),
+            None,
+        );
+        for m in file.matches {
+            select.add_item(
+                format!("  {:>5}: {}", m.line, m.text.trim()),
+                Some((file.file.clone(), m.line)),
+            );
+            total += 1;
+        }
+    }
+
+    // Start on the first match (index 0 is its file header).
+    let first_match = select.get_item(1).and_then(|(_, target)| target.clone());
+    let _ = select.set_selection(1);
+
+    let preview_root = path.to_path_buf();
+    select.set_on_select(move |s, target: &Option<(PathBuf, u64)>| match target {
+        Some((file, line)) => preview::show_file(s, PREVIEW, &preview_root.join(file), Some(*line)),
+        None => preview::show_message(s, PREVIEW, ""),
+    });
+
+    let config = config.clone();
+    let project = path.to_path_buf();
+    select.set_on_submit(move |s, target: &Option<(PathBuf, u64)>| {
+        let Some((file, line)) = target else {
+            return;
+        };
+        match editor::launch_at(&config, &project, file, *line) {
+            Ok(cmd) => suspend::run(s, cmd.into_iter().collect()),
+            Err(e) => {
+                error!("Failed to open {}: {e}", file.display());
+                error_view::show(s, "Failed to open editor", &e);
+            }
+        }
+    });
+
+    let truncated = if results.truncated { "+" } else { "" };
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::horizontal()
+                .child(select.scrollable().fixed_size((50, 24)))
+                .child(preview::pane(PREVIEW, 80, 24)),
+        )
+        .title(format!("'{pattern}': {total}{truncated} matches"))
+        .dismiss_button("Close"),
+    );
+    if let Some((file, line)) = first_match {
+        preview::show_file(s, PREVIEW, &path.join(file), Some(line));
+    }
+}
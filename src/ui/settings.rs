@@ -0,0 +1,211 @@
+//! Settings screen.
+//!
+//! Lists every setting of `config.yaml` (`config::SETTINGS`) with its current
+//! value. Enter edits a setting as YAML; saving goes through `Config::set`,
+//! which validates the whole configuration and writes it atomically. The
+//! caller gets the new configuration to replace the one its views captured.
+//!
+//! The theme has its own picker ("Theme...", or Enter on `theme`): moving
+//! through the list previews each theme on the whole screen; Cancel restores
+//! the current one.
+
+use std::sync::Arc;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, LinearLayout, SelectView, TextArea, TextView};
+use log::{error, info};
+
+use crate::config::{Config, SETTINGS, Setting};
+use crate::theme;
+
+const VALUE_INPUT: &str = "settings_value";
+const THEME_LIST: &str = "settings_theme_list";
+const THEME_STATUS: &str = "settings_theme_status";
+
+/// Longest value shown in the list.
+const VALUE_WIDTH: usize = 48;
+
+/// Called with the configuration after a setting was saved.
+pub type OnSaved = Arc<dyn Fn(&mut Cursive, Config) + Send + Sync>;
+
+/// Show the settings of `config`.
+pub fn show(s: &mut Cursive, config: &Config, on_saved: OnSaved) {
+    let mut select = SelectView::<&'static Setting>::new();
+    for setting in &SETTINGS {
+        select.add_item(row_label(config, setting), setting);
+    }
+    let edit_config = config.clone();
+    let edit_on_saved = Arc::clone(&on_saved);
+    select.set_on_submit(move |s, setting: &&'static Setting| {
+        if setting.key == "theme" {
+            pick_theme(s, &edit_config, Arc::clone(&edit_on_saved));
+        } else {
+            edit(s, &edit_config, setting, Arc::clone(&edit_on_saved));
+        }
+    });
+    let theme_config = config.clone();
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(format!(
+                    "{}\n",
+                    Config::file_path().display()
+                )))
+                .child(select.scrollable().fixed_size((90, 16))),
+        )
+        .title("Settings")
+        .button("Theme...", move |s| {
+            pick_theme(s, &theme_config, Arc::clone(&on_saved));
+        })
+        .dismiss_button("Close"),
+    );
+}
+
+/// `key  value` with the value on one line, shortened; the API tokens are masked.
+///
+/// Values are those of the file: overridden settings are flagged.
+fn row_label(config: &Config, setting: &Setting) -> String {
+    let value = match current_value(config, setting.key) {
+        None => "(unset)".to_string(),
+        Some(_) if setting.key == "github_token" || setting.key == "gitlab_token" => {
+            "********".to_string()
+        }
+        Some(yaml) => {
+            let line = yaml.lines().collect::<Vec<_>>().join(" ");
+            if line.chars().count() > VALUE_WIDTH {
+                let short: String = line.chars().take(VALUE_WIDTH - 3).collect();
+                format!("{short}...")
+            } else {
+                line
+            }
+        }
+    };
+    let overridden = if config.is_overridden(setting.key) {
+        "  (overridden for this run)"
+    } else {
+        ""
+    };
+    format!("{:<42}  {value}{overridden}", setting.key)
+}
+
+/// YAML of the setting at `key`; `None` when unset.
+fn current_value(config: &Config, key: &str) -> Option<String> {
+    let value = config.get(key).filter(|v| !v.is_null())?;
+    let yaml = serde_norway::to_string(&value).ok()?;
+    Some(yaml.trim_end().to_string())
+}
+
+fn edit(s: &mut Cursive, config: &Config, setting: &'static Setting, on_saved: OnSaved) {
+    let mut hint = format!("{}.", setting.description);
+    if setting.optional {
+        hint.push_str(" Leave empty to unset.");
+    }
+    let config = config.clone();
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical().child(TextView::new(hint)).child(
+                TextArea::new()
+                    .content(current_value(&config, setting.key).unwrap_or_default())
+                    .with_name(VALUE_INPUT)
+                    .fixed_size((72, 8)),
+            ),
+        )
+        .title(setting.key)
+        .button("Save", move |s| {
+            let value = s
+                .call_on_name(VALUE_INPUT, |v: &mut TextArea| v.get_content().to_string())
+                .unwrap_or_default();
+            save(s, &config, setting.key, value.trim(), &on_saved);
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+/// Theme list previewing the selected theme; saving sets `theme`.
+fn pick_theme(s: &mut Cursive, config: &Config, on_saved: OnSaved) {
+    let current = s.current_theme().clone();
+    let mut select = SelectView::<String>::new();
+    select.add_all_str(theme::names());
+    let position = select
+        .iter()
+        .position(|(_, name)| name == config.theme())
+        .unwrap_or(0);
+    select.set_selection(position);
+    select.set_on_select(|s, name: &String| {
+        let status = match theme::load(name) {
+            Ok(theme) => {
+                s.set_theme(theme);
+                String::new()
+            }
+            Err(e) => e.to_string(),
+        };
+        s.call_on_name(THEME_STATUS, |v: &mut TextView| v.set_content(status));
+    });
+    let submit_config = config.clone();
+    let submit_on_saved = Arc::clone(&on_saved);
+    select.set_on_submit(move |s, name: &String| {
+        save(s, &submit_config, "theme", name, &submit_on_saved);
+    });
+    let save_config = config.clone();
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(
+                    select
+                        .with_name(THEME_LIST)
+                        .scrollable()
+                        .fixed_size((40, 8)),
+                )
+                .child(TextView::new("").with_name(THEME_STATUS))
+                .child(TextView::new(format!(
+                    "\nTheme files: {}/<name>.yaml",
+                    theme::themes_dir().display()
+                ))),
+        )
+        .title("Theme")
+        .button("Save", move |s| {
+            let selected = s
+                .call_on_name(THEME_LIST, |v: &mut SelectView<String>| v.selection())
+                .flatten();
+            if let Some(name) = selected {
+                save(s, &save_config, "theme", &name, &on_saved);
+            }
+        })
+        .button("Cancel", move |s| {
+            s.set_theme(current.clone());
+            s.pop_layer();
+        }),
+    );
+}
+
+fn save(s: &mut Cursive, config: &Config, key: &str, value: &str, on_saved: &OnSaved) {
+    match config.set(key, value) {
+        Ok(updated) => {
+            info!("Setting '{key}' saved to {}", Config::file_path().display());
+            s.pop_layer();
+            on_saved(s, updated);
+        }
+        Err(e) => {
+            error!("Cannot save setting '{key}': {e}");
+            s.add_layer(Dialog::info(format!("Cannot save {key}:\n{e}")));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::testing::Harness;
+
+    #[test]
+    fn settings_screen_lists_the_values() {
+        let config = Config::from_yaml(
+            "projects_directory: /projects\neditor_cmd: vim\ngithub_token: secret\n",
+        );
+        let mut h = Harness::new(100, 40);
+        h.redact(Config::file_path().display().to_string(), "<config file>");
+        show(h.siv(), &config, Arc::new(|_, _| {}));
+        h.assert_snapshot("settings");
+    }
+}
@@ -0,0 +1,116 @@
+//! Safety snapshots of a project.
+//!
+//! Lists the snapshots taken before risky workflows (see
+//! `git_ops::snapshot`), newest first, with their age, what was about to
+//! happen and the branch. Enter on one offers "Restore" (after a
+//! confirmation: current uncommitted changes are lost) and "Delete".
+
+use std::path::{Path, PathBuf};
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, SelectView};
+use log::error;
+
+use crate::format;
+use crate::git_ops::{self, Snapshot};
+use crate::metadata;
+use crate::ui::git_actions::OnBranchChanged;
+use crate::ui::{error_view, notify};
+
+const SNAPSHOT_LIST: &str = "snapshot_list";
+
+/// Show the snapshots of the repository at `path`.
+pub fn show(s: &mut Cursive, path: &Path, on_changed: OnBranchChanged) {
+    let snapshots = match git_ops::snapshots(path) {
+        Ok(snapshots) if snapshots.is_empty() => {
+            s.add_layer(Dialog::info(
+                "No snapshot yet.\nOne is taken before rewriting commits, converting to a \
+                 workspace, hoisting dependencies, applying the lint policy and clippy fixes.",
+            ));
+            return;
+        }
+        Ok(snapshots) => snapshots,
+        Err(e) => {
+            error_view::show(s, "Cannot list the snapshots", &e);
+            return;
+        }
+    };
+
+    let now = i64::try_from(metadata::now()).unwrap_or(i64::MAX);
+    let mut select = SelectView::<Snapshot>::new();
+    for snapshot in snapshots {
+        let label = format!(
+            "{:>4}  {:<24}  {}",
+            format::timestamp_column(snapshot.time, now),
+            snapshot.label,
+            snapshot.branch.as_deref().unwrap_or("(detached)")
+        );
+        select.add_item(label, snapshot);
+    }
+    let path = path.to_path_buf();
+    select.set_on_submit(move |s, snapshot: &Snapshot| {
+        actions(s, &path, snapshot, on_changed.clone());
+    });
+    s.add_layer(
+        Dialog::around(
+            select
+                .with_name(SNAPSHOT_LIST)
+                .scrollable()
+                .fixed_size((60, 12)),
+        )
+        .title("Snapshots")
+        .dismiss_button("Close"),
+    );
+}
+
+fn actions(s: &mut Cursive, path: &Path, snapshot: &Snapshot, on_changed: OnBranchChanged) {
+    let text = format!(
+        "Snapshot taken before: {}\n\nRestoring checks out {} at the commit of the time and \
+         brings back the uncommitted changes of the time. Current uncommitted changes to \
+         tracked files are lost.",
+        snapshot.label,
+        snapshot.branch.as_deref().unwrap_or("the commit")
+    );
+    let restore_path: PathBuf = path.to_path_buf();
+    let delete_path: PathBuf = path.to_path_buf();
+    let restore_name = snapshot.name.clone();
+    let delete_name = snapshot.name.clone();
+    s.add_layer(
+        Dialog::text(text)
+            .title("Snapshot")
+            .button("Restore", move |s| {
+                s.pop_layer();
+                match git_ops::restore_snapshot(&restore_path, &restore_name) {
+                    Ok(()) => {
+                        s.pop_layer();
+                        on_changed(s);
+                        notify::toast(s, "Snapshot restored.");
+                    }
+                    Err(e) => {
+                        error!(
+                            "Restoring snapshot {restore_name} of {} failed: {e}",
+                            restore_path.display()
+                        );
+                        error_view::show(s, "Cannot restore the snapshot", &e);
+                    }
+                }
+            })
+            .button("Delete", move |s| {
+                s.pop_layer();
+                match git_ops::delete_snapshot(&delete_path, &delete_name) {
+                    Ok(()) => {
+                        s.call_on_name(SNAPSHOT_LIST, |v: &mut SelectView<Snapshot>| {
+                            let index = v.iter().position(|(_, sn)| sn.name == delete_name);
+                            if let Some(i) = index {
+                                v.remove_item(i);
+                            }
+                        });
+                        notify::toast(s, "Snapshot deleted.");
+                    }
+                    Err(e) => error_view::show(s, "Cannot delete the snapshot", &e),
+                }
+            })
+            .dismiss_button("Cancel"),
+    );
+}
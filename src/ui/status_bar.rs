@@ -0,0 +1,137 @@
+//! Status bar.
+//!
+//! One line at the bottom of the screen, under every dialog: the projects
+//! directory, the number of projects and of projects with uncommitted
+//! changes, and the background work in progress. It lives in the bottom
+//! layer of the screen (`install`), so dialogs stack above it; `clear_screen`
+//! pops everything else. The toasts of `notify` show right above it.
+//!
+//! The rest of the UI keeps it current through the functions of this module,
+//! called on the UI thread (directly or through the callback sink): the
+//! project list reports its scans, `progress::run` its tasks. Counts come from
+//! the metadata cache until the project list is first scanned.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use cursive::Cursive;
+use cursive::theme::PaletteColor;
+use cursive::view::{Nameable, Resizable};
+use cursive::views::{DummyView, LinearLayout, TextView};
+
+use crate::config::Config;
+use crate::project::list::{ProjectInfo, cached_projects};
+use crate::ui::notify;
+
+const STATUS_BAR: &str = "status_bar";
+
+static STATE: Mutex<State> = Mutex::new(State {
+    projects_directory: String::new(),
+    projects: None,
+    tasks: Vec::new(),
+    next_task: 0,
+});
+
+#[derive(Debug)]
+struct State {
+    projects_directory: String,
+    /// Number of projects and of projects with uncommitted changes, once known.
+    projects: Option<(usize, usize)>,
+    /// Running background tasks, oldest first.
+    tasks: Vec<(u64, String)>,
+    next_task: u64,
+}
+
+/// A background task shown in the status bar until `task_finished`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskId(u64);
+
+/// Add the status bar as the bottom layer of the screen.
+pub fn install(s: &mut Cursive, config: &Config) {
+    s.add_fullscreen_layer(
+        LinearLayout::vertical()
+            .child(DummyView.full_height())
+            .child(notify::area())
+            .child(
+                TextView::new("")
+                    .style(PaletteColor::Secondary)
+                    .with_name(STATUS_BAR)
+                    .full_width(),
+            ),
+    );
+    reset(s, config);
+}
+
+/// Show the projects directory of `config` and the cached counts for it.
+pub fn reset(s: &mut Cursive, config: &Config) {
+    {
+        let mut state = lock();
+        state.projects_directory = config.projects_directory().to_string();
+        let cached = cached_projects(config);
+        state.projects = (!cached.is_empty()).then(|| counts(&cached));
+    }
+    refresh(s);
+}
+
+/// Pop every layer but the status bar.
+pub fn clear_screen(s: &mut Cursive) {
+    while s.screen().len() > 1 {
+        s.pop_layer();
+    }
+}
+
+/// Count the projects of a completed scan.
+pub fn set_projects(s: &mut Cursive, projects: &[ProjectInfo]) {
+    lock().projects = Some(counts(projects));
+    refresh(s);
+}
+
+/// Show `title` as running background work.
+pub fn task_started(s: &mut Cursive, title: &str) -> TaskId {
+    let id = {
+        let mut state = lock();
+        state.next_task += 1;
+        let id = state.next_task;
+        state.tasks.push((id, title.to_string()));
+        id
+    };
+    refresh(s);
+    TaskId(id)
+}
+
+/// Remove the task `id` from the status bar.
+pub fn task_finished(s: &mut Cursive, id: TaskId) {
+    lock().tasks.retain(|(task, _)| *task != id.0);
+    refresh(s);
+}
+
+fn counts(projects: &[ProjectInfo]) -> (usize, usize) {
+    let dirty = projects
+        .iter()
+        .filter(|p| p.has_uncommitted_changes)
+        .count();
+    (projects.len(), dirty)
+}
+
+fn refresh(s: &mut Cursive) {
+    let line = render(&lock());
+    s.call_on_name(STATUS_BAR, |v: &mut TextView| v.set_content(line));
+}
+
+/// ` dir  |  12 projects, 3 with changes  |  Scanning projects (+1)`.
+fn render(state: &State) -> String {
+    let projects = match state.projects {
+        None => "projects not scanned yet".to_string(),
+        Some((1, dirty)) => format!("1 project, {dirty} with changes"),
+        Some((count, dirty)) => format!("{count} projects, {dirty} with changes"),
+    };
+    let tasks = match state.tasks.as_slice() {
+        [] => "idle".to_string(),
+        [(_, title)] => format!("{title}..."),
+        [.., (_, title)] => format!("{title}... (+{})", state.tasks.len() - 1),
+    };
+    format!(" {}  |  {projects}  |  {tasks}", state.projects_directory)
+}
+
+fn lock() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
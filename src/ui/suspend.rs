@@ -0,0 +1,49 @@
+//! Terminal editors.
+//!
+//! An editor running in the terminal (vim, helix, ...) cannot share it with
+//! the TUI. `run` queues its command and stops the event loop; `run_loop`,
+//! which drives the TUI in `main`, then runs the queued commands in the
+//! foreground and restarts the event loop once they exit, every view as it
+//! was. Stopping the loop with nothing queued quits rustm.
+
+use std::process::Command;
+use std::sync::Mutex;
+
+use cursive::{Cursive, CursiveRunnable};
+use log::{error, info};
+
+use crate::editor;
+use crate::ui::error_view;
+
+/// Commands waiting for the TUI to stop.
+static PENDING: Mutex<Vec<Command>> = Mutex::new(Vec::new());
+
+/// Suspend the TUI and run `commands` one after the other in the terminal.
+pub fn run(s: &mut Cursive, commands: Vec<Command>) {
+    if commands.is_empty() {
+        return;
+    }
+    PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .extend(commands);
+    s.quit();
+}
+
+/// Run the TUI until it quits, running the queued commands whenever it stops for them.
+pub fn run_loop(siv: &mut CursiveRunnable) {
+    loop {
+        siv.run();
+        let commands = std::mem::take(&mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()));
+        if commands.is_empty() {
+            return;
+        }
+        for cmd in commands {
+            info!("Running {cmd:?} with the TUI suspended");
+            if let Err(e) = editor::run_foreground(cmd) {
+                error!("Editor failed: {e}");
+                error_view::show(siv, "Editor failed", &e);
+            }
+        }
+    }
+}
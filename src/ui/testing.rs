@@ -0,0 +1,140 @@
+//! Headless TUI driver for tests.
+//!
+//! `Harness` runs a `Cursive` root on cursive's puppet backend: tests build
+//! screens on it with the same functions the application uses, send synthetic
+//! key events, and compare the rendered text with a snapshot file of
+//! `tests/tui_snapshots/`. Only characters are compared, not colors: themes
+//! change those.
+//!
+//! A missing snapshot is written and the test passes; set
+//! `RUSTM_UPDATE_SNAPSHOTS=1` to rewrite the ones that differ after an
+//! intended change, then review the diff.
+
+use std::fs;
+use std::path::PathBuf;
+
+use cursive::backends::puppet::Backend;
+use cursive::backends::puppet::observed::{GraphemePart, ObservedScreen};
+use cursive::event::{Event, Key};
+use cursive::reexports::crossbeam_channel::{Receiver, Sender};
+use cursive::utils::markup::StyledString;
+use cursive::{Cursive, CursiveRunner, Vec2};
+
+/// Environment variable rewriting differing snapshots instead of failing.
+const UPDATE_VAR: &str = "RUSTM_UPDATE_SNAPSHOTS";
+
+/// A `Cursive` root rendered on a virtual screen.
+pub struct Harness {
+    runner: CursiveRunner<Cursive>,
+    input: Sender<Option<Event>>,
+    frames: Receiver<ObservedScreen>,
+    /// Text replaced in the rendered screen: values depending on the machine.
+    redactions: Vec<(String, String)>,
+}
+
+impl Harness {
+    /// Screen of `width` x `height` cells.
+    pub fn new(width: usize, height: usize) -> Self {
+        let backend = Backend::init(Some(Vec2::new(width, height)));
+        let input = backend.input();
+        let frames = backend.stream();
+        Self {
+            runner: CursiveRunner::new(Cursive::new(), backend),
+            input,
+            frames,
+            redactions: Vec::new(),
+        }
+    }
+
+    /// The root, to add the screens under test.
+    pub fn siv(&mut self) -> &mut Cursive {
+        &mut self.runner
+    }
+
+    /// Show `placeholder` wherever `text` is rendered, padded to the width of
+    /// `text` so that the borders around it stay in place.
+    pub fn redact(&mut self, text: impl Into<String>, placeholder: &str) {
+        let text = text.into();
+        let width = text.chars().count();
+        self.redactions
+            .push((text, format!("{placeholder:<width$}")));
+    }
+
+    /// Send `event` and process it, with the callbacks it triggered.
+    pub fn send(&mut self, event: Event) {
+        // Views ignore events until laid out, as on the first frame of a real terminal.
+        self.runner.refresh();
+        self.input
+            .send(Some(event))
+            .expect("the puppet backend is alive");
+        self.runner.process_events();
+    }
+
+    pub fn press(&mut self, key: Key) {
+        self.send(Event::Key(key));
+    }
+
+    /// Type `text` one character at a time.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.send(Event::Char(c));
+        }
+    }
+
+    /// The screen as text: one line per row, trailing spaces and empty rows removed.
+    pub fn screen(&mut self) -> String {
+        self.runner.refresh();
+        let frame = self
+            .frames
+            .try_iter()
+            .last()
+            .expect("refresh renders a frame");
+        let size = frame.size();
+        let mut rows = Vec::with_capacity(size.y);
+        for y in 0..size.y {
+            let mut row = String::new();
+            // Cells covered by the wide character before them.
+            let mut covered = 0;
+            for x in 0..size.x {
+                match frame[Vec2::new(x, y)].as_ref().map(|cell| &cell.letter) {
+                    Some(GraphemePart::Begin(grapheme)) => {
+                        covered = StyledString::plain(grapheme.as_str()).width().max(1) - 1;
+                        row.push_str(grapheme);
+                    }
+                    _ if covered > 0 => covered -= 1,
+                    _ => row.push(' '),
+                }
+            }
+            rows.push(row.trim_end().to_string());
+        }
+        while rows.last().is_some_and(String::is_empty) {
+            rows.pop();
+        }
+        let mut text = rows.join("\n") + "\n";
+        for (from, to) in &self.redactions {
+            text = text.replace(from.as_str(), to);
+        }
+        text
+    }
+
+    /// Compare the screen with the snapshot `name` (see the module documentation).
+    pub fn assert_snapshot(&mut self, name: &str) {
+        let actual = self.screen();
+        let path = snapshot_path(name);
+        let update = std::env::var_os(UPDATE_VAR).is_some_and(|v| v == "1");
+        match fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => {}
+            Ok(_) if !update => panic!(
+                "screen differs from {}:\n{actual}\n(set {UPDATE_VAR}=1 to update the snapshot)",
+                path.display()
+            ),
+            _ => fs::write(&path, actual).expect("snapshot written"),
+        }
+    }
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/tui_snapshots")
+        .join(format!("{name}.txt"))
+}
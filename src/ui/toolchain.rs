@@ -0,0 +1,193 @@
+//! Toolchain dialog of the project detail ("Toolchain...").
+//!
+//! Detects the active toolchain in the background (see `project::toolchain`),
+//! then offers what applies: install it or its missing components, pin a
+//! channel in the project's toolchain file, set or remove a rustup directory
+//! override. After a change, the caller's `OnToolchainChanged` refreshes what
+//! it shows.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable};
+use cursive::views::{Dialog, EditView, LinearLayout, SelectView, TextView};
+use log::error;
+
+use crate::project::toolchain::{self, Source, Toolchain, ToolchainFile};
+use crate::ui::{error_view, notify, progress};
+
+const CHANNEL_INPUT: &str = "toolchain_channel";
+
+/// Called after the toolchain of the project changed.
+pub type OnToolchainChanged = Arc<dyn Fn(&mut Cursive) + Send + Sync>;
+
+#[derive(Clone, Copy)]
+enum Action {
+    Install,
+    Pin,
+    Override,
+    RemoveOverride,
+}
+
+/// Detect the toolchain of the project at `path`, then show the actions.
+pub fn show(s: &mut Cursive, path: &Path, on_changed: OnToolchainChanged) {
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        "Detecting the toolchain",
+        {
+            let path = path.clone();
+            move |_| toolchain::detect(&path)
+        },
+        move |s, result| match result {
+            Ok(detected) => show_actions(s, &path, detected, on_changed.clone()),
+            Err(e) => {
+                error!("Cannot detect the toolchain of {}: {e}", path.display());
+                error_view::show(s, "Cannot detect the toolchain", &e);
+            }
+        },
+    );
+}
+
+fn show_actions(s: &mut Cursive, path: &Path, detected: Toolchain, on_changed: OnToolchainChanged) {
+    let mut menu = SelectView::<Action>::new();
+    if !detected.installed {
+        menu.add_item(format!("Install {}", detected.name), Action::Install);
+    } else if !detected.missing_components.is_empty() {
+        menu.add_item(
+            format!("Install {}", detected.missing_components.join(", ")),
+            Action::Install,
+        );
+    }
+    menu.add_item("Pin a channel in rust-toolchain.toml...", Action::Pin);
+    menu.add_item("Override for this machine...", Action::Override);
+    if matches!(detected.source, Source::Override(_)) {
+        menu.add_item("Remove the override", Action::RemoveOverride);
+    }
+
+    let text = format!("Active: {}", detected.summary());
+    let path = path.to_path_buf();
+    menu.set_on_submit(move |s, action: &Action| {
+        s.pop_layer();
+        let on_changed = on_changed.clone();
+        match action {
+            Action::Install => install(s, &path, detected.clone(), on_changed),
+            Action::Pin => ask_channel(s, &path, false, on_changed),
+            Action::Override => ask_channel(s, &path, true, on_changed),
+            Action::RemoveOverride => match toolchain::unset_override(&path) {
+                Ok(()) => changed(s, "Override removed.", &on_changed),
+                Err(e) => error_view::show(s, "Cannot remove the override", &e),
+            },
+        }
+    });
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(TextView::new(text))
+                .child(TextView::new(""))
+                .child(menu),
+        )
+        .title("Toolchain")
+        .dismiss_button("Cancel"),
+    );
+}
+
+fn install(s: &mut Cursive, path: &Path, detected: Toolchain, on_changed: OnToolchainChanged) {
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        &format!("Installing {}", detected.name),
+        {
+            let detected = detected.clone();
+            move |p| toolchain::install(&path, &detected, &|line| p.log(line))
+        },
+        move |s, result| match result {
+            Ok(()) => changed(s, &format!("{} is ready.", detected.name), &on_changed),
+            Err(e) => {
+                error!("Cannot install {}: {e}", detected.name);
+                error_view::show(s, "Cannot install the toolchain", &e);
+            }
+        },
+    );
+}
+
+/// Ask for a toolchain name, then pin it in the toolchain file or, with
+/// `override_dir`, make it the rustup override of `path`.
+fn ask_channel(s: &mut Cursive, path: &Path, override_dir: bool, on_changed: OnToolchainChanged) {
+    let current = ToolchainFile::find(path)
+        .and_then(|f| f.channel)
+        .unwrap_or_else(|| "stable".to_string());
+    let (title, hint) = if override_dir {
+        (
+            "Override the toolchain",
+            "Toolchain for this directory on this machine (stable, nightly, 1.85.0):",
+        )
+    } else {
+        (
+            "Pin the toolchain",
+            "Channel written to the toolchain file, shared with the repository\n(stable, nightly-2025-06-01, 1.85.0):",
+        )
+    };
+    let path = path.to_path_buf();
+    s.add_layer(
+        Dialog::around(
+            LinearLayout::vertical().child(TextView::new(hint)).child(
+                EditView::new()
+                    .content(current)
+                    .with_name(CHANNEL_INPUT)
+                    .fixed_width(30),
+            ),
+        )
+        .title(title)
+        .button("Apply", move |s| {
+            let channel = s
+                .call_on_name(CHANNEL_INPUT, |v: &mut EditView| v.get_content())
+                .map(|c| c.trim().to_string())
+                .unwrap_or_default();
+            if channel.is_empty() || channel.contains(char::is_whitespace) {
+                s.add_layer(Dialog::info("Enter a toolchain name."));
+                return;
+            }
+            s.pop_layer();
+            if override_dir {
+                set_override(s, &path, channel, on_changed.clone());
+                return;
+            }
+            match toolchain::pin(&path, &channel) {
+                Ok(file) => {
+                    let file = file.file_name().unwrap_or_default().to_string_lossy();
+                    changed(s, &format!("{channel} pinned in {file}."), &on_changed);
+                }
+                Err(e) => error_view::show(s, "Cannot pin the toolchain", &e),
+            }
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+/// Set the override in the background: rustup installs the toolchain when missing.
+fn set_override(s: &mut Cursive, path: &Path, channel: String, on_changed: OnToolchainChanged) {
+    let path = path.to_path_buf();
+    progress::run(
+        s,
+        &format!("Overriding the toolchain with {channel}"),
+        {
+            let channel = channel.clone();
+            move |_| toolchain::set_override(&path, &channel)
+        },
+        move |s, result| match result {
+            Ok(()) => changed(
+                s,
+                &format!("{channel} overrides the toolchain here."),
+                &on_changed,
+            ),
+            Err(e) => error_view::show(s, "Cannot override the toolchain", &e),
+        },
+    );
+}
+
+fn changed(s: &mut Cursive, message: &str, on_changed: &OnToolchainChanged) {
+    notify::toast(s, message.to_string());
+    on_changed(s);
+}
@@ -0,0 +1,94 @@
+//! Untrusted project quarantine.
+//!
+//! A project marked untrusted in the metadata store (by hand from the project
+//! menu, or when it appears in the projects directory with
+//! `quarantine_new_projects`) is one whose code rustm must not run: builds,
+//! tests, checks and generators execute its build scripts, proc macros and
+//! tests, and its `.rustm.yaml` could name any command (`Config::for_project`
+//! ignores it). Every action running project code goes through `guard`, which
+//! first asks to trust the project. Builds and tests in the container sandbox
+//! (see `project::sandbox`) need no trust.
+
+use std::path::Path;
+
+use cursive::Cursive;
+use cursive::views::Dialog;
+use log::info;
+
+use crate::metadata;
+use crate::ui::{notify, project_list};
+
+/// Run `action` (`what` runs the project's code, e.g. "Building") once the project at `path` is trusted.
+pub fn guard(
+    s: &mut Cursive,
+    path: &Path,
+    what: &str,
+    action: impl Fn(&mut Cursive) + Send + Sync + 'static,
+) {
+    if !metadata::is_untrusted(path) {
+        action(s);
+        return;
+    }
+    let name = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    );
+    let path = path.to_path_buf();
+    s.add_layer(
+        Dialog::text(format!(
+            "{name} is untrusted: {what} runs its code (build scripts, proc macros, \
+             tests, commands of .rustm.yaml).\n\nTrust it only if you know where it comes from."
+        ))
+        .title("Untrusted project")
+        .button("Trust and continue", move |s| {
+            s.pop_layer();
+            set_trusted(s, &path, true);
+            action(s);
+        })
+        .dismiss_button("Cancel"),
+    );
+}
+
+/// Lift (`trusted`) or put the quarantine of the project at `path`.
+pub fn set_trusted(s: &mut Cursive, path: &Path, trusted: bool) {
+    info!(
+        "{} marked as {}",
+        path.display(),
+        if trusted { "trusted" } else { "untrusted" }
+    );
+    metadata::set_untrusted(path, !trusted);
+    project_list::set_untrusted(s, path, !trusted);
+    notify::toast(
+        s,
+        if trusted {
+            "Project trusted."
+        } else {
+            "Project quarantined: its code will not run without asking."
+        },
+    );
+}
+
+/// Button callback running `action` through `guard`.
+pub fn guarded(
+    path: &Path,
+    what: &'static str,
+    action: impl Fn(&mut Cursive) + Clone + Send + Sync + 'static,
+) -> impl Fn(&mut Cursive) + Send + Sync + 'static {
+    let path = path.to_path_buf();
+    move |s| guard(s, &path, what, action.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::testing::Harness;
+
+    #[test]
+    fn untrusted_project_asks_before_running() {
+        let path = Path::new("/projects/trust_prompt_demo");
+        metadata::set_untrusted(path, true);
+        let mut h = Harness::new(100, 20);
+        guard(h.siv(), path, "Building", |_| panic!("ran untrusted code"));
+        h.assert_snapshot("trust_prompt");
+    }
+}
@@ -0,0 +1,200 @@
+//! Unpushed work report.
+//!
+//! Lists the projects whose work exists only on this machine: local branches
+//! with commits missing from every remote-tracking branch, or repositories
+//! without any remote. Enter on a project offers the fix: push the branches
+//! (upstream `origin/<branch>` is set when missing), or add a remote first.
+//! Fixed projects are checked again and drop out of the list.
+
+use std::path::{Path, PathBuf};
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, EditView, LinearLayout, SelectView, TextView};
+use log::{error, warn};
+
+use crate::git_ops::{self, GitError, UnpushedWork};
+use crate::project::list::ProjectInfo;
+use crate::ui::{error_view, progress};
+
+const REPORT_VIEW: &str = "unpushed_report";
+const REMOTE_NAME_INPUT: &str = "unpushed_remote_name";
+const REMOTE_URL_INPUT: &str = "unpushed_remote_url";
+
+/// A project and what is missing from its remotes.
+type AtRisk = (ProjectInfo, UnpushedWork);
+
+/// Check every project of `projects` in the background, then show the ones at risk.
+pub fn show(s: &mut Cursive, projects: Vec<ProjectInfo>) {
+    progress::run(
+        s,
+        "Unpushed work",
+        move |p| {
+            let mut at_risk = Vec::new();
+            for project in projects {
+                p.log(format!("Checking {}...", project.name));
+                match git_ops::unpushed_work(&project.path) {
+                    Ok(work) if work.branches.is_empty() => {}
+                    Ok(work) => at_risk.push((project, work)),
+                    Err(GitError::NotARepository(_)) => {}
+                    Err(e) => warn!("Unpushed check of {} failed: {e}", project.path.display()),
+                }
+            }
+            at_risk
+        },
+        |s, at_risk: Vec<AtRisk>| {
+            if at_risk.is_empty() {
+                s.add_layer(Dialog::info("Every commit is on a remote."));
+                return;
+            }
+            let mut select = SelectView::<AtRisk>::new();
+            for entry in at_risk {
+                select.add_item(row_label(&entry), entry);
+            }
+            select.set_on_submit(|s, (project, work): &AtRisk| {
+                if work.has_remote {
+                    confirm_push(s, project, work);
+                } else {
+                    add_remote(s, project);
+                }
+            });
+            s.add_layer(
+                Dialog::around(
+                    select
+                        .with_name(REPORT_VIEW)
+                        .scrollable()
+                        .fixed_size((80, 16)),
+                )
+                .title("Unpushed work")
+                .dismiss_button("Close"),
+            );
+        },
+    );
+}
+
+/// `name  no remote: main (3)` or `name  main (2), feature (5)`.
+fn row_label((project, work): &AtRisk) -> String {
+    let branches = work
+        .branches
+        .iter()
+        .map(|(name, commits)| format!("{name} ({commits})"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if work.has_remote {
+        format!("{:<24}  {branches}", project.name)
+    } else {
+        format!("{:<24}  no remote: {branches}", project.name)
+    }
+}
+
+fn confirm_push(s: &mut Cursive, project: &ProjectInfo, work: &UnpushedWork) {
+    let branches: Vec<String> = work.branches.iter().map(|(b, _)| b.clone()).collect();
+    let text = format!(
+        "Push {} commit(s) of {} on {}?",
+        work.commits(),
+        project.name,
+        branches.join(", ")
+    );
+    let path = project.path.clone();
+    s.add_layer(
+        Dialog::text(text)
+            .title("Push")
+            .button("Push", move |s| {
+                s.pop_layer();
+                push(s, path.clone(), branches.clone());
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+/// Push `branches` one after the other, stopping at the first failure.
+fn push(s: &mut Cursive, path: PathBuf, branches: Vec<String>) {
+    let work_path = path.clone();
+    progress::run(
+        s,
+        "Push",
+        move |p| {
+            for branch in &branches {
+                git_ops::push_branch(&work_path, branch, &|line| p.log(line))
+                    .map_err(|e| format!("{branch}: {e}"))?;
+            }
+            Ok(())
+        },
+        move |s, result: Result<(), String>| {
+            if let Err(e) = result {
+                error!("Push of {} failed: {e}", path.display());
+                error_view::show_message(s, "Push failed", &e);
+            }
+            recheck(s, &path);
+        },
+    );
+}
+
+fn add_remote(s: &mut Cursive, project: &ProjectInfo) {
+    let form = LinearLayout::vertical()
+        .child(TextView::new("Remote name:"))
+        .child(
+            EditView::new()
+                .content("origin")
+                .with_name(REMOTE_NAME_INPUT)
+                .fixed_width(40),
+        )
+        .child(TextView::new("URL (e.g. git@github.com:me/project.git):"))
+        .child(EditView::new().with_name(REMOTE_URL_INPUT).fixed_width(40));
+    let path = project.path.clone();
+    s.add_layer(
+        Dialog::around(form)
+            .title(format!("Add remote to {}", project.name))
+            .button("Add", move |s| {
+                let read = |s: &mut Cursive, name: &str| {
+                    s.call_on_name(name, |v: &mut EditView| v.get_content())
+                        .map(|c| c.trim().to_string())
+                        .unwrap_or_default()
+                };
+                let name = read(s, REMOTE_NAME_INPUT);
+                let url = read(s, REMOTE_URL_INPUT);
+                if name.is_empty() || url.is_empty() {
+                    s.add_layer(Dialog::info("Both the name and the URL are required."));
+                    return;
+                }
+                match git_ops::add_remote(&path, &name, &url) {
+                    Ok(()) => {
+                        s.pop_layer();
+                        recheck(s, &path);
+                    }
+                    Err(e) => {
+                        error!("Adding remote to {} failed: {e}", path.display());
+                        error_view::show(s, "Cannot add the remote", &e);
+                    }
+                }
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+/// Check the project at `path` again: drop it from the report when nothing is at risk anymore.
+fn recheck(s: &mut Cursive, path: &Path) {
+    let work = match git_ops::unpushed_work(path) {
+        Ok(work) => work,
+        Err(e) => {
+            warn!("Unpushed check of {} failed: {e}", path.display());
+            return;
+        }
+    };
+    s.call_on_name(REPORT_VIEW, |v: &mut SelectView<AtRisk>| {
+        let Some(index) = v.iter().position(|(_, (p, _))| p.path == path) else {
+            return;
+        };
+        if work.branches.is_empty() {
+            v.remove_item(index);
+            return;
+        }
+        if let Some((project, _)) = v.get_item(index).map(|(_, entry)| entry.clone()) {
+            let entry = (project, work);
+            let label = row_label(&entry);
+            v.remove_item(index);
+            v.insert_item(index, label, entry);
+            v.set_selection(index);
+        }
+    });
+}
@@ -0,0 +1,193 @@
+//! Prompts about projects that disappeared behind rustm's back.
+//!
+//! After each scan of the project list, the projects whose directory is gone
+//! (see `project::vanished`) are asked about, each once per session. A
+//! project found in the trash is offered to be forgotten. "Deleted" /
+//! "Forget" drops its metadata (tags, pin, trust, ...). "Moved..." moves the
+//! metadata to where the project went, picked among the projects with the same
+//! `origin` URL or name, or typed. "Later" asks again in the next session.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, EditView, LinearLayout, SelectView, TextView};
+use log::info;
+
+use crate::config::Config;
+use crate::metadata;
+use crate::project::list::ProjectInfo;
+use crate::project::vanished::{self, Vanished};
+use crate::ui::{notify, project_list};
+
+const LOCATION_INPUT: &str = "vanished_location";
+
+/// Paths already asked about in this session.
+static ASKED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// Ask about every project that vanished since it was last scanned, given the freshly scanned `projects`.
+pub fn check(s: &mut Cursive, config: &Config, projects: &[ProjectInfo]) {
+    let root = Path::new(config.projects_directory());
+    let lost: Vec<Vanished> = {
+        let mut asked = ASKED.lock().unwrap_or_else(PoisonError::into_inner);
+        vanished::find(&metadata::snapshot(), root)
+            .into_iter()
+            .filter(|v| asked.insert(v.path.clone()))
+            .collect()
+    };
+    for project in lost {
+        ask(s, config, project, projects);
+    }
+}
+
+fn ask(s: &mut Cursive, config: &Config, lost: Vanished, projects: &[ProjectInfo]) {
+    let trashed = vanished::in_trash(&lost.path);
+    let text = match &trashed {
+        Some(location) => format!(
+            "{} was moved to the trash:\n{}\n\nForget it in rustm, with its tags, pin and trust \
+             setting?",
+            lost.name,
+            location.display()
+        ),
+        None => format!(
+            "{} is gone:\n{}\n\nIt was deleted or moved outside rustm. Deleted forgets its tags, \
+             pin and trust setting; Moved keeps them for its new location.",
+            lost.name,
+            lost.path.display()
+        ),
+    };
+    let candidates: Vec<(String, PathBuf)> = vanished::candidates(&lost, projects)
+        .into_iter()
+        .map(|c| {
+            let why = if c.same_remote {
+                "same remote"
+            } else {
+                "same name"
+            };
+            (format!("{}  ({why})", c.path.display()), c.path)
+        })
+        .collect();
+
+    let forget_path = lost.path.clone();
+    let forget_name = lost.name.clone();
+    let config = config.clone();
+    s.add_layer(
+        Dialog::text(text)
+            .title("Project disappeared")
+            .button(
+                if trashed.is_some() {
+                    "Forget"
+                } else {
+                    "Deleted"
+                },
+                move |s| {
+                    s.pop_layer();
+                    metadata::update(|store| store.remove(&forget_path));
+                    info!("Forgot vanished project {}", forget_path.display());
+                    notify::toast(s, format!("{forget_name} forgotten."));
+                },
+            )
+            .button("Moved...", move |s| {
+                s.pop_layer();
+                pick_location(s, &config, &lost, &candidates);
+            })
+            .dismiss_button("Later"),
+    );
+}
+
+/// Ask where `lost` went: one of `candidates` (label, path) or a typed path.
+fn pick_location(
+    s: &mut Cursive,
+    config: &Config,
+    lost: &Vanished,
+    candidates: &[(String, PathBuf)],
+) {
+    let mut layout = LinearLayout::vertical();
+    if candidates.is_empty() {
+        layout.add_child(TextView::new(
+            "No listed project has the same remote or name.\n",
+        ));
+    } else {
+        let mut select = SelectView::<PathBuf>::new();
+        for (label, path) in candidates {
+            select.add_item(label.clone(), path.clone());
+        }
+        let (config, lost) = (config.clone(), lost.clone());
+        select.set_on_submit(move |s, path: &PathBuf| relocate(s, &config, &lost, path));
+        layout.add_child(TextView::new("Probably one of:"));
+        layout.add_child(select.scrollable().max_height(6));
+        layout.add_child(TextView::new(" "));
+    }
+    layout.add_child(TextView::new(
+        "Or its new location (inside the projects directory):",
+    ));
+    layout.add_child(EditView::new().with_name(LOCATION_INPUT).fixed_width(60));
+
+    let (config, lost) = (config.clone(), lost.clone());
+    s.add_layer(
+        Dialog::around(layout)
+            .title(format!("Where did {} go?", lost.name))
+            .button("Relocate", move |s| {
+                let typed = s
+                    .call_on_name(LOCATION_INPUT, |v: &mut EditView| {
+                        v.get_content().trim().to_string()
+                    })
+                    .unwrap_or_default();
+                if typed.is_empty() {
+                    s.add_layer(Dialog::info("No location entered."));
+                    return;
+                }
+                // Relative locations are taken from the projects directory.
+                let path = Path::new(config.projects_directory()).join(typed);
+                relocate(s, &config, &lost, &path);
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+/// Move the metadata of `lost` to `path`, then rescan.
+fn relocate(s: &mut Cursive, config: &Config, lost: &Vanished, path: &Path) {
+    if !path.join("Cargo.toml").is_file() {
+        s.add_layer(Dialog::info(format!(
+            "{} is not a Rust project.",
+            path.display()
+        )));
+        return;
+    }
+    if !path.starts_with(config.projects_directory()) {
+        s.add_layer(Dialog::info(
+            "rustm only lists projects inside the projects directory.",
+        ));
+        return;
+    }
+    s.pop_layer();
+    metadata::update(|store| store.rename(&lost.path, path));
+    info!(
+        "Vanished project {} relocated to {}",
+        lost.path.display(),
+        path.display()
+    );
+    project_list::refresh(s, config);
+    notify::toast(s, format!("{} relocated to {}.", lost.name, path.display()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::testing::Harness;
+
+    #[test]
+    fn vanished_project_is_asked_about() {
+        let config = Config::from_yaml("projects_directory: /projects\neditor_cmd: vim\n");
+        let lost = Vanished {
+            name: "demo".to_string(),
+            path: PathBuf::from("/projects/vanished_prompt_demo"),
+            remote_url: None,
+        };
+        let mut h = Harness::new(100, 20);
+        ask(h.siv(), &config, lost, &[]);
+        h.assert_snapshot("vanished_prompt");
+    }
+}
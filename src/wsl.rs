@@ -0,0 +1,163 @@
+//! WSL interop.
+//!
+//! On Windows, projects may live in the filesystem of a WSL distribution,
+//! seen from Windows as `\\wsl$\<distro>\home\me\projects\demo` (or
+//! `\\wsl.localhost\<distro>\...`). Windows tools are slow on those paths and
+//! build for the wrong platform, so commands for such a project run inside the
+//! distribution instead: `wsl.exe -d <distro> --cd <linux dir> -- <program>
+//! <args>` (see `command`). Editors that open WSL remotes (VS Code and its
+//! forks) get `--remote wsl+<distro>` and the Linux path (see `remote_args`);
+//! other editors get the Windows path.
+//!
+//! WSL is detected when rustm runs on Windows and `wsl.exe` is on `PATH`.
+//! Elsewhere, and for ordinary Windows paths, commands run as before.
+
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use crate::cargo_ops;
+
+/// UNC hosts of the WSL filesystems, lowercase.
+const WSL_HOSTS: [&str; 2] = ["wsl$", "wsl.localhost"];
+
+/// Editors opening WSL folders with `--remote wsl+<distro> <linux path>`.
+const REMOTE_EDITORS: [&str; 5] = ["code", "code-insiders", "codium", "cursor", "windsurf"];
+
+/// A path inside a WSL distribution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WslPath {
+    pub distro: String,
+    /// Absolute Linux path (`/home/me/projects/demo`).
+    pub path: String,
+}
+
+/// `true` when running on Windows with WSL installed.
+pub fn available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| cfg!(windows) && cargo_ops::tool_available("wsl"))
+}
+
+/// Where `path` is inside WSL, when WSL is available and `path` is a WSL path.
+pub fn locate(path: &Path) -> Option<WslPath> {
+    if !available() {
+        return None;
+    }
+    parse(&path.to_string_lossy())
+}
+
+/// Parse `\\wsl$\<distro>\<path>`, `\\wsl.localhost\...`, their `\\?\UNC\` and
+/// forward-slash forms.
+pub fn parse(path: &str) -> Option<WslPath> {
+    let path = path.replace('\\', "/");
+    let rest = path
+        .strip_prefix("//?/UNC/")
+        .or_else(|| path.strip_prefix("//"))?;
+    let (host, rest) = rest.split_once('/')?;
+    if !WSL_HOSTS.contains(&host.to_ascii_lowercase().as_str()) {
+        return None;
+    }
+    let mut parts = rest.split('/').filter(|p| !p.is_empty());
+    let distro = parts.next()?.to_string();
+    let path = format!("/{}", parts.collect::<Vec<_>>().join("/"));
+    Some(WslPath { distro, path })
+}
+
+/// `program`, to run in `dir`: inside WSL for WSL paths, directly otherwise.
+///
+/// Arguments added by the caller are passed through unchanged: paths among
+/// them must be relative to `dir`.
+pub fn command(program: &str, dir: &Path) -> Command {
+    match locate(dir) {
+        Some(wsl) => {
+            let mut cmd = Command::new("wsl.exe");
+            cmd.args(wsl_args(&wsl, program));
+            cmd
+        }
+        None => {
+            let mut cmd = Command::new(program);
+            cmd.current_dir(dir);
+            cmd
+        }
+    }
+}
+
+/// Make `envs` (set on `cmd` by the caller) visible inside WSL for WSL paths.
+///
+/// `wsl.exe` only passes the variables named in `WSLENV` on.
+pub fn forward_env(cmd: &mut Command, dir: &Path, envs: &[(&str, &str)]) {
+    if envs.is_empty() || locate(dir).is_none() {
+        return;
+    }
+    let mut names: Vec<String> = std::env::var("WSLENV")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .into_iter()
+        .collect();
+    names.extend(envs.iter().map(|(name, _)| format!("{name}/u")));
+    cmd.env("WSLENV", names.join(":"));
+}
+
+fn wsl_args(wsl: &WslPath, program: &str) -> Vec<String> {
+    vec![
+        "-d".into(),
+        wsl.distro.clone(),
+        "--cd".into(),
+        wsl.path.clone(),
+        "--".into(),
+        program.into(),
+    ]
+}
+
+/// `--remote wsl+<distro>`, when the editor `program` opens WSL remotes; the
+/// paths given to it are then the Linux ones.
+pub fn remote_args(program: &str, target: &WslPath) -> Option<Vec<String>> {
+    let name = Path::new(program)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(program);
+    REMOTE_EDITORS
+        .contains(&name)
+        .then(|| vec!["--remote".into(), format!("wsl+{}", target.distro)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_wsl_paths() {
+        let demo = WslPath {
+            distro: "Ubuntu".into(),
+            path: "/home/me/projects/demo".into(),
+        };
+        assert_eq!(
+            parse(r"\\wsl$\Ubuntu\home\me\projects\demo").as_ref(),
+            Some(&demo)
+        );
+        assert_eq!(
+            parse(r"\\?\UNC\wsl.localhost\Ubuntu\home\me\projects\demo\").as_ref(),
+            Some(&demo)
+        );
+        assert_eq!(parse("//WSL$/Ubuntu").unwrap().path, "/");
+        assert!(parse(r"\\server\share\demo").is_none());
+        assert!(parse(r"C:\Users\me\demo").is_none());
+
+        assert_eq!(
+            wsl_args(&demo, "cargo"),
+            [
+                "-d",
+                "Ubuntu",
+                "--cd",
+                "/home/me/projects/demo",
+                "--",
+                "cargo"
+            ]
+        );
+        assert_eq!(
+            remote_args("/usr/bin/code", &demo).unwrap(),
+            ["--remote", "wsl+Ubuntu"]
+        );
+        assert!(remote_args("notepad++", &demo).is_none());
+    }
+}